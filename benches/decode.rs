@@ -0,0 +1,35 @@
+//! Decode throughput of the table-driven decoders: `cargo bench --bench decode`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mbase::codec::Registry;
+use mbase::Mode;
+
+/// Codec and input size in bytes. Big-integer codecs are quadratic, so they get less.
+const CASES: &[(&str, usize)] = &[
+    ("z85", 64 * 1024),
+    ("base45", 64 * 1024),
+    ("base85chunked", 64 * 1024),
+    ("base85python", 64 * 1024),
+    ("base92chunked", 64 * 1024),
+    ("crockford32", 64 * 1024),
+    ("base92", 1024),
+    ("base85rfc1924", 16),
+];
+
+fn decode(c: &mut Criterion) {
+    let registry = Registry::global();
+    let mut group = c.benchmark_group("decode");
+    for &(name, size) in CASES {
+        let codec = registry.get(name).unwrap();
+        let data: Vec<u8> = (0..size).map(|i| (i * 31 + 7) as u8).collect();
+        let encoded = codec.encode(&data).unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &encoded, |b, encoded| {
+            b.iter(|| codec.decode(encoded, Mode::Strict).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
@@ -0,0 +1,4 @@
+//! Builds the C library declared in `include/mbase.h`. The functions live in `mbase::ffi`; the
+//! main crate stays an rlib so it can also build as `no_std`.
+
+pub use mbase_core::ffi::*;
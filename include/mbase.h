@@ -0,0 +1,44 @@
+/* C interface to the mbase codec library. Build with `cargo build --release -p mbase-ffi`. */
+#ifndef MBASE_H
+#define MBASE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Return codes; identical to the mbase CLI exit codes. */
+#define MBASE_OK 0
+#define MBASE_ERR_GENERAL 1 /* also an internal error (a caught panic) */
+#define MBASE_ERR_NULL 2
+#define MBASE_ERR_INVALID_INPUT 10
+#define MBASE_ERR_CHECKSUM 11
+#define MBASE_ERR_IO 12
+#define MBASE_ERR_UNSUPPORTED_CODEC 13
+#define MBASE_ERR_LIMIT_EXCEEDED 14
+
+/* Encode `len` bytes; `*out` receives a string to release with mbase_string_free. */
+int mbase_encode(const char *codec, const uint8_t *data, size_t len, char **out);
+
+/* Decode `text`; `*out`/`*out_len` receive a buffer to release with mbase_bytes_free. */
+int mbase_decode(const char *codec, const char *text, int lenient, uint8_t **out, size_t *out_len);
+
+/* JSON array of codec metadata; release with mbase_string_free. */
+int mbase_list_codecs(char **out);
+
+/* JSON array of {codec, confidence, reasons, warnings}, best first; release with mbase_string_free. */
+int mbase_detect(const char *text, char **out);
+
+/* Message for the last failure on this thread, or NULL. Do not free. */
+const char *mbase_last_error(void);
+
+void mbase_string_free(char *s);
+void mbase_bytes_free(uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MBASE_H */
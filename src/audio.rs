@@ -0,0 +1,118 @@
+//! WAV rendering of Morse code for keying practice.
+//!
+//! ```
+//! let wav = mbase::audio::morse_wav(b"SOS", &mbase::audio::WavOptions::default()).unwrap();
+//! assert_eq!(&wav[..4], b"RIFF");
+//! ```
+
+use crate::codec::morse::timing_units;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::Utf8Policy;
+
+/// Tone and speed settings; one dit lasts `1.2 / wpm` seconds (PARIS standard).
+#[derive(Debug, Clone, Copy)]
+pub struct WavOptions {
+    pub sample_rate: u32,
+    pub frequency: f64,
+    pub wpm: f64,
+    /// Peak amplitude from 0.0 to 1.0.
+    pub volume: f64,
+}
+
+impl Default for WavOptions {
+    fn default() -> Self {
+        Self {
+            sample_rate: 8000,
+            frequency: 600.0,
+            wpm: 20.0,
+            volume: 0.5,
+        }
+    }
+}
+
+/// Rise and fall time of each element, which keeps the keying free of clicks.
+const RAMP_SECONDS: f64 = 0.005;
+
+/// Renders `text` as a mono 16-bit PCM WAV file, with one dit of silence at both ends.
+pub fn morse_wav(text: &[u8], opts: &WavOptions) -> Result<Vec<u8>> {
+    if opts.sample_rate == 0 || opts.wpm <= 0.0 || !(0.0..=1.0).contains(&opts.volume) {
+        return Err(MbaseError::invalid_input("sample rate and wpm must be positive and volume within 0.0..=1.0"));
+    }
+
+    let unit_samples = ((f64::from(opts.sample_rate) * 1.2 / opts.wpm).round() as usize).max(1);
+    let ramp = ((f64::from(opts.sample_rate) * RAMP_SECONDS) as usize).max(1);
+    let mut units = vec![false];
+    units.extend(timing_units(text, Utf8Policy::Strict, |_| Ok(()))?);
+    units.push(false);
+
+    let mut samples: Vec<i16> = Vec::with_capacity(units.len() * unit_samples);
+    let mut i = 0;
+    while i < units.len() {
+        let run = units[i..].iter().take_while(|&&u| u == units[i]).count();
+        let len = run * unit_samples;
+        if units[i] {
+            for n in 0..len {
+                let envelope = (n.min(len - 1 - n) as f64 / ramp as f64).min(1.0);
+                let phase = 2.0 * core::f64::consts::PI * opts.frequency * n as f64 / f64::from(opts.sample_rate);
+                samples.push((phase.sin() * envelope * opts.volume * f64::from(i16::MAX)) as i16);
+            }
+        } else {
+            samples.extend(core::iter::repeat_n(0, len));
+        }
+        i += run;
+    }
+
+    let data_len = u32::try_from(samples.len() * 2).map_err(|_| MbaseError::invalid_input("audio too long for WAV"))?;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&opts.sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(opts.sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    Ok(wav)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_morse_wav_length_matches_timing() {
+        let opts = WavOptions {
+            sample_rate: 1000,
+            wpm: 12.0,
+            ..Default::default()
+        };
+        // E is a single dit: silence + dit + silence = 3 units of 100 samples.
+        let wav = morse_wav(b"E", &opts).unwrap();
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 600);
+        assert_eq!(wav.len(), 44 + 600);
+
+        let samples: Vec<i16> = wav[44..].chunks(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+        assert!(samples[..100].iter().all(|&s| s == 0));
+        assert!(samples[100..200].iter().any(|&s| s.unsigned_abs() > 10_000));
+        assert!(samples[200..].iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn test_morse_wav_rejects_bad_options() {
+        let opts = WavOptions {
+            wpm: 0.0,
+            ..Default::default()
+        };
+        assert!(morse_wav(b"E", &opts).is_err());
+        assert!(morse_wav(b"~", &WavOptions::default()).is_err());
+    }
+}
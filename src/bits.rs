@@ -0,0 +1,146 @@
+//! Bit- and byte-order transforms for raw streams, applied between decoding and encoding
+//! (`mbase bits`), such as captures from hardware that sends bytes LSB first.
+
+use core::fmt;
+
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOp {
+    /// Reverses the bit order within each byte (`0x01` becomes `0x80`).
+    Reverse,
+    /// Inverts every bit.
+    Not,
+    /// Swaps the high and low nibble of each byte (`0x12` becomes `0x21`).
+    Nibbles,
+    /// Reverses the byte order of each 2-, 4- or 8-byte word.
+    Swap(usize),
+    /// Shifts the whole stream this many bits towards its start, filling the end with zeros.
+    Shl(usize),
+    /// Shifts the whole stream this many bits towards its end, filling the start with zeros.
+    Shr(usize),
+}
+
+impl BitOp {
+    /// Parses `reverse`, `not`, `nibbles`, `swap16`, `swap32`, `swap64`, `shl=N` or `shr=N`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let shift = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|_| MbaseError::invalid_input(format!("'{}' is not a bit count", n)))
+        };
+        match spec.split_once('=') {
+            Some(("shl", n)) => return Ok(BitOp::Shl(shift(n)?)),
+            Some(("shr", n)) => return Ok(BitOp::Shr(shift(n)?)),
+            _ => {}
+        }
+        match spec {
+            "reverse" => Ok(BitOp::Reverse),
+            "not" | "invert" => Ok(BitOp::Not),
+            "nibbles" => Ok(BitOp::Nibbles),
+            "swap16" => Ok(BitOp::Swap(2)),
+            "swap32" => Ok(BitOp::Swap(4)),
+            "swap64" => Ok(BitOp::Swap(8)),
+            _ => Err(MbaseError::invalid_input(format!(
+                "unknown bit operation '{}' (expected reverse, not, nibbles, swap16, swap32, swap64, shl=N or shr=N)",
+                spec
+            ))),
+        }
+    }
+
+    pub fn apply(self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(match self {
+            BitOp::Reverse => data.iter().map(|b| b.reverse_bits()).collect(),
+            BitOp::Not => data.iter().map(|b| !b).collect(),
+            BitOp::Nibbles => data.iter().map(|b| b.rotate_left(4)).collect(),
+            BitOp::Swap(width) => {
+                if !data.len().is_multiple_of(width) {
+                    return Err(MbaseError::invalid_input(format!(
+                        "{} bytes is not a whole number of {}-bit words for {}",
+                        data.len(),
+                        width * 8,
+                        self
+                    )));
+                }
+                data.chunks(width).flat_map(|word| word.iter().rev().copied()).collect()
+            }
+            BitOp::Shl(n) => shift(data, n, true),
+            BitOp::Shr(n) => shift(data, n, false),
+        })
+    }
+}
+
+impl fmt::Display for BitOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitOp::Reverse => f.write_str("reverse"),
+            BitOp::Not => f.write_str("not"),
+            BitOp::Nibbles => f.write_str("nibbles"),
+            BitOp::Swap(width) => write!(f, "swap{}", width * 8),
+            BitOp::Shl(n) => write!(f, "shl={}", n),
+            BitOp::Shr(n) => write!(f, "shr={}", n),
+        }
+    }
+}
+
+/// Shifts `data`, read as one big-endian bit string, by `n` bits; the length stays the same.
+fn shift(data: &[u8], n: usize, left: bool) -> Vec<u8> {
+    let (bytes, bits) = (n / 8, (n % 8) as u32);
+    let at = |i: Option<usize>| i.and_then(|i| data.get(i)).copied().unwrap_or(0);
+    (0..data.len())
+        .map(|i| {
+            let (near, far) = if left {
+                (at(i.checked_add(bytes)), at(i.checked_add(bytes).and_then(|j| j.checked_add(1))))
+            } else {
+                (at(i.checked_sub(bytes)), at(i.checked_sub(bytes).and_then(|j| j.checked_sub(1))))
+            };
+            match (bits, left) {
+                (0, _) => near,
+                (_, true) => (near << bits) | (far >> (8 - bits)),
+                (_, false) => (near >> bits) | (far << (8 - bits)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(spec: &str, data: &[u8]) -> Result<Vec<u8>> {
+        BitOp::parse(spec)?.apply(data)
+    }
+
+    #[test]
+    fn test_byte_ops() {
+        assert_eq!(apply("reverse", &[0x01, 0xe0]).unwrap(), [0x80, 0x07]);
+        assert_eq!(apply("not", &[0x00, 0x5a]).unwrap(), [0xff, 0xa5]);
+        assert_eq!(apply("nibbles", &[0x12, 0xab]).unwrap(), [0x21, 0xba]);
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(apply("swap16", &data).unwrap(), [2, 1, 4, 3, 6, 5, 8, 7]);
+        assert_eq!(apply("swap32", &data).unwrap(), [4, 3, 2, 1, 8, 7, 6, 5]);
+        assert_eq!(apply("swap64", &data).unwrap(), [8, 7, 6, 5, 4, 3, 2, 1]);
+        assert!(apply("swap32", &data[..6]).unwrap_err().to_string().contains("6 bytes"));
+    }
+
+    #[test]
+    fn test_shifts() {
+        assert_eq!(apply("shl=4", &[0x12, 0x34]).unwrap(), [0x23, 0x40]);
+        assert_eq!(apply("shr=4", &[0x12, 0x34]).unwrap(), [0x01, 0x23]);
+        assert_eq!(apply("shl=9", &[0x80, 0xff]).unwrap(), [0xfe, 0x00]);
+        assert_eq!(apply("shr=8", &[0x12, 0x34]).unwrap(), [0x00, 0x12]);
+        assert_eq!(apply("shl=0", &[0x12]).unwrap(), [0x12]);
+        assert_eq!(apply("shr=64", &[0xff; 4]).unwrap(), [0; 4]);
+        assert_eq!(apply(&format!("shl={}", usize::MAX), &[0xff]).unwrap(), [0]);
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for spec in ["reverse", "not", "nibbles", "swap16", "swap32", "swap64", "shl=3", "shr=12"] {
+            assert_eq!(BitOp::parse(spec).unwrap().to_string(), spec);
+        }
+        assert_eq!(BitOp::parse("invert").unwrap(), BitOp::Not);
+        assert!(BitOp::parse("shl=-1").is_err());
+        assert!(BitOp::parse("rotate").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+//! Check-digit schemes for numbers people type: IBAN (ISO 7064 mod 97-10), Luhn (payment cards,
+//! IMEI), Verhoeff and Damm. Spaces and hyphens between groups are ignored.
+
+use serde::Serialize;
+
+use crate::error::{LengthConstraint, MbaseError, Result};
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scheme {
+    /// IBAN: two check digits after the country code, ISO 7064 mod 97-10 over the rearranged
+    /// number with letters as 10-35.
+    Iban,
+    /// Luhn mod 10, as on payment cards and IMEIs; catches every single-digit error.
+    Luhn,
+    /// Verhoeff's dihedral-group check; also catches every adjacent transposition.
+    Verhoeff,
+    /// Damm's quasigroup check; catches what Verhoeff does with a single table.
+    Damm,
+}
+
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+const VERHOEFF_INV: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+const DAMM: [[u8; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+/// `input` without the spaces and hyphens that group it for reading.
+fn compact(input: &str) -> String {
+    input.chars().filter(|&c| !c.is_whitespace() && c != '-').collect()
+}
+
+fn digits(scheme: Scheme, input: &str) -> Result<Vec<u8>> {
+    if input.is_empty() {
+        return Err(MbaseError::invalid_input(format!("{} needs at least one digit", scheme.name())));
+    }
+    input
+        .chars()
+        .enumerate()
+        .map(|(position, c)| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or(MbaseError::InvalidCharacter { char: c, position })
+        })
+        .collect()
+}
+
+fn luhn(payload: &[u8]) -> u8 {
+    let sum: u32 = payload
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            let d = u32::from(d);
+            // The digit next to the check digit is doubled, then every second one.
+            if i % 2 == 0 {
+                let doubled = d * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+fn verhoeff(payload: &[u8]) -> u8 {
+    let c = payload
+        .iter()
+        .rev()
+        .enumerate()
+        .fold(0, |c, (i, &d)| VERHOEFF_D[usize::from(c)][usize::from(VERHOEFF_P[(i + 1) % 8][usize::from(d)])]);
+    VERHOEFF_INV[usize::from(c)]
+}
+
+fn damm(payload: &[u8]) -> u8 {
+    payload.iter().fold(0, |interim, &d| DAMM[usize::from(interim)][usize::from(d)])
+}
+
+/// An IBAN split into country code and BBAN, checked for shape.
+fn iban_parts(input: &str, with_check: bool) -> Result<(String, String)> {
+    let upper = input.to_ascii_uppercase();
+    if let Some((position, c)) = upper.chars().enumerate().find(|(_, c)| !c.is_ascii_alphanumeric()) {
+        return Err(MbaseError::InvalidCharacter { char: c, position });
+    }
+    let country = upper.get(..2).filter(|cc| cc.chars().all(|c| c.is_ascii_alphabetic()));
+    let Some(country) = country else {
+        return Err(MbaseError::invalid_input("an IBAN starts with a two-letter country code"));
+    };
+    if with_check && !upper.get(2..4).is_some_and(|d| d.chars().all(|c| c.is_ascii_digit())) {
+        return Err(MbaseError::invalid_input_at(2, "IBAN check digits must be two digits"));
+    }
+    let bban = &upper[if with_check { 4 } else { 2 }..];
+    let length = bban.len() + 4;
+    if !(15..=34).contains(&length) {
+        return Err(MbaseError::invalid_length_msg(LengthConstraint::Range { min: 15, max: Some(34) }, length, "IBAN length"));
+    }
+    Ok((country.to_string(), bban.to_string()))
+}
+
+/// ISO 7064 mod 97-10 of `text`, reading letters as 10 to 35.
+fn mod97(text: &str) -> u32 {
+    text.chars().fold(0, |rem, c| {
+        let value = c.to_digit(36).unwrap_or(0);
+        if value >= 10 {
+            (rem * 100 + value) % 97
+        } else {
+            (rem * 10 + value) % 97
+        }
+    })
+}
+
+fn iban_check(country: &str, bban: &str) -> String {
+    format!("{:02}", 98 - mod97(&format!("{}{}00", bban, country)))
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 4] = [Scheme::Iban, Scheme::Luhn, Scheme::Verhoeff, Scheme::Damm];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scheme::Iban => "iban",
+            Scheme::Luhn => "luhn",
+            Scheme::Verhoeff => "verhoeff",
+            Scheme::Damm => "damm",
+        }
+    }
+
+    /// Also takes `mod97` for the IBAN scheme.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("mod97") {
+            return Some(Scheme::Iban);
+        }
+        Self::ALL.into_iter().find(|s| s.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The check digits `payload` needs: for an IBAN, the country code and BBAN without check
+    /// digits; otherwise the number without its final check digit.
+    pub fn check_digits(self, payload: &str) -> Result<String> {
+        let payload = compact(payload);
+        if self == Scheme::Iban {
+            let (country, bban) = iban_parts(&payload, false)?;
+            return Ok(iban_check(&country, &bban));
+        }
+        let digits = digits(self, &payload)?;
+        let check = match self {
+            Scheme::Luhn => luhn(&digits),
+            Scheme::Verhoeff => verhoeff(&digits),
+            Scheme::Damm => damm(&digits),
+            Scheme::Iban => unreachable!(),
+        };
+        Ok(check.to_string())
+    }
+
+    /// `payload` with its check digits in place, without separators.
+    pub fn generate(self, payload: &str) -> Result<String> {
+        let check = self.check_digits(payload)?;
+        let mut number = compact(payload);
+        if self == Scheme::Iban {
+            number.make_ascii_uppercase();
+            number.insert_str(2, &check);
+        } else {
+            number.push_str(&check);
+        }
+        Ok(number)
+    }
+
+    /// Splits a complete number into the payload and the check digits it carries.
+    pub fn split(self, number: &str) -> Result<(String, String)> {
+        let number = compact(number);
+        if self == Scheme::Iban {
+            let (country, bban) = iban_parts(&number, true)?;
+            return Ok((format!("{}{}", country, bban), number[2..4].to_string()));
+        }
+        if number.chars().count() < 2 {
+            return Err(MbaseError::invalid_length_msg(
+                LengthConstraint::Range { min: 2, max: None },
+                number.chars().count(),
+                format!("{} needs a payload digit and a check digit", self.name()),
+            ));
+        }
+        let (last, _) = number.char_indices().last().unwrap_or_default();
+        let (payload, check) = number.split_at(last);
+        Ok((payload.to_string(), check.to_string()))
+    }
+
+    /// Checks a complete number, naming the expected check digits when they differ.
+    pub fn validate(self, number: &str) -> Result<()> {
+        let (payload, found) = self.split(number)?;
+        if self != Scheme::Iban {
+            digits(self, &compact(number))?;
+        }
+        let expected = self.check_digits(&payload)?;
+        if found != expected {
+            let position = if self == Scheme::Iban { 2 } else { payload.len() };
+            return Err(MbaseError::checksum_mismatch_at(
+                position,
+                format!("{} check digit{} {}, expected {}", self.name(), if expected.len() > 1 { "s are" } else { " is" }, found, expected),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_digit_reference_values() {
+        assert_eq!(Scheme::Luhn.check_digits("7992739871").unwrap(), "3");
+        assert_eq!(Scheme::Verhoeff.check_digits("236").unwrap(), "3");
+        assert_eq!(Scheme::Damm.check_digits("572").unwrap(), "4");
+        assert_eq!(Scheme::Iban.check_digits("GB WEST 1234 5698 7654 32").unwrap(), "82");
+        assert_eq!(Scheme::Iban.generate("de370400440532013000").unwrap(), "DE89370400440532013000");
+        assert_eq!(Scheme::Luhn.generate("4111 1111 1111 111").unwrap(), "4111111111111111");
+    }
+
+    #[test]
+    fn test_validate() {
+        for (scheme, number) in [
+            (Scheme::Iban, "GB82 WEST 1234 5698 7654 32"),
+            (Scheme::Luhn, "4111-1111-1111-1111"),
+            (Scheme::Luhn, "490154203237518"),
+            (Scheme::Verhoeff, "2363"),
+            (Scheme::Damm, "5724"),
+        ] {
+            assert!(scheme.validate(number).is_ok(), "{} {}", scheme.name(), number);
+        }
+        let err = Scheme::Luhn.validate("4111111111111112").unwrap_err();
+        assert!(matches!(err, MbaseError::ChecksumMismatchAt { position: 15, .. }));
+        assert!(err.to_string().contains("luhn check digit is 2, expected 1"));
+        let err = Scheme::Iban.validate("GB83WEST12345698765432").unwrap_err();
+        assert!(err.to_string().contains("check digits are 83, expected 82"));
+        assert!(Scheme::Verhoeff.validate("2336").is_err());
+        assert!(Scheme::Damm.validate("5742").is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(matches!(Scheme::Luhn.validate("41x1"), Err(MbaseError::InvalidCharacter { char: 'x', position: 2 })));
+        assert!(Scheme::Luhn.validate("4").is_err());
+        assert!(matches!(Scheme::Luhn.validate("4é"), Err(MbaseError::InvalidCharacter { char: 'é', position: 1 })));
+        assert!(matches!(Scheme::Verhoeff.validate("1é"), Err(MbaseError::InvalidCharacter { char: 'é', position: 1 })));
+        assert!(Scheme::Damm.validate("é1").is_err());
+        assert_eq!(Scheme::Luhn.split("4é").unwrap(), ("4".to_string(), "é".to_string()));
+        assert!(Scheme::Iban.validate("1282WEST12345698765432").is_err());
+        assert!(Scheme::Iban.validate("GBXXWEST12345698765432").is_err());
+        assert!(Scheme::Iban.validate("GB82WEST").is_err());
+        assert_eq!(Scheme::from_name("MOD97"), Some(Scheme::Iban));
+        assert_eq!(Scheme::from_name("crc32"), None);
+    }
+}
@@ -1,12 +1,57 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
-use crate::types::Mode;
+use crate::commands::{ListSort, OutputCharset, SriAlgorithm};
+use crate::io::{find_preset, InputPolicy, OutputDigest, Records};
+use crate::types::{CodecOptions, LenientPolicy, Mode};
+use mbase::checkdigit::Scheme;
+use mbase::checksum::Checksum;
+use mbase::codec::LETTER_ALPHABETS;
 
 #[derive(Parser)]
 #[command(name = "mbase")]
 #[command(about = "Universal base encode/decode/convert CLI")]
 #[command(version)]
 pub struct Cli {
+    #[arg(
+        long,
+        global = true,
+        help = "Seed for commands that use randomness (reproducible, predictable output; never for secrets)"
+    )]
+    pub seed: Option<u64>,
+
+    #[arg(long, global = true, value_name = "SIZE", value_parser = parse_size, help = "Refuse to produce more than SIZE bytes of output (e.g. 64K, 10M)")]
+    pub max_output_size: Option<usize>,
+
+    #[arg(long, short = 'v', global = true, action = clap::ArgAction::Count, help = "Log decisions to stderr (-v debug, -vv trace)")]
+    pub verbose: u8,
+
+    #[arg(long, global = true, help = "Emit log lines as JSON")]
+    pub log_json: bool,
+
+    #[arg(long, global = true, help = "Never show a progress bar when reading large files")]
+    pub no_progress: bool,
+
+    #[arg(long, global = true, help = "Read terminal stdin as one line without echo (for secrets)")]
+    pub hidden: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Keep a trailing newline that the command would drop (typed input included)"
+    )]
+    pub keep_trailing_newline: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "keep_trailing_newline",
+        help = "Strip leading and trailing whitespace from the input"
+    )]
+    pub trim: bool,
+
+    #[arg(long, global = true, value_name = "CHARS", value_parser = LenientPolicy::parse, help = "Separators lenient decoding ignores: any of - _ . and 'whitespace' (collapse runs)")]
+    pub ignore_chars: Option<LenientPolicy>,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -15,37 +60,61 @@ pub struct Cli {
 pub enum Command {
     #[command(about = "Encode bytes to text")]
     Enc {
-        #[arg(long, default_value = "base64")]
+        #[arg(long, env = "MBASE_CODEC", default_value = "base64")]
         codec: String,
 
         #[arg(long, short = 'i', default_value = "-")]
         r#in: String,
 
-        #[arg(long, short = 'o', default_value = "-")]
+        #[arg(long, short = 'o', env = "MBASE_OUTPUT", default_value = "-")]
         out: String,
 
         #[arg(long, help = "Emit multibase prefix")]
         multibase: bool,
 
+        #[arg(long = "opt", value_name = "KEY=VALUE", help = "Codec-specific option (repeatable)")]
+        opts: Vec<String>,
+
+        #[command(flatten)]
+        alphabet: AlphabetArgs,
+
         #[arg(long, help = "Show encoding with all codecs")]
         all: bool,
 
+        #[arg(
+            long,
+            help = "Drop one trailing newline from the input before encoding (default when typing at a terminal)"
+        )]
+        strip_trailing_newline: bool,
+
+        #[arg(long, value_name = "N", help = "With --all, only codecs whose output has at most N characters")]
+        max_len: Option<usize>,
+
+        #[arg(long, value_enum, help = "With --all, only codecs whose output uses these characters")]
+        charset: Option<CharsetArg>,
+
+        #[command(flatten)]
+        records: RecordArgs,
+
+        #[command(flatten)]
+        digest: DigestArgs,
+
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
 
     #[command(about = "Decode text to bytes")]
     Dec {
-        #[arg(long, default_value = "base64")]
+        #[arg(long, env = "MBASE_CODEC", default_value = "base64")]
         codec: String,
 
         #[arg(long, short = 'i', default_value = "-")]
         r#in: String,
 
-        #[arg(long, short = 'o', default_value = "-")]
+        #[arg(long, short = 'o', env = "MBASE_OUTPUT", default_value = "-")]
         out: String,
 
-        #[arg(long, default_value = "strict")]
+        #[arg(long, env = "MBASE_MODE", default_value = "strict")]
         mode: ModeArg,
 
         #[arg(long)]
@@ -54,9 +123,68 @@ pub enum Command {
         #[arg(long, help = "Consume multibase prefix to detect codec")]
         multibase: bool,
 
+        #[arg(long = "opt", value_name = "KEY=VALUE", help = "Codec-specific option (repeatable)")]
+        opts: Vec<String>,
+
+        #[command(flatten)]
+        alphabet: AlphabetArgs,
+
         #[arg(long, help = "Try all codecs and show successful decodes")]
         all: bool,
 
+        #[arg(long, help = "Show all 25 letter rotations ranked by English likelihood (rot/caesar)")]
+        all_shifts: bool,
+
+        #[arg(long, help = "Decode the longest decodable substring of noisy input (log line, JSON, HTML)")]
+        extract: bool,
+
+        #[arg(long, help = "Print the DER structure of the decoded bytes instead of the bytes")]
+        asn1: bool,
+
+        #[arg(long, help = "Leave all-zero 4 KiB blocks of --out FILE as holes (sparse file)")]
+        sparse: bool,
+
+        #[arg(long, help = "Only check that the input decodes; write nothing and exit non-zero if it does not")]
+        check: bool,
+
+        #[arg(
+            long,
+            help = "If the codec fails, decode with a sibling variant that succeeds (base64 -> base64url)"
+        )]
+        try_siblings: bool,
+
+        #[arg(
+            long,
+            help = "Strip quotes, trailing punctuation, '>' quoting, line numbers and smart quotes from pasted input"
+        )]
+        clean: bool,
+
+        #[arg(
+            long,
+            help = "Decode damaged base16/32/64 or uuencode input as far as possible, zero-filling lost groups and reporting the gaps"
+        )]
+        recover: bool,
+
+        #[arg(
+            long,
+            value_name = "N",
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+            help = "Percent-decode double-encoded urlencoding input (%2520) up to N times, reporting each pass (lenient mode)"
+        )]
+        max_passes: Option<usize>,
+
+        #[arg(
+            long,
+            help = "Decode in both strict and lenient mode and report whether and why the results differ (exit 10 if they do)"
+        )]
+        both_modes: bool,
+
+        #[command(flatten)]
+        records: RecordArgs,
+
+        #[command(flatten)]
+        digest: DigestArgs,
+
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
@@ -72,11 +200,37 @@ pub enum Command {
         #[arg(long, short = 'i', default_value = "-")]
         r#in: String,
 
-        #[arg(long, short = 'o', default_value = "-")]
+        #[arg(long, short = 'o', env = "MBASE_OUTPUT", default_value = "-")]
         out: String,
 
-        #[arg(long, default_value = "strict")]
-        mode: ModeArg,
+        #[arg(
+            long = "from-mode",
+            visible_alias = "mode",
+            env = "MBASE_MODE",
+            default_value = "strict",
+            help = "Decode mode for the source codec"
+        )]
+        from_mode: ModeArg,
+
+        #[arg(long = "from-opts", value_name = "KEY=VALUE", value_delimiter = ',', help = "Source codec options")]
+        from_opts: Vec<String>,
+
+        #[arg(
+            long = "to-opts",
+            value_name = "KEY=VALUE",
+            value_delimiter = ',',
+            help = "Target codec options; wrap=N breaks the output into lines"
+        )]
+        to_opts: Vec<String>,
+
+        #[arg(long, help = "Re-decode the output and compare bytes before writing")]
+        verify_roundtrip: bool,
+
+        #[command(flatten)]
+        records: RecordArgs,
+
+        #[command(flatten)]
+        digest: DigestArgs,
 
         #[arg(long, help = "Output as JSON")]
         json: bool,
@@ -84,6 +238,12 @@ pub enum Command {
 
     #[command(about = "List supported codecs")]
     List {
+        #[arg(long, help = "Wide table with alphabet, example encoding of \"hello\", bits/char and tags")]
+        long: bool,
+
+        #[arg(long, value_enum, help = "Sort order (default: by ID)")]
+        sort: Option<ListSortArg>,
+
         #[arg(long)]
         json: bool,
     },
@@ -92,37 +252,69 @@ pub enum Command {
     Info {
         codec: String,
 
+        #[arg(long, help = "Show encode/decode sample pairs")]
+        examples: bool,
+
+        #[arg(long, conflicts_with_all = ["examples", "json"], help = "Print only the specification the codec implements")]
+        spec: bool,
+
         #[arg(long)]
         json: bool,
     },
 
     #[command(about = "Verify input conforms to codec")]
     Verify {
-        #[arg(long, default_value = "base64")]
+        #[arg(long, env = "MBASE_CODEC", default_value = "base64")]
         codec: String,
 
         #[arg(long, short = 'i', default_value = "-")]
         r#in: String,
 
-        #[arg(long, default_value = "strict")]
+        #[arg(long, env = "MBASE_MODE", default_value = "strict")]
         mode: ModeArg,
 
+        #[arg(long, help = "Fail unless the input is the canonical encoding of its bytes")]
+        require_canonical: bool,
+
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with = "require_canonical",
+            help = "Check the digits of a number instead of a codec"
+        )]
+        scheme: Option<SchemeArg>,
+
+        #[arg(long, requires = "scheme", help = "Print the number with its check digits added")]
+        generate: bool,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Check fixture files against the encodings listed in a TOML manifest")]
+    VerifyTree {
+        #[arg(long, value_name = "FILE", help = "Manifest of [[fixture]] tables; files are relative to it")]
+        manifest: std::path::PathBuf,
+
+        #[arg(long, conflicts_with = "json", help = "Print a JUnit XML report")]
+        junit: bool,
+
         #[arg(long)]
         json: bool,
     },
 
     #[command(about = "Normalize/format encoded data")]
     Fmt {
-        #[arg(long, default_value = "base64")]
+        #[arg(long, env = "MBASE_CODEC", default_value = "base64")]
         codec: String,
 
         #[arg(long, short = 'i', default_value = "-")]
         r#in: String,
 
-        #[arg(long, short = 'o', default_value = "-")]
+        #[arg(long, short = 'o', env = "MBASE_OUTPUT", default_value = "-")]
         out: String,
 
-        #[arg(long, default_value = "lenient")]
+        #[arg(long, env = "MBASE_MODE", default_value = "lenient")]
         mode: ModeArg,
 
         #[arg(long, help = "Wrap output at N characters")]
@@ -133,6 +325,24 @@ pub enum Command {
 
         #[arg(long, default_value = " ", help = "Separator for grouping")]
         sep: String,
+
+        #[arg(long, conflicts_with = "lower", help = "Uppercase the output (case-insensitive codecs only)")]
+        upper: bool,
+
+        #[arg(long, help = "Lowercase the output (case-insensitive codecs only)")]
+        lower: bool,
+
+        #[arg(long, default_value = "", help = "Text to put before the output, e.g. 0x")]
+        prefix: String,
+
+        #[arg(long, default_value = "", help = "Text to put after the output")]
+        suffix: String,
+
+        #[arg(long, default_value = "", help = "Text to put at the start of every line, e.g. indentation")]
+        line_prefix: String,
+
+        #[arg(long, help = "Write nothing; exit non-zero unless the input is already formatted")]
+        check: bool,
     },
 
     #[command(about = "Detect likely codec(s) for input")]
@@ -145,22 +355,558 @@ pub enum Command {
 
         #[arg(long, default_value = "5", help = "Number of candidates to show")]
         top: usize,
+
+        #[arg(long, help = "Split a cookie header or URL query into fields and detect each value")]
+        fields: bool,
     },
 
     #[command(about = "Explain why input fails to decode")]
     Explain {
+        #[arg(long, env = "MBASE_CODEC", default_value = "base64")]
+        codec: String,
+
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, env = "MBASE_MODE", default_value = "strict")]
+        mode: ModeArg,
+
+        #[arg(long, help = "Explain the longest decodable substring of noisy input")]
+        extract: bool,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Brute-force unknown cipher parameters")]
+    Analyze {
+        #[arg(long, default_value = "affine")]
+        codec: String,
+
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, default_value = "5", help = "Number of candidates to show")]
+        top: usize,
+
+        #[arg(
+            long,
+            value_name = "BYTES",
+            help = "Known start of the plaintext (e.g. MZ, hex:4d5a90); recovers a shuffled base64 alphabet"
+        )]
+        known_prefix: Option<String>,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Inspect DNSSEC records (NSEC3, DNSKEY, DS) in presentation format")]
+    Dns {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, help = "Re-encode binary fields with this codec")]
+        to: Option<String>,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Generate or verify Subresource Integrity strings")]
+    Sri {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, default_value = "sha384")]
+        algo: SriAlgoArg,
+
+        #[arg(long, value_name = "INTEGRITY", help = "Verify input against an integrity string")]
+        verify: Option<String>,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Compute a CRC or Adler-32 checksum, written in any encoding")]
+    Crc {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, default_value = "crc32")]
+        algo: CrcAlgoArg,
+
+        #[arg(long, value_name = "CODEC", help = "Decode the input with this codec first")]
+        decode: Option<String>,
+
+        #[arg(long, env = "MBASE_MODE", default_value = "strict")]
+        mode: ModeArg,
+
+        #[arg(long, default_value = "base16lower", help = "Codec for the big-endian checksum bytes")]
+        to: String,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Decode two inputs and compare the bytes")]
+    Cmp {
         #[arg(long, default_value = "base64")]
+        codec_a: String,
+
+        #[arg(long)]
+        in_a: String,
+
+        #[arg(long, default_value = "base64")]
+        codec_b: String,
+
+        #[arg(long)]
+        in_b: String,
+
+        #[arg(long, env = "MBASE_MODE", default_value = "strict")]
+        mode: ModeArg,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Reverse, invert, shift or byte-swap the bits of raw bytes")]
+    Bits {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, short = 'o', default_value = "-")]
+        out: String,
+
+        #[arg(
+            long = "op",
+            value_name = "OP",
+            required = true,
+            help = "reverse, not, nibbles, swap16, swap32, swap64, shl=N or shr=N (repeatable, applied in order)"
+        )]
+        ops: Vec<String>,
+
+        #[arg(long, help = "Write binary output to a terminal")]
+        force: bool,
+    },
+
+    #[command(about = "Convert integers between number bases (2-64)")]
+    Radix {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, value_name = "RADIX", help = "Base of the input numbers [default: 10]")]
+        from: Option<u32>,
+
+        #[arg(long, value_name = "RADIX", help = "Base of the output numbers [default: 10]")]
+        to: Option<u32>,
+
+        #[arg(long, value_name = "DIGITS", help = "Input digits in value order (sets the base)")]
+        from_alphabet: Option<String>,
+
+        #[arg(long, value_name = "DIGITS", help = "Output digits in value order (sets the base)")]
+        to_alphabet: Option<String>,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Check a QR payload for alphanumeric mode and estimate the QR version per error-correction level")]
+    Qr {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(
+            long = "codec",
+            value_name = "CODEC",
+            help = "Encode the input with this codec first (repeatable, to compare)"
+        )]
+        codecs: Vec<String>,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Generate random bytes in any encoding (OS randomness; with --seed, reproducible and not secret)")]
+    Rand {
+        #[arg(long, default_value = "hex")]
         codec: String,
 
+        #[arg(long, short = 'n', default_value = "16")]
+        bytes: usize,
+
+        #[arg(long = "opt", value_name = "KEY=VALUE", help = "Codec-specific option (repeatable)")]
+        opts: Vec<String>,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Inspect a base45/zlib/COSE health certificate (EU DCC \"HC1:\")")]
+    Hcert {
         #[arg(long, short = 'i', default_value = "-")]
         r#in: String,
 
-        #[arg(long, default_value = "strict")]
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Decode each segment of a compact JWS/JWT or JWE token")]
+    Jwt {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, env = "MBASE_MODE", default_value = "strict")]
         mode: ModeArg,
 
         #[arg(long)]
         json: bool,
     },
+
+    #[command(about = "Inspect OpenSSH public keys and age recipients (type, size, fingerprint)")]
+    Key {
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Check a custom alphabet for duplicates, confusables and prefix clashes")]
+    LintCodec {
+        #[arg(long, help = "Symbols of the alphabet, in digit order")]
+        alphabet: String,
+
+        #[arg(long, help = "Multibase prefix character the codec would use")]
+        prefix: Option<char>,
+
+        #[arg(long, help = "Treat case collisions as errors (symbols matched case-insensitively)")]
+        case_insensitive: bool,
+
+        #[arg(long)]
+        json: bool,
+    },
+
+    #[command(about = "Replay a regression corpus against every codec")]
+    Regress {
+        #[arg(long, value_name = "DIR", help = "Directory of .case files")]
+        corpus: std::path::PathBuf,
+
+        #[arg(
+            long,
+            value_name = "NAME",
+            conflicts_with = "update",
+            help = "Record the input as a new case instead"
+        )]
+        add: Option<String>,
+
+        #[arg(long, short = 'i', default_value = "-", requires = "add")]
+        r#in: String,
+
+        #[arg(long, value_name = "TEXT", requires = "add", help = "Comment for the new case (repeatable)")]
+        note: Vec<String>,
+
+        #[arg(long, help = "Re-record the outcomes of every case")]
+        update: bool,
+
+        #[arg(long, conflicts_with_all = ["add", "update"])]
+        json: bool,
+    },
+
+    #[command(about = "List, save, import and export recipes: named pipelines of codec steps")]
+    Recipes {
+        #[arg(
+            long,
+            value_name = "NAME",
+            requires = "steps",
+            conflicts_with_all = ["import", "export"],
+            help = "Save the --step list as a recipe"
+        )]
+        save: Option<String>,
+
+        #[arg(
+            long = "step",
+            value_name = "STEP",
+            requires = "save",
+            help = "enc:CODEC[:KEY=VALUE...], dec:CODEC[:KEY=VALUE...] or bits:OP (repeatable, applied in order)"
+        )]
+        steps: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "export",
+            help = "Add the recipes of a shared recipe file"
+        )]
+        import: Option<std::path::PathBuf>,
+
+        #[arg(long, value_name = "FILE", help = "Write recipes to a file to share ('-' for stdout)")]
+        export: Option<String>,
+
+        #[arg(value_name = "NAME", requires = "export", help = "Recipes to export [default: all]")]
+        names: Vec<String>,
+
+        #[arg(long, help = "Overwrite recipes of the same name on --save or --import")]
+        replace: bool,
+
+        #[arg(long, conflicts_with_all = ["save", "import", "export"])]
+        json: bool,
+    },
+
+    #[command(about = "Run a saved recipe on the input")]
+    Run {
+        #[arg(help = "Recipe name (see `mbase recipes`)")]
+        recipe: String,
+
+        #[arg(long, short = 'i', default_value = "-")]
+        r#in: String,
+
+        #[arg(long, short = 'o', env = "MBASE_OUTPUT", default_value = "-")]
+        out: String,
+
+        #[arg(long, env = "MBASE_MODE", default_value = "strict", help = "Mode of the dec steps")]
+        mode: ModeArg,
+
+        #[arg(long, help = "Write binary output to a terminal")]
+        force: bool,
+    },
+
+    #[command(about = "Print the JSON Schema of a --json report, or list the reports")]
+    Schema {
+        #[arg(help = "Report name such as dec or list (see `mbase schema`)")]
+        report: Option<String>,
+    },
+}
+
+impl Command {
+    /// What the command does with the ends of its input unless `--keep-trailing-newline` or
+    /// `--trim` is given: commands on raw bytes keep them, commands on text drop a final newline.
+    pub fn input_policy(&self) -> InputPolicy {
+        match self {
+            Command::Enc {
+                strip_trailing_newline: true,
+                ..
+            } => InputPolicy::StripNewline,
+            Command::Enc { .. } | Command::Sri { .. } | Command::Crc { .. } | Command::Bits { .. } | Command::Regress { .. } => {
+                InputPolicy::Bytes
+            }
+            _ => InputPolicy::StripNewline,
+        }
+    }
+
+    /// Whether the command prints a JSON document instead of text.
+    pub fn json(&self) -> bool {
+        match self {
+            Command::Enc { json, .. }
+            | Command::Dec { json, .. }
+            | Command::Conv { json, .. }
+            | Command::List { json, .. }
+            | Command::Info { json, .. }
+            | Command::Verify { json, .. }
+            | Command::VerifyTree { json, .. }
+            | Command::Detect { json, .. }
+            | Command::Explain { json, .. }
+            | Command::Analyze { json, .. }
+            | Command::Dns { json, .. }
+            | Command::Sri { json, .. }
+            | Command::Crc { json, .. }
+            | Command::Cmp { json, .. }
+            | Command::Radix { json, .. }
+            | Command::Qr { json, .. }
+            | Command::Rand { json, .. }
+            | Command::Hcert { json, .. }
+            | Command::Jwt { json, .. }
+            | Command::Key { json, .. }
+            | Command::LintCodec { json, .. }
+            | Command::Regress { json, .. }
+            | Command::Recipes { json, .. } => *json,
+            Command::Fmt { .. } | Command::Bits { .. } | Command::Run { .. } | Command::Schema { .. } => false,
+        }
+    }
+}
+
+/// Batch mode: convert each record of the input separately.
+#[derive(Args)]
+pub struct RecordArgs {
+    #[arg(long, help = "Read NUL-separated input records (find -print0)")]
+    pub in0: bool,
+
+    #[arg(long, help = "Terminate each output record with NUL")]
+    pub out0: bool,
+
+    #[arg(
+        long,
+        value_name = "SEP",
+        help = "Record separator for input and output (escapes: \\n \\t \\0 \\xNN)"
+    )]
+    pub record_sep: Option<String>,
+}
+
+impl RecordArgs {
+    pub fn build(&self) -> crate::error::Result<Option<Records>> {
+        Records::from_flags(self.in0, self.out0, self.record_sep.as_deref())
+    }
+}
+
+/// A custom base64 table, given inline or by preset name.
+#[derive(Args)]
+pub struct AlphabetArgs {
+    #[arg(
+        long,
+        value_name = "CHARS|PRESET",
+        help = "Base64 with a custom 64-character table, atbash/caesar with latin, greek, cyrillic or hebrew letters, or a preset name from the alphabets file ($MBASE_ALPHABETS)"
+    )]
+    pub alphabet: Option<String>,
+
+    #[arg(long, value_name = "CHAR", help = "Padding character of a custom base64 table (default '=')")]
+    pub pad_char: Option<char>,
+}
+
+impl AlphabetArgs {
+    /// Adds the `alphabet=` and `pad=` codec options; `--pad-char` overrides a preset's padding.
+    /// Letter alphabet names pass through unless a preset of that name exists.
+    pub fn apply(&self, mut opts: CodecOptions) -> crate::error::Result<CodecOptions> {
+        let mut pad = self.pad_char;
+        if let Some(alphabet) = &self.alphabet {
+            let alphabet = if alphabet.chars().count() == 64 {
+                alphabet.clone()
+            } else {
+                match find_preset(alphabet) {
+                    Ok(preset) => {
+                        pad = pad.or(preset.pad);
+                        preset.alphabet
+                    }
+                    Err(_) if LETTER_ALPHABETS.iter().any(|(name, _)| name.eq_ignore_ascii_case(alphabet)) => alphabet.clone(),
+                    Err(e) => return Err(e),
+                }
+            };
+            opts = opts.with("alphabet", &alphabet);
+        }
+        if let Some(pad) = pad {
+            opts = opts.with("pad", &pad.to_string());
+        }
+        Ok(opts)
+    }
+}
+
+/// Report a hash of the written output, to check large transfers without a second pass.
+#[derive(Args)]
+pub struct DigestArgs {
+    #[arg(long, value_name = "ALGO", help = "After writing, print a digest of the output bytes to stderr")]
+    pub print_digest: Option<SriAlgoArg>,
+
+    #[arg(
+        long,
+        value_name = "CODEC",
+        default_value = "base16lower",
+        help = "Codec for the --print-digest value"
+    )]
+    pub digest_codec: String,
+}
+
+impl DigestArgs {
+    pub fn build(&self) -> crate::error::Result<Option<OutputDigest>> {
+        let Some(algorithm) = self.print_digest else {
+            return Ok(None);
+        };
+        Ok(Some(OutputDigest {
+            algorithm: algorithm.into(),
+            codec: mbase::codec::Registry::global().get(&self.digest_codec)?,
+        }))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ListSortArg {
+    Name,
+    Efficiency,
+    Family,
+}
+
+impl From<ListSortArg> for ListSort {
+    fn from(arg: ListSortArg) -> Self {
+        match arg {
+            ListSortArg::Name => ListSort::Name,
+            ListSortArg::Efficiency => ListSort::Efficiency,
+            ListSortArg::Family => ListSort::Family,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CharsetArg {
+    Ascii,
+    Alnum,
+    Urlsafe,
+}
+
+impl From<CharsetArg> for OutputCharset {
+    fn from(arg: CharsetArg) -> Self {
+        match arg {
+            CharsetArg::Ascii => OutputCharset::Ascii,
+            CharsetArg::Alnum => OutputCharset::Alnum,
+            CharsetArg::Urlsafe => OutputCharset::UrlSafe,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SriAlgoArg {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl From<SriAlgoArg> for SriAlgorithm {
+    fn from(arg: SriAlgoArg) -> Self {
+        match arg {
+            SriAlgoArg::Sha256 => SriAlgorithm::Sha256,
+            SriAlgoArg::Sha384 => SriAlgorithm::Sha384,
+            SriAlgoArg::Sha512 => SriAlgorithm::Sha512,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CrcAlgoArg {
+    Crc32,
+    Crc32c,
+    Crc16Ccitt,
+    Crc16Xmodem,
+    Crc24,
+    Adler32,
+}
+
+impl From<CrcAlgoArg> for Checksum {
+    fn from(arg: CrcAlgoArg) -> Self {
+        match arg {
+            CrcAlgoArg::Crc32 => Checksum::Crc32,
+            CrcAlgoArg::Crc32c => Checksum::Crc32c,
+            CrcAlgoArg::Crc16Ccitt => Checksum::Crc16Ccitt,
+            CrcAlgoArg::Crc16Xmodem => Checksum::Crc16Xmodem,
+            CrcAlgoArg::Crc24 => Checksum::Crc24,
+            CrcAlgoArg::Adler32 => Checksum::Adler32,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SchemeArg {
+    #[value(alias = "mod97")]
+    Iban,
+    Luhn,
+    Verhoeff,
+    Damm,
+}
+
+impl From<SchemeArg> for Scheme {
+    fn from(arg: SchemeArg) -> Self {
+        match arg {
+            SchemeArg::Iban => Scheme::Iban,
+            SchemeArg::Luhn => Scheme::Luhn,
+            SchemeArg::Verhoeff => Scheme::Verhoeff,
+            SchemeArg::Damm => Scheme::Damm,
+        }
+    }
 }
 
 #[derive(Clone, Copy, ValueEnum)]
@@ -177,3 +923,16 @@ impl From<ModeArg> for Mode {
         }
     }
 }
+
+/// Parses a byte count with an optional binary `K`, `M` or `G` suffix.
+fn parse_size(s: &str) -> Result<usize, String> {
+    let s = s.trim();
+    let (digits, shift) = match s.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&s[..i], 10),
+        Some((i, 'm' | 'M')) => (&s[..i], 20),
+        Some((i, 'g' | 'G')) => (&s[..i], 30),
+        _ => (s, 0),
+    };
+    let n: usize = digits.parse().map_err(|_| format!("invalid size '{}'", s))?;
+    n.checked_mul(1 << shift).ok_or_else(|| format!("size '{}' is too large", s))
+}
@@ -0,0 +1,478 @@
+use alloc::borrow::Cow;
+
+use super::{util, Codec};
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Scripts the `alphabet=` option of atbash and caesar knows by name, lowercase letters in order.
+/// Greek final sigma and the Hebrew final forms are not letters of their own and pass through.
+pub const LETTER_ALPHABETS: [(&str, &str); 4] = [
+    ("latin", "abcdefghijklmnopqrstuvwxyz"),
+    ("greek", "αβγδεζηθικλμνξοπρστυφχψω"),
+    ("cyrillic", "абвгдеёжзийклмнопрстуфхцчшщъыьэюя"),
+    ("hebrew", "אבגדהוזחטיכלמנסעפצקרשת"),
+];
+
+fn mod_inverse(a: u8) -> Option<u8> {
+    (1..26u8).find(|&x| (a as u32 * x as u32) % 26 == 1)
+}
+
+/// Letter substitution `E(x) = (a*x + b) mod 26`; atbash is a=25,b=25 and caesar is a=1.
+#[derive(Debug, Clone, Copy)]
+struct AffineKey {
+    a: u8,
+    b: u8,
+    a_inv: u8,
+}
+
+impl AffineKey {
+    fn new(a: i64, b: i64) -> Result<Self> {
+        let a = a.rem_euclid(26) as u8;
+        let b = b.rem_euclid(26) as u8;
+        let a_inv = mod_inverse(a).ok_or_else(|| {
+            MbaseError::invalid_input(format!("affine multiplier a={} has no inverse mod 26 (must be coprime with 26)", a))
+        })?;
+        Ok(Self { a, b, a_inv })
+    }
+
+    fn from_options(opts: &CodecOptions, codec: &str, default_a: i64, default_b: i64) -> Result<Self> {
+        opts.ensure_known(codec, &["a", "b", "utf8"])?;
+        let parse = |key: &str, default: i64| -> Result<i64> {
+            match opts.get(key) {
+                None => Ok(default),
+                Some(v) => v
+                    .parse()
+                    .map_err(|_| MbaseError::invalid_input(format!("option '{}' expects an integer, got '{}'", key, v))),
+            }
+        };
+        Self::new(parse("a", default_a)?, parse("b", default_b)?)
+    }
+
+    fn shift(base: u8, x: u8, mul: u8, add: u8) -> u8 {
+        base + ((mul as u32 * x as u32 + add as u32) % 26) as u8
+    }
+
+    /// Substitutes an ASCII letter; every other character, non-ASCII letters included, passes through.
+    fn map_char(c: char, mul: u8, add: u8) -> char {
+        match c {
+            'A'..='Z' => Self::shift(b'A', c as u8 - b'A', mul, add) as char,
+            'a'..='z' => Self::shift(b'a', c as u8 - b'a', mul, add) as char,
+            _ => c,
+        }
+    }
+
+    fn encode(&self, text: &str) -> String {
+        text.chars().map(|c| Self::map_char(c, self.a, self.b)).collect()
+    }
+
+    fn decode(&self, input: &str) -> Vec<u8> {
+        // x = a^-1 * (y - b) = a^-1 * y + a^-1 * (26 - b)
+        let add = ((self.a_inv as u32 * (26 - self.b as u32)) % 26) as u8;
+        input
+            .chars()
+            .map(|c| Self::map_char(c, self.a_inv, add))
+            .collect::<String>()
+            .into_bytes()
+    }
+}
+
+/// A letter alphabet from the `alphabet=` option: a name from [`LETTER_ALPHABETS`] or the
+/// letters themselves. Capitals come from Unicode case mapping, so a caseless script like Hebrew
+/// simply has none; every other character passes through.
+struct Letters {
+    lower: Vec<char>,
+    upper: Vec<Option<char>>,
+}
+
+impl Letters {
+    fn from_options(opts: &CodecOptions) -> Result<Option<Self>> {
+        let Some(value) = opts.get("alphabet") else {
+            return Ok(None);
+        };
+        let letters = LETTER_ALPHABETS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(value))
+            .map_or(value, |(_, letters)| *letters);
+        let lower: Vec<char> = letters.chars().collect();
+        if lower.len() < 2 {
+            return Err(MbaseError::invalid_input(format!(
+                "option 'alphabet' expects latin, greek, cyrillic, hebrew or at least two letters, got '{}'",
+                value
+            )));
+        }
+        if let Some((position, &c)) = lower.iter().enumerate().find(|&(i, c)| lower[..i].contains(c)) {
+            return Err(MbaseError::invalid_input(format!(
+                "option 'alphabet' repeats '{}' at position {}; every letter must be unique",
+                c, position
+            )));
+        }
+        let upper = lower
+            .iter()
+            .map(|&c| {
+                let mut capitals = c.to_uppercase();
+                match (capitals.next(), capitals.next()) {
+                    (Some(u), None) if u != c && !lower.contains(&u) => Some(u),
+                    _ => None,
+                }
+            })
+            .collect();
+        Ok(Some(Self { lower, upper }))
+    }
+
+    /// Replaces the letter at index `i` of `n` with the one at `f(i, n)`, keeping its case.
+    fn map(&self, text: &str, f: impl Fn(usize, usize) -> usize) -> String {
+        let n = self.lower.len();
+        text.chars()
+            .map(|c| {
+                if let Some(i) = self.lower.iter().position(|&l| l == c) {
+                    return self.lower[f(i, n)];
+                }
+                match self.upper.iter().position(|&u| u == Some(c)) {
+                    Some(i) => {
+                        let j = f(i, n);
+                        self.upper[j].unwrap_or(self.lower[j])
+                    }
+                    None => c,
+                }
+            })
+            .collect()
+    }
+}
+
+/// `input` as text; an invalid byte is an error unless `--opt utf8=lenient` asks for U+FFFD.
+fn utf8_text<'a>(codec: &str, input: &'a [u8], opts: &CodecOptions) -> Result<Cow<'a, str>> {
+    util::text_input(codec, input, opts.utf8_policy()?, '\u{FFFD}')
+}
+
+fn letter_candidate(codec: &str, input: &str, confidence: f64, warning: &str) -> DetectCandidate {
+    if input.is_empty() {
+        return DetectCandidate {
+            codec: codec.to_string(),
+            confidence: 0.0,
+            reasons: vec!["empty input".to_string()],
+            warnings: vec![],
+        };
+    }
+
+    let alpha_count = input.chars().filter(|c| c.is_ascii_alphabetic()).count();
+    let alpha_ratio = alpha_count as f64 / input.len() as f64;
+
+    if alpha_ratio > 0.5 {
+        DetectCandidate {
+            codec: codec.to_string(),
+            confidence,
+            reasons: vec!["contains alphabetic characters".to_string()],
+            warnings: vec![warning.to_string()],
+        }
+    } else {
+        DetectCandidate {
+            codec: codec.to_string(),
+            confidence: 0.0,
+            reasons: vec![],
+            warnings: vec![],
+        }
+    }
+}
+
+pub struct Affine;
+
+impl Affine {
+    const DEFAULT_A: i64 = 5;
+    const DEFAULT_B: i64 = 8;
+}
+
+impl Codec for Affine {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "affine",
+            aliases: &[],
+            alphabet: LETTERS,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Affine cipher (a*x+b mod 26, options a=5 b=8)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let key = AffineKey::from_options(opts, "affine", Self::DEFAULT_A, Self::DEFAULT_B)?;
+        Ok(key.encode(&utf8_text("affine", input, opts)?))
+    }
+
+    fn decode_with(&self, input: &str, _mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let key = AffineKey::from_options(opts, "affine", Self::DEFAULT_A, Self::DEFAULT_B)?;
+        Ok(key.decode(input))
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        letter_candidate("affine", input, 0.10, "Affine parameters are unknown; try `mbase analyze --codec affine`")
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
+}
+
+pub struct Atbash;
+
+const ATBASH: AffineKey = AffineKey { a: 25, b: 25, a_inv: 25 };
+
+impl Codec for Atbash {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "atbash",
+            aliases: &[],
+            alphabet: LETTERS,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Atbash cipher (A↔Z, B↔Y, etc.; option alphabet=greek|cyrillic|hebrew|LETTERS)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, _mode: Mode) -> Result<Vec<u8>> {
+        Ok(ATBASH.decode(input))
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("atbash", &["alphabet", "utf8"])?;
+        let text = utf8_text("atbash", input, opts)?;
+        match Letters::from_options(opts)? {
+            Some(letters) => Ok(letters.map(&text, |i, n| n - 1 - i)),
+            None => Ok(ATBASH.encode(&text)),
+        }
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        opts.ensure_known("atbash", &["alphabet", "utf8"])?;
+        match Letters::from_options(opts)? {
+            Some(letters) => Ok(letters.map(input, |i, n| n - 1 - i).into_bytes()),
+            None => self.decode(input, mode),
+        }
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        letter_candidate("atbash", input, 0.15, "Atbash is ambiguous without context")
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
+}
+
+pub struct Caesar;
+
+impl Codec for Caesar {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "caesar",
+            aliases: &["rot", "shift"],
+            alphabet: LETTERS,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Caesar shift cipher (options shift=3, alphabet=greek|cyrillic|hebrew|LETTERS)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let shift = caesar_shift(opts)?;
+        let text = utf8_text("caesar", input, opts)?;
+        match Letters::from_options(opts)? {
+            Some(letters) => Ok(letters.map(&text, |i, n| (i as i64 + shift).rem_euclid(n as i64) as usize)),
+            None => Ok(AffineKey::new(1, shift)?.encode(&text)),
+        }
+    }
+
+    fn decode_with(&self, input: &str, _mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let shift = caesar_shift(opts)?;
+        match Letters::from_options(opts)? {
+            Some(letters) => Ok(letters
+                .map(input, |i, n| (i as i64 - shift).rem_euclid(n as i64) as usize)
+                .into_bytes()),
+            None => Ok(AffineKey::new(1, shift)?.decode(input)),
+        }
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        letter_candidate("caesar", input, 0.10, "Caesar shift is unknown; try `mbase analyze --codec caesar`")
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
+}
+
+fn caesar_shift(opts: &CodecOptions) -> Result<i64> {
+    opts.ensure_known("caesar", &["shift", "alphabet", "utf8"])?;
+    match opts.get("shift") {
+        None => Ok(3),
+        Some(v) => v
+            .parse()
+            .map_err(|_| MbaseError::invalid_input(format!("option 'shift' expects an integer, got '{}'", v))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_MULTIPLIERS: [u8; 12] = [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25];
+
+    #[test]
+    fn test_atbash_encode() {
+        assert_eq!(Atbash.encode(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap(), "ZYXWVUTSRQPONMLKJIHGFEDCBA");
+        assert_eq!(Atbash.encode(b"abcdefghijklmnopqrstuvwxyz").unwrap(), "zyxwvutsrqponmlkjihgfedcba");
+        assert_eq!(Atbash.encode(b"Hello").unwrap(), "Svool");
+        assert_eq!(Atbash.encode(b"HELLO").unwrap(), "SVOOL");
+    }
+
+    #[test]
+    fn test_atbash_decode() {
+        assert_eq!(Atbash.decode("Svool", Mode::Strict).unwrap(), b"Hello");
+        assert_eq!(Atbash.decode("SVOOL", Mode::Strict).unwrap(), b"HELLO");
+    }
+
+    #[test]
+    fn test_atbash_roundtrip() {
+        let data = b"The Quick Brown Fox";
+        let encoded = Atbash.encode(data).unwrap();
+        assert_eq!(Atbash.decode(&encoded, Mode::Strict).unwrap(), data);
+    }
+
+    #[test]
+    fn test_atbash_symmetric() {
+        let encoded = Atbash.encode(b"test").unwrap();
+        let double_encoded = Atbash.encode(encoded.as_bytes()).unwrap();
+        assert_eq!(double_encoded, "test");
+    }
+
+    #[test]
+    fn test_atbash_non_alpha() {
+        assert_eq!(Atbash.encode(b"Hello, World! 123").unwrap(), "Svool, Dliow! 123");
+    }
+
+    #[test]
+    fn test_atbash_empty() {
+        assert_eq!(Atbash.encode(&[]).unwrap(), "");
+        assert_eq!(Atbash.decode("", Mode::Strict).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_atbash_mixed_case() {
+        assert_eq!(Atbash.encode(b"HeLLo").unwrap(), "SvOOl");
+    }
+
+    #[test]
+    fn test_affine_default_key() {
+        // Classic textbook example with a=5, b=8.
+        assert_eq!(Affine.encode(b"AFFINE CIPHER").unwrap(), "IHHWVC SWFRCP");
+        assert_eq!(Affine.decode("IHHWVC SWFRCP", Mode::Strict).unwrap(), b"AFFINE CIPHER");
+    }
+
+    #[test]
+    fn test_affine_special_cases() {
+        let atbash = CodecOptions::new().with("a", "25").with("b", "25");
+        assert_eq!(Affine.encode_with(b"Hello", &atbash).unwrap(), "Svool");
+
+        let rot13 = CodecOptions::new().with("a", "1").with("b", "13");
+        assert_eq!(Affine.encode_with(b"Hello", &rot13).unwrap(), "Uryyb");
+
+        assert_eq!(Caesar.encode(b"abc xyz").unwrap(), "def abc");
+        let back = CodecOptions::new().with("shift", "-3");
+        assert_eq!(Caesar.encode_with(b"def", &back).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_letter_alphabets() {
+        let greek = CodecOptions::new().with("alphabet", "greek");
+        assert_eq!(Atbash.encode_with("αβγ Αλφα!".as_bytes(), &greek).unwrap(), "ωψχ Ωξδω!");
+        assert_eq!(Atbash.decode_with("ωψχ", Mode::Strict, &greek).unwrap(), "αβγ".as_bytes());
+        assert_eq!(Atbash.encode_with(b"plain ascii", &greek).unwrap(), "plain ascii");
+
+        let hebrew = CodecOptions::new().with("alphabet", "hebrew");
+        assert_eq!(Atbash.encode_with("אב ך".as_bytes(), &hebrew).unwrap(), "תש ך");
+
+        let cyrillic = CodecOptions::new().with("alphabet", "Cyrillic").with("shift", "1");
+        assert_eq!(Caesar.encode_with("Яблоко".as_bytes(), &cyrillic).unwrap(), "Авмплп");
+        assert_eq!(Caesar.decode_with("Авмплп", Mode::Strict, &cyrillic).unwrap(), "Яблоко".as_bytes());
+
+        let custom = CodecOptions::new().with("alphabet", "xyz").with("shift", "-1");
+        assert_eq!(Caesar.encode_with(b"xyzXa", &custom).unwrap(), "zxyZa");
+
+        assert!(Atbash.encode_with(&[0xff], &greek).is_err());
+        assert!(Atbash.encode_with(b"a", &CodecOptions::new().with("alphabet", "x")).is_err());
+        assert!(Atbash.encode_with(b"a", &CodecOptions::new().with("alphabet", "abca")).is_err());
+        assert!(Atbash.encode_with(b"a", &CodecOptions::new().with("shift", "1")).is_err());
+    }
+
+    #[test]
+    fn test_non_ascii_passes_through() {
+        assert_eq!(Caesar.encode("héllo ω".as_bytes()).unwrap(), "kéoor ω");
+        assert_eq!(Caesar.decode("kéoor ω", Mode::Strict).unwrap(), "héllo ω".as_bytes());
+        assert_eq!(Atbash.decode("Ωé", Mode::Strict).unwrap(), "Ωé".as_bytes());
+        let shift = CodecOptions::new().with("shift", "1");
+        assert_eq!(Caesar.decode_with("bé", Mode::Strict, &shift).unwrap(), "aé".as_bytes());
+
+        assert!(matches!(Caesar.encode(b"a\xffb"), Err(MbaseError::InvalidInputAt { position: 1, .. })));
+        let lenient = CodecOptions::new().with("utf8", "lenient");
+        assert_eq!(Affine.encode_with(b"a\xffb", &lenient).unwrap(), "i\u{FFFD}n");
+    }
+
+    #[test]
+    fn test_affine_roundtrip_all_keys() {
+        let data = b"The Quick Brown Fox, 42!";
+        for a in VALID_MULTIPLIERS {
+            for b in 0..26 {
+                let opts = CodecOptions::new().with("a", &a.to_string()).with("b", &b.to_string());
+                let encoded = Affine.encode_with(data, &opts).unwrap();
+                assert_eq!(Affine.decode_with(&encoded, Mode::Strict, &opts).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_affine_rejects_non_invertible_multiplier() {
+        for a in ["0", "2", "13", "26"] {
+            let opts = CodecOptions::new().with("a", a);
+            assert!(Affine.encode_with(b"abc", &opts).is_err(), "a={} should be rejected", a);
+        }
+        assert!(Affine.encode_with(b"abc", &CodecOptions::new().with("a", "x")).is_err());
+        assert!(Affine.encode_with(b"abc", &CodecOptions::new().with("c", "1")).is_err());
+    }
+}
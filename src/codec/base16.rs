@@ -2,12 +2,19 @@ use data_encoding::{Encoding, HEXLOWER, HEXLOWER_PERMISSIVE, HEXUPPER, HEXUPPER_
 
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 const LOWER_ALPHABET: &str = "0123456789abcdef";
 const UPPER_ALPHABET: &str = "0123456789ABCDEF";
 
 fn decode_hex(input: &str, mode: Mode, strict_enc: &Encoding, lenient_enc: &Encoding) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    decode_hex_into(input, mode, strict_enc, lenient_enc, &mut out)?;
+    Ok(out)
+}
+
+fn decode_hex_into(input: &str, mode: Mode, strict_enc: &Encoding, lenient_enc: &Encoding, out: &mut Vec<u8>) -> Result<()> {
     let cleaned = util::clean_for_mode(input, mode);
 
     let to_decode = if mode == Mode::Lenient && cleaned.starts_with("0x") {
@@ -25,8 +32,124 @@ fn decode_hex(input: &str, mode: Mode, strict_enc: &Encoding, lenient_enc: &Enco
         Mode::Lenient => lenient_enc,
     };
 
-    enc.decode(to_decode.as_bytes())
-        .map_err(|e| MbaseError::invalid_input(e.to_string()))
+    let start = out.len();
+    out.resize(start + to_decode.len() / 2, 0);
+    match enc.decode_mut(to_decode.as_bytes(), &mut out[start..]) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            out.truncate(start);
+            Err(MbaseError::invalid_input(e.error.to_string()))
+        }
+    }
+}
+
+/// Output layout selected by the `style` option.
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    Plain,
+    /// `0xde, 0xad` as in a C array initializer.
+    CArray,
+    /// `\xde\xad` as in a C or Python string literal.
+    Escape,
+}
+
+fn style(codec: &str, opts: &CodecOptions) -> Result<Style> {
+    opts.ensure_known(codec, &["style"])?;
+    match opts.get("style") {
+        None | Some("plain") => Ok(Style::Plain),
+        Some("c") | Some("array") => Ok(Style::CArray),
+        Some("escape") => Ok(Style::Escape),
+        Some(other) => Err(MbaseError::invalid_input(format!("option 'style' expects plain, c or escape, got '{}'", other))),
+    }
+}
+
+fn styled_len(codec: &str, input_len: usize, opts: &CodecOptions) -> Option<usize> {
+    match style(codec, opts).ok()? {
+        Style::Plain => input_len.checked_mul(2),
+        // "0xde" plus ", " between bytes.
+        Style::CArray => input_len.checked_mul(6).map(|n| n.saturating_sub(2)),
+        Style::Escape => input_len.checked_mul(4),
+    }
+}
+
+fn encode_styled(input: &[u8], style: Style, upper: bool) -> String {
+    let byte = |b: &u8| if upper { format!("{:02X}", b) } else { format!("{:02x}", b) };
+    match style {
+        Style::Plain => input.iter().map(byte).collect(),
+        Style::CArray => input.iter().map(|b| format!("0x{}", byte(b))).collect::<Vec<_>>().join(", "),
+        Style::Escape => input.iter().map(|b| format!("\\x{}", byte(b))).collect(),
+    }
+}
+
+fn parse_hex_byte(digits: &str, position: usize, mode: Mode, upper: bool) -> Result<u8> {
+    let case_ok = |c: char| mode == Mode::Lenient || !c.is_ascii_alphabetic() || c.is_ascii_uppercase() == upper;
+    let width_ok = digits.len() == 2 || (mode == Mode::Lenient && digits.len() == 1);
+    match digits.chars().enumerate().find(|(_, c)| !c.is_ascii_hexdigit() || !case_ok(*c)) {
+        Some((i, c)) => Err(MbaseError::InvalidCharacter {
+            char: c,
+            position: position + i,
+        }),
+        None if !width_ok => Err(MbaseError::invalid_input(format!("expected two hex digits at position {}, got '{}'", position, digits))),
+        None => u8::from_str_radix(digits, 16).map_err(|e| MbaseError::invalid_input(e.to_string())),
+    }
+}
+
+/// Parses `0xde, 0xad`. Lenient mode also accepts braces, a trailing comma or semicolon, `0X`,
+/// single-digit bytes and either letter case.
+fn decode_c_array(input: &str, mode: Mode, upper: bool) -> Result<Vec<u8>> {
+    let body = match mode {
+        Mode::Strict => input,
+        Mode::Lenient => input
+            .trim()
+            .trim_end_matches(';')
+            .trim()
+            .trim_start_matches('{')
+            .trim_end_matches('}'),
+    };
+    let offset = body.as_ptr() as usize - input.as_ptr() as usize;
+    let mut out = Vec::new();
+    let mut pos = offset;
+    for (i, token) in body.split(',').enumerate() {
+        let start = pos + token.len() - token.trim_start().len();
+        pos += token.len() + 1;
+        let token = token.trim();
+        if token.is_empty() && mode == Mode::Lenient && i > 0 {
+            continue;
+        }
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| if mode == Mode::Lenient { token.strip_prefix("0X") } else { None })
+            .ok_or_else(|| MbaseError::invalid_input(format!("expected 0x-prefixed byte at position {}, got '{}'", start, token)))?;
+        out.push(parse_hex_byte(digits, start + 2, mode, upper)?);
+    }
+    Ok(out)
+}
+
+/// Parses `\xde\xad`. Lenient mode also accepts surrounding or repeated double quotes, whitespace
+/// between escapes and either letter case.
+fn decode_escaped(input: &str, mode: Mode, upper: bool) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some((_, 'x'))) => {
+                chars.next();
+                let rest = &input[i + 2..];
+                let digits = match mode {
+                    Mode::Strict => rest.get(..2).unwrap_or(rest),
+                    Mode::Lenient => &rest[..rest.bytes().take(2).take_while(u8::is_ascii_hexdigit).count()],
+                };
+                out.push(parse_hex_byte(digits, i + 2, mode, upper)?);
+                for _ in 0..digits.len() {
+                    chars.next();
+                }
+            }
+            '"' if mode == Mode::Lenient => {}
+            c if mode == Mode::Lenient && c.is_whitespace() => {}
+            c => return Err(MbaseError::InvalidCharacter { char: c, position: i }),
+        }
+    }
+    Ok(out)
 }
 
 fn detect_hex(input: &str, codec_name: &str, multibase_code: char) -> DetectCandidate {
@@ -95,6 +218,35 @@ impl Codec for Base16Lower {
         decode_hex(input, mode, &HEXLOWER, &HEXLOWER_PERMISSIVE)
     }
 
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        HEXLOWER.encode_append(input, out);
+        Ok(())
+    }
+
+    fn decode_into(&self, input: &str, mode: Mode, out: &mut Vec<u8>) -> Result<()> {
+        decode_hex_into(input, mode, &HEXLOWER, &HEXLOWER_PERMISSIVE, out)
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Ok(encode_styled(input, style("base16lower", opts)?, false))
+    }
+
+    fn encoded_len(&self, input_len: usize, opts: &CodecOptions) -> Option<usize> {
+        styled_len("base16lower", input_len, opts)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        match style("base16lower", opts)? {
+            Style::Plain => self.decode(input, mode),
+            Style::CArray => decode_c_array(input, mode, false),
+            Style::Escape => decode_escaped(input, mode, false),
+        }
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((1, 2))
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         detect_hex(input, "base16lower", 'f')
     }
@@ -123,6 +275,35 @@ impl Codec for Base16Upper {
         decode_hex(input, mode, &HEXUPPER, &HEXUPPER_PERMISSIVE)
     }
 
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        HEXUPPER.encode_append(input, out);
+        Ok(())
+    }
+
+    fn decode_into(&self, input: &str, mode: Mode, out: &mut Vec<u8>) -> Result<()> {
+        decode_hex_into(input, mode, &HEXUPPER, &HEXUPPER_PERMISSIVE, out)
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Ok(encode_styled(input, style("base16upper", opts)?, true))
+    }
+
+    fn encoded_len(&self, input_len: usize, opts: &CodecOptions) -> Option<usize> {
+        styled_len("base16upper", input_len, opts)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        match style("base16upper", opts)? {
+            Style::Plain => self.decode(input, mode),
+            Style::CArray => decode_c_array(input, mode, true),
+            Style::Escape => decode_escaped(input, mode, true),
+        }
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((1, 2))
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         detect_hex(input, "base16upper", 'F')
     }
@@ -150,6 +331,21 @@ mod tests {
         assert_eq!(Base16Lower.decode(&Base16Lower.encode(data).unwrap(), Mode::Strict).unwrap(), data);
     }
 
+    #[test]
+    fn test_base16_into_appends() {
+        let mut text = String::from("id=");
+        Base16Lower.encode_into(b"Hi", &mut text).unwrap();
+        Base16Upper.encode_into(b"\xff", &mut text).unwrap();
+        assert_eq!(text, "id=4869FF");
+
+        let mut bytes = b"<".to_vec();
+        Base16Lower.decode_into("4869", Mode::Strict, &mut bytes).unwrap();
+        Base16Lower.decode_into("0x4A", Mode::Lenient, &mut bytes).unwrap();
+        assert_eq!(bytes, b"<HiJ");
+        assert!(Base16Lower.decode_into("48zz", Mode::Strict, &mut bytes).is_err());
+        assert_eq!(bytes, b"<HiJ");
+    }
+
     #[test]
     fn test_base16_lenient_case() {
         assert_eq!(Base16Lower.decode("48656C6C6F", Mode::Lenient).unwrap(), b"Hello");
@@ -180,4 +376,29 @@ mod tests {
         assert_eq!(Base16Lower.encode(&[]).unwrap(), "");
         assert_eq!(Base16Lower.decode("", Mode::Strict).unwrap(), Vec::<u8>::new());
     }
+
+    #[test]
+    fn test_base16_c_array_style() {
+        let opts = CodecOptions::new().with("style", "c");
+        assert_eq!(Base16Lower.encode_with(&[0xde, 0xad, 0xbe, 0xef], &opts).unwrap(), "0xde, 0xad, 0xbe, 0xef");
+        assert_eq!(Base16Upper.encode_with(&[0xde, 0x01], &opts).unwrap(), "0xDE, 0x01");
+        assert_eq!(Base16Lower.decode_with("0xde,0xad", Mode::Strict, &opts).unwrap(), vec![0xde, 0xad]);
+        assert_eq!(Base16Lower.decode_with("{ 0xDE, 0XAD,\n  0x1, };", Mode::Lenient, &opts).unwrap(), vec![0xde, 0xad, 0x01]);
+        assert!(Base16Lower.decode_with("0xDE", Mode::Strict, &opts).is_err());
+        assert!(Base16Lower.decode_with("0x1", Mode::Strict, &opts).is_err());
+        assert!(Base16Lower.decode_with("{0xde}", Mode::Strict, &opts).is_err());
+    }
+
+    #[test]
+    fn test_base16_escape_style() {
+        let opts = CodecOptions::new().with("style", "escape");
+        assert_eq!(Base16Lower.encode_with(&[0xde, 0xad], &opts).unwrap(), "\\xde\\xad");
+        assert_eq!(Base16Lower.decode_with("\\xde\\xad", Mode::Strict, &opts).unwrap(), vec![0xde, 0xad]);
+        assert_eq!(Base16Lower.decode_with("\"\\xDE\\xad\"\n\"\\xbe\"", Mode::Lenient, &opts).unwrap(), vec![0xde, 0xad, 0xbe]);
+        assert_eq!(Base16Lower.decode_with("\\xd\\x0a", Mode::Lenient, &opts).unwrap(), vec![0x0d, 0x0a]);
+        let err = Base16Lower.decode_with("\\xde\\xzz", Mode::Strict, &opts).unwrap_err();
+        assert!(matches!(err, MbaseError::InvalidCharacter { char: 'z', position: 6 }), "{:?}", err);
+        assert!(Base16Lower.decode_with("\\xd", Mode::Strict, &opts).is_err());
+        assert!(Base16Lower.encode_with(b"", &CodecOptions::new().with("style", "bogus")).is_err());
+    }
 }
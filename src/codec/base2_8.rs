@@ -1,6 +1,7 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 pub struct Base2;
 
@@ -21,10 +22,21 @@ impl Codec for Base2 {
         Ok(input.iter().map(|&b| format!("{:08b}", b)).collect::<String>())
     }
 
+    fn encoded_len(&self, input_len: usize, opts: &CodecOptions) -> Option<usize> {
+        opts.ensure_known(self.name(), &[]).ok()?;
+        input_len.checked_mul(8)
+    }
+
+    fn decoded_len(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Option<usize> {
+        opts.ensure_known(self.name(), &[]).ok()?;
+        let digits = util::clean_for_mode(input, mode).len();
+        digits.is_multiple_of(8).then_some(digits / 8)
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
 
-        if cleaned.len() % 8 != 0 {
+        if !cleaned.len().is_multiple_of(8) {
             return Err(MbaseError::invalid_length(crate::error::LengthConstraint::MultipleOf(8), cleaned.len()));
         }
 
@@ -32,12 +44,16 @@ impl Codec for Base2 {
             .as_bytes()
             .chunks(8)
             .map(|chunk| {
-                let s = std::str::from_utf8(chunk).map_err(|_| MbaseError::invalid_input("invalid UTF-8"))?;
+                let s = core::str::from_utf8(chunk).map_err(|_| MbaseError::invalid_input("invalid UTF-8"))?;
                 u8::from_str_radix(s, 2).map_err(|e| MbaseError::invalid_input(format!("invalid binary digit: {}", e)))
             })
             .collect()
     }
 
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((1, 8))
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -59,10 +75,10 @@ impl Codec for Base2 {
             confidence = util::confidence::ALPHABET_MATCH;
             reasons.push("all characters are binary digits".to_string());
 
-            if input.len() >= 16 && input.len() % 8 == 0 {
+            if input.len() >= 16 && input.len().is_multiple_of(8) {
                 confidence = util::confidence::ALPHABET_MATCH;
                 reasons.push("length is multiple of 8".to_string());
-            } else if input.len() % 8 != 0 {
+            } else if !input.len().is_multiple_of(8) {
                 warnings.push("length not multiple of 8".to_string());
             }
         } else if ratio > 0.9 {
@@ -98,10 +114,21 @@ impl Codec for Base8 {
         Ok(input.iter().map(|&b| format!("{:03o}", b)).collect::<String>())
     }
 
+    fn encoded_len(&self, input_len: usize, opts: &CodecOptions) -> Option<usize> {
+        opts.ensure_known(self.name(), &[]).ok()?;
+        input_len.checked_mul(3)
+    }
+
+    fn decoded_len(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Option<usize> {
+        opts.ensure_known(self.name(), &[]).ok()?;
+        let digits = util::clean_for_mode(input, mode).len();
+        digits.is_multiple_of(3).then_some(digits / 3)
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
 
-        if cleaned.len() % 3 != 0 {
+        if !cleaned.len().is_multiple_of(3) {
             return Err(MbaseError::invalid_length(crate::error::LengthConstraint::MultipleOf(3), cleaned.len()));
         }
 
@@ -109,12 +136,16 @@ impl Codec for Base8 {
             .as_bytes()
             .chunks(3)
             .map(|chunk| {
-                let s = std::str::from_utf8(chunk).map_err(|_| MbaseError::invalid_input("invalid UTF-8"))?;
+                let s = core::str::from_utf8(chunk).map_err(|_| MbaseError::invalid_input("invalid UTF-8"))?;
                 u8::from_str_radix(s, 8).map_err(|e| MbaseError::invalid_input(format!("invalid octal digit: {}", e)))
             })
             .collect()
     }
 
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((1, 3))
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -136,7 +167,7 @@ impl Codec for Base8 {
             confidence = util::confidence::ALPHABET_MATCH;
             reasons.push("all characters are octal digits".to_string());
 
-            if input.len() % 3 == 0 {
+            if input.len().is_multiple_of(3) {
                 confidence = util::confidence::ALPHABET_MATCH;
                 reasons.push("length is multiple of 3".to_string());
             } else {
@@ -159,6 +190,24 @@ impl Codec for Base8 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::MbaseError;
+    use crate::types::{CodecOptions, Context};
+
+    #[test]
+    fn test_base2_output_limit_rejected_up_front() {
+        let ctx = Context::default().with_max_output_size(Some(15));
+        assert!(matches!(ctx.encode(&Base2, b"AB", &CodecOptions::new()), Err(MbaseError::OutputTooLarge { size: 16, limit: 15 })));
+        assert_eq!(ctx.encode(&Base8, b"AB", &CodecOptions::new()).unwrap(), "101102");
+        assert!(ctx.decode(&Base8, "101102103104105106", Mode::Strict, &CodecOptions::new()).is_ok());
+        let limited = ctx.with_max_output_size(Some(5));
+        let err = limited
+            .decode(&Base8, "101102103104105106", Mode::Strict, &CodecOptions::new())
+            .unwrap_err();
+        assert!(matches!(err, MbaseError::OutputTooLarge { size: 6, limit: 5 }));
+        assert!(limited
+            .decode(&Base8, "10110210310410510", Mode::Strict, &CodecOptions::new())
+            .is_err());
+    }
 
     #[test]
     fn test_base2_encode() {
@@ -1,9 +1,10 @@
 use data_encoding::{Encoding, Specification};
-use std::sync::OnceLock;
+use once_cell::race::OnceBox;
 
 use super::util;
 use super::Codec;
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const RFC4648_LOWER: &str = "abcdefghijklmnopqrstuvwxyz234567";
@@ -20,38 +21,38 @@ fn make_encoding(alphabet: &str, padding: bool) -> Encoding {
     spec.encoding().unwrap()
 }
 
-static BASE32_LOWER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_UPPER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_PAD_LOWER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_PAD_UPPER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_HEX_LOWER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_HEX_UPPER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_HEX_PAD_LOWER: OnceLock<Encoding> = OnceLock::new();
-static BASE32_HEX_PAD_UPPER: OnceLock<Encoding> = OnceLock::new();
+static BASE32_LOWER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_UPPER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_PAD_LOWER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_PAD_UPPER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_HEX_LOWER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_HEX_UPPER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_HEX_PAD_LOWER: OnceBox<Encoding> = OnceBox::new();
+static BASE32_HEX_PAD_UPPER: OnceBox<Encoding> = OnceBox::new();
 
 fn get_base32_lower() -> &'static Encoding {
-    BASE32_LOWER.get_or_init(|| make_encoding(RFC4648_LOWER, false))
+    BASE32_LOWER.get_or_init(|| Box::new(make_encoding(RFC4648_LOWER, false)))
 }
 fn get_base32_upper() -> &'static Encoding {
-    BASE32_UPPER.get_or_init(|| make_encoding(RFC4648_UPPER, false))
+    BASE32_UPPER.get_or_init(|| Box::new(make_encoding(RFC4648_UPPER, false)))
 }
 fn get_base32_pad_lower() -> &'static Encoding {
-    BASE32_PAD_LOWER.get_or_init(|| make_encoding(RFC4648_LOWER, true))
+    BASE32_PAD_LOWER.get_or_init(|| Box::new(make_encoding(RFC4648_LOWER, true)))
 }
 fn get_base32_pad_upper() -> &'static Encoding {
-    BASE32_PAD_UPPER.get_or_init(|| make_encoding(RFC4648_UPPER, true))
+    BASE32_PAD_UPPER.get_or_init(|| Box::new(make_encoding(RFC4648_UPPER, true)))
 }
 fn get_base32_hex_lower() -> &'static Encoding {
-    BASE32_HEX_LOWER.get_or_init(|| make_encoding(HEX_LOWER, false))
+    BASE32_HEX_LOWER.get_or_init(|| Box::new(make_encoding(HEX_LOWER, false)))
 }
 fn get_base32_hex_upper() -> &'static Encoding {
-    BASE32_HEX_UPPER.get_or_init(|| make_encoding(HEX_UPPER, false))
+    BASE32_HEX_UPPER.get_or_init(|| Box::new(make_encoding(HEX_UPPER, false)))
 }
 fn get_base32_hex_pad_lower() -> &'static Encoding {
-    BASE32_HEX_PAD_LOWER.get_or_init(|| make_encoding(HEX_LOWER, true))
+    BASE32_HEX_PAD_LOWER.get_or_init(|| Box::new(make_encoding(HEX_LOWER, true)))
 }
 fn get_base32_hex_pad_upper() -> &'static Encoding {
-    BASE32_HEX_PAD_UPPER.get_or_init(|| make_encoding(HEX_UPPER, true))
+    BASE32_HEX_PAD_UPPER.get_or_init(|| Box::new(make_encoding(HEX_UPPER, true)))
 }
 
 fn decode_base32(
@@ -61,11 +62,13 @@ fn decode_base32(
     pad_enc: &Encoding,
     expects_padding: bool,
     is_lowercase: bool,
+    alphabet: &str,
 ) -> Result<Vec<u8>> {
     let cleaned = util::clean_for_mode(input, mode);
 
     match mode {
         Mode::Strict => {
+            util::check_trailing_bits(cleaned.trim_end_matches('='), alphabet, 5)?;
             let e = if expects_padding { pad_enc } else { enc };
             e.decode(cleaned.as_bytes()).map_err(|e| MbaseError::invalid_input(e.to_string()))
         }
@@ -76,6 +79,7 @@ fn decode_base32(
                 cleaned.to_uppercase()
             };
             let stripped = normalized.trim_end_matches('=').trim_end_matches('=');
+            util::check_trailing_bits(stripped, alphabet, 5)?;
             enc.decode(stripped.as_bytes())
                 .or_else(|_| {
                     let padded = pad_to_base32(stripped);
@@ -135,6 +139,9 @@ fn detect_base32(input: &str, codec_name: &str, alphabet: &str, multibase_code:
         confidence += 0.1;
     }
 
+    // Separates variants whose alphabets accept the same characters (base32 and base32hex).
+    confidence += util::payload_fit_bonus(input, alphabet, 5, 0.1, &mut reasons);
+
     DetectCandidate {
         codec: codec_name.to_string(),
         confidence: confidence.min(1.0),
@@ -168,7 +175,11 @@ macro_rules! impl_base32_codec {
             }
 
             fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-                decode_base32(input, mode, $enc_fn(), $pad_enc_fn(), $expects_padding, $is_lowercase)
+                decode_base32(input, mode, $enc_fn(), $pad_enc_fn(), $expects_padding, $is_lowercase, $alphabet)
+            }
+
+            fn stream_block(&self) -> Option<(usize, usize)> {
+                Some((5, 8))
             }
 
             fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -356,6 +367,15 @@ mod tests {
         assert!(Base32Lower.validate("jbswy3dp======", Mode::Strict).is_err());
     }
 
+    #[test]
+    fn test_base32_non_canonical_trailing_bits() {
+        let err = Base32Lower.decode("nbswy3dpeb", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("'b' at position 9 (canonical: 'a')"), "{}", err);
+        assert!(Base32PadUpper.decode("NBSWY3DPEB======", Mode::Strict).is_err());
+        assert!(Base32HexLower.decode("d1imor3f41", Mode::Lenient).is_err());
+        assert_eq!(Base32Lower.decode("nbswy3dpea", Mode::Strict).unwrap(), b"hello ");
+    }
+
     #[test]
     fn test_base32_empty() {
         assert_eq!(Base32Lower.encode(&[]).unwrap(), "");
@@ -1,20 +1,23 @@
 use data_encoding::{Encoding, Specification};
-use std::sync::OnceLock;
+use once_cell::race::OnceBox;
 
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const ZBASE32_ALPHABET_FULL: &str = "ybndrfg8ejkmcpqxot1uwisza345h769";
 const CROCKFORD_ALPHABET: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
 
-static ZBASE32_ENCODING: OnceLock<Encoding> = OnceLock::new();
+static CROCKFORD_SYMBOLS: util::SymbolTable = util::SymbolTable::new(CROCKFORD_ALPHABET);
+
+static ZBASE32_ENCODING: OnceBox<Encoding> = OnceBox::new();
 
 fn get_zbase32() -> &'static Encoding {
     ZBASE32_ENCODING.get_or_init(|| {
         let mut spec = Specification::new();
         spec.symbols.push_str(ZBASE32_ALPHABET_FULL);
-        spec.encoding().unwrap()
+        Box::new(spec.encoding().unwrap())
     })
 }
 
@@ -61,6 +64,7 @@ fn crockford_decode(input: &str, mode: Mode) -> Result<Vec<u8>> {
     let mut bits = 0;
     let mut result = Vec::new();
 
+    let last = cleaned.char_indices().last();
     for (pos, ch) in cleaned.chars().enumerate() {
         let val = crockford_char_value(ch, mode)?;
         if val.is_none() {
@@ -80,7 +84,11 @@ fn crockford_decode(input: &str, mode: Mode) -> Result<Vec<u8>> {
     if bits > 0 {
         let remaining_bits = buffer & ((1 << bits) - 1);
         if remaining_bits != 0 {
-            return Err(MbaseError::invalid_input("crockford32 decode: non-zero padding bits"));
+            let (position, ch) = last.unwrap_or_default();
+            return Err(MbaseError::invalid_input(format!(
+                "crockford32 decode: non-zero trailing bits in final character '{}' at position {}",
+                ch, position
+            )));
         }
     }
 
@@ -148,6 +156,47 @@ fn validate_crockford(input: &str, mode: Mode) -> Result<()> {
     Ok(())
 }
 
+/// Clockwork Base32 decoding: Crockford's alphabet in either case. Lenient mode also reads O as
+/// 0 and I/L as 1, skips whitespace and drops the leftover bits of a final character instead of
+/// requiring them to be zero.
+fn clockwork_decode(input: &str, mode: Mode) -> Result<Vec<u8>> {
+    let cleaned: String = match mode {
+        Mode::Strict => input.to_string(),
+        Mode::Lenient => input.chars().filter(|c| !c.is_ascii_whitespace()).collect(),
+    };
+    let count = cleaned.chars().count();
+    if mode == Mode::Strict {
+        if matches!(count % 8, 1 | 3 | 6) {
+            return Err(MbaseError::invalid_input(format!(
+                "clockwork32 decode: {} characters leave a partial byte ({} extra bits)",
+                count,
+                count * 5 % 8
+            )));
+        }
+        util::check_trailing_bits(&cleaned.to_ascii_uppercase(), CROCKFORD_ALPHABET, 5)?;
+    }
+
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    let mut result = Vec::with_capacity(count * 5 / 8);
+    for (pos, ch) in cleaned.chars().enumerate() {
+        let val = match mode {
+            Mode::Strict => CROCKFORD_SYMBOLS.value(ch.to_ascii_uppercase()),
+            Mode::Lenient => crockford_char_value(ch, mode)?,
+        };
+        let Some(val) = val else {
+            return Err(MbaseError::InvalidCharacter { char: ch, position: pos });
+        };
+        buffer = (buffer << 5 | u32::from(val)) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(result)
+}
+
 pub struct ZBase32;
 
 impl Codec for ZBase32 {
@@ -176,6 +225,7 @@ impl Codec for ZBase32 {
             Mode::Strict => input.to_string(),
             Mode::Lenient => input.chars().filter(|c| !c.is_ascii_whitespace()).collect(),
         };
+        util::check_trailing_bits(&cleaned, ZBASE32_ALPHABET_FULL, 5)?;
         get_zbase32()
             .decode(cleaned.as_bytes())
             .map_err(|e| MbaseError::invalid_input(e.to_string()))
@@ -191,7 +241,7 @@ impl Codec for ZBase32 {
                 return Err(MbaseError::InvalidCharacter { char: ch, position: pos });
             }
         }
-        Ok(())
+        util::check_trailing_bits(&cleaned, ZBASE32_ALPHABET_FULL, 5)
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -220,6 +270,10 @@ impl Codec for ZBase32 {
             reasons.push("all characters valid".to_string());
         }
 
+        // Its characters are valid in most other base32 and base64 alphabets too, so how well
+        // the symbol values fit a payload is what sets it apart.
+        confidence += util::payload_fit_bonus(input, ZBASE32_ALPHABET_FULL, 5, 0.3, &mut reasons);
+
         DetectCandidate {
             codec: "zbase32".to_string(),
             confidence: confidence.min(1.0),
@@ -285,6 +339,8 @@ impl Codec for Crockford32 {
             reasons.push("all characters valid".to_string());
         }
 
+        confidence += util::payload_fit_bonus(input, CROCKFORD_ALPHABET, 5, 0.3, &mut reasons);
+
         if input.chars().any(|c| c == 'I' || c == 'L' || c == 'O') {
             warnings.push("contains confusable characters (I/L/O)".to_string());
         }
@@ -298,6 +354,56 @@ impl Codec for Crockford32 {
     }
 }
 
+pub struct Clockwork32;
+
+impl Codec for Clockwork32 {
+    fn name(&self) -> &'static str {
+        "clockwork32"
+    }
+
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "clockwork32",
+            aliases: &["clockwork", "cw32"],
+            alphabet: CROCKFORD_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Clockwork Base32 (Crockford alphabet, no check symbol or hyphens)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        Ok(crockford_encode(input))
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        clockwork_decode(input, mode)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        if input.is_empty() || !input.chars().all(|c| CROCKFORD_ALPHABET.contains(c.to_ascii_uppercase())) {
+            return util::confidence::no_match("clockwork32");
+        }
+        let mut reasons = vec!["all characters valid".to_string()];
+        // Upper-case output is the same as crockford32's, which is the better-known name; only
+        // lower-case input, which Crockford decoders treat as non-canonical, leans this way.
+        let mut confidence = util::confidence::PARTIAL_MATCH - 0.05;
+        if input.chars().any(|c| c.is_ascii_lowercase()) {
+            confidence += 0.1;
+            reasons.push("lower-case letters (Clockwork decoders ignore case)".to_string());
+        }
+        confidence += util::payload_fit_bonus(input, CROCKFORD_ALPHABET, 5, 0.3, &mut reasons);
+
+        DetectCandidate {
+            codec: "clockwork32".to_string(),
+            confidence: confidence.min(1.0),
+            reasons,
+            warnings: vec![],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,6 +413,17 @@ mod tests {
         assert_eq!(ZBase32.encode(b"Hello").unwrap(), "jb1sa5dx");
     }
 
+    #[test]
+    fn test_detect_prefers_the_alphabet_that_fits() {
+        use crate::codec::base32::Base32Lower;
+        // "hello world" in z-base-32 and in RFC 4648 base32; both fit either alphabet.
+        let zbase = "pb1sa5dxrb5s6hucco";
+        let rfc = "nbswy3dpeb3w64tmmq";
+        assert!(ZBase32.detect_score(zbase).confidence > ZBase32.detect_score(rfc).confidence);
+        assert!(Base32Lower.detect_score(rfc).confidence > ZBase32.detect_score(rfc).confidence);
+        assert!(ZBase32.detect_score(zbase).reasons.iter().any(|r| r.contains("typical payload")));
+    }
+
     #[test]
     fn test_zbase32_decode() {
         assert_eq!(ZBase32.decode("jb1sa5dx", Mode::Strict).unwrap(), b"Hello");
@@ -398,4 +515,32 @@ mod tests {
         let result = Crockford32.decode(&modified, Mode::Strict);
         assert!(result.is_err(), "should reject invalid padding bits");
     }
+
+    #[test]
+    fn test_clockwork_reference_vectors() {
+        // From the Clockwork Base32 specification.
+        for (data, encoded) in [
+            (&b""[..], ""),
+            (b"f", "CR"),
+            (b"foobar", "CSQPYRK1E8"),
+            (b"Hello, world!", "91JPRV3F5GG7EVVJDHJ22"),
+            (b"The quick brown fox jumps over the lazy dog.", "AHM6A83HENMP6TS0C9S6YXVE41K6YY10D9TPTW3K41QQCSBJ41T6GS90DHGQMY90CHQPEBG"),
+        ] {
+            assert_eq!(Clockwork32.encode(data).unwrap(), encoded);
+            assert_eq!(Clockwork32.decode(encoded, Mode::Strict).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_clockwork_decode_modes() {
+        assert_eq!(Clockwork32.decode("csqpyrk1e8", Mode::Strict).unwrap(), b"foobar");
+        assert_eq!(Clockwork32.decode("CSQPYRKIE8", Mode::Lenient).unwrap(), b"foobar");
+        assert!(Clockwork32.decode("CSQPYRKIE8", Mode::Strict).is_err());
+        // "CR" carries two leftover bits; "CS" sets one of them.
+        assert!(Clockwork32.decode("CS", Mode::Strict).is_err());
+        assert_eq!(Clockwork32.decode("CS", Mode::Lenient).unwrap(), b"f");
+        assert!(Clockwork32.decode("CRC", Mode::Strict).is_err());
+        assert!(matches!(Clockwork32.decode("CU", Mode::Lenient), Err(MbaseError::InvalidCharacter { char: 'U', position: 1 })));
+        assert!(Clockwork32.decode("91JP-RV3F", Mode::Lenient).is_err());
+    }
 }
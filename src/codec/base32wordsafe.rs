@@ -1,14 +1,15 @@
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 use data_encoding::{Encoding, Specification};
-use std::sync::OnceLock;
+use once_cell::race::OnceBox;
 
 pub struct Base32WordSafe;
 
 const WORDSAFE_ALPHABET: &str = "ybndrfg8ejkmcpqxot1uwisza345h769";
 
-static BASE32_WORDSAFE: OnceLock<Encoding> = OnceLock::new();
+static BASE32_WORDSAFE: OnceBox<Encoding> = OnceBox::new();
 
 fn get_wordsafe_encoding() -> &'static Encoding {
     BASE32_WORDSAFE.get_or_init(|| {
@@ -17,7 +18,7 @@ fn get_wordsafe_encoding() -> &'static Encoding {
         // Make it case-insensitive by translating uppercase to lowercase
         spec.translate.from.push_str(&WORDSAFE_ALPHABET.to_uppercase());
         spec.translate.to.push_str(WORDSAFE_ALPHABET);
-        spec.encoding().unwrap()
+        Box::new(spec.encoding().unwrap())
     })
 }
 
@@ -45,6 +46,7 @@ impl Codec for Base32WordSafe {
             input.to_string()
         };
 
+        util::check_trailing_bits(&cleaned.to_lowercase(), WORDSAFE_ALPHABET, 5)?;
         get_wordsafe_encoding()
             .decode(cleaned.as_bytes())
             .map_err(|e| Error::invalid_input(e.to_string()))
@@ -64,10 +66,14 @@ impl Codec for Base32WordSafe {
         let ratio = valid_chars as f32 / input.len() as f32;
 
         if ratio > 0.95 {
+            // The z-base-32 alphabet: most other base32 and base64 input fits it too, so the
+            // symbol values have to fit a payload before it ranks with the RFC 4648 variants.
+            let mut reasons = vec!["high match ratio".to_string()];
+            let bonus = util::payload_fit_bonus(input, WORDSAFE_ALPHABET, 5, 0.3, &mut reasons);
             DetectCandidate {
                 codec: "base32wordsafe".to_string(),
-                confidence: util::confidence::ALPHABET_MATCH,
-                reasons: vec!["high match ratio".to_string()],
+                confidence: util::confidence::PARTIAL_MATCH + bonus,
+                reasons,
                 warnings: vec![],
             }
         } else if ratio > 0.8 {
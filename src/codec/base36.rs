@@ -1,5 +1,6 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const LOWER_ALPHABET: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
@@ -25,7 +26,7 @@ fn encode_base36(input: &[u8], alphabet: &[u8]) -> String {
     });
 
     let leading_zeros = input.iter().take_while(|&&b| b == 0).count();
-    num.extend(std::iter::repeat_n(0, leading_zeros));
+    num.extend(core::iter::repeat_n(0, leading_zeros));
 
     num.iter().rev().map(|&d| alphabet[d as usize] as char).collect()
 }
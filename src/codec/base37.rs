@@ -1,76 +1,130 @@
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
+/// Digits, letters and space, in that order.
 pub struct Base37;
 
+/// The legacy name packing order: `_` as zero, then the letters, then the digits.
+pub struct Base37Name;
+
 const ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ ";
+const NAME_ALPHABET: &str = "_abcdefghijklmnopqrstuvwxyz0123456789";
 
-fn encode_base37(input: &[u8]) -> String {
-    if input.is_empty() {
-        return String::new();
-    }
+/// A base37 digit order plus how input letters are case-folded before lookup.
+struct Digits<'a> {
+    alphabet: &'a str,
+    fold: fn(&str) -> String,
+}
 
-    let alphabet = ALPHABET.as_bytes();
-    let mut num = input.iter().fold(Vec::new(), |mut acc, &byte| {
-        let mut carry = byte as u32;
-        for digit in acc.iter_mut() {
-            carry += (*digit as u32) << 8;
-            *digit = (carry % 37) as u8;
-            carry /= 37;
+impl<'a> Digits<'a> {
+    /// The built-in order, or `--opt alphabet=` with 37 distinct characters matched exactly.
+    fn from_options(codec: &str, opts: &'a CodecOptions, alphabet: &'static str, fold: fn(&str) -> String) -> Result<Self> {
+        opts.ensure_known(codec, &["alphabet"])?;
+        let Some(custom) = opts.get("alphabet") else {
+            return Ok(Self { alphabet, fold });
+        };
+        let mut chars: Vec<char> = custom.chars().collect();
+        chars.sort_unstable();
+        chars.dedup();
+        if chars.len() != 37 || custom.chars().count() != 37 {
+            return Err(Error::invalid_input(format!("option 'alphabet' needs 37 distinct characters, got '{}'", custom)));
         }
-        while carry > 0 {
-            acc.push((carry % 37) as u8);
-            carry /= 37;
-        }
-        acc
-    });
+        Ok(Self {
+            alphabet: custom,
+            fold: str::to_string,
+        })
+    }
 
-    let leading_zeros = input.iter().take_while(|&&b| b == 0).count();
-    num.extend(std::iter::repeat_n(0, leading_zeros));
+    fn encode(&self, input: &[u8]) -> String {
+        if input.is_empty() {
+            return String::new();
+        }
 
-    num.iter().rev().map(|&d| alphabet[d as usize] as char).collect()
-}
+        let alphabet: Vec<char> = self.alphabet.chars().collect();
+        let mut num = input.iter().fold(Vec::new(), |mut acc, &byte| {
+            let mut carry = byte as u32;
+            for digit in acc.iter_mut() {
+                carry += (*digit as u32) << 8;
+                *digit = (carry % 37) as u8;
+                carry /= 37;
+            }
+            while carry > 0 {
+                acc.push((carry % 37) as u8);
+                carry /= 37;
+            }
+            acc
+        });
 
-fn decode_base37(input: &str, mode: Mode) -> Result<Vec<u8>> {
-    let cleaned = if mode == Mode::Lenient {
-        input.chars().filter(|c| !c.is_whitespace() || *c == ' ').collect::<String>()
-    } else {
-        input.to_string()
-    };
+        let leading_zeros = input.iter().take_while(|&&b| b == 0).count();
+        num.extend(core::iter::repeat_n(0, leading_zeros));
 
-    if cleaned.is_empty() {
-        return Ok(Vec::new());
+        num.iter().rev().map(|&d| alphabet[d as usize]).collect()
     }
 
-    let normalized = cleaned.to_uppercase();
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let cleaned = if mode == Mode::Lenient {
+            input
+                .chars()
+                .filter(|&c| !c.is_whitespace() || self.alphabet.contains(c))
+                .collect::<String>()
+        } else {
+            input.to_string()
+        };
 
-    for (pos, ch) in normalized.chars().enumerate() {
-        if !ALPHABET.contains(ch) {
-            return Err(Error::InvalidCharacter { char: ch, position: pos });
+        if cleaned.is_empty() {
+            return Ok(Vec::new());
         }
-    }
-
-    let leading_zeros = normalized.chars().take_while(|&c| c == '0').count();
 
-    let mut result = normalized.chars().fold(Vec::new(), |mut acc, ch| {
-        let digit = ALPHABET.chars().position(|c| c == ch).unwrap() as u8;
+        let normalized = (self.fold)(&cleaned);
+        let alphabet: Vec<char> = self.alphabet.chars().collect();
 
-        let mut carry = digit as u32;
-        for byte in acc.iter_mut().rev() {
-            carry += (*byte as u32) * 37;
-            *byte = (carry & 0xff) as u8;
-            carry >>= 8;
-        }
-        while carry > 0 {
-            acc.insert(0, (carry & 0xff) as u8);
-            carry >>= 8;
+        let mut digits = Vec::with_capacity(normalized.len());
+        for (pos, ch) in normalized.chars().enumerate() {
+            match alphabet.iter().position(|&c| c == ch) {
+                Some(digit) => digits.push(digit as u8),
+                None => return Err(Error::InvalidCharacter { char: ch, position: pos }),
+            }
         }
-        acc
-    });
 
-    result.splice(0..0, std::iter::repeat_n(0, leading_zeros));
-    Ok(result)
+        let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+
+        let mut result = digits.iter().fold(Vec::new(), |mut acc, &digit| {
+            let mut carry = digit as u32;
+            for byte in acc.iter_mut().rev() {
+                carry += (*byte as u32) * 37;
+                *byte = (carry & 0xff) as u8;
+                carry >>= 8;
+            }
+            while carry > 0 {
+                acc.insert(0, (carry & 0xff) as u8);
+                carry >>= 8;
+            }
+            acc
+        });
+
+        result.splice(0..0, core::iter::repeat_n(0, leading_zeros));
+        Ok(result)
+    }
+}
+
+/// Share of `input` in `alphabet` (case-folded), scored higher when `marker` shows up too.
+fn detect_base37(codec: &str, input: &str, alphabet: &str, marker: char, fold: fn(&str) -> String) -> DetectCandidate {
+    if input.is_empty() {
+        return util::confidence::no_match(codec);
+    }
+
+    let valid_chars = fold(input).chars().filter(|&c| alphabet.contains(c)).count();
+    let ratio = valid_chars as f32 / input.chars().count() as f32;
+
+    if ratio > 0.95 && input.contains(marker) {
+        util::confidence::with_confidence(codec, util::confidence::PARTIAL_MATCH, format!("high ratio with '{}'", marker))
+    } else if ratio > 0.95 {
+        util::confidence::with_confidence(codec, util::confidence::WEAK_MATCH, "high ratio".to_string())
+    } else {
+        util::confidence::no_match(codec)
+    }
 }
 
 impl Codec for Base37 {
@@ -87,49 +141,57 @@ impl Codec for Base37 {
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        Ok(encode_base37(input))
+        self.encode_with(input, &CodecOptions::default())
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        decode_base37(input, mode)
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Ok(Digits::from_options("base37", opts, ALPHABET, str::to_uppercase)?.encode(input))
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        Digits::from_options("base37", opts, ALPHABET, str::to_uppercase)?.decode(input, mode)
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
-        if input.is_empty() {
-            return DetectCandidate {
-                codec: "base37".to_string(),
-                confidence: 0.0,
-                reasons: vec![],
-                warnings: vec![],
-            };
+        detect_base37("base37", input, ALPHABET, ' ', str::to_uppercase)
+    }
+}
+
+impl Codec for Base37Name {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "base37name",
+            aliases: &["name37"],
+            alphabet: NAME_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Lower,
+            description: "Base37 in legacy name order (_, a-z, 0-9)",
         }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
 
-        let valid_chars = input.chars().filter(|c| ALPHABET.contains(c.to_ascii_uppercase())).count();
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Ok(Digits::from_options("base37name", opts, NAME_ALPHABET, str::to_lowercase)?.encode(input))
+    }
 
-        let ratio = valid_chars as f32 / input.len() as f32;
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        Digits::from_options("base37name", opts, NAME_ALPHABET, str::to_lowercase)?.decode(input, mode)
+    }
 
-        if ratio > 0.95 && input.contains(' ') {
-            DetectCandidate {
-                codec: "base37".to_string(),
-                confidence: util::confidence::PARTIAL_MATCH,
-                reasons: vec!["high ratio with space".to_string()],
-                warnings: vec![],
-            }
-        } else if ratio > 0.95 {
-            DetectCandidate {
-                codec: "base37".to_string(),
-                confidence: util::confidence::WEAK_MATCH,
-                reasons: vec!["high ratio".to_string()],
-                warnings: vec![],
-            }
-        } else {
-            DetectCandidate {
-                codec: "base37".to_string(),
-                confidence: 0.0,
-                reasons: vec![],
-                warnings: vec![],
-            }
-        }
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        detect_base37("base37name", input, NAME_ALPHABET, '_', str::to_lowercase)
     }
 }
 
@@ -206,4 +268,28 @@ mod tests {
         assert!(codec.detect_score("ABC123").confidence > 0.2);
         assert!(codec.detect_score("hello$world").confidence < 0.1);
     }
+
+    #[test]
+    fn test_base37_name_order() {
+        assert_eq!(Base37Name.encode(&[36]).unwrap(), "9");
+        assert_eq!(Base37Name.encode(&[0, 1]).unwrap(), "_a");
+        assert_eq!(Base37Name.decode("HELLO_world", Mode::Strict).unwrap(), Base37Name.decode("hello_world", Mode::Strict).unwrap());
+        let encoded = Base37Name.encode(b"mbase").unwrap();
+        assert_eq!(Base37Name.decode(&encoded, Mode::Strict).unwrap(), b"mbase");
+        assert!(Base37Name.decode("a b", Mode::Strict).is_err());
+        assert!(Base37Name.detect_score("zezima_2").confidence >= 0.5);
+    }
+
+    #[test]
+    fn test_base37_custom_alphabet() {
+        let order = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-";
+        let opts = CodecOptions::new().with("alphabet", order);
+        let encoded = Base37.encode_with(b"\x00AS400", &opts).unwrap();
+        assert!(encoded.starts_with('A'));
+        assert_eq!(Base37.decode_with(&encoded, Mode::Strict, &opts).unwrap(), b"\x00AS400");
+        assert!(Base37.decode_with(&encoded.to_lowercase(), Mode::Strict, &opts).is_err());
+        assert!(Base37.encode_with(b"x", &CodecOptions::new().with("alphabet", "ABC")).is_err());
+        let repeated = format!("{}A", &order[..36]);
+        assert!(Base37.encode_with(b"x", &CodecOptions::new().with("alphabet", &repeated)).is_err());
+    }
 }
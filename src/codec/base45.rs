@@ -1,15 +1,18 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
 
+static SYMBOLS: util::SymbolTable = util::SymbolTable::new(ALPHABET);
+
 fn char_to_val(c: char) -> Option<u32> {
-    ALPHABET.chars().position(|x| x == c).map(|p| p as u32)
+    SYMBOLS.value(c).map(u32::from)
 }
 
 fn val_to_char(v: u32) -> char {
-    ALPHABET.chars().nth(v as usize).unwrap()
+    ALPHABET.as_bytes()[v as usize] as char
 }
 
 fn encode_base45(input: &[u8]) -> String {
@@ -48,7 +51,7 @@ fn decode_base45(input: &str, mode: Mode) -> Result<Vec<u8>> {
         Mode::Lenient => cleaned.to_uppercase(),
     };
 
-    let vals: std::result::Result<Vec<u32>, _> = normalized
+    let vals: core::result::Result<Vec<u32>, _> = normalized
         .chars()
         .enumerate()
         .map(|(pos, c)| char_to_val(c).ok_or(MbaseError::InvalidCharacter { char: c, position: pos }))
@@ -176,6 +179,10 @@ impl Codec for Base45 {
         validate_base45(input, mode)
     }
 
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((2, 3))
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         detect_base45(input)
     }
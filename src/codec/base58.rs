@@ -3,6 +3,7 @@ use sha2::{Digest, Sha256};
 use super::util;
 use super::Codec;
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const BTC_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
@@ -39,7 +40,7 @@ fn detect_base58(input: &str, codec_name: &str, multibase_code: Option<char>, al
     }
 }
 
-fn double_sha256(data: &[u8]) -> [u8; 32] {
+pub(super) fn double_sha256(data: &[u8]) -> [u8; 32] {
     let first = Sha256::digest(data);
     Sha256::digest(first).into()
 }
@@ -201,6 +202,70 @@ impl Codec for Base58Check {
     }
 }
 
+/// Avalanche CB58: base58btc with the last 4 bytes of a single SHA-256 appended.
+pub struct Cb58;
+
+impl Codec for Cb58 {
+    fn name(&self) -> &'static str {
+        "cb58"
+    }
+
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "cb58",
+            aliases: &["avalanche"],
+            alphabet: BTC_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Base58 with 4-byte checksum (Avalanche CB58, last 4 bytes of SHA-256)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        let hash = Sha256::digest(input);
+        let mut with_checksum = input.to_vec();
+        with_checksum.extend_from_slice(&hash[28..]);
+        Ok(bs58::encode(&with_checksum).with_alphabet(bs58::Alphabet::BITCOIN).into_string())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let cleaned = util::clean_for_mode(input, mode);
+        let decoded = bs58::decode(&cleaned)
+            .with_alphabet(bs58::Alphabet::BITCOIN)
+            .into_vec()
+            .map_err(|e| match e {
+                bs58::decode::Error::InvalidCharacter { character, index } => MbaseError::InvalidCharacter {
+                    char: character,
+                    position: index,
+                },
+                _ => MbaseError::invalid_input(e.to_string()),
+            })?;
+
+        if decoded.len() < 4 {
+            return Err(MbaseError::invalid_input("input too short for checksum"));
+        }
+
+        let (payload, checksum) = decoded.split_at(decoded.len() - 4);
+        if checksum != &Sha256::digest(payload)[28..] {
+            return Err(MbaseError::ChecksumMismatch);
+        }
+
+        Ok(payload.to_vec())
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let mut candidate = detect_base58(input, "cb58", None, BTC_ALPHABET);
+
+        if self.decode(input, Mode::Lenient).is_ok() {
+            candidate.confidence = candidate.confidence.max(0.9);
+            candidate.reasons.push("checksum valid".to_string());
+        }
+
+        candidate
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -320,4 +385,14 @@ mod tests {
         assert!(candidate.confidence >= 0.9);
         assert!(candidate.reasons.iter().any(|r| r.contains("multibase")));
     }
+
+    #[test]
+    fn test_cb58_roundtrip_and_checksum() {
+        let encoded = Cb58.encode(b"\x00\x01\x02\x03avalanche").unwrap();
+        assert_eq!(Cb58.decode(&encoded, Mode::Strict).unwrap(), b"\x00\x01\x02\x03avalanche");
+
+        // A base58check string carries a double-SHA256 checksum, which CB58 must reject.
+        let btc = Base58Check.encode(b"\x00\x01\x02\x03avalanche").unwrap();
+        assert!(matches!(Cb58.decode(&btc, Mode::Strict), Err(MbaseError::ChecksumMismatch)));
+    }
 }
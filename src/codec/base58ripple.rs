@@ -1,5 +1,7 @@
+use super::base58::double_sha256;
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct Base58Ripple;
@@ -72,6 +74,55 @@ impl Codec for Base58Ripple {
     }
 }
 
+/// Ripple address format: the Ripple alphabet with a Bitcoin-style double-SHA256 checksum.
+pub struct Base58RippleCheck;
+
+impl Codec for Base58RippleCheck {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "base58ripplecheck",
+            aliases: &["xrpcheck", "ripple-address"],
+            alphabet: RIPPLE_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Base58 Ripple alphabet with 4-byte double-SHA256 checksum (XRP addresses)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        let mut with_checksum = input.to_vec();
+        with_checksum.extend_from_slice(&double_sha256(input)[..4]);
+        Base58Ripple.encode(&with_checksum)
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let decoded = Base58Ripple.decode(input, mode)?;
+        if decoded.len() < 4 {
+            return Err(Error::invalid_input("input too short for checksum"));
+        }
+
+        let (payload, checksum) = decoded.split_at(decoded.len() - 4);
+        if checksum != &double_sha256(payload)[..4] {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        Ok(payload.to_vec())
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let mut candidate = Base58Ripple.detect_score(input);
+        candidate.codec = "base58ripplecheck".to_string();
+
+        if !input.is_empty() && self.decode(input, Mode::Lenient).is_ok() {
+            candidate.confidence = candidate.confidence.max(0.9);
+            candidate.reasons.push("checksum valid".to_string());
+        }
+
+        candidate
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -157,4 +208,22 @@ mod tests {
         let encoded = codec.encode(&[0]).unwrap();
         assert_eq!(encoded, "r");
     }
+
+    #[test]
+    fn test_base58ripplecheck_account_address() {
+        let codec = Base58RippleCheck;
+        // The XRP Ledger genesis account.
+        let decoded = codec.decode("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh", Mode::Strict).unwrap();
+        assert_eq!(decoded.len(), 21);
+        assert_eq!(decoded[0], 0x00);
+        assert_eq!(codec.encode(&decoded).unwrap(), "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh");
+        assert!(codec.detect_score("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh").confidence >= 0.9);
+    }
+
+    #[test]
+    fn test_base58ripplecheck_checksum_mismatch() {
+        let codec = Base58RippleCheck;
+        assert!(matches!(codec.decode("rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTi", Mode::Strict), Err(Error::ChecksumMismatch)));
+        assert!(codec.decode("r", Mode::Strict).is_err());
+    }
 }
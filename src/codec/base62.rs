@@ -1,5 +1,6 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
@@ -25,7 +26,7 @@ fn encode_base62(input: &[u8]) -> String {
     });
 
     let leading_zeros = input.iter().take_while(|&&b| b == 0).count();
-    num.extend(std::iter::repeat_n(0, leading_zeros));
+    num.extend(core::iter::repeat_n(0, leading_zeros));
 
     num.iter().rev().map(|&d| alphabet[d as usize] as char).collect()
 }
@@ -122,7 +123,7 @@ fn detect_base62(input: &str) -> DetectCandidate {
     }
 
     let len = input.len();
-    let is_base64_len = len % 4 == 0 || (len % 4 == 2 || len % 4 == 3);
+    let is_base64_len = len.is_multiple_of(4) || (len % 4 == 2 || len % 4 == 3);
 
     let mut confidence = if has_mixed_case && has_digits {
         if is_base64_len {
@@ -1,13 +1,74 @@
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
 use base64::prelude::*;
-use base64::Engine;
+use base64::{alphabet, Engine};
 
 use super::util;
 use super::Codec;
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 const STANDARD_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
 const URL_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const CRYPT_ALPHABET: &str = "./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BCRYPT_ALPHABET: &str = "./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+const UNPADDED: GeneralPurposeConfig = GeneralPurposeConfig::new()
+    .with_encode_padding(false)
+    .with_decode_padding_mode(DecodePaddingMode::RequireNone);
+/// Hash fields written by old tools do not always zero the bits after the last byte.
+const UNPADDED_LOOSE: GeneralPurposeConfig = UNPADDED.with_decode_allow_trailing_bits(true);
+const CRYPT: GeneralPurpose = GeneralPurpose::new(&alphabet::CRYPT, UNPADDED);
+const CRYPT_LOOSE: GeneralPurpose = GeneralPurpose::new(&alphabet::CRYPT, UNPADDED_LOOSE);
+const BCRYPT: GeneralPurpose = GeneralPurpose::new(&alphabet::BCRYPT, UNPADDED);
+const BCRYPT_LOOSE: GeneralPurpose = GeneralPurpose::new(&alphabet::BCRYPT, UNPADDED_LOOSE);
+
+/// Characters of the other base64 alphabet, paired with their counterpart in this one.
+fn foreign_chars(url: bool) -> [(char, char); 2] {
+    if url {
+        [('+', '-'), ('/', '_')]
+    } else {
+        [('-', '+'), ('_', '/')]
+    }
+}
+
+/// Rewrites characters from the sibling alphabet (`-_` vs `+/`) into this codec's alphabet.
+fn normalize_alphabet(input: &str, url: bool) -> String {
+    let map = foreign_chars(url);
+    input
+        .chars()
+        .map(|c| map.iter().find(|(from, _)| *from == c).map_or(c, |(_, to)| *to))
+        .collect()
+}
+
+fn alphabet_warning(input: &str, url: bool) -> Option<String> {
+    let map = foreign_chars(url);
+    let count = input.chars().filter(|c| map.iter().any(|(from, _)| from == c)).count();
+    (count > 0).then(|| {
+        format!(
+            "normalized {} {} character(s) to the {} alphabet",
+            count,
+            if url { "standard (+/)" } else { "URL-safe (-_)" },
+            if url { "URL-safe" } else { "standard" }
+        )
+    })
+}
+
+/// Rejects sibling-alphabet characters with a hint instead of a bare invalid-character error.
+fn reject_foreign(input: &str, url: bool, sibling: &str) -> Result<()> {
+    let map = foreign_chars(url);
+    match input.chars().enumerate().find(|(_, c)| map.iter().any(|(from, _)| from == c)) {
+        Some((pos, c)) => Err(MbaseError::invalid_input(format!(
+            "character '{}' at position {} belongs to the {} alphabet; use {} or --mode lenient",
+            c,
+            pos,
+            if url { "standard" } else { "URL-safe" },
+            sibling
+        ))),
+        None => Ok(()),
+    }
+}
 
 fn validate_padding(input: &str, padding_rule: PaddingRule) -> Result<()> {
     let pad_count = input.chars().rev().take_while(|&c| c == '=').count();
@@ -84,6 +145,88 @@ fn detect_base64_common(input: &str, codec_name: &str, alphabet: &str, multibase
     }
 }
 
+/// A substitute table from the `alphabet=` and `pad=` options, as used by obfuscated base64 in
+/// malware configs and proprietary protocols. Input is mapped onto the codec's own alphabet
+/// position by position, so the codec's padding and mode rules still apply.
+struct CustomTable {
+    custom: Vec<char>,
+    own: Vec<char>,
+    pad: char,
+}
+
+impl CustomTable {
+    fn from_options(codec: &str, own: &str, opts: &CodecOptions) -> Result<Option<Self>> {
+        opts.ensure_known(codec, &["alphabet", "pad"])?;
+        if opts.is_empty() {
+            return Ok(None);
+        }
+        let custom: Vec<char> = opts.get("alphabet").unwrap_or(own).chars().collect();
+        if custom.len() != 64 {
+            return Err(MbaseError::invalid_input(format!("option 'alphabet' needs 64 characters, got {}", custom.len())));
+        }
+        if let Some((i, c)) = custom.iter().enumerate().find(|(i, c)| custom[..*i].contains(c)) {
+            return Err(MbaseError::invalid_input(format!(
+                "option 'alphabet' repeats '{}' at position {}; every symbol must be unique",
+                c, i
+            )));
+        }
+        let pad = opts.get_char("pad")?.unwrap_or('=');
+        if custom.contains(&pad) {
+            return Err(MbaseError::invalid_input(format!(
+                "padding character '{}' is also in the alphabet; choose another with --opt pad=",
+                pad
+            )));
+        }
+        Ok(Some(Self {
+            custom,
+            own: own.chars().collect(),
+            pad,
+        }))
+    }
+
+    fn to_custom(&self, encoded: &str) -> String {
+        encoded
+            .chars()
+            .map(|c| match self.own.iter().position(|&o| o == c) {
+                Some(i) => self.custom[i],
+                None if c == '=' => self.pad,
+                None => c,
+            })
+            .collect()
+    }
+
+    /// Whitespace passes through for the mode to handle; any other character outside the
+    /// table is rejected here, since it could be a valid symbol of the codec's own alphabet.
+    fn to_own(&self, input: &str) -> Result<String> {
+        input
+            .chars()
+            .enumerate()
+            .map(|(position, c)| match self.custom.iter().position(|&s| s == c) {
+                Some(i) => Ok(self.own[i]),
+                None if c == self.pad => Ok('='),
+                None if c.is_whitespace() => Ok(c),
+                None => Err(MbaseError::InvalidCharacter { char: c, position }),
+            })
+            .collect()
+    }
+}
+
+fn encode_custom(codec: &dyn Codec, input: &[u8], opts: &CodecOptions) -> Result<String> {
+    let meta = codec.meta();
+    match CustomTable::from_options(meta.name, meta.alphabet, opts)? {
+        Some(table) => Ok(table.to_custom(&codec.encode(input)?)),
+        None => codec.encode(input),
+    }
+}
+
+fn decode_custom(codec: &dyn Codec, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+    let meta = codec.meta();
+    match CustomTable::from_options(meta.name, meta.alphabet, opts)? {
+        Some(table) => codec.decode(&table.to_own(input)?, mode),
+        None => codec.decode(input, mode),
+    }
+}
+
 pub struct Base64;
 
 impl Codec for Base64 {
@@ -107,6 +250,11 @@ impl Codec for Base64 {
         Ok(BASE64_STANDARD_NO_PAD.encode(input))
     }
 
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        BASE64_STANDARD_NO_PAD.encode_string(input, out);
+        Ok(())
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
         let to_decode = match mode {
@@ -114,7 +262,11 @@ impl Codec for Base64 {
                 self.validate(&cleaned, mode)?;
                 cleaned
             }
-            Mode::Lenient => cleaned.trim_end_matches('=').to_string(),
+            Mode::Lenient => {
+                let normalized = normalize_alphabet(cleaned.trim_end_matches('='), false);
+                util::check_trailing_bits(&normalized, STANDARD_ALPHABET, 6)?;
+                normalized
+            }
         };
         BASE64_STANDARD_NO_PAD
             .decode(&to_decode)
@@ -122,10 +274,36 @@ impl Codec for Base64 {
     }
 
     fn validate(&self, input: &str, mode: Mode) -> Result<()> {
-        let cleaned = util::clean_for_mode(input, mode);
+        let cleaned = match mode {
+            Mode::Strict => {
+                let cleaned = util::clean_for_mode(input, mode);
+                reject_foreign(&cleaned, false, "base64url")?;
+                cleaned
+            }
+            Mode::Lenient => normalize_alphabet(&util::clean_for_mode(input, mode), false),
+        };
         util::validate_alphabet_with_padding(&cleaned, STANDARD_ALPHABET, false)?;
         validate_padding(&cleaned, PaddingRule::None)?;
-        Ok(())
+        util::check_trailing_bits(cleaned.trim_end_matches('='), STANDARD_ALPHABET, 6)
+    }
+
+    fn decode_warnings(&self, input: &str, mode: Mode) -> Vec<String> {
+        match mode {
+            Mode::Lenient => alphabet_warning(input, false).into_iter().collect(),
+            Mode::Strict => Vec::new(),
+        }
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        encode_custom(self, input, opts)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_custom(self, input, mode, opts)
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((3, 4))
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -156,6 +334,11 @@ impl Codec for Base64Pad {
         Ok(BASE64_STANDARD.encode(input))
     }
 
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        BASE64_STANDARD.encode_string(input, out);
+        Ok(())
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
         match mode {
@@ -166,7 +349,9 @@ impl Codec for Base64Pad {
                     .map_err(|e| MbaseError::invalid_input(e.to_string()))
             }
             Mode::Lenient => {
-                let padded = pad_to_multiple(&cleaned, 4);
+                let normalized = normalize_alphabet(&cleaned, false);
+                util::check_trailing_bits(normalized.trim_end_matches('='), STANDARD_ALPHABET, 6)?;
+                let padded = pad_to_multiple(&normalized, 4);
                 BASE64_STANDARD
                     .decode(&padded)
                     .map_err(|e| MbaseError::invalid_input(e.to_string()))
@@ -175,10 +360,36 @@ impl Codec for Base64Pad {
     }
 
     fn validate(&self, input: &str, mode: Mode) -> Result<()> {
-        let cleaned = util::clean_for_mode(input, mode);
+        let cleaned = match mode {
+            Mode::Strict => {
+                let cleaned = util::clean_for_mode(input, mode);
+                reject_foreign(&cleaned, false, "base64urlpad")?;
+                cleaned
+            }
+            Mode::Lenient => normalize_alphabet(&util::clean_for_mode(input, mode), false),
+        };
         util::validate_alphabet_with_padding(&cleaned, STANDARD_ALPHABET, true)?;
         validate_padding(&cleaned, PaddingRule::Required)?;
-        Ok(())
+        util::check_trailing_bits(cleaned.trim_end_matches('='), STANDARD_ALPHABET, 6)
+    }
+
+    fn decode_warnings(&self, input: &str, mode: Mode) -> Vec<String> {
+        match mode {
+            Mode::Lenient => alphabet_warning(input, false).into_iter().collect(),
+            Mode::Strict => Vec::new(),
+        }
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        encode_custom(self, input, opts)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_custom(self, input, mode, opts)
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((3, 4))
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -209,6 +420,11 @@ impl Codec for Base64Url {
         Ok(BASE64_URL_SAFE_NO_PAD.encode(input))
     }
 
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        BASE64_URL_SAFE_NO_PAD.encode_string(input, out);
+        Ok(())
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
         let to_decode = match mode {
@@ -216,7 +432,11 @@ impl Codec for Base64Url {
                 self.validate(&cleaned, mode)?;
                 cleaned
             }
-            Mode::Lenient => cleaned.trim_end_matches('=').to_string(),
+            Mode::Lenient => {
+                let normalized = normalize_alphabet(cleaned.trim_end_matches('='), true);
+                util::check_trailing_bits(&normalized, URL_ALPHABET, 6)?;
+                normalized
+            }
         };
         BASE64_URL_SAFE_NO_PAD
             .decode(&to_decode)
@@ -224,10 +444,36 @@ impl Codec for Base64Url {
     }
 
     fn validate(&self, input: &str, mode: Mode) -> Result<()> {
-        let cleaned = util::clean_for_mode(input, mode);
+        let cleaned = match mode {
+            Mode::Strict => {
+                let cleaned = util::clean_for_mode(input, mode);
+                reject_foreign(&cleaned, true, "base64")?;
+                cleaned
+            }
+            Mode::Lenient => normalize_alphabet(&util::clean_for_mode(input, mode), true),
+        };
         util::validate_alphabet_with_padding(&cleaned, URL_ALPHABET, false)?;
         validate_padding(&cleaned, PaddingRule::None)?;
-        Ok(())
+        util::check_trailing_bits(cleaned.trim_end_matches('='), URL_ALPHABET, 6)
+    }
+
+    fn decode_warnings(&self, input: &str, mode: Mode) -> Vec<String> {
+        match mode {
+            Mode::Lenient => alphabet_warning(input, true).into_iter().collect(),
+            Mode::Strict => Vec::new(),
+        }
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        encode_custom(self, input, opts)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_custom(self, input, mode, opts)
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((3, 4))
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -258,6 +504,11 @@ impl Codec for Base64UrlPad {
         Ok(BASE64_URL_SAFE.encode(input))
     }
 
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        BASE64_URL_SAFE.encode_string(input, out);
+        Ok(())
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
         match mode {
@@ -268,7 +519,9 @@ impl Codec for Base64UrlPad {
                     .map_err(|e| MbaseError::invalid_input(e.to_string()))
             }
             Mode::Lenient => {
-                let padded = pad_to_multiple(&cleaned, 4);
+                let normalized = normalize_alphabet(&cleaned, true);
+                util::check_trailing_bits(normalized.trim_end_matches('='), URL_ALPHABET, 6)?;
+                let padded = pad_to_multiple(&normalized, 4);
                 BASE64_URL_SAFE
                     .decode(&padded)
                     .map_err(|e| MbaseError::invalid_input(e.to_string()))
@@ -277,10 +530,36 @@ impl Codec for Base64UrlPad {
     }
 
     fn validate(&self, input: &str, mode: Mode) -> Result<()> {
-        let cleaned = util::clean_for_mode(input, mode);
+        let cleaned = match mode {
+            Mode::Strict => {
+                let cleaned = util::clean_for_mode(input, mode);
+                reject_foreign(&cleaned, true, "base64pad")?;
+                cleaned
+            }
+            Mode::Lenient => normalize_alphabet(&util::clean_for_mode(input, mode), true),
+        };
         util::validate_alphabet_with_padding(&cleaned, URL_ALPHABET, true)?;
         validate_padding(&cleaned, PaddingRule::Required)?;
-        Ok(())
+        util::check_trailing_bits(cleaned.trim_end_matches('='), URL_ALPHABET, 6)
+    }
+
+    fn decode_warnings(&self, input: &str, mode: Mode) -> Vec<String> {
+        match mode {
+            Mode::Lenient => alphabet_warning(input, true).into_iter().collect(),
+            Mode::Strict => Vec::new(),
+        }
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        encode_custom(self, input, opts)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_custom(self, input, mode, opts)
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((3, 4))
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -288,6 +567,197 @@ impl Codec for Base64UrlPad {
     }
 }
 
+/// Decodes one JWT segment. `at` is its index and byte offset in a whole token, named in errors.
+fn decode_jwt_segment(segment: &str, at: Option<(usize, usize)>) -> Result<Vec<u8>> {
+    let offset = at.map_or(0, |(_, offset)| offset);
+    let span = |message: String| match at {
+        Some((index, offset)) => format!("segment {} (bytes {}..{}): {}", index + 1, offset, offset + segment.len(), message),
+        None => message,
+    };
+    if let Some(pos) = segment.find('=') {
+        return Err(MbaseError::invalid_padding(span(format!("'=' at position {}; JWT segments are never padded", offset + pos))));
+    }
+    if let Some((pos, c)) = segment.char_indices().find(|(_, c)| !URL_ALPHABET.contains(*c)) {
+        let hint = if c == '+' || c == '/' {
+            " (standard alphabet; JWT uses -_)"
+        } else {
+            ""
+        };
+        return Err(MbaseError::invalid_input(span(format!("invalid character '{}' at position {}{}", c, offset + pos, hint))));
+    }
+    if segment.len() % 4 == 1 {
+        return Err(MbaseError::invalid_input(span(format!("length {} is 1 mod 4, which no byte string encodes to", segment.len()))));
+    }
+    BASE64_URL_SAFE_NO_PAD.decode(segment).map_err(|e| match e {
+        base64::DecodeError::InvalidLastSymbol(pos, byte) => {
+            MbaseError::invalid_input(span(format!("non-zero trailing bits in '{}' at position {}", byte as char, offset + pos)))
+        }
+        other => MbaseError::invalid_input(span(other.to_string())),
+    })
+}
+
+/// Decodes every `.`-separated segment of a compact JWS/JWT/JWE token, with the checks of
+/// `base64url-jwt`. Errors name the segment and its byte span. Lenient mode only drops whitespace.
+pub fn decode_jwt(token: &str, mode: Mode) -> Result<Vec<Vec<u8>>> {
+    let cleaned = util::clean_for_mode(token, mode);
+    let mut offset = 0;
+    let mut segments = Vec::new();
+    for (index, segment) in cleaned.split('.').enumerate() {
+        segments.push(decode_jwt_segment(segment, Some((index, offset)))?);
+        offset += segment.len() + 1;
+    }
+    Ok(segments)
+}
+
+/// Base64url as used in one JWS/JWT segment: never padded, and checked strictly.
+///
+/// A whole compact token is several segments; [`decode_jwt`] splits and decodes it. Lenient mode
+/// only drops whitespace; padding, `len % 4 == 1` and non-zero trailing bits are rejected in both modes.
+pub struct Base64UrlJwt;
+
+impl Codec for Base64UrlJwt {
+    fn name(&self) -> &'static str {
+        "base64url-jwt"
+    }
+
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "base64url-jwt",
+            aliases: &["jwt64", "b64jwt"],
+            alphabet: URL_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Base64url for JWT segments (no padding, strict per-segment checks)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        Ok(BASE64_URL_SAFE_NO_PAD.encode(input))
+    }
+
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        BASE64_URL_SAFE_NO_PAD.encode_string(input, out);
+        Ok(())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let cleaned = util::clean_for_mode(input, mode);
+        if let Some(pos) = cleaned.find('.') {
+            return Err(MbaseError::invalid_input(format!(
+                "'.' at position {}: this is a whole token, not one segment; `mbase jwt` decodes each segment",
+                pos
+            )));
+        }
+        decode_jwt_segment(&cleaned, None)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let segments = input.split('.').count();
+        if input.starts_with("eyJ") && (2..=5).contains(&segments) {
+            return util::confidence::with_confidence(
+                "base64url-jwt",
+                0.9,
+                format!("JSON header segment (eyJ...) and {} dot-separated segments; `mbase jwt` decodes them", segments),
+            );
+        }
+        let mut candidate = detect_base64_common(input, "base64url-jwt", URL_ALPHABET, 'u', false);
+        candidate.confidence *= 0.9;
+        candidate.reasons.retain(|r| !r.starts_with("multibase"));
+        candidate
+    }
+}
+
+/// Unpadded base64 over a password-hash alphabet that starts with `./`, for fields migrated out
+/// of crypt(3)-style hashes. The bits are packed in the usual big-endian order, as in bcrypt;
+/// the little-endian groups of MD5-crypt and SHA-crypt are a different layout.
+///
+/// Lenient mode drops whitespace and `=` and accepts non-zero bits after the last byte.
+macro_rules! ordered_base64 {
+    ($codec:ident, $name:expr, $aliases:expr, $alphabet:expr, $strict:expr, $loose:expr, $description:expr) => {
+        pub struct $codec;
+
+        impl Codec for $codec {
+            fn meta(&self) -> CodecMeta {
+                CodecMeta {
+                    name: $name,
+                    aliases: $aliases,
+                    alphabet: $alphabet,
+                    multibase_code: None,
+                    padding: PaddingRule::None,
+                    case_sensitivity: CaseSensitivity::Sensitive,
+                    description: $description,
+                }
+            }
+
+            fn encode(&self, input: &[u8]) -> Result<String> {
+                Ok($strict.encode(input))
+            }
+
+            fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+                $strict.encode_string(input, out);
+                Ok(())
+            }
+
+            fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+                let cleaned = util::clean_for_mode(input, mode);
+                let decoded = match mode {
+                    Mode::Strict => {
+                        self.validate(&cleaned, mode)?;
+                        $strict.decode(&cleaned)
+                    }
+                    Mode::Lenient => $loose.decode(cleaned.trim_end_matches('=')),
+                };
+                decoded.map_err(|e| MbaseError::invalid_input(e.to_string()))
+            }
+
+            fn validate(&self, input: &str, mode: Mode) -> Result<()> {
+                let cleaned = util::clean_for_mode(input, mode);
+                util::validate_alphabet_with_padding(&cleaned, $alphabet, false)?;
+                if mode == Mode::Strict {
+                    util::check_trailing_bits(&cleaned, $alphabet, 6)?;
+                }
+                if cleaned.trim_end_matches('=').len() % 4 == 1 {
+                    return Err(MbaseError::invalid_input(format!("length {} is 1 mod 4, which no byte string encodes to", cleaned.len())));
+                }
+                Ok(())
+            }
+
+            fn stream_block(&self) -> Option<(usize, usize)> {
+                Some((3, 4))
+            }
+
+            fn detect_score(&self, input: &str) -> DetectCandidate {
+                // Without `.` or `/` the input is just as likely plain base64.
+                let ordered = input.contains(['.', '/']) && input.chars().all(|c| $alphabet.contains(c));
+                if !ordered || input.len() % 4 == 1 {
+                    return util::confidence::no_match($name);
+                }
+                util::confidence::with_confidence($name, util::confidence::WEAK_MATCH, "only ./ and alphanumerics".to_string())
+            }
+        }
+    };
+}
+
+ordered_base64!(
+    Base64Crypt,
+    "base64crypt",
+    &["crypt64"],
+    CRYPT_ALPHABET,
+    CRYPT,
+    CRYPT_LOOSE,
+    "Base64 in crypt(3) order (./0-9A-Za-z, no padding)"
+);
+ordered_base64!(
+    Base64Bcrypt,
+    "base64bcrypt",
+    &["bcrypt64"],
+    BCRYPT_ALPHABET,
+    BCRYPT,
+    BCRYPT_LOOSE,
+    "Base64 in bcrypt order (./A-Za-z0-9, no padding)"
+);
+
 fn pad_to_multiple(input: &str, multiple: usize) -> String {
     let stripped = input.trim_end_matches('=');
     let remainder = stripped.len() % multiple;
@@ -313,6 +783,47 @@ mod tests {
         assert_eq!(Base64.encode(b"Hello").unwrap(), "SGVsbG8");
     }
 
+    #[test]
+    fn test_base64_encode_into_appends() {
+        let mut out = String::from("data:");
+        Base64Pad.encode_into(b"Hi", &mut out).unwrap();
+        Base64Url.encode_into(&[0xfb, 0xff], &mut out).unwrap();
+        assert_eq!(out, "data:SGk=-_8");
+
+        let mut bytes = vec![0];
+        Base64.decode_into("SGk", Mode::Strict, &mut bytes).unwrap();
+        assert_eq!(bytes, b"\0Hi");
+    }
+
+    const SCRAMBLED: &str = "_-9876543210zyxwvutsrqponmlkjihgfedcbaZYXWVUTSRQPONMLKJIHGFEDCBA";
+
+    #[test]
+    fn test_base64_custom_alphabet() {
+        let opts = CodecOptions::new().with("alphabet", SCRAMBLED).with("pad", "~");
+        let encoded = Base64Pad.encode_with(b"Hello", &opts).unwrap();
+        assert_eq!(encoded, "t5qTk5D~");
+        assert_eq!(Base64Pad.decode_with(&encoded, Mode::Strict, &opts).unwrap(), b"Hello");
+        assert_eq!(Base64Pad.decode_with("t5qT k5D~", Mode::Lenient, &opts).unwrap(), b"Hello");
+        let unpadded = CodecOptions::new().with("alphabet", SCRAMBLED);
+        assert_eq!(Base64.encode_with(b"Hello", &unpadded).unwrap(), "t5qTk5D");
+        // A symbol of the standard table is not silently read with its standard value.
+        let err = Base64.decode_with("t5qT+5D", Mode::Strict, &unpadded).unwrap_err();
+        assert!(matches!(err, MbaseError::InvalidCharacter { char: '+', position: 4 }));
+        // Padding alone can change too.
+        let tilde = CodecOptions::new().with("pad", "~");
+        assert_eq!(Base64UrlPad.encode_with(b"He", &tilde).unwrap(), "SGU~");
+    }
+
+    #[test]
+    fn test_base64_custom_alphabet_errors() {
+        let err = |opts: CodecOptions| Base64.encode_with(b"x", &opts).unwrap_err().to_string();
+        assert!(err(CodecOptions::new().with("alphabet", "ABC")).contains("needs 64 characters, got 3"));
+        let repeated = format!("A{}", &STANDARD_ALPHABET[1..63]) + "A";
+        assert!(err(CodecOptions::new().with("alphabet", &repeated)).contains("repeats 'A' at position 63"));
+        assert!(err(CodecOptions::new().with("pad", "A")).contains("also in the alphabet"));
+        assert!(err(CodecOptions::new().with("pads", "~")).contains("unknown option"));
+    }
+
     #[test]
     fn test_base64_decode_hello() {
         assert_eq!(Base64.decode("SGVsbG8", Mode::Strict).unwrap(), b"Hello".to_vec());
@@ -376,4 +887,94 @@ mod tests {
         assert!(candidate.confidence > 0.9);
         assert!(candidate.reasons.iter().any(|r| r.contains("multibase")));
     }
+
+    #[test]
+    fn test_lenient_normalizes_mixed_alphabet() {
+        // 0xfb 0xff 0xbf encodes to "+/+/" (standard) and "-_-_" (URL-safe).
+        assert_eq!(Base64.decode("+_-/", Mode::Lenient).unwrap(), vec![0xfb, 0xff, 0xbf]);
+        assert_eq!(Base64UrlPad.decode("+_-/", Mode::Lenient).unwrap(), vec![0xfb, 0xff, 0xbf]);
+        assert_eq!(Base64.decode_warnings("+_-/", Mode::Lenient), vec!["normalized 2 URL-safe (-_) character(s) to the standard alphabet"]);
+        assert!(Base64.decode_warnings("+/+/", Mode::Lenient).is_empty());
+        assert!(Base64.validate("+_-/", Mode::Lenient).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mixed_alphabet_names_sibling() {
+        let err = Base64Pad.decode("+_-/", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("'_' at position 1 belongs to the URL-safe alphabet; use base64urlpad"), "{}", err);
+        let err = Base64Url.decode("-_+/", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("use base64 or"), "{}", err);
+    }
+
+    #[test]
+    fn test_non_canonical_trailing_bits() {
+        let err = Base64.decode("SGVsbG9", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("'9' at position 6 (canonical: '8')"), "{}", err);
+        assert!(Base64.validate("SGVsbG9", Mode::Strict).is_err());
+        assert!(Base64Pad.validate("SGVsbG9=", Mode::Strict).is_err());
+        assert!(Base64Url.decode("SGVsbG9", Mode::Lenient).is_err());
+        assert!(Base64UrlPad.decode("SGVsbG9", Mode::Lenient).is_err());
+        assert_eq!(Base64.decode("SGVsbG8", Mode::Strict).unwrap(), b"Hello");
+    }
+
+    #[test]
+    fn test_base64url_jwt_segments() {
+        let token = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln";
+        let segments = decode_jwt(token, Mode::Strict).unwrap();
+        assert_eq!(segments, [br#"{"alg":"HS256"}"#.to_vec(), br#"{"sub":"1"}"#.to_vec(), b"sig".to_vec()]);
+        assert_eq!(Base64UrlJwt.encode(b"\xfb\xff").unwrap(), "-_8");
+        assert_eq!(Base64UrlJwt.decode("-_8", Mode::Strict).unwrap(), b"\xfb\xff");
+
+        // The codec is one segment, so encoding and decoding round-trip even across a `.` byte.
+        let encoded = Base64UrlJwt.encode(br#"{"a":1}.{"b":2}"#).unwrap();
+        assert_eq!(Base64UrlJwt.decode(&encoded, Mode::Strict).unwrap(), br#"{"a":1}.{"b":2}"#);
+        let err = Base64UrlJwt.decode(token, Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("'.' at position 20") && err.contains("mbase jwt"), "{}", err);
+    }
+
+    #[test]
+    fn test_base64url_jwt_errors() {
+        let err = decode_jwt("eyJ9.c2lnbg==", Mode::Lenient).unwrap_err().to_string();
+        assert!(err.contains("segment 2 (bytes 5..13)") && err.contains("position 11"), "{}", err);
+        let err = Base64UrlJwt.decode("c2lnbg==", Mode::Lenient).unwrap_err().to_string();
+        assert!(err.contains("'=' at position 6") && !err.contains("segment 1"), "{}", err);
+
+        let err = Base64UrlJwt.decode("abcde", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("1 mod 4"), "{}", err);
+
+        let err = decode_jwt("e30.ab+c", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("'+' at position 6") && err.contains("standard alphabet"), "{}", err);
+
+        let err = Base64UrlJwt.decode("QR", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("trailing bits"), "{}", err);
+    }
+
+    #[test]
+    fn test_password_hash_orderings() {
+        let data = b"legacy field \x00\xff\x10";
+        let standard = BASE64_STANDARD_NO_PAD.encode(data);
+        for (codec, alphabet) in [(&Base64Crypt as &dyn Codec, CRYPT_ALPHABET), (&Base64Bcrypt, BCRYPT_ALPHABET)] {
+            let expected: String = standard
+                .chars()
+                .map(|c| alphabet.as_bytes()[STANDARD_ALPHABET.find(c).unwrap()] as char)
+                .collect();
+            let encoded = codec.encode(data).unwrap();
+            assert_eq!(encoded, expected);
+            assert_eq!(codec.decode(&encoded, Mode::Strict).unwrap(), data);
+        }
+        assert_eq!(Base64Crypt.encode(&[0, 0, 0, 0xff, 0xff, 0xff]).unwrap(), "....zzzz");
+        assert_eq!(Base64Bcrypt.encode(&[0, 0, 0, 0xff, 0xff, 0xff]).unwrap(), "....9999");
+    }
+
+    #[test]
+    fn test_password_hash_orderings_lenient() {
+        // bcrypt salts from some implementations leave the last character's low bits set.
+        assert!(Base64Bcrypt.decode("//", Mode::Strict).is_err());
+        assert_eq!(Base64Bcrypt.decode("//", Mode::Lenient).unwrap(), [4]);
+        assert_eq!(Base64Crypt.decode(" .. .. ==\n", Mode::Lenient).unwrap(), [0, 0, 0]);
+        assert!(Base64Crypt.decode("ab+c", Mode::Strict).is_err());
+        assert!(Base64Crypt.decode("abcde", Mode::Strict).is_err());
+        assert!(Base64Crypt.detect_score("N9qo8uLOickgx2ZMRZoMye").confidence == 0.0);
+        assert!(Base64Bcrypt.detect_score("N9qo8uLOickgx2ZMRZo./e").confidence > 0.0);
+    }
 }
@@ -1,6 +1,8 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use alloc::collections::BTreeMap;
 
 const BLOCK_START: [u32; 256] = [
     0x03400, 0x03500, 0x03600, 0x03700, 0x03800, 0x03900, 0x03A00, 0x03B00, 0x03C00, 0x03D00, 0x03E00, 0x03F00, 0x04000, 0x04100, 0x04200,
@@ -25,8 +27,8 @@ const BLOCK_START: [u32; 256] = [
 
 const PADDING_BLOCK_START: u32 = 0x01800;
 
-fn build_reverse_map() -> std::collections::HashMap<u32, (u8, u8)> {
-    let mut map = std::collections::HashMap::new();
+fn build_reverse_map() -> BTreeMap<u32, (u8, u8)> {
+    let mut map = BTreeMap::new();
     for (hi, &base) in BLOCK_START.iter().enumerate() {
         for lo in 0u32..256 {
             map.insert(base + lo, (hi as u8, lo as u8));
@@ -53,6 +55,10 @@ impl Codec for Base65536 {
         }
     }
 
+    fn is_symbol(&self, c: char) -> bool {
+        !c.is_ascii()
+    }
+
     fn encode(&self, input: &[u8]) -> Result<String> {
         if input.is_empty() {
             return Ok(String::new());
@@ -1,17 +1,45 @@
 use super::{util, Codec};
-use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::error::{LengthConstraint, MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 const ASCII85_ALPHABET: &str = "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstu";
 
 const Z85_ALPHABET: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
 
-fn encode_ascii85(input: &[u8]) -> String {
-    if input.is_empty() {
-        return String::new();
+static Z85_SYMBOLS: util::SymbolTable = util::SymbolTable::new(Z85_ALPHABET);
+
+/// Adobe Ascii85 settings, from `--opt wrapper=true`, `--opt y=true` and `--opt strict=true`.
+#[derive(Default)]
+struct Ascii85Options {
+    /// Emit the `<~ ~>` delimiters Adobe tools expect.
+    wrapper: bool,
+    /// Abbreviate four spaces as `y`, as btoa 4.2 does.
+    y: bool,
+    /// Neither emit nor accept the `z`/`y` abbreviations.
+    strict: bool,
+}
+
+impl Ascii85Options {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("ascii85", &["wrapper", "y", "strict"])?;
+        let parsed = Self {
+            wrapper: opts.get_bool("wrapper")?.unwrap_or(false),
+            y: opts.get_bool("y")?.unwrap_or(false),
+            strict: opts.get_bool("strict")?.unwrap_or(false),
+        };
+        if parsed.y && parsed.strict {
+            return Err(MbaseError::invalid_input("options 'y' and 'strict' are mutually exclusive"));
+        }
+        Ok(parsed)
     }
+}
 
+fn encode_ascii85(input: &[u8], opts: &Ascii85Options) -> String {
     let mut result = String::new();
+    if opts.wrapper {
+        result.push_str("<~");
+    }
 
     for chunk in input.chunks(4) {
         let mut val: u32 = 0;
@@ -19,8 +47,10 @@ fn encode_ascii85(input: &[u8]) -> String {
             val |= (byte as u32) << (24 - i * 8);
         }
 
-        if chunk.len() == 4 && val == 0 {
+        if chunk.len() == 4 && val == 0 && !opts.strict {
             result.push('z');
+        } else if chunk.len() == 4 && val == 0x2020_2020 && opts.y {
+            result.push('y');
         } else {
             let output_len = chunk.len() + 1;
             let mut chars = [0u8; 5];
@@ -35,10 +65,18 @@ fn encode_ascii85(input: &[u8]) -> String {
         }
     }
 
+    if opts.wrapper {
+        result.push_str("~>");
+    }
     result
 }
 
-fn decode_ascii85(input: &str, mode: Mode) -> Result<Vec<u8>> {
+fn ascii85_group(chars: &[u8], position: usize) -> Result<u32> {
+    let val = chars.iter().fold(0u64, |acc, &v| acc * 85 + v as u64);
+    u32::try_from(val).map_err(|_| MbaseError::invalid_input(format!("group ending at position {} exceeds 32 bits", position)))
+}
+
+fn decode_ascii85(input: &str, mode: Mode, opts: &Ascii85Options) -> Result<Vec<u8>> {
     let cleaned = util::clean_for_mode(input, mode);
 
     let stripped = if cleaned.starts_with("<~") && cleaned.ends_with("~>") {
@@ -56,11 +94,14 @@ fn decode_ascii85(input: &str, mode: Mode) -> Result<Vec<u8>> {
     let mut pos = 0;
 
     for c in stripped.chars() {
-        if c == 'z' {
+        if c == 'z' || c == 'y' {
+            if opts.strict {
+                return Err(MbaseError::invalid_input(format!("'{}' abbreviation at position {} is not allowed with strict=true", c, pos)));
+            }
             if !chars.is_empty() {
-                return Err(MbaseError::invalid_input("'z' in middle of group"));
+                return Err(MbaseError::invalid_input(format!("'{}' in middle of group at position {}", c, pos)));
             }
-            result.extend_from_slice(&[0, 0, 0, 0]);
+            result.extend_from_slice(if c == 'z' { &[0; 4] } else { b"    " });
             pos += 1;
             continue;
         }
@@ -73,20 +114,15 @@ fn decode_ascii85(input: &str, mode: Mode) -> Result<Vec<u8>> {
         pos += 1;
 
         if chars.len() == 5 {
-            let val = chars.iter().fold(0u32, |acc, &v| acc * 85 + v as u32);
-            result.push((val >> 24) as u8);
-            result.push((val >> 16) as u8);
-            result.push((val >> 8) as u8);
-            result.push(val as u8);
+            result.extend_from_slice(&ascii85_group(&chars, pos - 1)?.to_be_bytes());
             chars.clear();
         }
     }
 
     if !chars.is_empty() {
         let pad_count = 5 - chars.len();
-        chars.extend(std::iter::repeat_n(84, pad_count));
-        let val = chars.iter().fold(0u32, |acc, &v| acc * 85 + v as u32);
-        let bytes = [(val >> 24) as u8, (val >> 16) as u8, (val >> 8) as u8, val as u8];
+        chars.extend(core::iter::repeat_n(84, pad_count));
+        let bytes = ascii85_group(&chars, pos - 1)?.to_be_bytes();
         result.extend_from_slice(&bytes[..4 - pad_count]);
     }
 
@@ -142,17 +178,16 @@ fn decode_z85(input: &str, mode: Mode) -> Result<Vec<u8>> {
 
     let mut i = 0;
     while i < chars.len() {
-        let chunk_len = std::cmp::min(5, chars.len() - i);
+        let chunk_len = core::cmp::min(5, chars.len() - i);
         let chunk = &chars[i..i + chunk_len];
 
-        let mut val: u32 = 0;
+        let mut val: u64 = 0;
         for (j, &c) in chunk.iter().enumerate() {
             let pos = i + j;
-            let v = Z85_ALPHABET
-                .chars()
-                .position(|x| x == c)
+            let v = Z85_SYMBOLS
+                .value(c)
                 .ok_or(MbaseError::InvalidCharacter { char: c, position: pos })?;
-            val = val * 85 + v as u32;
+            val = val * 85 + v as u64;
         }
 
         if chunk_len < 5 {
@@ -160,6 +195,7 @@ fn decode_z85(input: &str, mode: Mode) -> Result<Vec<u8>> {
                 val = val * 85 + 84;
             }
         }
+        let val = u32::try_from(val).map_err(|_| MbaseError::invalid_input(format!("z85 group at position {} exceeds 32 bits", i)))?;
 
         let bytes = [(val >> 24) as u8, (val >> 16) as u8, (val >> 8) as u8, val as u8];
 
@@ -261,11 +297,19 @@ impl Codec for Ascii85 {
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        Ok(encode_ascii85(input))
+        Ok(encode_ascii85(input, &Ascii85Options::default()))
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        decode_ascii85(input, mode)
+        decode_ascii85(input, mode, &Ascii85Options::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Ok(encode_ascii85(input, &Ascii85Options::from_options(opts)?))
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_ascii85(input, mode, &Ascii85Options::from_options(opts)?)
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -301,6 +345,84 @@ impl Codec for Z85 {
     }
 }
 
+/// Z85 for any input length: zero-pad to a multiple of 4, encode per RFC 32, then append one
+/// digit `1`-`3` giving the number of pad bytes. Input that is already a multiple of 4 encodes
+/// to plain spec-compliant Z85.
+pub struct Z85Padded;
+
+impl Codec for Z85Padded {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "z85padded",
+            aliases: &["z85pad"],
+            alphabet: Z85_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Z85 with zero padding and a trailing pad-count digit",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        let pad = (4 - input.len() % 4) % 4;
+        let mut padded = input.to_vec();
+        padded.resize(input.len() + pad, 0);
+        let mut encoded = encode_z85(&padded)?;
+        if pad > 0 {
+            encoded.push(char::from(b'0' + pad as u8));
+        }
+        Ok(encoded)
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let cleaned = util::clean_for_mode(input, mode);
+        let (body, pad) = match cleaned.len() % 5 {
+            0 => (cleaned.as_str(), 0),
+            1 => {
+                let (end, digit) = cleaned.char_indices().last().unwrap_or_default();
+                match digit {
+                    '1'..='3' => (&cleaned[..end], usize::from(digit as u8 - b'0')),
+                    _ => return Err(MbaseError::invalid_input(format!("pad count '{}' at position {} must be 1, 2 or 3", digit, end))),
+                }
+            }
+            _ => {
+                return Err(MbaseError::invalid_length_msg(
+                    LengthConstraint::MultipleOf(5),
+                    cleaned.len(),
+                    "plus an optional pad-count digit",
+                ))
+            }
+        };
+        if pad > 0 && body.is_empty() {
+            return Err(MbaseError::invalid_input("pad count without any data"));
+        }
+
+        let mut decoded = decode_z85(body, Mode::Strict)?;
+        let data_len = decoded.len() - pad;
+        if mode == Mode::Strict && decoded[data_len..].iter().any(|&b| b != 0) {
+            return Err(MbaseError::invalid_padding(format!("the {} trimmed pad byte(s) are not zero", pad)));
+        }
+        decoded.truncate(data_len);
+        Ok(decoded)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let mut candidate = detect_z85(
+            input
+                .strip_suffix(['1', '2', '3'])
+                .filter(|_| input.len() % 5 == 1)
+                .unwrap_or(input),
+        );
+        candidate.codec = "z85padded".to_string();
+        if input.len() % 5 == 1 && candidate.confidence > 0.0 {
+            candidate.reasons.push("length is 5n+1 with a trailing pad-count digit".to_string());
+        } else {
+            candidate.confidence *= 0.9;
+        }
+        candidate
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,6 +469,27 @@ mod tests {
         assert_eq!(decoded, b"Test");
     }
 
+    #[test]
+    fn test_ascii85_options() {
+        let opts = CodecOptions::new().with("wrapper", "true").with("y", "true");
+        let encoded = Ascii85.encode_with(b"    \0\0\0\0Hi", &opts).unwrap();
+        assert_eq!(encoded, "<~yz88/~>");
+        assert_eq!(Ascii85.decode(&encoded, Mode::Strict).unwrap(), b"    \0\0\0\0Hi");
+        assert_eq!(Ascii85.encode(b"    ").unwrap(), "+<VdL");
+
+        let strict = CodecOptions::new().with("strict", "true");
+        assert_eq!(Ascii85.encode_with(&[0; 4], &strict).unwrap(), "!!!!!");
+        assert!(Ascii85.decode_with("z", Mode::Strict, &strict).is_err());
+        assert!(Ascii85.decode_with("y", Mode::Strict, &strict).is_err());
+        assert!(Ascii85.encode_with(b"", &strict.with("y", "true")).is_err());
+    }
+
+    #[test]
+    fn test_ascii85_group_overflow() {
+        assert!(Ascii85.decode("uuuuu", Mode::Strict).is_err());
+        assert_eq!(Ascii85.decode("s8W-!", Mode::Strict).unwrap(), [0xff; 4]);
+    }
+
     #[test]
     fn test_ascii85_partial_block() {
         let encoded = Ascii85.encode(b"Hi").unwrap();
@@ -381,6 +524,12 @@ mod tests {
         assert_eq!(Z85.decode("", Mode::Strict).unwrap(), Vec::<u8>::new());
     }
 
+    #[test]
+    fn test_z85_group_overflow() {
+        // "%%%%%" is 84 * (85^4 + ... + 1), well above u32::MAX.
+        assert!(Z85.decode("%%%%%", Mode::Strict).is_err());
+    }
+
     #[test]
     fn test_z85_variable_length_input() {
         let test_cases = [
@@ -412,6 +561,31 @@ mod tests {
         assert_eq!(decoded, &[0x42, 0x43, 0x44]);
     }
 
+    #[test]
+    fn test_z85padded() {
+        assert_eq!(Z85Padded.encode(&[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]).unwrap(), "HelloWorld");
+        for len in 0..10u8 {
+            let input: Vec<u8> = (1..=len).collect();
+            let encoded = Z85Padded.encode(&input).unwrap();
+            assert_eq!(encoded.len() % 5, usize::from(len % 4 != 0));
+            assert_eq!(Z85Padded.decode(&encoded, Mode::Strict).unwrap(), input);
+        }
+        assert_eq!(Z85Padded.encode(b"A").unwrap(), "k(Z(+3");
+    }
+
+    #[test]
+    fn test_z85padded_errors() {
+        assert!(Z85Padded.decode("k(Z(+4", Mode::Strict).is_err());
+        assert!(Z85Padded.decode("k(Z(", Mode::Strict).is_err());
+        assert!(Z85Padded.decode("3", Mode::Strict).is_err());
+        assert!(Z85Padded.decode("abcdé", Mode::Strict).is_err());
+        assert!(Z85Padded.decode("abcé1", Mode::Strict).is_err());
+        // "A\x01\0\0" with pad count 3: the trimmed bytes are not zero.
+        let nonzero = encode_z85(&[0x41, 1, 0, 0]).unwrap() + "3";
+        assert!(Z85Padded.decode(&nonzero, Mode::Strict).is_err());
+        assert_eq!(Z85Padded.decode(&nonzero, Mode::Lenient).unwrap(), b"A");
+    }
+
     #[test]
     fn test_z85_known_vector() {
         let input = [0x8E, 0x0B, 0xDD, 0x69, 0x76, 0x28, 0xB9, 0x1D];
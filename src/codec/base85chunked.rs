@@ -1,6 +1,7 @@
 use super::{rfc1924, util, Codec};
 use crate::error::{MbaseError as Error, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 pub struct Base85Chunked;
 
@@ -70,7 +71,7 @@ impl Codec for Base85Chunked {
 
         let mut i = 0;
         while i < chars.len() {
-            let chunk_len = std::cmp::min(5, chars.len() - i);
+            let chunk_len = core::cmp::min(5, chars.len() - i);
             let chunk = &chars[i..i + chunk_len];
 
             if chunk_len == 1 {
@@ -80,10 +81,9 @@ impl Codec for Base85Chunked {
             let mut val: u32 = 0;
             for (j, &c) in chunk.iter().enumerate() {
                 let pos = i + j;
-                let v = rfc1924::RFC1924_ALPHABET
-                    .chars()
-                    .position(|x| x == c)
-                    .ok_or_else(|| Error::InvalidCharacter { char: c, position: pos })?;
+                let v = rfc1924::RFC1924_SYMBOLS
+                    .value(c)
+                    .ok_or(Error::InvalidCharacter { char: c, position: pos })?;
                 val = val * 85 + v as u32;
             }
 
@@ -125,7 +125,7 @@ impl Codec for Base85Chunked {
 
         // Require 100% match and prefer length divisible by 5
         if ratio == 1.0 {
-            if input.len() % 5 == 0 {
+            if input.len().is_multiple_of(5) {
                 DetectCandidate {
                     codec: "base85chunked".to_string(),
                     confidence: util::confidence::PARTIAL_MATCH,
@@ -151,6 +151,104 @@ impl Codec for Base85Chunked {
     }
 }
 
+const PYTHON_OPTIONS: &[&str] = &["pad"];
+
+/// Python's `base64.b85encode`: the RFC 1924 alphabet over big-endian 4-byte groups. The input
+/// is zero-filled to a whole group and, unless `pad`, the characters for the fill are dropped.
+fn python_encode(input: &[u8], pad: bool) -> String {
+    let alphabet = rfc1924::RFC1924_ALPHABET.as_bytes();
+    let mut result = String::with_capacity(input.len().div_ceil(4) * 5);
+    for chunk in input.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut v = u32::from_be_bytes(group);
+        let mut chars = [0u8; 5];
+        for c in chars.iter_mut().rev() {
+            *c = alphabet[(v % 85) as usize];
+            v /= 85;
+        }
+        let len = if pad { 5 } else { chunk.len() + 1 };
+        result.extend(chars[..len].iter().map(|&c| c as char));
+    }
+    result
+}
+
+/// Python's `base64.b85decode`: a short final group is filled with `~` and the bytes for the
+/// fill dropped. A group above 2^32 - 1 is an error, as in CPython.
+fn python_decode(input: &str, mode: Mode) -> Result<Vec<u8>> {
+    let chars: Vec<char> = match mode {
+        Mode::Strict => input.chars().collect(),
+        Mode::Lenient => input.chars().filter(|c| !c.is_whitespace()).collect(),
+    };
+    // CPython decodes a lone final character to nothing; no encoder writes one.
+    if mode == Mode::Strict && chars.len() % 5 == 1 {
+        return Err(Error::invalid_input(format!(
+            "base85python decode: a final group of one character at position {} holds no bytes",
+            chars.len() - 1
+        )));
+    }
+    let mut result = Vec::with_capacity(chars.len() / 5 * 4 + 4);
+    for (index, chunk) in chars.chunks(5).enumerate() {
+        let start = index * 5;
+        let mut v: u32 = 0;
+        for j in 0..5 {
+            let digit = match chunk.get(j) {
+                Some(&c) => rfc1924::RFC1924_SYMBOLS.value(c).ok_or(Error::InvalidCharacter {
+                    char: c,
+                    position: start + j,
+                })?,
+                None => 84,
+            };
+            v = v
+                .checked_mul(85)
+                .and_then(|v| v.checked_add(digit as u32))
+                .ok_or_else(|| Error::invalid_input_at(start, "base85 overflow in group"))?;
+        }
+        result.extend_from_slice(&v.to_be_bytes()[..chunk.len() - 1]);
+    }
+    Ok(result)
+}
+
+pub struct Base85Python;
+
+impl Codec for Base85Python {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "base85python",
+            aliases: &["b85", "pyb85"],
+            alphabet: rfc1924::RFC1924_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Base85 as Python's base64.b85encode (RFC1924 alphabet, pad=true keeps whole groups)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        Ok(python_encode(input, false))
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("base85python", PYTHON_OPTIONS)?;
+        Ok(python_encode(input, opts.get_bool("pad")?.unwrap_or(false)))
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        python_decode(input, mode)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let mut candidate = Base85Chunked.detect_score(input);
+        candidate.codec = "base85python".to_string();
+        // The unpadded output is base85chunked's; only an overflowing group tells them apart.
+        // Input that only decodes leniently is left to the detect engine to score and explain.
+        if candidate.confidence > 0.0 && python_decode(input, Mode::Strict).is_err() {
+            return util::confidence::no_match("base85python");
+        }
+        candidate
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,4 +345,49 @@ mod tests {
         // Use a string with characters NOT in the alphabet (space and comma)
         assert!(codec.detect_score("hello, world").confidence < 0.1);
     }
+
+    #[test]
+    fn test_base85python_cpython_vectors() {
+        // base64.b85encode(data) and base64.b85encode(data, pad=True) from CPython.
+        let pad = CodecOptions::new().with("pad", "true");
+        for (data, unpadded, padded) in [
+            (&b""[..], "", ""),
+            (b"www.python.org", "cXxL#aCvlSZ*DGca%T", "cXxL#aCvlSZ*DGca%TVl"),
+            (b"no padding..", "Zf_uPVPs@!Zf7no", "Zf_uPVPs@!Zf7no"),
+            (b"zero compression\0\0\0\0", "dS!BNAY*TBaB^jHb7^mG00000", "dS!BNAY*TBaB^jHb7^mG00000"),
+            (b"hello", "Xk~0{Zv", "Xk~0{ZvX%Q"),
+            (b"\xff\xff\xff\xff", "|NsC0", "|NsC0"),
+            (b"\xff", "{{", "{{R30"),
+        ] {
+            assert_eq!(Base85Python.encode(data).unwrap(), unpadded);
+            assert_eq!(Base85Python.encode_with(data, &pad).unwrap(), padded);
+            assert_eq!(Base85Python.decode(unpadded, Mode::Strict).unwrap(), data);
+        }
+        // The padded form decodes to the zero fill too, as in CPython.
+        assert_eq!(Base85Python.decode("{{R30", Mode::Strict).unwrap(), b"\xff\0\0\0");
+        let all: Vec<u8> = (0..=254).collect();
+        let encoded = Base85Python.encode(&all).unwrap();
+        assert!(encoded.starts_with("009C61O)~M2nh-c3=Iws5D^j+6crX17#SKH9337X") && encoded.ends_with("Qdp"));
+        assert_eq!(Base85Python.decode(&encoded, Mode::Strict).unwrap(), all);
+    }
+
+    #[test]
+    fn test_base85python_decode_errors() {
+        assert!(matches!(Base85Python.decode("|NsC1", Mode::Strict), Err(Error::InvalidInputAt { position: 0, .. })));
+        assert!(Base85Python
+            .decode("00000|Ns", Mode::Lenient)
+            .unwrap_err()
+            .to_string()
+            .contains("overflow"));
+        assert!(Base85Python.decode("0", Mode::Strict).is_err());
+        assert_eq!(Base85Python.decode("0", Mode::Lenient).unwrap(), b"");
+        assert!(matches!(Base85Python.decode("Xk\"0", Mode::Strict), Err(Error::InvalidCharacter { char: '"', position: 2 })));
+    }
+
+    #[test]
+    fn test_base85python_detect_neutral_on_strict_failure() {
+        let candidate = Base85Python.detect_score("SGVsbG8gd29ybGQ=");
+        assert_eq!(candidate.confidence, 0.0);
+        assert!(candidate.reasons.is_empty());
+    }
 }
@@ -1,5 +1,6 @@
 use super::{rfc1924, util, Codec};
 use crate::error::Result;
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct Base85Rfc1924;
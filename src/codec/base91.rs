@@ -1,5 +1,6 @@
-use super::{util, Codec};
+use super::{util, Codec, StreamDecoder, StreamEncoder};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 const ALPHABET: &[u8; 91] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!#$%&()*+,./:;<=>?@[]^_`{|}~\"";
@@ -12,105 +13,150 @@ fn decode_table() -> [i8; 256] {
     table
 }
 
-pub struct Base91;
-
-impl Codec for Base91 {
-    fn meta(&self) -> CodecMeta {
-        CodecMeta {
-            name: "base91",
-            aliases: &["b91"],
-            alphabet: std::str::from_utf8(ALPHABET).unwrap(),
-            multibase_code: None,
-            padding: PaddingRule::None,
-            case_sensitivity: CaseSensitivity::Sensitive,
-            description: "basE91 encoding (highest density printable ASCII)",
-        }
-    }
-
-    fn encode(&self, input: &[u8]) -> Result<String> {
-        if input.is_empty() {
-            return Ok(String::new());
-        }
-
-        let mut result = Vec::with_capacity(input.len() * 16 / 13 + 2);
-        let mut queue: u32 = 0;
-        let mut nbits: u32 = 0;
+/// basE91 bit queue: 13 or 14 bits at a time become two characters.
+#[derive(Default)]
+struct Base91Encoder {
+    queue: u32,
+    nbits: u32,
+}
 
+impl StreamEncoder for Base91Encoder {
+    fn update(&mut self, input: &[u8], out: &mut String) -> Result<()> {
+        out.reserve(input.len() * 16 / 13 + 2);
         for &byte in input {
-            queue |= (byte as u32) << nbits;
-            nbits += 8;
+            self.queue |= (byte as u32) << self.nbits;
+            self.nbits += 8;
 
-            if nbits > 13 {
-                let mut val = queue & 8191; // 13 bits
+            if self.nbits > 13 {
+                let mut val = self.queue & 8191; // 13 bits
                 if val > 88 {
-                    queue >>= 13;
-                    nbits -= 13;
+                    self.queue >>= 13;
+                    self.nbits -= 13;
                 } else {
-                    val = queue & 16383; // 14 bits
-                    queue >>= 14;
-                    nbits -= 14;
+                    val = self.queue & 16383; // 14 bits
+                    self.queue >>= 14;
+                    self.nbits -= 14;
                 }
-                result.push(ALPHABET[(val % 91) as usize]);
-                result.push(ALPHABET[(val / 91) as usize]);
+                out.push(ALPHABET[(val % 91) as usize] as char);
+                out.push(ALPHABET[(val / 91) as usize] as char);
             }
         }
+        Ok(())
+    }
 
-        if nbits > 0 {
-            result.push(ALPHABET[(queue % 91) as usize]);
-            if nbits > 7 || queue > 90 {
-                result.push(ALPHABET[(queue / 91) as usize]);
+    fn finish(&mut self, out: &mut String) -> Result<()> {
+        if self.nbits > 0 {
+            out.push(ALPHABET[(self.queue % 91) as usize] as char);
+            if self.nbits > 7 || self.queue > 90 {
+                out.push(ALPHABET[(self.queue / 91) as usize] as char);
             }
         }
-
-        Ok(String::from_utf8(result).unwrap())
+        *self = Self::default();
+        Ok(())
     }
+}
 
-    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let table = decode_table();
-        let input = if mode == Mode::Lenient {
-            input.chars().filter(|c| !c.is_whitespace()).collect::<String>()
-        } else {
-            input.to_string()
-        };
+struct Base91Decoder {
+    table: [i8; 256],
+    mode: Mode,
+    queue: u32,
+    nbits: u32,
+    /// First character of an incomplete pair.
+    val: Option<u32>,
+    position: usize,
+}
 
-        if input.is_empty() {
-            return Ok(Vec::new());
+impl Base91Decoder {
+    fn new(mode: Mode) -> Self {
+        Self {
+            table: decode_table(),
+            mode,
+            queue: 0,
+            nbits: 0,
+            val: None,
+            position: 0,
         }
+    }
+}
 
-        let mut result = Vec::with_capacity(input.len() * 14 / 16);
-        let mut queue: u32 = 0;
-        let mut nbits: u32 = 0;
-        let mut val: i32 = -1;
-
-        for (pos, c) in input.chars().enumerate() {
-            let d = table[c as usize];
-            if d == -1 {
-                return Err(MbaseError::InvalidCharacter { char: c, position: pos });
+impl StreamDecoder for Base91Decoder {
+    fn update(&mut self, input: &str, out: &mut Vec<u8>) -> Result<()> {
+        out.reserve(input.len() * 14 / 16);
+        for c in input.chars() {
+            let position = self.position;
+            self.position += 1;
+            if self.mode == Mode::Lenient && c.is_whitespace() {
+                continue;
             }
+            let d = match self.table.get(c as usize) {
+                Some(&d) if d >= 0 => d as u32,
+                _ => return Err(MbaseError::InvalidCharacter { char: c, position }),
+            };
 
-            if val == -1 {
-                val = d as i32;
-            } else {
-                val += (d as i32) * 91;
-                queue |= (val as u32) << nbits;
-                nbits += if (val & 8191) > 88 { 13 } else { 14 };
-
-                while nbits > 7 {
-                    result.push((queue & 255) as u8);
-                    queue >>= 8;
-                    nbits -= 8;
-                }
-                val = -1;
+            let Some(first) = self.val.take() else {
+                self.val = Some(d);
+                continue;
+            };
+            let val = first + d * 91;
+            self.queue |= val << self.nbits;
+            self.nbits += if (val & 8191) > 88 { 13 } else { 14 };
+            while self.nbits > 7 {
+                out.push((self.queue & 255) as u8);
+                self.queue >>= 8;
+                self.nbits -= 8;
             }
         }
+        Ok(())
+    }
 
-        if val != -1 {
-            result.push((queue | ((val as u32) << nbits)) as u8);
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        if let Some(val) = self.val {
+            out.push((self.queue | (val << self.nbits)) as u8);
         }
+        *self = Self::new(self.mode);
+        Ok(())
+    }
+}
+
+pub struct Base91;
 
+impl Codec for Base91 {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "base91",
+            aliases: &["b91"],
+            alphabet: core::str::from_utf8(ALPHABET).unwrap(),
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "basE91 encoding (highest density printable ASCII)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        let mut encoder = Base91Encoder::default();
+        let mut result = String::new();
+        encoder.update(input, &mut result)?;
+        encoder.finish(&mut result)?;
         Ok(result)
     }
 
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let mut decoder = Base91Decoder::new(mode);
+        let mut result = Vec::new();
+        decoder.update(input, &mut result)?;
+        decoder.finish(&mut result)?;
+        Ok(result)
+    }
+
+    fn stream_encoder(&self) -> Option<Box<dyn StreamEncoder>> {
+        Some(Box::new(Base91Encoder::default()))
+    }
+
+    fn stream_decoder(&self, mode: Mode) -> Option<Box<dyn StreamDecoder>> {
+        Some(Box::new(Base91Decoder::new(mode)))
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let table = decode_table();
         let clean: String = input.chars().filter(|c| !c.is_whitespace()).collect();
@@ -124,7 +170,7 @@ impl Codec for Base91 {
             };
         }
 
-        let invalid_count = clean.chars().filter(|&c| table[c as usize] == -1).count();
+        let invalid_count = clean.chars().filter(|&c| table.get(c as usize).is_none_or(|&d| d == -1)).count();
         if invalid_count > 0 {
             return DetectCandidate {
                 codec: self.name().to_string(),
@@ -214,6 +260,38 @@ mod tests {
         assert_eq!(decoded, b"test");
     }
 
+    #[test]
+    fn test_base91_chunked_matches_one_shot() {
+        let input: Vec<u8> = (0..1000u32).map(|i| (i * 37 % 251) as u8).collect();
+        let expected = Base91.encode(&input).unwrap();
+        let mut encoder = Base91.stream_encoder().unwrap();
+        let mut encoded = String::new();
+        for chunk in input.chunks(7) {
+            encoder.update(chunk, &mut encoded).unwrap();
+        }
+        encoder.finish(&mut encoded).unwrap();
+        assert_eq!(encoded, expected);
+
+        let mut decoder = Base91.stream_decoder(Mode::Strict).unwrap();
+        let mut decoded = Vec::new();
+        for chunk in expected.as_bytes().chunks(5) {
+            decoder.update(core::str::from_utf8(chunk).unwrap(), &mut decoded).unwrap();
+        }
+        decoder.finish(&mut decoded).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn test_base91_error_position() {
+        assert!(matches!(
+            Base91.decode("ab\u{e9}", Mode::Strict),
+            Err(MbaseError::InvalidCharacter {
+                char: '\u{e9}',
+                position: 2
+            })
+        ));
+    }
+
     #[test]
     fn test_base91_density() {
         let codec = Base91;
@@ -1,11 +1,14 @@
 use super::{util, Codec};
-use crate::error::{MbaseError as Error, Result};
+use crate::error::{LengthConstraint, MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct Base92;
 
 const BASE92_ALPHABET: &str = "!#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[]^_`abcdefghijklmnopqrstuvwxyz{|}~";
 
+static BASE92_SYMBOLS: util::SymbolTable = util::SymbolTable::new(BASE92_ALPHABET);
+
 impl Codec for Base92 {
     fn meta(&self) -> CodecMeta {
         CodecMeta {
@@ -41,7 +44,7 @@ impl Codec for Base92 {
         while !num.iter().all(|&x| x == 0) {
             let mut remainder = 0u16;
             for byte in num.iter_mut() {
-                let temp = (remainder as u16 * 256) + *byte as u16;
+                let temp = (remainder * 256) + *byte as u16;
                 *byte = (temp / 92) as u8;
                 remainder = temp % 92;
             }
@@ -87,7 +90,7 @@ impl Codec for Base92 {
         let mut num: Vec<u8> = vec![0];
 
         for (i, c) in cleaned.chars().skip(leading_zeros).enumerate() {
-            let val = BASE92_ALPHABET.find(c).ok_or_else(|| Error::InvalidCharacter {
+            let val = BASE92_SYMBOLS.value(c).ok_or_else(|| Error::InvalidCharacter {
                 char: c,
                 position: i + leading_zeros,
             })? as u16;
@@ -145,6 +148,102 @@ impl Codec for Base92 {
     }
 }
 
+/// Bytes per base92chunked group; 2^104 just fits in 16 base-92 digits (92^16 ~ 2^104.4).
+const CHUNK_BYTES: usize = 13;
+const CHUNK_CHARS: usize = 16;
+
+/// Characters a group of `bytes` bytes (1..=13) encodes to: the fewest digits covering 256^bytes.
+fn chunk_chars(bytes: usize) -> usize {
+    let limit = 1u128 << (8 * bytes);
+    let mut span = 1u128;
+    let mut chars = 0;
+    while span < limit {
+        span *= 92;
+        chars += 1;
+    }
+    chars
+}
+
+/// Base92 in independent 13-byte groups of 16 characters (a short final group takes fewer), so
+/// unlike the whole-buffer `base92` it can be encoded and decoded as a stream.
+pub struct Base92Chunked;
+
+impl Codec for Base92Chunked {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "base92chunked",
+            aliases: &[],
+            alphabet: BASE92_ALPHABET,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Base92 with chunked encoding (13-byte groups to 16-char groups)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        let alphabet = BASE92_ALPHABET.as_bytes();
+        let mut result = String::with_capacity(input.len().div_ceil(CHUNK_BYTES) * CHUNK_CHARS);
+        for chunk in input.chunks(CHUNK_BYTES) {
+            let mut val = chunk.iter().fold(0u128, |acc, &b| (acc << 8) | b as u128);
+            let mut digits = [0u8; CHUNK_CHARS];
+            let len = chunk_chars(chunk.len());
+            for digit in digits[..len].iter_mut().rev() {
+                *digit = alphabet[(val % 92) as usize];
+                val /= 92;
+            }
+            result.extend(digits[..len].iter().map(|&c| c as char));
+        }
+        Ok(result)
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let chars: Vec<char> = input.chars().filter(|c| mode == Mode::Strict || !c.is_whitespace()).collect();
+        let mut result = Vec::with_capacity(chars.len() / CHUNK_CHARS * CHUNK_BYTES + CHUNK_BYTES);
+        for (group, chunk) in chars.chunks(CHUNK_CHARS).enumerate() {
+            let bytes = (1..=CHUNK_BYTES).find(|&b| chunk_chars(b) == chunk.len()).ok_or_else(|| {
+                Error::invalid_length_msg(
+                    LengthConstraint::MultipleOf(CHUNK_CHARS),
+                    chars.len(),
+                    "the final group has a length no byte count encodes to",
+                )
+            })?;
+            let mut val = 0u128;
+            for (j, &c) in chunk.iter().enumerate() {
+                let digit = BASE92_SYMBOLS.value(c).ok_or(Error::InvalidCharacter {
+                    char: c,
+                    position: group * CHUNK_CHARS + j,
+                })?;
+                val = val * 92 + digit as u128;
+            }
+            if val >> (8 * bytes) != 0 {
+                return Err(Error::invalid_input(format!("group {} overflows {} bytes", group + 1, bytes)));
+            }
+            result.extend_from_slice(&val.to_be_bytes()[16 - bytes..]);
+        }
+        Ok(result)
+    }
+
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        Some((CHUNK_BYTES, CHUNK_CHARS))
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        if input.is_empty() || !input.chars().all(|c| BASE92_ALPHABET.contains(c)) {
+            return util::confidence::no_match(self.name());
+        }
+        if self.decode(input, Mode::Strict).is_err() {
+            return util::confidence::no_match(self.name());
+        }
+        // Below whole-buffer base92, which shares the alphabet and is the more common form.
+        util::confidence::with_confidence(
+            self.name(),
+            util::confidence::WEAK_MATCH,
+            "all characters in alphabet, group lengths valid".to_string(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,7 +329,37 @@ mod tests {
     #[test]
     fn test_base92_alphabet_size() {
         assert_eq!(BASE92_ALPHABET.len(), 92);
-        let unique: std::collections::HashSet<_> = BASE92_ALPHABET.chars().collect();
+        let unique: alloc::collections::BTreeSet<_> = BASE92_ALPHABET.chars().collect();
         assert_eq!(unique.len(), 92);
     }
+
+    #[test]
+    fn test_base92chunked_group_lengths() {
+        let lengths: Vec<usize> = (1..=CHUNK_BYTES).map(chunk_chars).collect();
+        assert_eq!(lengths, [2, 3, 4, 5, 7, 8, 9, 10, 12, 13, 14, 15, 16]);
+    }
+
+    #[test]
+    fn test_base92chunked_roundtrip() {
+        let codec = Base92Chunked;
+        let data: Vec<u8> = (0..100u8).map(|i| i.wrapping_mul(53)).collect();
+        for len in 0..data.len() {
+            let encoded = codec.encode(&data[..len]).unwrap();
+            assert_eq!(codec.decode(&encoded, Mode::Strict).unwrap(), &data[..len], "length {}", len);
+        }
+        assert_eq!(codec.encode(&[0; 13]).unwrap(), "!".repeat(16));
+        assert_eq!(codec.encode(&[0xff; 26]).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_base92chunked_errors() {
+        let codec = Base92Chunked;
+        assert!(codec.decode("!", Mode::Strict).is_err());
+        assert!(codec.decode("!!!!!!", Mode::Strict).is_err());
+        assert!(codec.decode("~~", Mode::Strict).is_err());
+        assert!(matches!(codec.decode("!\"", Mode::Strict), Err(Error::InvalidCharacter { char: '"', position: 1 })));
+        let encoded = codec.encode(b"hello world").unwrap();
+        let spaced = format!("{} {}", &encoded[..4], &encoded[4..]);
+        assert_eq!(codec.decode(&spaced, Mode::Lenient).unwrap(), b"hello world");
+    }
 }
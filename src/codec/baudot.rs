@@ -1,175 +1,254 @@
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+
+use crate::types::{CodecOptions, Utf8Policy};
 
 pub struct Baudot;
+pub struct BaudotUs;
+
+/// Letters and figures for one Baudot-Murray variant, indexed by 5-bit code; `'\0'` marks unassigned codes.
+struct Table {
+    codec: &'static str,
+    letters: [char; 32],
+    figures: [char; 32],
+}
 
-const BAUDOT_LETTERS: [char; 32] = [
+const ITA2_LETTERS: [char; 32] = [
     '\0', 'E', '\n', 'A', ' ', 'S', 'I', 'U', '\r', 'D', 'R', 'J', 'N', 'F', 'C', 'K', 'T', 'Z', 'L', 'W', 'H', 'Y', 'P', 'Q', 'O', 'B',
     'G', '\0', 'M', 'X', 'V', '\0',
 ];
 
-const BAUDOT_FIGURES: [char; 32] = [
-    '\0', '3', '\n', '-', ' ', '\'', '8', '7', '\r', '$', '4', '\u{0007}', ',', '!', ':', '(', '5', '"', ')', '2', '#', '6', '0', '1', '9',
-    '?', '&', '\0', '.', '/', ';', '\0',
-];
+/// CCITT ITA2: D is who-are-you (ENQ); F, G and H are left to national use.
+const ITA2: Table = Table {
+    codec: "baudot",
+    letters: ITA2_LETTERS,
+    figures: [
+        '\0', '3', '\n', '-', ' ', '\'', '8', '7', '\r', '\u{0005}', '4', '\u{0007}', ',', '\0', ':', '(', '5', '+', ')', '2', '\0', '6',
+        '0', '1', '9', '?', '\0', '\0', '.', '/', '=', '\0',
+    ],
+};
+
+/// US-TTY (ASA): bell moves to S, apostrophe to J, and F/G/H/D/Z/V carry `! & # $ " ;`.
+const US_TTY: Table = Table {
+    codec: "baudot-us",
+    letters: ITA2_LETTERS,
+    figures: [
+        '\0', '3', '\n', '-', ' ', '\u{0007}', '8', '7', '\r', '$', '4', '\'', ',', '!', ':', '(', '5', '"', ')', '2', '#', '6', '0', '1',
+        '9', '?', '&', '\0', '.', '/', ';', '\0',
+    ],
+};
 
 const LTRS_CODE: u8 = 0x1F;
 const FIGS_CODE: u8 = 0x1B;
+const LTRS_MARKER: &str = "[LTRS]";
+const FIGS_MARKER: &str = "[FIGS]";
+
+fn push_code(out: &mut String, code: u8) {
+    out.push_str(&format!("{:05b}", code));
+}
+
+/// Encodes text, inserting shifts as needed; literal `[LTRS]`/`[FIGS]` markers force a shift.
+fn encode_table(table: &Table, input: &[u8], policy: Utf8Policy) -> Result<String> {
+    let text = util::text_input(table.codec, input, policy, '?')?;
+    let letter_map: BTreeMap<char, u8> = table
+        .letters
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c != '\0')
+        .map(|(i, &c)| (c, i as u8))
+        .collect();
+    let figure_map: BTreeMap<char, u8> = table
+        .figures
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c != '\0')
+        .map(|(i, &c)| (c, i as u8))
+        .collect();
+
+    let mut result = String::new();
+    let mut in_letters = true;
+    let mut rest = text.as_ref();
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with(LTRS_MARKER) || rest.starts_with(FIGS_MARKER) {
+            in_letters = rest.starts_with(LTRS_MARKER);
+            push_code(&mut result, if in_letters { LTRS_CODE } else { FIGS_CODE });
+            rest = &rest[LTRS_MARKER.len()..];
+            continue;
+        }
 
-impl Codec for Baudot {
-    fn meta(&self) -> CodecMeta {
-        CodecMeta {
-            name: "baudot",
-            aliases: &["ita2", "baudot-ita2"],
-            alphabet: "01",
-            multibase_code: None,
-            padding: PaddingRule::None,
-            case_sensitivity: CaseSensitivity::Insensitive,
-            description: "Baudot code (ITA2 5-bit telegraph encoding)",
+        rest = &rest[c.len_utf8()..];
+        let ch = c.to_ascii_uppercase();
+        if let Some(&code) = letter_map.get(&ch) {
+            if !in_letters {
+                push_code(&mut result, LTRS_CODE);
+                in_letters = true;
+            }
+            push_code(&mut result, code);
+        } else if let Some(&code) = figure_map.get(&ch) {
+            if in_letters {
+                push_code(&mut result, FIGS_CODE);
+                in_letters = false;
+            }
+            push_code(&mut result, code);
+        } else {
+            return Err(Error::invalid_input(format!("character '{}' not supported in {}", ch.escape_debug(), table.codec)));
         }
     }
 
-    fn encode(&self, input: &[u8]) -> Result<String> {
-        let mut result = Vec::new();
-        let mut in_letters = true;
+    Ok(result)
+}
 
-        let letter_map: HashMap<char, u8> = BAUDOT_LETTERS
-            .iter()
-            .enumerate()
-            .filter(|(_, &c)| c != '\0')
-            .map(|(i, &c)| (c, i as u8))
-            .collect();
+/// Decodes 5-bit groups; with `show_shifts` the LTRS/FIGS codes appear as `[LTRS]`/`[FIGS]`.
+fn decode_table(table: &Table, input: &str, mode: Mode, show_shifts: bool) -> Result<Vec<u8>> {
+    let cleaned = if mode == Mode::Lenient {
+        input.chars().filter(|c| *c == '0' || *c == '1').collect::<String>()
+    } else {
+        input.to_string()
+    };
 
-        let figure_map: HashMap<char, u8> = BAUDOT_FIGURES
-            .iter()
-            .enumerate()
-            .filter(|(_, &c)| c != '\0')
-            .map(|(i, &c)| (c, i as u8))
-            .collect();
+    if cleaned.len() % 5 != 0 {
+        return Err(Error::invalid_input("Baudot input length must be multiple of 5"));
+    }
 
-        for &byte in input {
-            let ch = (byte as char).to_uppercase().next().unwrap_or(byte as char);
+    let mut result = Vec::new();
+    let mut in_letters = true;
 
-            if let Some(&code) = letter_map.get(&ch) {
-                if !in_letters {
-                    result.extend_from_slice(&format!("{:05b}", LTRS_CODE).as_bytes());
-                    in_letters = true;
-                }
-                result.extend_from_slice(&format!("{:05b}", code).as_bytes());
-            } else if let Some(&code) = figure_map.get(&ch) {
-                if in_letters {
-                    result.extend_from_slice(&format!("{:05b}", FIGS_CODE).as_bytes());
-                    in_letters = false;
-                }
-                result.extend_from_slice(&format!("{:05b}", code).as_bytes());
-            } else {
-                return Err(Error::invalid_input(format!("character '{}' not supported in Baudot", ch)));
+    for chunk in cleaned.as_bytes().chunks(5) {
+        let binary_str = core::str::from_utf8(chunk).map_err(|_| Error::invalid_input("invalid UTF-8 in binary string"))?;
+        let code = u8::from_str_radix(binary_str, 2).map_err(|_| Error::invalid_input("invalid binary digits"))?;
+
+        if code == LTRS_CODE || code == FIGS_CODE {
+            in_letters = code == LTRS_CODE;
+            if show_shifts {
+                result.extend_from_slice(if in_letters { LTRS_MARKER } else { FIGS_MARKER }.as_bytes());
             }
+            continue;
         }
 
-        Ok(String::from_utf8(result).unwrap())
-    }
-
-    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let cleaned = if mode == Mode::Lenient {
-            input.chars().filter(|c| *c == '0' || *c == '1').collect::<String>()
+        let ch = if in_letters {
+            table.letters[code as usize]
         } else {
-            input.to_string()
+            table.figures[code as usize]
         };
 
-        if cleaned.len() % 5 != 0 {
-            return Err(Error::invalid_input("Baudot input length must be multiple of 5"));
+        if ch == '\0' {
+            return Err(Error::invalid_input(format!(
+                "Baudot code {:05b} is unassigned in {} {}",
+                code,
+                table.codec,
+                if in_letters { "letters" } else { "figures" }
+            )));
         }
 
-        let mut result = Vec::new();
-        let mut in_letters = true;
+        result.push(ch as u8);
+    }
 
-        for chunk in cleaned.as_bytes().chunks(5) {
-            let binary_str = std::str::from_utf8(chunk).map_err(|_| Error::invalid_input("invalid UTF-8 in binary string"))?;
-            let code = u8::from_str_radix(binary_str, 2).map_err(|_| Error::invalid_input("invalid binary digits"))?;
+    Ok(result)
+}
 
-            if code == LTRS_CODE {
-                in_letters = true;
-                continue;
-            } else if code == FIGS_CODE {
-                in_letters = false;
-                continue;
-            }
+fn shift_option(codec: &str, opts: &CodecOptions) -> Result<bool> {
+    opts.ensure_known(codec, &["shifts"])?;
+    match opts.get("shifts") {
+        None | Some("strip") => Ok(false),
+        Some("show") => Ok(true),
+        Some(other) => Err(Error::invalid_input(format!("option 'shifts' expects show or strip, got '{}'", other))),
+    }
+}
 
-            let ch = if in_letters {
-                BAUDOT_LETTERS[code as usize]
-            } else {
-                BAUDOT_FIGURES[code as usize]
-            };
+fn detect_table(table: &Table, input: &str) -> DetectCandidate {
+    let cleaned = input.chars().filter(|c| *c == '0' || *c == '1').collect::<String>();
 
-            if ch == '\0' {
-                return Err(Error::invalid_input(format!("invalid Baudot code: {:05b}", code)));
-            }
+    if cleaned.is_empty() || cleaned.len() % 5 != 0 {
+        return DetectCandidate {
+            codec: table.codec.to_string(),
+            confidence: 0.0,
+            reasons: vec!["empty or invalid length".to_string()],
+            warnings: vec![],
+        };
+    }
 
-            result.push(ch as u8);
-        }
+    let binary_chars = input.chars().filter(|c| *c == '0' || *c == '1').count();
+    let ratio = binary_chars as f32 / input.len() as f32;
 
-        Ok(result)
+    if ratio < 0.9 {
+        return DetectCandidate {
+            codec: table.codec.to_string(),
+            confidence: 0.0,
+            reasons: vec!["low binary ratio".to_string()],
+            warnings: vec![],
+        };
     }
 
-    fn detect_score(&self, input: &str) -> DetectCandidate {
-        let cleaned = input.chars().filter(|c| *c == '0' || *c == '1').collect::<String>();
-
-        if cleaned.is_empty() || cleaned.len() % 5 != 0 {
-            return DetectCandidate {
-                codec: "baudot".to_string(),
-                confidence: 0.0,
-                reasons: vec!["empty or invalid length".to_string()],
-                warnings: vec![],
-            };
+    if decode_table(table, &cleaned, Mode::Strict, false).is_ok() {
+        DetectCandidate {
+            codec: table.codec.to_string(),
+            confidence: util::confidence::PARTIAL_MATCH,
+            reasons: vec!["valid baudot codes".to_string()],
+            warnings: vec![],
         }
-
-        let binary_chars = input.chars().filter(|c| *c == '0' || *c == '1').count();
-        let ratio = binary_chars as f32 / input.len() as f32;
-
-        if ratio < 0.9 {
-            return DetectCandidate {
-                codec: "baudot".to_string(),
-                confidence: 0.0,
-                reasons: vec!["low binary ratio".to_string()],
-                warnings: vec![],
-            };
+    } else {
+        DetectCandidate {
+            codec: table.codec.to_string(),
+            confidence: 0.0,
+            reasons: vec!["invalid codes".to_string()],
+            warnings: vec![],
         }
+    }
+}
 
-        let valid_chunks = cleaned
-            .as_bytes()
-            .chunks(5)
-            .filter(|chunk| {
-                if let Ok(s) = std::str::from_utf8(chunk) {
-                    if let Ok(code) = u8::from_str_radix(s, 2) {
-                        return code < 32;
-                    }
+macro_rules! baudot_codec {
+    ($codec:ident, $table:expr, $aliases:expr, $description:expr) => {
+        impl Codec for $codec {
+            fn meta(&self) -> CodecMeta {
+                CodecMeta {
+                    name: $table.codec,
+                    aliases: $aliases,
+                    alphabet: "01",
+                    multibase_code: None,
+                    padding: PaddingRule::None,
+                    case_sensitivity: CaseSensitivity::Insensitive,
+                    description: $description,
                 }
-                false
-            })
-            .count();
-
-        let total_chunks = cleaned.len() / 5;
-        if valid_chunks == total_chunks {
-            DetectCandidate {
-                codec: "baudot".to_string(),
-                confidence: util::confidence::PARTIAL_MATCH,
-                reasons: vec!["valid baudot codes".to_string()],
-                warnings: vec![],
             }
-        } else {
-            DetectCandidate {
-                codec: "baudot".to_string(),
-                confidence: 0.0,
-                reasons: vec!["invalid codes".to_string()],
-                warnings: vec![],
+
+            fn encode(&self, input: &[u8]) -> Result<String> {
+                self.encode_with(input, &CodecOptions::default())
+            }
+
+            fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+                opts.ensure_known($table.codec, &["utf8"])?;
+                encode_table(&$table, input, opts.utf8_policy()?)
+            }
+
+            fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+                decode_table(&$table, input, mode, false)
+            }
+
+            fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+                decode_table(&$table, input, mode, shift_option($table.codec, opts)?)
+            }
+
+            fn text_only(&self) -> bool {
+                true
+            }
+
+            fn lossy(&self) -> bool {
+                true
+            }
+
+            fn detect_score(&self, input: &str) -> DetectCandidate {
+                detect_table(&$table, input)
             }
         }
-    }
+    };
 }
 
+baudot_codec!(Baudot, ITA2, &["ita2", "baudot-ita2"], "Baudot code (ITA2 5-bit telegraph encoding)");
+baudot_codec!(BaudotUs, US_TTY, &["us-tty", "ustty", "baudot-ustty"], "Baudot code (US-TTY figures: bell on S, $ ! & # \" ;)");
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,4 +315,43 @@ mod tests {
         assert!(codec.detect_score("not binary").confidence < 0.1);
         assert!(codec.detect_score("0001").confidence < 0.1);
     }
+
+    #[test]
+    fn test_baudot_figure_tables_differ() {
+        // FIGS, then S: apostrophe in ITA2, bell in US-TTY.
+        assert_eq!(Baudot.decode("1101100101", Mode::Strict).unwrap(), b"'");
+        assert_eq!(BaudotUs.decode("1101100101", Mode::Strict).unwrap(), b"\x07");
+        assert_eq!(BaudotUs.decode("1101101011", Mode::Strict).unwrap(), b"'");
+
+        assert!(Baudot.encode(b"$").is_err());
+        assert_eq!(BaudotUs.encode(b"$").unwrap(), "1101101001");
+        assert_eq!(Baudot.encode(b"+").unwrap(), "1101110001");
+        assert!(BaudotUs.encode(b"+").is_err());
+        assert!(Baudot
+            .decode("1101101101", Mode::Strict)
+            .unwrap_err()
+            .to_string()
+            .contains("unassigned in baudot figures"));
+    }
+
+    #[test]
+    fn test_baudot_shift_markers() {
+        let encoded = Baudot.encode(b"A1B").unwrap();
+        let shown = CodecOptions::new().with("shifts", "show");
+        assert_eq!(Baudot.decode_with(&encoded, Mode::Strict, &shown).unwrap(), b"A[FIGS]1[LTRS]B");
+        assert_eq!(Baudot.decode_with(&encoded, Mode::Strict, &CodecOptions::new()).unwrap(), b"A1B");
+        assert_eq!(Baudot.encode(b"A[FIGS]1[LTRS]B").unwrap(), encoded);
+        assert!(Baudot
+            .decode_with(&encoded, Mode::Strict, &CodecOptions::new().with("shifts", "x"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_baudot_invalid_utf8() {
+        assert!(Baudot.encode(b"A\xffB").is_err());
+        let lenient = CodecOptions::new().with("utf8", "lenient");
+        assert_eq!(Baudot.encode_with(b"A\xffB", &lenient).unwrap(), Baudot.encode(b"A?B").unwrap());
+        let err = Baudot.encode("\u{e9}".as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("'\u{e9}'"), "{}", err);
+    }
 }
@@ -1,13 +1,90 @@
-use bech32::{Bech32 as Bech32Variant, Bech32m as Bech32mVariant, Hrp};
+use bech32::primitives::decode::{CheckedHrpstring, SegwitHrpstring, SegwitHrpstringError};
+use bech32::{Bech32 as Bech32Variant, Bech32m as Bech32mVariant, Fe32, Hrp};
 
 use super::util;
 use super::Codec;
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 const BECH32_ALPHABET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
 const DEFAULT_HRP: &str = "data";
 
+/// What decoding yields (`--opt form=`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Form {
+    /// All data characters regrouped into bytes, trailing bits dropped.
+    Bytes,
+    /// One byte (0-31) per data character, checksum removed.
+    Raw,
+    /// The witness program of a segwit address, without the version character.
+    Program,
+    /// The scriptPubKey of a segwit address: version opcode, push length, program.
+    Script,
+}
+
+fn form(codec: &str, opts: &CodecOptions) -> Result<Form> {
+    opts.ensure_known(codec, &["form"])?;
+    match opts.get("form") {
+        None | Some("bytes") => Ok(Form::Bytes),
+        Some("raw") => Ok(Form::Raw),
+        Some("program") => Ok(Form::Program),
+        Some("script") => Ok(Form::Script),
+        Some(other) => Err(MbaseError::invalid_input(format!("option 'form' expects bytes, raw, program or script, got '{}'", other))),
+    }
+}
+
+fn decode_raw(input: &str, mode: Mode, is_m: bool) -> Result<Vec<u8>> {
+    let cleaned = util::clean_for_mode(input, mode).to_lowercase();
+    let checked = if is_m {
+        CheckedHrpstring::new::<Bech32mVariant>(&cleaned)
+    } else {
+        CheckedHrpstring::new::<Bech32Variant>(&cleaned)
+    }
+    .map_err(|_| MbaseError::ChecksumMismatch)?;
+    Ok(checked
+        .data_part_ascii_no_checksum()
+        .iter()
+        .map(|&c| Fe32::from_char_unchecked(c).to_u8())
+        .collect())
+}
+
+/// Decodes a segwit address under the BIP-173/350 rules: witness version 0-16, bech32 for
+/// version 0 and bech32m above, a 2-40 byte program (20 or 32 for version 0), zero padding.
+fn decode_segwit(input: &str, mode: Mode, is_m: bool, form: Form) -> Result<Vec<u8>> {
+    let cleaned = util::clean_for_mode(input, mode).to_lowercase();
+    let segwit = SegwitHrpstring::new(&cleaned).map_err(|e| match e {
+        SegwitHrpstringError::Checksum(_) => MbaseError::ChecksumMismatch,
+        other => MbaseError::invalid_input(format!("not a segwit address: {}", other)),
+    })?;
+    let version = segwit.witness_version().to_u8();
+    if (version > 0) != is_m {
+        let codec = if version == 0 { "bech32" } else { "bech32m" };
+        return Err(MbaseError::invalid_input(format!(
+            "witness version {} addresses use the {} checksum; decode with --codec {}",
+            version, codec, codec
+        )));
+    }
+    let program: Vec<u8> = segwit.byte_iter().collect();
+    if form == Form::Program {
+        return Ok(program);
+    }
+    let opcode = if version == 0 { 0x00 } else { 0x50 + version };
+    let mut script = Vec::with_capacity(program.len() + 2);
+    script.push(opcode);
+    script.push(program.len() as u8);
+    script.extend_from_slice(&program);
+    Ok(script)
+}
+
+fn decode_form(input: &str, mode: Mode, is_m: bool, form: Form) -> Result<Vec<u8>> {
+    match form {
+        Form::Bytes => decode_bech32_strict(input, mode, is_m),
+        Form::Raw => decode_raw(input, mode, is_m),
+        Form::Program | Form::Script => decode_segwit(input, mode, is_m, form),
+    }
+}
+
 fn encode_bech32<V: bech32::Checksum>(hrp_str: &str, data: &[u8]) -> Result<String> {
     let hrp = Hrp::parse(hrp_str).map_err(|e| MbaseError::invalid_input(format!("invalid HRP: {}", e)))?;
     bech32::encode::<V>(hrp, data).map_err(|e| MbaseError::invalid_input(format!("encoding failed: {}", e)))
@@ -60,8 +137,8 @@ fn detect_bech32(input: &str, codec_name: &str, is_m: bool) -> DetectCandidate {
         };
     }
 
-    if let Some(sep_pos) = input.to_lowercase().rfind('1') {
-        if sep_pos > 0 && sep_pos < input.len() - 7 {
+    if let Some(sep_pos) = input.rfind('1') {
+        if sep_pos > 0 && sep_pos + 7 < input.len() {
             confidence = util::confidence::PARTIAL_MATCH;
             reasons.push("contains bech32 separator '1'".to_string());
 
@@ -115,6 +192,10 @@ impl Codec for Bech32Codec {
         decode_bech32_strict(input, mode, false)
     }
 
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_form(input, mode, false, form(self.name(), opts)?)
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         detect_bech32(input, "bech32", false)
     }
@@ -143,6 +224,10 @@ impl Codec for Bech32mCodec {
         decode_bech32_strict(input, mode, true)
     }
 
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        decode_form(input, mode, true, form(self.name(), opts)?)
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         detect_bech32(input, "bech32m", true)
     }
@@ -253,6 +338,49 @@ mod tests {
         assert!(candidate.confidence >= 0.9);
     }
 
+    #[test]
+    fn test_bech32_detect_short_non_ascii() {
+        assert!(Bech32Codec.detect_score("é1").confidence < 0.5);
+        assert!(Bech32Codec.detect_score("ééé1qqq").confidence < 0.5);
+    }
+
+    #[test]
+    fn test_bech32_forms() {
+        let form = |f: &str| CodecOptions::new().with("form", f);
+        // BIP-173 and BIP-350 test vectors.
+        let v0 = "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4";
+        let program = Bech32Codec.decode_with(v0, Mode::Strict, &form("program")).unwrap();
+        assert_eq!(hex(&program), "751e76e8199196d454941c45d1b3a323f1433bd6");
+        let script = Bech32Codec.decode_with(v0, Mode::Strict, &form("script")).unwrap();
+        assert_eq!(hex(&script), "0014751e76e8199196d454941c45d1b3a323f1433bd6");
+        let raw = Bech32Codec.decode_with(v0, Mode::Strict, &form("raw")).unwrap();
+        assert_eq!((raw.len(), raw[0], raw[1]), (33, 0, 14));
+        // 33 characters are 165 bits, not a whole number of bytes.
+        assert!(Bech32Codec.decode_with(v0, Mode::Strict, &form("bytes")).is_err());
+
+        let v1 = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+        let script = Bech32mCodec.decode_with(v1, Mode::Strict, &form("script")).unwrap();
+        assert_eq!(hex(&script), "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let err = Bech32Codec.decode_with(v1, Mode::Strict, &form("program")).unwrap_err();
+        assert!(err.to_string().contains("--codec bech32m"), "{}", err);
+        let err = Bech32mCodec.decode_with(v0, Mode::Strict, &form("program")).unwrap_err();
+        assert!(err.to_string().contains("--codec bech32"), "{}", err);
+
+        // Version 0 with a 16-byte program and a version 1 address with a bech32 checksum.
+        assert!(Bech32Codec
+            .decode_with("bc1qr508d6qejxtdg4y5r3zarvaryvq37ewvp", Mode::Strict, &form("program"))
+            .is_err());
+        assert!(Bech32Codec
+            .decode_with("bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4", Mode::Strict, &form("program"))
+            .is_err());
+        let data = Bech32Codec.encode(b"Hello").unwrap();
+        assert!(Bech32Codec.decode_with(&data, Mode::Strict, &form("witness")).is_err());
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
     #[test]
     fn test_bech32m_detect() {
         let encoded = Bech32mCodec.encode(b"Test").unwrap();
@@ -0,0 +1,350 @@
+use alloc::collections::BTreeMap;
+use once_cell::race::OnceBox;
+use sha2::{Digest, Sha256};
+
+use super::util;
+use super::Codec;
+use crate::error::{LengthConstraint, MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// One of the 2048-word BIP39 lists, split and indexed on first use.
+pub(crate) struct Wordlist {
+    pub name: &'static str,
+    codes: &'static [&'static str],
+    separator: &'static str,
+    text: &'static str,
+    words: OnceBox<Vec<&'static str>>,
+    folded: OnceBox<BTreeMap<String, u16>>,
+}
+
+impl Wordlist {
+    const fn new(name: &'static str, codes: &'static [&'static str], separator: &'static str, text: &'static str) -> Self {
+        Self {
+            name,
+            codes,
+            separator,
+            text,
+            words: OnceBox::new(),
+            folded: OnceBox::new(),
+        }
+    }
+
+    pub fn words(&self) -> &[&'static str] {
+        self.words.get_or_init(|| Box::new(self.text.lines().collect()))
+    }
+
+    /// Index of `word`: an exact (NFC or NFD) match in strict mode; in lenient mode case and
+    /// Latin accents are ignored, which the lists are designed to survive.
+    pub fn index(&self, word: &str, mode: Mode) -> Option<u16> {
+        let folded = self
+            .folded
+            .get_or_init(|| Box::new(self.words().iter().enumerate().map(|(i, w)| (fold(w), i as u16)).collect()));
+        let index = *folded.get(&fold(word))?;
+        (mode == Mode::Lenient || self.words()[index as usize] == compose(word)).then_some(index)
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        let name = name.to_lowercase().replace('_', "-");
+        self.name.replace('_', "-") == name || self.codes.contains(&name.as_str())
+    }
+}
+
+/// The lists in the order BIP39 publishes them; decoding tries them in this order.
+pub(crate) static WORDLISTS: [Wordlist; 10] = [
+    Wordlist::new("english", &["en"], " ", include_str!("wordlists/bip39/english.txt")),
+    Wordlist::new("japanese", &["ja", "jp"], "\u{3000}", include_str!("wordlists/bip39/japanese.txt")),
+    Wordlist::new("korean", &["ko"], " ", include_str!("wordlists/bip39/korean.txt")),
+    Wordlist::new("spanish", &["es"], " ", include_str!("wordlists/bip39/spanish.txt")),
+    Wordlist::new("chinese_simplified", &["zh", "zh-hans", "zh-cn"], " ", include_str!("wordlists/bip39/chinese_simplified.txt")),
+    Wordlist::new("chinese_traditional", &["zh-hant", "zh-tw"], " ", include_str!("wordlists/bip39/chinese_traditional.txt")),
+    Wordlist::new("french", &["fr"], " ", include_str!("wordlists/bip39/french.txt")),
+    Wordlist::new("italian", &["it"], " ", include_str!("wordlists/bip39/italian.txt")),
+    Wordlist::new("czech", &["cs", "cz"], " ", include_str!("wordlists/bip39/czech.txt")),
+    Wordlist::new("portuguese", &["pt"], " ", include_str!("wordlists/bip39/portuguese.txt")),
+];
+
+pub(crate) fn wordlist(name: &str) -> Result<&'static Wordlist> {
+    WORDLISTS.iter().find(|list| list.matches(name)).ok_or_else(|| {
+        let names: Vec<&str> = WORDLISTS.iter().map(|list| list.name).collect();
+        MbaseError::invalid_input(format!("unknown BIP39 language '{}': expected one of {}", name, names.join(", ")))
+    })
+}
+
+/// Composes the decomposed forms the lists can arrive in (kana voicing marks, Hangul jamo and
+/// the few accented Latin letters BIP39 uses), so NFD/NFKD input matches the NFC lists.
+fn compose(word: &str) -> String {
+    let mut out: Vec<char> = Vec::with_capacity(word.len());
+    for c in word.chars() {
+        let composed = match (out.last().copied(), c) {
+            (Some(kana @ '\u{3041}'..='\u{30FA}'), '\u{3099}') => char::from_u32(kana as u32 + 1),
+            (Some(kana @ '\u{3041}'..='\u{30FA}'), '\u{309A}') => char::from_u32(kana as u32 + 2),
+            (Some(l @ '\u{1100}'..='\u{1112}'), '\u{1161}'..='\u{1175}') => {
+                char::from_u32(0xAC00 + ((l as u32 - 0x1100) * 21 + (c as u32 - 0x1161)) * 28)
+            }
+            (Some(lv @ '\u{AC00}'..='\u{D7A3}'), '\u{11A8}'..='\u{11C2}') if (lv as u32 - 0xAC00).is_multiple_of(28) => {
+                char::from_u32(lv as u32 + (c as u32 - 0x11A7))
+            }
+            (Some(base), '\u{0300}'..='\u{0303}') => LATIN.iter().find(|&&(b, m, _)| b == base && m == c).map(|&(_, _, p)| p),
+            _ => None,
+        };
+        match composed {
+            Some(p) => *out.last_mut().unwrap() = p,
+            None => out.push(c),
+        }
+    }
+    out.into_iter().collect()
+}
+
+const LATIN: &[(char, char, char)] = &[
+    ('a', '\u{301}', 'á'),
+    ('e', '\u{301}', 'é'),
+    ('i', '\u{301}', 'í'),
+    ('o', '\u{301}', 'ó'),
+    ('u', '\u{301}', 'ú'),
+    ('e', '\u{300}', 'è'),
+    ('n', '\u{303}', 'ñ'),
+];
+
+fn fold(word: &str) -> String {
+    compose(&word.to_lowercase())
+        .chars()
+        .map(|c| LATIN.iter().find(|&&(_, _, p)| p == c).map_or(c, |&(b, _, _)| b))
+        .collect()
+}
+
+/// Entropy sizes BIP39 defines: 128 to 256 bits in steps of 32.
+fn check_entropy_len(len: usize) -> Result<()> {
+    if !(16..=32).contains(&len) || !len.is_multiple_of(4) {
+        return Err(MbaseError::invalid_length_msg(
+            LengthConstraint::MultipleOf(4),
+            len,
+            format!("BIP39 entropy must be 16, 20, 24, 28 or 32 bytes, got {}", len),
+        ));
+    }
+    Ok(())
+}
+
+/// Word indices for `entropy` followed by its checksum (the first `len / 4` bits of its SHA-256).
+pub(crate) fn entropy_to_indices(entropy: &[u8]) -> Result<Vec<u16>> {
+    check_entropy_len(entropy.len())?;
+    let mut bits = entropy.to_vec();
+    bits.push(Sha256::digest(entropy)[0]);
+    let count = entropy.len() * 3 / 4;
+    Ok((0..count)
+        .map(|word| {
+            (0..11).fold(0u16, |acc, bit| {
+                let i = word * 11 + bit;
+                (acc << 1) | u16::from(bits[i / 8] >> (7 - i % 8) & 1)
+            })
+        })
+        .collect())
+}
+
+/// Inverse of [`entropy_to_indices`], verifying the checksum.
+pub(crate) fn indices_to_entropy(indices: &[u16]) -> Result<Vec<u8>> {
+    if !matches!(indices.len(), 12 | 15 | 18 | 21 | 24) {
+        return Err(MbaseError::invalid_length_msg(
+            LengthConstraint::MultipleOf(3),
+            indices.len(),
+            format!("a BIP39 mnemonic has 12, 15, 18, 21 or 24 words, got {}", indices.len()),
+        ));
+    }
+    let mut bits = vec![0u8; indices.len() * 11 / 8 + 1];
+    for (word, &index) in indices.iter().enumerate() {
+        for bit in 0..11 {
+            let i = word * 11 + bit;
+            bits[i / 8] |= ((index >> (10 - bit)) as u8 & 1) << (7 - i % 8);
+        }
+    }
+    let len = indices.len() * 4 / 3;
+    let checksum_bits = len / 4;
+    let expected = Sha256::digest(&bits[..len])[0] >> (8 - checksum_bits);
+    if bits[len] >> (8 - checksum_bits) != expected {
+        return Err(MbaseError::ChecksumMismatch);
+    }
+    bits.truncate(len);
+    Ok(bits)
+}
+
+/// BIP39 mnemonic sentences in any of the ten published languages.
+///
+/// Encodes 16-32 bytes of entropy (`--opt lang=japanese` etc., English by default). Decoding
+/// takes the first language whose list holds every word and whose checksum verifies, unless
+/// `lang` pins one.
+pub struct Bip39;
+
+impl Bip39 {
+    fn language(opts: &CodecOptions) -> Result<Option<&'static Wordlist>> {
+        opts.ensure_known("bip39", &["lang"])?;
+        opts.get("lang").map(wordlist).transpose()
+    }
+
+    /// Entropy and the list it decoded against.
+    fn decode_words(input: &str, mode: Mode, only: Option<&'static Wordlist>) -> Result<(Vec<u8>, &'static Wordlist)> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let lists: Vec<&'static Wordlist> = match only {
+            Some(list) => vec![list],
+            None => WORDLISTS.iter().collect(),
+        };
+
+        let mut best: Option<(usize, &'static Wordlist)> = None;
+        let mut checksum_failed = false;
+        for list in lists {
+            let indices: Vec<Option<u16>> = words.iter().map(|w| list.index(w, mode)).collect();
+            let found = indices.iter().take_while(|i| i.is_some()).count();
+            if found == words.len() {
+                let indices: Vec<u16> = indices.into_iter().flatten().collect();
+                match indices_to_entropy(&indices) {
+                    Ok(entropy) => return Ok((entropy, list)),
+                    Err(MbaseError::ChecksumMismatch) => checksum_failed = true,
+                    Err(e) => return Err(e),
+                }
+            } else if best.is_none_or(|(most, _)| found > most) {
+                best = Some((found, list));
+            }
+        }
+        if checksum_failed || words.is_empty() {
+            return Err(if words.is_empty() {
+                MbaseError::invalid_input("empty BIP39 mnemonic")
+            } else {
+                MbaseError::ChecksumMismatch
+            });
+        }
+
+        let (found, list) = best.expect("at least one wordlist tried");
+        Err(MbaseError::invalid_input(format!("word {} ('{}') is not in the {} BIP39 wordlist", found + 1, words[found], list.name)))
+    }
+}
+
+impl Codec for Bip39 {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "bip39",
+            aliases: &["mnemonic"],
+            alphabet: "",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Lower,
+            description: "BIP39 mnemonic (16-32 byte entropy, 10 languages, SHA-256 checksum)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let list = Self::language(opts)?.unwrap_or(&WORDLISTS[0]);
+        let words = list.words();
+        let indices = entropy_to_indices(input)?;
+        Ok(indices.iter().map(|&i| words[i as usize]).collect::<Vec<_>>().join(list.separator))
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        Self::decode_words(input, mode, Self::language(opts)?).map(|(entropy, _)| entropy)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let count = input.split_whitespace().count();
+        if !matches!(count, 12 | 15 | 18 | 21 | 24) {
+            return util::confidence::no_match(self.name());
+        }
+        match Self::decode_words(input, Mode::Lenient, None) {
+            Ok((_, list)) => {
+                util::confidence::with_confidence(self.name(), 0.9, format!("{} words from the {} list, checksum valid", count, list.name))
+            }
+            Err(MbaseError::ChecksumMismatch) => {
+                let mut candidate =
+                    util::confidence::with_confidence(self.name(), util::confidence::PARTIAL_MATCH, format!("{} BIP39 words", count));
+                candidate.warnings.push("checksum mismatch".to_string());
+                candidate
+            }
+            Err(_) => util::confidence::no_match(self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZEROS: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn lang(name: &str) -> CodecOptions {
+        CodecOptions::new().with("lang", name)
+    }
+
+    #[test]
+    fn test_bip39_reference_vectors() {
+        assert_eq!(Bip39.encode(&[0; 16]).unwrap(), ZEROS);
+        assert_eq!(Bip39.decode(ZEROS, Mode::Strict).unwrap(), [0; 16]);
+        assert_eq!(Bip39.encode(&[0x7f; 16]).unwrap(), "legal winner thank year wave sausage worth useful legal winner thank yellow");
+        assert_eq!(Bip39.encode(&[0xff; 32]).unwrap().rsplit(' ').next(), Some("vote"));
+        assert_eq!(
+            Bip39.encode_with(&[0; 16], &lang("ja")).unwrap(),
+            "あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}\
+             あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}あいこくしん\u{3000}あおぞら"
+        );
+    }
+
+    #[test]
+    fn test_bip39_languages_roundtrip_and_autodetect() {
+        let entropy: Vec<u8> = (0..24u8).map(|i| i.wrapping_mul(73) ^ 0x5a).collect();
+        for list in &WORDLISTS {
+            assert_eq!(list.words().len(), 2048, "{}", list.name);
+            let mnemonic = Bip39.encode_with(&entropy, &lang(list.name)).unwrap();
+            let (decoded, detected) = Bip39::decode_words(&mnemonic, Mode::Strict, None).unwrap();
+            assert_eq!(decoded, entropy, "{}", list.name);
+            // Chinese lists share characters, so either may claim a mnemonic first.
+            if !list.name.starts_with("chinese") {
+                assert_eq!(detected.name, list.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bip39_lenient_folding() {
+        for list in &WORDLISTS {
+            let mut keys: Vec<String> = list.words().iter().map(|w| fold(w)).collect();
+            keys.sort();
+            keys.dedup();
+            assert_eq!(keys.len(), 2048, "{} has words that differ only by accent", list.name);
+        }
+
+        let spanish = Bip39.encode_with(&[0x11; 16], &lang("es")).unwrap();
+        let plain: String = spanish
+            .chars()
+            .map(|c| LATIN.iter().find(|l| l.2 == c).map_or(c, |l| l.0))
+            .collect();
+        assert!(Bip39.decode_with(&plain.to_uppercase(), Mode::Lenient, &lang("es")).is_ok());
+        assert!(Bip39.decode_with(&plain.to_uppercase(), Mode::Strict, &lang("es")).is_err());
+
+        // NFD input: kana with a combining voicing mark, Hangul as jamo.
+        assert_eq!(compose("か\u{3099}"), "が");
+        assert_eq!(compose("\u{1100}\u{1161}\u{11A8}"), "각");
+        assert_eq!(compose("e\u{301}le\u{300}ve"), "élève");
+    }
+
+    #[test]
+    fn test_bip39_errors() {
+        let bad = ZEROS.replace("about", "abandon");
+        assert!(matches!(Bip39.decode(&bad, Mode::Strict), Err(MbaseError::ChecksumMismatch)));
+        let err = Bip39.decode(&ZEROS.replace("about", "zzz"), Mode::Strict).unwrap_err();
+        assert!(err.to_string().contains("word 12 ('zzz')"), "{}", err);
+        assert!(Bip39.decode("abandon about", Mode::Strict).is_err());
+        assert!(Bip39.encode(&[0; 15]).is_err());
+        assert!(Bip39.encode_with(&[0; 16], &lang("klingon")).is_err());
+        assert!(Bip39.decode_with(ZEROS, Mode::Strict, &lang("french")).is_err());
+    }
+
+    #[test]
+    fn test_bip39_detect() {
+        assert!(Bip39.detect_score(ZEROS).confidence >= 0.9);
+        assert!(Bip39.detect_score("hello world").confidence == 0.0);
+    }
+}
@@ -0,0 +1,259 @@
+use super::braille::{BRAILLE_BASE, BRAILLE_DOTS};
+use super::{util, Codec};
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+// Quadrant block elements indexed by a nibble: bit 3 = top-left, 2 = top-right,
+// 1 = bottom-left, 0 = bottom-right.
+const QUADRANTS: [char; 16] = [' ', '▗', '▖', '▄', '▝', '▐', '▞', '▟', '▘', '▚', '▌', '▙', '▀', '▜', '▛', '█'];
+
+/// Lays a byte out as a 2x4 raster: high nibble down the left column, low nibble
+/// down the right, most significant bit on top.
+fn byte_to_raster(byte: u8) -> u8 {
+    let mut dots = 0;
+    for (row, cells) in BRAILLE_DOTS.iter().enumerate() {
+        if byte & (0x80 >> row) != 0 {
+            dots |= cells[0];
+        }
+        if byte & (0x08 >> row) != 0 {
+            dots |= cells[1];
+        }
+    }
+    dots
+}
+
+fn raster_to_byte(dots: u8) -> u8 {
+    let mut byte = 0;
+    for (row, cells) in BRAILLE_DOTS.iter().enumerate() {
+        if dots & cells[0] != 0 {
+            byte |= 0x80 >> row;
+        }
+        if dots & cells[1] != 0 {
+            byte |= 0x08 >> row;
+        }
+    }
+    byte
+}
+
+fn wrap(symbols: Vec<String>, per_line: usize) -> String {
+    if per_line == 0 {
+        return symbols.concat();
+    }
+    symbols.chunks(per_line).map(|line| line.concat()).collect::<Vec<_>>().join("\n")
+}
+
+fn width_option(opts: &CodecOptions, codec: &str) -> Result<usize> {
+    opts.ensure_known(codec, &["width"])?;
+    Ok(opts.get_usize("width")?.unwrap_or(0))
+}
+
+// Line breaks are layout only; lenient mode also drops any other whitespace except
+// the space character, which is a valid blank quadrant.
+fn is_layout(c: char, mode: Mode) -> bool {
+    c == '\n' || c == '\r' || (mode == Mode::Lenient && c.is_whitespace() && c != ' ')
+}
+
+fn bitmap_candidate(codec: &str, input: &str, is_symbol: impl Fn(char) -> bool, confidence: f64) -> DetectCandidate {
+    let chars: Vec<char> = input.chars().filter(|c| *c != '\n' && *c != '\r').collect();
+    if chars.is_empty() {
+        return util::confidence::no_match(codec);
+    }
+
+    let matching = chars.iter().filter(|&&c| is_symbol(c)).count();
+    if matching == chars.len() && chars.iter().any(|c| !c.is_whitespace()) {
+        util::confidence::with_confidence(codec, confidence, "all characters are bitmap cells".to_string())
+    } else {
+        util::confidence::no_match(codec)
+    }
+}
+
+pub struct BrailleRaster;
+
+impl Codec for BrailleRaster {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "brailleraster",
+            aliases: &["braille-raster", "brailledots"],
+            alphabet: "\u{2800}-\u{28FF}",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Bytes as 2x4 Braille dot rasters (option width=N cells per line)",
+        }
+    }
+
+    fn is_symbol(&self, c: char) -> bool {
+        ('\u{2800}'..='\u{28FF}').contains(&c)
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let width = width_option(opts, "brailleraster")?;
+        let cells = input
+            .iter()
+            .map(|&b| char::from_u32(BRAILLE_BASE + byte_to_raster(b) as u32).unwrap().to_string())
+            .collect();
+        Ok(wrap(cells, width))
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        width_option(opts, "brailleraster")?;
+        let mut result = Vec::with_capacity(input.len() / 3);
+        for (pos, ch) in input.chars().enumerate() {
+            if is_layout(ch, mode) {
+                continue;
+            }
+            let cp = ch as u32;
+            if !(BRAILLE_BASE..=BRAILLE_BASE + 0xFF).contains(&cp) {
+                return Err(MbaseError::invalid_char(ch, pos));
+            }
+            result.push(raster_to_byte((cp - BRAILLE_BASE) as u8));
+        }
+        Ok(result)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        bitmap_candidate(
+            "brailleraster",
+            input,
+            |c| (BRAILLE_BASE..=BRAILLE_BASE + 0xFF).contains(&(c as u32)),
+            util::confidence::PARTIAL_MATCH,
+        )
+    }
+}
+
+pub struct Blocks;
+
+impl Codec for Blocks {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "blocks",
+            aliases: &["quadrants", "blockbitmap"],
+            alphabet: " ▗▖▄▝▐▞▟▘▚▌▙▀▜▛█",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Bytes as quadrant block-element bitmaps, 2 cells per byte (option width=N)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let width = width_option(opts, "blocks")?;
+        let cells = input
+            .iter()
+            .map(|&b| [QUADRANTS[(b >> 4) as usize], QUADRANTS[(b & 0x0F) as usize]].iter().collect())
+            .collect();
+        Ok(wrap(cells, width))
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        width_option(opts, "blocks")?;
+        let mut nibbles = Vec::with_capacity(input.len() / 3);
+        let mut last = 0;
+        for (pos, ch) in input.chars().enumerate() {
+            if is_layout(ch, mode) {
+                continue;
+            }
+            let nibble = QUADRANTS.iter().position(|&q| q == ch).ok_or(MbaseError::invalid_char(ch, pos))?;
+            nibbles.push(nibble as u8);
+            last = pos;
+        }
+
+        if nibbles.len() % 2 != 0 {
+            return Err(MbaseError::invalid_input_at(
+                last,
+                format!("odd number of cells ({}); this one has no partner to complete its byte", nibbles.len()),
+            ));
+        }
+
+        Ok(nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        bitmap_candidate("blocks", input, |c| QUADRANTS.contains(&c), util::confidence::ALPHABET_MATCH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_braille_raster_layout() {
+        assert_eq!(BrailleRaster.encode(&[0x00]).unwrap(), "\u{2800}");
+        assert_eq!(BrailleRaster.encode(&[0xFF]).unwrap(), "\u{28FF}");
+        // High nibble fills the left column (dots 1,2,3,7), low nibble the right (4,5,6,8).
+        assert_eq!(BrailleRaster.encode(&[0xF0]).unwrap(), "\u{2847}");
+        assert_eq!(BrailleRaster.encode(&[0x0F]).unwrap(), "\u{28B8}");
+        assert_eq!(BrailleRaster.encode(&[0x88]).unwrap(), "\u{2809}");
+    }
+
+    #[test]
+    fn test_braille_raster_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let encoded = BrailleRaster.encode(&data).unwrap();
+        assert_eq!(encoded.chars().count(), 256);
+        assert_eq!(BrailleRaster.decode(&encoded, Mode::Strict).unwrap(), data);
+    }
+
+    #[test]
+    fn test_braille_raster_width() {
+        let opts = CodecOptions::new().with("width", "2");
+        let encoded = BrailleRaster.encode_with(&[1, 2, 3, 4, 5], &opts).unwrap();
+        assert_eq!(encoded.lines().count(), 3);
+        assert_eq!(BrailleRaster.decode(&encoded, Mode::Strict).unwrap(), &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_braille_raster_invalid_char() {
+        assert!(matches!(BrailleRaster.decode("\u{2800}a", Mode::Strict), Err(MbaseError::InvalidCharacter { char: 'a', position: 1 })));
+    }
+
+    #[test]
+    fn test_blocks_encode() {
+        assert_eq!(Blocks.encode(&[0x00]).unwrap(), "  ");
+        assert_eq!(Blocks.encode(&[0xFF]).unwrap(), "██");
+        assert_eq!(Blocks.encode(&[0xC3]).unwrap(), "▀▄");
+        assert_eq!(Blocks.encode(&[0xA5]).unwrap(), "▌▐");
+    }
+
+    #[test]
+    fn test_blocks_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let encoded = Blocks.encode_with(&data, &CodecOptions::new().with("width", "16")).unwrap();
+        assert_eq!(encoded.lines().count(), 16);
+        assert_eq!(Blocks.decode(&encoded, Mode::Strict).unwrap(), data);
+    }
+
+    #[test]
+    fn test_blocks_odd_cells() {
+        assert!(matches!(Blocks.decode("▀", Mode::Strict), Err(MbaseError::InvalidInputAt { position: 0, .. })));
+        assert!(matches!(Blocks.decode("▀▄\n▌", Mode::Strict), Err(MbaseError::InvalidInputAt { position: 3, .. })));
+        assert!(Blocks.decode("▀x", Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_bitmap_detect() {
+        assert!(Blocks.detect_score("▀▄▌▐").confidence >= 0.7);
+        assert!(Blocks.detect_score("▀ ▄").confidence >= 0.7);
+        assert_eq!(Blocks.detect_score("hello").confidence, 0.0);
+        assert_eq!(Blocks.detect_score("   ").confidence, 0.0);
+        assert!(BrailleRaster.detect_score("\u{28FF}\u{2801}").confidence > 0.0);
+    }
+}
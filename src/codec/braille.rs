@@ -1,10 +1,14 @@
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct Braille;
 
-const BRAILLE_BASE: u32 = 0x2800;
+pub(super) const BRAILLE_BASE: u32 = 0x2800;
+
+/// Unicode Braille dot bits for a 2x4 cell, listed row by row (left, right).
+pub(super) const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
 
 const BRAILLE_MAP: &[(char, u8)] = &[
     ('a', 0b00000001),
@@ -59,6 +63,10 @@ impl Codec for Braille {
         }
     }
 
+    fn is_symbol(&self, c: char) -> bool {
+        ('\u{2800}'..='\u{28FF}').contains(&c)
+    }
+
     fn encode(&self, input: &[u8]) -> Result<String> {
         let mut result = String::new();
 
@@ -86,7 +94,7 @@ impl Codec for Braille {
         for ch in input.chars() {
             let codepoint = ch as u32;
 
-            if codepoint < BRAILLE_BASE || codepoint > (BRAILLE_BASE + 0xFF) {
+            if !(BRAILLE_BASE..=(BRAILLE_BASE + 0xFF)).contains(&codepoint) {
                 return Err(Error::invalid_input(format!("character '{}' is not a Braille pattern", ch)));
             }
 
@@ -116,7 +124,7 @@ impl Codec for Braille {
             .chars()
             .filter(|&c| {
                 let cp = c as u32;
-                cp >= BRAILLE_BASE && cp <= (BRAILLE_BASE + 0xFF)
+                (BRAILLE_BASE..=(BRAILLE_BASE + 0xFF)).contains(&cp)
             })
             .count();
 
@@ -1,5 +1,6 @@
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct BubbleBabble;
@@ -89,43 +90,52 @@ impl Codec for BubbleBabble {
         if !cleaned_lower.starts_with('x') || !cleaned_lower.ends_with('x') {
             return Err(Error::invalid_input("Bubble Babble must start and end with 'x'"));
         }
+        if cleaned_lower.len() < 2 {
+            return Err(Error::invalid_input_at(1, "truncated: the closing 'x' is missing"));
+        }
 
         let core = &cleaned_lower[1..cleaned_lower.len() - 1];
         if core.is_empty() {
+            if mode == Mode::Strict {
+                return Err(Error::invalid_input_at(1, "missing trailing checksum tuple"));
+            }
             return Ok(Vec::new());
         }
 
         let tuples: Vec<&str> = core.split('-').collect();
         let mut result = Vec::new();
         let mut checksum = 1u32;
+        let mut offset = 1;
+        let strict = mode == Mode::Strict;
+
+        let invalid_char = |chars: &[char], i: usize, offset: usize| Error::InvalidCharacter {
+            char: chars[i],
+            position: offset + i,
+        };
+
+        // Recovers the two low bits hidden in a checksum-shifted vowel; values >= 4
+        // can only come from a corrupted tuple.
+        let unshift = |v: u8, shift: u32, offset: usize, idx: usize| -> Result<u32> {
+            let bits = (v as u32 + 36 - shift) % 6;
+            if strict && bits > 3 {
+                return Err(Error::checksum_mismatch_at(offset, format!("tuple {} does not match the running checksum", idx + 1)));
+            }
+            Ok(bits)
+        };
 
         for (idx, tuple) in tuples.iter().enumerate() {
             let chars: Vec<char> = tuple.chars().collect();
+            let is_last = idx + 1 == tuples.len();
 
             if chars.len() == 5 {
-                let v1 = vowel_index(chars[0]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[0],
-                    position: idx * 6,
-                })?;
-                let c1 = consonant_index(chars[1]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[1],
-                    position: idx * 6 + 1,
-                })?;
-                let v2 = vowel_index(chars[2]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[2],
-                    position: idx * 6 + 2,
-                })?;
-                let c2 = consonant_index(chars[3]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[3],
-                    position: idx * 6 + 3,
-                })?;
-                let c3 = consonant_index(chars[4]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[4],
-                    position: idx * 6 + 4,
-                })?;
-
-                let high_bits = ((v1 as u32 + 36 - checksum) % 6) << 6;
-                let byte1 = (high_bits | ((c1 as u32) << 2) | ((v2 as u32 + 36 - (checksum / 6)) % 6)) as u8;
+                let v1 = vowel_index(chars[0]).ok_or_else(|| invalid_char(&chars, 0, offset))?;
+                let c1 = consonant_index(chars[1]).ok_or_else(|| invalid_char(&chars, 1, offset))?;
+                let v2 = vowel_index(chars[2]).ok_or_else(|| invalid_char(&chars, 2, offset))?;
+                let c2 = consonant_index(chars[3]).ok_or_else(|| invalid_char(&chars, 3, offset))?;
+                let c3 = consonant_index(chars[4]).ok_or_else(|| invalid_char(&chars, 4, offset))?;
+
+                let high_bits = unshift(v1, checksum, offset, idx)? << 6;
+                let byte1 = (high_bits | ((c1 as u32) << 2) | unshift(v2, checksum / 6, offset, idx)?) as u8;
                 let byte2 = (((c2 as u32) << 4) | c3 as u32) as u8;
 
                 result.push(byte1);
@@ -133,28 +143,40 @@ impl Codec for BubbleBabble {
 
                 checksum = ((checksum * 5) + (byte1 as u32 * 7) + byte2 as u32) % 36;
             } else if chars.len() == 3 {
-                let v1 = vowel_index(chars[0]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[0],
-                    position: idx * 6,
-                })?;
-                let c1 = consonant_index(chars[1]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[1],
-                    position: idx * 6 + 1,
-                })?;
-                let v2 = vowel_index(chars[2]).ok_or_else(|| Error::InvalidCharacter {
-                    char: chars[2],
-                    position: idx * 6 + 2,
-                })?;
-
-                let high_bits = ((v1 as u32 + 36 - checksum) % 6) << 6;
-                let byte = (high_bits | ((c1 as u32) << 2) | ((v2 as u32 + 36 - (checksum / 6)) % 6)) as u8;
+                if strict && idx + 2 < tuples.len() {
+                    return Err(Error::invalid_input(format!("odd-byte tuple at position {} must be the last data tuple", offset)));
+                }
+                let v1 = vowel_index(chars[0]).ok_or_else(|| invalid_char(&chars, 0, offset))?;
+                let c1 = consonant_index(chars[1]).ok_or_else(|| invalid_char(&chars, 1, offset))?;
+                let v2 = vowel_index(chars[2]).ok_or_else(|| invalid_char(&chars, 2, offset))?;
+
+                let high_bits = unshift(v1, checksum, offset, idx)? << 6;
+                let byte = (high_bits | ((c1 as u32) << 2) | unshift(v2, checksum / 6, offset, idx)?) as u8;
 
                 result.push(byte);
             } else if chars.len() == 1 {
-                continue;
+                if !strict {
+                    continue;
+                }
+                if !is_last {
+                    return Err(Error::invalid_input(format!("checksum tuple at position {} must be the last tuple", offset)));
+                }
+                let v = vowel_index(chars[0]).ok_or_else(|| invalid_char(&chars, 0, offset))?;
+                if v as u32 != checksum % 6 {
+                    return Err(Error::checksum_mismatch_at(
+                        offset,
+                        format!("expected checksum vowel '{}', found '{}'", VOWELS[(checksum % 6) as usize] as char, chars[0]),
+                    ));
+                }
             } else {
                 return Err(Error::invalid_input(format!("invalid tuple length: {}", chars.len())));
             }
+
+            if strict && is_last && chars.len() != 1 {
+                return Err(Error::invalid_input("missing trailing checksum tuple"));
+            }
+
+            offset += chars.len() + 1;
         }
 
         Ok(result)
@@ -315,6 +337,62 @@ mod tests {
         assert!(codec.detect_score("xhello").confidence < 0.6);
     }
 
+    #[test]
+    fn test_bubblebabble_checksum_vowel_mismatch() {
+        let codec = BubbleBabble;
+        let encoded = codec.encode(b"test").unwrap();
+        let checksum_pos = encoded.len() - 2;
+        let expected = encoded.as_bytes()[checksum_pos] as char;
+        let wrong = VOWELS.iter().map(|&v| v as char).find(|&v| v != expected).unwrap();
+        let mut tampered = encoded.clone();
+        tampered.replace_range(checksum_pos..checksum_pos + 1, &wrong.to_string());
+
+        match codec.decode(&tampered, Mode::Strict) {
+            Err(Error::ChecksumMismatchAt { position, .. }) => assert_eq!(position, checksum_pos),
+            other => panic!("expected checksum mismatch, got {:?}", other),
+        }
+        assert_eq!(codec.decode(&tampered, Mode::Lenient).unwrap(), b"test");
+    }
+
+    #[test]
+    fn test_bubblebabble_corrupted_tuple() {
+        let codec = BubbleBabble;
+        let encoded = codec.encode(b"hello world").unwrap();
+        let tuples: Vec<&str> = encoded.split('-').collect();
+        // Find a vowel substitution in the second tuple that breaks the running checksum.
+        let second = 1 + tuples[0].len();
+        let mut found = false;
+        for &v in VOWELS {
+            let mut tampered = encoded.clone().into_bytes();
+            tampered[second] = v;
+            let tampered = String::from_utf8(tampered).unwrap();
+            if let Err(Error::ChecksumMismatchAt { position, .. }) = codec.decode(&tampered, Mode::Strict) {
+                assert!(position >= second);
+                found |= position == second;
+            }
+        }
+        assert!(found);
+    }
+
+    #[test]
+    fn test_bubblebabble_missing_checksum_tuple() {
+        let codec = BubbleBabble;
+        let encoded = codec.encode(b"test").unwrap();
+        let truncated = format!("{}x", &encoded[..encoded.len() - 3]);
+        assert!(codec.decode(&truncated, Mode::Strict).is_err());
+        assert_eq!(codec.decode(&truncated, Mode::Lenient).unwrap(), b"test");
+
+        assert!(matches!(codec.decode("xx", Mode::Strict), Err(Error::InvalidInputAt { position: 1, .. })));
+        assert_eq!(codec.decode("xx", Mode::Lenient).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_bubblebabble_lone_x() {
+        for mode in [Mode::Strict, Mode::Lenient] {
+            assert!(matches!(BubbleBabble.decode("x", mode), Err(Error::InvalidInputAt { position: 1, .. })));
+        }
+    }
+
     #[test]
     fn test_bubblebabble_single_byte() {
         let codec = BubbleBabble;
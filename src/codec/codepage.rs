@@ -0,0 +1,336 @@
+use super::{util, Codec};
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// IBM code page 037 (EBCDIC US/Canada), indexed by byte, with the C1 control mapping IBM publishes.
+const CP037: [char; 256] = [
+    '\u{00}', '\u{01}', '\u{02}', '\u{03}', '\u{9c}', '\u{09}', '\u{86}', '\u{7f}', '\u{97}', '\u{8d}', '\u{8e}', '\u{0b}', '\u{0c}',
+    '\u{0d}', '\u{0e}', '\u{0f}', '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9d}', '\u{85}', '\u{08}', '\u{87}', '\u{18}', '\u{19}',
+    '\u{92}', '\u{8f}', '\u{1c}', '\u{1d}', '\u{1e}', '\u{1f}', '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{0a}', '\u{17}',
+    '\u{1b}', '\u{88}', '\u{89}', '\u{8a}', '\u{8b}', '\u{8c}', '\u{05}', '\u{06}', '\u{07}', '\u{90}', '\u{91}', '\u{16}', '\u{93}',
+    '\u{94}', '\u{95}', '\u{96}', '\u{04}', '\u{98}', '\u{99}', '\u{9a}', '\u{9b}', '\u{14}', '\u{15}', '\u{9e}', '\u{1a}', ' ', '\u{a0}',
+    'â', 'ä', 'à', 'á', 'ã', 'å', 'ç', 'ñ', '¢', '.', '<', '(', '+', '|', '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', 'ì', 'ß', '!', '$', '*',
+    ')', ';', '¬', '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', 'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?', 'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï',
+    'Ì', '`', ':', '#', '@', '\'', '=', '"', 'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±', '°', 'j', 'k',
+    'l', 'm', 'n', 'o', 'p', 'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤', 'µ', '~', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '¡', '¿', 'Ð', 'Ý',
+    'Þ', '®', '^', '£', '¥', '·', '©', '§', '¶', '¼', '½', '¾', '[', ']', '¯', '¨', '´', '×', '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H',
+    'I', '\u{ad}', 'ô', 'ö', 'ò', 'ó', 'õ', '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ', '\\', '÷', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '³', 'Û', 'Ü', 'Ù',
+    'Ú', '\u{9f}',
+];
+
+/// Germany/Austria: umlauts take the national-use positions of `{ | } [ ] \ @ ~`.
+const CP273: [char; 256] = patch(
+    CP037,
+    &[
+        (0x43, '{'),
+        (0x4A, 'Ä'),
+        (0x4F, '!'),
+        (0x59, '~'),
+        (0x5A, 'Ü'),
+        (0x5F, '^'),
+        (0x63, '['),
+        (0x6A, 'ö'),
+        (0x7C, '§'),
+        (0xA1, 'ß'),
+        (0xB0, '¢'),
+        (0xB5, '@'),
+        (0xBA, '¬'),
+        (0xBB, '|'),
+        (0xBC, '‾'),
+        (0xC0, 'ä'),
+        (0xCC, '¦'),
+        (0xD0, 'ü'),
+        (0xDC, '}'),
+        (0xE0, 'Ö'),
+        (0xEC, '\\'),
+        (0xFC, ']'),
+    ],
+);
+
+/// International Latin-1, the common default of z/OS and IBM i outside the US.
+const CP500: [char; 256] = patch(
+    CP037,
+    &[
+        (0x4A, '['),
+        (0x4F, '!'),
+        (0x5A, ']'),
+        (0x5F, '^'),
+        (0xB0, '¢'),
+        (0xBA, '¬'),
+        (0xBB, '|'),
+    ],
+);
+
+/// CP037 with the euro sign in place of the currency sign.
+const CP1140: [char; 256] = patch(CP037, &[(0x9F, '€')]);
+
+/// The EBCDIC `SUB` control, written for characters a code page lacks in lenient mode.
+const EBCDIC_SUB: u8 = 0x3F;
+
+const fn patch(mut table: [char; 256], changes: &[(u8, char)]) -> [char; 256] {
+    let mut i = 0;
+    while i < changes.len() {
+        table[changes[i].0 as usize] = changes[i].1;
+        i += 1;
+    }
+    table
+}
+
+fn code_page(opts: &CodecOptions) -> Result<&'static [char; 256]> {
+    opts.ensure_known("ebcdic", &["cp"])?;
+    let Some(cp) = opts.get("cp") else {
+        return Ok(&CP037);
+    };
+    match cp.trim_start_matches("cp").trim_start_matches('0') {
+        "37" => Ok(&CP037),
+        "273" => Ok(&CP273),
+        "500" => Ok(&CP500),
+        "1140" => Ok(&CP1140),
+        _ => Err(MbaseError::invalid_input(format!("unknown EBCDIC code page '{}' (expected 037, 273, 500 or 1140)", cp))),
+    }
+}
+
+/// EBCDIC bytes to text and back, for mainframe dumps. Every byte has a character, so encoding
+/// cannot fail; decoding rejects characters the code page lacks, or writes `SUB` in lenient mode.
+pub struct Ebcdic;
+
+impl Codec for Ebcdic {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "ebcdic",
+            aliases: &["ibm037"],
+            alphabet: "",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "EBCDIC transliteration (option cp=037|273|500|1140)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let table = code_page(opts)?;
+        Ok(input.iter().map(|&b| table[b as usize]).collect())
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let table = code_page(opts)?;
+        input
+            .chars()
+            .enumerate()
+            .map(|(position, c)| match (table.iter().position(|&t| t == c), mode) {
+                (Some(byte), _) => Ok(byte as u8),
+                (None, Mode::Lenient) => Ok(EBCDIC_SUB),
+                (None, Mode::Strict) => Err(MbaseError::InvalidCharacter { char: c, position }),
+            })
+            .collect()
+    }
+
+    fn detect_score(&self, _input: &str) -> DetectCandidate {
+        // Any text transliterates; there is nothing to recognise.
+        util::confidence::no_match(self.name())
+    }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
+}
+
+/// Which of the two PETSCII character sets the machine was showing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Charset {
+    /// Power-on set: uppercase letters and graphics.
+    Upper,
+    /// Shifted set: lowercase at `$41-$5A`, uppercase at `$C1-$DA`.
+    Lower,
+}
+
+struct PetsciiOptions {
+    charset: Charset,
+    /// Write U+FFFD for graphics and control bytes instead of failing.
+    replace: bool,
+}
+
+impl PetsciiOptions {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("petscii", &["charset", "unmapped"])?;
+        let charset = match opts.get("charset") {
+            None | Some("upper") => Charset::Upper,
+            Some("lower") => Charset::Lower,
+            Some(other) => return Err(MbaseError::invalid_input(format!("unknown charset '{}' (expected upper or lower)", other))),
+        };
+        let replace = match opts.get("unmapped") {
+            None | Some("error") => false,
+            Some("replace") => true,
+            Some(other) => {
+                return Err(MbaseError::invalid_input(format!("unknown unmapped policy '{}' (expected error or replace)", other)))
+            }
+        };
+        Ok(Self { charset, replace })
+    }
+}
+
+fn petscii_char(byte: u8, charset: Charset) -> Option<char> {
+    match (byte, charset) {
+        (0x0D, _) => Some('\n'),
+        (0x20..=0x5B | 0x5D, Charset::Upper) | (0x20..=0x40 | 0x5B | 0x5D, Charset::Lower) => Some(byte as char),
+        (0x41..=0x5A, Charset::Lower) => Some(byte.to_ascii_lowercase() as char),
+        // `$61-$7A` repeat `$C1-$DA`; files written with either show up.
+        (0x61..=0x7A | 0xC1..=0xDA, Charset::Lower) => Some(((byte & 0x1F) | 0x40) as char),
+        (0x5C, _) => Some('£'),
+        (0x5E, _) => Some('↑'),
+        (0x5F, _) => Some('←'),
+        (0xA0, _) => Some('\u{a0}'),
+        (0xFF, Charset::Upper) => Some('π'),
+        _ => None,
+    }
+}
+
+fn petscii_byte(c: char, charset: Charset) -> Option<u8> {
+    match (c, charset) {
+        ('\n', _) => Some(0x0D),
+        (' '..='@' | '[' | ']', _) | ('A'..='Z', Charset::Upper) => Some(c as u8),
+        ('A'..='Z', Charset::Lower) => Some(c as u8 | 0x80),
+        ('a'..='z', Charset::Lower) => Some(c.to_ascii_uppercase() as u8),
+        ('£', _) => Some(0x5C),
+        ('↑', _) => Some(0x5E),
+        ('←', _) => Some(0x5F),
+        ('\u{a0}', _) => Some(0xA0),
+        ('π', Charset::Upper) => Some(0xFF),
+        _ => None,
+    }
+}
+
+/// Commodore 8-bit PETSCII (C64, VIC-20, PET, C128; the Amiga uses Latin-1) as text. Only the
+/// text characters and RETURN are mapped; graphics, colour and cursor codes are unmappable bytes.
+pub struct Petscii;
+
+impl Codec for Petscii {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "petscii",
+            aliases: &["c64"],
+            alphabet: "",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Commodore PETSCII transliteration (options charset=upper|lower, unmapped=error|replace)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let opts = PetsciiOptions::from_options(opts)?;
+        input
+            .iter()
+            .enumerate()
+            .map(|(position, &b)| match petscii_char(b, opts.charset) {
+                Some(c) => Ok(c),
+                None if opts.replace => Ok(char::REPLACEMENT_CHARACTER),
+                None => Err(MbaseError::invalid_input_at(
+                    position,
+                    format!("byte 0x{:02x} is a PETSCII graphic or control code (--opt unmapped=replace writes U+FFFD instead)", b),
+                )),
+            })
+            .collect()
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let opts = PetsciiOptions::from_options(opts)?;
+        let mut output = Vec::with_capacity(input.len());
+        let mut chars = input.chars().enumerate().peekable();
+        while let Some((position, c)) = chars.next() {
+            match (petscii_byte(c, opts.charset), mode) {
+                (Some(byte), _) => output.push(byte),
+                (None, Mode::Strict) => return Err(MbaseError::InvalidCharacter { char: c, position }),
+                // CRLF line endings carry a single RETURN.
+                (None, Mode::Lenient) if c == '\r' && chars.peek().is_some_and(|&(_, next)| next == '\n') => {}
+                (None, Mode::Lenient) => output.push(petscii_byte(c.to_ascii_uppercase(), opts.charset).unwrap_or(b'?')),
+            }
+        }
+        Ok(output)
+    }
+
+    fn detect_score(&self, _input: &str) -> DetectCandidate {
+        util::confidence::no_match(self.name())
+    }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ebcdic_code_pages() {
+        let hello = [0xC8, 0x85, 0x93, 0x93, 0x96, 0x5A];
+        assert_eq!(Ebcdic.encode(&hello).unwrap(), "Hello!");
+        assert_eq!(Ebcdic.decode("Hello!", Mode::Strict).unwrap(), hello);
+        let cp500 = CodecOptions::new().with("cp", "500");
+        assert_eq!(Ebcdic.encode_with(&hello, &cp500).unwrap(), "Hello]");
+        assert_eq!(Ebcdic.encode_with(&[0x9F], &CodecOptions::new().with("cp", "1140")).unwrap(), "€");
+        assert_eq!(Ebcdic.encode_with(&[0x4A, 0xC0], &CodecOptions::new().with("cp", "cp273")).unwrap(), "Ää");
+        assert!(Ebcdic.encode_with(&hello, &CodecOptions::new().with("cp", "1047")).is_err());
+
+        for table in [&CP037, &CP273, &CP500, &CP1140] {
+            let mut chars = table.to_vec();
+            chars.sort_unstable();
+            chars.dedup();
+            assert_eq!(chars.len(), 256);
+        }
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(Ebcdic.decode(&Ebcdic.encode(&all).unwrap(), Mode::Strict).unwrap(), all);
+        // Non-ASCII output, so the codec is one-shot only (see `crate::stream`).
+        assert_eq!(Ebcdic.encode("Aé".as_bytes()).unwrap(), "\u{a0}Cz");
+        assert!(!Ebcdic.is_streamable() && !Petscii.is_streamable());
+    }
+
+    #[test]
+    fn test_ebcdic_unmappable() {
+        assert!(matches!(Ebcdic.decode("a€b", Mode::Strict), Err(MbaseError::InvalidCharacter { char: '€', position: 1 })));
+        assert_eq!(Ebcdic.decode("a€b", Mode::Lenient).unwrap(), [0x81, EBCDIC_SUB, 0x82]);
+    }
+
+    #[test]
+    fn test_petscii_charsets() {
+        assert_eq!(Petscii.encode(b"HELLO, WORLD!\r").unwrap(), "HELLO, WORLD!\n");
+        assert_eq!(Petscii.encode(&[0x5C, 0x5E, 0x5F, 0xFF]).unwrap(), "£↑←π");
+        let lower = CodecOptions::new().with("charset", "lower");
+        assert_eq!(Petscii.encode_with(&[0xC8, 0x45, 0x4C, 0x4C, 0x4F], &lower).unwrap(), "Hello");
+        assert_eq!(Petscii.encode_with(&[0x68, 0x49], &lower).unwrap(), "Hi");
+        assert_eq!(Petscii.decode_with("Hi\n", Mode::Strict, &lower).unwrap(), [0xC8, 0x49, 0x0D]);
+        assert_eq!(Petscii.decode("READY.\n", Mode::Strict).unwrap(), b"READY.\r");
+    }
+
+    #[test]
+    fn test_petscii_unmappable() {
+        // CLR/HOME and a graphics character.
+        let err = Petscii.encode(&[0x41, 0x93, 0xC1]).unwrap_err().to_string();
+        assert!(err.contains("0x93") && err.contains("unmapped=replace"));
+        let replace = CodecOptions::new().with("unmapped", "replace");
+        assert_eq!(Petscii.encode_with(&[0x41, 0x93, 0xC1], &replace).unwrap(), "A\u{fffd}\u{fffd}");
+        assert!(Petscii.encode_with(b"A", &CodecOptions::new().with("unmapped", "skip")).is_err());
+
+        assert!(matches!(Petscii.decode("ready", Mode::Strict), Err(MbaseError::InvalidCharacter { char: 'r', position: 0 })));
+        assert_eq!(Petscii.decode("ready~\r\n", Mode::Lenient).unwrap(), b"READY?\r");
+    }
+}
@@ -0,0 +1,291 @@
+use super::util;
+use super::Codec;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// Words in a Diceware list: one per five-dice roll.
+const LIST_SIZE: usize = 7776;
+
+/// Big-endian bytes to base-7776 digits, one leading zero digit per leading zero byte.
+fn to_digits(input: &[u8]) -> Vec<u16> {
+    let mut digits = input.iter().fold(Vec::new(), |mut acc: Vec<u16>, &byte| {
+        let mut carry = byte as u32;
+        for digit in acc.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % LIST_SIZE as u32) as u16;
+            carry /= LIST_SIZE as u32;
+        }
+        while carry > 0 {
+            acc.push((carry % LIST_SIZE as u32) as u16);
+            carry /= LIST_SIZE as u32;
+        }
+        acc
+    });
+    let leading_zeros = input.iter().take_while(|&&b| b == 0).count();
+    digits.extend(core::iter::repeat_n(0, leading_zeros));
+    digits.reverse();
+    digits
+}
+
+fn from_digits(digits: &[u16]) -> Vec<u8> {
+    let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+    let mut bytes = digits.iter().fold(Vec::new(), |mut acc: Vec<u8>, &digit| {
+        let mut carry = digit as u32;
+        for byte in acc.iter_mut().rev() {
+            carry += (*byte as u32) * LIST_SIZE as u32;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            acc.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+        acc
+    });
+    let mut output = vec![0u8; leading_zeros];
+    output.append(&mut bytes);
+    output
+}
+
+fn roll(digit: u16) -> String {
+    (0..5).rev().map(|i| char::from(b'1' + (digit / 6u16.pow(i) % 6) as u8)).collect()
+}
+
+fn parse_roll(token: &str) -> Option<u16> {
+    (token.len() == 5 && token.bytes().all(|b| (b'1'..=b'6').contains(&b)))
+        .then(|| token.bytes().fold(0, |acc, b| acc * 6 + u16::from(b - b'1')))
+}
+
+/// A Diceware list: `11111<tab>word` lines (EFF, Reinhold) or 7776 bare words in roll order.
+/// Blank lines and `#` comments are skipped.
+fn parse_wordlist(path: &str, text: &str) -> Result<Vec<String>> {
+    let mut words = vec![String::new(); LIST_SIZE];
+    let mut count = 0;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (index, word) = match line.split_once(char::is_whitespace) {
+            Some((dice, word)) if parse_roll(dice).is_some() => (parse_roll(dice).unwrap() as usize, word.trim()),
+            _ => (count, line),
+        };
+        if count == LIST_SIZE || !words[index].is_empty() {
+            return Err(MbaseError::invalid_input(format!("{}:{}: more than one word for roll {}", path, number + 1, roll(index as u16))));
+        }
+        words[index] = word.to_string();
+        count += 1;
+    }
+    if count != LIST_SIZE {
+        return Err(MbaseError::invalid_input(format!("{}: {} words, a Diceware list needs {}", path, count, LIST_SIZE)));
+    }
+    Ok(words)
+}
+
+fn load_wordlist(path: &str) -> Result<Vec<String>> {
+    parse_wordlist(path, &util::read_wordlist(path)?)
+}
+
+/// Diceware: the input as a base-7776 number, one five-dice roll (`11111`-`66666`) per digit.
+///
+/// With `--opt wordlist=FILE` the rolls are looked up in a Diceware list and written as words.
+/// Several comma-separated lists may be given (one per language, say): encoding uses the first,
+/// decoding whichever holds every word.
+pub struct Diceware;
+
+struct DicewareOptions {
+    wordlists: Vec<Vec<String>>,
+    sep: Option<String>,
+}
+
+impl DicewareOptions {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("diceware", &["wordlist", "sep"])?;
+        let wordlists = match opts.get("wordlist") {
+            Some(paths) => paths.split(',').map(load_wordlist).collect::<Result<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self {
+            wordlists,
+            sep: opts.get("sep").map(String::from),
+        })
+    }
+}
+
+impl Diceware {
+    fn decode_rolls(tokens: &[&str], mode: Mode) -> Result<Vec<u16>> {
+        let mut digits = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            // Lenient input may run the rolls together: "1111166666".
+            let chunks: Vec<&str> = match mode {
+                Mode::Lenient if token.len().is_multiple_of(5) && token.is_ascii() => {
+                    (0..token.len()).step_by(5).map(|i| &token[i..i + 5]).collect()
+                }
+                _ => vec![token],
+            };
+            for chunk in chunks {
+                digits.push(
+                    parse_roll(chunk)
+                        .ok_or_else(|| MbaseError::invalid_input(format!("'{}' is not a five-dice roll (11111-66666)", chunk)))?,
+                );
+            }
+        }
+        Ok(digits)
+    }
+
+    fn decode_words(tokens: &[&str], mode: Mode, wordlists: &[Vec<String>]) -> Result<Vec<u16>> {
+        let find = |list: &Vec<String>, token: &str| {
+            list.iter().position(|w| match mode {
+                Mode::Strict => w == token,
+                Mode::Lenient => w.to_lowercase() == token.to_lowercase(),
+            })
+        };
+        let mut best = (0, "");
+        for list in wordlists {
+            let digits: Vec<Option<usize>> = tokens.iter().map(|t| find(list, t)).collect();
+            let found = digits.iter().take_while(|d| d.is_some()).count();
+            if found == tokens.len() {
+                return Ok(digits.into_iter().flatten().map(|d| d as u16).collect());
+            }
+            if found >= best.0 {
+                best = (found, tokens[found]);
+            }
+        }
+        Err(MbaseError::invalid_input(format!("word {} ('{}') is not in any given Diceware list", best.0 + 1, best.1)))
+    }
+}
+
+impl Codec for Diceware {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "diceware",
+            aliases: &["dice"],
+            alphabet: "123456-",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Diceware five-dice rolls, or words from a Diceware list (base 7776)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let opts = DicewareOptions::from_options(opts)?;
+        let digits = to_digits(input);
+        Ok(match opts.wordlists.first() {
+            Some(list) => {
+                let words: Vec<&str> = digits.iter().map(|&d| list[d as usize].as_str()).collect();
+                words.join(opts.sep.as_deref().unwrap_or(" "))
+            }
+            None => {
+                let rolls: Vec<String> = digits.into_iter().map(roll).collect();
+                rolls.join(opts.sep.as_deref().unwrap_or("-"))
+            }
+        })
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let opts = DicewareOptions::from_options(opts)?;
+        // Words may contain hyphens ("t-shirt" in the EFF list), so only rolls split on them.
+        let tokens: Vec<&str> = match (&opts.sep, opts.wordlists.is_empty()) {
+            (Some(sep), _) if !sep.is_empty() => input.split(sep.as_str()).flat_map(str::split_whitespace).collect(),
+            (_, true) => input
+                .split(|c: char| c == '-' || c.is_whitespace())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            (_, false) => input.split_whitespace().collect(),
+        };
+        let digits = match tokens.iter().all(|t| t.bytes().all(|b| b.is_ascii_digit())) {
+            true => Self::decode_rolls(&tokens, mode)?,
+            false if opts.wordlists.is_empty() => {
+                return Err(MbaseError::invalid_input("diceware words need --opt wordlist=FILE; without one only rolls decode"));
+            }
+            false => Self::decode_words(&tokens, mode, &opts.wordlists)?,
+        };
+        Ok(from_digits(&digits))
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let tokens: Vec<&str> = input
+            .split(|c: char| c == '-' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if tokens.is_empty() || !tokens.iter().all(|t| parse_roll(t).is_some()) {
+            return util::confidence::no_match(self.name());
+        }
+        let confidence = if tokens.len() >= 2 {
+            util::confidence::ALPHABET_MATCH
+        } else {
+            util::confidence::WEAK_MATCH
+        };
+        util::confidence::with_confidence(self.name(), confidence, format!("{} five-dice rolls", tokens.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_list() -> String {
+        (0..LIST_SIZE as u16).map(|d| format!("{}\tw{}\n", roll(d), d)).collect()
+    }
+
+    #[test]
+    fn test_diceware_rolls() {
+        assert_eq!(roll(0), "11111");
+        assert_eq!(roll(7775), "66666");
+        assert_eq!(Diceware.encode(b"").unwrap(), "");
+        assert_eq!(Diceware.encode(&[0, 1]).unwrap(), "11111-11112");
+        assert_eq!(Diceware.encode(&[0x1e, 0x5f]).unwrap(), "66666");
+        for input in [&b"hello diceware"[..], &[0, 0, 255], &[255; 9]] {
+            let encoded = Diceware.encode(input).unwrap();
+            assert_eq!(Diceware.decode(&encoded, Mode::Strict).unwrap(), input);
+        }
+        assert_eq!(Diceware.decode("1111111112", Mode::Lenient).unwrap(), [0, 1]);
+        assert!(Diceware.decode("1111111112", Mode::Strict).is_err());
+        assert!(Diceware.decode("11117", Mode::Strict).is_err());
+        assert!(Diceware.decode("apple", Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_diceware_wordlist_parsing() {
+        let list = parse_wordlist("eff.txt", &numbered_list()).unwrap();
+        assert_eq!(list[0], "w0");
+        assert_eq!(list[7775], "w7775");
+
+        let bare: String = (0..LIST_SIZE).map(|i| format!("word{}\n", i)).collect();
+        assert_eq!(parse_wordlist("bare.txt", &bare).unwrap()[42], "word42");
+        assert!(parse_wordlist("short.txt", "11111 a\n11112 b\n").is_err());
+        let duplicate = numbered_list().replacen("11112\tw1", "11111\tw1", 1);
+        assert!(parse_wordlist("dup.txt", &duplicate).is_err());
+    }
+
+    #[test]
+    fn test_diceware_words_pick_matching_list() {
+        let english = parse_wordlist("en", &numbered_list()).unwrap();
+        let other: Vec<String> = english.iter().map(|w| w.replace('w', "x")).collect();
+        let lists = [english, other];
+
+        let digits = to_digits(b"mbase");
+        let words: Vec<&str> = digits.iter().map(|&d| lists[1][d as usize].as_str()).collect();
+        let decoded = Diceware::decode_words(&words, Mode::Strict, &lists).unwrap();
+        assert_eq!(from_digits(&decoded), b"mbase");
+
+        let err = Diceware::decode_words(&["w1", "nope"], Mode::Strict, &lists).unwrap_err();
+        assert!(err.to_string().contains("word 2 ('nope')"), "{}", err);
+    }
+
+    #[test]
+    fn test_diceware_detect() {
+        assert!(Diceware.detect_score("16655-15143-64321").confidence >= 0.7);
+        assert_eq!(Diceware.detect_score("16655-15147").confidence, 0.0);
+    }
+}
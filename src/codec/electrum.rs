@@ -0,0 +1,233 @@
+use sha2::{Digest, Sha512};
+
+use super::bip39::{self, WORDLISTS};
+use super::util;
+use super::Codec;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// Seed types and the hex prefix of `HMAC-SHA512("Seed version", phrase)` that marks them.
+const VERSIONS: &[(&str, &str)] = &[("standard", "01"), ("segwit", "100"), ("2fa", "101"), ("2fa_segwit", "102")];
+
+fn hmac_sha512(key: &[u8], message: &[u8]) -> [u8; 64] {
+    let mut block = [0u8; 128];
+    block[..key.len()].copy_from_slice(key);
+    let pad = |byte: u8| block.iter().map(|b| b ^ byte).collect::<Vec<u8>>();
+    let inner = Sha512::new().chain_update(pad(0x36)).chain_update(message).finalize();
+    Sha512::new().chain_update(pad(0x5c)).chain_update(inner).finalize().into()
+}
+
+/// The seed type `phrase` (normalized: lowercase words, single spaces) carries, if any.
+fn seed_version(phrase: &str) -> Option<&'static str> {
+    let digest = hmac_sha512(b"Seed version", phrase.as_bytes());
+    let hex: String = digest[..2].iter().map(|b| format!("{:02x}", b)).collect();
+    VERSIONS.iter().find(|(_, prefix)| hex.starts_with(prefix)).map(|&(name, _)| name)
+}
+
+/// Base-2048 digits of a big-endian number, least significant first (Electrum's word order).
+fn to_indices(number: &[u8]) -> Vec<u16> {
+    number.iter().fold(Vec::new(), |mut acc: Vec<u16>, &byte| {
+        let mut carry = byte as u32;
+        for digit in acc.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 2048) as u16;
+            carry /= 2048;
+        }
+        while carry > 0 {
+            acc.push((carry % 2048) as u16);
+            carry /= 2048;
+        }
+        acc
+    })
+}
+
+fn from_indices(indices: &[u16]) -> Vec<u8> {
+    indices.iter().rev().fold(Vec::new(), |mut acc: Vec<u8>, &digit| {
+        let mut carry = digit as u32;
+        for byte in acc.iter_mut().rev() {
+            carry += (*byte as u32) * 2048;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            acc.insert(0, (carry & 0xff) as u8);
+            carry >>= 8;
+        }
+        acc
+    })
+}
+
+fn phrase(indices: &[u16]) -> String {
+    let words = WORDLISTS[0].words();
+    indices.iter().map(|&i| words[i as usize]).collect::<Vec<_>>().join(" ")
+}
+
+fn increment(number: &mut Vec<u8>) {
+    for byte in number.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            return;
+        }
+    }
+    number.insert(0, 1);
+}
+
+/// Electrum 2.0+ seed phrases: the seed is a number written in base 2048 with the BIP39
+/// English list, least significant word first, and is only valid when the HMAC-SHA512 of the
+/// phrase starts with a version prefix (`01` standard, `100` segwit, `101`/`102` 2FA).
+///
+/// Encoding arbitrary bytes rarely lands on a prefix, so `--opt version=segwit` (etc.) counts
+/// upward from the input to the first number that does, the way Electrum generates seeds; the
+/// decoded bytes are then that number, not the input.
+pub struct Electrum;
+
+impl Electrum {
+    fn version(opts: &CodecOptions) -> Result<Option<&'static str>> {
+        opts.ensure_known("electrum", &["version"])?;
+        let Some(name) = opts.get("version") else {
+            return Ok(None);
+        };
+        let name = name.to_lowercase().replace('-', "_");
+        VERSIONS.iter().find(|(v, _)| *v == name).map(|&(v, _)| Some(v)).ok_or_else(|| {
+            MbaseError::invalid_input(format!("unknown Electrum seed version '{}': expected standard, segwit, 2fa or 2fa_segwit", name))
+        })
+    }
+
+    /// The number and seed type of `input`.
+    fn decode_seed(input: &str, mode: Mode) -> Result<(Vec<u8>, &'static str)> {
+        let mut indices = Vec::new();
+        for (i, word) in input.split_whitespace().enumerate() {
+            let index = WORDLISTS[0].index(word, mode).ok_or_else(|| {
+                MbaseError::invalid_input(format!("word {} ('{}') is not in the Electrum (BIP39 English) wordlist", i + 1, word))
+            })?;
+            indices.push(index);
+        }
+        if indices.is_empty() {
+            return Err(MbaseError::invalid_input("empty Electrum seed"));
+        }
+        let version = seed_version(&phrase(&indices)).ok_or(MbaseError::ChecksumMismatch)?;
+        Ok((from_indices(&indices), version))
+    }
+}
+
+impl Codec for Electrum {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "electrum",
+            aliases: &["electrum-seed"],
+            alphabet: "abcdefghijklmnopqrstuvwxyz ",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Lower,
+            description: "Electrum 2.0+ seed phrase (base 2048, HMAC-SHA512 version prefix)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let wanted = Self::version(opts)?;
+        if input.first().is_none_or(|&b| b == 0) {
+            return Err(MbaseError::invalid_input("an Electrum seed is a number: input must be non-empty without leading zero bytes"));
+        }
+        let mut number = input.to_vec();
+        loop {
+            let indices = to_indices(&number);
+            let candidate = phrase(&indices);
+            let version = seed_version(&candidate);
+            match wanted {
+                None if version.is_some() => return Ok(candidate),
+                None => {
+                    return Err(MbaseError::invalid_input(
+                        "these bytes do not form an Electrum seed (no version prefix); use --opt version=segwit to search upward for one",
+                    ))
+                }
+                // Like Electrum, skip numbers whose phrase would also pass as BIP39.
+                Some(v) if version == Some(v) && bip39::indices_to_entropy(&indices).is_err() => return Ok(candidate),
+                Some(_) => increment(&mut number),
+            }
+        }
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let wanted = Self::version(opts)?;
+        let (number, version) = Self::decode_seed(input, mode)?;
+        match wanted {
+            Some(v) if v != version => Err(MbaseError::invalid_input(format!("this is a {} Electrum seed, not {}", version, v))),
+            _ => Ok(number),
+        }
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        if input.split_whitespace().count() < 2 {
+            return util::confidence::no_match(self.name());
+        }
+        match Self::decode_seed(input, Mode::Lenient) {
+            Ok((_, version)) => {
+                util::confidence::with_confidence(self.name(), 0.9, format!("{} seed (HMAC version prefix matches)", version))
+            }
+            Err(_) => util::confidence::no_match(self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From Electrum's test_mnemonic.py.
+    const SEGWIT: &str = "wild father tree among universe such mobile favorite target dynamic credit identify";
+    const TWO_FA: &str = "science dawn member doll dutch real can brick knife deny drive list";
+
+    fn version(name: &str) -> CodecOptions {
+        CodecOptions::new().with("version", name)
+    }
+
+    #[test]
+    fn test_hmac_sha512_rfc4231() {
+        let mac = hmac_sha512(&[0x0b; 20], b"Hi There");
+        assert_eq!(mac[..8], [0x87, 0xaa, 0x7c, 0xde, 0xa5, 0xef, 0x61, 0x9d]);
+    }
+
+    #[test]
+    fn test_electrum_reference_seeds() {
+        let number = Electrum.decode(SEGWIT, Mode::Strict).unwrap();
+        assert_eq!(number[0], 0x07);
+        assert_eq!(number.len(), 17);
+        assert_eq!(Electrum.encode(&number).unwrap(), SEGWIT);
+        assert_eq!(Electrum::decode_seed(SEGWIT, Mode::Strict).unwrap().1, "segwit");
+        assert_eq!(Electrum::decode_seed(TWO_FA, Mode::Strict).unwrap().1, "2fa");
+
+        assert!(Electrum.decode_with(SEGWIT, Mode::Strict, &version("segwit")).is_ok());
+        assert!(Electrum.decode_with(SEGWIT, Mode::Strict, &version("standard")).is_err());
+        assert!(Electrum.decode(&SEGWIT.to_uppercase(), Mode::Lenient).is_ok());
+        assert!(Electrum.detect_score(SEGWIT).confidence >= 0.9);
+    }
+
+    #[test]
+    fn test_electrum_version_search() {
+        let start = [0x5a; 17];
+        assert!(Electrum.encode(&start).is_err());
+        for name in ["standard", "segwit"] {
+            let seed = Electrum.encode_with(&start, &version(name)).unwrap();
+            assert_eq!(Electrum::decode_seed(&seed, Mode::Strict).unwrap().1, name);
+            assert!(Electrum.decode(&seed, Mode::Strict).unwrap() >= start.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_electrum_errors() {
+        let corrupted = SEGWIT.replace("wild", "zoo");
+        assert!(matches!(Electrum.decode(&corrupted, Mode::Strict), Err(MbaseError::ChecksumMismatch)));
+        assert!(Electrum.decode("wild fathr", Mode::Strict).is_err());
+        assert!(Electrum.encode(&[0, 1]).is_err());
+        assert!(Electrum.encode_with(&[1], &version("legacy")).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Post-processing for detection scores: negative evidence and pruning of near-duplicates.
+
+use super::util::confidence::MULTIBASE_MATCH;
+use super::{Codec, Registry};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, DetectCandidate, Mode, PaddingRule};
+
+/// What a feature the codec's encoder never produces says about a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evidence {
+    /// Strict decoding fails too: the codec cannot have produced the input.
+    RuledOut(&'static str),
+    /// The decoder tolerates it (e.g. Crockford's case folding), but the input is not canonical.
+    Penalized(&'static str),
+}
+
+/// A feature of `input` that `codec` never emits, if any.
+///
+/// Codecs that pass foreign characters through (the letter ciphers, percent-encoding) still
+/// decode strictly, so they are at most penalized, never ruled out.
+pub fn negative_evidence(codec: &dyn Codec, input: &str) -> Option<Evidence> {
+    let meta = codec.meta();
+    let reason = if input.ends_with('=') && meta.padding == PaddingRule::None && !meta.alphabet.contains('=') {
+        "'=' padding, which this codec never emits"
+    } else if meta.case_sensitivity == CaseSensitivity::Upper && input.chars().any(|c| c.is_ascii_lowercase()) {
+        "lowercase letters in an uppercase-only alphabet"
+    } else if meta.case_sensitivity == CaseSensitivity::Lower && input.chars().any(|c| c.is_ascii_uppercase()) {
+        "uppercase letters in a lowercase-only alphabet"
+    } else {
+        return None;
+    };
+    Some(match codec.decode(input, Mode::Strict) {
+        Ok(_) => Evidence::Penalized(reason),
+        Err(_) => Evidence::RuledOut(reason),
+    })
+}
+
+/// Drops candidates ruled out by [`negative_evidence`] and halves penalized ones, then folds
+/// candidates that decode `input` to the same bytes as a higher-ranked one into it (`base64`
+/// under `base64pad`, and so on).
+///
+/// `candidates` must already be sorted, most likely first. Multibase-prefix matches are kept as is.
+pub fn refine(registry: &Registry, input: &str, candidates: Vec<DetectCandidate>) -> Vec<DetectCandidate> {
+    let mut kept: Vec<(DetectCandidate, Option<Vec<u8>>, Vec<String>)> = Vec::new();
+
+    for candidate in candidates {
+        let Ok(codec) = registry.get(&candidate.codec) else {
+            kept.push((candidate, None, Vec::new()));
+            continue;
+        };
+        if candidate.confidence >= MULTIBASE_MATCH {
+            kept.push((candidate, None, Vec::new()));
+            continue;
+        }
+        let mut candidate = candidate;
+        match negative_evidence(codec, input) {
+            Some(Evidence::RuledOut(reason)) => {
+                tracing::trace!(codec = %candidate.codec, reason, "ruled out by negative evidence");
+                continue;
+            }
+            Some(Evidence::Penalized(reason)) => {
+                candidate.confidence *= 0.5;
+                candidate.warnings.push(format!("non-canonical: {}", reason));
+            }
+            None => {}
+        }
+
+        let decoded = codec.decode(input, Mode::Lenient).ok();
+        let twin = decoded
+            .as_ref()
+            .and_then(|bytes| kept.iter_mut().find(|(_, other, _)| other.as_ref() == Some(bytes)));
+        match twin {
+            Some((_, _, twins)) => twins.push(candidate.codec),
+            None => kept.push((candidate, decoded, Vec::new())),
+        }
+    }
+
+    let mut refined: Vec<DetectCandidate> = kept
+        .into_iter()
+        .map(|(mut candidate, _, twins)| {
+            if !twins.is_empty() {
+                candidate.reasons.push(format!("same bytes as {}", twins.join(", ")));
+            }
+            candidate
+        })
+        .collect();
+    refined.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    refined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(input: &str) -> Vec<String> {
+        Registry::global().detect(input).into_iter().map(|c| c.codec).collect()
+    }
+
+    #[test]
+    fn test_negative_evidence() {
+        let registry = Registry::global();
+        let evidence = |codec: &str, input: &str| negative_evidence(registry.get(codec).unwrap(), input);
+        assert!(matches!(evidence("base58btc", "SGVsbG8="), Some(Evidence::RuledOut(_))));
+        assert!(matches!(evidence("base64", "SGVsbG8="), Some(Evidence::RuledOut(_))));
+        assert_eq!(evidence("base64pad", "SGVsbG8="), None);
+        assert!(matches!(evidence("base32upper", "nbswy3dp"), Some(Evidence::RuledOut(_))));
+        assert!(matches!(evidence("crockford32", "d1jprv3f"), Some(Evidence::Penalized(_))));
+        assert_eq!(evidence("crockford32", "D1JPRV3F"), None);
+        assert!(matches!(evidence("rot13", "a=b="), Some(Evidence::Penalized(_))));
+    }
+
+    #[test]
+    fn test_refine_prunes_equivalent_variants() {
+        let found = names("SGVsbG8=");
+        assert_eq!(found[0], "base64pad");
+        assert!(!found.iter().any(|c| c == "base64" || c == "base64urlpad" || c == "base58btc"), "{:?}", found);
+
+        let best = &Registry::global().detect("SGVsbG8=")[0];
+        assert!(best.reasons.iter().any(|r| r.starts_with("same bytes as base64urlpad")), "{:?}", best.reasons);
+    }
+}
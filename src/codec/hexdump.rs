@@ -0,0 +1,335 @@
+use super::util;
+use super::Codec;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+const LINE_BYTES: usize = 16;
+/// Width of the hex column: two groups of eight `xx ` plus the space between them.
+const HEX_WIDTH: usize = LINE_BYTES * 3 + 1;
+
+fn ascii_column(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+        .collect()
+}
+
+fn format_line(out: &mut String, offset: usize, bytes: &[u8]) {
+    let mut hex = String::with_capacity(HEX_WIDTH);
+    for (i, b) in bytes.iter().enumerate() {
+        if i == LINE_BYTES / 2 {
+            hex.push(' ');
+        }
+        hex.push_str(&format!("{:02x} ", b));
+    }
+    out.push_str(&format!("{:08x}  {:<width$} |{}|\n", offset, hex, ascii_column(bytes), width = HEX_WIDTH));
+}
+
+/// What to do when an offset skips past the bytes dumped so far.
+#[derive(Clone, Copy, PartialEq)]
+enum Holes {
+    Zero,
+    Error,
+}
+
+struct HexdumpOptions {
+    squeeze: bool,
+    holes: Holes,
+}
+
+impl HexdumpOptions {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("hexdump", &["squeeze", "holes"])?;
+        let holes = match opts.get("holes") {
+            None | Some("zero") => Holes::Zero,
+            Some("error") => Holes::Error,
+            Some(other) => return Err(MbaseError::invalid_input(format!("option 'holes' must be zero or error, not '{}'", other))),
+        };
+        Ok(Self {
+            squeeze: opts.get_bool("squeeze")?.unwrap_or(true),
+            holes,
+        })
+    }
+}
+
+/// Canonical hex+ASCII dump, as printed by `hexdump -C`: an offset, sixteen hex bytes and the
+/// printable characters per line, `*` for runs of repeated lines, and the total length last.
+///
+/// Decoding honours the offsets instead of just concatenating the hex: a `*` expands to the
+/// repeated line up to the next offset, and an offset beyond the data so far (a dump that
+/// starts late or skips the holes of a sparse image) is filled with zeros, so the original
+/// layout comes back. `--opt holes=error` rejects such gaps; `dec --sparse` writes them as
+/// holes in the output file.
+pub struct Hexdump;
+
+impl Hexdump {
+    fn parse_offset(token: &str, mode: Mode, line: usize) -> Result<usize> {
+        let token = match mode {
+            Mode::Lenient => token.trim_end_matches(':'),
+            Mode::Strict => token,
+        };
+        // Dump tools print at least 7 digits; short tokens are hex data, not offsets.
+        if token.len() < 4 || !token.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(MbaseError::invalid_input(format!("line {}: '{}' is not a hex offset", line, token)));
+        }
+        usize::from_str_radix(token, 16).map_err(|_| MbaseError::invalid_input(format!("line {}: offset {} is too large", line, token)))
+    }
+
+    /// The bytes of one dump line, checking the ASCII column against them in strict mode.
+    fn parse_bytes(rest: &str, mode: Mode, line: usize) -> Result<Vec<u8>> {
+        let (hex, ascii) = match rest.find('|') {
+            Some(bar) => (&rest[..bar], Some(&rest[bar..])),
+            None => (rest, None),
+        };
+        let mut bytes = Vec::with_capacity(LINE_BYTES);
+        for token in hex.split_whitespace() {
+            let valid = token.len() == 2 && token.bytes().all(|b| b.is_ascii_hexdigit());
+            if !valid || (mode == Mode::Strict && token.bytes().any(|b| b.is_ascii_uppercase())) {
+                return Err(MbaseError::invalid_input(format!("line {}: '{}' is not a hex byte", line, token)));
+            }
+            bytes.push(u8::from_str_radix(token, 16).unwrap());
+        }
+        if mode == Mode::Strict {
+            if let Some(ascii) = ascii {
+                let expected = format!("|{}|", ascii_column(&bytes));
+                if ascii.trim_end() != expected {
+                    return Err(MbaseError::invalid_input(format!(
+                        "line {}: ASCII column {} does not match the hex bytes ({})",
+                        line,
+                        ascii.trim_end(),
+                        expected
+                    )));
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Extends `output` to `offset`, repeating `squeezed` (a `*` line) or filling a hole.
+    fn advance(output: &mut Vec<u8>, offset: usize, squeezed: Option<&[u8]>, holes: Holes, line: usize) -> Result<()> {
+        if offset < output.len() {
+            return Err(MbaseError::invalid_input(format!(
+                "line {}: offset {:#x} overlaps data already dumped up to {:#x}",
+                line,
+                offset,
+                output.len()
+            )));
+        }
+        let gap = offset - output.len();
+        // Offsets come from the input; a corrupt one must not abort the process.
+        output
+            .try_reserve(gap)
+            .map_err(|_| MbaseError::invalid_input(format!("line {}: offset {:#x} is too large to reconstruct in memory", line, offset)))?;
+        match squeezed {
+            Some(repeat) if !repeat.is_empty() && gap.is_multiple_of(repeat.len()) => {
+                for _ in 0..gap / repeat.len() {
+                    output.extend_from_slice(repeat);
+                }
+            }
+            Some(_) => {
+                return Err(MbaseError::invalid_input(format!("line {}: '*' does not repeat whole lines up to {:#x}", line, offset)));
+            }
+            None if gap > 0 && holes == Holes::Error => {
+                return Err(MbaseError::invalid_input(format!(
+                    "line {}: gap of {} bytes before offset {:#x} (holes=error)",
+                    line, gap, offset
+                )));
+            }
+            None => output.resize(offset, 0),
+        }
+        Ok(())
+    }
+}
+
+impl Codec for Hexdump {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "hexdump",
+            aliases: &["hexdump-c", "canonical"],
+            alphabet: "0123456789abcdef",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Lower,
+            description: "Canonical hex+ASCII dump (hexdump -C) with offsets, * runs and holes",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let opts = HexdumpOptions::from_options(opts)?;
+        let mut out = String::new();
+        if input.is_empty() {
+            return Ok(out);
+        }
+        let mut previous: Option<&[u8]> = None;
+        let mut starred = false;
+        for (i, bytes) in input.chunks(LINE_BYTES).enumerate() {
+            if opts.squeeze && previous == Some(bytes) {
+                if !starred {
+                    out.push_str("*\n");
+                    starred = true;
+                }
+                continue;
+            }
+            starred = false;
+            format_line(&mut out, i * LINE_BYTES, bytes);
+            previous = Some(bytes);
+        }
+        out.push_str(&format!("{:08x}", input.len()));
+        Ok(out)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let opts = HexdumpOptions::from_options(opts)?;
+        let mut output = Vec::new();
+        let mut previous: Vec<u8> = Vec::new();
+        let mut starred = false;
+        let mut length = None;
+
+        for (i, text) in input.lines().enumerate() {
+            let line = i + 1;
+            let text = text.trim_end();
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(end) = length {
+                return Err(MbaseError::invalid_input(format!("line {}: data after the final offset {:#x}", line, end)));
+            }
+            if text.trim() == "*" {
+                if previous.is_empty() || starred {
+                    return Err(MbaseError::invalid_input(format!("line {}: '*' without a line to repeat", line)));
+                }
+                starred = true;
+                continue;
+            }
+            let text = if mode == Mode::Lenient { text.trim_start() } else { text };
+            let (offset, rest) = text.split_once(' ').unwrap_or((text, ""));
+            let offset = Self::parse_offset(offset, mode, line)?;
+            let bytes = Self::parse_bytes(rest, mode, line)?;
+            if bytes.is_empty() {
+                // A lone number is not a dump; only a line after some data can give the length.
+                if previous.is_empty() {
+                    return Err(MbaseError::invalid_input(format!("line {}: offset without any dumped bytes before it", line)));
+                }
+                length = Some(offset);
+            }
+            Self::advance(&mut output, offset, starred.then_some(previous.as_slice()), opts.holes, line)?;
+            starred = false;
+            output.extend_from_slice(&bytes);
+            previous = bytes;
+        }
+
+        if starred {
+            return Err(MbaseError::invalid_input("dump ends in '*' without the offset that closes the run"));
+        }
+        if length.is_none() && mode == Mode::Strict && !output.is_empty() {
+            return Err(MbaseError::invalid_input("dump has no final offset line giving its length"));
+        }
+        Ok(output)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let first = input.lines().next().unwrap_or("");
+        let looks_like_dump = first.len() > 10
+            && first.as_bytes()[..8].iter().all(u8::is_ascii_hexdigit)
+            && first[8..].starts_with("  ")
+            && first.contains('|');
+        if !looks_like_dump {
+            return util::confidence::no_match(self.name());
+        }
+        match self.decode(input, Mode::Lenient) {
+            Ok(bytes) => {
+                util::confidence::with_confidence(self.name(), 0.9, format!("hexdump -C layout ({} bytes with offsets)", bytes.len()))
+            }
+            Err(_) => util::confidence::no_match(self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `printf 'hello world\n' | hexdump -C`
+    const HELLO: &str = "00000000  68 65 6c 6c 6f 20 77 6f  72 6c 64 0a              |hello world.|\n0000000c";
+
+    #[test]
+    fn test_hexdump_matches_hexdump_c() {
+        assert_eq!(Hexdump.encode(b"hello world\n").unwrap(), HELLO);
+        assert_eq!(Hexdump.decode(HELLO, Mode::Strict).unwrap(), b"hello world\n");
+        assert_eq!(Hexdump.encode(b"").unwrap(), "");
+        assert_eq!(Hexdump.decode("", Mode::Strict).unwrap(), b"");
+
+        let full = Hexdump.encode(b"0123456789abcdefXYZ").unwrap();
+        assert_eq!(
+            full,
+            "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\n\
+             00000010  58 59 5a                                          |XYZ|\n\
+             00000013"
+        );
+    }
+
+    #[test]
+    fn test_hexdump_squeezes_repeats() {
+        let mut data = vec![0u8; 64];
+        data.extend_from_slice(b"tail");
+        let dump = Hexdump.encode(&data).unwrap();
+        assert_eq!(dump.lines().nth(1), Some("*"));
+        assert_eq!(dump.lines().count(), 4);
+        assert_eq!(Hexdump.decode(&dump, Mode::Strict).unwrap(), data);
+
+        let opts = CodecOptions::new().with("squeeze", "false");
+        let verbose = Hexdump.encode_with(&data, &opts).unwrap();
+        assert!(!verbose.contains('*'));
+        assert_eq!(Hexdump.decode(&verbose, Mode::Strict).unwrap(), data);
+    }
+
+    #[test]
+    fn test_hexdump_holes() {
+        // A dump that starts late, skips a region and ends in a hole.
+        let sparse = "00000010  41 42                                             |AB|\n\
+                      00000100  43                                                |C|\n\
+                      00000200";
+        let data = Hexdump.decode(sparse, Mode::Strict).unwrap();
+        assert_eq!(data.len(), 0x200);
+        assert_eq!(&data[0x10..0x12], b"AB");
+        assert_eq!(data[0x100], b'C');
+        assert!(data[..0x10].iter().chain(&data[0x12..0x100]).chain(&data[0x101..]).all(|&b| b == 0));
+
+        let opts = CodecOptions::new().with("holes", "error");
+        assert!(Hexdump.decode_with(sparse, Mode::Strict, &opts).is_err());
+    }
+
+    #[test]
+    fn test_hexdump_errors_and_lenient() {
+        let tampered = HELLO.replace("|hello world.|", "|hello w0rld.|");
+        assert!(Hexdump.decode(&tampered, Mode::Strict).is_err());
+        assert_eq!(Hexdump.decode(&tampered, Mode::Lenient).unwrap(), b"hello world\n");
+
+        let backwards = "00000010  41 |A|\n00000000  42 |B|\n00000011";
+        assert!(Hexdump.decode(backwards, Mode::Strict).is_err());
+        assert!(Hexdump.decode("00000000  4g |.|\n00000001", Mode::Strict).is_err());
+        assert!(Hexdump.decode("*\n00000010", Mode::Strict).is_err());
+        assert!(Hexdump.decode("00000000  41 |A|\nffffffffffffff", Mode::Strict).is_err());
+        assert!(Hexdump.decode("ff", Mode::Lenient).is_err());
+        assert!(Hexdump.decode("de ad be ef", Mode::Lenient).is_err());
+        let unterminated = HELLO.lines().next().unwrap();
+        assert!(Hexdump.decode(unterminated, Mode::Strict).is_err());
+        assert_eq!(Hexdump.decode(unterminated, Mode::Lenient).unwrap(), b"hello world\n");
+        assert_eq!(Hexdump.decode("00000000: 68 69\n00000002", Mode::Lenient).unwrap(), b"hi");
+    }
+
+    #[test]
+    fn test_hexdump_detect() {
+        assert!(Hexdump.detect_score(HELLO).confidence >= 0.9);
+        assert_eq!(Hexdump.detect_score("68656c6c6f").confidence, 0.0);
+    }
+}
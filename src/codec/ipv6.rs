@@ -1,8 +1,9 @@
 use super::{rfc1924, util, Codec};
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
-use std::net::Ipv6Addr;
-use std::str::FromStr;
+use core::net::Ipv6Addr;
+use core::str::FromStr;
 
 pub struct Ipv6;
 
@@ -20,7 +21,7 @@ impl Codec for Ipv6 {
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        let input_str = std::str::from_utf8(input).map_err(|_| Error::invalid_input("input must be valid UTF-8 IPv6 address string"))?;
+        let input_str = core::str::from_utf8(input).map_err(|_| Error::invalid_input("input must be valid UTF-8 IPv6 address string"))?;
 
         let addr = Ipv6Addr::from_str(input_str.trim()).map_err(|e| Error::invalid_input(format!("invalid IPv6 address: {}", e)))?;
 
@@ -86,7 +87,7 @@ mod tests {
         assert_eq!(encoded, "4)+k&C#VzJ4br>0wv%Yp");
 
         let decoded = codec.decode(&encoded, Mode::Strict).unwrap();
-        assert_eq!(std::str::from_utf8(&decoded).unwrap(), "1080::8:800:200c:417a");
+        assert_eq!(core::str::from_utf8(&decoded).unwrap(), "1080::8:800:200c:417a");
     }
 
     #[test]
@@ -96,7 +97,7 @@ mod tests {
         assert_eq!(encoded.len(), 20);
 
         let decoded = codec.decode(&encoded, Mode::Strict).unwrap();
-        assert_eq!(std::str::from_utf8(&decoded).unwrap(), "fedc:ba98:7654:3210:fedc:ba98:7654:3210");
+        assert_eq!(core::str::from_utf8(&decoded).unwrap(), "fedc:ba98:7654:3210:fedc:ba98:7654:3210");
     }
 
     #[test]
@@ -106,7 +107,7 @@ mod tests {
         assert_eq!(encoded.len(), 20);
 
         let decoded = codec.decode(&encoded, Mode::Strict).unwrap();
-        assert_eq!(std::str::from_utf8(&decoded).unwrap(), "::");
+        assert_eq!(core::str::from_utf8(&decoded).unwrap(), "::");
     }
 
     #[test]
@@ -116,7 +117,7 @@ mod tests {
         assert_eq!(encoded.len(), 20);
 
         let decoded = codec.decode(&encoded, Mode::Strict).unwrap();
-        assert_eq!(std::str::from_utf8(&decoded).unwrap(), "::1");
+        assert_eq!(core::str::from_utf8(&decoded).unwrap(), "::1");
     }
 
     #[test]
@@ -147,7 +148,7 @@ mod tests {
         let encoded = codec.encode(b"::1").unwrap();
         let with_space = format!("{} {}", &encoded[..10], &encoded[10..]);
         let decoded = codec.decode(&with_space, Mode::Lenient).unwrap();
-        assert_eq!(std::str::from_utf8(&decoded).unwrap(), "::1");
+        assert_eq!(core::str::from_utf8(&decoded).unwrap(), "::1");
     }
 
     #[test]
@@ -176,7 +177,7 @@ mod tests {
             let encoded = codec.encode(input.as_bytes()).unwrap();
             assert_eq!(encoded.len(), 20, "encoding failed for {}", input);
             let decoded = codec.decode(&encoded, Mode::Strict).unwrap();
-            let decoded_str = std::str::from_utf8(&decoded).unwrap();
+            let decoded_str = core::str::from_utf8(&decoded).unwrap();
             assert_eq!(decoded_str, expected_output, "roundtrip failed for {}", input);
         }
     }
@@ -192,7 +193,7 @@ mod tests {
 
         // Decoding should give canonical form
         let decoded = codec.decode(&addr1, Mode::Strict).unwrap();
-        assert_eq!(std::str::from_utf8(&decoded).unwrap(), "2001:db8::1");
+        assert_eq!(core::str::from_utf8(&decoded).unwrap(), "2001:db8::1");
     }
 
     #[test]
@@ -0,0 +1,156 @@
+//! Sanity checks for custom alphabets before they are used to build a codec.
+
+use serde::Serialize;
+
+use crate::codec::Registry;
+use crate::prelude::*;
+
+/// Characters that are easily mistaken for one another when read or transcribed.
+const CONFUSABLE_GROUPS: &[&str] = &["0Oo", "1lI|", "5Ss", "2Zz", "8B", "6G", "9gq", "UV", "uv", "'`"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintIssue {
+    pub severity: Severity,
+    pub kind: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    /// The codec will match symbols case-insensitively, so `a`/`A` collide.
+    pub case_insensitive: bool,
+    /// Multibase prefix the codec would claim.
+    pub prefix: Option<char>,
+}
+
+fn issue(severity: Severity, kind: &'static str, message: String) -> LintIssue {
+    LintIssue { severity, kind, message }
+}
+
+/// Checks `alphabet` for duplicates, whitespace and control characters, case collisions,
+/// confusable pairs and a clash between `opts.prefix` and the codecs in `registry`.
+pub fn lint_alphabet(alphabet: &str, opts: &LintOptions, registry: &Registry) -> Vec<LintIssue> {
+    let chars: Vec<char> = alphabet.chars().collect();
+    let mut issues = Vec::new();
+
+    if chars.len() < 2 {
+        issues.push(issue(Severity::Error, "length", format!("alphabet needs at least 2 symbols, got {}", chars.len())));
+    }
+
+    for (i, &c) in chars.iter().enumerate() {
+        if let Some(j) = chars[..i].iter().position(|&d| d == c) {
+            issues.push(issue(Severity::Error, "duplicate", format!("{:?} appears at positions {} and {}", c, j, i)));
+        }
+        if c.is_whitespace() {
+            issues.push(issue(Severity::Error, "whitespace", format!("whitespace {:?} at position {}", c, i)));
+        } else if c.is_control() {
+            issues.push(issue(Severity::Error, "control", format!("control character {:?} at position {}", c, i)));
+        }
+    }
+
+    let mut seen_folds: Vec<char> = Vec::new();
+    for &c in &chars {
+        if !c.is_alphabetic() || seen_folds.contains(&c) {
+            continue;
+        }
+        let other = chars.iter().copied().find(|&d| d != c && d.to_lowercase().eq(c.to_lowercase()));
+        if let Some(other) = other {
+            seen_folds.extend([c, other]);
+            let (severity, note) = if opts.case_insensitive {
+                (Severity::Error, "collide under case-insensitive matching")
+            } else {
+                (Severity::Warning, "would collide if matched case-insensitively")
+            };
+            issues.push(issue(severity, "case", format!("{:?} and {:?} {}", c, other, note)));
+        }
+    }
+
+    for group in CONFUSABLE_GROUPS {
+        let present: Vec<char> = group.chars().filter(|c| chars.contains(c)).collect();
+        // Case pairs inside a group (o/O, s/S) are already reported above.
+        let distinct = present
+            .iter()
+            .filter(|c| !present.iter().any(|d| d != *c && d.to_lowercase().eq(c.to_lowercase()) && d < *c))
+            .count();
+        if distinct > 1 {
+            let list: Vec<String> = present.iter().map(|c| format!("{:?}", c)).collect();
+            issues.push(issue(Severity::Warning, "confusable", format!("{} look alike", list.join(", "))));
+        }
+    }
+
+    if let Some(prefix) = opts.prefix {
+        if let Some(owner) = registry.multibase_map().get(&prefix) {
+            issues.push(issue(Severity::Error, "prefix", format!("multibase prefix {:?} is already used by {}", prefix, owner)));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(alphabet: &str, opts: &LintOptions) -> Vec<&'static str> {
+        lint_alphabet(alphabet, opts, Registry::global())
+            .into_iter()
+            .map(|i| i.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_clean_alphabets() {
+        let opts = LintOptions::default();
+        assert!(kinds("123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz", &opts)
+            .iter()
+            .all(|k| *k != "duplicate"));
+        assert!(kinds("0123456789abcdef", &opts).is_empty());
+        assert!(kinds("0123456789ABCDEFGHJKMNPQRSTVWXYZ", &opts).iter().all(|k| *k == "confusable"));
+    }
+
+    #[test]
+    fn test_lint_errors() {
+        let opts = LintOptions::default();
+        assert_eq!(kinds("abca", &opts), vec!["duplicate"]);
+        assert_eq!(kinds("ab c", &opts), vec!["whitespace"]);
+        assert_eq!(kinds("a", &opts), vec!["length"]);
+        assert_eq!(kinds("ab\u{7}", &opts), vec!["control"]);
+    }
+
+    #[test]
+    fn test_lint_case_and_confusables() {
+        let issues = lint_alphabet("aA01O", &LintOptions::default(), Registry::global());
+        assert_eq!(issues[0].kind, "case");
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[1].kind, "confusable");
+
+        let strict = LintOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+        assert_eq!(lint_alphabet("xX", &strict, Registry::global())[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_lint_prefix_conflict() {
+        let opts = LintOptions {
+            prefix: Some('z'),
+            ..Default::default()
+        };
+        let issues = lint_alphabet("01", &opts, Registry::global());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("base58btc"), "{}", issues[0].message);
+        let free = LintOptions {
+            prefix: Some('!'),
+            ..Default::default()
+        };
+        assert!(lint_alphabet("01", &free, Registry::global()).is_empty());
+    }
+}
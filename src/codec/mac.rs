@@ -0,0 +1,295 @@
+use super::util::confidence;
+use super::Codec;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// How a MAC or EUI-64 address is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// `aa:bb:cc:dd:ee:ff`, as Linux and macOS print it.
+    Colon,
+    /// `AA-BB-CC-DD-EE-FF`, the IEEE 802 canonical form Windows uses.
+    Hyphen,
+    /// `aabb.ccdd.eeff`, as Cisco IOS prints it.
+    Cisco,
+    /// `aabbccddeeff`.
+    Hex,
+}
+
+impl Style {
+    fn name(self) -> &'static str {
+        match self {
+            Style::Colon => "colon-separated",
+            Style::Hyphen => "hyphen-separated",
+            Style::Cisco => "Cisco dotted",
+            Style::Hex => "raw hex",
+        }
+    }
+}
+
+const KNOWN_OPTIONS: &[&str] = &["style", "upper", "eui64"];
+
+fn style(opts: &CodecOptions) -> Result<Style> {
+    match opts.get("style") {
+        None | Some("colon") => Ok(Style::Colon),
+        Some("hyphen") | Some("windows") => Ok(Style::Hyphen),
+        Some("cisco") | Some("dot") => Ok(Style::Cisco),
+        Some("hex") | Some("raw") => Ok(Style::Hex),
+        Some(other) => Err(MbaseError::invalid_input(format!("option 'style' expects colon, hyphen, cisco or hex, got '{}'", other))),
+    }
+}
+
+/// The universal/local bit of the first octet; modified EUI-64 (RFC 4291 appendix A) inverts it.
+const UL_BIT: u8 = 0x02;
+/// The individual/group bit of the first octet.
+const GROUP_BIT: u8 = 0x01;
+
+/// Modified EUI-64 of a MAC: `ff:fe` in the middle and the U/L bit flipped, as in IPv6 SLAAC
+/// interface identifiers.
+fn expand(mac: &[u8]) -> Vec<u8> {
+    let mut eui = Vec::with_capacity(8);
+    eui.extend_from_slice(&mac[..3]);
+    eui.extend_from_slice(&[0xff, 0xfe]);
+    eui.extend_from_slice(&mac[3..]);
+    eui[0] ^= UL_BIT;
+    eui
+}
+
+/// The MAC a modified EUI-64 was built from, if it has the `ff:fe` filler.
+fn contract(eui: &[u8]) -> Option<Vec<u8>> {
+    if eui.len() != 8 || eui[3..5] != [0xff, 0xfe] {
+        return None;
+    }
+    let mut mac: Vec<u8> = eui[..3].iter().chain(&eui[5..]).copied().collect();
+    mac[0] ^= UL_BIT;
+    Some(mac)
+}
+
+fn format_address(bytes: &[u8], style: Style, upper: bool) -> String {
+    let hex: Vec<String> = bytes
+        .iter()
+        .map(|b| if upper { format!("{:02X}", b) } else { format!("{:02x}", b) })
+        .collect();
+    match style {
+        Style::Colon => hex.join(":"),
+        Style::Hyphen => hex.join("-"),
+        Style::Cisco => hex.chunks(2).map(|pair| pair.concat()).collect::<Vec<_>>().join("."),
+        Style::Hex => hex.concat(),
+    }
+}
+
+/// Parses any of the four forms into 6 or 8 bytes. Lenient mode also takes single-digit octets
+/// in the separated forms (`0:1b:...`, as macOS prints them) and surrounding whitespace.
+fn parse_address(input: &str, mode: Mode) -> Result<(Vec<u8>, Style)> {
+    let input = if mode == Mode::Lenient { input.trim() } else { input };
+    let (style, groups): (Style, Vec<&str>) = if input.contains(':') {
+        (Style::Colon, input.split(':').collect())
+    } else if input.contains('-') {
+        (Style::Hyphen, input.split('-').collect())
+    } else if input.contains('.') {
+        (Style::Cisco, input.split('.').collect())
+    } else {
+        (Style::Hex, vec![input])
+    };
+    let separator = match style {
+        Style::Colon => Some(':'),
+        Style::Hyphen => Some('-'),
+        Style::Cisco => Some('.'),
+        Style::Hex => None,
+    };
+    let mut bytes = Vec::with_capacity(8);
+    let mut position = 0;
+    for group in &groups {
+        if let Some((i, c)) = group.char_indices().find(|&(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(MbaseError::InvalidCharacter {
+                char: c,
+                position: position + i,
+            });
+        }
+        let width = if style == Style::Cisco { 4 } else { 2 };
+        let short_ok = mode == Mode::Lenient && matches!(style, Style::Colon | Style::Hyphen) && group.len() == 1;
+        let valid = match style {
+            Style::Hex => !group.is_empty() && group.len() % 2 == 0,
+            _ => group.len() == width || short_ok,
+        };
+        if !valid {
+            return Err(MbaseError::invalid_input_at(
+                position,
+                format!(
+                    "{} address group '{}' must have {} hex digits",
+                    style.name(),
+                    group,
+                    if style == Style::Hex {
+                        "an even number of".to_string()
+                    } else {
+                        width.to_string()
+                    }
+                ),
+            ));
+        }
+        let padded = if short_ok { format!("0{}", group) } else { group.to_string() };
+        for pair in padded.as_bytes().chunks(2) {
+            let digits = core::str::from_utf8(pair).map_err(|e| MbaseError::invalid_input(e.to_string()))?;
+            bytes.push(u8::from_str_radix(digits, 16).map_err(|e| MbaseError::invalid_input(e.to_string()))?);
+        }
+        position += group.len() + separator.map_or(0, char::len_utf8);
+    }
+    if bytes.len() != 6 && bytes.len() != 8 {
+        return Err(MbaseError::invalid_input(format!("{} address has {} octets; a MAC has 6 and an EUI-64 8", style.name(), bytes.len())));
+    }
+    Ok((bytes, style))
+}
+
+/// What the flag bits of the first octet say about an address.
+fn describe_flags(first: u8) -> Vec<String> {
+    let mut notes = Vec::new();
+    if first & GROUP_BIT != 0 {
+        notes.push("group (multicast) address".to_string());
+    }
+    if first & UL_BIT != 0 {
+        notes.push("locally administered".to_string());
+    }
+    notes
+}
+
+/// MAC (EUI-48) and EUI-64 addresses in colon, hyphen, Cisco dotted or raw hex form. Encoding
+/// writes 6 or 8 bytes in the `style` chosen; `eui64=true` first expands a MAC to its modified
+/// EUI-64, and on decode contracts one back to the MAC.
+pub struct Mac;
+
+impl Codec for Mac {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "mac",
+            aliases: &["eui48", "eui64", "mac-address"],
+            alphabet: "0123456789abcdef:-.",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "MAC/EUI-64 address (colon, hyphen, Cisco dotted, raw hex)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("mac", KNOWN_OPTIONS)?;
+        let style = style(opts)?;
+        let upper = opts.get_bool("upper")?.unwrap_or(style == Style::Hyphen);
+        let bytes = if opts.get_bool("eui64")?.unwrap_or(false) {
+            if input.len() != 6 {
+                return Err(MbaseError::invalid_input(format!("eui64=true expands a 6-byte MAC, got {} bytes", input.len())));
+            }
+            expand(input)
+        } else {
+            if input.len() != 6 && input.len() != 8 {
+                return Err(MbaseError::invalid_input(format!("a MAC is 6 bytes and an EUI-64 8, got {}", input.len())));
+            }
+            input.to_vec()
+        };
+        Ok(format_address(&bytes, style, upper))
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        opts.ensure_known("mac", KNOWN_OPTIONS)?;
+        let (bytes, _) = parse_address(input, mode)?;
+        if !opts.get_bool("eui64")?.unwrap_or(false) {
+            return Ok(bytes);
+        }
+        if bytes.len() != 8 {
+            return Err(MbaseError::invalid_input("eui64=true contracts an 8-octet EUI-64, got a 6-octet MAC"));
+        }
+        contract(&bytes).ok_or_else(|| {
+            MbaseError::invalid_input(format!("not derived from a MAC: octets 4-5 are {:02x}:{:02x}, expected ff:fe", bytes[3], bytes[4]))
+        })
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let Ok((bytes, style)) = parse_address(input.trim(), Mode::Strict) else {
+            return confidence::no_match("mac");
+        };
+        let kind = if bytes.len() == 6 { "MAC (EUI-48)" } else { "EUI-64" };
+        let mut reasons = vec![format!("{} {}", style.name(), kind)];
+        if let Some(mac) = contract(&bytes) {
+            reasons.push(format!("modified EUI-64 of MAC {} (ff:fe filler, U/L bit flipped)", format_address(&mac, Style::Colon, false)));
+        }
+        reasons.extend(describe_flags(bytes[0]));
+        // Separated groups of exactly this shape are unambiguous, and often start with a digit
+        // that doubles as a multibase prefix (0 for base2, f for base16). Twelve or sixteen bare
+        // hex digits are more often just hex.
+        let confidence = if style == Style::Hex { confidence::WEAK_MATCH } else { 0.99 };
+        DetectCandidate {
+            codec: "mac".to_string(),
+            confidence,
+            reasons,
+            warnings: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: [u8; 6] = [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6];
+
+    fn encode(style: &str) -> String {
+        Mac.encode_with(&MAC, &CodecOptions::new().with("style", style)).unwrap()
+    }
+
+    #[test]
+    fn test_mac_styles() {
+        assert_eq!(encode("colon"), "00:1b:63:84:45:e6");
+        assert_eq!(encode("hyphen"), "00-1B-63-84-45-E6");
+        assert_eq!(encode("cisco"), "001b.6384.45e6");
+        assert_eq!(encode("hex"), "001b638445e6");
+        for text in ["00:1b:63:84:45:e6", "00-1B-63-84-45-E6", "001b.6384.45e6", "001B638445E6"] {
+            assert_eq!(Mac.decode(text, Mode::Strict).unwrap(), MAC);
+        }
+        assert!(Mac.encode(&MAC[..5]).is_err());
+    }
+
+    #[test]
+    fn test_mac_parse_errors() {
+        assert_eq!(Mac.decode(" 0:1b:63:84:45:e6\n", Mode::Lenient).unwrap(), MAC);
+        assert!(Mac.decode("0:1b:63:84:45:e6", Mode::Strict).is_err());
+        assert!(matches!(Mac.decode("00:1b:63:84:45:eg", Mode::Strict), Err(MbaseError::InvalidCharacter { char: 'g', position: 16 })));
+        assert!(Mac.decode("00:1b:63:84:45", Mode::Strict).is_err());
+        assert!(Mac.decode("001b.6384.45e", Mode::Strict).is_err());
+        assert!(Mac.decode("00:1b-63:84:45:e6", Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_eui64_expansion() {
+        let eui64 = CodecOptions::new().with("eui64", "true");
+        let expanded = Mac.encode_with(&MAC, &eui64).unwrap();
+        assert_eq!(expanded, "02:1b:63:ff:fe:84:45:e6");
+        assert_eq!(Mac.decode(&expanded, Mode::Strict).unwrap().len(), 8);
+        assert_eq!(Mac.decode_with(&expanded, Mode::Strict, &eui64).unwrap(), MAC);
+        let cisco = eui64.clone().with("style", "cisco");
+        assert_eq!(Mac.encode_with(&MAC, &cisco).unwrap(), "021b.63ff.fe84.45e6");
+        assert!(Mac.decode_with("02:1b:63:00:00:84:45:e6", Mode::Strict, &eui64).is_err());
+        assert!(Mac.decode_with("00:1b:63:84:45:e6", Mode::Strict, &eui64).is_err());
+    }
+
+    #[test]
+    fn test_mac_detection() {
+        let colon = Mac.detect_score("00:1b:63:84:45:e6");
+        assert!(colon.confidence > 0.8);
+        assert_eq!(colon.reasons, ["colon-separated MAC (EUI-48)"]);
+        let eui = Mac.detect_score("021b.63ff.fe84.45e6");
+        assert_eq!(eui.reasons[0], "Cisco dotted EUI-64");
+        assert!(eui.reasons[1].contains("MAC 00:1b:63:84:45:e6"));
+        assert!(eui.reasons.iter().any(|r| r == "locally administered"));
+        assert!(Mac.detect_score("01-00-5E-00-00-FB").reasons.iter().any(|r| r.starts_with("group")));
+        assert_eq!(Mac.detect_score("001b638445e6").confidence, confidence::WEAK_MATCH);
+        assert_eq!(Mac.detect_score("hello").confidence, 0.0);
+    }
+}
@@ -1,4 +1,4 @@
-mod atbash;
+mod affine;
 mod base16;
 mod base2_8;
 mod base32;
@@ -19,26 +19,62 @@ mod base91;
 mod base92;
 mod baudot;
 mod bech32;
+mod bip39;
+mod bitmap;
 mod braille;
 mod bubblebabble;
+mod codepage;
+pub mod confusables;
+mod diceware;
+mod electrum;
+pub mod evidence;
+mod hexdump;
 mod ipv6;
-mod morse;
+pub mod lint;
+mod mac;
+mod monero;
+pub(crate) mod morse;
 mod proquint;
 mod punycode;
 mod quotedprintable;
 pub mod registry;
 pub(crate) mod rfc1924;
 mod rot;
+mod shellquote;
 mod simple_text;
+mod slug;
+mod strkey;
+mod translate;
+mod triplet;
 mod unicode_tap;
-mod urlencoding;
+pub mod urlencoding;
 pub(crate) mod util;
 mod uuencode;
 
+pub use affine::LETTER_ALPHABETS;
+pub use base64::decode_jwt;
 pub use registry::Registry;
 
+use alloc::borrow::Cow;
+
 use crate::error::Result;
-use crate::types::{CodecMeta, DetectCandidate, Mode};
+use crate::prelude::*;
+use crate::types::{CodecMeta, CodecOptions, DetectCandidate, Mode};
+
+/// Incremental encoder for codecs that carry state across chunk boundaries instead of working
+/// in independent fixed-size blocks (see [`Codec::stream_encoder`]).
+pub trait StreamEncoder {
+    /// Consumes `input`, appending every character that is already determined to `out`.
+    fn update(&mut self, input: &[u8], out: &mut String) -> Result<()>;
+    /// Appends whatever the buffered state still encodes.
+    fn finish(&mut self, out: &mut String) -> Result<()>;
+}
+
+/// Incremental decoder counterpart of [`StreamEncoder`].
+pub trait StreamDecoder {
+    fn update(&mut self, input: &str, out: &mut Vec<u8>) -> Result<()>;
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()>;
+}
 
 pub trait Codec: Send + Sync {
     fn meta(&self) -> CodecMeta;
@@ -46,6 +82,99 @@ pub trait Codec: Send + Sync {
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>>;
     fn detect_score(&self, input: &str) -> DetectCandidate;
 
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known(self.name(), &[])?;
+        self.encode(input)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        opts.ensure_known(self.name(), &[])?;
+        self.decode(input, mode)
+    }
+
+    /// Appends the encoding of `input` to `out`, so a caller converting many records can reuse
+    /// one buffer. `out` is left as it was on error.
+    fn encode_into(&self, input: &[u8], out: &mut String) -> Result<()> {
+        out.push_str(&self.encode(input)?);
+        Ok(())
+    }
+
+    /// Appends the decoding of `input` to `out`; the buffer-reusing counterpart of [`Codec::decode`].
+    fn decode_into(&self, input: &str, mode: Mode, out: &mut Vec<u8>) -> Result<()> {
+        out.extend_from_slice(&self.decode(input, mode)?);
+        Ok(())
+    }
+
+    /// Decodes `input`, borrowing from it when the decoded bytes are the input itself (plain text
+    /// through urlencoding, ROT13 without letters), so bulk callers skip the copy.
+    fn decode_cow<'a>(&self, input: &'a str, mode: Mode) -> Result<Cow<'a, [u8]>> {
+        self.decode(input, mode).map(Cow::Owned)
+    }
+
+    /// Exact encoded length for `input_len` bytes under `opts`, when it depends on the length
+    /// alone, so an output limit can reject the input before any output is built. `None` also for
+    /// options the codec would reject.
+    fn encoded_len(&self, _input_len: usize, _opts: &CodecOptions) -> Option<usize> {
+        None
+    }
+
+    /// Exact decoded length of `input` under `opts`, when it can be told without decoding, so an
+    /// output limit can reject the input before any output is built. `None` also for input that
+    /// cannot decode.
+    fn decoded_len(&self, _input: &str, _mode: Mode, _opts: &CodecOptions) -> Option<usize> {
+        None
+    }
+
+    /// `(bytes, chars)` when each `bytes`-sized input block always encodes to exactly `chars`
+    /// characters, independent of its neighbours, so the codec can be applied piecewise.
+    fn stream_block(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Stateful encoder for streaming codecs that are not block-based.
+    fn stream_encoder(&self) -> Option<Box<dyn StreamEncoder>> {
+        None
+    }
+
+    fn stream_decoder(&self, _mode: Mode) -> Option<Box<dyn StreamDecoder>> {
+        None
+    }
+
+    /// Whether [`crate::stream`] can apply the codec piecewise.
+    fn is_streamable(&self) -> bool {
+        self.stream_block().is_some() || self.stream_encoder().is_some()
+    }
+
+    /// Whether `c` is one of the codec's symbols, which lenient cleaning keeps even when
+    /// `--ignore-chars` names it. Defaults to membership in `meta().alphabet`; codecs whose
+    /// alphabet field describes their symbols rather than listing them override it.
+    fn is_symbol(&self, c: char) -> bool {
+        self.meta().alphabet.contains(c)
+    }
+
+    /// Whether `encode` takes UTF-8 text rather than arbitrary bytes. Such codecs reject
+    /// invalid UTF-8 unless `--opt utf8=lenient` asks for a marker in its place.
+    fn text_only(&self) -> bool {
+        false
+    }
+
+    /// Whether decoding can give back something other than what was encoded, because the codec
+    /// folds case or drops characters it has no symbol for.
+    fn lossy(&self) -> bool {
+        false
+    }
+
+    /// Whether nearly any text decodes, because decoding rewrites characters rather than
+    /// unpacking an encoding. Detection takes no evidence from such a decode succeeding.
+    fn accepts_any_text(&self) -> bool {
+        false
+    }
+
+    /// Non-fatal notes on how a lenient decode had to reinterpret `input`.
+    fn decode_warnings(&self, _input: &str, _mode: Mode) -> Vec<String> {
+        Vec::new()
+    }
+
     fn validate(&self, input: &str, mode: Mode) -> Result<()> {
         self.decode(input, mode)?;
         Ok(())
@@ -0,0 +1,217 @@
+use super::util;
+use super::Codec;
+use crate::checksum::Checksum;
+use crate::error::{LengthConstraint, MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// Words in a Monero (and Electrum 1.x) list.
+const LIST_SIZE: u32 = 1626;
+
+/// Index of the checksum word: CRC-32 (zlib) over the first `prefix` characters of every word.
+fn checksum_index(words: &[&str], prefix: usize) -> usize {
+    let trimmed: String = words.iter().flat_map(|w| w.chars().take(prefix)).collect();
+    (Checksum::Crc32.compute(trimmed.as_bytes()) % words.len() as u32) as usize
+}
+
+struct MoneroOptions {
+    words: Vec<String>,
+    prefix: usize,
+}
+
+impl MoneroOptions {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("monero", &["wordlist", "prefix"])?;
+        let path = opts
+            .get("wordlist")
+            .ok_or_else(|| MbaseError::invalid_input("monero needs its 1626-word list: --opt wordlist=FILE (one word per line)"))?;
+        let text = util::read_wordlist(path)?;
+        let words: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(String::from)
+            .collect();
+        if words.len() != LIST_SIZE as usize {
+            return Err(MbaseError::invalid_input(format!("{}: {} words, a Monero list needs {}", path, words.len(), LIST_SIZE)));
+        }
+        let prefix = opts.get_usize("prefix")?.unwrap_or(3);
+        if prefix == 0 {
+            return Err(MbaseError::invalid_input("option 'prefix' must be at least 1"));
+        }
+        Ok(Self { words, prefix })
+    }
+
+    /// Exact match, or in lenient mode any case and a unique `prefix`-character abbreviation,
+    /// which is how Monero itself matches words.
+    fn index(&self, word: &str, mode: Mode) -> Option<u32> {
+        if let Some(i) = self.words.iter().position(|w| w == word) {
+            return Some(i as u32);
+        }
+        if mode == Mode::Strict {
+            return None;
+        }
+        let word = word.to_lowercase();
+        let head: String = word.chars().take(self.prefix).collect();
+        self.words
+            .iter()
+            .position(|w| {
+                w.to_lowercase() == word
+                    || (word.chars().count() >= self.prefix && w.to_lowercase().chars().take(self.prefix).eq(head.chars()))
+            })
+            .map(|i| i as u32)
+    }
+}
+
+/// Monero mnemonic seeds: every 4 bytes (little-endian) become three words, and a checksum
+/// word chosen by CRC32 of the word prefixes is appended (25 words for a 32-byte key).
+///
+/// The word list is not bundled: pass Monero's English (or other) list with
+/// `--opt wordlist=FILE`, and `--opt prefix=N` for lists whose unique prefix is not 3 letters.
+pub struct Monero;
+
+impl Monero {
+    fn encode_words(input: &[u8], opts: &MoneroOptions) -> Result<String> {
+        if input.is_empty() || !input.len().is_multiple_of(4) {
+            return Err(MbaseError::invalid_length(LengthConstraint::MultipleOf(4), input.len()));
+        }
+        let mut words: Vec<&str> = Vec::with_capacity(input.len() / 4 * 3 + 1);
+        for chunk in input.chunks_exact(4) {
+            let x = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            let w1 = x % LIST_SIZE;
+            let w2 = (x / LIST_SIZE + w1) % LIST_SIZE;
+            let w3 = (x / LIST_SIZE / LIST_SIZE + w2) % LIST_SIZE;
+            words.extend([w1, w2, w3].map(|w| opts.words[w as usize].as_str()));
+        }
+        words.push(words[checksum_index(&words, opts.prefix)]);
+        Ok(words.join(" "))
+    }
+
+    fn decode_words(input: &str, mode: Mode, opts: &MoneroOptions) -> Result<Vec<u8>> {
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let has_checksum = tokens.len() % 3 == 1;
+        if tokens.len() < 3 || (!has_checksum && (mode == Mode::Strict || !tokens.len().is_multiple_of(3))) {
+            return Err(MbaseError::invalid_length_msg(
+                LengthConstraint::MultipleOf(3),
+                tokens.len(),
+                "expected three words per 4 bytes plus a checksum word",
+            ));
+        }
+        let mut indices = Vec::with_capacity(tokens.len());
+        for (i, token) in tokens.iter().enumerate() {
+            let index = opts
+                .index(token, mode)
+                .ok_or_else(|| MbaseError::invalid_input(format!("word {} ('{}') is not in the Monero wordlist", i + 1, token)))?;
+            indices.push(index);
+        }
+
+        let data = &indices[..indices.len() / 3 * 3];
+        if has_checksum {
+            let canonical: Vec<&str> = data.iter().map(|&i| opts.words[i as usize].as_str()).collect();
+            let expected = data[checksum_index(&canonical, opts.prefix)];
+            if indices[data.len()] != expected {
+                return Err(MbaseError::ChecksumMismatchAt {
+                    position: data.len(),
+                    message: format!("checksum word should be '{}'", opts.words[expected as usize]),
+                });
+            }
+        }
+
+        let mut output = Vec::with_capacity(data.len() / 3 * 4);
+        for (group, w) in data.chunks_exact(3).enumerate() {
+            // 1626^3 exceeds u32; Monero wraps here and lets the check below catch it.
+            let x = w[0]
+                .wrapping_add(LIST_SIZE * ((LIST_SIZE - w[0] + w[1]) % LIST_SIZE))
+                .wrapping_add((LIST_SIZE * LIST_SIZE).wrapping_mul((LIST_SIZE - w[1] + w[2]) % LIST_SIZE));
+            if x % LIST_SIZE != w[0] {
+                return Err(MbaseError::invalid_input(format!("words {}-{} do not encode a 32-bit value", group * 3 + 1, group * 3 + 3)));
+            }
+            output.extend_from_slice(&x.to_le_bytes());
+        }
+        Ok(output)
+    }
+}
+
+impl Codec for Monero {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "monero",
+            aliases: &["xmr-mnemonic"],
+            alphabet: "",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Monero mnemonic seed (3 words per 4 bytes + CRC32 checksum word; needs a wordlist)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Self::encode_words(input, &MoneroOptions::from_options(opts)?)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        Self::decode_words(input, mode, &MoneroOptions::from_options(opts)?)
+    }
+
+    fn detect_score(&self, _input: &str) -> DetectCandidate {
+        // Nothing to check words against without a list.
+        util::confidence::no_match(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> MoneroOptions {
+        // Stand-in list with three-letter unique prefixes, like Monero's English one.
+        let words = (0..LIST_SIZE)
+            .map(|i| {
+                let letters: String = [i / 676, i / 26 % 26, i % 26].iter().map(|&d| char::from(b'a' + d as u8)).collect();
+                format!("{}word", letters)
+            })
+            .collect();
+        MoneroOptions { words, prefix: 3 }
+    }
+
+    #[test]
+    fn test_monero_roundtrip_and_checksum() {
+        let opts = options();
+        let key: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(97) ^ 0xc3).collect();
+        let seed = Monero::encode_words(&key, &opts).unwrap();
+        let words: Vec<&str> = seed.split(' ').collect();
+        assert_eq!(words.len(), 25);
+        assert!(words[..24].contains(&words[24]));
+        assert_eq!(Monero::decode_words(&seed, Mode::Strict, &opts).unwrap(), key);
+
+        // Three-letter abbreviations and a missing checksum word are lenient-only.
+        let short: Vec<String> = words.iter().map(|w| w[..3].to_uppercase()).collect();
+        assert_eq!(Monero::decode_words(&short.join(" "), Mode::Lenient, &opts).unwrap(), key);
+        let unchecked = words[..24].join(" ");
+        assert_eq!(Monero::decode_words(&unchecked, Mode::Lenient, &opts).unwrap(), key);
+        assert!(Monero::decode_words(&unchecked, Mode::Strict, &opts).is_err());
+    }
+
+    #[test]
+    fn test_monero_errors() {
+        let opts = options();
+        let seed = Monero::encode_words(&[0x42; 32], &opts).unwrap();
+        let mut words: Vec<&str> = seed.split(' ').collect();
+        let wrong = if words[24] == "aaaword" { "aabword" } else { "aaaword" };
+        words[24] = wrong;
+        assert!(matches!(
+            Monero::decode_words(&words.join(" "), Mode::Strict, &opts),
+            Err(MbaseError::ChecksumMismatchAt { position: 24, .. })
+        ));
+        assert!(Monero::encode_words(&[1, 2, 3], &opts).is_err());
+        assert!(Monero.encode(&[0; 32]).is_err());
+    }
+}
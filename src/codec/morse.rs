@@ -1,10 +1,11 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
-use std::collections::HashMap;
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule, Utf8Policy};
+use alloc::collections::BTreeMap;
 
-fn morse_table() -> HashMap<char, &'static str> {
-    let mut map = HashMap::new();
+fn morse_table() -> BTreeMap<char, &'static str> {
+    let mut map = BTreeMap::new();
     map.insert('A', ".-");
     map.insert('B', "-...");
     map.insert('C', "-.-.");
@@ -52,10 +53,157 @@ fn morse_table() -> HashMap<char, &'static str> {
     map
 }
 
-fn reverse_morse_table() -> HashMap<&'static str, char> {
+fn reverse_morse_table() -> BTreeMap<&'static str, char> {
     morse_table().into_iter().map(|(k, v)| (v, k)).collect()
 }
 
+/// Gaps between elements, letters and words, in dit units (PARIS timing).
+const ELEMENT_GAP: usize = 1;
+const LETTER_GAP: usize = 3;
+const WORD_GAP: usize = 7;
+const DAH_UNITS: usize = 3;
+
+/// Hands the code of each encodable character of `input` to `each`, with `/` standing for a
+/// word break; `each` can stop the walk by failing.
+fn for_each_code(input: &[u8], policy: Utf8Policy, mut each: impl FnMut(&'static str) -> Result<()>) -> Result<()> {
+    let table = morse_table();
+    let text = util::text_input("morse", input, policy, '?')?.to_uppercase();
+    let mut encodable = false;
+    for code in text.chars().filter_map(|c| table.get(&c).copied()) {
+        encodable = true;
+        each(code)?;
+    }
+    if !encodable && !input.is_empty() {
+        return Err(MbaseError::invalid_input("no encodable characters found"));
+    }
+    Ok(())
+}
+
+/// Key-down/key-up state per dit unit, without leading or trailing silence. `check` gets the
+/// unit count so far after each character and can stop the encoding by failing.
+pub(crate) fn timing_units(input: &[u8], policy: Utf8Policy, check: impl Fn(usize) -> Result<()>) -> Result<Vec<bool>> {
+    let mut units = Vec::new();
+    let mut gap = 0;
+    for_each_code(input, policy, |code| {
+        if code == "/" {
+            gap = WORD_GAP;
+            return Ok(());
+        }
+        if !units.is_empty() {
+            units.extend(core::iter::repeat_n(false, gap.max(LETTER_GAP)));
+        }
+        for (i, symbol) in code.chars().enumerate() {
+            if i > 0 {
+                units.extend(core::iter::repeat_n(false, ELEMENT_GAP));
+            }
+            units.extend(core::iter::repeat_n(true, if symbol == '-' { DAH_UNITS } else { 1 }));
+        }
+        gap = 0;
+        check(units.len())
+    })?;
+    Ok(units)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Words,
+    Bits,
+}
+
+fn options(opts: &CodecOptions) -> Result<(Format, usize, Utf8Policy)> {
+    opts.ensure_known("morse", &["format", "unit", "utf8"])?;
+    let format = match opts.get("format") {
+        None | Some("text") => Format::Text,
+        Some("words") => Format::Words,
+        Some("bits") => Format::Bits,
+        Some(other) => return Err(MbaseError::invalid_input(format!("option 'format' expects text, words or bits, got '{}'", other))),
+    };
+    let unit = opts.get_usize("unit")?.unwrap_or(1);
+    if unit == 0 {
+        return Err(MbaseError::invalid_input("option 'unit' must be at least 1"));
+    }
+    Ok((format, unit, opts.utf8_policy()?))
+}
+
+/// Converts `dit-dah dah` words (`/` between words) back to dot-dash text.
+fn words_to_text(input: &str, mode: Mode) -> Result<String> {
+    let mut letters = Vec::new();
+    for token in input.split_whitespace() {
+        if token == "/" {
+            letters.push("/".to_string());
+            continue;
+        }
+        let mut code = String::new();
+        for element in token.split('-') {
+            let element = if mode == Mode::Lenient {
+                element.to_lowercase()
+            } else {
+                element.to_string()
+            };
+            match element.as_str() {
+                "dit" => code.push('.'),
+                "di" if mode == Mode::Lenient => code.push('.'),
+                "dah" => code.push('-'),
+                _ => return Err(MbaseError::invalid_input(format!("expected dit or dah, got '{}'", element))),
+            }
+        }
+        letters.push(code);
+    }
+    Ok(letters.join(" "))
+}
+
+/// Converts an on/off pattern back to dot-dash text. Strict mode requires exact 1/3 unit marks and
+/// 1/3/7 unit gaps; lenient mode rounds run lengths and ignores characters other than `0`/`1`.
+fn bits_to_text(input: &str, mode: Mode, unit: usize) -> Result<String> {
+    let bits: Vec<char> = match mode {
+        Mode::Lenient => input.chars().filter(|c| matches!(c, '0' | '1')).collect(),
+        Mode::Strict => {
+            if let Some((pos, c)) = input.chars().enumerate().find(|(_, c)| !matches!(c, '0' | '1')) {
+                return Err(MbaseError::InvalidCharacter { char: c, position: pos });
+            }
+            input.chars().collect()
+        }
+    };
+
+    let mut text = String::new();
+    let start = bits.iter().position(|&c| c == '1').unwrap_or(bits.len());
+    let end = bits.iter().rposition(|&c| c == '1').map_or(start, |i| i + 1);
+    let mut i = start;
+    while i < end {
+        let on = bits[i] == '1';
+        let len = bits[i..end].iter().take_while(|&&c| c == bits[i]).count();
+        let units = match mode {
+            Mode::Strict if len % unit != 0 => {
+                return Err(MbaseError::invalid_input(format!(
+                    "run of {} at position {} is not a multiple of the {}-bit unit",
+                    len, i, unit
+                )))
+            }
+            Mode::Strict => len / unit,
+            Mode::Lenient => (len + unit / 2) / unit,
+        };
+        let symbol = match (on, mode, units) {
+            (true, Mode::Strict, 1) | (true, Mode::Lenient, 0..=1) => ".",
+            (true, Mode::Strict, DAH_UNITS) | (true, Mode::Lenient, _) => "-",
+            (false, Mode::Strict, ELEMENT_GAP) | (false, Mode::Lenient, 0..=1) => "",
+            (false, Mode::Strict, LETTER_GAP) | (false, Mode::Lenient, 2..=4) => " ",
+            (false, Mode::Strict, WORD_GAP) | (false, Mode::Lenient, _) => " / ",
+            _ => {
+                return Err(MbaseError::invalid_input(format!(
+                    "{} of {} units at position {} is not a valid morse timing",
+                    if on { "mark" } else { "gap" },
+                    units,
+                    i
+                )))
+            }
+        };
+        text.push_str(symbol);
+        i += len;
+    }
+    Ok(text)
+}
+
 pub struct Morse;
 
 impl Codec for Morse {
@@ -72,16 +220,45 @@ impl Codec for Morse {
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        let table = morse_table();
-        let text = String::from_utf8_lossy(input).to_uppercase();
-
-        let morse_chars: Vec<&str> = text.chars().filter_map(|c| table.get(&c).copied()).collect();
+        self.encode_with(input, &CodecOptions::default())
+    }
 
-        if morse_chars.is_empty() && !input.is_empty() {
-            return Err(MbaseError::invalid_input("no encodable characters found"));
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let (format, unit, policy) = options(opts)?;
+        if format == Format::Bits {
+            let units = timing_units(input, policy, |n| opts.check_output_size(n.saturating_mul(unit)))?;
+            return Ok(units
+                .into_iter()
+                .flat_map(|on| core::iter::repeat_n(if on { '1' } else { '0' }, unit))
+                .collect());
         }
+        // Morse is several times longer than its text, so stop once the output passes the limit.
+        let mut out = String::new();
+        for_each_code(input, policy, |code| {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            if format == Format::Words && code != "/" {
+                for (i, c) in code.chars().enumerate() {
+                    if i > 0 {
+                        out.push('-');
+                    }
+                    out.push_str(if c == '-' { "dah" } else { "dit" });
+                }
+            } else {
+                out.push_str(code);
+            }
+            opts.check_output_size(out.len())
+        })?;
+        Ok(out)
+    }
 
-        Ok(morse_chars.join(" "))
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        match options(opts)? {
+            (Format::Text, ..) => self.decode(input, mode),
+            (Format::Words, ..) => self.decode(&words_to_text(input, mode)?, Mode::Strict),
+            (Format::Bits, unit, _) => self.decode(&bits_to_text(input, mode, unit)?, Mode::Strict),
+        }
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
@@ -114,6 +291,14 @@ impl Codec for Morse {
         Ok(result.into_bytes())
     }
 
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn lossy(&self) -> bool {
+        true
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -171,6 +356,18 @@ mod tests {
         assert_eq!(Morse.decode(".-", Mode::Strict).unwrap(), b"A");
     }
 
+    #[test]
+    fn test_morse_output_limit_checks_real_length() {
+        let ctx = crate::types::Context::default().with_max_output_size(Some(5));
+        assert_eq!(ctx.encode(&Morse, b"E", &CodecOptions::new()).unwrap(), ".");
+        let err = ctx.encode(&Morse, b"SOS", &CodecOptions::new()).unwrap_err();
+        // The encoder stops at the first code past the limit: "... ---".
+        assert!(matches!(err, MbaseError::OutputTooLarge { size: 7, limit: 5 }));
+        let bits = CodecOptions::new().with("format", "bits").with("unit", "4");
+        assert_eq!(ctx.encode(&Morse, b"E", &bits).unwrap(), "1111");
+        assert!(matches!(ctx.encode(&Morse, b"EE", &bits), Err(MbaseError::OutputTooLarge { limit: 5, .. })));
+    }
+
     #[test]
     fn test_morse_roundtrip() {
         let data = b"HELLO WORLD";
@@ -209,6 +406,15 @@ mod tests {
         assert!(Morse.decode("xyz", Mode::Strict).is_err()); // Invalid symbols
     }
 
+    #[test]
+    fn test_morse_invalid_utf8() {
+        assert!(Morse.encode(b"SOS\xff").is_err());
+        let lenient = CodecOptions::new().with("utf8", "lenient");
+        assert_eq!(Morse.encode_with(b"SOS\xff", &lenient).unwrap(), "... --- ... ..--..");
+        assert!(Morse.encode_with(b"SOS", &CodecOptions::new().with("utf8", "lossy")).is_err());
+        assert!(Morse.text_only());
+    }
+
     #[test]
     fn test_morse_empty() {
         assert_eq!(Morse.decode("", Mode::Strict).unwrap(), Vec::<u8>::new());
@@ -229,4 +435,38 @@ mod tests {
         let decoded = Morse.decode(&encoded, Mode::Strict).unwrap();
         assert_eq!(decoded, digits);
     }
+
+    #[test]
+    fn test_morse_words_format() {
+        let opts = CodecOptions::new().with("format", "words");
+        assert_eq!(Morse.encode_with(b"SO S", &opts).unwrap(), "dit-dit-dit dah-dah-dah / dit-dit-dit");
+        assert_eq!(Morse.decode_with("dit-dah / dah-dit-dit-dit", Mode::Strict, &opts).unwrap(), b"A B");
+        assert_eq!(Morse.decode_with("Di-Dah", Mode::Lenient, &opts).unwrap(), b"A");
+        assert!(Morse.decode_with("di-dah", Mode::Strict, &opts).is_err());
+    }
+
+    #[test]
+    fn test_morse_bits_format() {
+        let opts = CodecOptions::new().with("format", "bits");
+        // A = dit dah, letter gap, N = dah dit, word gap, E = dit.
+        let bits = Morse.encode_with(b"AN E", &opts).unwrap();
+        assert_eq!(bits, "101110001110100000001");
+        assert_eq!(Morse.decode_with(&bits, Mode::Strict, &opts).unwrap(), b"AN E");
+
+        let wide = CodecOptions::new().with("format", "bits").with("unit", "2");
+        assert_eq!(Morse.encode_with(b"E", &wide).unwrap(), "11");
+        assert_eq!(Morse.decode_with("0011001111110000", Mode::Strict, &wide).unwrap(), b"A");
+    }
+
+    #[test]
+    fn test_morse_bits_timing_tolerance() {
+        let opts = CodecOptions::new().with("format", "bits").with("unit", "4");
+        // Hand-keyed marks and gaps drift from exact multiples of the unit.
+        let sloppy = "1111 000 11111111111 0000000000000 111111111111";
+        assert_eq!(Morse.decode_with(sloppy, Mode::Lenient, &opts).unwrap(), b"AT");
+        assert!(Morse.decode_with(sloppy, Mode::Strict, &opts).is_err());
+        assert!(Morse
+            .decode_with("11011", Mode::Strict, &CodecOptions::new().with("format", "bits"))
+            .is_err());
+    }
 }
@@ -1,6 +1,7 @@
 use super::{util, Codec};
-use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::error::{LengthConstraint, MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 const CONSONANTS: &[u8; 16] = b"bdfghjklmnprstvz";
 const VOWELS: &[u8; 4] = b"aiou";
@@ -58,6 +59,62 @@ impl Proquint {
 
         Ok(((c0 as u16) << 12) | ((v0 as u16) << 10) | ((c1 as u16) << 6) | ((v1 as u16) << 4) | (c2 as u16))
     }
+
+    // A trailing odd byte is written as a CVC syllable: high nibble -> consonant,
+    // next two bits -> vowel, low two bits -> one of the first four consonants (b d f g).
+    fn encode_u8(val: u8) -> String {
+        let result = vec![
+            CONSONANTS[(val >> 4) as usize],
+            VOWELS[((val >> 2) & 0x03) as usize],
+            CONSONANTS[(val & 0x03) as usize],
+        ];
+        String::from_utf8(result).unwrap()
+    }
+
+    fn decode_syllable(chars: &[char]) -> Result<u8> {
+        let c0 = consonant_index(chars[0]).ok_or(MbaseError::InvalidCharacter {
+            char: chars[0],
+            position: 0,
+        })?;
+        let v0 = vowel_index(chars[1]).ok_or(MbaseError::InvalidCharacter {
+            char: chars[1],
+            position: 1,
+        })?;
+        let c1 = consonant_index(chars[2]).filter(|&i| i < 4).ok_or(MbaseError::InvalidCharacter {
+            char: chars[2],
+            position: 2,
+        })?;
+
+        Ok((c0 << 4) | (v0 << 2) | c1)
+    }
+
+    fn options(opts: &CodecOptions) -> Result<(Option<char>, usize)> {
+        opts.ensure_known("proquint", &["sep", "group"])?;
+        let sep = match opts.get("sep") {
+            Some("") => None,
+            Some(_) => opts.get_char("sep")?,
+            None => Some('-'),
+        };
+        let group = opts.get_usize("group")?.unwrap_or(1);
+        if group == 0 {
+            return Err(MbaseError::invalid_input("option 'group' must be at least 1"));
+        }
+        Ok((sep, group))
+    }
+
+    fn is_word(chars: &[char]) -> bool {
+        match chars.len() {
+            5 => {
+                consonant_index(chars[0]).is_some()
+                    && vowel_index(chars[1]).is_some()
+                    && consonant_index(chars[2]).is_some()
+                    && vowel_index(chars[3]).is_some()
+                    && consonant_index(chars[4]).is_some()
+            }
+            3 => Self::decode_syllable(chars).is_ok(),
+            _ => false,
+        }
+    }
 }
 
 impl Codec for Proquint {
@@ -69,57 +126,83 @@ impl Codec for Proquint {
             multibase_code: None,
             padding: PaddingRule::None,
             case_sensitivity: CaseSensitivity::Insensitive,
-            description: "Proquint pronounceable identifiers (2 bytes per quint)",
+            description: "Proquint pronounceable identifiers (2 bytes per quint, odd tail as CVC)",
         }
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        if input.is_empty() {
-            return Ok(String::new());
-        }
+        self.encode_with(input, &CodecOptions::default())
+    }
 
-        if !input.len().is_multiple_of(2) {
-            return Err(MbaseError::invalid_length(crate::error::LengthConstraint::MultipleOf(2), input.len()));
-        }
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let (sep, group) = Self::options(opts)?;
 
-        let quints: Vec<String> = input
-            .chunks(2)
-            .map(|chunk| {
-                let val = ((chunk[0] as u16) << 8) | (chunk[1] as u16);
-                Self::encode_u16(val)
-            })
+        let mut words: Vec<String> = input
+            .chunks_exact(2)
+            .map(|chunk| Self::encode_u16(((chunk[0] as u16) << 8) | (chunk[1] as u16)))
             .collect();
+        if let [last] = input.chunks_exact(2).remainder() {
+            words.push(Self::encode_u8(*last));
+        }
 
-        Ok(quints.join("-"))
+        let sep = sep.map(String::from).unwrap_or_default();
+        Ok(words.chunks(group).map(|g| g.concat()).collect::<Vec<_>>().join(&sep))
     }
 
-    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let input = if mode == Mode::Lenient {
-            input.chars().filter(|c| !c.is_whitespace() || *c == '-').collect::<String>()
-        } else {
-            input.to_string()
-        };
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let (sep, _) = Self::options(opts)?;
 
-        if input.is_empty() {
-            return Ok(Vec::new());
-        }
+        let is_sep = |c: char| Some(c) == sep || (mode == Mode::Lenient && (c.is_whitespace() || c == '-'));
+        let chars: Vec<char> = input.chars().collect();
 
-        let quints: Vec<&str> = input.split('-').filter(|s| !s.is_empty()).collect();
-        let mut result = Vec::with_capacity(quints.len() * 2);
+        let mut groups: Vec<(usize, &[char])> = Vec::new();
+        let mut start = 0;
+        for (i, &c) in chars.iter().enumerate() {
+            if is_sep(c) {
+                if i > start {
+                    groups.push((start, &chars[start..i]));
+                }
+                start = i + 1;
+            }
+        }
+        if chars.len() > start {
+            groups.push((start, &chars[start..]));
+        }
 
-        for (idx, quint) in quints.iter().enumerate() {
-            let val = Self::decode_quint(quint).map_err(|e| {
-                if let MbaseError::InvalidCharacter { char: c, position: p } = e {
-                    MbaseError::InvalidCharacter {
-                        char: c,
-                        position: idx * 6 + p,
-                    }
+        let mut result = Vec::with_capacity(chars.len() / 3);
+        let group_count = groups.len();
+        for (idx, (offset, group)) in groups.into_iter().enumerate() {
+            let is_last_group = idx + 1 == group_count;
+            let mut pos = 0;
+            while pos < group.len() {
+                let remaining = group.len() - pos;
+                let at = offset + pos;
+                let shift = |e: MbaseError| match e {
+                    MbaseError::InvalidCharacter { char: c, position: p } => MbaseError::InvalidCharacter { char: c, position: at + p },
+                    e => e,
+                };
+
+                if remaining >= 5 {
+                    let quint: String = group[pos..pos + 5].iter().collect();
+                    let val = Self::decode_quint(&quint).map_err(shift)?;
+                    result.push((val >> 8) as u8);
+                    result.push((val & 0xFF) as u8);
+                    pos += 5;
+                } else if remaining == 3 && is_last_group {
+                    result.push(Self::decode_syllable(&group[pos..]).map_err(shift)?);
+                    pos += 3;
                 } else {
-                    e
+                    return Err(MbaseError::invalid_length_msg(
+                        LengthConstraint::MultipleOf(5),
+                        group.len(),
+                        format!("group at position {} has {} trailing characters", offset, remaining),
+                    ));
                 }
-            })?;
-            result.push((val >> 8) as u8);
-            result.push((val & 0xFF) as u8);
+            }
         }
 
         Ok(result)
@@ -148,14 +231,10 @@ impl Codec for Proquint {
         }
 
         let valid_quints = parts.iter().filter(|q| q.len() == 5).count();
-        let all_valid_pattern = parts.iter().all(|q| {
+        let last = parts.len() - 1;
+        let all_valid_pattern = parts.iter().enumerate().all(|(i, q)| {
             let chars: Vec<char> = q.chars().collect();
-            chars.len() == 5
-                && consonant_index(chars[0]).is_some()
-                && vowel_index(chars[1]).is_some()
-                && consonant_index(chars[2]).is_some()
-                && vowel_index(chars[3]).is_some()
-                && consonant_index(chars[4]).is_some()
+            Self::is_word(&chars) && (chars.len() == 5 || i == last)
         });
 
         if !all_valid_pattern {
@@ -238,10 +317,49 @@ mod tests {
     }
 
     #[test]
-    fn test_proquint_odd_length_error() {
+    fn test_proquint_odd_length() {
         let codec = Proquint;
-        let result = codec.encode(&[1, 2, 3]);
-        assert!(result.is_err());
+        assert_eq!(codec.encode(&[0x7F, 0x00, 0x01]).unwrap(), "lusab-bad");
+        assert_eq!(codec.encode(&[0xFF]).unwrap(), "zug");
+        assert_eq!(codec.decode("lusab-bad", Mode::Strict).unwrap(), &[0x7F, 0x00, 0x01]);
+
+        for len in 0..9u8 {
+            let input: Vec<u8> = (0..len).map(|i| i.wrapping_mul(37)).collect();
+            let encoded = codec.encode(&input).unwrap();
+            assert_eq!(codec.decode(&encoded, Mode::Strict).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_proquint_syllable_only_at_end() {
+        let codec = Proquint;
+        assert!(codec.decode("bad-lusab", Mode::Strict).is_err());
+        // Final consonant of a syllable only carries two bits.
+        assert!(codec.decode("lusab-bah", Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_proquint_separator_and_group_options() {
+        let codec = Proquint;
+        let data = [0x7F, 0x00, 0x00, 0x01, 0x3F, 0x54, 0xDC];
+
+        let opts = CodecOptions::new().with("sep", ".").with("group", "2");
+        let encoded = codec.encode_with(&data, &opts).unwrap();
+        assert_eq!(encoded, "lusabbabad.gutihtub");
+        assert_eq!(codec.decode_with(&encoded, Mode::Strict, &opts).unwrap(), data);
+
+        let opts = CodecOptions::new().with("sep", "");
+        let encoded = codec.encode_with(&data, &opts).unwrap();
+        assert_eq!(encoded, "lusabbabadgutihtub");
+        assert_eq!(codec.decode_with(&encoded, Mode::Strict, &opts).unwrap(), data);
+    }
+
+    #[test]
+    fn test_proquint_invalid_options() {
+        let codec = Proquint;
+        assert!(codec.encode_with(b"ab", &CodecOptions::new().with("group", "0")).is_err());
+        assert!(codec.encode_with(b"ab", &CodecOptions::new().with("sep", "--")).is_err());
+        assert!(codec.encode_with(b"ab", &CodecOptions::new().with("wrap", "4")).is_err());
     }
 
     #[test]
@@ -1,7 +1,16 @@
 use super::{util, Codec};
 use crate::error::{MbaseError as Error, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
-
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// Punycode (RFC 3492), the Bootstring profile behind IDNA's `xn--` labels.
+///
+/// Basic code points are copied with their case, and the case of each delta's final digit is
+/// the mixed-case annotation: uppercase asks for the decoded letter to be uppercased. By default
+/// an all-ASCII label without `-` encodes to itself and input without a delimiter decodes as
+/// plain ASCII, as IDNA treats them; `--opt rfc3492=true` always emits the delimiter and reads
+/// delimiter-less input as deltas. `--opt annotate=true` encodes uppercase letters outside ASCII
+/// as lowercase plus an annotation, as in the RFC's sample strings.
 pub struct Punycode;
 
 const BASE: u32 = 36;
@@ -25,11 +34,12 @@ fn adapt(mut delta: u32, numpoints: u32, firsttime: bool) -> u32 {
     k + (((BASE - TMIN + 1) * delta) / (delta + SKEW))
 }
 
-fn encode_digit(d: u32) -> char {
-    if d < 26 {
-        (b'a' + d as u8) as char
+fn encode_digit(d: u32, uppercase: bool) -> char {
+    let c = if d < 26 { b'a' + d as u8 } else { b'0' + (d - 26) as u8 };
+    if uppercase {
+        c.to_ascii_uppercase() as char
     } else {
-        (b'0' + (d - 26) as u8) as char
+        c as char
     }
 }
 
@@ -42,172 +52,218 @@ fn decode_digit(c: char) -> Option<u32> {
     }
 }
 
-impl Codec for Punycode {
-    fn meta(&self) -> CodecMeta {
-        CodecMeta {
-            name: "punycode",
-            aliases: &["pcode"],
-            alphabet: "abcdefghijklmnopqrstuvwxyz0123456789-",
-            multibase_code: None,
-            padding: PaddingRule::None,
-            case_sensitivity: CaseSensitivity::Insensitive,
-            description: "Punycode (RFC3492 IDN encoding)",
-        }
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        TMIN
+    } else if k >= bias + TMAX {
+        TMAX
+    } else {
+        k - bias
     }
+}
 
-    fn encode(&self, input: &[u8]) -> Result<String> {
-        let input_str = std::str::from_utf8(input).map_err(|e| Error::invalid_input(format!("invalid UTF-8: {}", e)))?;
+/// The lowercase form of an uppercase letter that the case flag can restore, if any.
+fn annotated_lowercase(c: char) -> Option<char> {
+    let mut lower = c.to_lowercase();
+    let l = lower.next().filter(|_| lower.next().is_none())?;
+    (l != c && flagged_uppercase(l) == c).then_some(l)
+}
 
-        let chars: Vec<u32> = input_str.chars().map(|c| c as u32).collect();
+/// What an uppercase case flag (RFC 3492 appendix A) makes of `c`.
+fn flagged_uppercase(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
 
-        let mut output = String::new();
-        let basic: Vec<char> = chars.iter().filter(|&&c| c < 0x80).map(|&c| c as u8 as char).collect();
+struct PunycodeOptions {
+    /// Follow RFC 3492 to the letter instead of passing plain ASCII labels through.
+    rfc: bool,
+    /// Encode uppercase letters as lowercase plus a mixed-case annotation.
+    annotate: bool,
+}
 
-        let h = basic.len();
-        let b = h;
+impl PunycodeOptions {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("punycode", &["rfc3492", "annotate"])?;
+        Ok(Self {
+            rfc: opts.get_bool("rfc3492")?.unwrap_or(false),
+            annotate: opts.get_bool("annotate")?.unwrap_or(false),
+        })
+    }
+}
 
-        output.push_str(&basic.iter().collect::<String>());
-        if b > 0 && b < chars.len() {
+impl Punycode {
+    fn encode_punycode(input: &[u8], opts: &PunycodeOptions) -> Result<String> {
+        let input_str = core::str::from_utf8(input).map_err(|e| Error::invalid_input(format!("invalid UTF-8: {}", e)))?;
+
+        let (chars, flags): (Vec<u32>, Vec<bool>) = input_str
+            .chars()
+            .map(|c| match annotated_lowercase(c) {
+                Some(lower) if opts.annotate && !c.is_ascii() => (lower as u32, true),
+                _ => (c as u32, false),
+            })
+            .unzip();
+        let overflow = || Error::invalid_input("input too long: punycode delta overflows 32 bits");
+
+        let basic: String = chars.iter().filter(|&&c| c < 0x80).map(|&c| c as u8 as char).collect();
+        let b = basic.len();
+        let mut output = basic;
+        if b > 0 && (opts.rfc || b < chars.len() || output.contains('-')) {
             output.push('-');
         }
 
         let mut n = INITIAL_N;
         let mut delta = 0u32;
         let mut bias = INITIAL_BIAS;
-        let mut h = h;
+        let mut h = b;
 
         while h < chars.len() {
             let m = *chars.iter().filter(|&&c| c >= n).min().unwrap();
-
-            delta = delta
-                .checked_add(
-                    (m - n)
-                        .checked_mul((h + 1) as u32)
-                        .ok_or_else(|| Error::invalid_input("overflow"))?,
-                )
-                .ok_or_else(|| Error::invalid_input("overflow"))?;
+            delta = (m - n)
+                .checked_mul((h + 1) as u32)
+                .and_then(|d| delta.checked_add(d))
+                .ok_or_else(overflow)?;
             n = m;
 
-            for &c in &chars {
+            for (&c, &flag) in chars.iter().zip(&flags) {
                 if c < n {
-                    delta = delta.checked_add(1).ok_or_else(|| Error::invalid_input("overflow"))?;
+                    delta = delta.checked_add(1).ok_or_else(overflow)?;
                 } else if c == n {
                     let mut q = delta;
                     let mut k = BASE;
-
                     loop {
-                        let t = if k <= bias {
-                            TMIN
-                        } else if k >= bias + TMAX {
-                            TMAX
-                        } else {
-                            k - bias
-                        };
-
+                        let t = threshold(k, bias);
                         if q < t {
                             break;
                         }
-
-                        output.push(encode_digit(t + ((q - t) % (BASE - t))));
+                        output.push(encode_digit(t + ((q - t) % (BASE - t)), false));
                         q = (q - t) / (BASE - t);
                         k += BASE;
                     }
-
-                    output.push(encode_digit(q));
+                    output.push(encode_digit(q, flag));
                     bias = adapt(delta, (h + 1) as u32, h == b);
                     delta = 0;
                     h += 1;
                 }
             }
 
-            delta += 1;
+            delta = delta.checked_add(1).ok_or_else(overflow)?;
             n += 1;
         }
 
         Ok(output)
     }
 
-    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let cleaned = if mode == Mode::Lenient {
-            input.chars().filter(|c| !c.is_whitespace()).collect::<String>()
-        } else {
-            input.to_string()
-        };
-
-        if cleaned.is_empty() {
+    fn decode_punycode(input: &str, mode: Mode, opts: &PunycodeOptions) -> Result<Vec<u8>> {
+        // Characters with their position in `input`, for errors that explain can point at.
+        let chars: Vec<(usize, char)> = input
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| mode == Mode::Strict || !c.is_whitespace())
+            .collect();
+        if chars.is_empty() {
             return Ok(Vec::new());
         }
 
-        let input_lower = cleaned.to_lowercase();
-        let delimiter_pos = input_lower.rfind('-');
+        let (basic, encoded) = match chars.iter().rposition(|&(_, c)| c == '-') {
+            Some(delimiter) => (&chars[..delimiter], &chars[delimiter + 1..]),
+            None if opts.rfc => (&chars[..0], &chars[..]),
+            None => (&chars[..], &chars[..0]),
+        };
 
-        let (basic, encoded) = match delimiter_pos {
-            Some(pos) => {
-                let basic = &input_lower[..pos];
-                let encoded = &input_lower[pos + 1..];
-                (basic.to_string(), encoded)
-            }
-            None => {
-                // No delimiter means all characters are basic (ASCII-only)
-                (input_lower.clone(), "")
+        let mut output: Vec<(char, bool)> = Vec::with_capacity(chars.len());
+        for &(position, c) in basic {
+            if !c.is_ascii() && mode == Mode::Strict {
+                return Err(Error::InvalidCharacter { char: c, position });
             }
-        };
+            output.push((c, false));
+        }
 
-        let mut output: Vec<u32> = basic.chars().map(|c| c as u32).collect();
         let mut n = INITIAL_N;
         let mut i = 0u32;
         let mut bias = INITIAL_BIAS;
+        let mut digits = encoded.iter();
 
-        let mut pos = 0;
-        while pos < encoded.len() {
+        while let Some(&(start, _)) = digits.as_slice().first() {
             let oldi = i;
             let mut w = 1u32;
             let mut k = BASE;
-
-            loop {
-                if pos >= encoded.len() {
-                    return Err(Error::invalid_input("truncated input"));
-                }
-
-                let c = encoded.chars().nth(pos).unwrap();
-                pos += 1;
-
-                let digit = decode_digit(c).ok_or_else(|| Error::invalid_input(format!("invalid punycode digit: '{}'", c)))?;
-
-                i = i
-                    .checked_add(digit.checked_mul(w).ok_or_else(|| Error::invalid_input("overflow"))?)
-                    .ok_or_else(|| Error::invalid_input("overflow"))?;
-
-                let t = if k <= bias {
-                    TMIN
-                } else if k >= bias + TMAX {
-                    TMAX
-                } else {
-                    k - bias
+            let (position, uppercase) = loop {
+                let Some(&(position, c)) = digits.next() else {
+                    return Err(Error::invalid_input_at(start, "truncated: the delta starting here is missing digits"));
                 };
-
+                let digit = decode_digit(c).ok_or(Error::InvalidCharacter { char: c, position })?;
+                i = digit
+                    .checked_mul(w)
+                    .and_then(|d| i.checked_add(d))
+                    .ok_or_else(|| Error::invalid_input_at(position, "delta overflows 32 bits"))?;
+                let t = threshold(k, bias);
                 if digit < t {
-                    break;
+                    break (position, c.is_ascii_uppercase());
                 }
-
-                w = w.checked_mul(BASE - t).ok_or_else(|| Error::invalid_input("overflow"))?;
+                w = w
+                    .checked_mul(BASE - t)
+                    .ok_or_else(|| Error::invalid_input_at(position, "delta overflows 32 bits"))?;
                 k += BASE;
-            }
+            };
 
-            bias = adapt(i - oldi, (output.len() + 1) as u32, oldi == 0);
+            let len = (output.len() + 1) as u32;
+            bias = adapt(i - oldi, len, oldi == 0);
             n = n
-                .checked_add(i / ((output.len() + 1) as u32))
-                .ok_or_else(|| Error::invalid_input("overflow"))?;
-            i %= (output.len() + 1) as u32;
-
-            output.insert(i as usize, n);
+                .checked_add(i / len)
+                .ok_or_else(|| Error::invalid_input_at(position, "code point overflows 32 bits"))?;
+            i %= len;
+
+            let c = match char::from_u32(n) {
+                Some(c) => c,
+                None if mode == Mode::Lenient => char::REPLACEMENT_CHARACTER,
+                None => {
+                    return Err(Error::invalid_input_at(position, format!("decodes to U+{:X}, which is not a Unicode scalar value", n)))
+                }
+            };
+            output.insert(i as usize, (c, uppercase));
             i += 1;
         }
 
-        let result: String = output.iter().filter_map(|&c| char::from_u32(c)).collect();
-
+        let result: String = output
+            .into_iter()
+            .map(|(c, uppercase)| if uppercase { flagged_uppercase(c) } else { c })
+            .collect();
         Ok(result.into_bytes())
     }
+}
+
+impl Codec for Punycode {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "punycode",
+            aliases: &["pcode"],
+            alphabet: "abcdefghijklmnopqrstuvwxyz0123456789-",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Punycode (RFC3492 IDN encoding)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Self::encode_punycode(input, &PunycodeOptions::from_options(opts)?)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        Self::decode_punycode(input, mode, &PunycodeOptions::from_options(opts)?)
+    }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
         if input.is_empty() {
@@ -327,11 +383,167 @@ mod tests {
     }
 
     #[test]
-    fn test_punycode_case_insensitive() {
+    fn test_punycode_case_annotation() {
+        let codec = Punycode;
+        // Basic code points keep their case; an uppercase final digit uppercases its letter.
+        assert_eq!(codec.decode("bcher-kva", Mode::Strict).unwrap(), "bücher".as_bytes());
+        assert_eq!(codec.decode("BCHER-KVA", Mode::Strict).unwrap(), "BÜCHER".as_bytes());
+        assert_eq!(codec.decode("bcher-kvA", Mode::Strict).unwrap(), "bÜcher".as_bytes());
+        assert_eq!(codec.decode("BCHER-kva", Mode::Strict).unwrap(), "BüCHER".as_bytes());
+
+        let annotate = CodecOptions::new().with("annotate", "true");
+        assert_eq!(codec.encode_with("BÜCHER".as_bytes(), &annotate).unwrap(), "BCHER-kvA");
+        assert_eq!(codec.encode("BÜCHER".as_bytes()).unwrap(), "BCHER-2pa");
+    }
+
+    fn utf8(code_points: &[u32]) -> Vec<u8> {
+        code_points
+            .iter()
+            .map(|&c| char::from_u32(c).unwrap())
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    /// RFC 3492 section 7.1; uppercase `U+` marks code points carrying an uppercase flag.
+    #[test]
+    fn test_punycode_rfc3492_vectors() {
+        let vectors: &[(&[u32], &str)] = &[
+            (
+                &[
+                    0x0644, 0x064A, 0x0647, 0x0645, 0x0627, 0x0628, 0x062A, 0x0643, 0x0644, 0x0645, 0x0648, 0x0634, 0x0639, 0x0631, 0x0628,
+                    0x064A, 0x061F,
+                ],
+                "egbpdaj6bu4bxfgehfvwxn",
+            ),
+            (&[0x4ED6, 0x4EEC, 0x4E3A, 0x4EC0, 0x4E48, 0x4E0D, 0x8BF4, 0x4E2D, 0x6587], "ihqwcrb4cv8a8dqg056pqjye"),
+            (&[0x4ED6, 0x5011, 0x7232, 0x4EC0, 0x9EBD, 0x4E0D, 0x8AAA, 0x4E2D, 0x6587], "ihqwctvzc91f659drss3x8bo0yb"),
+            (
+                &[
+                    0x0050, 0x0072, 0x006F, 0x010D, 0x0070, 0x0072, 0x006F, 0x0073, 0x0074, 0x011B, 0x006E, 0x0065, 0x006D, 0x006C, 0x0075,
+                    0x0076, 0x00ED, 0x010D, 0x0065, 0x0073, 0x006B, 0x0079,
+                ],
+                "Proprostnemluvesky-uyb24dma41a",
+            ),
+            (
+                &[
+                    0x05DC, 0x05DE, 0x05D4, 0x05D4, 0x05DD, 0x05E4, 0x05E9, 0x05D5, 0x05D8, 0x05DC, 0x05D0, 0x05DE, 0x05D3, 0x05D1, 0x05E8,
+                    0x05D9, 0x05DD, 0x05E2, 0x05D1, 0x05E8, 0x05D9, 0x05EA,
+                ],
+                "4dbcagdahymbxekheh6e0a7fei0b",
+            ),
+            (
+                &[
+                    0x092F, 0x0939, 0x0932, 0x094B, 0x0917, 0x0939, 0x093F, 0x0928, 0x094D, 0x0926, 0x0940, 0x0915, 0x094D, 0x092F, 0x094B,
+                    0x0902, 0x0928, 0x0939, 0x0940, 0x0902, 0x092C, 0x094B, 0x0932, 0x0938, 0x0915, 0x0924, 0x0947, 0x0939, 0x0948, 0x0902,
+                ],
+                "i1baa7eci9glrd9b2ae1bj0hfcgg6iyaf8o0a1dig0cd",
+            ),
+            (
+                &[
+                    0x306A, 0x305C, 0x307F, 0x3093, 0x306A, 0x65E5, 0x672C, 0x8A9E, 0x3092, 0x8A71, 0x3057, 0x3066, 0x304F, 0x308C, 0x306A,
+                    0x3044, 0x306E, 0x304B,
+                ],
+                "n8jok5ay5dzabd5bym9f0cm5685rrjetr6pdxa",
+            ),
+            (
+                &[
+                    0xC138, 0xACC4, 0xC758, 0xBAA8, 0xB4E0, 0xC0AC, 0xB78C, 0xB4E4, 0xC774, 0xD55C, 0xAD6D, 0xC5B4, 0xB97C, 0xC774, 0xD574,
+                    0xD55C, 0xB2E4, 0xBA74, 0xC5BC, 0xB9C8, 0xB098, 0xC88B, 0xC744, 0xAE4C,
+                ],
+                "989aomsvi5e83db1d2a355cv1e0vak1dwrv93d5xbh15a0dt30a5jpsd879ccm6fea98c",
+            ),
+            (
+                &[
+                    0x0050, 0x006F, 0x0072, 0x0071, 0x0075, 0x00E9, 0x006E, 0x006F, 0x0070, 0x0075, 0x0065, 0x0064, 0x0065, 0x006E, 0x0073,
+                    0x0069, 0x006D, 0x0070, 0x006C, 0x0065, 0x006D, 0x0065, 0x006E, 0x0074, 0x0065, 0x0068, 0x0061, 0x0062, 0x006C, 0x0061,
+                    0x0072, 0x0065, 0x006E, 0x0045, 0x0073, 0x0070, 0x0061, 0x00F1, 0x006F, 0x006C,
+                ],
+                "PorqunopuedensimplementehablarenEspaol-fmd56a",
+            ),
+            (
+                &[
+                    0x0054, 0x1EA1, 0x0069, 0x0073, 0x0061, 0x006F, 0x0068, 0x1ECD, 0x006B, 0x0068, 0x00F4, 0x006E, 0x0067, 0x0074, 0x0068,
+                    0x1EC3, 0x0063, 0x0068, 0x1EC9, 0x006E, 0x00F3, 0x0069, 0x0074, 0x0069, 0x1EBF, 0x006E, 0x0067, 0x0056, 0x0069, 0x1EC7,
+                    0x0074,
+                ],
+                "TisaohkhngthchnitingVit-kjcr8268qyxafd2f1b9g",
+            ),
+            (&[0x0033, 0x5E74, 0x0042, 0x7D44, 0x91D1, 0x516B, 0x5148, 0x751F], "3B-ww4c5e180e575a65lsy2b"),
+            (
+                &[
+                    0x5B89, 0x5BA4, 0x5948, 0x7F8E, 0x6075, 0x002D, 0x0077, 0x0069, 0x0074, 0x0068, 0x002D, 0x0053, 0x0055, 0x0050, 0x0045,
+                    0x0052, 0x002D, 0x004D, 0x004F, 0x004E, 0x004B, 0x0045, 0x0059, 0x0053,
+                ],
+                "-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n",
+            ),
+            (
+                &[
+                    0x0048, 0x0065, 0x006C, 0x006C, 0x006F, 0x002D, 0x0041, 0x006E, 0x006F, 0x0074, 0x0068, 0x0065, 0x0072, 0x002D, 0x0057,
+                    0x0061, 0x0079, 0x002D, 0x305D, 0x308C, 0x305E, 0x308C, 0x306E, 0x5834, 0x6240,
+                ],
+                "Hello-Another-Way--fc4qua05auwb3674vfr0b",
+            ),
+            (&[0x3072, 0x3068, 0x3064, 0x5C4B, 0x6839, 0x306E, 0x4E0B, 0x0032], "2-u9tlzr9756bt3uc0v"),
+            (
+                &[
+                    0x004D, 0x0061, 0x006A, 0x0069, 0x3067, 0x004B, 0x006F, 0x0069, 0x3059, 0x308B, 0x0035, 0x79D2, 0x524D,
+                ],
+                "MajiKoi5-783gue6qz075azm5e",
+            ),
+            (&[0x30D1, 0x30D5, 0x30A3, 0x30FC, 0x0064, 0x0065, 0x30EB, 0x30F3, 0x30D0], "de-jg4avhby1noc0d"),
+            (&[0x305D, 0x306E, 0x30B9, 0x30D4, 0x30FC, 0x30C9, 0x3067], "d9juau41awczczp"),
+            (
+                &[
+                    0x002D, 0x003E, 0x0020, 0x0024, 0x0031, 0x002E, 0x0030, 0x0030, 0x0020, 0x003C, 0x002D,
+                ],
+                "-> $1.00 <--",
+            ),
+        ];
+        let rfc = CodecOptions::new().with("rfc3492", "true");
+        for (code_points, encoded) in vectors {
+            let text = utf8(code_points);
+            assert_eq!(Punycode.encode_with(&text, &rfc).unwrap(), *encoded);
+            assert_eq!(Punycode.decode_with(encoded, Mode::Strict, &rfc).unwrap(), text, "{}", encoded);
+        }
+
+        // (I) Russian: U+043F is flagged, which shows as the uppercase `D`.
+        let russian = "Почемужеонинеговорятпорусски";
+        let annotate = rfc.clone().with("annotate", "true");
+        assert_eq!(Punycode.encode_with(russian.as_bytes(), &annotate).unwrap(), "b1abfaaepdrnnbgefbaDotcwatmq2g4l");
+        assert_eq!(
+            Punycode
+                .decode_with("b1abfaaepdrnnbgefbaDotcwatmq2g4l", Mode::Strict, &rfc)
+                .unwrap(),
+            russian.as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_punycode_ascii_labels() {
+        let rfc = CodecOptions::new().with("rfc3492", "true");
+        assert_eq!(Punycode.encode_with(b"hello", &rfc).unwrap(), "hello-");
+        assert_eq!(Punycode.decode_with("hello-", Mode::Strict, &rfc).unwrap(), b"hello");
+        // Without a delimiter RFC input is all deltas; the default reads it as plain ASCII.
+        assert_eq!(Punycode.decode("d9juau41awczczp", Mode::Strict).unwrap(), b"d9juau41awczczp");
+
+        // A hyphen in an ASCII label would be taken for the delimiter, so one is added.
+        assert_eq!(Punycode.encode(b"a-b").unwrap(), "a-b-");
+        assert_eq!(Punycode.decode("a-b-", Mode::Strict).unwrap(), b"a-b");
+    }
+
+    #[test]
+    fn test_punycode_positioned_errors() {
         let codec = Punycode;
-        let lower = codec.decode("bcher-kva", Mode::Strict).unwrap();
-        let upper = codec.decode("BCHER-KVA", Mode::Strict).unwrap();
-        assert_eq!(lower, upper);
+        assert!(matches!(codec.decode("bcher-kv!", Mode::Strict), Err(Error::InvalidCharacter { char: '!', position: 8 })));
+        assert!(matches!(codec.decode("bücher-kva", Mode::Strict), Err(Error::InvalidCharacter { char: 'ü', position: 1 })));
+        assert!(codec.decode("bücher-kva", Mode::Lenient).is_ok());
+        // `9` (35) is never below a threshold, so the delta starting at `k` never ends.
+        assert!(matches!(codec.decode("abc-k9", Mode::Strict), Err(Error::InvalidInputAt { position: 4, .. })));
+        assert!(matches!(codec.decode("x-99999999999", Mode::Strict), Err(Error::InvalidInputAt { .. })));
+        // A delta past U+10FFFF is rejected in strict mode and replaced in lenient mode.
+        let beyond = "-9999999b";
+        assert!(matches!(codec.decode(beyond, Mode::Strict), Err(Error::InvalidInputAt { position: 8, .. })));
+        assert_eq!(codec.decode(beyond, Mode::Lenient).unwrap(), "\u{fffd}".as_bytes());
     }
 
     #[test]
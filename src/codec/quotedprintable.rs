@@ -1,6 +1,7 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
 
 const HEX_UPPER: &[u8; 16] = b"0123456789ABCDEF";
 
@@ -17,6 +18,93 @@ fn is_safe_char(b: u8) -> bool {
     matches!(b, 33..=60 | 62..=126) && b != b'='
 }
 
+/// Encoder settings, from `--opt line=N`, `--opt binary=false` and `--opt eol=crlf|lf`.
+struct QpOptions {
+    /// Longest encoded line including the `=` of a soft break; 0 never wraps.
+    line: usize,
+    /// Escape CR and LF like any other byte; otherwise line breaks in the input stay hard breaks.
+    binary: bool,
+    eol: &'static str,
+}
+
+impl Default for QpOptions {
+    fn default() -> Self {
+        Self {
+            line: 76,
+            binary: true,
+            eol: "\r\n",
+        }
+    }
+}
+
+impl QpOptions {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("quoted-printable", &["line", "binary", "eol"])?;
+        let mut qp = Self::default();
+        if let Some(line) = opts.get_usize("line")? {
+            if line != 0 && line < 4 {
+                return Err(MbaseError::invalid_input("option 'line' must be 0 (no wrapping) or at least 4"));
+            }
+            qp.line = line;
+        }
+        qp.binary = opts.get_bool("binary")?.unwrap_or(qp.binary);
+        qp.eol = match opts.get("eol") {
+            None | Some("crlf") => "\r\n",
+            Some("lf") => "\n",
+            Some(other) => return Err(MbaseError::invalid_input(format!("option 'eol' expects crlf or lf, got '{}'", other))),
+        };
+        Ok(qp)
+    }
+
+    /// Length of the line break starting at `i` in text mode (LF or CRLF), if any.
+    fn hard_break(&self, input: &[u8], i: usize) -> Option<usize> {
+        match input.get(i..) {
+            _ if self.binary => None,
+            Some([b'\n', ..]) => Some(1),
+            Some([b'\r', b'\n', ..]) => Some(2),
+            _ => None,
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> String {
+        let mut result = String::new();
+        let mut line_len = 0;
+        let mut i = 0;
+
+        while i < input.len() {
+            if let Some(len) = self.hard_break(input, i) {
+                result.push_str(self.eol);
+                line_len = 0;
+                i += len;
+                continue;
+            }
+
+            let byte = input[i];
+            // RFC 2045 6.7 (3): space and tab may be literal in text, but never at the end of a line.
+            let at_eol = i + 1 == input.len() || self.hard_break(input, i + 1).is_some();
+            let literal = is_safe_char(byte) || (!self.binary && matches!(byte, b' ' | b'\t') && !at_eol);
+            let width = if literal { 1 } else { 3 };
+
+            if self.line > 0 && line_len + width > self.line - 1 {
+                result.push('=');
+                result.push_str(self.eol);
+                line_len = 0;
+            }
+            if literal {
+                result.push(byte as char);
+            } else {
+                result.push('=');
+                result.push(HEX_UPPER[(byte >> 4) as usize] as char);
+                result.push(HEX_UPPER[(byte & 0x0F) as usize] as char);
+            }
+            line_len += width;
+            i += 1;
+        }
+
+        result
+    }
+}
+
 pub struct QuotedPrintable;
 
 impl Codec for QuotedPrintable {
@@ -32,37 +120,16 @@ impl Codec for QuotedPrintable {
         }
     }
 
-    fn encode(&self, input: &[u8]) -> Result<String> {
-        if input.is_empty() {
-            return Ok(String::new());
-        }
-
-        let mut result = String::new();
-        let mut line_len = 0;
-
-        for &byte in input {
-            let encoded = if is_safe_char(byte) {
-                let c = byte as char;
-                if line_len >= 75 {
-                    result.push_str("=\r\n");
-                    line_len = 0;
-                }
-                result.push(c);
-                line_len += 1;
-                continue;
-            } else {
-                format!("={}{}", HEX_UPPER[(byte >> 4) as usize] as char, HEX_UPPER[(byte & 0x0F) as usize] as char)
-            };
+    fn is_symbol(&self, c: char) -> bool {
+        c == ' ' || c == '\t' || c.is_ascii_graphic()
+    }
 
-            if line_len + encoded.len() > 75 {
-                result.push_str("=\r\n");
-                line_len = 0;
-            }
-            result.push_str(&encoded);
-            line_len += encoded.len();
-        }
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        Ok(QpOptions::default().encode(input))
+    }
 
-        Ok(result)
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        Ok(QpOptions::from_options(opts)?.encode(input))
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
@@ -183,6 +250,10 @@ impl Codec for QuotedPrintable {
             warnings: vec![],
         }
     }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -264,4 +335,35 @@ mod tests {
         let score = codec.detect_score("Hello=20World=3D=C3=A9");
         assert!(score.confidence >= 0.6);
     }
+
+    #[test]
+    fn test_qp_line_length_option() {
+        let codec = QuotedPrintable;
+        let input = [b'a'; 100];
+        let default = codec.encode(&input).unwrap();
+        assert_eq!(default.split("=\r\n").next().unwrap().len(), 75);
+
+        let opts = CodecOptions::new().with("line", "20");
+        let encoded = codec.encode_with(&input, &opts).unwrap();
+        assert!(encoded.split("\r\n").all(|line| line.len() <= 20));
+        assert_eq!(codec.decode(&encoded, Mode::Strict).unwrap(), input);
+
+        let unwrapped = codec.encode_with(&input, &CodecOptions::new().with("line", "0")).unwrap();
+        assert_eq!(unwrapped.len(), 100);
+        assert!(codec.encode_with(&input, &CodecOptions::new().with("line", "3")).is_err());
+    }
+
+    #[test]
+    fn test_qp_text_mode() {
+        let codec = QuotedPrintable;
+        let opts = CodecOptions::new().with("binary", "false");
+        let encoded = codec.encode_with(b"Hello World \r\nnext\tline\t", &opts).unwrap();
+        assert_eq!(encoded, "Hello World=20\r\nnext\tline=09");
+        assert_eq!(codec.decode(&encoded, Mode::Strict).unwrap(), b"Hello World \r\nnext\tline\t");
+
+        let lf = opts.clone().with("eol", "lf");
+        assert_eq!(codec.encode_with(b"a\nb\rc", &lf).unwrap(), "a\nb=0Dc");
+        assert_eq!(codec.encode_with(b"a\nb", &CodecOptions::new()).unwrap(), "a=0Ab");
+        assert!(codec.encode_with(b"a", &CodecOptions::new().with("eol", "cr")).is_err());
+    }
 }
@@ -1,38 +1,22 @@
-use std::collections::HashMap;
-use std::sync::OnceLock;
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use once_cell::race::OnceBox;
 
 use super::Codec;
-use crate::error::{MbaseError, Result};
-use crate::types::CodecMeta;
+use crate::error::{MbaseError, RegistryConflictKind, Result};
+use crate::prelude::*;
+use crate::types::{CodecMeta, CodecMetadata, DetectCandidate};
 
+/// Registers codecs with their stable numeric IDs.
+///
+/// IDs are part of the public interface: they are never renumbered or reused, and a new codec takes
+/// the next free number wherever it is listed. List order only decides `list()` and detection order.
 macro_rules! register_codecs {
-    ($($module:ident :: $codec:ident),* $(,)?) => {
-        fn build_registry() -> Registry {
-            let codecs: Vec<Box<dyn Codec>> = vec![
-                $(Box::new(super::$module::$codec)),*
-            ];
-
-            let mut name_map = HashMap::new();
-            for (idx, codec) in codecs.iter().enumerate() {
-                name_map.insert(codec.name(), idx);
-                for alias in codec.meta().aliases {
-                    name_map.insert(*alias, idx);
-                }
-            }
-
-            let mut multibase_codes: HashMap<char, &str> = HashMap::new();
-            for codec in codecs.iter() {
-                if let Some(code) = codec.meta().multibase_code {
-                    if let Some(existing) = multibase_codes.insert(code, codec.name()) {
-                        panic!(
-                            "Duplicate multibase code '{}' for codecs '{}' and '{}'",
-                            code, existing, codec.name()
-                        );
-                    }
-                }
-            }
-
-            Registry { codecs, name_map }
+    ($($module:ident :: $codec:ident = $id:literal),* $(,)?) => {
+        fn registered_codecs() -> Vec<(u16, Box<dyn Codec>)> {
+            vec![
+                $(($id, Box::new(super::$module::$codec))),*
+            ]
         }
 
         // Public for testing - generates list of expected codec names
@@ -46,93 +30,385 @@ macro_rules! register_codecs {
 }
 
 register_codecs! {
-    atbash::Atbash,
-    base2_8::Base2,
-    base2_8::Base8,
-    base16::Base16Lower,
-    base16::Base16Upper,
-    base32::Base32Lower,
-    base32::Base32Upper,
-    base32::Base32PadLower,
-    base32::Base32PadUpper,
-    base32::Base32HexLower,
-    base32::Base32HexUpper,
-    base32::Base32HexPadLower,
-    base32::Base32HexPadUpper,
-    base32human::ZBase32,
-    base32human::Crockford32,
-    base32wordsafe::Base32WordSafe,
-    base36::Base36Lower,
-    base36::Base36Upper,
-    base37::Base37,
-    base45::Base45,
-    base58::Base58Btc,
-    base58::Base58Flickr,
-    base58::Base58Check,
-    base58ripple::Base58Ripple,
-    base62::Base62,
-    base64::Base64,
-    base64::Base64Pad,
-    base64::Base64Url,
-    base64::Base64UrlPad,
-    base65536::Base65536,
-    base85::Ascii85,
-    base85::Z85,
-    base85chunked::Base85Chunked,
-    base85rfc1924::Base85Rfc1924,
-    base91::Base91,
-    base92::Base92,
-    baudot::Baudot,
-    bech32::Bech32Codec,
-    bech32::Bech32mCodec,
-    braille::Braille,
-    bubblebabble::BubbleBabble,
-    ipv6::Ipv6,
-    morse::Morse,
-    proquint::Proquint,
-    punycode::Punycode,
-    quotedprintable::QuotedPrintable,
-    rot::Rot13,
-    rot::Rot47,
-    simple_text::A1Z26,
-    simple_text::Rot18,
-    unicode_tap::UnicodeCodepoints,
-    unicode_tap::TapCode,
-    uuencode::Uuencode,
-    urlencoding::UrlEncoding,
+    affine::Affine = 1,
+    affine::Atbash = 2,
+    affine::Caesar = 3,
+    base2_8::Base2 = 4,
+    base2_8::Base8 = 5,
+    base16::Base16Lower = 6,
+    base16::Base16Upper = 7,
+    hexdump::Hexdump = 72,
+    base32::Base32Lower = 8,
+    base32::Base32Upper = 9,
+    base32::Base32PadLower = 10,
+    base32::Base32PadUpper = 11,
+    base32::Base32HexLower = 12,
+    base32::Base32HexUpper = 13,
+    base32::Base32HexPadLower = 14,
+    base32::Base32HexPadUpper = 15,
+    base32human::ZBase32 = 16,
+    base32human::Crockford32 = 17,
+    base32human::Clockwork32 = 83,
+    base32wordsafe::Base32WordSafe = 18,
+    base36::Base36Lower = 19,
+    base36::Base36Upper = 20,
+    base37::Base37 = 21,
+    base37::Base37Name = 77,
+    base45::Base45 = 22,
+    base58::Base58Btc = 23,
+    base58::Base58Flickr = 24,
+    base58::Base58Check = 25,
+    base58::Cb58 = 26,
+    base58ripple::Base58Ripple = 27,
+    base58ripple::Base58RippleCheck = 28,
+    base62::Base62 = 29,
+    base64::Base64 = 30,
+    base64::Base64Pad = 31,
+    base64::Base64Url = 32,
+    base64::Base64UrlPad = 33,
+    base64::Base64UrlJwt = 34,
+    base64::Base64Crypt = 75,
+    base64::Base64Bcrypt = 76,
+    base65536::Base65536 = 35,
+    base85::Ascii85 = 36,
+    base85::Z85 = 37,
+    base85::Z85Padded = 65,
+    base85chunked::Base85Chunked = 38,
+    base85chunked::Base85Python = 84,
+    base85rfc1924::Base85Rfc1924 = 39,
+    base91::Base91 = 40,
+    base92::Base92 = 41,
+    base92::Base92Chunked = 71,
+    baudot::Baudot = 42,
+    baudot::BaudotUs = 43,
+    bech32::Bech32Codec = 44,
+    bech32::Bech32mCodec = 45,
+    bip39::Bip39 = 66,
+    bitmap::Blocks = 46,
+    bitmap::BrailleRaster = 47,
+    braille::Braille = 48,
+    bubblebabble::BubbleBabble = 49,
+    codepage::Ebcdic = 78,
+    codepage::Petscii = 79,
+    diceware::Diceware = 67,
+    electrum::Electrum = 68,
+    ipv6::Ipv6 = 50,
+    mac::Mac = 82,
+    monero::Monero = 69,
+    morse::Morse = 51,
+    proquint::Proquint = 52,
+    punycode::Punycode = 53,
+    quotedprintable::QuotedPrintable = 54,
+    rot::Rot13 = 55,
+    rot::Rot47 = 56,
+    shellquote::ShellQuote = 80,
+    simple_text::A1Z26 = 57,
+    simple_text::Rot18 = 58,
+    slug::Slug = 81,
+    strkey::StrKey = 59,
+    translate::Translate = 60,
+    triplet::Triplet = 70,
+    unicode_tap::UnicodeCodepoints = 61,
+    unicode_tap::TapCode = 62,
+    unicode_tap::TapCode6 = 73,
+    unicode_tap::TapCodeCyrillic = 74,
+    uuencode::Uuencode = 63,
+    urlencoding::UrlEncoding = 64,
+}
+
+/// Codecs that are variants of one encoding (alphabet, padding or checksum differ). Codecs not
+/// listed are a family of their own.
+const FAMILIES: &[(&str, &[&str])] = &[
+    ("base16", &["base16lower", "base16upper"]),
+    (
+        "base32",
+        &[
+            "base32lower",
+            "base32upper",
+            "base32padlower",
+            "base32padupper",
+            "base32hexlower",
+            "base32hexupper",
+            "base32hexpadlower",
+            "base32hexpadupper",
+            "zbase32",
+            "crockford32",
+            "clockwork32",
+            "base32wordsafe",
+        ],
+    ),
+    ("base36", &["base36lower", "base36upper"]),
+    ("base37", &["base37", "base37name"]),
+    (
+        "base58",
+        &[
+            "base58btc",
+            "base58flickr",
+            "base58check",
+            "cb58",
+            "base58ripple",
+            "base58ripplecheck",
+        ],
+    ),
+    ("base64", &["base64", "base64pad", "base64url", "base64urlpad", "base64url-jwt"]),
+    ("base64crypt", &["base64crypt", "base64bcrypt"]),
+    ("base85", &["ascii85", "z85", "z85padded", "base85chunked", "base85python", "base85rfc1924"]),
+    ("base92", &["base92", "base92chunked"]),
+    ("baudot", &["baudot", "baudot-us"]),
+    ("bech32", &["bech32", "bech32m"]),
+    ("bitmap", &["blocks", "brailleraster"]),
+    ("cipher", &["affine", "atbash", "caesar", "rot13", "rot18", "rot47"]),
+    ("tapcode", &["tapcode", "tapcode6", "tapcode-ru"]),
+    ("mnemonic", &["bip39", "bubblebabble", "diceware", "electrum", "monero", "proquint", "triplet"]),
+];
+
+/// The normative reference of each codec that follows a published specification: codec, short
+/// title and URL. Codecs without an external spec are not listed.
+const SPECS: &[(&str, &str, &str)] = &[
+    ("base16lower", "RFC 4648 §8", "https://www.rfc-editor.org/rfc/rfc4648#section-8"),
+    ("base16upper", "RFC 4648 §8", "https://www.rfc-editor.org/rfc/rfc4648#section-8"),
+    ("base32lower", "RFC 4648 §6", "https://www.rfc-editor.org/rfc/rfc4648#section-6"),
+    ("base32upper", "RFC 4648 §6", "https://www.rfc-editor.org/rfc/rfc4648#section-6"),
+    ("base32padlower", "RFC 4648 §6", "https://www.rfc-editor.org/rfc/rfc4648#section-6"),
+    ("base32padupper", "RFC 4648 §6", "https://www.rfc-editor.org/rfc/rfc4648#section-6"),
+    ("base32hexlower", "RFC 4648 §7", "https://www.rfc-editor.org/rfc/rfc4648#section-7"),
+    ("base32hexupper", "RFC 4648 §7", "https://www.rfc-editor.org/rfc/rfc4648#section-7"),
+    ("base32hexpadlower", "RFC 4648 §7", "https://www.rfc-editor.org/rfc/rfc4648#section-7"),
+    ("base32hexpadupper", "RFC 4648 §7", "https://www.rfc-editor.org/rfc/rfc4648#section-7"),
+    ("zbase32", "z-base-32", "https://philzimmermann.com/docs/human-oriented-base-32-encoding.txt"),
+    ("crockford32", "Crockford Base32", "https://www.crockford.com/base32.html"),
+    ("base45", "RFC 9285", "https://www.rfc-editor.org/rfc/rfc9285"),
+    ("base58btc", "draft-msporny-base58", "https://datatracker.ietf.org/doc/html/draft-msporny-base58-03"),
+    ("base58check", "Base58Check", "https://en.bitcoin.it/wiki/Base58Check_encoding"),
+    ("base64", "RFC 4648 §4", "https://www.rfc-editor.org/rfc/rfc4648#section-4"),
+    ("base64pad", "RFC 4648 §4", "https://www.rfc-editor.org/rfc/rfc4648#section-4"),
+    ("base64url", "RFC 4648 §5", "https://www.rfc-editor.org/rfc/rfc4648#section-5"),
+    ("base64urlpad", "RFC 4648 §5", "https://www.rfc-editor.org/rfc/rfc4648#section-5"),
+    ("base64url-jwt", "RFC 7515 §2", "https://www.rfc-editor.org/rfc/rfc7515#section-2"),
+    ("base65536", "base65536", "https://github.com/qntm/base65536"),
+    ("z85", "ZeroMQ RFC 32", "https://rfc.zeromq.org/spec/32/"),
+    ("base85python", "Python base64.b85encode", "https://docs.python.org/3/library/base64.html#base64.b85encode"),
+    ("base85rfc1924", "RFC 1924", "https://www.rfc-editor.org/rfc/rfc1924"),
+    ("base91", "basE91", "https://base91.sourceforge.net/"),
+    ("baudot", "ITU-T S.1 (ITA2)", "https://www.itu.int/rec/T-REC-S.1"),
+    ("bech32", "BIP 173", "https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki"),
+    ("bech32m", "BIP 350", "https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki"),
+    ("bip39", "BIP 39", "https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki"),
+    ("braille", "Unicode Braille Patterns", "https://www.unicode.org/charts/PDF/U2800.pdf"),
+    ("electrum", "Electrum seed version system", "https://electrum.readthedocs.io/en/latest/seedphrase.html"),
+    ("ipv6", "RFC 5952", "https://www.rfc-editor.org/rfc/rfc5952"),
+    ("monero", "Monero base58", "https://monerodocs.org/cryptography/base58/"),
+    ("morse", "ITU-R M.1677-1", "https://www.itu.int/rec/R-REC-M.1677-1-200910-I/"),
+    ("proquint", "arXiv:0901.4016", "https://arxiv.org/abs/0901.4016"),
+    ("punycode", "RFC 3492", "https://www.rfc-editor.org/rfc/rfc3492"),
+    ("quoted-printable", "RFC 2045 §6.7", "https://www.rfc-editor.org/rfc/rfc2045#section-6.7"),
+    ("strkey", "Stellar SEP-23", "https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0023.md"),
+    ("urlencoding", "RFC 3986 §2.1", "https://www.rfc-editor.org/rfc/rfc3986#section-2.1"),
+    ("uuencode", "POSIX uuencode", "https://pubs.opengroup.org/onlinepubs/9799919799/utilities/uuencode.html"),
+];
+
+fn boxed_to_shared(codecs: Vec<(u16, Box<dyn Codec>)>) -> Vec<(u16, Arc<dyn Codec>)> {
+    codecs.into_iter().map(|(id, codec)| (id, Arc::from(codec))).collect()
 }
 
-static REGISTRY: OnceLock<Registry> = OnceLock::new();
+/// The family a codec (by canonical name) belongs to, if it has siblings.
+pub fn family_of(name: &str) -> Option<&'static str> {
+    FAMILIES
+        .iter()
+        .find(|(_, members)| members.contains(&name))
+        .map(|&(family, _)| family)
+}
+
+/// The specification a codec (by canonical name) implements, as `(title, url)`.
+pub fn spec_of(name: &str) -> Option<(&'static str, &'static str)> {
+    SPECS.iter().find(|(codec, ..)| *codec == name).map(|&(_, title, url)| (title, url))
+}
 
+static REGISTRY: OnceBox<Registry> = OnceBox::new();
+
+/// The codecs an application can reach by name. Cloning is cheap (the tables are shared behind
+/// an [`Arc`]), so a registry can be snapshotted, handed to other threads, or narrowed with
+/// [`filtered`](Self::filtered) for a [`Context`](crate::types::Context) that should only see
+/// some codecs.
+#[derive(Clone)]
 pub struct Registry {
-    codecs: Vec<Box<dyn Codec>>,
-    name_map: HashMap<&'static str, usize>,
+    inner: Arc<Tables>,
+}
+
+struct Tables {
+    codecs: Vec<Arc<dyn Codec>>,
+    ids: Vec<u16>,
+    name_map: BTreeMap<&'static str, usize>,
 }
 
 impl Registry {
     fn new() -> Self {
-        build_registry()
+        let (registry, conflict) = Self::assemble(boxed_to_shared(registered_codecs()));
+        debug_assert!(conflict.is_none(), "{}", conflict.map(|e| e.to_string()).unwrap_or_default());
+        registry
+    }
+
+    /// Builds a registry from the built-in codecs, failing on any name, alias, multibase code or ID
+    /// claimed twice.
+    pub fn try_new() -> Result<Self> {
+        Self::with_codecs(registered_codecs())
+    }
+
+    /// Builds a registry from a custom codec set, with the same conflict checks as [`try_new`](Self::try_new).
+    pub fn with_codecs(codecs: Vec<(u16, Box<dyn Codec>)>) -> Result<Self> {
+        Self::checked(boxed_to_shared(codecs))
+    }
+
+    /// A registry with only the codecs `keep` accepts, sharing them with this one.
+    pub fn filtered(&self, mut keep: impl FnMut(u16, &dyn Codec) -> bool) -> Self {
+        let codecs = self.shared().filter(|(id, codec)| keep(*id, codec.as_ref())).collect();
+        Self::assemble(codecs).0
+    }
+
+    /// This registry plus `codecs`, which must not reuse a name, alias, multibase code or ID.
+    pub fn extended(&self, codecs: Vec<(u16, Box<dyn Codec>)>) -> Result<Self> {
+        Self::checked(self.shared().chain(boxed_to_shared(codecs)).collect())
+    }
+
+    fn shared(&self) -> impl Iterator<Item = (u16, Arc<dyn Codec>)> + '_ {
+        self.inner.ids.iter().copied().zip(self.inner.codecs.iter().cloned())
+    }
+
+    fn checked(codecs: Vec<(u16, Arc<dyn Codec>)>) -> Result<Self> {
+        match Self::assemble(codecs) {
+            (registry, None) => Ok(registry),
+            (_, Some(conflict)) => Err(conflict),
+        }
+    }
+
+    /// Indexes `codecs`, keeping the first registration of any duplicated key and reporting the
+    /// first conflict found.
+    fn assemble(codecs: Vec<(u16, Arc<dyn Codec>)>) -> (Self, Option<MbaseError>) {
+        fn claim<K: Ord + ToString>(
+            map: &mut BTreeMap<K, usize>,
+            key: K,
+            idx: usize,
+            kind: RegistryConflictKind,
+            codecs: &[(u16, Arc<dyn Codec>)],
+            conflict: &mut Option<MbaseError>,
+        ) {
+            match map.get(&key) {
+                Some(&existing) if existing != idx => {
+                    conflict.get_or_insert_with(|| MbaseError::RegistryConflict {
+                        kind,
+                        key: key.to_string(),
+                        first: codecs[existing].1.name().to_string(),
+                        second: codecs[idx].1.name().to_string(),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    map.insert(key, idx);
+                }
+            }
+        }
+
+        let mut conflict = None;
+        let mut name_map = BTreeMap::new();
+        let mut multibase_codes = BTreeMap::new();
+        let mut id_map = BTreeMap::new();
+        for (idx, (id, codec)) in codecs.iter().enumerate() {
+            let meta = codec.meta();
+            claim(&mut id_map, *id, idx, RegistryConflictKind::Id, &codecs, &mut conflict);
+            for name in core::iter::once(&meta.name).chain(meta.aliases) {
+                claim(&mut name_map, *name, idx, RegistryConflictKind::Name, &codecs, &mut conflict);
+            }
+            if let Some(code) = meta.multibase_code {
+                claim(&mut multibase_codes, code, idx, RegistryConflictKind::MultibaseCode, &codecs, &mut conflict);
+            }
+        }
+
+        let (ids, codecs) = codecs.into_iter().unzip();
+        let inner = Arc::new(Tables { codecs, ids, name_map });
+        (Registry { inner }, conflict)
     }
 
     pub fn global() -> &'static Registry {
-        REGISTRY.get_or_init(Registry::new)
+        REGISTRY.get_or_init(|| Box::new(Registry::new()))
     }
 
     pub fn get(&self, name: &str) -> Result<&dyn Codec> {
-        let name_lower = name.to_lowercase();
-        self.name_map
-            .get(name_lower.as_str())
-            .or_else(|| self.name_map.get(name))
-            .map(|&idx| self.codecs[idx].as_ref())
+        // Exact match first so case-distinct aliases such as `hex`/`HEX` both stay reachable.
+        let tables = &*self.inner;
+        tables
+            .name_map
+            .get(name)
+            .or_else(|| tables.name_map.get(name.to_lowercase().as_str()))
+            .map(|&idx| tables.codecs[idx].as_ref())
             .ok_or_else(|| MbaseError::unsupported_codec(name))
     }
 
     pub fn list(&self) -> Vec<CodecMeta> {
-        self.codecs.iter().map(|c| c.meta()).collect()
+        self.inner.codecs.iter().map(|c| c.meta()).collect()
+    }
+
+    /// Looks a codec up by its stable numeric ID.
+    pub fn get_by_id(&self, id: u16) -> Result<&dyn Codec> {
+        self.inner
+            .ids
+            .iter()
+            .position(|&i| i == id)
+            .map(|idx| self.inner.codecs[idx].as_ref())
+            .ok_or_else(|| MbaseError::unsupported_codec(format!("#{}", id)))
+    }
+
+    /// Codecs with their stable IDs, in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &dyn Codec)> + '_ {
+        self.inner.ids.iter().copied().zip(self.inner.codecs.iter().map(|c| c.as_ref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.codecs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.codecs.is_empty()
+    }
+
+    /// Owned, serializable descriptions of every codec, for tools that outlive a given mbase version.
+    pub fn metadata(&self) -> Vec<CodecMetadata> {
+        self.iter().map(|(id, codec)| CodecMetadata::new(id, codec)).collect()
+    }
+
+    /// [`metadata`](Self::metadata) for one codec, by name or alias.
+    pub fn metadata_of(&self, name: &str) -> Result<CodecMetadata> {
+        let codec = self.get(name)?;
+        let idx = self.inner.name_map[codec.name()];
+        Ok(CodecMetadata::new(self.inner.ids[idx], codec))
+    }
+
+    /// The other members of the codec's family (see [`family_of`]), in family order.
+    pub fn siblings(&self, name: &str) -> Result<Vec<&dyn Codec>> {
+        let name = self.get(name)?.name();
+        let members = FAMILIES
+            .iter()
+            .find(|(_, members)| members.contains(&name))
+            .map_or(&[][..], |&(_, m)| m);
+        Ok(members.iter().filter(|&&m| m != name).filter_map(|m| self.get(m).ok()).collect())
+    }
+
+    /// Scores `input` against every codec and returns the non-zero candidates, most likely first,
+    /// without codecs ruled out by negative evidence or duplicating a better candidate's output.
+    pub fn detect(&self, input: &str) -> Vec<DetectCandidate> {
+        let mut candidates: Vec<DetectCandidate> = self
+            .inner
+            .codecs
+            .iter()
+            .map(|c| c.detect_score(input))
+            .filter(|c| c.confidence > 0.0)
+            .collect();
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        super::evidence::refine(self, input, candidates)
     }
 
-    pub fn multibase_map(&self) -> HashMap<char, &'static str> {
-        self.codecs
+    pub fn multibase_map(&self) -> BTreeMap<char, &'static str> {
+        self.inner
+            .codecs
             .iter()
             .filter_map(|c| {
                 let meta = c.meta();
@@ -141,3 +417,140 @@ impl Registry {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Mode;
+
+    #[test]
+    fn test_codec_ids_stable_and_unique() {
+        let registry = Registry::new();
+        assert_eq!(registry.len(), expected_codec_names().len());
+        let mut ids: Vec<u16> = registry.iter().map(|(id, _)| id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), registry.len());
+
+        assert_eq!(registry.get_by_id(1).unwrap().name(), "affine");
+        assert_eq!(registry.metadata_of("b64").unwrap().name, "base64");
+        assert!(registry.get_by_id(0).is_err());
+    }
+
+    #[test]
+    fn test_families_name_registered_codecs() {
+        let registry = Registry::global();
+        for (family, members) in FAMILIES {
+            for member in *members {
+                assert_eq!(registry.get(member).map(|c| c.name()).ok(), Some(*member), "{} in {}", member, family);
+            }
+        }
+        assert_eq!(family_of("base64urlpad"), Some("base64"));
+        assert_eq!(family_of("morse"), None);
+    }
+
+    #[test]
+    fn test_specs_name_registered_codecs() {
+        let registry = Registry::global();
+        for (codec, title, url) in SPECS {
+            assert_eq!(registry.get(codec).map(|c| c.name()).ok(), Some(*codec), "{}", codec);
+            assert!(!title.is_empty() && url.starts_with("https://"), "{}", codec);
+        }
+        assert_eq!(spec_of("base64url").map(|(title, _)| title), Some("RFC 4648 §5"));
+        assert_eq!(spec_of("base62"), None);
+    }
+
+    #[test]
+    fn test_siblings() {
+        let registry = Registry::global();
+        let names = |codecs: Vec<&dyn Codec>| codecs.iter().map(|c| c.name()).collect::<Vec<_>>();
+        assert_eq!(names(registry.siblings("b64").unwrap()), ["base64pad", "base64url", "base64urlpad", "base64url-jwt"]);
+        assert!(registry.siblings("morse").unwrap().is_empty());
+        assert!(registry.siblings("nope").is_err());
+
+        let ctx = crate::types::Context::default();
+        let found = ctx.decoding_siblings("base64", "-_8", Mode::Strict).unwrap();
+        assert_eq!(names(found), ["base64url", "base64url-jwt"]);
+        assert_eq!(names(ctx.decoding_siblings("base32lower", "MZXW6===", Mode::Strict).unwrap()), ["base32padupper"]);
+        // Every family member sees arbitrary input, through the lenient policy and output limit.
+        for name in ["proquint", "bip39", "triplet", "electrum", "monero", "diceware"] {
+            assert!(ctx.decoding_siblings(name, "x", Mode::Strict).is_ok());
+            assert!(ctx.decoding_siblings(name, "x", Mode::Lenient).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_builtin_registry_has_no_conflicts() {
+        let registry = Registry::try_new().unwrap();
+        for (_, codec) in registry.iter() {
+            let meta = codec.meta();
+            for name in core::iter::once(meta.name).chain(meta.aliases.iter().copied()) {
+                assert_eq!(registry.get(name).unwrap().name(), meta.name, "'{}' resolves elsewhere", name);
+            }
+            if let Some(code) = meta.multibase_code {
+                assert_eq!(registry.multibase_map()[&code], meta.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_registry_conflicts() {
+        use crate::codec::{base16, base64};
+
+        let err = Registry::with_codecs(vec![(1, Box::new(base64::Base64)), (2, Box::new(base64::Base64))])
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            MbaseError::RegistryConflict { kind: RegistryConflictKind::Name, ref key, .. } if key == "base64"
+        ));
+
+        let err = Registry::with_codecs(vec![(1, Box::new(base16::Base16Lower)), (1, Box::new(base64::Base64))])
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            MbaseError::RegistryConflict {
+                kind: RegistryConflictKind::Id,
+                ..
+            }
+        ));
+
+        let registry = Registry::with_codecs(vec![(7, Box::new(base64::Base64))]).unwrap();
+        assert_eq!(registry.get_by_id(7).unwrap().name(), "base64");
+    }
+
+    #[test]
+    fn test_filtered_and_extended_registries() {
+        use crate::codec::base64;
+
+        let rfc4648 = Registry::global().filtered(|_, c| matches!(family_of(c.name()), Some("base16" | "base32" | "base64")));
+        assert!(rfc4648.get("b64").is_ok());
+        assert!(rfc4648.get("base58btc").is_err());
+        assert!(rfc4648.detect("SGVsbG8=").iter().all(|c| rfc4648.get(&c.codec).is_ok()));
+        assert_eq!(rfc4648.metadata_of("hex").unwrap().id, Registry::global().metadata_of("hex").unwrap().id);
+        let ctx = crate::types::Context::default().with_registry(rfc4648);
+        assert!(ctx.registry.get("morse").is_err());
+
+        let small = Registry::global().filtered(|id, _| id == 6);
+        let extended = small.extended(vec![(30, Box::new(base64::Base64))]).unwrap();
+        assert_eq!((small.len(), extended.len()), (1, 2));
+        assert!(small.extended(vec![(6, Box::new(base64::Base64))]).is_err());
+        assert!(extended.extended(vec![(99, Box::new(base64::Base64))]).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_registry_clones_share_across_threads() {
+        let snapshot = Registry::global().filtered(|_, c| c.name() == "base64pad");
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let registry = snapshot.clone();
+                std::thread::spawn(move || registry.get("base64pad").unwrap().encode(b"hi").unwrap())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), "aGk=");
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use crate::error::{MbaseError as Error, Result};
+use crate::prelude::*;
 
 pub const RFC1924_ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
 pub const RFC1924_ENCODED_LEN: usize = 20;
 pub const RFC1924_BYTES_LEN: usize = 16;
 
+pub static RFC1924_SYMBOLS: super::util::SymbolTable = super::util::SymbolTable::new(RFC1924_ALPHABET);
+
 pub fn encode_u128(num: u128) -> String {
     let alphabet = RFC1924_ALPHABET.as_bytes();
     let mut result = Vec::with_capacity(RFC1924_ENCODED_LEN);
@@ -31,10 +34,7 @@ pub fn decode_u128(input: &str) -> Result<u128> {
     let mut num: u128 = 0;
 
     for (pos, c) in input.chars().enumerate() {
-        let digit = RFC1924_ALPHABET
-            .chars()
-            .position(|x| x == c)
-            .ok_or_else(|| Error::InvalidCharacter { char: c, position: pos })? as u128;
+        let digit = RFC1924_SYMBOLS.value(c).ok_or(Error::InvalidCharacter { char: c, position: pos })? as u128;
 
         num = num * 85 + digit;
     }
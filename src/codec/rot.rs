@@ -1,5 +1,8 @@
+use alloc::borrow::Cow;
+
 use super::Codec;
 use crate::error::Result;
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct Rot13;
@@ -42,6 +45,13 @@ impl Codec for Rot13 {
             .collect())
     }
 
+    fn decode_cow<'a>(&self, input: &'a str, mode: Mode) -> Result<Cow<'a, [u8]>> {
+        if input.is_ascii() && !input.bytes().any(|b| b.is_ascii_alphabetic()) {
+            return Ok(Cow::Borrowed(input.as_bytes()));
+        }
+        self.decode(input, mode).map(Cow::Owned)
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -72,6 +82,10 @@ impl Codec for Rot13 {
             warnings,
         }
     }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
 }
 
 pub struct Rot47;
@@ -94,7 +108,7 @@ impl Codec for Rot47 {
             .iter()
             .map(|&b| {
                 let c = b as char;
-                if c >= '!' && c <= '~' {
+                if ('!'..='~').contains(&c) {
                     let shifted = (c as u8 - b'!' + 47) % 94 + b'!';
                     shifted as char
                 } else {
@@ -108,7 +122,7 @@ impl Codec for Rot47 {
         Ok(input
             .chars()
             .map(|c| {
-                if c >= '!' && c <= '~' {
+                if ('!'..='~').contains(&c) {
                     (c as u8 - b'!' + 47) % 94 + b'!'
                 } else {
                     c as u8
@@ -117,6 +131,14 @@ impl Codec for Rot47 {
             .collect())
     }
 
+    fn decode_cow<'a>(&self, input: &'a str, mode: Mode) -> Result<Cow<'a, [u8]>> {
+        // Only spaces and control characters pass through ROT47 unchanged.
+        if input.is_ascii() && !input.bytes().any(|b| b.is_ascii_graphic()) {
+            return Ok(Cow::Borrowed(input.as_bytes()));
+        }
+        self.decode(input, mode).map(Cow::Owned)
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -147,6 +169,10 @@ impl Codec for Rot47 {
             warnings,
         }
     }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +210,15 @@ mod tests {
         assert_eq!(double_encoded, "test");
     }
 
+    #[test]
+    fn test_decode_cow_borrows_unchanged_input() {
+        assert!(matches!(Rot13.decode_cow("123 !?", Mode::Strict).unwrap(), Cow::Borrowed(b"123 !?")));
+        assert_eq!(Rot13.decode_cow("Uryyb 1", Mode::Strict).unwrap().as_ref(), b"Hello 1");
+        assert!(matches!(Rot13.decode_cow("caf\u{e9}", Mode::Strict).unwrap(), Cow::Owned(_)));
+        assert!(matches!(Rot47.decode_cow(" \t ", Mode::Strict).unwrap(), Cow::Borrowed(b" \t ")));
+        assert_eq!(Rot47.decode_cow("w6==@", Mode::Strict).unwrap().as_ref(), b"Hello");
+    }
+
     #[test]
     fn test_rot13_non_alpha() {
         assert_eq!(Rot13.encode(b"Hello, World! 123").unwrap(), "Uryyb, Jbeyq! 123");
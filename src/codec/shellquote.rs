@@ -0,0 +1,197 @@
+use super::util::{self, confidence};
+use super::Codec;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// POSIX shell quoting: text becomes one word that `sh` passes on unchanged, as Python's
+/// `shlex.quote` writes it. Words made only of [`SAFE`] characters are left bare; anything else
+/// goes in single quotes, with each `'` written as `'\''`.
+///
+/// Decoding reads one word in the POSIX quoting rules (single and double quotes, backslash
+/// escapes), so words quoted by hand or by other tools decode too. Expansions (`$`, backquotes)
+/// and unquoted operators or blanks are rejected, since only a shell can say what they become;
+/// lenient mode also takes unquoted glob and tilde characters literally.
+pub struct ShellQuote;
+
+/// Characters that never need quoting.
+const SAFE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789@%+=:,./-_";
+
+/// Unquoted characters that end the word or start a command list, redirection or subshell.
+const OPERATORS: &str = "|&;<>() \t\n";
+
+/// Unquoted characters the shell may expand as a pattern; `~` and `#` only at the word start.
+const GLOBS: &str = "*?[";
+
+fn quote(text: &str) -> String {
+    if !text.is_empty() && text.chars().all(|c| SAFE.contains(c)) {
+        return text.to_string();
+    }
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn unquote(input: &str, mode: Mode) -> Result<String> {
+    let expansion = |pos: usize, c: char| {
+        MbaseError::invalid_input_at(pos, format!("'{}' starts a shell expansion, which only a shell can evaluate", c))
+    };
+    let mut out = String::new();
+    let mut chars = input.char_indices();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some((_, '\'')) => break,
+                    Some((_, c)) => out.push(c),
+                    None => return Err(MbaseError::invalid_input_at(pos, "unterminated single quote")),
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, '\n')) => {}
+                        Some((_, c @ ('$' | '`' | '"' | '\\'))) => out.push(c),
+                        Some((_, c)) => {
+                            out.push('\\');
+                            out.push(c);
+                        }
+                        None => return Err(MbaseError::invalid_input_at(pos, "unterminated double quote")),
+                    },
+                    Some((at, c @ ('$' | '`'))) => return Err(expansion(at, c)),
+                    Some((_, c)) => out.push(c),
+                    None => return Err(MbaseError::invalid_input_at(pos, "unterminated double quote")),
+                }
+            },
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, c)) => out.push(c),
+                None => return Err(MbaseError::invalid_input_at(pos, "trailing backslash escapes nothing")),
+            },
+            '$' | '`' => return Err(expansion(pos, c)),
+            c if OPERATORS.contains(c) => {
+                let what = if c.is_ascii_whitespace() {
+                    "unquoted blank splits the input into several words"
+                } else {
+                    "unquoted shell operator"
+                };
+                return Err(MbaseError::invalid_input_at(pos, format!("{} {:?}", what, c)));
+            }
+            c if mode == Mode::Strict && (GLOBS.contains(c) || (pos == 0 && matches!(c, '~' | '#'))) => {
+                return Err(MbaseError::invalid_input_at(
+                    pos,
+                    format!("unquoted '{}' is special to the shell (lenient mode takes it literally)", c),
+                ));
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+impl Codec for ShellQuote {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "shellquote",
+            aliases: &["shell", "shlex"],
+            alphabet: "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789@%+=:,./-_'\\",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "POSIX shell quoting (one word, as shlex.quote)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("shellquote", &["utf8"])?;
+        let text = util::text_input("shellquote", input, opts.utf8_policy()?, '\u{FFFD}')?;
+        if let Some(pos) = text.find('\0') {
+            return Err(MbaseError::invalid_input_at(pos, "NUL cannot appear in a shell argument"));
+        }
+        Ok(quote(&text))
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let input = match mode {
+            Mode::Strict => input,
+            Mode::Lenient => input.trim(),
+        };
+        Ok(unquote(input, mode)?.into_bytes())
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let input = input.trim();
+        if input.len() < 2 || !input.starts_with('\'') || !input.ends_with('\'') || unquote(input, Mode::Strict).is_err() {
+            return confidence::no_match("shellquote");
+        }
+        let reason = if input.contains("'\\''") {
+            "single-quoted word with '\\'' escapes"
+        } else {
+            "single-quoted shell word"
+        };
+        confidence::with_confidence("shellquote", confidence::WEAK_MATCH, reason.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(input: &str) -> Result<String> {
+        Ok(String::from_utf8(ShellQuote.decode(input, Mode::Strict)?).unwrap())
+    }
+
+    #[test]
+    fn test_quote_like_shlex() {
+        assert_eq!(ShellQuote.encode(b"file-1.txt").unwrap(), "file-1.txt");
+        assert_eq!(ShellQuote.encode(b"").unwrap(), "''");
+        assert_eq!(ShellQuote.encode(b"hello world").unwrap(), "'hello world'");
+        assert_eq!(ShellQuote.encode(b"it's $HOME").unwrap(), "'it'\\''s $HOME'");
+        assert!(ShellQuote.encode(b"a\0b").is_err());
+        assert!(ShellQuote.encode(b"\xff").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for text in [
+            "",
+            "plain",
+            "two words",
+            "'",
+            "''",
+            "a'b\"c\\d",
+            "$(rm -rf /)",
+            "tab\tnew\nline",
+            "ünïcødé *?",
+        ] {
+            let encoded = ShellQuote.encode(text.as_bytes()).unwrap();
+            assert_eq!(decode(&encoded).unwrap(), text, "{}", encoded);
+        }
+    }
+
+    #[test]
+    fn test_posix_word_rules() {
+        assert_eq!(decode(r#"a\ b"c\"d\e"'f'"#).unwrap(), "a bc\"d\\ef");
+        assert_eq!(decode("\"line\\\ncontinued\"").unwrap(), "linecontinued");
+        assert!(decode("'open").is_err());
+        assert!(decode("\"$HOME\"").is_err());
+        assert!(decode("a b").is_err());
+        assert!(decode("a;b").is_err());
+        assert!(decode("*.txt").is_err());
+        assert_eq!(ShellQuote.decode("  *.txt\n", Mode::Lenient).unwrap(), b"*.txt");
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(ShellQuote.detect_score("'it'\\''s'").confidence > 0.0);
+        assert_eq!(ShellQuote.detect_score("'open").confidence, 0.0);
+        assert_eq!(ShellQuote.detect_score("plain").confidence, 0.0);
+    }
+}
@@ -1,6 +1,54 @@
+use super::util;
 use super::Codec;
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+fn is_a1z26_separator(c: char) -> bool {
+    matches!(c, '-' | ',' | '.') || c.is_ascii_whitespace()
+}
+
+fn letter(num: u8) -> char {
+    (b'A' + num - 1) as char
+}
+
+/// Splits an unseparated digit run into numbers 1-26, preferring two-digit numbers and backing
+/// off where that would strand a digit (`8512` is 8-5-12). Also returns how many splits exist.
+fn segment_digits(run: &str) -> Option<(Vec<u8>, usize)> {
+    let digits: Vec<u8> = run.bytes().map(|b| b - b'0').collect();
+    let n = digits.len();
+    let pair = |i: usize| {
+        (i + 1 < n)
+            .then(|| digits[i] * 10 + digits[i + 1])
+            .filter(|v| (10..=26).contains(v))
+    };
+    // `ways[i]`: the number of splits of `digits[i..]`, saturating.
+    let mut ways = vec![0usize; n + 1];
+    ways[n] = 1;
+    for i in (0..n).rev() {
+        let single = if digits[i] != 0 { ways[i + 1] } else { 0 };
+        let double = if pair(i).is_some() { ways[i + 2] } else { 0 };
+        ways[i] = single.saturating_add(double);
+    }
+    if ways[0] == 0 {
+        return None;
+    }
+    let mut letters = Vec::new();
+    let mut i = 0;
+    while i < n {
+        match pair(i) {
+            Some(v) if ways[i + 2] > 0 => {
+                letters.push(v);
+                i += 2;
+            }
+            _ => {
+                letters.push(digits[i]);
+                i += 1;
+            }
+        }
+    }
+    Some((letters, ways[0]))
+}
 
 pub struct A1Z26;
 
@@ -9,7 +57,7 @@ impl Codec for A1Z26 {
         CodecMeta {
             name: "a1z26",
             aliases: &["letternum", "alphanumeric"],
-            alphabet: "0123456789-",
+            alphabet: "0123456789-,. ",
             multibase_code: None,
             padding: PaddingRule::None,
             case_sensitivity: CaseSensitivity::Insensitive,
@@ -18,11 +66,16 @@ impl Codec for A1Z26 {
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        let text = String::from_utf8_lossy(input).to_uppercase();
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("a1z26", &["utf8"])?;
+        let text = util::text_input("a1z26", input, opts.utf8_policy()?, 'X')?.to_uppercase();
         let numbers: Vec<String> = text
             .chars()
-            .filter_map(|c| {
-                if c >= 'A' && c <= 'Z' {
+            .filter_map(|c: char| {
+                if c.is_ascii_uppercase() {
                     Some((c as u8 - b'A' + 1).to_string())
                 } else if c == ' ' {
                     Some("0".to_string())
@@ -40,36 +93,54 @@ impl Codec for A1Z26 {
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let cleaned = if mode == Mode::Lenient {
-            input.replace(&[' ', '\t', '\n', '\r'], "")
-        } else {
-            input.to_string()
-        };
-
-        let parts: Vec<&str> = cleaned.split('-').collect();
         let mut result = String::new();
 
-        for part in parts {
+        for part in input.split(is_a1z26_separator) {
             if part.is_empty() {
                 continue;
             }
 
-            let num: u8 = part
-                .parse()
-                .map_err(|_| MbaseError::invalid_input(format!("invalid number: {}", part)))?;
-
-            if num == 0 {
-                result.push(' ');
-            } else if num >= 1 && num <= 26 {
-                result.push((b'A' + num - 1) as char);
-            } else {
-                return Err(MbaseError::invalid_input(format!("number out of range (1-26): {}", num)));
+            match part.parse::<u8>() {
+                Ok(0) => result.push(' '),
+                Ok(num @ 1..=26) => result.push(letter(num)),
+                _ if mode == Mode::Lenient && part.bytes().all(|b| b.is_ascii_digit()) => {
+                    let (letters, _) = segment_digits(part)
+                        .ok_or_else(|| MbaseError::invalid_input(format!("digit run '{}' cannot be split into numbers 1-26", part)))?;
+                    result.extend(letters.into_iter().map(letter));
+                }
+                Ok(num) => return Err(MbaseError::invalid_input(format!("number out of range (1-26): {}", num))),
+                Err(_) => return Err(MbaseError::invalid_input(format!("invalid number: {}", part))),
             }
         }
 
         Ok(result.into_bytes())
     }
 
+    fn decode_warnings(&self, input: &str, mode: Mode) -> Vec<String> {
+        if mode == Mode::Strict {
+            return Vec::new();
+        }
+        input
+            .split(is_a1z26_separator)
+            .filter(|part| part.len() > 1 && part.bytes().all(|b| b.is_ascii_digit()) && !matches!(part.parse::<u8>(), Ok(0..=26)))
+            .filter_map(|run| {
+                let (letters, readings) = segment_digits(run)?;
+                (readings > 1).then(|| {
+                    let word: String = letters.into_iter().map(letter).collect();
+                    format!("digit run '{}' has {} readings; took the longest numbers first ({})", run, readings, word)
+                })
+            })
+            .collect()
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn lossy(&self) -> bool {
+        true
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -185,6 +256,10 @@ impl Codec for Rot18 {
             warnings: vec!["ROT18 is ambiguous without context".to_string()],
         }
     }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -218,6 +293,13 @@ mod tests {
         assert_eq!(A1Z26.decode("8-5-12-12-15-0-23-15-18-12-4", Mode::Strict).unwrap(), b"HELLO WORLD");
     }
 
+    #[test]
+    fn test_a1z26_invalid_utf8() {
+        assert!(A1Z26.encode(b"HI\xc3").is_err());
+        let lenient = CodecOptions::new().with("utf8", "lenient");
+        assert_eq!(A1Z26.encode_with(b"HI\xc3", &lenient).unwrap(), "8-9-24");
+    }
+
     #[test]
     fn test_a1z26_case_insensitive() {
         let upper = A1Z26.encode(b"HELLO").unwrap();
@@ -231,6 +313,36 @@ mod tests {
         assert!(A1Z26.decode("1-27-3", Mode::Strict).is_err());
     }
 
+    #[test]
+    fn test_a1z26_separators() {
+        assert_eq!(A1Z26.decode("8 5 12 12 15", Mode::Strict).unwrap(), b"HELLO");
+        assert_eq!(A1Z26.decode("8,5,12, 12.15", Mode::Strict).unwrap(), b"HELLO");
+        assert_eq!(A1Z26.decode("8-5 0 23", Mode::Strict).unwrap(), b"HE W");
+    }
+
+    #[test]
+    fn test_a1z26_digit_runs() {
+        assert!(A1Z26.decode("8512", Mode::Strict).is_err());
+        assert_eq!(A1Z26.decode("8512", Mode::Lenient).unwrap(), b"HEL");
+        assert_eq!(A1Z26.decode("85121215", Mode::Lenient).unwrap(), b"HELLO");
+        // 22 would strand the 0, so the split backs off to 2-20-6.
+        assert_eq!(A1Z26.decode("2206", Mode::Lenient).unwrap(), b"BTF");
+        assert_eq!(A1Z26.decode("27", Mode::Lenient).unwrap(), b"BG");
+        assert!(A1Z26.decode("300", Mode::Lenient).is_err());
+        assert!(A1Z26.decode("8x5", Mode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_a1z26_ambiguity_warnings() {
+        assert_eq!(
+            A1Z26.decode_warnings("8512 9", Mode::Lenient),
+            ["digit run '8512' has 2 readings; took the longest numbers first (HEL)"]
+        );
+        assert!(A1Z26.decode_warnings("8-5-12", Mode::Lenient).is_empty());
+        assert!(A1Z26.decode_warnings("851", Mode::Lenient).is_empty());
+        assert!(A1Z26.decode_warnings("8512", Mode::Strict).is_empty());
+    }
+
     #[test]
     fn test_rot18_encode() {
         assert_eq!(Rot18.encode(b"Hello123").unwrap(), "Uryyb678");
@@ -0,0 +1,207 @@
+use super::util::{self, confidence};
+use super::Codec;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// URL slugs: text lowercased, Latin letters with diacritics transliterated to ASCII (`é` to
+/// `e`, `ß` to `ss`), apostrophes dropped and every other run of characters turned into one
+/// separator, as in `hello-wrld` for "Hello, Wörld!". This throws information away, so decoding
+/// only splits a slug back into words: `decode(encode(x))` is not `x`, but encoding a decoded
+/// slug gives the slug again. `--opt sep=_` changes the separator.
+pub struct Slug;
+
+/// Lowercase letters outside ASCII and the ASCII they transliterate to.
+const FOLDS: &[(&str, &str)] = &[
+    ("àáâãäåāăą", "a"),
+    ("æ", "ae"),
+    ("çćĉċč", "c"),
+    ("ďđð", "d"),
+    ("èéêëēĕėęě", "e"),
+    ("ĝğġģ", "g"),
+    ("ĥħ", "h"),
+    ("ìíîïĩīĭįı", "i"),
+    ("ĳ", "ij"),
+    ("ĵ", "j"),
+    ("ķĸ", "k"),
+    ("ĺļľŀł", "l"),
+    ("ñńņňŉ", "n"),
+    ("ŋ", "ng"),
+    ("òóôõöøōŏő", "o"),
+    ("œ", "oe"),
+    ("ŕŗř", "r"),
+    ("śŝşšșſ", "s"),
+    ("ß", "ss"),
+    ("ţťŧț", "t"),
+    ("þ", "th"),
+    ("ùúûüũūŭůűų", "u"),
+    ("ŵ", "w"),
+    ("ýÿŷ", "y"),
+    ("źżž", "z"),
+];
+
+fn fold(c: char) -> Option<&'static str> {
+    FOLDS.iter().find(|(from, _)| from.contains(c)).map(|&(_, to)| to)
+}
+
+/// Combining diacritics, which decomposed input carries after the base letter.
+fn is_combining(c: char) -> bool {
+    ('\u{0300}'..='\u{036f}').contains(&c)
+}
+
+fn slugify(text: &str, sep: char) -> String {
+    let mut slug = String::new();
+    let mut pending = false;
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            if pending && !slug.is_empty() {
+                slug.push(sep);
+            }
+            pending = false;
+            slug.push(c);
+        } else if let Some(ascii) = fold(c) {
+            if pending && !slug.is_empty() {
+                slug.push(sep);
+            }
+            pending = false;
+            slug.push_str(ascii);
+        } else if !matches!(c, '\'' | '\u{2019}') && !is_combining(c) {
+            pending = true;
+        }
+    }
+    slug
+}
+
+fn separator(opts: &CodecOptions) -> Result<char> {
+    let sep = opts.get_char("sep")?.unwrap_or('-');
+    if sep.is_ascii_alphanumeric() {
+        return Err(MbaseError::invalid_input(format!("slug separator '{}' must not be a letter or digit", sep)));
+    }
+    Ok(sep)
+}
+
+impl Codec for Slug {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "slug",
+            aliases: &["slugify"],
+            alphabet: "abcdefghijklmnopqrstuvwxyz0123456789-",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "URL slug (lowercase ASCII words joined by '-'; lossy)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("slug", &["sep", "utf8"])?;
+        let sep = separator(opts)?;
+        let text = util::text_input("slug", input, opts.utf8_policy()?, ' ')?;
+        let slug = slugify(&text, sep);
+        if slug.is_empty() && !text.trim().is_empty() {
+            return Err(MbaseError::invalid_input("no letters or digits to make a slug from"));
+        }
+        Ok(slug)
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        opts.ensure_known("slug", &["sep", "utf8"])?;
+        let sep = separator(opts)?;
+        if mode == Mode::Lenient {
+            let text = input.replace(sep, " ");
+            return Ok(slugify(&text, ' ').into_bytes());
+        }
+        let mut words = Vec::new();
+        let mut start = 0;
+        for (pos, c) in input.char_indices() {
+            if c == sep {
+                if pos == start {
+                    return Err(MbaseError::invalid_input_at(pos, format!("empty word: '{}' at the start, end or doubled", sep)));
+                }
+                words.push(&input[start..pos]);
+                start = pos + c.len_utf8();
+            } else if !c.is_ascii_lowercase() && !c.is_ascii_digit() {
+                return Err(MbaseError::InvalidCharacter { char: c, position: pos });
+            }
+        }
+        if !input.is_empty() {
+            if start == input.len() {
+                return Err(MbaseError::invalid_input_at(start - 1, format!("empty word: '{}' at the start, end or doubled", sep)));
+            }
+            words.push(&input[start..]);
+        }
+        Ok(words.join(" ").into_bytes())
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn lossy(&self) -> bool {
+        true
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let input = input.trim();
+        if !input.contains('-') || self.decode(input, Mode::Strict).is_err() {
+            return confidence::no_match("slug");
+        }
+        let words = input.split('-').count();
+        confidence::with_confidence("slug", confidence::WEAK_MATCH, format!("{} lowercase words joined by '-'", words))
+    }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slug(text: &str) -> String {
+        Slug.encode(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slug("Hello, Wörld!"), "hello-world");
+        assert_eq!(slug("  Straße & Æsir -- Øl  "), "strasse-aesir-ol");
+        assert_eq!(slug("Don't Stop Me Now (2011 Remaster)"), "dont-stop-me-now-2011-remaster");
+        assert_eq!(slug("Cafe\u{301} ÉLAN"), "cafe-elan");
+        assert_eq!(slug("東京 2024"), "2024");
+        assert_eq!(slug(""), "");
+        assert!(Slug.encode("東京".as_bytes()).is_err());
+        let opts = CodecOptions::new().with("sep", "_");
+        assert_eq!(Slug.encode_with(b"a b", &opts).unwrap(), "a_b");
+        assert!(Slug.encode_with(b"a b", &CodecOptions::new().with("sep", "x")).is_err());
+    }
+
+    #[test]
+    fn test_decode_splits_words() {
+        assert_eq!(Slug.decode("hello-world-2", Mode::Strict).unwrap(), b"hello world 2");
+        assert!(Slug.decode("Hello-world", Mode::Strict).is_err());
+        assert!(Slug.decode("hello--world", Mode::Strict).is_err());
+        assert!(Slug.decode("hello-", Mode::Strict).is_err());
+        assert_eq!(Slug.decode("-Hello--World-", Mode::Lenient).unwrap(), b"hello world");
+        let opts = CodecOptions::new().with("sep", "_");
+        assert_eq!(Slug.decode_with("a_b", Mode::Strict, &opts).unwrap(), b"a b");
+    }
+
+    #[test]
+    fn test_slug_round_trip() {
+        for text in ["hello-world", "a", "2024-recap", ""] {
+            let decoded = Slug.decode(text, Mode::Strict).unwrap();
+            assert_eq!(slug(core::str::from_utf8(&decoded).unwrap()), text);
+        }
+        assert!(Slug.lossy());
+    }
+}
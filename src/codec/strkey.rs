@@ -0,0 +1,119 @@
+use data_encoding::BASE32_NOPAD;
+
+use super::util;
+use super::Codec;
+use crate::checksum::Checksum;
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+
+const RFC4648_UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// CRC-16/XMODEM (poly 0x1021, init 0), as used by Stellar StrKey.
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    Checksum::Crc16Xmodem.compute(data) as u16
+}
+
+/// Stellar StrKey: version byte and payload followed by a little-endian CRC16, as unpadded base32.
+///
+/// The version byte is the key type's base32 letter shifted left by 3, so it shows up as the
+/// first character (`G` account, `S` seed, `M` muxed account, ...).
+pub struct StrKey;
+
+impl Codec for StrKey {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "strkey",
+            aliases: &["stellar"],
+            alphabet: RFC4648_UPPER,
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Sensitive,
+            description: "Stellar StrKey (version byte + payload + CRC16, base32)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        if input.is_empty() {
+            return Err(MbaseError::invalid_input("strkey needs at least a version byte"));
+        }
+        let mut with_checksum = input.to_vec();
+        with_checksum.extend_from_slice(&crc16_xmodem(input).to_le_bytes());
+        Ok(BASE32_NOPAD.encode(&with_checksum))
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        let cleaned = util::clean_for_mode(input, mode);
+        let cleaned = match mode {
+            Mode::Strict => cleaned,
+            Mode::Lenient => cleaned.to_uppercase(),
+        };
+        if let Some((position, char)) = cleaned.char_indices().find(|(_, c)| !RFC4648_UPPER.contains(*c)) {
+            return Err(MbaseError::InvalidCharacter { char, position });
+        }
+        let decoded = BASE32_NOPAD
+            .decode(cleaned.as_bytes())
+            .map_err(|e| MbaseError::invalid_input(e.to_string()))?;
+
+        if decoded.len() < 3 {
+            return Err(MbaseError::invalid_input("input too short for version byte and checksum"));
+        }
+        if decoded[0] & 0x07 != 0 {
+            return Err(MbaseError::invalid_input(format!("invalid version byte 0x{:02x}", decoded[0])));
+        }
+
+        let (payload, checksum) = decoded.split_at(decoded.len() - 2);
+        if checksum != crc16_xmodem(payload).to_le_bytes() {
+            return Err(MbaseError::ChecksumMismatch);
+        }
+
+        Ok(payload.to_vec())
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let mut candidate = DetectCandidate {
+            codec: "strkey".to_string(),
+            confidence: 0.0,
+            reasons: vec![],
+            warnings: vec![],
+        };
+
+        if !input.is_empty() && self.decode(input, Mode::Strict).is_ok() {
+            candidate.confidence = 0.9;
+            candidate.reasons.push("CRC16 checksum valid".to_string());
+        }
+
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+    #[test]
+    fn test_crc16_xmodem() {
+        assert_eq!(crc16_xmodem(b"123456789"), 0x31c3);
+    }
+
+    #[test]
+    fn test_strkey_account() {
+        let decoded = StrKey.decode(ACCOUNT, Mode::Strict).unwrap();
+        assert_eq!(decoded.len(), 33);
+        assert_eq!(decoded[0], 6 << 3);
+        assert_eq!(StrKey.encode(&decoded).unwrap(), ACCOUNT);
+        assert!(StrKey.detect_score(ACCOUNT).confidence >= 0.9);
+    }
+
+    #[test]
+    fn test_strkey_errors() {
+        let mut corrupted = ACCOUNT.to_string();
+        corrupted.replace_range(10..11, "A");
+        assert!(matches!(StrKey.decode(&corrupted, Mode::Strict), Err(MbaseError::ChecksumMismatch)));
+        assert!(StrKey.decode(&ACCOUNT.to_lowercase(), Mode::Strict).is_err());
+        assert!(StrKey.decode(&ACCOUNT.to_lowercase(), Mode::Lenient).is_ok());
+        assert!(StrKey.encode(b"").is_err());
+    }
+}
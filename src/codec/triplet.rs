@@ -0,0 +1,212 @@
+use once_cell::race::OnceBox;
+
+use super::util;
+use super::Codec;
+use crate::error::{LengthConstraint, MbaseError, Result};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule};
+
+/// The English, Italian, Portuguese, Czech, Spanish and French BIP39 lists in that order, accents
+/// stripped and duplicates dropped: 12114 open-licensed ASCII words, so three carry 40.7 bits.
+static WORDS: OnceBox<Vec<&'static str>> = OnceBox::new();
+
+fn words() -> &'static [&'static str] {
+    WORDS.get_or_init(|| Box::new(include_str!("wordlists/triplet.txt").lines().collect()))
+}
+
+const CHUNK: usize = 5;
+const MASK: u64 = (1 << 40) - 1;
+/// Odd multiplier (and its inverse mod 2^40) that spreads consecutive IDs over unrelated words.
+const SPREAD: u64 = 0x9e_3779_b97f;
+const UNSPREAD: u64 = 0x4c_19bc_067f;
+
+/// Three words for each 5-byte (40-bit) chunk, dot-separated as in `index.home.raft`.
+///
+/// This is an mbase-specific scheme over an open wordlist; it is not, and does not interoperate
+/// with, the proprietary what3words addressing.
+pub struct Triplet;
+
+impl Triplet {
+    fn separator(opts: &CodecOptions) -> Result<char> {
+        opts.ensure_known("triplet", &["sep"])?;
+        Ok(opts.get_char("sep")?.unwrap_or('.'))
+    }
+
+    fn encode_chunk(chunk: &[u8]) -> [&'static str; 3] {
+        let value = chunk.iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+        let mut spread = value.wrapping_mul(SPREAD) & MASK;
+        let words = words();
+        let size = words.len() as u64;
+        let mut out = [""; 3];
+        for slot in out.iter_mut().rev() {
+            *slot = words[(spread % size) as usize];
+            spread /= size;
+        }
+        out
+    }
+
+    fn decode_triplet(triplet: &str, sep: char, mode: Mode, offset: usize) -> Result<[u8; CHUNK]> {
+        let words = words();
+        let size = words.len() as u64;
+        let parts: Vec<&str> = triplet.split(sep).collect();
+        if parts.len() != 3 {
+            return Err(MbaseError::invalid_input(format!("'{}' at position {} is not three words", triplet, offset)));
+        }
+        let mut spread = 0u64;
+        for part in parts {
+            let index = match mode {
+                Mode::Strict => words.iter().position(|w| *w == part),
+                Mode::Lenient => words.iter().position(|w| w.eq_ignore_ascii_case(part)),
+            }
+            .ok_or_else(|| MbaseError::invalid_input(format!("'{}' is not in the triplet wordlist", part)))?;
+            spread = spread * size + index as u64;
+        }
+        if spread > MASK {
+            return Err(MbaseError::invalid_input(format!("'{}' is out of range: three words encode at most 40 bits", triplet)));
+        }
+        let value = spread.wrapping_mul(UNSPREAD) & MASK;
+        let bytes = value.to_be_bytes();
+        Ok(bytes[8 - CHUNK..].try_into().unwrap())
+    }
+}
+
+impl Codec for Triplet {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "triplet",
+            aliases: &["words3", "w3"],
+            alphabet: "",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Lower,
+            description: "Three words per 40 bits from an open 12114-word list (mbase-specific)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let sep = Self::separator(opts)?;
+        if !input.len().is_multiple_of(CHUNK) {
+            return Err(MbaseError::invalid_length_msg(
+                LengthConstraint::MultipleOf(CHUNK),
+                input.len(),
+                "each word triplet carries exactly 5 bytes",
+            ));
+        }
+        let triplets: Vec<String> = input
+            .chunks(CHUNK)
+            .map(|chunk| Self::encode_chunk(chunk).join(&sep.to_string()))
+            .collect();
+        Ok(triplets.join(" "))
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let sep = Self::separator(opts)?;
+        let mut output = Vec::new();
+        if input.is_empty() {
+            return Ok(output);
+        }
+        let mut offset = 0;
+        for triplet in input.split(' ') {
+            let start = offset;
+            offset += triplet.len() + 1;
+            let triplet = match mode {
+                // what3words-style `///word.word.word` and stray whitespace.
+                Mode::Lenient => triplet.trim().trim_start_matches('/'),
+                Mode::Strict => triplet,
+            };
+            if triplet.is_empty() && mode == Mode::Lenient {
+                continue;
+            }
+            output.extend_from_slice(&Self::decode_triplet(triplet, sep, mode, start)?);
+        }
+        Ok(output)
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        let input = input.trim().trim_start_matches('/');
+        if input.is_empty() || !input.contains('.') {
+            return util::confidence::no_match(self.name());
+        }
+        match self.decode(input, Mode::Strict) {
+            Ok(bytes) => util::confidence::with_confidence(
+                self.name(),
+                util::confidence::ALPHABET_MATCH,
+                format!("{} word triplet(s) from the triplet wordlist", bytes.len() / CHUNK),
+            ),
+            Err(_) => util::confidence::no_match(self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triplet_wordlist() {
+        let words = words();
+        assert_eq!(words.len(), 12114);
+        assert!((words.len() as u64).pow(3) > MASK);
+        assert_eq!(SPREAD.wrapping_mul(UNSPREAD) & MASK, 1);
+        let mut sorted = words.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), words.len());
+    }
+
+    #[test]
+    fn test_triplet_roundtrip() {
+        for input in [&[0u8; 5][..], &[0xff; 5], b"mbase", b"0123456789"] {
+            let encoded = Triplet.encode(input).unwrap();
+            assert_eq!(encoded.split(' ').count(), input.len() / 5);
+            assert_eq!(Triplet.decode(&encoded, Mode::Strict).unwrap(), input);
+        }
+        assert_eq!(Triplet.encode(&[0; 5]).unwrap(), "abandon.abandon.abandon");
+        assert_eq!(Triplet.encode(b"").unwrap(), "");
+        assert_eq!(Triplet.decode("", Mode::Strict).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_triplet_spreads_neighbours() {
+        let a = Triplet.encode(&[0, 0, 0, 0, 1]).unwrap();
+        let b = Triplet.encode(&[0, 0, 0, 0, 2]).unwrap();
+        assert_ne!(a.split('.').next(), b.split('.').next());
+    }
+
+    #[test]
+    fn test_triplet_lenient_and_options() {
+        let encoded = Triplet.encode(b"mbase").unwrap();
+        let shouted = format!("///{}", encoded.to_uppercase());
+        assert_eq!(Triplet.decode(&shouted, Mode::Lenient).unwrap(), b"mbase");
+        assert!(Triplet.decode(&shouted, Mode::Strict).is_err());
+
+        let opts = CodecOptions::new().with("sep", "-");
+        let dashed = Triplet.encode_with(b"mbase", &opts).unwrap();
+        assert_eq!(dashed, encoded.replace('.', "-"));
+        assert_eq!(Triplet.decode_with(&dashed, Mode::Strict, &opts).unwrap(), b"mbase");
+    }
+
+    #[test]
+    fn test_triplet_errors() {
+        assert!(Triplet.encode(b"abc").is_err());
+        assert!(Triplet.decode("abandon.abandon", Mode::Strict).is_err());
+        assert!(Triplet.decode("abandon.abandon.qqq", Mode::Strict).is_err());
+        let last = words().last().unwrap();
+        assert!(Triplet.decode(&format!("{0}.{0}.{0}", last), Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_triplet_detect() {
+        let encoded = Triplet.encode(b"mbase").unwrap();
+        assert!(Triplet.detect_score(&encoded).confidence >= 0.7);
+        assert_eq!(Triplet.detect_score("hello.world").confidence, 0.0);
+    }
+}
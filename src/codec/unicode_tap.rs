@@ -1,9 +1,172 @@
-use super::Codec;
+use once_cell::race::OnceBox;
+
+use super::{util, Codec};
 use crate::error::{MbaseError, Result};
-use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
+use crate::prelude::*;
+use crate::types::{CaseSensitivity, CodecMeta, CodecOptions, DetectCandidate, Mode, PaddingRule, Utf8Policy};
+
+/// Names for `\N{...}`: Latin-1 and Latin Extended-A, Greek, Cyrillic, punctuation through
+/// mathematical operators, box drawing, shapes, symbols, dingbats and the common emoji blocks
+/// (Unicode 14). Code points outside those blocks have no name here.
+static NAMES: OnceBox<Vec<(u32, &'static str)>> = OnceBox::new();
+
+fn names() -> &'static [(u32, &'static str)] {
+    NAMES.get_or_init(|| {
+        Box::new(
+            include_str!("wordlists/unicode_names.txt")
+                .lines()
+                .filter_map(|line| {
+                    let (cp, name) = line.split_once(' ')?;
+                    Some((u32::from_str_radix(cp, 16).ok()?, name))
+                })
+                .collect(),
+        )
+    })
+}
+
+fn name_of(cp: u32) -> Option<&'static str> {
+    let names = names();
+    names.binary_search_by_key(&cp, |&(c, _)| c).ok().map(|i| names[i].1)
+}
+
+/// Names match case-insensitively, as in Python; lenient mode also takes `_` for a space.
+fn code_point_named(name: &str, mode: Mode) -> Option<u32> {
+    let same = |a: u8, b: u8| a.eq_ignore_ascii_case(&b) || (mode == Mode::Lenient && a == b' ' && b == b'_');
+    names()
+        .iter()
+        .find(|(_, n)| n.len() == name.len() && n.bytes().zip(name.bytes()).all(|(a, b)| same(a, b)))
+        .map(|&(cp, _)| cp)
+}
 
+/// Encoded form selected by the `style` option.
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    /// `U+1F980`, space-separated.
+    Plus,
+    /// `\u{1F980}`
+    Rust,
+    /// `&#x1F980;`
+    Html,
+    /// `\ud83e\udd80`: UTF-16 escapes as in JSON and JavaScript.
+    Json,
+    /// `\U0001f980`, or `\u00e9` below U+10000.
+    Python,
+    /// `\N{CRAB}`, falling back to the Python escape for code points without a known name.
+    Name,
+}
+
+impl Style {
+    fn from_options(opts: &CodecOptions) -> Result<Self> {
+        opts.ensure_known("unicode", &["style"])?;
+        match opts.get("style") {
+            None | Some("plus") | Some("u+") => Ok(Style::Plus),
+            Some("rust") => Ok(Style::Rust),
+            Some("html") => Ok(Style::Html),
+            Some("json") | Some("js") => Ok(Style::Json),
+            Some("python") => Ok(Style::Python),
+            Some("name") => Ok(Style::Name),
+            Some(other) => {
+                Err(MbaseError::invalid_input(format!("option 'style' expects plus, rust, html, json, python or name, got '{}'", other)))
+            }
+        }
+    }
+
+    fn push(self, c: char, out: &mut String) {
+        let cp = c as u32;
+        match self {
+            Style::Plus => out.push_str(&format!("U+{:04X}", cp)),
+            Style::Rust => out.push_str(&format!("\\u{{{:X}}}", cp)),
+            Style::Html => out.push_str(&format!("&#x{:X};", cp)),
+            Style::Json => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+            Style::Name if name_of(cp).is_some() => out.push_str(&format!("\\N{{{}}}", name_of(cp).unwrap())),
+            Style::Python | Style::Name if cp > 0xFFFF => out.push_str(&format!("\\U{:08x}", cp)),
+            Style::Python | Style::Name => out.push_str(&format!("\\u{:04x}", cp)),
+        }
+    }
+}
+
+enum Token {
+    CodePoint(u32),
+    /// A `\uXXXX` escape, which may be half of a surrogate pair.
+    Utf16(u32),
+    /// A `\xNN` escape for a byte that is not part of valid UTF-8.
+    Byte(u8),
+}
+
+/// Up to `max` hex digits at the start of `s` (at least one), with their count.
+fn hex_prefix(s: &str, max: usize) -> Option<(u32, usize)> {
+    let len = s.bytes().take(max).take_while(u8::is_ascii_hexdigit).count();
+    (len > 0).then(|| (u32::from_str_radix(&s[..len], 16).unwrap(), len))
+}
+
+/// The token at the start of `s` and its length in bytes.
+fn next_token(s: &str, mode: Mode) -> Option<(Token, usize)> {
+    let closed = |value: Option<(u32, usize)>, prefix: usize, close: char| {
+        let (v, len) = value?;
+        s[prefix + len..]
+            .starts_with(close)
+            .then_some((Token::CodePoint(v), prefix + len + 1))
+    };
+    let token_end = s.find(char::is_whitespace).unwrap_or(s.len());
+    let bare = |prefix: usize| {
+        let (v, len) = hex_prefix(&s[prefix..token_end], 8)?;
+        (prefix + len == token_end).then_some((Token::CodePoint(v), token_end))
+    };
+
+    if let Some(rest) = s.strip_prefix("U+").or_else(|| s.strip_prefix("u+")) {
+        hex_prefix(rest, 6).map(|(v, len)| (Token::CodePoint(v), 2 + len))
+    } else if let Some(rest) = s.strip_prefix("\\u{") {
+        closed(hex_prefix(rest, 6), 3, '}')
+    } else if let Some(rest) = s.strip_prefix("\\U") {
+        hex_prefix(rest, 8)
+            .filter(|&(_, len)| len == 8)
+            .map(|(v, len)| (Token::CodePoint(v), 2 + len))
+    } else if let Some(rest) = s.strip_prefix("\\u") {
+        hex_prefix(rest, 4).map(|(v, len)| (Token::Utf16(v), 2 + len))
+    } else if let Some(rest) = s.strip_prefix("\\x") {
+        hex_prefix(rest, 2)
+            .filter(|&(_, len)| len == 2)
+            .map(|(v, len)| (Token::Byte(v as u8), 2 + len))
+    } else if let Some(rest) = s.strip_prefix("\\N{") {
+        let end = rest.find('}')?;
+        code_point_named(&rest[..end], mode).map(|cp| (Token::CodePoint(cp), 3 + end + 1))
+    } else if let Some(rest) = s.strip_prefix("&#x").or_else(|| s.strip_prefix("&#X")) {
+        closed(hex_prefix(rest, 6), 3, ';')
+    } else if let Some(rest) = s.strip_prefix("&#") {
+        let len = rest.bytes().take(7).take_while(u8::is_ascii_digit).count();
+        closed(rest[..len].parse().ok().map(|v| (v, len)), 2, ';')
+    } else if s.starts_with("0x") || s.starts_with("0X") {
+        bare(2)
+    } else {
+        bare(0)
+    }
+}
+
+fn is_surrogate(cp: u32) -> bool {
+    (0xD800..=0xDFFF).contains(&cp)
+}
+
+/// Code points in any of `U+1F980`, `\u{1F980}`, `\U0001F980`, `\ud83e\udd80`, `&#x1F980;`,
+/// `&#129408;`, `\N{CRAB}`, `0x1F980` or bare hex, plus `\xNN` for bytes that are not UTF-8.
 pub struct UnicodeCodepoints;
 
+impl UnicodeCodepoints {
+    fn lone_surrogate(cp: u32, position: usize, mode: Mode, out: &mut Vec<u8>) -> Result<()> {
+        match mode {
+            Mode::Strict => Err(MbaseError::invalid_input_at(position, format!("lone surrogate U+{:04X} is not a character", cp))),
+            Mode::Lenient => {
+                out.extend_from_slice(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]).as_bytes());
+                Ok(())
+            }
+        }
+    }
+}
+
 impl Codec for UnicodeCodepoints {
     fn meta(&self) -> CodecMeta {
         CodecMeta {
@@ -13,45 +176,90 @@ impl Codec for UnicodeCodepoints {
             multibase_code: None,
             padding: PaddingRule::None,
             case_sensitivity: CaseSensitivity::Insensitive,
-            description: "Unicode code points (U+XXXX format)",
+            description: "Unicode code points (U+XXXX, \\u{...}, &#x...;, \\N{...})",
         }
     }
 
     fn encode(&self, input: &[u8]) -> Result<String> {
-        let text = String::from_utf8_lossy(input);
-        let codepoints: Vec<String> = text.chars().map(|c| format!("U+{:04X}", c as u32)).collect();
-        Ok(codepoints.join(" "))
+        self.encode_with(input, &CodecOptions::default())
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let cleaned = if mode == Mode::Lenient {
-            input.replace(&['\t', '\n', '\r'], " ")
-        } else {
-            input.to_string()
-        };
-
-        let parts: Vec<&str> = cleaned.split_whitespace().collect();
-        let mut result = String::new();
-
-        for part in parts {
-            let hex_str = if part.starts_with("U+") || part.starts_with("u+") {
-                &part[2..]
-            } else if part.starts_with("\\u") {
-                &part[2..]
-            } else if part.starts_with("0x") || part.starts_with("0X") {
-                &part[2..]
-            } else {
-                part
-            };
-
-            let codepoint = u32::from_str_radix(hex_str, 16).map_err(|_| MbaseError::invalid_input(format!("invalid hex: {}", part)))?;
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
 
-            let ch = char::from_u32(codepoint).ok_or_else(|| MbaseError::invalid_input(format!("invalid codepoint: U+{:X}", codepoint)))?;
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let style = Style::from_options(opts)?;
+        let mut out = String::new();
+        let separate = |out: &mut String| {
+            if style == Style::Plus && !out.is_empty() {
+                out.push(' ');
+            }
+        };
+        // Bytes that are not UTF-8 become `\xNN`, so any input survives the round trip.
+        for chunk in input.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                separate(&mut out);
+                style.push(c, &mut out);
+            }
+            for byte in chunk.invalid() {
+                separate(&mut out);
+                out.push_str(&format!("\\x{:02X}", byte));
+            }
+        }
+        Ok(out)
+    }
 
-            result.push(ch);
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        // The style only shapes encoder output; every syntax decodes.
+        Style::from_options(opts)?;
+        let mut out = Vec::new();
+        let mut high: Option<(u32, usize)> = None;
+        let mut pos = 0;
+        while pos < input.len() {
+            let rest = &input[pos..];
+            let c = rest.chars().next().unwrap();
+            if c.is_whitespace() {
+                pos += c.len_utf8();
+                continue;
+            }
+            let (token, len) = next_token(rest, mode).ok_or_else(|| {
+                let token = rest.split(char::is_whitespace).next().unwrap_or(rest);
+                MbaseError::invalid_input_at(pos, format!("'{}' is not a code point", token))
+            })?;
+
+            let pending = high.take();
+            match token {
+                Token::Utf16(low @ 0xDC00..=0xDFFF) if pending.is_some() => {
+                    let (hi, _) = pending.unwrap();
+                    let cp = 0x10000 + ((hi - 0xD800) << 10) + (low - 0xDC00);
+                    out.extend_from_slice(char::from_u32(cp).unwrap().encode_utf8(&mut [0; 4]).as_bytes());
+                    pos += len;
+                    continue;
+                }
+                _ => {
+                    if let Some((hi, at)) = pending {
+                        Self::lone_surrogate(hi, at, mode, &mut out)?;
+                    }
+                }
+            }
+            match token {
+                Token::Utf16(hi @ 0xD800..=0xDBFF) => high = Some((hi, pos)),
+                Token::Byte(byte) => out.push(byte),
+                Token::CodePoint(cp) | Token::Utf16(cp) if is_surrogate(cp) => Self::lone_surrogate(cp, pos, mode, &mut out)?,
+                Token::CodePoint(cp) | Token::Utf16(cp) => {
+                    let ch =
+                        char::from_u32(cp).ok_or_else(|| MbaseError::invalid_input_at(pos, format!("invalid codepoint: U+{:X}", cp)))?;
+                    out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+            }
+            pos += len;
+        }
+        if let Some((hi, at)) = high {
+            Self::lone_surrogate(hi, at, mode, &mut out)?;
         }
 
-        Ok(result.into_bytes())
+        Ok(out)
     }
 
     fn detect_score(&self, input: &str) -> DetectCandidate {
@@ -101,48 +309,100 @@ impl Codec for UnicodeCodepoints {
     }
 }
 
-pub struct TapCode;
+/// A tap code square: one string per row, one cell per character, plus the letters that are
+/// tapped as another letter's cell because the square has no room for them.
+struct TapGrid {
+    rows: &'static [&'static str],
+    merges: &'static [(char, char)],
+    /// Letter tapped for input bytes that are not UTF-8 under `--opt utf8=lenient`.
+    marker: char,
+}
 
-impl Codec for TapCode {
-    fn meta(&self) -> CodecMeta {
-        CodecMeta {
-            name: "tapcode",
-            aliases: &["tap", "knock"],
-            alphabet: "12345 ",
-            multibase_code: None,
-            padding: PaddingRule::None,
-            case_sensitivity: CaseSensitivity::Insensitive,
-            description: "Tap code (Polybius square as digit pairs)",
+/// The classic 5x5 tap code: K is tapped as C.
+const LATIN: TapGrid = TapGrid {
+    rows: &["ABCDE", "FGHIJ", "LMNOP", "QRSTU", "VWXYZ"],
+    merges: &[('K', 'C')],
+    marker: 'X',
+};
+
+/// The Polybius square layout: J is tapped as I and K has its own cell.
+const LATIN_IJ: TapGrid = TapGrid {
+    rows: &["ABCDE", "FGHIK", "LMNOP", "QRSTU", "VWXYZ"],
+    merges: &[('J', 'I')],
+    marker: 'X',
+};
+
+const LATIN_DIGITS: TapGrid = TapGrid {
+    rows: &["ABCDEF", "GHIJKL", "MNOPQR", "STUVWX", "YZ0123", "456789"],
+    merges: &[],
+    marker: 'X',
+};
+
+/// The Russian prison tap code: 30 letters in five rows of six, Ё/Й/Ъ tapped as Е/И/Ь.
+const CYRILLIC: TapGrid = TapGrid {
+    rows: &["АБВГДЕ", "ЖЗИКЛМ", "НОПРСТ", "УФХЦЧШ", "ЩЫЬЭЮЯ"],
+    merges: &[('Ё', 'Е'), ('Й', 'И'), ('Ъ', 'Ь')],
+    marker: 'Х',
+};
+
+/// How letters without a cell of their own are encoded (`--opt merge=`).
+#[derive(Clone, Copy, PartialEq)]
+enum Merge {
+    /// Tap them as the cell they share (K as C in the Latin square).
+    Shared,
+    /// The Latin square with I/J sharing a cell instead of C/K.
+    IJ,
+    /// Reject them.
+    None,
+}
+
+impl Merge {
+    fn from_options(codec: &str, opts: &CodecOptions, ij: bool) -> Result<Self> {
+        opts.ensure_known(codec, &["merge", "utf8"])?;
+        match opts.get("merge") {
+            None => Ok(Merge::Shared),
+            Some("none") => Ok(Merge::None),
+            Some("ck") if ij => Ok(Merge::Shared),
+            Some("ij") if ij => Ok(Merge::IJ),
+            Some(other) => Err(MbaseError::invalid_input(format!(
+                "option 'merge' expects {}, got '{}'",
+                if ij { "ck, ij or none" } else { "none" },
+                other
+            ))),
         }
     }
+}
 
-    fn encode(&self, input: &[u8]) -> Result<String> {
-        let text = String::from_utf8_lossy(input).to_uppercase();
-        let codes: Vec<String> = text
-            .chars()
-            .filter_map(|c| {
-                let pos = match c {
-                    'A'..='Z' => {
-                        let mut p = c as u8 - b'A';
-                        if c >= 'K' {
-                            // K maps to same position as C (position 2)
-                            if c == 'K' {
-                                p = 2;
-                            } else {
-                                // L-Z: subtract 1 because K is skipped
-                                p -= 1;
-                            }
-                        }
-                        Some(p)
-                    }
-                    ' ' => return Some("  ".to_string()),
-                    _ => None,
-                }?;
-                let row = pos / 5 + 1;
-                let col = pos % 5 + 1;
-                Some(format!("{}{}", row, col))
-            })
-            .collect();
+impl TapGrid {
+    fn cell(&self, c: char, merge: Merge) -> Result<Option<(usize, usize)>> {
+        let target = match self.merges.iter().find(|&&(from, _)| from == c) {
+            Some(_) if merge == Merge::None => {
+                return Err(MbaseError::invalid_input(format!("'{}' has no cell of its own and merge=none is set", c)))
+            }
+            Some(&(_, to)) => to,
+            None => c,
+        };
+        Ok(self
+            .rows
+            .iter()
+            .enumerate()
+            .find_map(|(r, row)| row.chars().position(|cell| cell == target).map(|col| (r, col))))
+    }
+
+    fn letter(&self, row: usize, col: usize) -> Option<char> {
+        self.rows.get(row)?.chars().nth(col)
+    }
+
+    fn encode(&self, codec: &str, input: &[u8], merge: Merge, policy: Utf8Policy) -> Result<String> {
+        let text = util::text_input(codec, input, policy, self.marker)?.to_uppercase();
+        let mut codes = Vec::new();
+        for c in text.chars() {
+            if c == ' ' {
+                codes.push("  ".to_string());
+            } else if let Some((row, col)) = self.cell(c, merge)? {
+                codes.push(format!("{}{}", row + 1, col + 1));
+            }
+        }
 
         if codes.is_empty() {
             return Err(MbaseError::invalid_input("no encodable characters found"));
@@ -152,52 +412,29 @@ impl Codec for TapCode {
     }
 
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
-        let cleaned = if mode == Mode::Lenient {
-            input.trim().to_string()
-        } else {
-            input.to_string()
-        };
+        let cleaned = if mode == Mode::Lenient { input.trim() } else { input };
 
         let mut result = String::new();
 
         for pair in cleaned.split_whitespace() {
-            if pair.is_empty() {
-                continue;
-            }
-
-            if pair.len() != 2 {
+            let mut chars = pair.chars();
+            if pair.chars().count() != 2 {
                 return Err(MbaseError::invalid_input(format!("invalid tap code pair: {}", pair)));
             }
 
-            let row = pair
-                .chars()
-                .nth(0)
-                .unwrap()
-                .to_digit(10)
-                .ok_or_else(|| MbaseError::invalid_input(format!("invalid row digit: {}", pair)))?;
-            let col = pair
-                .chars()
-                .nth(1)
-                .unwrap()
-                .to_digit(10)
-                .ok_or_else(|| MbaseError::invalid_input(format!("invalid col digit: {}", pair)))?;
-
-            if row < 1 || row > 5 || col < 1 || col > 5 {
-                return Err(MbaseError::invalid_input(format!("coordinates out of range: {}", pair)));
-            }
-
-            let pos = (row - 1) * 5 + (col - 1);
-
-            // Tap code grid: A-J (pos 0-9), then L-Z (pos 10-24)
-            // K shares position 2 with C
-            let ch = if pos == 2 {
-                'C' // C/K share this position, decode as C
-            } else if pos < 10 {
-                (b'A' + pos as u8) as char
-            } else {
-                // For pos >= 10, we're in L-Z range, add 1 to skip K
-                (b'A' + pos as u8 + 1) as char
+            let digit = |c: Option<char>, what: &str| {
+                c.and_then(|c| c.to_digit(10))
+                    .map(|d| d as usize)
+                    .ok_or_else(|| MbaseError::invalid_input(format!("invalid {} digit: {}", what, pair)))
             };
+            let (row, col) = (digit(chars.next(), "row")?, digit(chars.next(), "col")?);
+
+            // Merged letters decode as the letter that owns the cell (C for C/K).
+            let ch = row
+                .checked_sub(1)
+                .zip(col.checked_sub(1))
+                .and_then(|(r, c)| self.letter(r, c))
+                .ok_or_else(|| MbaseError::invalid_input(format!("coordinates out of range: {}", pair)))?;
 
             result.push(ch);
         }
@@ -205,43 +442,198 @@ impl Codec for TapCode {
         Ok(result.into_bytes())
     }
 
-    fn detect_score(&self, input: &str) -> DetectCandidate {
-        let mut confidence = 0.0;
-        let mut reasons = Vec::new();
-        let warnings = Vec::new();
-
+    /// `confidence` when every token is a pair inside the grid, less when most are.
+    fn detect(&self, name: &str, input: &str, confidence: f64) -> DetectCandidate {
         if input.is_empty() {
             return DetectCandidate {
-                codec: "tapcode".to_string(),
+                codec: name.to_string(),
                 confidence: 0.0,
                 reasons: vec!["empty input".to_string()],
                 warnings: vec![],
             };
         }
 
+        let rows = self.rows.len() as u32;
+        let cols = self.rows[0].chars().count() as u32;
         let parts: Vec<&str> = input.split_whitespace().collect();
         let valid_pairs = parts
             .iter()
-            .filter(|p| p.len() == 2 && p.chars().all(|c| c >= '1' && c <= '5'))
+            .filter(|p| {
+                let digits: Vec<u32> = p.chars().filter_map(|c| c.to_digit(10)).collect();
+                p.len() == 2 && digits.len() == 2 && (1..=rows).contains(&digits[0]) && (1..=cols).contains(&digits[1])
+            })
             .count();
 
-        if valid_pairs == parts.len() && valid_pairs > 0 {
-            confidence = 0.8;
-            reasons.push(format!("all {} tokens are valid tap code pairs (11-55)", valid_pairs));
+        let mut reasons = Vec::new();
+        let confidence = if valid_pairs == parts.len() && valid_pairs > 0 {
+            reasons.push(format!("all {} tokens are valid tap code pairs (11-{}{})", valid_pairs, rows, cols));
+            confidence
         } else if valid_pairs > parts.len() / 2 {
-            confidence = 0.4;
             reasons.push(format!("{}/{} tokens valid", valid_pairs, parts.len()));
-        }
+            confidence / 2.0
+        } else {
+            0.0
+        };
 
         DetectCandidate {
-            codec: "tapcode".to_string(),
+            codec: name.to_string(),
             confidence,
             reasons,
-            warnings,
+            warnings: vec![],
         }
     }
 }
 
+pub struct TapCode;
+
+impl Codec for TapCode {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "tapcode",
+            aliases: &["tap", "knock"],
+            alphabet: "12345 ",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Tap code (Polybius square as digit pairs)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let policy = opts.utf8_policy()?;
+        match Merge::from_options("tapcode", opts, true)? {
+            Merge::IJ => LATIN_IJ.encode("tapcode", input, Merge::Shared, policy),
+            merge => LATIN.encode("tapcode", input, merge, policy),
+        }
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        match Merge::from_options("tapcode", opts, true)? {
+            Merge::IJ => LATIN_IJ.decode(input, mode),
+            _ => LATIN.decode(input, mode),
+        }
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn lossy(&self) -> bool {
+        true
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        LATIN.detect("tapcode", input, 0.8)
+    }
+}
+
+/// Tap code on a 6x6 square that adds the digits, so no letters share a cell.
+pub struct TapCode6;
+
+impl Codec for TapCode6 {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "tapcode6",
+            aliases: &["tap6", "knock6"],
+            alphabet: "123456 ",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Tap code on a 6x6 square with digits (A-Z, 0-9)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        opts.ensure_known("tapcode6", &["utf8"])?;
+        LATIN_DIGITS.encode("tapcode6", input, Merge::Shared, opts.utf8_policy()?)
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        LATIN_DIGITS.decode(input, mode)
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn lossy(&self) -> bool {
+        true
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        // Pairs within 11-55 are more likely the classic square.
+        let candidate = LATIN_DIGITS.detect("tapcode6", input, 0.8);
+        if input.contains('6') {
+            candidate
+        } else {
+            DetectCandidate {
+                confidence: candidate.confidence.min(0.5),
+                ..candidate
+            }
+        }
+    }
+}
+
+/// The Russian tap code on a 5x6 Cyrillic square.
+pub struct TapCodeCyrillic;
+
+impl Codec for TapCodeCyrillic {
+    fn meta(&self) -> CodecMeta {
+        CodecMeta {
+            name: "tapcode-ru",
+            aliases: &["tap-ru", "tapcode-cyrillic"],
+            alphabet: "123456 ",
+            multibase_code: None,
+            padding: PaddingRule::None,
+            case_sensitivity: CaseSensitivity::Insensitive,
+            description: "Russian tap code (5x6 Cyrillic square, Ё/Й/Ъ merged)",
+        }
+    }
+
+    fn encode(&self, input: &[u8]) -> Result<String> {
+        self.encode_with(input, &CodecOptions::default())
+    }
+
+    fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+        self.decode_with(input, mode, &CodecOptions::default())
+    }
+
+    fn encode_with(&self, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        let merge = Merge::from_options("tapcode-ru", opts, false)?;
+        CYRILLIC.encode("tapcode-ru", input, merge, opts.utf8_policy()?)
+    }
+
+    fn decode_with(&self, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        Merge::from_options("tapcode-ru", opts, false)?;
+        CYRILLIC.decode(input, mode)
+    }
+
+    fn text_only(&self) -> bool {
+        true
+    }
+
+    fn lossy(&self) -> bool {
+        true
+    }
+
+    fn detect_score(&self, input: &str) -> DetectCandidate {
+        // The digits alone cannot tell it from tapcode6; decoding to Cyrillic is the real test.
+        CYRILLIC.detect("tapcode-ru", input, 0.3)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +683,81 @@ mod tests {
         assert_eq!(String::from_utf8(decoded).unwrap(), crab);
     }
 
+    #[test]
+    fn test_unicode_decode_syntaxes() {
+        let crab = "🦀".as_bytes();
+        for input in [
+            "U+1F980",
+            "\\u{1F980}",
+            "\\U0001F980",
+            "\\ud83e\\udd80",
+            "&#x1F980;",
+            "&#129408;",
+            "\\N{CRAB}",
+            "\\N{crab}",
+            "0x1F980",
+        ] {
+            assert_eq!(UnicodeCodepoints.decode(input, Mode::Strict).unwrap(), crab, "{}", input);
+        }
+        assert_eq!(UnicodeCodepoints.decode("\\u{48}\\u{69}&#33;", Mode::Strict).unwrap(), b"Hi!");
+        assert_eq!(
+            UnicodeCodepoints
+                .decode("\\N{LATIN_SMALL_LETTER_E_WITH_ACUTE}", Mode::Lenient)
+                .unwrap(),
+            "é".as_bytes()
+        );
+        assert!(UnicodeCodepoints
+            .decode("\\N{LATIN_SMALL_LETTER_E_WITH_ACUTE}", Mode::Strict)
+            .is_err());
+        assert!(matches!(
+            UnicodeCodepoints.decode("U+0041 \\N{NO SUCH NAME}", Mode::Strict),
+            Err(MbaseError::InvalidInputAt { position: 7, .. })
+        ));
+        assert!(UnicodeCodepoints.decode("U+110000", Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_unicode_lone_surrogates() {
+        for input in ["U+D800", "\\ud83e", "\\udd80 U+0041", "\\ud83e U+0041"] {
+            assert!(UnicodeCodepoints.decode(input, Mode::Strict).is_err(), "{}", input);
+        }
+        assert_eq!(UnicodeCodepoints.decode("\\ud83e U+0041", Mode::Lenient).unwrap(), "\u{FFFD}A".as_bytes());
+        assert!(matches!(UnicodeCodepoints.decode("U+0041 \\ud83e", Mode::Strict), Err(MbaseError::InvalidInputAt { position: 7, .. })));
+    }
+
+    #[test]
+    fn test_unicode_encode_styles() {
+        let encode = |style: &str, input: &str| {
+            UnicodeCodepoints
+                .encode_with(input.as_bytes(), &CodecOptions::new().with("style", style))
+                .unwrap()
+        };
+        assert_eq!(encode("rust", "A🦀"), "\\u{41}\\u{1F980}");
+        assert_eq!(encode("html", "A🦀"), "&#x41;&#x1F980;");
+        assert_eq!(encode("json", "A🦀"), "\\u0041\\ud83e\\udd80");
+        assert_eq!(encode("python", "é🦀"), "\\u00e9\\U0001f980");
+        assert_eq!(encode("name", "é🦀\u{E000}"), "\\N{LATIN SMALL LETTER E WITH ACUTE}\\N{CRAB}\\ue000");
+        for style in ["plus", "rust", "html", "json", "python", "name"] {
+            let encoded = encode(style, "Grüße 🦀!");
+            assert_eq!(UnicodeCodepoints.decode(&encoded, Mode::Strict).unwrap(), "Grüße 🦀!".as_bytes(), "{}", style);
+        }
+        assert!(UnicodeCodepoints
+            .encode_with(b"A", &CodecOptions::new().with("style", "c"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_unicode_raw_bytes_roundtrip() {
+        let data = b"a\xff\xc3(\xe2\x82\xac";
+        let encoded = UnicodeCodepoints.encode(data).unwrap();
+        assert_eq!(encoded, "U+0061 \\xFF \\xC3 U+0028 U+20AC");
+        assert_eq!(UnicodeCodepoints.decode(&encoded, Mode::Strict).unwrap(), data);
+        let rust = UnicodeCodepoints
+            .encode_with(data, &CodecOptions::new().with("style", "rust"))
+            .unwrap();
+        assert_eq!(UnicodeCodepoints.decode(&rust, Mode::Strict).unwrap(), data);
+    }
+
     #[test]
     fn test_tapcode_encode() {
         assert_eq!(TapCode.encode(b"HELLO").unwrap(), "23 15 31 31 34");
@@ -335,5 +802,53 @@ mod tests {
         assert!(TapCode.decode("16", Mode::Strict).is_err());
         assert!(TapCode.decode("61", Mode::Strict).is_err());
         assert!(TapCode.decode("00", Mode::Strict).is_err());
+        assert!(TapCode.decode("é", Mode::Strict).is_err());
+        assert!(TapCode.decode("1é", Mode::Strict).unwrap_err().to_string().contains("col digit"));
+    }
+
+    #[test]
+    fn test_tapcode_merge_options() {
+        let ij = CodecOptions::new().with("merge", "ij");
+        assert_eq!(TapCode.encode_with(b"JK", &ij).unwrap(), "24 25");
+        assert_eq!(TapCode.decode_with("24 25", Mode::Strict, &ij).unwrap(), b"IK");
+        assert_eq!(TapCode.decode("24 25", Mode::Strict).unwrap(), b"IJ");
+
+        let none = CodecOptions::new().with("merge", "none");
+        assert_eq!(TapCode.encode_with(b"CAT", &none).unwrap(), "13 11 44");
+        assert!(TapCode.encode_with(b"KAT", &none).is_err());
+        assert!(TapCode.encode_with(b"A", &CodecOptions::new().with("merge", "xy")).is_err());
+        assert!(TapCode6.encode_with(b"A", &none).is_err());
+    }
+
+    #[test]
+    fn test_tapcode_invalid_utf8() {
+        let lenient = CodecOptions::new().with("utf8", "lenient");
+        assert!(TapCode.encode(b"HI\xff").is_err());
+        assert_eq!(TapCode.encode_with(b"HI\xff", &lenient).unwrap(), "23 24 53");
+        assert!(TapCode6.encode(b"HI\xff").is_err());
+        let ru = TapCodeCyrillic.encode_with(b"\xff", &lenient).unwrap();
+        assert_eq!(TapCodeCyrillic.decode(&ru, Mode::Strict).unwrap(), "Х".as_bytes());
+    }
+
+    #[test]
+    fn test_tapcode6() {
+        assert_eq!(TapCode6.encode(b"K9").unwrap(), "25 66");
+        assert_eq!(TapCode6.encode(b"Agent 007").unwrap(), "11 21 15 32 42    53 53 64");
+        assert_eq!(TapCode6.decode("11 21 15 32 42 53 53 64", Mode::Strict).unwrap(), b"AGENT007");
+        assert!(TapCode6.decode("17", Mode::Strict).is_err());
+        assert!(TapCode6.detect_score("11 66").confidence > TapCode.detect_score("11 66").confidence);
+        assert!(TapCode.detect_score("11 23").confidence > TapCode6.detect_score("11 23").confidence);
+    }
+
+    #[test]
+    fn test_tapcode_cyrillic() {
+        let encoded = TapCodeCyrillic.encode("Привет".as_bytes()).unwrap();
+        assert_eq!(encoded, "33 34 23 13 16 36");
+        assert_eq!(TapCodeCyrillic.decode(&encoded, Mode::Strict).unwrap(), "ПРИВЕТ".as_bytes());
+        assert_eq!(TapCodeCyrillic.encode("ёж".as_bytes()).unwrap(), "16 21");
+        assert_eq!(TapCodeCyrillic.decode("15 16", Mode::Strict).unwrap(), "ДЕ".as_bytes());
+        assert!(TapCodeCyrillic.decode("61", Mode::Strict).is_err());
+        let none = CodecOptions::new().with("merge", "none");
+        assert!(TapCodeCyrillic.encode_with("Й".as_bytes(), &none).is_err());
     }
 }
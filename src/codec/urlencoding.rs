@@ -1,9 +1,51 @@
+//! RFC 3986 percent-encoding, plus helpers for input that was percent-encoded more than once
+//! (`%2520` for a space).
+
+use alloc::borrow::Cow;
+
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 pub struct UrlEncoding;
 
+/// Number of well-formed `%XX` escapes in `text`.
+pub fn escape_count(text: &str) -> usize {
+    text.as_bytes()
+        .windows(3)
+        .filter(|w| w[0] == b'%' && w[1].is_ascii_hexdigit() && w[2].is_ascii_hexdigit())
+        .count()
+}
+
+/// Whether `text` holds an escaped percent sign followed by two hex digits (`%2520`), the mark
+/// of a value percent-encoded twice.
+pub fn double_encoded(text: &str) -> bool {
+    text.as_bytes()
+        .windows(5)
+        .any(|w| w[0] == b'%' && w[1] == b'2' && w[2] == b'5' && w[3].is_ascii_hexdigit() && w[4].is_ascii_hexdigit())
+}
+
+/// Percent-decodes `input` up to `max_passes` times and returns the output of every pass.
+/// Decoding stops early when an output has no escapes left, is not UTF-8, or does not decode
+/// again (a literal `100%`); only a failing first pass is an error.
+pub fn decode_passes(input: &str, mode: Mode, max_passes: usize) -> Result<Vec<Vec<u8>>> {
+    let mut passes = vec![UrlEncoding.decode(input, mode)?];
+    while passes.len() < max_passes {
+        let Ok(text) = core::str::from_utf8(passes.last().unwrap()) else {
+            break;
+        };
+        if escape_count(text) == 0 {
+            break;
+        }
+        match UrlEncoding.decode(text, mode) {
+            Ok(next) => passes.push(next),
+            Err(_) => break,
+        }
+    }
+    Ok(passes)
+}
+
 impl Codec for UrlEncoding {
     fn meta(&self) -> CodecMeta {
         CodecMeta {
@@ -30,6 +72,19 @@ impl Codec for UrlEncoding {
         Ok(result)
     }
 
+    fn decode_cow<'a>(&self, input: &'a str, mode: Mode) -> Result<Cow<'a, [u8]>> {
+        // Lenient mode can still borrow when the only whitespace is around the text.
+        let text = match mode {
+            Mode::Strict => input,
+            Mode::Lenient => input.trim_matches(|c: char| c.is_ascii_whitespace()),
+        };
+        let unescaped = text.is_ascii() && !text.contains('%');
+        if unescaped && (mode == Mode::Strict || !text.bytes().any(|b| b.is_ascii_whitespace())) {
+            return Ok(Cow::Borrowed(text.as_bytes()));
+        }
+        self.decode(input, mode).map(Cow::Owned)
+    }
+
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         let cleaned = util::clean_for_mode(input, mode);
         let mut result = Vec::new();
@@ -58,6 +113,14 @@ impl Codec for UrlEncoding {
         Ok(result)
     }
 
+    fn decode_warnings(&self, input: &str, _mode: Mode) -> Vec<String> {
+        if double_encoded(input) {
+            vec!["input looks percent-encoded twice (%25XX); --mode lenient --max-passes 2 decodes it again".to_string()]
+        } else {
+            Vec::new()
+        }
+    }
+
     fn detect_score(&self, input: &str) -> DetectCandidate {
         let mut confidence = 0.0;
         let mut reasons = Vec::new();
@@ -109,6 +172,10 @@ impl Codec for UrlEncoding {
             warnings,
         }
     }
+
+    fn accepts_any_text(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +197,32 @@ mod tests {
         assert_eq!(UrlEncoding.decode("test%40example.com", Mode::Strict).unwrap(), b"test@example.com");
     }
 
+    #[test]
+    fn test_decode_cow() {
+        let input = "plain-text_1.0";
+        let decoded = UrlEncoding.decode_cow(input, Mode::Strict).unwrap();
+        assert!(matches!(decoded, Cow::Borrowed(b) if b.as_ptr() == input.as_ptr()));
+        assert!(matches!(UrlEncoding.decode_cow(" abc\n", Mode::Lenient).unwrap(), Cow::Borrowed(b"abc")));
+        assert_eq!(UrlEncoding.decode_cow("a b", Mode::Lenient).unwrap().as_ref(), b"ab");
+        assert!(matches!(UrlEncoding.decode_cow("a%20b", Mode::Strict).unwrap(), Cow::Owned(ref v) if v == b"a b"));
+        assert!(UrlEncoding.decode_cow("a%2", Mode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_decode_passes() {
+        assert!(double_encoded("a%2520b"));
+        assert!(!double_encoded("a%20b%25"));
+        assert_eq!(escape_count("%41%4g%%42"), 2);
+
+        let passes = decode_passes("a%252520b", Mode::Lenient, 5).unwrap();
+        assert_eq!(passes, [&b"a%2520b"[..], b"a%20b", b"a b"]);
+        assert_eq!(decode_passes("a%252520b", Mode::Lenient, 2).unwrap().len(), 2);
+        assert_eq!(decode_passes("100%2525", Mode::Lenient, 5).unwrap(), [&b"100%25"[..], b"100%"]);
+        assert_eq!(decode_passes("%25zz", Mode::Lenient, 5).unwrap(), [b"%zz"]);
+        assert!(decode_passes("%zz", Mode::Lenient, 5).is_err());
+        assert_eq!(UrlEncoding.decode_warnings("x%2541", Mode::Strict).len(), 1);
+    }
+
     #[test]
     fn test_url_roundtrip() {
         let data = b"Hello, World! @#$%^&*()";
@@ -141,7 +234,7 @@ mod tests {
     fn test_url_unreserved() {
         let unreserved = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_.~";
         let encoded = UrlEncoding.encode(unreserved).unwrap();
-        assert_eq!(encoded, std::str::from_utf8(unreserved).unwrap());
+        assert_eq!(encoded, core::str::from_utf8(unreserved).unwrap());
     }
 
     #[test]
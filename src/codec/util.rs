@@ -1,5 +1,8 @@
+use alloc::borrow::Cow;
+
 use crate::error::{MbaseError, Result};
-use crate::types::Mode;
+use crate::prelude::*;
+use crate::types::{LenientPolicy, Mode, Utf8Policy};
 
 pub mod confidence {
     pub const MULTIBASE_MATCH: f64 = 0.95;
@@ -7,6 +10,7 @@ pub mod confidence {
     pub const PARTIAL_MATCH: f64 = 0.50;
     pub const WEAK_MATCH: f64 = 0.30;
 
+    use crate::prelude::*;
     use crate::types::DetectCandidate;
 
     #[allow(dead_code)]
@@ -33,8 +37,30 @@ pub mod confidence {
 pub fn clean_for_mode(input: &str, mode: Mode) -> String {
     match mode {
         Mode::Strict => input.to_string(),
-        Mode::Lenient => input.chars().filter(|c| !c.is_ascii_whitespace()).collect(),
+        Mode::Lenient => {
+            let cleaned: String = input.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+            if cleaned.len() != input.len() {
+                tracing::trace!(removed = input.len() - cleaned.len(), "lenient mode stripped whitespace");
+            }
+            cleaned
+        }
+    }
+}
+
+/// Applies `policy` to lenient input, keeping separators for which `is_symbol` holds
+/// ([`Codec::is_symbol`](super::Codec::is_symbol)).
+pub fn clean_with_policy<'a>(input: &'a str, mode: Mode, policy: &LenientPolicy, is_symbol: impl Fn(char) -> bool) -> Cow<'a, str> {
+    if mode == Mode::Strict || policy.is_empty() {
+        return Cow::Borrowed(input);
+    }
+    let mut cleaned: String = input.chars().filter(|&c| !policy.strips(c) || is_symbol(c)).collect();
+    if policy.collapse_whitespace {
+        cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+    if cleaned.len() != input.len() {
+        tracing::trace!(removed = input.len() - cleaned.len(), "lenient policy stripped separators");
     }
+    Cow::Owned(cleaned)
 }
 
 pub fn validate_alphabet(input: &str, alphabet: &str, mode: Mode) -> Result<()> {
@@ -59,6 +85,141 @@ pub fn validate_alphabet_with_padding(input: &str, alphabet: &str, allow_padding
     Ok(())
 }
 
+/// Symbol values of an ASCII alphabet, indexed by byte, so decoding a character is one lookup
+/// instead of a scan of the alphabet. Built at compile time: `static T: SymbolTable = SymbolTable::new(A);`.
+pub struct SymbolTable([u8; 128]);
+
+impl SymbolTable {
+    const NONE: u8 = u8::MAX;
+
+    pub const fn new(alphabet: &str) -> Self {
+        let symbols = alphabet.as_bytes();
+        assert!(symbols.len() < Self::NONE as usize, "alphabet too long for a symbol table");
+        let mut table = [Self::NONE; 128];
+        let mut i = 0;
+        while i < symbols.len() {
+            assert!(symbols[i].is_ascii(), "symbol table alphabets must be ASCII");
+            table[symbols[i] as usize] = i as u8;
+            i += 1;
+        }
+        Self(table)
+    }
+
+    /// The value of `c`, or `None` when it is not in the alphabet.
+    #[inline]
+    pub fn value(&self, c: char) -> Option<u8> {
+        let value = *self.0.get(c as usize)?;
+        (value != Self::NONE).then_some(value)
+    }
+}
+
+/// Rejects a final symbol that carries set bits past the end of the data, as in `aGVsbG9` for
+/// `aGVsbG8`. `input` must have padding removed; symbols outside `alphabet` are left to the decoder.
+pub fn check_trailing_bits(input: &str, alphabet: &str, bits_per_char: usize) -> Result<()> {
+    let Some((position, last)) = input.char_indices().last() else {
+        return Ok(());
+    };
+    let unused = input.len() * bits_per_char % 8;
+    let Some(value) = alphabet.find(last) else {
+        return Ok(());
+    };
+    let mask = (1 << unused) - 1;
+    if unused == 0 || value & mask == 0 {
+        return Ok(());
+    }
+    let canonical = alphabet.as_bytes()[value & !mask] as char;
+    Err(MbaseError::invalid_input(format!(
+        "non-zero trailing bits in final character '{}' at position {} (canonical: '{}')",
+        last, position, canonical
+    )))
+}
+
+/// Average [`byte_weight`] of random bytes: 64 weigh 1.0 and 36 weigh 0.5.
+const RANDOM_FIT: f64 = 82.0 / 256.0;
+
+/// How typical `b` is of a payload: letters, digits, space and NUL (zero padding in binary
+/// fields) fully, other printable ASCII and line breaks half.
+fn byte_weight(b: u8) -> f64 {
+    if b == 0 || b == b' ' || b.is_ascii_alphanumeric() {
+        1.0
+    } else if b.is_ascii_graphic() || b == b'\t' || b == b'\n' || b == b'\r' {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+/// How well the symbol values of `input`, read in `alphabet` (case-insensitively) with
+/// `bits_per_char` bits each, fit a typical payload, from 0.0 (no better than random bytes) to
+/// 1.0. Alphabets that accept the same characters give the values different frequencies, so the
+/// right one packs them into text or zero-padded fields and the wrong ones into noise.
+/// `None` when a symbol is outside the alphabet or the input is shorter than two bytes.
+pub fn payload_fit(input: &str, alphabet: &str, bits_per_char: u32) -> Option<f64> {
+    let mut bytes = Vec::new();
+    let (mut acc, mut bits) = (0u32, 0u32);
+    for c in input.trim_end_matches('=').chars() {
+        let value = alphabet.chars().position(|a| a.eq_ignore_ascii_case(&c))? as u32;
+        acc = (acc << bits_per_char | value) & 0xffff;
+        bits += bits_per_char;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+    }
+    if bytes.len() < 2 {
+        return None;
+    }
+    let share = bytes.iter().map(|&b| byte_weight(b)).sum::<f64>() / bytes.len() as f64;
+    Some(((share - RANDOM_FIT) / (1.0 - RANDOM_FIT)).clamp(0.0, 1.0))
+}
+
+/// Confidence to add for [`payload_fit`], up to `weight`, with a reason when the fit is good.
+pub fn payload_fit_bonus(input: &str, alphabet: &str, bits_per_char: u32, weight: f64, reasons: &mut Vec<String>) -> f64 {
+    let Some(fit) = payload_fit(input, alphabet, bits_per_char) else {
+        return 0.0;
+    };
+    if fit >= 0.5 {
+        reasons.push(format!("symbol values fit a typical payload ({:.0}%)", fit * 100.0));
+    }
+    weight * fit
+}
+
+/// Reads a wordlist named by a codec option (`--opt wordlist=FILE`).
+/// `input` as text for a text-only codec: an error at the first invalid byte under the strict
+/// policy, each invalid sequence replaced by `marker` under the lenient one.
+pub fn text_input<'a>(codec: &str, input: &'a [u8], policy: Utf8Policy, marker: char) -> Result<Cow<'a, str>> {
+    if let Ok(text) = core::str::from_utf8(input) {
+        return Ok(Cow::Borrowed(text));
+    }
+    let mut text = String::with_capacity(input.len());
+    for chunk in input.utf8_chunks() {
+        text.push_str(chunk.valid());
+        if let Some(&byte) = chunk.invalid().first() {
+            if policy == Utf8Policy::Strict {
+                return Err(MbaseError::invalid_input_at(
+                    text.len(),
+                    format!(
+                        "byte 0x{:02X} is not UTF-8; {} encodes text only (--opt utf8=lenient writes '{}' instead)",
+                        byte, codec, marker
+                    ),
+                ));
+            }
+            text.push(marker);
+        }
+    }
+    Ok(Cow::Owned(text))
+}
+
+#[cfg(feature = "std")]
+pub fn read_wordlist(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| MbaseError::invalid_input(format!("cannot read wordlist '{}': {}", path, e)))
+}
+
+#[cfg(not(feature = "std"))]
+pub fn read_wordlist(path: &str) -> Result<String> {
+    Err(MbaseError::invalid_input(format!("cannot read wordlist '{}': file access needs the std feature", path)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,6 +234,39 @@ mod tests {
         assert_eq!(clean_for_mode("ab c\td\n", Mode::Lenient), "abcd");
     }
 
+    #[test]
+    fn test_clean_with_policy() {
+        let policy = LenientPolicy::parse("-.,whitespace").unwrap();
+        assert_eq!(clean_with_policy("ab-cd.ef_g", Mode::Lenient, &policy, |c| "abcdefg_".contains(c)), "abcdef_g");
+        assert_eq!(clean_with_policy("ab-cd", Mode::Lenient, &policy, |c| "abcd-".contains(c)), "ab-cd");
+        let collapse = LenientPolicy {
+            collapse_whitespace: true,
+            ..Default::default()
+        };
+        assert_eq!(clean_with_policy("  .-  --.\n..-\n", Mode::Lenient, &collapse, |c| ".-".contains(c)), ".- --. ..-");
+        assert_eq!(clean_with_policy("ab-cd", Mode::Strict, &policy, |c| "abcd".contains(c)), "ab-cd");
+        assert!(LenientPolicy::parse("-x").is_err());
+    }
+
+    #[test]
+    fn test_payload_fit() {
+        // "hello world" in RFC 4648 base32; read as z-base-32 the same letters are noise.
+        let fit = |alphabet| payload_fit("nbswy3dpeb3w64tmmq", alphabet, 5).unwrap();
+        assert_eq!(fit("abcdefghijklmnopqrstuvwxyz234567"), 1.0);
+        assert!(fit("ybndrfg8ejkmcpqxot1uwisza345h769") < 0.7);
+        assert_eq!(payload_fit("NBSWY3DP", "abcdefghijklmnopqrstuvwxyz234567", 5), Some(1.0));
+        assert_eq!(payload_fit("nb", "abcdefghijklmnopqrstuvwxyz234567", 5), None);
+        assert_eq!(payload_fit("nbswy3d!", "abcdefghijklmnopqrstuvwxyz234567", 5), None);
+    }
+
+    #[test]
+    fn test_text_input() {
+        assert!(matches!(text_input("morse", b"SOS", Utf8Policy::Strict, '?').unwrap(), Cow::Borrowed("SOS")));
+        assert!(matches!(text_input("morse", b"S\xffS", Utf8Policy::Strict, '?'), Err(MbaseError::InvalidInputAt { position: 1, .. })));
+        assert_eq!(text_input("morse", b"S\xffO\xe2\x82S", Utf8Policy::Lenient, '?').unwrap(), "S?O?S");
+        assert_eq!(text_input("morse", "\u{e9}".as_bytes(), Utf8Policy::Strict, '?').unwrap(), "\u{e9}");
+    }
+
     #[test]
     fn test_validate_alphabet_success() {
         assert!(validate_alphabet("abc123", "abcdefghijklmnopqrstuvwxyz0123456789", Mode::Strict).is_ok());
@@ -108,4 +302,28 @@ mod tests {
         let result = validate_alphabet_with_padding("SGVsbG8=", "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/", false);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_symbol_table() {
+        static TABLE: SymbolTable = SymbolTable::new("0123456789ABCDEF");
+        assert_eq!(TABLE.value('0'), Some(0));
+        assert_eq!(TABLE.value('F'), Some(15));
+        assert_eq!(TABLE.value('f'), None);
+        assert_eq!(TABLE.value('\u{7f}'), None);
+        assert_eq!(TABLE.value('é'), None);
+    }
+
+    #[test]
+    fn test_check_trailing_bits() {
+        let b64 = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        assert!(check_trailing_bits("aGVsbG8", b64, 6).is_ok());
+        assert!(check_trailing_bits("aGVsbA", b64, 6).is_ok());
+        assert!(check_trailing_bits("", b64, 6).is_ok());
+        let err = check_trailing_bits("aGVsbG9", b64, 6).unwrap_err().to_string();
+        assert!(err.contains("'9' at position 6") && err.contains("canonical: '8'"), "{}", err);
+
+        let b32 = "abcdefghijklmnopqrstuvwxyz234567";
+        assert!(check_trailing_bits("nbswy3dp", b32, 5).is_ok());
+        assert!(check_trailing_bits("nbswy3dpeb", b32, 5).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use super::{util, Codec};
 use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, Mode, PaddingRule};
 
 fn encode_char(val: u8) -> char {
@@ -18,6 +19,40 @@ fn decode_char(c: char) -> Option<u8> {
     }
 }
 
+/// Strict per-line consistency: declared length vs quads carried, zero padding bits, checksum.
+fn check_line(data: &[u8], length: usize, encoded: &[char], line: usize) -> Result<()> {
+    let expected_quads = length.div_ceil(3);
+    let quads = encoded.len() / 4;
+    if quads != expected_quads {
+        return Err(MbaseError::invalid_input(format!(
+            "line {} declares {} bytes ({} characters) but carries {} characters",
+            line,
+            length,
+            expected_quads * 4,
+            encoded.len()
+        )));
+    }
+    if let Some(idx) = data[length..].iter().position(|&b| b != 0) {
+        return Err(MbaseError::invalid_input(format!(
+            "line {}: padding byte {} past the declared length {} is 0x{:02x}, expected 0",
+            line,
+            length + idx + 1,
+            length,
+            data[length + idx]
+        )));
+    }
+    if let Some(&checksum) = encoded.get(quads * 4) {
+        let expected = data[..length].iter().fold(0u32, |acc, &b| acc + u32::from(b)) % 64;
+        if decode_char(checksum) != Some(expected as u8) {
+            return Err(MbaseError::checksum_mismatch_at(
+                quads * 4 + 1,
+                format!("line {} checksum '{}', expected '{}'", line, checksum, encode_char(expected as u8)),
+            ));
+        }
+    }
+    Ok(())
+}
+
 pub struct Uuencode;
 
 impl Codec for Uuencode {
@@ -57,20 +92,23 @@ impl Codec for Uuencode {
         Ok(result)
     }
 
+    /// Strict mode checks every line: its quads must cover exactly the declared length, the
+    /// bits past that length must be zero, and an optional trailing checksum character (sum of
+    /// the line's bytes mod 64, as some encoders append) must match. Lenient mode truncates.
     fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
         if input.is_empty() {
             return Ok(Vec::new());
         }
 
         let mut result = Vec::new();
-        let lines: Vec<&str> = input.lines().collect();
+        let mut offset = 0;
 
-        for (line_num, line) in lines.iter().enumerate() {
-            let line = if mode == Mode::Lenient { line.trim_end() } else { *line };
-
-            if line.is_empty() {
-                continue;
-            }
+        for (line_num, raw) in input.split_inclusive('\n').enumerate() {
+            let line_start = offset;
+            offset += raw.chars().count();
+            let line = raw.trim_end_matches('\n').trim_end_matches('\r');
+            let line = if mode == Mode::Lenient { line.trim_end() } else { line };
+            let line_num = line_num + 1;
 
             let chars: Vec<char> = line.chars().collect();
             if chars.is_empty() {
@@ -79,7 +117,7 @@ impl Codec for Uuencode {
 
             let length = decode_char(chars[0]).ok_or_else(|| MbaseError::InvalidCharacter {
                 char: chars[0],
-                position: 0,
+                position: line_start,
             })? as usize;
 
             if length == 0 {
@@ -87,19 +125,23 @@ impl Codec for Uuencode {
             }
 
             let encoded_chars = &chars[1..];
+            let expected_quads = length.div_ceil(3);
             let mut line_data = Vec::new();
 
-            for quad in encoded_chars.chunks(4) {
+            for (q, quad) in encoded_chars.chunks(4).enumerate() {
                 if quad.len() < 4 {
-                    if mode == Mode::Strict {
-                        return Err(MbaseError::invalid_input(format!("incomplete quad at line {}", line_num + 1)));
+                    if mode == Mode::Strict && !(q == expected_quads && quad.len() == 1) {
+                        return Err(MbaseError::invalid_input(format!("incomplete quad at line {}", line_num)));
                     }
                     break;
                 }
 
                 let mut vals = [0u8; 4];
                 for (i, &c) in quad.iter().enumerate() {
-                    vals[i] = decode_char(c).ok_or_else(|| MbaseError::InvalidCharacter { char: c, position: 1 + i })?;
+                    vals[i] = decode_char(c).ok_or_else(|| MbaseError::InvalidCharacter {
+                        char: c,
+                        position: line_start + 1 + q * 4 + i,
+                    })?;
                 }
 
                 line_data.push((vals[0] << 2) | (vals[1] >> 4));
@@ -107,6 +149,9 @@ impl Codec for Uuencode {
                 line_data.push((vals[2] << 6) | vals[3]);
             }
 
+            if mode == Mode::Strict {
+                check_line(&line_data, length, encoded_chars, line_num)?;
+            }
             line_data.truncate(length);
             result.extend(line_data);
         }
@@ -251,4 +296,24 @@ mod tests {
         let score = codec.detect_score(&encoded);
         assert!(score.confidence > 0.5);
     }
+
+    #[test]
+    fn test_uuencode_strict_line_checks() {
+        let codec = Uuencode;
+        // "Cat" declared as 2 bytes: the third byte is non-zero padding.
+        let err = codec.decode("\"0V%T\n", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("line 1: padding byte 3"), "{}", err);
+        assert_eq!(codec.decode("\"0V%T\n", Mode::Lenient).unwrap(), b"Ca");
+
+        // Declared 6 bytes, only one quad present.
+        let err = codec.decode("&0V%T\n", Mode::Strict).unwrap_err().to_string();
+        assert!(err.contains("declares 6 bytes (8 characters) but carries 4"), "{}", err);
+
+        // Trailing checksum character: (0x43 + 0x61 + 0x74) % 64 = 24 -> '8'.
+        assert_eq!(codec.decode("#0V%T8\n", Mode::Strict).unwrap(), b"Cat");
+        assert!(matches!(codec.decode("#0V%T9\n", Mode::Strict), Err(MbaseError::ChecksumMismatchAt { .. })));
+
+        let err = codec.decode("#0V%T\n#0V~T\n", Mode::Strict).unwrap_err();
+        assert!(matches!(err, MbaseError::InvalidCharacter { char: '~', position: 9 }), "{:?}", err);
+    }
 }
@@ -0,0 +1,416 @@
+use super::report::{AlphabetRecovery, AnalyzeCandidate, AnalyzeResult};
+use crate::io::read_input;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+// Relative letter frequencies of English text (A-Z).
+const ENGLISH_FREQ: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153, 0.00772, 0.04025, 0.02406, 0.06749, 0.07507,
+    0.01929, 0.00095, 0.05987, 0.06327, 0.09056, 0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+const COMMON_WORDS: &[&str] = &[
+    "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on", "with", "he", "as", "you", "do", "at",
+    "this", "but", "his", "by", "from", "they", "we", "is", "are", "was", "or", "an", "will", "my", "all", "there", "what", "so", "if",
+    "me", "no", "can", "your",
+];
+
+/// Scores how much `data` looks like English plaintext, from 0.0 (noise) to 1.0.
+///
+/// Combines the printable ratio, the mean log-likelihood of the letters under
+/// English frequencies, and the share of very common English words.
+pub fn english_score(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let printable = data
+        .iter()
+        .filter(|&&b| b.is_ascii_graphic() || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t')
+        .count();
+    let printable_ratio = printable as f64 / data.len() as f64;
+
+    let letters: Vec<usize> = data
+        .iter()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| (b.to_ascii_lowercase() - b'a') as usize)
+        .collect();
+    if letters.is_empty() {
+        return 0.0;
+    }
+
+    // English text averages about -2.9 nats per letter; shuffled alphabets sit near -4.
+    let log_likelihood = letters.iter().map(|&i| ENGLISH_FREQ[i].ln()).sum::<f64>() / letters.len() as f64;
+    let letter_fit = ((log_likelihood + 4.2) / 1.3).clamp(0.0, 1.0);
+
+    let text = String::from_utf8_lossy(data).to_ascii_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_ascii_alphabetic()).filter(|w| !w.is_empty()).collect();
+    let common = words.iter().filter(|w| COMMON_WORDS.contains(w)).count();
+    let word_fit = (common as f64 / words.len() as f64 * 3.0).min(1.0);
+
+    printable_ratio * (0.7 * letter_fit + 0.3 * word_fit)
+}
+
+fn preview(data: &[u8]) -> String {
+    let s = String::from_utf8_lossy(data);
+    if s.chars().count() > 60 {
+        format!("{}...", s.chars().take(57).collect::<String>())
+    } else {
+        s.to_string()
+    }
+}
+
+fn key_space(codec: &str) -> Result<Vec<(String, CodecOptions)>> {
+    match codec {
+        "affine" => Ok((1..26)
+            .flat_map(|a: u32| {
+                (0..26).map(move |b: u32| {
+                    (format!("a={} b={}", a, b), CodecOptions::new().with("a", &a.to_string()).with("b", &b.to_string()))
+                })
+            })
+            .collect()),
+        "caesar" => Ok((1..26)
+            .map(|shift: u32| (format!("shift={}", shift), CodecOptions::new().with("shift", &shift.to_string())))
+            .collect()),
+        _ => Err(MbaseError::invalid_input(format!(
+            "brute-force analysis is not available for codec '{}' (supported: affine, caesar)",
+            codec
+        ))),
+    }
+}
+
+pub fn run_analyze(ctx: &Context, codec_name: &str, input: &InputSource, top_n: usize) -> Result<AnalyzeResult> {
+    let codec = ctx.registry.get(codec_name)?;
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let trimmed = text.trim();
+
+    let mut tried = 0;
+    let mut candidates = Vec::new();
+    for (params, opts) in key_space(codec.name())? {
+        // Keys the codec rejects (e.g. non-invertible multipliers) are skipped.
+        let Ok(decoded) = ctx.decode(codec, trimmed, Mode::Lenient, &opts) else {
+            continue;
+        };
+        tried += 1;
+        candidates.push(AnalyzeCandidate {
+            params,
+            score: english_score(&decoded),
+            output: preview(&decoded),
+        });
+    }
+
+    candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    candidates.truncate(top_n);
+
+    Ok(AnalyzeResult {
+        codec: codec.name().to_string(),
+        input_preview: preview(trimmed.as_bytes()),
+        tried,
+        candidates,
+    })
+}
+
+const STANDARD_BASE64: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Alphabets that shuffled base64 is often derived from; all their rotations are tried too.
+const KNOWN_BASE64_ALPHABETS: &[(&str, &str)] = &[
+    ("standard", STANDARD_BASE64),
+    ("url-safe", "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"),
+    ("crypt", "./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"),
+    ("bcrypt", "./ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"),
+    ("xxencode", "+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"),
+    ("reversed", "/+9876543210zyxwvutsrqponmlkjihgfedcbaZYXWVUTSRQPONMLKJIHGFEDCBA"),
+];
+
+/// Ciphertext symbols scored per candidate alphabet during recovery.
+const RECOVERY_SAMPLE: usize = 4096;
+
+/// Decodes sextets to bytes; a byte is `None` unless both sextets it draws bits from are known.
+fn decode_sextets(values: &[Option<u8>]) -> Vec<Option<u8>> {
+    let mut out = Vec::with_capacity(values.len() * 3 / 4);
+    for group in values.chunks(4) {
+        let pair = |i: usize, hi_shift: u32, lo_shift: u32| match (group.get(i).copied().flatten(), group.get(i + 1).copied().flatten()) {
+            (Some(a), Some(b)) => Some((a << hi_shift) | (b >> lo_shift)),
+            _ => None,
+        };
+        if group.len() >= 2 {
+            out.push(pair(0, 2, 4));
+        }
+        if group.len() >= 3 {
+            out.push(pair(1, 4, 2));
+        }
+        if group.len() == 4 {
+            out.push(pair(2, 6, 0));
+        }
+    }
+    out
+}
+
+/// Log-likelihood of a byte under a rough model of English text with some punctuation.
+fn byte_log_prob(b: u8) -> f64 {
+    let p = match b {
+        b' ' => 0.15,
+        b'a'..=b'z' => ENGLISH_FREQ[(b - b'a') as usize] * 0.75,
+        b'A'..=b'Z' => ENGLISH_FREQ[(b - b'A') as usize] * 0.03,
+        b'0'..=b'9' => 0.003,
+        b'.' | b',' | b'\'' | b'"' | b'-' | b':' | b';' | b'\n' | b'(' | b')' | b'{' | b'}' => 0.004,
+        _ if b.is_ascii_graphic() || b == b'\t' || b == b'\r' => 0.0005,
+        _ => 1e-6,
+    };
+    p.ln()
+}
+
+/// The most frequent English letter pairs, including word boundaries.
+const COMMON_BIGRAMS: &[&[u8; 2]] = &[
+    b"th", b"he", b"in", b"er", b"an", b"re", b"on", b"at", b"en", b"nd", b"ti", b"es", b"or", b"te", b"of", b"ed", b"is", b"it", b"al",
+    b"ar", b"st", b"to", b"nt", b"ng", b"se", b"ha", b"as", b"ou", b"io", b"le", b"ve", b"co", b"me", b"de", b"hi", b"ri", b"ro", b"ic",
+    b"ne", b"ea", b"ra", b"ce", b"e ", b"s ", b" t", b"d ", b"t ", b" a", b"n ", b" s", b" o", b" w", b"y ", b" i",
+];
+
+/// Scores partially decoded bytes; undetermined bytes count as average English text.
+fn plaintext_score(bytes: &[Option<u8>]) -> f64 {
+    let unigrams: f64 = bytes.iter().map(|b| b.map_or(-3.5, byte_log_prob)).sum();
+    let bigrams = bytes
+        .windows(2)
+        .filter(|w| matches!(w, [Some(a), Some(b)] if COMMON_BIGRAMS.contains(&&[*a, *b])))
+        .count();
+    unigrams + 1.5 * bigrams as f64
+}
+
+/// Recovers a shuffled base64 alphabet from ciphertext whose plaintext starts with `known`.
+///
+/// The known prefix pins down the symbols of its complete sextets. If those pairs fit a rotation
+/// of a well-known alphabet, that alphabet is used; otherwise the remaining symbols are assigned
+/// greedily, most frequent first, to whichever free value yields the most printable plaintext.
+pub fn recover_base64_alphabet(input: &InputSource, known: &[u8]) -> Result<AlphabetRecovery> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let cipher: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let cipher: Vec<char> = cipher[..cipher.len() - cipher.iter().rev().take_while(|&&c| c == '=').count()].to_vec();
+    let determined = known.len() * 8 / 6;
+    if determined == 0 {
+        return Err(MbaseError::invalid_input("known plaintext prefix must be at least 1 byte"));
+    }
+    if cipher.len() < determined {
+        return Err(MbaseError::invalid_input("ciphertext is shorter than the known plaintext prefix"));
+    }
+
+    let std_values: Vec<u8> = Context::default()
+        .registry
+        .get("base64")?
+        .encode(known)?
+        .chars()
+        .take(determined)
+        .map(|c| STANDARD_BASE64.find(c).unwrap() as u8)
+        .collect();
+
+    let mut symbols: Vec<Option<char>> = vec![None; 64];
+    for (i, (&c, &v)) in cipher.iter().zip(&std_values).enumerate() {
+        match (symbols[v as usize], symbols.iter().position(|&s| s == Some(c))) {
+            (Some(existing), _) if existing != c => {
+                return Err(MbaseError::invalid_input(format!(
+                    "known prefix contradicts the ciphertext at position {}: value {} is both '{}' and '{}'",
+                    i, v, existing, c
+                )))
+            }
+            (_, Some(other)) if other != v as usize => {
+                return Err(MbaseError::invalid_input(format!(
+                    "known prefix contradicts the ciphertext at position {}: '{}' is both value {} and {}",
+                    i, c, other, v
+                )))
+            }
+            _ => symbols[v as usize] = Some(c),
+        }
+    }
+    let confirmed = symbols.iter().flatten().count();
+
+    let mut distinct: Vec<char> = Vec::new();
+    for &c in &cipher {
+        if !distinct.contains(&c) {
+            distinct.push(c);
+        }
+    }
+    if distinct.len() > 64 {
+        return Err(MbaseError::invalid_input(format!("ciphertext uses {} distinct symbols; base64 has 64", distinct.len())));
+    }
+
+    let family = KNOWN_BASE64_ALPHABETS.iter().find_map(|(name, alphabet)| {
+        let chars: Vec<char> = alphabet.chars().collect();
+        (0..64).find_map(|rot| {
+            let rotated: Vec<char> = (0..64).map(|v| chars[(v + rot) % 64]).collect();
+            let fits = symbols.iter().zip(&rotated).all(|(s, r)| s.is_none_or(|s| s == *r)) && distinct.iter().all(|c| rotated.contains(c));
+            fits.then(|| (format!("{} rotated by {}", name, rot), rotated))
+        })
+    });
+
+    let family = match family {
+        Some((name, rotated)) => {
+            symbols = rotated.into_iter().map(Some).collect();
+            Some(name)
+        }
+        None => {
+            let mut unknown: Vec<char> = distinct.iter().copied().filter(|c| !symbols.contains(&Some(*c))).collect();
+            unknown.sort_by_key(|c| core::cmp::Reverse(cipher.iter().filter(|&d| d == c).count()));
+            let sample = &cipher[..cipher.len().min(RECOVERY_SAMPLE)];
+            let score = |trial: &[Option<char>]| {
+                let values: Vec<Option<u8>> = sample
+                    .iter()
+                    .map(|d| trial.iter().position(|s| *s == Some(*d)).map(|v| v as u8))
+                    .collect();
+                plaintext_score(&decode_sextets(&values))
+            };
+            for c in unknown {
+                // Ties go to the lowest value, keeping the result deterministic.
+                let best = (0..64)
+                    .filter(|&v| symbols[v].is_none())
+                    .map(|v| {
+                        let mut trial = symbols.clone();
+                        trial[v] = Some(c);
+                        (v, score(&trial))
+                    })
+                    .fold(None, |best: Option<(usize, f64)>, (v, s)| match best {
+                        Some((_, top)) if top >= s => best,
+                        _ => Some((v, s)),
+                    })
+                    .expect("fewer than 64 symbols are assigned")
+                    .0;
+                symbols[best] = Some(c);
+            }
+
+            // Greedy choices made early saw few neighbours; swap guessed values while that helps.
+            let pinned: Vec<bool> = symbols
+                .iter()
+                .map(|s| s.is_some_and(|c| cipher[..determined].contains(&c)))
+                .collect();
+            let movable: Vec<usize> = (0..64).filter(|&v| !pinned[v]).collect();
+            let mut current = score(&symbols);
+            let mut improved = true;
+            while improved {
+                improved = false;
+                for &a in &movable {
+                    for &b in &movable {
+                        if a == b || symbols[a].is_none() {
+                            continue;
+                        }
+                        symbols.swap(a, b);
+                        let trial = score(&symbols);
+                        if trial > current {
+                            current = trial;
+                            improved = true;
+                        } else {
+                            symbols.swap(a, b);
+                        }
+                    }
+                }
+            }
+            None
+        }
+    };
+
+    let values: Vec<Option<u8>> = cipher
+        .iter()
+        .map(|c| symbols.iter().position(|s| *s == Some(*c)).map(|v| v as u8))
+        .collect();
+    let decoded: Vec<u8> = decode_sextets(&values).into_iter().map(|b| b.unwrap_or(b'?')).collect();
+    let assigned = symbols.iter().flatten().count();
+
+    Ok(AlphabetRecovery {
+        codec: "base64".to_string(),
+        input_preview: preview(text.trim().as_bytes()),
+        known_prefix_length: known.len(),
+        alphabet: symbols.iter().map(|s| s.unwrap_or('?')).collect(),
+        confirmed,
+        guessed: assigned - confirmed,
+        unknown: 64 - assigned,
+        family,
+        output: preview(&decoded),
+        output_hex: decoded.iter().map(|b| format!("{:02x}", b)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_score_prefers_plaintext() {
+        assert!(
+            english_score(b"the quick brown fox jumps over the lazy dog") > english_score(b"gur dhvpx oebja sbk whzcf bire gur ynml qbt")
+        );
+        assert_eq!(english_score(b""), 0.0);
+        assert_eq!(english_score(&[0, 1, 2, 3]), 0.0);
+    }
+
+    #[test]
+    fn test_analyze_affine_recovers_key() {
+        let ctx = Context::default();
+        // "meet me at the usual place at ten rather than eight" with a=7, b=3
+        let ciphertext = ctx
+            .registry
+            .get("affine")
+            .unwrap()
+            .encode_with(b"meet me at the usual place at ten rather than eight", &CodecOptions::new().with("a", "7").with("b", "3"))
+            .unwrap();
+        let result = run_analyze(&ctx, "affine", &InputSource::Literal(ciphertext.into_bytes()), 3).unwrap();
+        assert_eq!(result.tried, 12 * 26);
+        assert_eq!(result.candidates[0].params, "a=7 b=3");
+        assert!(result.candidates[0].output.starts_with("meet me"));
+    }
+
+    #[test]
+    fn test_analyze_unsupported_codec() {
+        let ctx = Context::default();
+        assert!(run_analyze(&ctx, "base64", &InputSource::Literal(b"abc".to_vec()), 3).is_err());
+    }
+
+    fn shuffled_base64(plaintext: &[u8], alphabet: &str) -> String {
+        let ctx = Context::default();
+        let standard = ctx.registry.get("base64").unwrap().encode(plaintext).unwrap();
+        let opts = CodecOptions::new().with("from", "codec:base64").with("to", alphabet);
+        ctx.registry
+            .get("translate")
+            .unwrap()
+            .encode_with(standard.as_bytes(), &opts)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_recover_rotated_alphabet() {
+        let rotated = format!("{}{}", &STANDARD_BASE64[13..], &STANDARD_BASE64[..13]);
+        let cipher = shuffled_base64(b"MZ\x90\x00\x03\x00\x00\x00\x04\x00", &rotated);
+        let result = recover_base64_alphabet(&InputSource::Literal(cipher.into_bytes()), b"MZ").unwrap();
+        assert_eq!(result.family.as_deref(), Some("standard rotated by 13"));
+        assert_eq!(result.alphabet, rotated);
+        assert_eq!(result.output_hex, "4d5a9000030000000400");
+    }
+
+    #[test]
+    fn test_recover_shuffled_alphabet_from_prefix() {
+        let shuffled = "qwertyuiopasdfghjklzxcvbnmQWERTYUIOPASDFGHJKLZXCVBNM0987654321/+";
+        let plaintext = b"{\"cmd\":\"beacon\",\"note\":\"the operator will check in at the usual time and then wait for the next \
+            set of instructions from the server, so keep the channel open and do not reply to this message. if the server \
+            does not answer within an hour, try the backup address and report the failure when the link is back. \
+            remember that all of the traffic is logged by the other side, so write as little as you can and never \
+            mention the names of the people who are working with us on this\"}";
+        let cipher = shuffled_base64(plaintext, shuffled);
+        let result = recover_base64_alphabet(&InputSource::Literal(cipher.into_bytes()), b"{\"cmd\":\"").unwrap();
+        assert!(result.family.is_none());
+        assert_eq!(result.confirmed, 9);
+        let recovered = Context::default()
+            .registry
+            .get("base16lower")
+            .unwrap()
+            .decode(&result.output_hex, Mode::Strict)
+            .unwrap();
+        let correct = recovered.iter().zip(plaintext.iter()).filter(|(a, b)| a == b).count();
+        assert!(recovered.starts_with(b"{\"cmd\":\""));
+        assert!(correct * 10 >= plaintext.len() * 9, "{} of {} bytes recovered", correct, plaintext.len());
+    }
+
+    #[test]
+    fn test_recover_contradiction() {
+        let err = recover_base64_alphabet(&InputSource::Literal(b"AAAA".to_vec()), b"MZ").unwrap_err();
+        assert!(err.to_string().contains("contradicts"), "{}", err);
+    }
+}
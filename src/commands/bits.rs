@@ -0,0 +1,24 @@
+use crate::io::read_input;
+use mbase::bits::BitOp;
+use mbase::error::Result;
+use mbase::types::InputSource;
+
+/// Applies `ops` to the input bytes in order.
+pub fn run_bits(input: &InputSource, ops: &[BitOp]) -> Result<Vec<u8>> {
+    ops.iter().try_fold(read_input(input)?, |data, op| op.apply(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_applies_ops_in_order() {
+        let input = InputSource::Literal(vec![0x01, 0x02]);
+        let ops = [BitOp::Reverse, BitOp::Swap(2)];
+        assert_eq!(run_bits(&input, &ops).unwrap(), [0x40, 0x80]);
+        let ops = [BitOp::Swap(2), BitOp::Reverse];
+        assert_eq!(run_bits(&input, &ops).unwrap(), [0x40, 0x80]);
+        assert!(run_bits(&InputSource::Literal(vec![1, 2, 3]), &[BitOp::Not, BitOp::Swap(4)]).is_err());
+    }
+}
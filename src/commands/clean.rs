@@ -0,0 +1,193 @@
+use crate::io::read_input;
+use mbase::error::Result;
+use mbase::types::InputSource;
+
+/// Typographic characters word processors and chat clients substitute, and what was typed.
+const SUBSTITUTIONS: &[(char, &str)] = &[
+    ('\u{2018}', "'"),
+    ('\u{2019}', "'"),
+    ('\u{201c}', "\""),
+    ('\u{201d}', "\""),
+    ('\u{2010}', "-"),
+    ('\u{2011}', "-"),
+    ('\u{2012}', "-"),
+    ('\u{2013}', "-"),
+    ('\u{2014}', "-"),
+    ('\u{2212}', "-"),
+    ('\u{2026}', "..."),
+    ('\u{00a0}', " "),
+    ('\u{200b}', ""),
+    ('\u{200c}', ""),
+    ('\u{200d}', ""),
+    ('\u{2060}', ""),
+    ('\u{feff}', ""),
+];
+
+/// Quote and bracket pairs that may wrap a pasted value.
+const QUOTES: &[(char, char)] = &[
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+    ('\u{201c}', '\u{201d}'),
+    ('\u{2018}', '\u{2019}'),
+    ('«', '»'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+];
+
+/// Punctuation a sentence may leave after a pasted value.
+const TRAILING: &str = ".,;:!?)]}";
+
+fn substitute(text: &str, alphabet: &str, notes: &mut Vec<String>) -> String {
+    let mut count = 0;
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match SUBSTITUTIONS.iter().find(|&&(from, _)| from == c && !alphabet.contains(from)) {
+            Some((_, to)) => {
+                count += 1;
+                out.push_str(to);
+            }
+            None => out.push(c),
+        }
+    }
+    if count > 0 {
+        notes.push(format!("replaced {} typographic character(s) (smart quotes, dashes, invisible spaces)", count));
+    }
+    out
+}
+
+/// Strips `>` reply quoting when every non-empty line carries it.
+fn unquote_email(text: &str, notes: &mut Vec<String>) -> Option<String> {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.is_empty() || !lines.iter().all(|l| l.trim_start().starts_with('>')) {
+        return None;
+    }
+    notes.push(format!("removed '>' quoting from {} line(s)", lines.len()));
+    let stripped = text
+        .lines()
+        .map(|l| l.trim_start().trim_start_matches(['>', ' ', '\t']))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(stripped)
+}
+
+/// The rest of `line` after a line-number gutter (`12 | `, `12: `, `12<TAB>`) and the number.
+fn split_gutter(line: &str) -> Option<(u64, &str)> {
+    let trimmed = line.trim_start();
+    let digits = trimmed.len() - trimmed.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let number = trimmed[..digits].parse().ok()?;
+    let rest = &trimmed[digits..];
+    let after = rest.trim_start_matches(' ');
+    let rest = if let Some(r) = after.strip_prefix('|').or_else(|| after.strip_prefix(':')) {
+        r.strip_prefix(' ').unwrap_or(r)
+    } else {
+        rest.strip_prefix('\t')?
+    };
+    Some((number, rest))
+}
+
+/// Strips a gutter of consecutive line numbers from two or more lines.
+fn strip_gutter(text: &str, notes: &mut Vec<String>) -> Option<String> {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    let split: Vec<(u64, &str)> = lines.iter().map(|l| split_gutter(l)).collect::<Option<_>>()?;
+    if !split.windows(2).all(|w| w[1].0 == w[0].0 + 1) {
+        return None;
+    }
+    notes.push(format!("removed line numbers {}-{}", split[0].0, split[split.len() - 1].0));
+    Some(split.into_iter().map(|(_, rest)| rest).collect::<Vec<_>>().join("\n"))
+}
+
+/// Peels enclosing pairs and trailing punctuation off the ends until neither is left.
+fn strip_ends(text: &str, alphabet: &str, notes: &mut Vec<String>) -> String {
+    let mut text = text.trim();
+    loop {
+        let pair = QUOTES.iter().find(|&&(open, close)| {
+            text.chars().count() >= 2 && text.starts_with(open) && text.ends_with(close) && !alphabet.contains(open)
+        });
+        if let Some(&(open, close)) = pair {
+            notes.push(format!("removed surrounding {}{}", open, close));
+            text = text[open.len_utf8()..text.len() - close.len_utf8()].trim();
+            continue;
+        }
+        if let Some(c) = text.chars().last().filter(|&c| TRAILING.contains(c) && !alphabet.contains(c)) {
+            notes.push(format!("removed trailing '{}'", c));
+            text = text[..text.len() - c.len_utf8()].trim_end();
+            continue;
+        }
+        return text.to_string();
+    }
+}
+
+/// Undoes common copy/paste damage to an encoded value: typographic substitutions, `>` email
+/// quoting, line-number gutters, and quotes or sentence punctuation around it. Characters of
+/// `alphabet` are never removed or replaced. Returns the text and a note for each change.
+pub fn clean_text(text: &str, alphabet: &str) -> (String, Vec<String>) {
+    let mut notes = Vec::new();
+    let mut text = substitute(text, alphabet, &mut notes);
+    if let Some(unquoted) = unquote_email(&text, &mut notes) {
+        text = unquoted;
+    }
+    if let Some(stripped) = strip_gutter(&text, &mut notes) {
+        text = stripped;
+    }
+    let text = strip_ends(&text, alphabet, &mut notes);
+    (text, notes)
+}
+
+/// Reads `input` and cleans it for a codec with `alphabet` (empty when the codec is not known yet).
+pub fn clean_input(input: &InputSource, alphabet: &str) -> Result<(InputSource, Vec<String>)> {
+    let data = read_input(input)?;
+    let (text, notes) = clean_text(&String::from_utf8_lossy(&data), alphabet);
+    Ok((InputSource::Literal(text.into_bytes()), notes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE64: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    #[test]
+    fn test_clean_quotes_and_punctuation() {
+        let (text, notes) = clean_text("“aGVsbG8=”.\n", BASE64);
+        assert_eq!(text, "aGVsbG8=");
+        assert_eq!(notes[1..], ["removed trailing '.'", "removed surrounding \"\""]);
+        assert_eq!(clean_text("('deadbeef'),", "").0, "deadbeef");
+        assert!(clean_text("aGVsbG8=", BASE64).1.is_empty());
+    }
+
+    #[test]
+    fn test_clean_keeps_alphabet_characters() {
+        // Ascii85 uses quotes and punctuation as digits.
+        let ascii85 = "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstu";
+        assert_eq!(clean_text("'87cURD]i,\"Ebo80'", ascii85).0, "'87cURD]i,\"Ebo80'");
+        assert_eq!(clean_text("a\u{2013}b", "").0, "a-b");
+    }
+
+    #[test]
+    fn test_clean_email_and_gutters() {
+        let (text, notes) = clean_text("> aGVs\n> bG8=\n", BASE64);
+        assert_eq!(text, "aGVs\nbG8=");
+        assert_eq!(notes, ["removed '>' quoting from 2 line(s)"]);
+
+        let (text, notes) = clean_text(" 9 | aGVs\n10 | bG8=\n", BASE64);
+        assert_eq!(text, "aGVs\nbG8=");
+        assert_eq!(notes, ["removed line numbers 9-10"]);
+        assert_eq!(clean_text("1:ab\n2:cd", "").0, "ab\ncd");
+        assert_eq!(clean_text("1\tab\n2\tcd", "").0, "ab\ncd");
+        // Numbers that do not count up are data, not a gutter.
+        assert_eq!(clean_text("1: ab\n5: cd", "").0, "1: ab\n5: cd");
+    }
+
+    #[test]
+    fn test_clean_invisible_characters() {
+        let (text, notes) = clean_text("\u{feff}aGVs\u{200b}bG8=", BASE64);
+        assert_eq!(text, "aGVsbG8=");
+        assert_eq!(notes.len(), 1);
+    }
+}
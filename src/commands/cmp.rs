@@ -0,0 +1,103 @@
+use super::dec::run_decode;
+use super::report::{CmpContext, CmpResult};
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+/// Bytes shown on each side of the first difference.
+const CONTEXT_BYTES: usize = 8;
+
+/// Hex of `data[start..end]`, with the byte at `mark` in brackets.
+fn hex_context(data: &[u8], start: usize, end: usize, mark: usize) -> String {
+    let end = end.min(data.len());
+    let mut bytes: Vec<String> = (start.min(end)..end)
+        .map(|i| {
+            if i == mark {
+                format!("[{:02x}]", data[i])
+            } else {
+                format!("{:02x}", data[i])
+            }
+        })
+        .collect();
+    if mark >= data.len() {
+        bytes.push("[end]".to_string());
+    }
+    bytes.join(" ")
+}
+
+/// Decodes two inputs, each with its own codec, and compares the bytes.
+pub fn run_cmp(ctx: &Context, a: (&str, &InputSource), b: (&str, &InputSource), mode: Mode) -> Result<CmpResult> {
+    if matches!(a.1, InputSource::Stdin) && matches!(b.1, InputSource::Stdin) {
+        return Err(MbaseError::invalid_input("only one of --in-a and --in-b can read stdin"));
+    }
+    let opts = CodecOptions::default();
+    let data_a = run_decode(ctx, a.0, a.1, mode, false, &opts)?;
+    let data_b = run_decode(ctx, b.0, b.1, mode, false, &opts)?;
+
+    let common = data_a.len().min(data_b.len());
+    let first_difference = data_a
+        .iter()
+        .zip(&data_b)
+        .position(|(x, y)| x != y)
+        .or((data_a.len() != data_b.len()).then_some(common));
+    let context = first_difference.map(|offset| {
+        let start = offset.saturating_sub(CONTEXT_BYTES);
+        let end = offset + CONTEXT_BYTES + 1;
+        CmpContext {
+            offset: start,
+            a: hex_context(&data_a, start, end, offset),
+            b: hex_context(&data_b, start, end, offset),
+        }
+    });
+    Ok(CmpResult {
+        equal: first_difference.is_none(),
+        codec_a: ctx.registry.get(a.0)?.name().to_string(),
+        codec_b: ctx.registry.get(b.0)?.name().to_string(),
+        length_a: data_a.len(),
+        length_b: data_b.len(),
+        first_difference,
+        differing_bytes: data_a.iter().zip(&data_b).filter(|(x, y)| x != y).count() + data_a.len().abs_diff(data_b.len()),
+        context,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(s: &str) -> InputSource {
+        InputSource::Literal(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_cmp_equal_across_codecs() {
+        let ctx = Context::default();
+        let result = run_cmp(&ctx, ("base64", &literal("aGVsbG8")), ("base32", &literal("nbswy3dp")), Mode::Lenient).unwrap();
+        assert!(result.equal);
+        assert_eq!((result.codec_b.as_str(), result.length_a, result.first_difference), ("base32lower", 5, None));
+        assert!(result.context.is_none());
+    }
+
+    #[test]
+    fn test_cmp_first_difference() {
+        let ctx = Context::default();
+        let a = literal("000102030405060708090a0b0c0d0e0f10");
+        let b = literal("000102030405060708090aff0c0d0e0f10");
+        let result = run_cmp(&ctx, ("hex", &a), ("hex", &b), Mode::Strict).unwrap();
+        assert_eq!((result.equal, result.first_difference, result.differing_bytes), (false, Some(11), 1));
+        let context = result.context.unwrap();
+        assert_eq!(context.offset, 3);
+        assert_eq!(context.a, "03 04 05 06 07 08 09 0a [0b] 0c 0d 0e 0f 10");
+        assert_eq!(context.b, "03 04 05 06 07 08 09 0a [ff] 0c 0d 0e 0f 10");
+    }
+
+    #[test]
+    fn test_cmp_prefix_and_errors() {
+        let ctx = Context::default();
+        let result = run_cmp(&ctx, ("hex", &literal("6869")), ("hex", &literal("686921")), Mode::Strict).unwrap();
+        assert_eq!((result.first_difference, result.differing_bytes), (Some(2), 1));
+        let context = result.context.unwrap();
+        assert_eq!((context.a.as_str(), context.b.as_str()), ("68 69 [end]", "68 69 [21]"));
+        assert!(run_cmp(&ctx, ("hex", &literal("zz")), ("hex", &literal("00")), Mode::Strict).is_err());
+        assert!(run_cmp(&ctx, ("hex", &InputSource::Stdin), ("hex", &InputSource::Stdin), Mode::Strict).is_err());
+    }
+}
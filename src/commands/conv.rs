@@ -1,40 +1,113 @@
+use super::fmt::wrap_lines;
+use super::report::ConvertResult;
 use crate::io::read_input;
-use mbase::error::Result;
-use mbase::types::{Context, InputSource, Mode};
-use serde::Serialize;
-
-#[derive(Debug, Serialize)]
-pub struct ConvertResult {
-    pub from_codec: String,
-    pub to_codec: String,
-    pub input: String,
-    pub output: String,
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+/// Decode settings for the source side and encode settings for the target side.
+///
+/// `to_opts` may carry `wrap=N`, which is applied to the output rather than passed to the codec.
+#[derive(Default)]
+pub struct ConvOptions {
+    pub from_mode: Mode,
+    pub from_opts: CodecOptions,
+    pub to_opts: CodecOptions,
+    pub verify_roundtrip: bool,
 }
 
-pub fn run_conv(ctx: &Context, from_codec: &str, to_codec: &str, input: &InputSource, mode: Mode) -> Result<String> {
+pub fn convert(ctx: &Context, from_codec: &str, to_codec: &str, text: &str, opts: &ConvOptions) -> Result<String> {
     let decoder = ctx.registry.get(from_codec)?;
     let encoder = ctx.registry.get(to_codec)?;
 
+    let mut to_opts = opts.to_opts.clone();
+    let wrap = to_opts.get_usize("wrap")?;
+    to_opts.remove("wrap");
+
+    let decoded = ctx.decode(decoder, text, opts.from_mode, &opts.from_opts)?;
+    let output = ctx.encode(encoder, &decoded, &to_opts)?;
+
+    if opts.verify_roundtrip {
+        let again = ctx.decode(encoder, &output, Mode::Strict, &to_opts)?;
+        if again != decoded {
+            return Err(MbaseError::invalid_input(format!(
+                "round-trip verification failed: {} output decodes to {} bytes that differ from the {} input bytes",
+                to_codec,
+                again.len(),
+                decoded.len()
+            )));
+        }
+    }
+
+    Ok(match wrap {
+        Some(width) => wrap_lines(&output, width),
+        None => output,
+    })
+}
+
+pub fn run_conv(ctx: &Context, from_codec: &str, to_codec: &str, input: &InputSource, opts: &ConvOptions) -> Result<String> {
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
-    let decoded = decoder.decode(&text, mode)?;
-    encoder.encode(&decoded)
+    convert(ctx, from_codec, to_codec, &text, opts)
 }
 
-pub fn run_conv_json(ctx: &Context, from_codec: &str, to_codec: &str, input: &InputSource, mode: Mode) -> Result<ConvertResult> {
-    let decoder = ctx.registry.get(from_codec)?;
-    let encoder = ctx.registry.get(to_codec)?;
-
+pub fn run_conv_json(ctx: &Context, from_codec: &str, to_codec: &str, input: &InputSource, opts: &ConvOptions) -> Result<ConvertResult> {
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
     let input_str = text.trim().to_string();
-    let decoded = decoder.decode(&text, mode)?;
-    let output = encoder.encode(&decoded)?;
+    let output = convert(ctx, from_codec, to_codec, &text, opts)?;
 
     Ok(ConvertResult {
         from_codec: from_codec.to_string(),
         to_codec: to_codec.to_string(),
         input: input_str,
         output,
+        roundtrip_verified: opts.verify_roundtrip,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conv(from: &str, to: &str, input: &str, opts: &ConvOptions) -> Result<String> {
+        run_conv(&Context::default(), from, to, &InputSource::Literal(input.as_bytes().to_vec()), opts)
+    }
+
+    #[test]
+    fn test_conv_from_mode_and_wrap() {
+        let opts = ConvOptions {
+            from_mode: Mode::Lenient,
+            to_opts: CodecOptions::new().with("wrap", "8"),
+            ..Default::default()
+        };
+        assert_eq!(conv("base64pad", "base16lower", "aGVs\nbG8=\n", &opts).unwrap(), "68656c6c\n6f");
+        assert!(conv("base64pad", "base16lower", "aGVs\nbG8=\n", &ConvOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_conv_passes_codec_options() {
+        let opts = ConvOptions {
+            to_opts: CodecOptions::new().with("sep", "."),
+            ..Default::default()
+        };
+        assert_eq!(conv("base16lower", "proquint", "7f000001", &opts).unwrap(), "lusab.babad");
+        let opts = ConvOptions {
+            to_opts: CodecOptions::new().with("bogus", "1"),
+            ..Default::default()
+        };
+        assert!(conv("base16lower", "base64", "7f000001", &opts).is_err());
+    }
+
+    #[test]
+    fn test_conv_verify_roundtrip() {
+        let opts = ConvOptions {
+            verify_roundtrip: true,
+            ..Default::default()
+        };
+        assert_eq!(conv("base16lower", "base58btc", "68656c6c6f", &opts).unwrap(), "Cn8eVZg");
+        // Morse has no letter case, so "Hello" comes back as "HELLO".
+        assert!(conv("base16lower", "morse", "48656c6c6f", &ConvOptions::default()).is_ok());
+        let err = conv("base16lower", "morse", "48656c6c6f", &opts).unwrap_err();
+        assert!(err.to_string().contains("round-trip verification failed"), "{}", err);
+    }
+}
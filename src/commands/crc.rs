@@ -0,0 +1,52 @@
+use super::dec::run_decode;
+use super::report::CrcResult;
+use crate::io::read_input;
+use mbase::checksum::Checksum;
+use mbase::error::Result;
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+/// Checksums the input, or what `decode` makes of it, and writes the big-endian value with `to`.
+pub fn run_crc(ctx: &Context, input: &InputSource, algorithm: Checksum, decode: Option<&str>, mode: Mode, to: &str) -> Result<CrcResult> {
+    let data = match decode {
+        Some(codec) => run_decode(ctx, codec, input, mode, false, &CodecOptions::default())?,
+        None => read_input(input)?,
+    };
+    let codec = ctx.registry.get(to)?;
+    Ok(CrcResult {
+        algorithm,
+        input_length: data.len(),
+        value: algorithm.compute(&data),
+        codec: codec.name().to_string(),
+        output: codec.encode(&algorithm.bytes(&data))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(s: &str) -> InputSource {
+        InputSource::Literal(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_crc_output_codecs() {
+        let ctx = Context::default();
+        let result = run_crc(&ctx, &literal("123456789"), Checksum::Crc32, None, Mode::Strict, "hex").unwrap();
+        assert_eq!((result.value, result.output.as_str()), (0xcbf4_3926, "cbf43926"));
+        // The OpenPGP armor checksum line is the CRC24 in base64.
+        let result = run_crc(&ctx, &literal("123456789"), Checksum::Crc24, None, Mode::Strict, "base64pad").unwrap();
+        assert_eq!(result.output, "Ic8C");
+        let result = run_crc(&ctx, &literal("123456789"), Checksum::Adler32, None, Mode::Strict, "base2").unwrap();
+        assert_eq!(result.output.len(), 32);
+    }
+
+    #[test]
+    fn test_crc_of_decoded_input() {
+        let ctx = Context::default();
+        let result = run_crc(&ctx, &literal("MTIzNDU2Nzg5"), Checksum::Crc16Ccitt, Some("base64"), Mode::Strict, "hex").unwrap();
+        assert_eq!((result.input_length, result.output.as_str()), (9, "29b1"));
+        assert!(run_crc(&ctx, &literal("MTIz!"), Checksum::Crc32, Some("base64"), Mode::Strict, "hex").is_err());
+        assert!(run_crc(&ctx, &literal("x"), Checksum::Crc32, None, Mode::Strict, "nope").is_err());
+    }
+}
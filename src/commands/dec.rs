@@ -1,66 +1,181 @@
+use super::analyze::english_score;
+use super::pem::asn1_outline;
+use super::report::{
+    DecodeAllResult, DecodeCodecResult, DecodeModesResult, DecodePass, DecodeResult, DecodeShiftResult, DecodeShiftsResult, ModeOutcome,
+    PemBlock,
+};
 use crate::io::read_input;
-use mbase::error::Result;
-use mbase::types::{Context, InputSource, Mode};
-use serde::Serialize;
+use mbase::codec::urlencoding::{decode_passes, escape_count};
+use mbase::codec::Codec;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
 
-#[derive(Debug, Serialize)]
-pub struct DecodeResult {
-    pub codec: String,
-    pub input: String,
-    pub output_length: usize,
-    pub output_hex: String,
-    pub output_text: Option<String>,
-    pub multibase_prefix: Option<char>,
+impl DecodeResult {
+    pub(super) fn new(codec: String, input: String, decoded: &[u8], multibase_prefix: Option<char>, warnings: Vec<String>) -> Self {
+        let output_text = std::str::from_utf8(decoded)
+            .ok()
+            .filter(|s| s.chars().all(|c| c == '\n' || c == '\r' || c == '\t' || !c.is_control()))
+            .map(String::from);
+
+        DecodeResult {
+            codec,
+            input,
+            output_length: decoded.len(),
+            output_hex: decoded.iter().map(|b| format!("{:02x}", b)).collect(),
+            output_text,
+            multibase_prefix,
+            warnings,
+            extracted: None,
+            cleaned: Vec::new(),
+            pem: None,
+            asn1: None,
+            recovery: None,
+            passes: Vec::new(),
+        }
+    }
+
+    /// Fills `asn1` from the decoded output.
+    pub fn attach_asn1(&mut self) -> Result<()> {
+        let bytes: Vec<u8> = (0..self.output_hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&self.output_hex[i..i + 2], 16).unwrap_or_default())
+            .collect();
+        self.asn1 = Some(asn1_outline(&bytes)?);
+        Ok(())
+    }
 }
 
-#[derive(Debug, Serialize)]
-pub struct DecodeAllResult {
-    pub input: String,
-    pub results: Vec<DecodeCodecResult>,
+pub const PREVIEW_CHARS: usize = 40;
+
+/// Renders bytes as text, escaping control characters and invalid UTF-8 as `\xNN`.
+pub fn escaped_preview(data: &[u8], max_chars: usize) -> String {
+    let mut out = String::new();
+    let mut count = 0;
+    for chunk in data.utf8_chunks() {
+        let escaped = chunk
+            .valid()
+            .chars()
+            .map(|c| c.escape_debug().to_string())
+            .chain(chunk.invalid().iter().map(|b| format!("\\x{:02x}", b)));
+        for piece in escaped {
+            if count == max_chars {
+                out.push_str("...");
+                return out;
+            }
+            out.push_str(&piece);
+            count += 1;
+        }
+    }
+    out
 }
 
-#[derive(Debug, Serialize)]
-pub struct DecodeCodecResult {
-    pub codec: String,
-    pub output_length: Option<usize>,
-    pub output_hex: Option<String>,
-    pub output_text: Option<String>,
-    pub error: Option<String>,
+pub fn run_decode(
+    ctx: &Context,
+    codec_name: &str,
+    input: &InputSource,
+    mode: Mode,
+    multibase: bool,
+    opts: &CodecOptions,
+) -> Result<Vec<u8>> {
+    let data = read_input(input)?;
+    let mut decoded = Vec::new();
+    decode_record(ctx, codec_name, &String::from_utf8_lossy(&data), mode, multibase, opts, &mut decoded)?;
+    Ok(decoded)
 }
 
-pub fn run_decode(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode, multibase: bool) -> Result<Vec<u8>> {
+/// [`run_decode`] plus the notes on how a lenient decode reinterpreted the input, such as
+/// lookalike characters it replaced. Multibase input carries no notes.
+pub fn run_decode_warned(
+    ctx: &Context,
+    codec_name: &str,
+    input: &InputSource,
+    mode: Mode,
+    multibase: bool,
+    opts: &CodecOptions,
+) -> Result<(Vec<u8>, Vec<String>)> {
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
+    let mut decoded = Vec::new();
+    decode_record(ctx, codec_name, &text, mode, multibase, opts, &mut decoded)?;
+    let warnings = if multibase {
+        Vec::new()
+    } else {
+        ctx.decode_warnings(ctx.registry.get(codec_name)?, &text, mode, opts)
+    };
+    Ok((decoded, warnings))
+}
 
+/// Appends the decoding of `text` to `out`; with `multibase` its prefix picks the codec.
+pub fn decode_record(
+    ctx: &Context,
+    codec_name: &str,
+    text: &str,
+    mode: Mode,
+    multibase: bool,
+    opts: &CodecOptions,
+    out: &mut Vec<u8>,
+) -> Result<()> {
     if multibase && !text.is_empty() {
         let prefix = text.chars().next().unwrap();
         for meta in ctx.registry.list() {
             if meta.multibase_code == Some(prefix) {
+                tracing::debug!(%prefix, codec = meta.name, "multibase prefix selected codec");
                 let codec = ctx.registry.get(meta.name)?;
-                return codec.decode(&text[prefix.len_utf8()..], mode);
+                return ctx.decode_into(codec, &text[prefix.len_utf8()..], mode, opts, out);
             }
         }
     }
 
     let codec = ctx.registry.get(codec_name)?;
-    codec.decode(&text, mode)
+    ctx.decode_into(codec, text, mode, opts, out)
 }
 
-pub fn run_decode_json(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode, multibase: bool) -> Result<DecodeResult> {
+/// `dec --try-siblings`: `None` when `codec_name` decodes `text`, otherwise the first member of
+/// its family that does. Fails with the codec's own error when no sibling decodes it either.
+pub fn pick_sibling(ctx: &Context, codec_name: &str, text: &str, mode: Mode, opts: &CodecOptions) -> Result<Option<&'static str>> {
+    let codec = ctx.registry.get(codec_name)?;
+    let Err(e) = ctx.decode(codec, text, mode, opts) else {
+        return Ok(None);
+    };
+    match ctx.decoding_siblings(codec_name, text, mode)?.first() {
+        Some(sibling) => {
+            tracing::debug!(codec = codec.name(), sibling = sibling.name(), "sibling decodes input");
+            Ok(Some(sibling.name()))
+        }
+        None => Err(e),
+    }
+}
+
+/// The note printed when `--try-siblings` switched codecs.
+pub fn sibling_note(codec_name: &str, sibling: &str) -> String {
+    format!("input is valid {}, not {}; decoded as {}", sibling, codec_name, sibling)
+}
+
+pub fn run_decode_json(
+    ctx: &Context,
+    codec_name: &str,
+    input: &InputSource,
+    mode: Mode,
+    multibase: bool,
+    opts: &CodecOptions,
+) -> Result<DecodeResult> {
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
     let input_str = text.trim().to_string();
 
-    let (decoded, multibase_prefix, actual_codec) = if multibase && !text.is_empty() {
+    let (decoded, multibase_prefix, actual_codec, warnings) = if multibase && !text.is_empty() {
         let prefix = text.chars().next().unwrap();
         let mut found = false;
         let mut result = Vec::new();
         let mut detected_codec = codec_name.to_string();
+        let mut warnings = Vec::new();
 
         for meta in ctx.registry.list() {
             if meta.multibase_code == Some(prefix) {
+                tracing::debug!(%prefix, codec = meta.name, "multibase prefix selected codec");
                 let codec = ctx.registry.get(meta.name)?;
-                result = codec.decode(&text[prefix.len_utf8()..], mode)?;
+                result = ctx.decode(codec, &text[prefix.len_utf8()..], mode, opts)?;
+                warnings = ctx.decode_warnings(codec, &text[prefix.len_utf8()..], mode, opts);
                 detected_codec = meta.name.to_string();
                 found = true;
                 break;
@@ -68,31 +183,28 @@ pub fn run_decode_json(ctx: &Context, codec_name: &str, input: &InputSource, mod
         }
 
         if found {
-            (result, Some(prefix), detected_codec)
+            (result, Some(prefix), detected_codec, warnings)
         } else {
             let codec = ctx.registry.get(codec_name)?;
-            (codec.decode(&text, mode)?, None, codec_name.to_string())
+            let decoded = ctx.decode(codec, &text, mode, opts)?;
+            (decoded, None, codec_name.to_string(), ctx.decode_warnings(codec, &text, mode, opts))
         }
     } else {
         let codec = ctx.registry.get(codec_name)?;
-        (codec.decode(&text, mode)?, None, codec_name.to_string())
+        let decoded = ctx.decode(codec, &text, mode, opts)?;
+        (decoded, None, codec_name.to_string(), ctx.decode_warnings(codec, &text, mode, opts))
     };
 
-    let output_length = decoded.len();
-    let output_hex = decoded.iter().map(|b| format!("{:02x}", b)).collect::<String>();
-    let output_text = std::str::from_utf8(&decoded)
-        .ok()
-        .filter(|s| s.chars().all(|c| c == '\n' || c == '\r' || c == '\t' || !c.is_control()))
-        .map(String::from);
+    Ok(DecodeResult::new(actual_codec, input_str, &decoded, multibase_prefix, warnings))
+}
 
-    Ok(DecodeResult {
-        codec: actual_codec,
-        input: input_str,
-        output_length,
-        output_hex,
-        output_text,
-        multibase_prefix,
-    })
+/// JSON result for PEM input that [`read_pem`](super::pem::read_pem) already unwrapped.
+pub fn run_decode_pem_json(input: &InputSource, blocks: Vec<PemBlock>) -> Result<DecodeResult> {
+    let data = read_input(input)?;
+    let der: Vec<u8> = blocks.iter().flat_map(|b| b.der.iter().copied()).collect();
+    let mut result = DecodeResult::new("base64pad".to_string(), String::from_utf8_lossy(&data).trim().to_string(), &der, None, Vec::new());
+    result.pem = Some(blocks);
+    Ok(result)
 }
 
 pub fn run_decode_all_json(ctx: &Context, input: &InputSource, mode: Mode) -> Result<DecodeAllResult> {
@@ -103,7 +215,7 @@ pub fn run_decode_all_json(ctx: &Context, input: &InputSource, mode: Mode) -> Re
 
     for meta in ctx.registry.list() {
         let codec = ctx.registry.get(meta.name)?;
-        match codec.decode(&text, mode) {
+        match ctx.decode(codec, &text, mode, &CodecOptions::new()) {
             Ok(decoded) => {
                 let output_hex = decoded.iter().map(|b| format!("{:02x}", b)).collect::<String>();
                 let output_text = std::str::from_utf8(&decoded)
@@ -112,18 +224,26 @@ pub fn run_decode_all_json(ctx: &Context, input: &InputSource, mode: Mode) -> Re
                     .map(String::from);
                 results.push(DecodeCodecResult {
                     codec: meta.name.to_string(),
+                    success: true,
                     output_length: Some(decoded.len()),
                     output_hex: Some(output_hex),
                     output_text,
+                    utf8: Some(std::str::from_utf8(&decoded).is_ok()),
+                    preview: Some(escaped_preview(&decoded, PREVIEW_CHARS)),
+                    score: Some(english_score(&decoded)),
                     error: None,
                 });
             }
             Err(e) => {
                 results.push(DecodeCodecResult {
                     codec: meta.name.to_string(),
+                    success: false,
                     output_length: None,
                     output_hex: None,
                     output_text: None,
+                    utf8: None,
+                    preview: None,
+                    score: None,
                     error: Some(e.to_string()),
                 });
             }
@@ -133,6 +253,195 @@ pub fn run_decode_all_json(ctx: &Context, input: &InputSource, mode: Mode) -> Re
     Ok(DecodeAllResult { input: input_str, results })
 }
 
+/// `dec --max-passes N`: percent-decodes input that was encoded several times, up to
+/// `max_passes` rounds, and reports each pass.
+pub fn run_decode_passes(ctx: &Context, codec_name: &str, input: &InputSource, max_passes: usize) -> Result<(Vec<u8>, Vec<DecodePass>)> {
+    let codec = ctx.registry.get(codec_name)?;
+    if codec.name() != "urlencoding" {
+        return Err(MbaseError::invalid_input(format!("--max-passes applies to urlencoding, not '{}'", codec.name())));
+    }
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let mut outputs = decode_passes(&text, Mode::Lenient, max_passes)?;
+    let mut escapes = escape_count(&text);
+    let mut passes = Vec::with_capacity(outputs.len());
+    for (i, output) in outputs.iter().enumerate() {
+        passes.push(DecodePass {
+            pass: i + 1,
+            escapes,
+            output_length: output.len(),
+            preview: escaped_preview(output, PREVIEW_CHARS),
+        });
+        escapes = std::str::from_utf8(output).map_or(0, escape_count);
+    }
+    Ok((outputs.pop().unwrap_or_default(), passes))
+}
+
+pub fn run_decode_passes_json(ctx: &Context, codec_name: &str, input: &InputSource, max_passes: usize) -> Result<DecodeResult> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data).trim().to_string();
+    let (decoded, passes) = run_decode_passes(ctx, codec_name, &InputSource::Literal(data), max_passes)?;
+    let mut result = DecodeResult::new("urlencoding".to_string(), text, &decoded, None, Vec::new());
+    result.passes = passes;
+    Ok(result)
+}
+
+impl ModeOutcome {
+    fn new(result: &Result<Vec<u8>>, warnings: Vec<String>) -> Self {
+        match result {
+            Ok(decoded) => ModeOutcome {
+                ok: true,
+                output_length: Some(decoded.len()),
+                output_hex: Some(decoded.iter().map(|b| format!("{:02x}", b)).collect()),
+                preview: Some(escaped_preview(decoded, PREVIEW_CHARS)),
+                error: None,
+                warnings,
+            },
+            Err(e) => ModeOutcome {
+                ok: false,
+                output_length: None,
+                output_hex: None,
+                preview: None,
+                error: Some(e.to_string()),
+                warnings,
+            },
+        }
+    }
+}
+
+/// Why the lenient decode of `text` came out differently from the strict one: characters it
+/// ignored, padding it added or dropped, case it folded, and where the outputs part.
+fn mode_drift(
+    ctx: &Context,
+    codec: &dyn Codec,
+    text: &str,
+    strict: &Result<Vec<u8>>,
+    lenient: &Result<Vec<u8>>,
+    opts: &CodecOptions,
+) -> Vec<String> {
+    let alphabet = codec.meta().alphabet;
+    let mut drift = Vec::new();
+    match (strict, lenient) {
+        (Err(_), Ok(_)) => drift.push("strict rejects the input; lenient decodes it".to_string()),
+        (Ok(_), Err(_)) => drift.push("lenient rejects input that strict decodes".to_string()),
+        (Ok(a), Ok(b)) => match a.iter().zip(b).position(|(x, y)| x != y) {
+            Some(offset) => drift.push(format!("outputs differ from byte {}", offset)),
+            None => drift.push(format!("outputs differ in length: {} vs {} bytes", a.len(), b.len())),
+        },
+        (Err(_), Err(_)) => {}
+    }
+    let ignored = |strip: &dyn Fn(char) -> bool| text.chars().filter(|&c| strip(c) && !alphabet.contains(c)).count();
+    let whitespace = ignored(&|c: char| c.is_whitespace());
+    if whitespace > 0 {
+        drift.push(format!("lenient ignored {} whitespace character(s)", whitespace));
+    }
+    let separators = ignored(&|c: char| ctx.lenient.strips(c));
+    if separators > 0 {
+        drift.push(format!("lenient dropped {} separator(s) (--ignore-chars)", separators));
+    }
+    // Re-encoding the lenient output gives the spelling strict mode would have accepted.
+    let Ok(canonical) = lenient
+        .as_ref()
+        .map_err(|_| ())
+        .and_then(|b| ctx.encode(codec, b, opts).map_err(|_| ()))
+    else {
+        return drift;
+    };
+    let given: String = text
+        .chars()
+        .filter(|&c| alphabet.contains(c) || !(c.is_whitespace() || ctx.lenient.strips(c)))
+        .collect();
+    let padding = |s: &str| s.chars().rev().take_while(|&c| c == '=').count();
+    let (wanted, found) = (padding(&canonical), padding(&given));
+    if wanted > found {
+        drift.push(format!("lenient added {} missing padding character(s)", wanted - found));
+    } else if found > wanted {
+        drift.push(format!("lenient dropped {} extra padding character(s)", found - wanted));
+    }
+    let (canonical, given) = (canonical.trim_end_matches('='), given.trim_end_matches('='));
+    if canonical != given {
+        if canonical.eq_ignore_ascii_case(given) {
+            drift.push("lenient read letters of the other case".to_string());
+        } else if drift.len() == 1 {
+            // Nothing above explains the difference; show the spelling strict mode expects.
+            drift.push(format!("the output re-encodes as {}", escaped_preview(canonical.as_bytes(), PREVIEW_CHARS)));
+        }
+    }
+    drift
+}
+
+/// `dec --both-modes`: decodes the input strictly and leniently and explains any difference,
+/// to audit whether a lenient fix changed what the input means.
+pub fn run_decode_both_modes(ctx: &Context, codec_name: &str, input: &InputSource, opts: &CodecOptions) -> Result<DecodeModesResult> {
+    let codec = ctx.registry.get(codec_name)?;
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let strict = ctx.decode(codec, &text, Mode::Strict, opts);
+    let lenient = ctx.decode(codec, &text, Mode::Lenient, opts);
+    let differ = match (&strict, &lenient) {
+        (Ok(a), Ok(b)) => a != b,
+        (Err(_), Err(_)) => false,
+        _ => true,
+    };
+    let drift = if differ {
+        mode_drift(ctx, codec, &text, &strict, &lenient, opts)
+    } else {
+        Vec::new()
+    };
+    Ok(DecodeModesResult {
+        codec: codec.name().to_string(),
+        input: text.trim().to_string(),
+        strict: ModeOutcome::new(&strict, ctx.decode_warnings(codec, &text, Mode::Strict, opts)),
+        lenient: ModeOutcome::new(&lenient, ctx.decode_warnings(codec, &text, Mode::Lenient, opts)),
+        differ,
+        drift,
+    })
+}
+
+/// Undoes every ROT-n (n = 1..25) on the input, so `rot3` is the text a Caesar cipher produced.
+pub fn run_decode_all_shifts(ctx: &Context, codec_name: &str, input: &InputSource, opts: &CodecOptions) -> Result<DecodeShiftsResult> {
+    let codec = ctx.registry.get(codec_name)?;
+    if !matches!(codec.name(), "caesar" | "rot13") {
+        return Err(MbaseError::invalid_input(format!(
+            "--all-shifts only applies to letter rotations (rot, caesar, rot13), not '{}'",
+            codec.name()
+        )));
+    }
+    if !opts.is_empty() {
+        return Err(MbaseError::invalid_input(
+            "--all-shifts tries every shift of A-Z and takes no --opt; decode one shift with --opt shift=N",
+        ));
+    }
+
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let input_str = text.trim_end_matches(['\r', '\n']).to_string();
+    let caesar = ctx.registry.get("caesar")?;
+
+    let mut results = Vec::with_capacity(25);
+    for shift in 1..26u32 {
+        let opts = CodecOptions::new().with("shift", &shift.to_string());
+        let decoded = ctx.decode(caesar, &input_str, Mode::Lenient, &opts)?;
+        results.push(DecodeShiftResult {
+            shift,
+            score: english_score(&decoded),
+            output: String::from_utf8_lossy(&decoded).into_owned(),
+        });
+    }
+
+    let best_shift = results
+        .iter()
+        .filter(|r| r.score > 0.0)
+        .max_by(|a, b| a.score.partial_cmp(&b.score).unwrap())
+        .map(|r| r.shift);
+
+    Ok(DecodeShiftsResult {
+        input: input_str,
+        best_shift,
+        results,
+    })
+}
+
 pub fn run_decode_all(ctx: &Context, input: &InputSource, mode: Mode) -> Result<()> {
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
@@ -143,7 +452,7 @@ pub fn run_decode_all(ctx: &Context, input: &InputSource, mode: Mode) -> Result<
     let mut successes = 0;
     for meta in ctx.registry.list() {
         let codec = ctx.registry.get(meta.name)?;
-        if let Ok(decoded) = codec.decode(&text, mode) {
+        if let Ok(decoded) = ctx.decode(codec, &text, mode, &CodecOptions::new()) {
             successes += 1;
             let display = format_decoded(&decoded);
             println!("{:<18} {}", meta.name, display);
@@ -183,3 +492,142 @@ fn format_decoded(data: &[u8]) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_both_modes() {
+        let ctx = Context::default();
+        let opts = CodecOptions::default();
+        let both = |text: &str| run_decode_both_modes(&ctx, "base64pad", &InputSource::Literal(text.as_bytes().to_vec()), &opts).unwrap();
+
+        let agreed = both("aGVsbG8=");
+        assert!(!agreed.differ && agreed.strict.ok && agreed.drift.is_empty());
+
+        let unpadded = both("aGVs bG8");
+        assert!(unpadded.differ);
+        assert!(!unpadded.strict.ok && unpadded.strict.error.is_some());
+        assert_eq!(unpadded.lenient.preview.as_deref(), Some("hello"));
+        assert_eq!(
+            unpadded.drift,
+            [
+                "strict rejects the input; lenient decodes it",
+                "lenient ignored 1 whitespace character(s)",
+                "lenient added 1 missing padding character(s)",
+            ]
+        );
+
+        let neither = both("!!");
+        assert!(!neither.differ && !neither.strict.ok && !neither.lenient.ok);
+    }
+
+    #[test]
+    fn test_decode_all_shifts_finds_caesar() {
+        let ctx = Context::default();
+        let result =
+            run_decode_all_shifts(&ctx, "rot", &InputSource::Literal(b"Wkh vhfuhw lv lq wkh jdughq\n".to_vec()), &CodecOptions::new())
+                .unwrap();
+        assert_eq!(result.results.len(), 25);
+        assert_eq!(result.best_shift, Some(3));
+        assert_eq!(result.results[2].output, "The secret is in the garden");
+        assert_eq!(result.results[12].shift, 13);
+    }
+
+    #[test]
+    fn test_decode_passes_reports_each_pass() {
+        let ctx = Context::default();
+        let input = InputSource::Literal(b"q%3Da%25252Bb".to_vec());
+        let (decoded, passes) = run_decode_passes(&ctx, "url", &input, 5).unwrap();
+        assert_eq!(decoded, b"q=a+b");
+        let summary: Vec<(usize, usize, &str)> = passes.iter().map(|p| (p.pass, p.escapes, p.preview.as_str())).collect();
+        assert_eq!(summary, [(1, 2, "q=a%252Bb"), (2, 1, "q=a%2Bb"), (3, 1, "q=a+b")]);
+        assert!(run_decode_passes(&ctx, "base64", &input, 5).is_err());
+    }
+
+    #[test]
+    fn test_decode_record_appends() {
+        let ctx = Context::default();
+        let opts = CodecOptions::default();
+        let mut out = Vec::new();
+        decode_record(&ctx, "base64", "SGk", Mode::Strict, false, &opts, &mut out).unwrap();
+        decode_record(&ctx, "base64", "f2121", Mode::Strict, true, &opts, &mut out).unwrap();
+        assert_eq!(out, b"Hi!!");
+
+        let limited = Context::default().with_max_output_size(Some(1));
+        assert!(decode_record(&limited, "hex", "2121", Mode::Strict, false, &opts, &mut out).is_err());
+        assert_eq!(out, b"Hi!!");
+    }
+
+    #[test]
+    fn test_decode_json_reports_alphabet_mixing() {
+        let ctx = Context::default();
+        let input = InputSource::Literal(b"+_-/".to_vec());
+        let result = run_decode_json(&ctx, "base64", &input, Mode::Lenient, false, &CodecOptions::default()).unwrap();
+        assert_eq!(result.output_hex, "fbffbf");
+        assert_eq!(result.warnings.len(), 1);
+        assert!(run_decode_json(&ctx, "base64", &input, Mode::Strict, false, &CodecOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_decode_folds_lookalikes_in_lenient_mode() {
+        let ctx = Context::default();
+        let input = InputSource::Literal("аGVsbG８＝".as_bytes().to_vec());
+        let result = run_decode_json(&ctx, "base64pad", &input, Mode::Lenient, false, &CodecOptions::default()).unwrap();
+        assert_eq!(result.output_text.as_deref(), Some("hello"));
+        assert_eq!(result.warnings.len(), 3);
+        assert_eq!(result.warnings[1], "replaced lookalike '８' (U+FF18) at position 6 with '8'");
+        assert!(run_decode_json(&ctx, "base64pad", &input, Mode::Strict, false, &CodecOptions::default()).is_err());
+        let (decoded, warnings) =
+            run_decode_warned(&ctx, "hex", &InputSource::Literal("ｄｅａｄ".into()), Mode::Lenient, false, &CodecOptions::default())
+                .unwrap();
+        assert_eq!((decoded, warnings.len()), (vec![0xde, 0xad], 4));
+    }
+
+    #[test]
+    fn test_decode_pem_json() {
+        let input = InputSource::Literal(b"-----BEGIN PUBLIC KEY-----\nMAMCAQU=\n-----END PUBLIC KEY-----\n".to_vec());
+        let (input, blocks) = super::super::pem::read_pem(&Context::default(), &input).unwrap();
+        let mut result = run_decode_pem_json(&input, blocks.unwrap()).unwrap();
+        assert_eq!(result.output_hex, "3003020105");
+        assert_eq!(result.pem.as_ref().unwrap()[0].label, "PUBLIC KEY");
+        result.attach_asn1().unwrap();
+        let asn1 = result.asn1.unwrap();
+        assert_eq!(asn1[1].tag, "INTEGER");
+        assert_eq!(asn1[1].value.as_deref(), Some("05"));
+    }
+
+    #[test]
+    fn test_decode_all_json_reports_every_codec() {
+        let ctx = Context::default();
+        let result = run_decode_all_json(&ctx, &InputSource::Literal(b"aGVsbG8gd29ybGQ=".to_vec()), Mode::Strict).unwrap();
+        assert_eq!(result.results.len(), ctx.registry.list().len());
+
+        let b64 = result.results.iter().find(|r| r.codec == "base64pad").unwrap();
+        assert!(b64.success);
+        assert_eq!(b64.utf8, Some(true));
+        assert_eq!(b64.preview.as_deref(), Some("hello world"));
+        assert!(b64.score.unwrap() > 0.5);
+
+        let hex = result.results.iter().find(|r| r.codec == "base16lower").unwrap();
+        assert!(!hex.success);
+        assert!(hex.error.is_some() && hex.score.is_none());
+    }
+
+    #[test]
+    fn test_escaped_preview() {
+        assert_eq!(escaped_preview(b"a\n\xff\x00b", 10), "a\\n\\xff\\0b");
+        assert_eq!(escaped_preview(b"abcdef", 3), "abc...");
+    }
+
+    #[test]
+    fn test_decode_all_shifts_rejects_other_codecs() {
+        let ctx = Context::default();
+        let none = CodecOptions::new();
+        assert!(run_decode_all_shifts(&ctx, "base64", &InputSource::Literal(b"abc".to_vec()), &none).is_err());
+        let shift = CodecOptions::new().with("shift", "5");
+        let err = run_decode_all_shifts(&ctx, "shift", &InputSource::Literal(b"abc".to_vec()), &shift).unwrap_err();
+        assert!(err.to_string().contains("--all-shifts"), "{}", err);
+    }
+}
@@ -1,17 +1,100 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
 use serde::Serialize;
 
+use super::dec::{escaped_preview, PREVIEW_CHARS};
+use super::hashid::identify_hash;
+use super::pem::{is_pem, parse_pem};
+use super::report::{DetectFeatures, DetectField, DetectFieldsResult, DetectMatch, DetectResult};
 use crate::io::read_input;
-use mbase::error::Result;
-use mbase::types::{Context, DetectCandidate, InputSource, Mode};
-
-#[derive(Debug, Serialize)]
-pub struct DetectResult {
-    pub schema_version: u32,
-    pub candidates: Vec<DetectCandidate>,
-    pub input_preview: String,
+use mbase::codec::{evidence, Codec};
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CaseSensitivity, CodecOptions, Context, DetectCandidate, InputSource, Mode, PaddingRule};
+
+/// Candidates below this confidence get no decode preview.
+const PREVIEW_MIN_CONFIDENCE: f64 = 0.5;
+
+fn nanos(elapsed: Duration) -> u64 {
+    u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// A lenient decode under the context's `--ignore-chars` policy and output limit.
+fn lenient_decode(ctx: &Context, codec: &dyn Codec, input: &str) -> Result<Vec<u8>> {
+    ctx.decode(codec, input, Mode::Lenient, &CodecOptions::new())
+}
+
+/// The signals behind a candidate's score, measured the same way for every codec.
+fn features(ctx: &Context, codec: &dyn Codec, input: &str, candidate: &DetectCandidate) -> DetectFeatures {
+    let meta = codec.meta();
+    let chars: Vec<char> = input.chars().filter(|c| !c.is_whitespace()).collect();
+    // Padding is not part of the alphabet.
+    let symbols = match meta.padding {
+        PaddingRule::Required => chars.iter().rposition(|&c| c != '=').map_or(0, |last| last + 1),
+        PaddingRule::None => chars.len(),
+    };
+    let fold = meta.case_sensitivity != CaseSensitivity::Sensitive;
+    let in_alphabet = |c: char| {
+        meta.alphabet.contains(c)
+            || fold && (meta.alphabet.contains(c.to_ascii_lowercase()) || meta.alphabet.contains(c.to_ascii_uppercase()))
+    };
+    let charset_ratio = (!meta.alphabet.is_empty() && symbols > 0)
+        .then(|| chars[..symbols].iter().filter(|&&c| in_alphabet(c)).count() as f64 / symbols as f64);
+    let block_chars = codec.stream_block().map(|(_, chars)| chars);
+    let strict = codec.decode(input, Mode::Strict);
+    let checksum = match &strict {
+        Err(MbaseError::ChecksumMismatch | MbaseError::ChecksumMismatchAt { .. }) => Some(false),
+        _ if candidate.reasons.iter().any(|r| r.contains("checksum valid")) => Some(true),
+        _ => None,
+    };
+    DetectFeatures {
+        charset_ratio,
+        block_chars,
+        length_remainder: block_chars.map(|n| chars.len() % n),
+        prefix_hit: meta.multibase_code.is_some_and(|code| input.starts_with(code)),
+        strict_decode: strict.is_ok(),
+        lenient_decode: lenient_decode(ctx, codec, input).is_ok(),
+        checksum,
+    }
 }
 
-fn detect_multibase_prefix<'a>(input: &str, multibase_map: &'a std::collections::HashMap<char, &'static str>) -> Option<(&'a str, char)> {
+impl DetectMatch {
+    fn new(ctx: &Context, input: &str, candidate: DetectCandidate, scoring: Option<Duration>) -> Self {
+        let codec = ctx.registry.get(&candidate.codec).ok();
+        let decoded = (candidate.confidence >= PREVIEW_MIN_CONFIDENCE)
+            .then_some(codec)
+            .flatten()
+            .and_then(|codec| {
+                lenient_decode(ctx, codec, input).ok().or_else(|| {
+                    // Multibase matches decode without their prefix character.
+                    let prefixed = candidate.reasons.iter().any(|r| r.starts_with("multibase prefix"));
+                    let body = input.strip_prefix(codec.meta().multibase_code?)?;
+                    prefixed.then(|| lenient_decode(ctx, codec, body).ok()).flatten()
+                })
+            });
+        Self {
+            output_length: decoded.as_ref().map(Vec::len),
+            utf8: decoded.as_ref().map(|d| std::str::from_utf8(d).is_ok()),
+            preview: decoded.map(|d| escaped_preview(&d, PREVIEW_CHARS)),
+            scoring_ns: scoring.map(nanos),
+            features: codec.map(|codec| features(ctx, codec, input, &candidate)),
+            candidate,
+        }
+    }
+
+    fn without_preview(candidate: DetectCandidate) -> Self {
+        Self {
+            candidate,
+            output_length: None,
+            utf8: None,
+            preview: None,
+            scoring_ns: None,
+            features: None,
+        }
+    }
+}
+
+fn detect_multibase_prefix<'a>(input: &str, multibase_map: &'a std::collections::BTreeMap<char, &'static str>) -> Option<(&'a str, char)> {
     if input.is_empty() {
         return None;
     }
@@ -21,12 +104,46 @@ fn detect_multibase_prefix<'a>(input: &str, multibase_map: &'a std::collections:
 
 pub fn run_detect(ctx: &Context, input: InputSource, top_n: usize) -> Result<DetectResult> {
     let data = read_input(&input)?;
-    let text = String::from_utf8_lossy(&data);
-    let trimmed = text.trim();
+    Ok(detect_text(ctx, String::from_utf8_lossy(&data).trim(), top_n))
+}
+
+fn detect_text(ctx: &Context, trimmed: &str, top_n: usize) -> DetectResult {
+    let started = Instant::now();
+    let preview = match trimmed.char_indices().nth(60) {
+        Some((end, _)) => format!("{}...", &trimmed[..end]),
+        None => trimmed.to_string(),
+    };
 
     let multibase_map = ctx.registry.multibase_map();
     let mut candidates: Vec<DetectCandidate> = Vec::new();
 
+    if is_pem(trimmed) {
+        match parse_pem(ctx, trimmed) {
+            Ok(blocks) => {
+                let labels: Vec<_> = blocks.iter().map(|b| format!("'{}'", b.label)).collect();
+                candidates.push(DetectCandidate {
+                    codec: "base64pad".to_string(),
+                    confidence: 1.0,
+                    reasons: vec![format!("PEM armor {}", labels.join(", "))],
+                    warnings: vec![],
+                });
+                return DetectResult {
+                    candidates: candidates.into_iter().map(DetectMatch::without_preview).collect(),
+                    input_preview: preview,
+                    pem: Some(blocks),
+                    hashes: Vec::new(),
+                    elapsed_ns: nanos(started.elapsed()),
+                };
+            }
+            Err(e) => candidates.push(DetectCandidate {
+                codec: "base64pad".to_string(),
+                confidence: 0.5,
+                reasons: vec!["PEM armor".to_string()],
+                warnings: vec![e.to_string()],
+            }),
+        }
+    }
+
     if let Some((codec_name, code)) = detect_multibase_prefix(trimmed, &multibase_map) {
         let mut candidate = DetectCandidate {
             codec: codec_name.to_string(),
@@ -36,7 +153,7 @@ pub fn run_detect(ctx: &Context, input: InputSource, top_n: usize) -> Result<Det
         };
 
         if let Ok(codec) = ctx.registry.get(codec_name) {
-            let without_prefix = &trimmed[1..];
+            let without_prefix = trimmed.strip_prefix(code).unwrap_or(trimmed);
             if codec.validate(without_prefix, Mode::Lenient).is_ok() {
                 candidate.confidence = 1.0;
                 candidate.reasons.push("valid after removing prefix".to_string());
@@ -45,15 +162,22 @@ pub fn run_detect(ctx: &Context, input: InputSource, top_n: usize) -> Result<Det
         candidates.push(candidate);
     }
 
+    let mut timings: BTreeMap<&str, Duration> = BTreeMap::new();
     for codec in ctx.registry.list() {
         let codec_impl = ctx.registry.get(codec.name).unwrap();
+        let scored = Instant::now();
         let mut score = codec_impl.detect_score(trimmed);
 
         if candidates.iter().any(|c| c.codec == score.codec && c.confidence > score.confidence) {
+            timings.insert(codec.name, scored.elapsed());
             continue;
         }
 
-        if codec_impl.decode(trimmed, Mode::Lenient).is_ok() {
+        // A text transform decodes whatever it is given, and a decode that only succeeds once
+        // cleaning has dropped characters the codec's own detector rejected, or that yields
+        // nothing, says nothing about the input either.
+        let evidence = !codec_impl.accepts_any_text() && score.confidence > 0.0;
+        if evidence && lenient_decode(ctx, codec_impl, trimmed).is_ok_and(|bytes| !bytes.is_empty()) {
             if score.confidence < 0.5 {
                 score.confidence = 0.5;
             }
@@ -61,7 +185,9 @@ pub fn run_detect(ctx: &Context, input: InputSource, top_n: usize) -> Result<Det
                 score.reasons.push("decodes successfully".to_string());
             }
         }
+        timings.insert(codec.name, scored.elapsed());
 
+        tracing::trace!(codec = %score.codec, confidence = score.confidence, reasons = ?score.reasons, "detect score");
         if score.confidence > 0.0 {
             if let Some(existing) = candidates.iter_mut().find(|c| c.codec == score.codec) {
                 if score.confidence > existing.confidence {
@@ -74,21 +200,110 @@ pub fn run_detect(ctx: &Context, input: InputSource, top_n: usize) -> Result<Det
     }
 
     candidates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap());
+    let mut candidates = evidence::refine(&ctx.registry, trimmed, candidates);
+    tracing::debug!(matched = candidates.len(), best = candidates.first().map_or("-", |c| c.codec.as_str()), "detection finished");
     candidates.truncate(top_n);
 
-    let preview = if trimmed.len() > 60 {
-        format!("{}...", &trimmed[..60])
+    let candidates = candidates
+        .into_iter()
+        .map(|c| {
+            let scoring = timings.get(c.codec.as_str()).copied();
+            DetectMatch::new(ctx, trimmed, c, scoring)
+        })
+        .collect();
+    DetectResult {
+        candidates,
+        input_preview: preview,
+        pem: None,
+        hashes: identify_hash(trimmed),
+        elapsed_ns: nanos(started.elapsed()),
+    }
+}
+
+/// How `--fields` split the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldSource {
+    /// `name=value` pairs separated by `;`, with or without a `Cookie:` header name.
+    Cookie,
+    /// `name=value` pairs separated by `&`, from a bare query string or the part of a URL after `?`.
+    Query,
+}
+
+fn strip_header<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let (head, rest) = text.split_once(':')?;
+    head.trim().eq_ignore_ascii_case(name).then_some(rest)
+}
+
+/// Splits a cookie header or URL query into `(name, raw value)` pairs; `None` when no part
+/// has the `name=value` shape.
+fn split_fields(text: &str) -> Option<(FieldSource, Vec<(&str, &str)>)> {
+    let (source, body) = if let Some(rest) = strip_header(text, "cookie") {
+        (FieldSource::Cookie, rest)
+    } else if let Some((_, query)) = text.split_once('?') {
+        (FieldSource::Query, query.split('#').next().unwrap_or_default())
+    } else if text.contains(';') && !text.contains('&') {
+        (FieldSource::Cookie, text)
     } else {
-        trimmed.to_string()
+        (FieldSource::Query, text)
+    };
+    let separator = match source {
+        FieldSource::Cookie => ';',
+        FieldSource::Query => '&',
     };
+    let parts: Vec<&str> = body.split(separator).map(str::trim).filter(|p| !p.is_empty()).collect();
+    if !parts.iter().any(|p| p.contains('=')) {
+        return None;
+    }
+    let fields = parts.into_iter().map(|p| p.split_once('=').unwrap_or((p, ""))).collect();
+    Some((source, fields))
+}
 
-    let result = DetectResult {
-        schema_version: 1,
-        candidates,
-        input_preview: preview,
+/// Undoes the transport escaping of a field: percent-escapes, plus `+` for space in queries
+/// and the optional double quotes around cookie values. Left as is when that fails.
+fn unescape_field(ctx: &Context, raw: &str, source: FieldSource) -> String {
+    let text = match source {
+        FieldSource::Cookie => raw.strip_prefix('"').and_then(|r| r.strip_suffix('"')).unwrap_or(raw).to_string(),
+        FieldSource::Query => raw.replace('+', " "),
     };
+    if !text.contains('%') {
+        return text;
+    }
+    ctx.registry
+        .get("urlencoding")
+        .and_then(|codec| codec.decode(&text, Mode::Strict))
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or(text)
+}
 
-    Ok(result)
+/// Splits a cookie header or URL query into fields and runs detection on each value.
+pub fn run_detect_fields(ctx: &Context, input: InputSource, top_n: usize) -> Result<DetectFieldsResult> {
+    let data = read_input(&input)?;
+    let text = String::from_utf8_lossy(&data);
+    let (source, fields) = split_fields(text.trim())
+        .ok_or_else(|| MbaseError::invalid_input("no name=value fields found (expected a cookie header or URL query)"))?;
+    let fields = fields
+        .into_iter()
+        .map(|(name, raw)| {
+            let value = unescape_field(ctx, raw, source);
+            let (candidates, hashes) = match value.trim() {
+                "" => (Vec::new(), Vec::new()),
+                trimmed => {
+                    let detected = detect_text(ctx, trimmed, top_n);
+                    (detected.candidates, detected.hashes)
+                }
+            };
+            DetectField {
+                name: unescape_field(ctx, name, source),
+                raw: raw.to_string(),
+                value,
+                candidates,
+                hashes,
+            }
+        })
+        .collect();
+    Ok(DetectFieldsResult { source, fields })
 }
 
 #[cfg(test)]
@@ -97,8 +312,8 @@ mod tests {
 
     #[test]
     fn test_detect_multibase_prefix() {
-        use std::collections::HashMap;
-        let mut map = HashMap::new();
+        use std::collections::BTreeMap;
+        let mut map = BTreeMap::new();
         map.insert('z', "base58btc");
         map.insert('m', "base64");
 
@@ -107,12 +322,32 @@ mod tests {
         assert_eq!(detect_multibase_prefix("Hello", &map), None);
     }
 
+    #[test]
+    fn test_detect_lists_hash_shapes() {
+        let ctx = Context::default();
+        let sha1 = b"da39a3ee5e6b4b0d3255bfef95601890afd80709".to_vec();
+        let result = run_detect(&ctx, InputSource::Literal(sha1), 5).unwrap();
+        assert_eq!(result.hashes[0].algorithms[0], "SHA-1");
+        assert!(result.candidates.iter().any(|c| c.candidate.codec == "base16lower"));
+    }
+
+    #[test]
+    fn test_detect_pem_armor() {
+        let ctx = Context::default();
+        let pem = b"-----BEGIN CERTIFICATE-----\nMAA=\n-----END CERTIFICATE-----\n".to_vec();
+        let result = run_detect(&ctx, InputSource::Literal(pem), 5).unwrap();
+        assert_eq!(result.candidates.len(), 1);
+        assert_eq!(result.candidates[0].candidate.codec, "base64pad");
+        assert_eq!(result.candidates[0].candidate.reasons[0], "PEM armor 'CERTIFICATE'");
+        assert_eq!(result.pem.unwrap()[0].der_length, 2);
+    }
+
     #[test]
     fn test_detect_base64() {
         let ctx = Context::default();
         let result = run_detect(&ctx, InputSource::Literal(b"SGVsbG8gV29ybGQ".to_vec()), 5).unwrap();
         assert!(!result.candidates.is_empty());
-        assert!(result.candidates.iter().any(|c| c.codec.contains("base64")));
+        assert!(result.candidates.iter().any(|c| c.candidate.codec.contains("base64")));
     }
 
     #[test]
@@ -120,8 +355,8 @@ mod tests {
         let ctx = Context::default();
         let result = run_detect(&ctx, InputSource::Literal(b"zJxF12TrwUP45BMd".to_vec()), 5).unwrap();
         assert!(!result.candidates.is_empty());
-        assert_eq!(result.candidates[0].codec, "base58btc");
-        assert!(result.candidates[0].confidence >= 0.95);
+        assert_eq!(result.candidates[0].candidate.codec, "base58btc");
+        assert!(result.candidates[0].candidate.confidence >= 0.95);
     }
 
     #[test]
@@ -129,6 +364,110 @@ mod tests {
         let ctx = Context::default();
         let result = run_detect(&ctx, InputSource::Literal(b"f48656c6c6f".to_vec()), 5).unwrap();
         assert!(!result.candidates.is_empty());
-        assert_eq!(result.candidates[0].codec, "base16lower");
+        assert_eq!(result.candidates[0].candidate.codec, "base16lower");
+    }
+
+    #[test]
+    fn test_detect_decode_preview() {
+        let ctx = Context::default();
+        let result = run_detect(&ctx, InputSource::Literal(b"SGVsbG8sIFdvcmxkIQo=".to_vec()), 5).unwrap();
+        let best = &result.candidates[0];
+        assert_eq!(best.candidate.codec, "base64pad");
+        assert_eq!(best.preview.as_deref(), Some("Hello, World!\\n"));
+        assert_eq!(best.utf8, Some(true));
+        assert_eq!(best.output_length, Some(14));
+
+        let json = serde_json::to_value(best).unwrap();
+        assert_eq!(json["codec"], "base64pad");
+        assert_eq!(json["utf8"], true);
+
+        let multibase = run_detect(&ctx, InputSource::Literal(b"f68656c6c6fff".to_vec()), 5).unwrap();
+        let best = &multibase.candidates[0];
+        assert_eq!(best.preview.as_deref(), Some("hello\\xff"));
+        assert_eq!(best.utf8, Some(false));
+    }
+
+    #[test]
+    fn test_detect_non_ascii_input() {
+        let ctx = Context::default();
+        let text = format!("a{}", "é".repeat(70));
+        let result = run_detect(&ctx, InputSource::Literal(text.clone().into_bytes()), 5).unwrap();
+        assert_eq!(result.input_preview, format!("a{}...", "é".repeat(59)));
+        assert!(run_detect(&ctx, InputSource::Literal("🚀zQ3sh".as_bytes().to_vec()), 5).is_ok());
+    }
+
+    #[test]
+    fn test_detect_plain_text_is_not_a_decode() {
+        let ctx = Context::default();
+        for text in ["The quick brown fox jumps over the lazy dog", "Καλημέρα κόσμε"] {
+            let result = run_detect(&ctx, InputSource::Literal(text.as_bytes().to_vec()), 40).unwrap();
+            for DetectMatch { candidate, .. } in &result.candidates {
+                let transform = ctx.registry.get(&candidate.codec).unwrap().accepts_any_text();
+                assert!(!transform || candidate.confidence < 0.5, "{text}: {}", candidate.codec);
+                assert!(!candidate.codec.starts_with("baudot"), "{text}: {}", candidate.codec);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_features() {
+        let ctx = Context::default();
+        let result = run_detect(&ctx, InputSource::Literal(b"SGVsbG8sIFdvcmxkIQo=".to_vec()), 5).unwrap();
+        let best = &result.candidates[0];
+        assert!(best.scoring_ns.is_some());
+        let features = best.features.as_ref().unwrap();
+        assert_eq!(features.charset_ratio, Some(1.0));
+        assert_eq!((features.block_chars, features.length_remainder), (Some(4), Some(0)));
+        assert!(features.strict_decode && features.lenient_decode && !features.prefix_hit);
+        assert_eq!(features.checksum, None);
+
+        let address = b"1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_vec();
+        let result = run_detect(&ctx, InputSource::Literal(address), 10).unwrap();
+        let check = result.candidates.iter().find(|c| c.candidate.codec == "base58check").unwrap();
+        assert_eq!(check.features.as_ref().unwrap().checksum, Some(true));
+        let broken = b"1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3".to_vec();
+        let result = run_detect(&ctx, InputSource::Literal(broken), 40).unwrap();
+        let features = |name: &str| {
+            result
+                .candidates
+                .iter()
+                .find(|c| c.candidate.codec == name)
+                .and_then(|c| c.features.as_ref())
+        };
+        assert_eq!(features("base58check").map(|f| f.checksum), Some(Some(false)));
+    }
+
+    #[test]
+    fn test_split_fields() {
+        let (source, fields) = split_fields("Cookie: sid=abc; theme=\"dark\"; flag").unwrap();
+        assert_eq!(source, FieldSource::Cookie);
+        assert_eq!(fields, [("sid", "abc"), ("theme", "\"dark\""), ("flag", "")]);
+
+        let (source, fields) = split_fields("https://example.com/cb?code=SGk%3D&state=x+y#frag").unwrap();
+        assert_eq!(source, FieldSource::Query);
+        assert_eq!(fields, [("code", "SGk%3D"), ("state", "x+y")]);
+
+        assert_eq!(split_fields("a=1;b=2").unwrap().0, FieldSource::Cookie);
+        assert_eq!(split_fields("a=1&b=2;c").unwrap().0, FieldSource::Query);
+        assert_eq!(split_fields("SGVsbG8="), Some((FieldSource::Query, vec![("SGVsbG8", "")])));
+        assert_eq!(split_fields("plain text"), None);
+    }
+
+    #[test]
+    fn test_detect_fields() {
+        let ctx = Context::default();
+        let input = b"Cookie: session=SGVsbG8sIFdvcmxkIQ%3D%3D; id=5d41402abc4b2a76b9719d911017c592; empty=";
+        let result = run_detect_fields(&ctx, InputSource::Literal(input.to_vec()), 3).unwrap();
+        assert_eq!(result.source, FieldSource::Cookie);
+        let session = &result.fields[0];
+        assert_eq!((session.raw.as_str(), session.value.as_str()), ("SGVsbG8sIFdvcmxkIQ%3D%3D", "SGVsbG8sIFdvcmxkIQ=="));
+        assert_eq!(session.candidates[0].candidate.codec, "base64pad");
+        assert_eq!(session.candidates[0].preview.as_deref(), Some("Hello, World!"));
+        assert_eq!(result.fields[1].hashes[0].algorithms[0], "MD5");
+        assert!(result.fields[2].candidates.is_empty());
+
+        let query = run_detect_fields(&ctx, InputSource::Literal(b"?q=a+b%2".to_vec()), 3).unwrap();
+        assert_eq!(query.fields[0].value, "a b%2");
+        assert!(run_detect_fields(&ctx, InputSource::Literal(b"SGVsbG8".to_vec()), 3).is_err());
     }
 }
@@ -0,0 +1,358 @@
+use super::report::{DnsField, DnsRecord, DnsResult};
+use crate::io::read_input;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+const MAX_LABEL_LEN: usize = 63;
+const MAX_NAME_LEN: usize = 255;
+const CLASSES: &[&str] = &["IN", "CH", "HS", "CS"];
+
+impl DnsField {
+    fn plain(name: &str, value: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.into(),
+            encoding: None,
+            length: None,
+            hex: None,
+            converted: None,
+        }
+    }
+}
+
+/// Splits presentation-format text into records: strips `;` comments and joins
+/// lines inside parentheses.
+fn split_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut depth = 0usize;
+
+    for line in text.lines() {
+        let line = line.split(';').next().unwrap_or("");
+        for token in line.replace('(', " ( ").replace(')', " ) ").split_whitespace() {
+            match token {
+                "(" => depth += 1,
+                ")" => depth = depth.saturating_sub(1),
+                t => current.push(t.to_string()),
+            }
+        }
+        if depth == 0 && !current.is_empty() {
+            records.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        records.push(current);
+    }
+    records
+}
+
+/// Checks RFC 1035 length limits: 63 octets per label, 255 for the whole name.
+pub fn check_owner_name(name: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let trimmed = name.trim_end_matches('.');
+    if trimmed.is_empty() {
+        return warnings;
+    }
+
+    let mut wire_len = 1;
+    for label in trimmed.split('.') {
+        if label.is_empty() {
+            warnings.push(format!("owner name '{}' contains an empty label", name));
+        } else if label.len() > MAX_LABEL_LEN {
+            warnings.push(format!("label '{}' is {} octets; labels are limited to {}", label, label.len(), MAX_LABEL_LEN));
+        }
+        wire_len += label.len() + 1;
+    }
+    if wire_len > MAX_NAME_LEN {
+        warnings.push(format!("owner name is {} octets on the wire; names are limited to {}", wire_len, MAX_NAME_LEN));
+    }
+    warnings
+}
+
+struct FieldDecoder<'a> {
+    ctx: &'a Context,
+    to: Option<&'a str>,
+}
+
+impl FieldDecoder<'_> {
+    fn binary(&self, name: &str, value: &str, codec: &str) -> Result<(DnsField, Vec<u8>)> {
+        let no_opts = CodecOptions::new();
+        let decoded = self
+            .ctx
+            .decode(self.ctx.registry.get(codec)?, value, Mode::Lenient, &no_opts)
+            .map_err(|e| MbaseError::invalid_input(format!("{} is not valid {}: {}", name, codec, e)))?;
+        let converted = match self.to {
+            Some(to) => Some(self.ctx.encode(self.ctx.registry.get(to)?, &decoded, &no_opts)?),
+            None => None,
+        };
+        let field = DnsField {
+            name: name.to_string(),
+            value: value.to_string(),
+            encoding: Some(codec.to_string()),
+            length: Some(decoded.len()),
+            hex: Some(decoded.iter().map(|b| format!("{:02x}", b)).collect()),
+            converted,
+        };
+        Ok((field, decoded))
+    }
+}
+
+fn parse_u32(name: &str, value: Option<&String>) -> Result<u32> {
+    let value = value.ok_or_else(|| MbaseError::invalid_input(format!("missing {} field", name)))?;
+    value
+        .parse()
+        .map_err(|_| MbaseError::invalid_input(format!("{} must be a number, got '{}'", name, value)))
+}
+
+/// Key tag over DNSKEY RDATA (RFC 4034, Appendix B).
+pub fn dnskey_key_tag(flags: u16, protocol: u8, algorithm: u8, public_key: &[u8]) -> u16 {
+    let mut rdata = vec![(flags >> 8) as u8, flags as u8, protocol, algorithm];
+    rdata.extend_from_slice(public_key);
+
+    let mut acc: u32 = 0;
+    for (i, b) in rdata.iter().enumerate() {
+        acc += if i & 1 == 1 { *b as u32 } else { (*b as u32) << 8 };
+    }
+    acc += (acc >> 16) & 0xFFFF;
+    (acc & 0xFFFF) as u16
+}
+
+fn nsec3_hash_name(alg: u32) -> &'static str {
+    match alg {
+        1 => "SHA-1",
+        _ => "unknown",
+    }
+}
+
+fn ds_digest_len(digest_type: u32) -> Option<(usize, &'static str)> {
+    match digest_type {
+        1 => Some((20, "SHA-1")),
+        2 => Some((32, "SHA-256")),
+        3 => Some((32, "GOST R 34.11-94")),
+        4 => Some((48, "SHA-384")),
+        _ => None,
+    }
+}
+
+fn parse_record(decoder: &FieldDecoder, tokens: &[String]) -> Result<DnsRecord> {
+    let owner = tokens[0].clone();
+    let mut idx = 1;
+    let mut ttl = None;
+    let mut class = None;
+    while idx < tokens.len() {
+        let t = &tokens[idx];
+        if ttl.is_none() && t.chars().all(|c| c.is_ascii_digit()) {
+            ttl = t.parse().ok();
+        } else if class.is_none() && CLASSES.contains(&t.to_ascii_uppercase().as_str()) {
+            class = Some(t.to_ascii_uppercase());
+        } else {
+            break;
+        }
+        idx += 1;
+    }
+
+    let rtype = tokens
+        .get(idx)
+        .ok_or_else(|| MbaseError::invalid_input(format!("record for '{}' has no type", owner)))?
+        .to_ascii_uppercase();
+    let rdata = &tokens[idx + 1..];
+    let mut fields = Vec::new();
+    let mut warnings = check_owner_name(&owner);
+
+    match rtype.as_str() {
+        "NSEC3" | "NSEC3PARAM" => {
+            let alg = parse_u32("hash algorithm", rdata.first())?;
+            fields.push(DnsField::plain("hash-algorithm", format!("{} ({})", alg, nsec3_hash_name(alg))));
+            let flags = parse_u32("flags", rdata.get(1))?;
+            let opt_out = if flags & 1 == 1 { " (opt-out)" } else { "" };
+            fields.push(DnsField::plain("flags", format!("{}{}", flags, opt_out)));
+            fields.push(DnsField::plain("iterations", parse_u32("iterations", rdata.get(2))?.to_string()));
+
+            let salt = rdata.get(3).ok_or_else(|| MbaseError::invalid_input("missing salt field"))?;
+            if salt == "-" {
+                fields.push(DnsField::plain("salt", "- (empty)"));
+            } else {
+                fields.push(decoder.binary("salt", salt, "base16lower")?.0);
+            }
+
+            if rtype == "NSEC3" {
+                let next = rdata
+                    .get(4)
+                    .ok_or_else(|| MbaseError::invalid_input("missing next hashed owner field"))?;
+                let (field, hash) = decoder.binary("next-hashed-owner", next, "base32hexupper")?;
+                fields.push(field);
+                if alg == 1 && hash.len() != 20 {
+                    warnings.push(format!("next hashed owner is {} bytes; SHA-1 hashes are 20", hash.len()));
+                }
+                if next.len() > MAX_LABEL_LEN {
+                    warnings.push(format!("next hashed owner is {} characters and cannot be used as a label", next.len()));
+                }
+
+                let first_label = owner.split('.').next().unwrap_or_default();
+                match decoder.binary("owner-hash", first_label, "base32hexupper") {
+                    Ok((field, hash)) => {
+                        if alg == 1 && hash.len() != 20 {
+                            warnings.push(format!("owner hash label decodes to {} bytes; SHA-1 hashes are 20", hash.len()));
+                        }
+                        fields.push(field);
+                    }
+                    Err(_) => warnings.push(format!("first owner label '{}' is not a base32hex hash", first_label)),
+                }
+
+                fields.push(DnsField::plain("types", rdata[5.min(rdata.len())..].join(" ")));
+            }
+        }
+        "DNSKEY" | "CDNSKEY" => {
+            let flags = parse_u32("flags", rdata.first())?;
+            let mut roles = Vec::new();
+            if flags & 0x0100 != 0 {
+                roles.push("zone key");
+            }
+            if flags & 0x0001 != 0 {
+                roles.push("SEP");
+            }
+            if flags & 0x0080 != 0 {
+                roles.push("revoked");
+            }
+            fields.push(DnsField::plain("flags", format!("{} ({})", flags, roles.join(", "))));
+            let protocol = parse_u32("protocol", rdata.get(1))?;
+            if protocol != 3 {
+                warnings.push(format!("protocol is {}; DNSKEY requires 3", protocol));
+            }
+            fields.push(DnsField::plain("protocol", protocol.to_string()));
+            let algorithm = parse_u32("algorithm", rdata.get(2))?;
+            fields.push(DnsField::plain("algorithm", algorithm.to_string()));
+
+            let key_text = rdata[3.min(rdata.len())..].concat();
+            if key_text.is_empty() {
+                return Err(MbaseError::invalid_input("missing public key field"));
+            }
+            let (field, key) = decoder.binary("public-key", &key_text, "base64pad")?;
+            fields.push(field);
+            let tag = dnskey_key_tag(flags as u16, protocol as u8, algorithm as u8, &key);
+            fields.push(DnsField::plain("key-tag", tag.to_string()));
+        }
+        "DS" | "CDS" => {
+            fields.push(DnsField::plain("key-tag", parse_u32("key tag", rdata.first())?.to_string()));
+            fields.push(DnsField::plain("algorithm", parse_u32("algorithm", rdata.get(1))?.to_string()));
+            let digest_type = parse_u32("digest type", rdata.get(2))?;
+            let known = ds_digest_len(digest_type);
+            let label = known.map_or("unknown", |(_, name)| name);
+            fields.push(DnsField::plain("digest-type", format!("{} ({})", digest_type, label)));
+
+            let digest_text = rdata[3.min(rdata.len())..].concat();
+            if digest_text.is_empty() {
+                return Err(MbaseError::invalid_input("missing digest field"));
+            }
+            let (field, digest) = decoder.binary("digest", &digest_text, "base16lower")?;
+            fields.push(field);
+            if let Some((len, name)) = known {
+                if digest.len() != len {
+                    warnings.push(format!("digest is {} bytes; {} digests are {}", digest.len(), name, len));
+                }
+            }
+        }
+        other => {
+            return Err(MbaseError::invalid_input(format!(
+                "unsupported record type '{}' (supported: NSEC3, NSEC3PARAM, DNSKEY, CDNSKEY, DS, CDS)",
+                other
+            )))
+        }
+    }
+
+    Ok(DnsRecord {
+        owner,
+        ttl,
+        class,
+        rtype,
+        fields,
+        warnings,
+    })
+}
+
+pub fn run_dns(ctx: &Context, input: &InputSource, to: Option<&str>) -> Result<DnsResult> {
+    if let Some(to) = to {
+        ctx.registry.get(to)?;
+    }
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let decoder = FieldDecoder { ctx, to };
+
+    let records = split_records(&text)
+        .iter()
+        .map(|tokens| parse_record(&decoder, tokens))
+        .collect::<Result<Vec<_>>>()?;
+    if records.is_empty() {
+        return Err(MbaseError::invalid_input("no DNS records found in input"));
+    }
+
+    Ok(DnsResult { records })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 5155 Appendix A
+    const NSEC3: &str = "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example. NSEC3 1 1 12 aabbccdd (\n    2t7b4g4vsa5smi47k61mv5bv1a22bojr MX DNSKEY NS\n    SOA NSEC3PARAM RRSIG )";
+
+    #[test]
+    fn test_dns_nsec3() {
+        let ctx = Context::default();
+        let result = run_dns(&ctx, &InputSource::Literal(NSEC3.as_bytes().to_vec()), None).unwrap();
+        assert_eq!(result.records.len(), 1);
+        let rec = &result.records[0];
+        assert_eq!(rec.rtype, "NSEC3");
+        let next = rec.fields.iter().find(|f| f.name == "next-hashed-owner").unwrap();
+        assert_eq!(next.length, Some(20));
+        let owner = rec.fields.iter().find(|f| f.name == "owner-hash").unwrap();
+        assert_eq!(owner.length, Some(20));
+        let types = rec.fields.iter().find(|f| f.name == "types").unwrap();
+        assert_eq!(types.value, "MX DNSKEY NS SOA NSEC3PARAM RRSIG");
+        assert!(rec.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_dns_dnskey_key_tag() {
+        // RFC 4034 section 5.4 example
+        let ctx = Context::default();
+        let record = "dskey.example.com. 86400 IN DNSKEY 256 3 5 ( AQOeiiR0GOMYkDshWoSKz9Xz\n fwJr1AYtsmx3TGkJaNXVbfi/\n 2pHm822aJ5iI9BMzNXxeYCmZ\n DRD99WYwYqUSdjMmmAphXdvx\n egXd/M5+X7OrzKBaMbCVdFLU\n Uh6DhweJBjEVv5f2wwjM9Xzc\n nOf+EPbtG9DMBmADjFDc2w/r\n ljwvFw==\n ) ; key id = 60485";
+        let result = run_dns(&ctx, &InputSource::Literal(record.as_bytes().to_vec()), Some("base16lower")).unwrap();
+        let rec = &result.records[0];
+        assert_eq!(rec.ttl, Some(86400));
+        assert_eq!(rec.class.as_deref(), Some("IN"));
+        let tag = rec.fields.iter().find(|f| f.name == "key-tag").unwrap();
+        assert_eq!(tag.value, "60485");
+        let key = rec.fields.iter().find(|f| f.name == "public-key").unwrap();
+        assert_eq!(key.converted.as_deref(), key.hex.as_deref());
+    }
+
+    #[test]
+    fn test_dns_ds_digest_length() {
+        let ctx = Context::default();
+        let record = "example. DS 60485 5 1 2BB183AF5F22588179A53B0A98631FAD1A292118";
+        let result = run_dns(&ctx, &InputSource::Literal(record.as_bytes().to_vec()), None).unwrap();
+        let digest = result.records[0].fields.iter().find(|f| f.name == "digest").unwrap();
+        assert_eq!(digest.length, Some(20));
+        assert!(result.records[0].warnings.is_empty());
+
+        let short = "example. DS 60485 5 2 2BB183AF5F22588179A53B0A98631FAD1A292118";
+        let result = run_dns(&ctx, &InputSource::Literal(short.as_bytes().to_vec()), None).unwrap();
+        assert!(result.records[0].warnings.iter().any(|w| w.contains("SHA-256")));
+    }
+
+    #[test]
+    fn test_check_owner_name_label_limits() {
+        assert!(check_owner_name("example.com.").is_empty());
+        let long_label = format!("{}.example.", "a".repeat(64));
+        assert!(check_owner_name(&long_label).iter().any(|w| w.contains("limited to 63")));
+        let long_name = format!("{}.", vec!["a".repeat(63); 5].join("."));
+        assert!(check_owner_name(&long_name).iter().any(|w| w.contains("limited to 255")));
+    }
+
+    #[test]
+    fn test_dns_unsupported_type() {
+        let ctx = Context::default();
+        assert!(run_dns(&ctx, &InputSource::Literal(b"example. A 192.0.2.1".to_vec()), None).is_err());
+    }
+}
@@ -1,64 +1,156 @@
+use super::report::{EncodeAllResult, EncodeCodecResult, EncodeResult};
 use crate::io::read_input;
+use mbase::codec::Codec;
 use mbase::error::Result;
-use mbase::types::{Context, InputSource};
-use serde::Serialize;
-
-#[derive(Debug, Serialize)]
-pub struct EncodeResult {
-    pub codec: String,
-    pub input_length: usize,
-    pub output: String,
-    pub output_length: usize,
-    pub multibase_prefix: Option<char>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct EncodeAllResult {
-    pub input_length: usize,
-    pub results: Vec<EncodeCodecResult>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct EncodeCodecResult {
-    pub codec: String,
-    pub output: Option<String>,
-    pub error: Option<String>,
-}
+use mbase::types::{CodecOptions, Context, InputSource};
 
-pub fn run_encode(ctx: &Context, codec_name: &str, input: &InputSource, multibase: bool) -> Result<String> {
+pub fn run_encode(ctx: &Context, codec_name: &str, input: &InputSource, multibase: bool, opts: &CodecOptions) -> Result<String> {
     let codec = ctx.registry.get(codec_name)?;
     let data = read_input(input)?;
-    let encoded = codec.encode(&data)?;
+    let mut encoded = String::new();
+    encode_record(ctx, codec, &data, multibase, opts, &mut encoded)?;
+    Ok(encoded)
+}
 
+/// Appends the encoding of `data`, multibase-prefixed when asked, to `out`.
+pub fn encode_record(ctx: &Context, codec: &dyn Codec, data: &[u8], multibase: bool, opts: &CodecOptions, out: &mut String) -> Result<()> {
+    let start = out.len();
     if multibase {
         if let Some(prefix) = codec.meta().multibase_code {
-            return Ok(format!("{}{}", prefix, encoded));
+            out.push(prefix);
         }
     }
+    ctx.encode_into(codec, data, opts, out).inspect_err(|_| out.truncate(start))
+}
 
-    Ok(encoded)
+/// What the bytes given to `enc` look like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputClass {
+    /// Printable ASCII, tabs and line endings.
+    Text,
+    /// UTF-8 text with characters beyond ASCII.
+    Utf8,
+    /// Invalid UTF-8, or control characters other than tabs and line endings.
+    Binary,
 }
 
-pub fn run_encode_all(ctx: &Context, input: &InputSource) -> Result<String> {
+impl InputClass {
+    pub fn of(data: &[u8]) -> Self {
+        let Ok(text) = std::str::from_utf8(data) else {
+            return InputClass::Binary;
+        };
+        if text.chars().any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r')) {
+            InputClass::Binary
+        } else if text.is_ascii() {
+            InputClass::Text
+        } else {
+            InputClass::Utf8
+        }
+    }
+}
+
+/// Warnings about input that `codec` will probably not encode as meant: binary data for a
+/// text-only codec, and the newline `echo` and here-strings leave at the end of one line of text.
+pub fn encode_warnings(codec: Option<&dyn Codec>, data: &[u8]) -> Vec<String> {
+    let class = InputClass::of(data);
+    let mut warnings = Vec::new();
+    if let Some(codec) = codec.filter(|c| c.text_only() && class == InputClass::Binary) {
+        warnings.push(format!("{} encodes text, but the input looks binary ({} bytes)", codec.name(), data.len()));
+    }
+    let one_line = data.iter().position(|&b| b == b'\n') == Some(data.len().wrapping_sub(1));
+    if class != InputClass::Binary && one_line && data.len() > 1 {
+        warnings.push("the input's trailing newline is encoded too (--strip-trailing-newline drops it)".to_string());
+    }
+    warnings
+}
+
+/// Characters an encoding may use, for `enc --all --charset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputCharset {
+    /// Printable ASCII, space included.
+    Ascii,
+    /// ASCII letters and digits.
+    Alnum,
+    /// RFC 3986 unreserved characters: letters, digits and `-._~`.
+    UrlSafe,
+}
+
+impl OutputCharset {
+    pub fn allows(self, text: &str) -> bool {
+        text.bytes().all(|b| match self {
+            OutputCharset::Ascii => b == b' ' || b.is_ascii_graphic(),
+            OutputCharset::Alnum => b.is_ascii_alphanumeric(),
+            OutputCharset::UrlSafe => b.is_ascii_alphanumeric() || b"-._~".contains(&b),
+        })
+    }
+}
+
+/// Constraints for `enc --all`. With any set, only encodings that meet them are kept, shortest first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncodeAllFilter {
+    /// Most characters an encoding may have.
+    pub max_len: Option<usize>,
+    pub charset: Option<OutputCharset>,
+}
+
+impl EncodeAllFilter {
+    fn is_active(&self) -> bool {
+        self.max_len.is_some() || self.charset.is_some()
+    }
+
+    fn allows(&self, encoded: &str) -> bool {
+        self.max_len.is_none_or(|max| encoded.chars().count() <= max) && self.charset.is_none_or(|c| c.allows(encoded))
+    }
+}
+
+/// Every codec's encoding of `data` in registration order, or with an active filter the
+/// encodings that pass it, sorted by length.
+fn encode_all(ctx: &Context, data: &[u8], filter: &EncodeAllFilter) -> Result<Vec<(&'static str, Result<String>)>> {
+    let mut results = Vec::new();
+    for meta in ctx.registry.list() {
+        let codec = ctx.registry.get(meta.name)?;
+        results.push((meta.name, ctx.encode(codec, data, &CodecOptions::new())));
+    }
+    if filter.is_active() {
+        results.retain(|(_, encoded)| encoded.as_ref().is_ok_and(|e| filter.allows(e)));
+        results.sort_by_cached_key(|(_, encoded)| encoded.as_ref().map_or(0, |e| e.chars().count()));
+    }
+    Ok(results)
+}
+
+pub fn run_encode_all(ctx: &Context, input: &InputSource, filter: &EncodeAllFilter) -> Result<String> {
     let data = read_input(input)?;
+    let results = encode_all(ctx, &data, filter)?;
     let mut output = String::new();
 
-    output.push_str(&format!("{:<18} ENCODED\n", "CODEC"));
+    if filter.is_active() && results.is_empty() {
+        output.push_str("No codec output meets the constraints.\n");
+        return Ok(output);
+    }
+    if filter.is_active() {
+        output.push_str(&format!("{:<18} {:>6} ENCODED\n", "CODEC", "LEN"));
+    } else {
+        output.push_str(&format!("{:<18} ENCODED\n", "CODEC"));
+    }
     output.push_str(&format!("{}\n", "-".repeat(70)));
 
-    for meta in ctx.registry.list() {
-        let codec = ctx.registry.get(meta.name)?;
-        match codec.encode(&data) {
+    for (name, encoded) in results {
+        match encoded {
             Ok(encoded) => {
-                let display = if encoded.len() > 50 {
-                    format!("{}...", &encoded[..47])
+                let len = encoded.chars().count();
+                let display = if len > 50 {
+                    format!("{}...", encoded.chars().take(47).collect::<String>())
                 } else {
                     encoded
                 };
-                output.push_str(&format!("{:<18} {}\n", meta.name, display));
+                if filter.is_active() {
+                    output.push_str(&format!("{:<18} {:>6} {}\n", name, len, display));
+                } else {
+                    output.push_str(&format!("{:<18} {}\n", name, display));
+                }
             }
             Err(_) => {
-                output.push_str(&format!("{:<18} (encoding failed)\n", meta.name));
+                output.push_str(&format!("{:<18} (encoding failed)\n", name));
             }
         }
     }
@@ -66,11 +158,11 @@ pub fn run_encode_all(ctx: &Context, input: &InputSource) -> Result<String> {
     Ok(output)
 }
 
-pub fn run_encode_json(ctx: &Context, codec_name: &str, input: &InputSource, multibase: bool) -> Result<EncodeResult> {
+pub fn run_encode_json(ctx: &Context, codec_name: &str, input: &InputSource, multibase: bool, opts: &CodecOptions) -> Result<EncodeResult> {
     let codec = ctx.registry.get(codec_name)?;
     let data = read_input(input)?;
     let input_length = data.len();
-    let encoded = codec.encode(&data)?;
+    let encoded = ctx.encode(codec, &data, opts)?;
 
     let (output, multibase_prefix) = if multibase {
         if let Some(prefix) = codec.meta().multibase_code {
@@ -93,30 +185,105 @@ pub fn run_encode_json(ctx: &Context, codec_name: &str, input: &InputSource, mul
     })
 }
 
-pub fn run_encode_all_json(ctx: &Context, input: &InputSource) -> Result<EncodeAllResult> {
+pub fn run_encode_all_json(ctx: &Context, input: &InputSource, filter: &EncodeAllFilter) -> Result<EncodeAllResult> {
     let data = read_input(input)?;
-    let input_length = data.len();
-    let mut results = Vec::new();
+    let results = encode_all(ctx, &data, filter)?
+        .into_iter()
+        .map(|(name, encoded)| match encoded {
+            Ok(encoded) => EncodeCodecResult {
+                codec: name.to_string(),
+                output: Some(encoded),
+                error: None,
+            },
+            Err(e) => EncodeCodecResult {
+                codec: name.to_string(),
+                output: None,
+                error: Some(e.to_string()),
+            },
+        })
+        .collect();
 
-    for meta in ctx.registry.list() {
-        let codec = ctx.registry.get(meta.name)?;
-        match codec.encode(&data) {
-            Ok(encoded) => {
-                results.push(EncodeCodecResult {
-                    codec: meta.name.to_string(),
-                    output: Some(encoded),
-                    error: None,
-                });
-            }
-            Err(e) => {
-                results.push(EncodeCodecResult {
-                    codec: meta.name.to_string(),
-                    output: None,
-                    error: Some(e.to_string()),
-                });
-            }
-        }
+    Ok(EncodeAllResult {
+        input_length: data.len(),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(ctx: &Context, data: &[u8], filter: EncodeAllFilter) -> Vec<&'static str> {
+        encode_all(ctx, data, &filter).unwrap().into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn test_input_class() {
+        assert_eq!(InputClass::of(b"hello\tworld\r\n"), InputClass::Text);
+        assert_eq!(InputClass::of("héllo".as_bytes()), InputClass::Utf8);
+        assert_eq!(InputClass::of(b"\x00\x01hello"), InputClass::Binary);
+        assert_eq!(InputClass::of(b"\xff\xfe"), InputClass::Binary);
     }
 
-    Ok(EncodeAllResult { input_length, results })
+    #[test]
+    fn test_encode_warnings() {
+        let ctx = Context::default();
+        let morse = ctx.registry.get("morse").ok();
+        let base64 = ctx.registry.get("base64").ok();
+        assert_eq!(encode_warnings(morse, b"\x89PNG\r\n\x1a\n").len(), 1);
+        assert!(encode_warnings(base64, b"\x89PNG\r\n\x1a\n").is_empty());
+        assert!(encode_warnings(morse, b"sos").is_empty());
+        let newline = encode_warnings(base64, b"foo\n");
+        assert!(newline[0].contains("--strip-trailing-newline"));
+        assert!(encode_warnings(base64, b"two\nlines\n").is_empty());
+        assert!(encode_warnings(base64, b"\n").is_empty());
+        assert_eq!(encode_warnings(None, b"foo\n").len(), 1);
+    }
+
+    #[test]
+    fn test_output_charsets() {
+        assert!(OutputCharset::Ascii.allows("a b~"));
+        assert!(!OutputCharset::Ascii.allows("a\nb"));
+        assert!(OutputCharset::Alnum.allows("abc123"));
+        assert!(!OutputCharset::Alnum.allows("ab-c"));
+        assert!(OutputCharset::UrlSafe.allows("a-b_c.d~"));
+        assert!(!OutputCharset::UrlSafe.allows("a+b/c="));
+    }
+
+    #[test]
+    fn test_encode_all_filters() {
+        let ctx = Context::default();
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x00, 0x01, 0x02, 0x03];
+        assert_eq!(names(&ctx, &data, EncodeAllFilter::default()).len(), ctx.registry.list().len());
+
+        let url_safe = EncodeAllFilter {
+            charset: Some(OutputCharset::UrlSafe),
+            ..Default::default()
+        };
+        let results = encode_all(&ctx, &data, &url_safe).unwrap();
+        let lengths: Vec<usize> = results.iter().map(|(_, e)| e.as_ref().unwrap().chars().count()).collect();
+        assert!(lengths.windows(2).all(|w| w[0] <= w[1]));
+        assert!(results.iter().all(|(_, e)| OutputCharset::UrlSafe.allows(e.as_ref().unwrap())));
+        let url_safe = names(&ctx, &data, url_safe);
+        assert!(url_safe.contains(&"base64url") && !url_safe.contains(&"base64pad"));
+
+        let short = names(
+            &ctx,
+            &data,
+            EncodeAllFilter {
+                max_len: Some(11),
+                charset: Some(OutputCharset::Alnum),
+            },
+        );
+        assert!(short.contains(&"base62") && !short.contains(&"base16lower"));
+        assert!(names(
+            &ctx,
+            &data,
+            EncodeAllFilter {
+                max_len: Some(1),
+                charset: None
+            }
+        )
+        .is_empty());
+    }
 }
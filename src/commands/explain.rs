@@ -1,25 +1,20 @@
-use serde::Serialize;
-
+use super::extract::extract_input;
+use super::report::{ExplainError, ExplainResult, Segment};
 use crate::io::read_input;
+use mbase::codec::Codec;
 use mbase::error::{MbaseError, Result};
-use mbase::types::{Context, InputSource, Mode};
-
-#[derive(Debug, Serialize)]
-pub struct ExplainResult {
-    pub schema_version: u32,
-    pub codec: String,
-    pub input_preview: String,
-    pub valid: bool,
-    pub error: Option<ExplainError>,
-    pub suggestions: Vec<String>,
-}
+use mbase::types::{CodecOptions, Context, InputSource, Mode, PaddingRule};
 
-#[derive(Debug, Serialize)]
-pub struct ExplainError {
-    pub message: String,
-    pub position: Option<usize>,
-    pub offending_char: Option<char>,
-    pub context: Option<String>,
+impl Segment {
+    fn new(kind: &'static str, input: &str, start: usize, end: usize, note: Option<String>) -> Self {
+        Self {
+            kind,
+            start,
+            end,
+            text: input[start..end].to_string(),
+            note,
+        }
+    }
 }
 
 fn get_context(input: &str, pos: usize, window: usize) -> String {
@@ -76,6 +71,18 @@ fn suggest_fixes(error: &MbaseError, codec_name: &str, input: &str) -> Vec<Strin
             suggestions.push("Checksum validation failed; data may be corrupted".to_string());
             suggestions.push("Verify the input was copied correctly".to_string());
         }
+        MbaseError::ChecksumMismatchAt { position, .. } => {
+            if codec_name.starts_with("bubble") || codec_name == "babble" {
+                suggestions.push(format!(
+                    "The tuple starting at position {} is inconsistent with the checksum carried by the preceding tuples; \
+                     the error is in this tuple or an earlier one",
+                    position
+                ));
+            } else {
+                suggestions.push("Checksum validation failed; data may be corrupted".to_string());
+            }
+            suggestions.push("Verify the input was copied correctly".to_string());
+        }
         _ => {}
     }
 
@@ -86,6 +93,154 @@ fn suggest_fixes(error: &MbaseError, codec_name: &str, input: &str) -> Vec<Strin
     suggestions
 }
 
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Splits `input[from..to]` into payload and whitespace/line-break segments.
+fn push_payload_runs(segments: &mut Vec<Segment>, input: &str, from: usize, to: usize, note: Option<String>) {
+    let mut note = note;
+    let mut start = from;
+    let mut in_space = input[from..to].starts_with(|c: char| c.is_whitespace());
+    for (i, c) in input[from..to].char_indices().map(|(i, c)| (i + from, c)) {
+        if c.is_whitespace() != in_space {
+            push_run(segments, input, start, i, in_space, &mut note);
+            start = i;
+            in_space = !in_space;
+        }
+    }
+    if start < to {
+        push_run(segments, input, start, to, in_space, &mut note);
+    }
+}
+
+fn push_run(segments: &mut Vec<Segment>, input: &str, start: usize, end: usize, space: bool, note: &mut Option<String>) {
+    if !space {
+        segments.push(Segment::new("payload", input, start, end, note.take()));
+    } else if input[start..end].contains('\n') {
+        segments.push(Segment::new("line_break", input, start, end, Some("ignored in lenient mode".to_string())));
+    } else {
+        segments.push(Segment::new("whitespace", input, start, end, Some("ignored in lenient mode".to_string())));
+    }
+}
+
+fn ripple_type(version: u8) -> &'static str {
+    match version {
+        0x00 => "account ID",
+        0x1c => "node public key",
+        0x20 => "node private key",
+        0x21 => "family seed",
+        0x23 => "account public key",
+        _ => "unknown type",
+    }
+}
+
+fn strkey_type(version: u8) -> &'static str {
+    match version >> 3 {
+        2 => "contract",
+        6 => "ed25519 public key",
+        12 => "muxed account",
+        15 => "signed payload",
+        18 => "ed25519 secret seed",
+        19 => "pre-authorized transaction",
+        23 => "sha256 hash signer",
+        _ => "unknown type",
+    }
+}
+
+/// Describes the version byte and checksum of the checksummed base58/base32 families.
+fn checksum_note(ctx: &Context, codec: &str, body: &str) -> Option<String> {
+    let (raw_codec, checksum_len) = match codec {
+        "base58check" | "cb58" => ("base58btc", 4),
+        "base58ripplecheck" => ("base58ripple", 4),
+        "strkey" => ("base32upper", 2),
+        _ => return None,
+    };
+    let raw = ctx
+        .registry
+        .get(raw_codec)
+        .and_then(|c| ctx.decode(c, body, Mode::Lenient, &CodecOptions::new()))
+        .ok()
+        .filter(|raw| raw.len() > checksum_len)?;
+    let checksum = hex(&raw[raw.len() - checksum_len..]);
+    let version = raw[0];
+    let payload_len = raw.len() - checksum_len - 1;
+    const UNALIGNED: &str = "(base58 does not align bytes with characters)";
+    Some(match codec {
+        "base58check" => format!("version 0x{:02x}, {} payload bytes, checksum {} {}", version, payload_len, checksum, UNALIGNED),
+        "base58ripplecheck" => format!(
+            "version 0x{:02x} ({}), {} payload bytes, checksum {} {}",
+            version,
+            ripple_type(version),
+            payload_len,
+            checksum,
+            UNALIGNED
+        ),
+        "cb58" => format!("{} payload bytes, checksum {} (sha256) {}", raw.len() - checksum_len, checksum, UNALIGNED),
+        _ => format!(
+            "version 0x{:02x} '{}' ({}), {} payload bytes, CRC16 {}",
+            version,
+            body.chars().next().unwrap_or('?'),
+            strkey_type(version),
+            payload_len,
+            checksum
+        ),
+    })
+}
+
+/// Labels the parts of a valid input: multibase prefix, HRP/separator, payload, padding, checksum and line breaks.
+fn annotate(ctx: &Context, codec: &dyn Codec, input: &str, prefix_len: usize, decoded: &[u8]) -> Vec<Segment> {
+    let meta = codec.meta();
+    let mut segments = Vec::new();
+    if prefix_len > 0 {
+        segments.push(Segment::new("prefix", input, 0, prefix_len, Some(format!("multibase prefix for {}", meta.name))));
+    }
+
+    let end = input.len();
+    let payload_note = Some(format!("{} decoded bytes", decoded.len()));
+
+    if meta.name.starts_with("bech32") {
+        let sep = input.rfind('1').unwrap_or(prefix_len);
+        let checksum_start = end.saturating_sub(6).max(sep + 1);
+        segments.push(Segment::new("hrp", input, prefix_len, sep, Some("human-readable part".to_string())));
+        segments.push(Segment::new("separator", input, sep, sep + 1, None));
+        push_payload_runs(&mut segments, input, sep + 1, checksum_start, payload_note);
+        let variant = if meta.name == "bech32m" {
+            "BCH checksum (bech32m constant)"
+        } else {
+            "BCH checksum"
+        };
+        segments.push(Segment::new("checksum", input, checksum_start, end, Some(variant.to_string())));
+        return segments;
+    }
+
+    let padded = meta.padding == PaddingRule::Required || meta.name.contains("pad");
+    let body_end = if padded && !meta.alphabet.contains('=') {
+        input.trim_end_matches(['=', '\n', '\r', ' ', '\t']).len()
+    } else {
+        end
+    };
+
+    match checksum_note(ctx, meta.name, &input[prefix_len..body_end]) {
+        Some(note) => push_payload_runs(&mut segments, input, prefix_len, body_end, Some(note)),
+        None => push_payload_runs(&mut segments, input, prefix_len, body_end, payload_note),
+    }
+
+    if body_end < end {
+        let pad_start = body_end + input[body_end..].find('=').unwrap_or(end - body_end);
+        if pad_start > body_end {
+            push_payload_runs(&mut segments, input, body_end, pad_start, None);
+        }
+        let pad_end = pad_start + input[pad_start..].trim_end_matches(|c: char| c.is_whitespace()).len();
+        let count = input[pad_start..pad_end].chars().filter(|&c| c == '=').count();
+        segments.push(Segment::new("padding", input, pad_start, pad_end, Some(format!("{} padding character(s)", count))));
+        if pad_end < end {
+            push_payload_runs(&mut segments, input, pad_end, end, None);
+        }
+    }
+    segments
+}
+
 pub fn run_explain(ctx: &Context, input: InputSource, codec: &str, mode: Mode) -> Result<ExplainResult> {
     let data = read_input(&input)?;
     let text = String::from_utf8_lossy(&data);
@@ -93,31 +248,57 @@ pub fn run_explain(ctx: &Context, input: InputSource, codec: &str, mode: Mode) -
 
     let codec_impl = ctx.registry.get(codec)?;
 
-    let preview = if trimmed.len() > 60 {
-        format!("{}...", &trimmed[..60])
-    } else {
-        trimmed.to_string()
+    let preview = match trimmed.char_indices().nth(60) {
+        Some((end, _)) => format!("{}...", &trimmed[..end]),
+        None => trimmed.to_string(),
     };
 
-    let result = match codec_impl.decode(trimmed, mode) {
-        Ok(_) => ExplainResult {
-            schema_version: 1,
+    // Fall back to stripping the codec's own multibase prefix, so it can be labelled.
+    let decode = |text: &str| ctx.decode(codec_impl, text, mode, &CodecOptions::new());
+    let decoded =
+        decode(trimmed)
+            .map(|d| (0, d))
+            .or_else(|e| match codec_impl.meta().multibase_code.filter(|&code| trimmed.starts_with(code)) {
+                Some(code) => decode(&trimmed[code.len_utf8()..]).map(|d| (code.len_utf8(), d)).map_err(|_| e),
+                None => Err(e),
+            });
+
+    let result = match decoded {
+        Ok((prefix_len, bytes)) => ExplainResult {
             codec: codec.to_string(),
             input_preview: preview,
             valid: true,
             error: None,
             suggestions: vec![],
+            valid_as: vec![],
+            segments: annotate(ctx, codec_impl, trimmed, prefix_len, &bytes),
+            extracted: None,
         },
         Err(e) => {
             let (position, offending_char, context) = match &e {
                 MbaseError::InvalidCharacter { char: c, position: p } => (Some(*p), Some(*c), Some(get_context(trimmed, *p, 10))),
+                MbaseError::ChecksumMismatchAt { position: p, .. } | MbaseError::InvalidInputAt { position: p, .. }
+                    if *p < trimmed.len() =>
+                {
+                    (Some(*p), None, Some(get_context(trimmed, *p, 10)))
+                }
                 _ => (None, None, None),
             };
 
-            let suggestions = suggest_fixes(&e, codec, trimmed);
+            let mut suggestions = suggest_fixes(&e, codec, trimmed);
+            let valid_as: Vec<String> = ctx
+                .decoding_siblings(codec, trimmed, mode)?
+                .iter()
+                .map(|c| c.name().to_string())
+                .collect();
+            if let Some(sibling) = valid_as.first() {
+                suggestions.insert(
+                    0,
+                    format!("Input is valid {}, not {}; use --codec {} or dec --try-siblings", sibling, codec_impl.name(), sibling),
+                );
+            }
 
             ExplainResult {
-                schema_version: 1,
                 codec: codec.to_string(),
                 input_preview: preview,
                 valid: false,
@@ -128,6 +309,9 @@ pub fn run_explain(ctx: &Context, input: InputSource, codec: &str, mode: Mode) -
                     context,
                 }),
                 suggestions,
+                valid_as,
+                segments: vec![],
+                extracted: None,
             }
         }
     };
@@ -135,6 +319,18 @@ pub fn run_explain(ctx: &Context, input: InputSource, codec: &str, mode: Mode) -
     Ok(result)
 }
 
+/// Explains the longest decodable substring of `input`, with segment offsets into the raw input.
+pub fn run_explain_extracted(ctx: &Context, input: &InputSource, codec: &str, mode: Mode) -> Result<ExplainResult> {
+    let (narrowed, extracted) = extract_input(ctx, codec, input, mode)?;
+    let mut result = run_explain(ctx, narrowed, codec, mode)?;
+    for segment in &mut result.segments {
+        segment.start += extracted.start;
+        segment.end += extracted.start;
+    }
+    result.extracted = Some(extracted);
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +362,104 @@ mod tests {
         assert!(result.suggestions.iter().any(|s| s.contains("lenient")));
     }
 
+    #[test]
+    fn test_explain_valid_sibling() {
+        let ctx = Context::default();
+        let result = run_explain(&ctx, InputSource::Literal(b"SGk-_w".to_vec()), "b64", Mode::Strict).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.valid_as, ["base64url", "base64url-jwt"]);
+        assert_eq!(result.suggestions[0], "Input is valid base64url, not base64; use --codec base64url or dec --try-siblings");
+
+        let result = run_explain(&ctx, InputSource::Literal(b"SGk!".to_vec()), "base64", Mode::Strict).unwrap();
+        assert!(result.valid_as.is_empty());
+    }
+
+    #[test]
+    fn test_explain_bubblebabble_checksum_tuple() {
+        let ctx = Context::default();
+        // "xitakh-esalg-ox" is "test"; the trailing checksum vowel is 'o'.
+        let result = run_explain(&ctx, InputSource::Literal(b"xitakh-esalg-ax".to_vec()), "bubblebabble", Mode::Strict).unwrap();
+        assert!(!result.valid);
+        let err = result.error.unwrap();
+        assert_eq!(err.position, Some(13));
+        assert!(err.context.unwrap().contains('^'));
+        assert!(result.suggestions.iter().any(|s| s.contains("tuple starting at position 13")));
+    }
+
+    #[test]
+    fn test_explain_punycode_truncated_delta() {
+        let ctx = Context::default();
+        let result = run_explain(&ctx, InputSource::Literal(b"abc-k9".to_vec()), "punycode", Mode::Strict).unwrap();
+        let err = result.error.unwrap();
+        assert_eq!(err.position, Some(4));
+        assert!(err.message.contains("truncated"));
+        assert!(err.context.unwrap().ends_with("    ^"));
+    }
+
+    fn kinds(result: &ExplainResult) -> Vec<(&str, &str)> {
+        result.segments.iter().map(|s| (s.kind, s.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_explain_segments_padding_and_line_breaks() {
+        let ctx = Context::default();
+        let result = run_explain(&ctx, InputSource::Literal(b"aGVsbG8g\nd29ybGQ=".to_vec()), "base64pad", Mode::Lenient).unwrap();
+        assert!(result.valid);
+        assert_eq!(
+            kinds(&result),
+            vec![
+                ("payload", "aGVsbG8g"),
+                ("line_break", "\n"),
+                ("payload", "d29ybGQ"),
+                ("padding", "=")
+            ]
+        );
+        assert_eq!(result.segments[3].start, 16);
+        assert_eq!(result.segments[0].note.as_deref(), Some("11 decoded bytes"));
+    }
+
+    #[test]
+    fn test_explain_segments_multibase_prefix() {
+        let ctx = Context::default();
+        let result = run_explain(&ctx, InputSource::Literal(b"f68656c6c6f".to_vec()), "base16lower", Mode::Strict).unwrap();
+        assert!(result.valid);
+        assert_eq!(kinds(&result), vec![("prefix", "f"), ("payload", "68656c6c6f")]);
+    }
+
+    #[test]
+    fn test_explain_segments_bech32() {
+        let ctx = Context::default();
+        let result = run_explain(&ctx, InputSource::Literal(b"data1dpjkcmr0qeu8sq".to_vec()), "bech32", Mode::Strict).unwrap();
+        assert!(result.valid);
+        assert_eq!(kinds(&result), vec![("hrp", "data"), ("separator", "1"), ("payload", "dpjkcmr0"), ("checksum", "qeu8sq")]);
+    }
+
+    #[test]
+    fn test_explain_segments_base58check() {
+        let ctx = Context::default();
+        let encoded = ctx.registry.get("base58check").unwrap().encode(b"\x00hello").unwrap();
+        let result = run_explain(&ctx, InputSource::Literal(encoded.into_bytes()), "base58check", Mode::Strict).unwrap();
+        assert!(result.segments[0]
+            .note
+            .as_ref()
+            .unwrap()
+            .starts_with("version 0x00, 5 payload bytes, checksum "));
+    }
+
+    #[test]
+    fn test_explain_segments_checksum_families() {
+        let ctx = Context::default();
+        let note = |codec: &str, input: &str| {
+            let result = run_explain(&ctx, InputSource::Literal(input.as_bytes().to_vec()), codec, Mode::Strict).unwrap();
+            result.segments[0].note.clone().unwrap()
+        };
+        assert!(note("base58ripplecheck", "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh").starts_with("version 0x00 (account ID), 20 payload bytes"));
+        assert!(note("strkey", "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ")
+            .starts_with("version 0x30 'G' (ed25519 public key), 32 payload bytes, CRC16 "));
+        let cb58 = ctx.registry.get("cb58").unwrap().encode(b"avax").unwrap();
+        assert!(note("cb58", &cb58).starts_with("4 payload bytes, checksum "));
+    }
+
     #[test]
     fn test_get_context() {
         let input = "Hello World Test";
@@ -173,4 +467,15 @@ mod tests {
         assert!(ctx.contains("World"));
         assert!(ctx.contains("^"));
     }
+
+    #[test]
+    fn test_explain_extracted_offsets() {
+        let ctx = Context::default();
+        let input = InputSource::Literal(b"level=info token=aGVsbG8= done".to_vec());
+        let result = run_explain_extracted(&ctx, &input, "base64pad", Mode::Strict).unwrap();
+        assert!(result.valid);
+        let extracted = result.extracted.unwrap();
+        assert_eq!((extracted.start, extracted.end), (17, 25));
+        assert_eq!((result.segments[0].kind, result.segments[0].start), ("payload", 17));
+    }
 }
@@ -0,0 +1,97 @@
+use super::report::Extracted;
+use crate::io::read_input;
+use mbase::codec::Codec;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CaseSensitivity, Context, InputSource, Mode, PaddingRule};
+
+fn in_alphabet(codec: &dyn Codec, c: char) -> bool {
+    let meta = codec.meta();
+    if c == '=' && meta.padding == PaddingRule::Required {
+        return true;
+    }
+    match meta.case_sensitivity {
+        CaseSensitivity::Sensitive => meta.alphabet.contains(c),
+        _ => meta.alphabet.contains(c.to_ascii_lowercase()) || meta.alphabet.contains(c.to_ascii_uppercase()),
+    }
+}
+
+/// Finds the longest run of alphabet characters in `text` that decodes with `codec`.
+///
+/// Runs are tried longest first (earliest wins a tie), so a token inside a log line, JSON
+/// document or HTML attribute is preferred over the shorter words around it.
+pub fn extract_encoded(codec: &dyn Codec, text: &str, mode: Mode) -> Result<Extracted> {
+    if codec.meta().alphabet.is_empty() {
+        return Err(MbaseError::invalid_input(format!("--extract needs a fixed alphabet; {} has none", codec.name())));
+    }
+    // Padding only ends a token, so `key=value=` splits after the first `=`.
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut start = None;
+    let mut prev = '\0';
+    for (i, c) in text.char_indices().chain([(text.len(), '\0')]) {
+        let member = i < text.len() && in_alphabet(codec, c);
+        if let Some(s) = start {
+            if !member || (prev == '=' && c != '=') {
+                runs.push((s, i));
+                start = None;
+            }
+        }
+        if member && start.is_none() {
+            start = Some(i);
+        }
+        prev = c;
+    }
+    runs.sort_by_key(|&(s, e)| (core::cmp::Reverse(e - s), s));
+
+    for (s, e) in runs {
+        if codec.decode(&text[s..e], mode).is_ok() {
+            tracing::debug!(codec = codec.name(), start = s, end = e, "extracted encoded substring");
+            return Ok(Extracted {
+                start: s,
+                end: e,
+                text: text[s..e].to_string(),
+            });
+        }
+    }
+    Err(MbaseError::invalid_input(format!("no decodable {} substring found", codec.name())))
+}
+
+/// Reads `input` and narrows it to the extracted substring.
+pub fn extract_input(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode) -> Result<(InputSource, Extracted)> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let extracted = extract_encoded(ctx.registry.get(codec_name)?, &text, mode)?;
+    Ok((InputSource::Literal(extracted.text.clone().into_bytes()), extracted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(codec: &str, text: &str) -> Result<Extracted> {
+        extract_encoded(Context::default().registry.get(codec).unwrap(), text, Mode::Strict)
+    }
+
+    #[test]
+    fn test_extract_from_json() {
+        let found = extract("base64pad", r#"{"user":"alice","token":"aGVsbG8gd29ybGQ="}"#).unwrap();
+        assert_eq!(found.text, "aGVsbG8gd29ybGQ=");
+        assert_eq!((found.start, found.end), (25, 41));
+    }
+
+    #[test]
+    fn test_extract_from_log_line() {
+        let found = extract("base16upper", "2024-01-01 INFO digest=DEADBEEF00 ok").unwrap();
+        assert_eq!(found.text, "DEADBEEF00");
+
+        let found = extract("base64pad", "<img alt=\"x\" data-id=\"Zm9vYmFy\">").unwrap();
+        assert_eq!(found.text, "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_extract_skips_undecodable_runs() {
+        // "abcdefabc" is the longest base16 run but has odd length.
+        let found = extract("base16lower", "abcdefabc! cafe").unwrap();
+        assert_eq!(found.text, "cafe");
+        assert!(extract("base16lower", "xyz!").is_err());
+    }
+}
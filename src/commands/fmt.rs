@@ -1,11 +1,24 @@
 use crate::io::read_input;
-use mbase::error::Result;
-use mbase::types::{Context, InputSource, Mode};
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CaseSensitivity, CodecOptions, Context, InputSource, Mode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterCase {
+    Upper,
+    Lower,
+}
 
 pub struct FmtOptions {
     pub wrap: Option<usize>,
     pub group: Option<usize>,
     pub separator: String,
+    /// Letter case of the output; only for codecs that ignore case.
+    pub case: Option<LetterCase>,
+    /// Written once before and after the output, e.g. `0x`.
+    pub prefix: String,
+    pub suffix: String,
+    /// Written at the start of every output line, e.g. indentation for pasting into code.
+    pub line_prefix: String,
 }
 
 impl Default for FmtOptions {
@@ -14,18 +27,51 @@ impl Default for FmtOptions {
             wrap: None,
             group: None,
             separator: " ".to_string(),
+            case: None,
+            prefix: String::new(),
+            suffix: String::new(),
+            line_prefix: String::new(),
         }
     }
 }
 
 pub fn run_fmt(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode, opts: &FmtOptions) -> Result<String> {
-    let codec = ctx.registry.get(codec_name)?;
+    let data = read_input(input)?;
+    format_text(ctx, codec_name, &String::from_utf8_lossy(&data), mode, opts)
+}
 
+/// `fmt --check`: `None` when `input` already is what `fmt` would write (a final line ending
+/// aside), otherwise the formatted text.
+pub fn run_fmt_check(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode, opts: &FmtOptions) -> Result<Option<String>> {
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
+    let formatted = format_text(ctx, codec_name, &text, mode, opts)?;
+    let original = text.strip_suffix('\n').map_or(&*text, |t| t.strip_suffix('\r').unwrap_or(t));
+    Ok((original != formatted).then_some(formatted))
+}
 
-    let decoded = codec.decode(&text, mode)?;
-    let mut encoded = codec.encode(&decoded)?;
+/// Removes the line prefix, prefix and suffix `opts` would add, so formatted output reads back.
+fn strip_decoration(text: &str, opts: &FmtOptions) -> String {
+    if opts.prefix.is_empty() && opts.suffix.is_empty() && opts.line_prefix.is_empty() {
+        return text.to_string();
+    }
+    let text = text.trim_end_matches(['\r', '\n']);
+    let unindented = match opts.line_prefix.as_str() {
+        "" => text.to_string(),
+        line_prefix => text
+            .split('\n')
+            .map(|line| line.strip_prefix(line_prefix).unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    let body = unindented.strip_prefix(opts.prefix.as_str()).unwrap_or(&unindented);
+    body.strip_suffix(opts.suffix.as_str()).unwrap_or(body).to_string()
+}
+
+fn format_text(ctx: &Context, codec_name: &str, text: &str, mode: Mode, opts: &FmtOptions) -> Result<String> {
+    let codec = ctx.registry.get(codec_name)?;
+    let decoded = ctx.decode(codec, &strip_decoration(text, opts), mode, &CodecOptions::new())?;
+    let mut encoded = ctx.encode(codec, &decoded, &CodecOptions::new())?;
 
     if let Some(group_size) = opts.group {
         encoded = insert_separators(&encoded, group_size, &opts.separator);
@@ -35,6 +81,25 @@ pub fn run_fmt(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode,
         encoded = wrap_lines(&encoded, width);
     }
 
+    if let Some(case) = opts.case {
+        if codec.meta().case_sensitivity == CaseSensitivity::Sensitive {
+            return Err(MbaseError::invalid_input(format!("{} is case-sensitive; changing the case would change the data", codec.name())));
+        }
+        encoded = match case {
+            LetterCase::Upper => encoded.to_uppercase(),
+            LetterCase::Lower => encoded.to_lowercase(),
+        };
+    }
+
+    encoded = format!("{}{}{}", opts.prefix, encoded, opts.suffix);
+    if !opts.line_prefix.is_empty() {
+        encoded = encoded
+            .split('\n')
+            .map(|line| format!("{}{}", opts.line_prefix, line))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
     Ok(encoded)
 }
 
@@ -50,7 +115,7 @@ fn insert_separators(s: &str, group_size: usize, separator: &str) -> String {
         .join(separator)
 }
 
-fn wrap_lines(s: &str, width: usize) -> String {
+pub(super) fn wrap_lines(s: &str, width: usize) -> String {
     if width == 0 {
         return s.to_string();
     }
@@ -72,6 +137,55 @@ mod tests {
         assert_eq!(insert_separators("ABCDEFGHI", 4, "-"), "ABCD-EFGH-I");
     }
 
+    #[test]
+    fn test_fmt_check() {
+        let ctx = Context::default();
+        let check = |text: &str, wrap| {
+            let opts = FmtOptions {
+                wrap,
+                ..Default::default()
+            };
+            run_fmt_check(&ctx, "base64pad", &InputSource::Literal(text.as_bytes().to_vec()), Mode::Lenient, &opts)
+        };
+        assert_eq!(check("SGVsbG8=\n", None).unwrap(), None);
+        assert_eq!(check("SGVs\nbG8=\r\n", Some(4)).unwrap(), None);
+        assert_eq!(check("SGVs bG8=", None).unwrap().as_deref(), Some("SGVsbG8="));
+        assert_eq!(check("SGVsbG8=", Some(4)).unwrap().as_deref(), Some("SGVs\nbG8="));
+        assert!(check("SGVsbG8=\n\n", None).unwrap().is_some());
+        assert_eq!(check("SGVsbG8", None).unwrap().as_deref(), Some("SGVsbG8="));
+        assert!(check("SGV$", None).is_err());
+    }
+
+    #[test]
+    fn test_fmt_presentation() {
+        let ctx = Context::default();
+        let fmt = |codec, text: &str, opts: &FmtOptions| {
+            run_fmt(&ctx, codec, &InputSource::Literal(text.as_bytes().to_vec()), Mode::Lenient, opts)
+        };
+        let opts = FmtOptions {
+            group: Some(4),
+            case: Some(LetterCase::Upper),
+            prefix: "0x".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(fmt("hex", "deadbeef", &opts).unwrap(), "0xDEAD BEEF");
+        let opts = FmtOptions {
+            wrap: Some(4),
+            prefix: "\"".to_string(),
+            suffix: "\",".to_string(),
+            line_prefix: "    ".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(fmt("base64pad", "SGVsbG8=", &opts).unwrap(), "    \"SGVs\n    bG8=\",");
+        // Output that is already formatted reads back.
+        assert_eq!(fmt("base64pad", "    \"SGVs\n    bG8=\",\n", &opts).unwrap(), "    \"SGVs\n    bG8=\",");
+        let upper = FmtOptions {
+            case: Some(LetterCase::Upper),
+            ..Default::default()
+        };
+        assert!(fmt("base64pad", "SGVsbG8=", &upper).is_err());
+    }
+
     #[test]
     fn test_wrap_lines() {
         assert_eq!(wrap_lines("ABCDEFGH", 4), "ABCD\nEFGH");
@@ -0,0 +1,179 @@
+use super::report::HashGuess;
+
+impl HashGuess {
+    fn new(algorithms: &[&'static str], reason: impl Into<String>, caveat: Option<&'static str>) -> Self {
+        Self {
+            algorithms: algorithms.to_vec(),
+            reason: reason.into(),
+            caveat,
+        }
+    }
+}
+
+const LENGTH_ONLY: &str = "matched on length and charset only; any random value of this size fits";
+
+/// Modular crypt and LDAP-style prefixes, matched before the bare digest shapes.
+const PREFIXED: &[(&str, &[&str], &str)] = &[
+    ("$2a$", &["bcrypt"], "bcrypt ($2a$)"),
+    ("$2b$", &["bcrypt"], "bcrypt ($2b$)"),
+    ("$2y$", &["bcrypt"], "bcrypt ($2y$, PHP)"),
+    ("$2x$", &["bcrypt"], "bcrypt ($2x$, legacy crypt_blowfish)"),
+    ("$argon2id$", &["Argon2id"], "PHC string"),
+    ("$argon2i$", &["Argon2i"], "PHC string"),
+    ("$argon2d$", &["Argon2d"], "PHC string"),
+    ("$scrypt$", &["scrypt"], "PHC string"),
+    ("$7$", &["scrypt"], "crypt $7$"),
+    ("$y$", &["yescrypt"], "crypt $y$"),
+    ("$gy$", &["gost-yescrypt"], "crypt $gy$"),
+    ("$6$", &["SHA-512 crypt"], "crypt $6$"),
+    ("$5$", &["SHA-256 crypt"], "crypt $5$"),
+    ("$1$", &["MD5 crypt"], "crypt $1$"),
+    ("$apr1$", &["Apache MD5 (apr1)"], "htpasswd $apr1$"),
+    ("$pbkdf2-sha256$", &["PBKDF2-HMAC-SHA256"], "PHC/passlib string"),
+    ("$pbkdf2-sha512$", &["PBKDF2-HMAC-SHA512"], "PHC/passlib string"),
+    ("$pbkdf2$", &["PBKDF2-HMAC-SHA1"], "passlib string"),
+    ("pbkdf2_sha256$", &["PBKDF2-HMAC-SHA256"], "Django password field"),
+    ("{SSHA}", &["salted SHA-1"], "LDAP {SSHA}"),
+    ("{SHA}", &["SHA-1"], "LDAP {SHA}"),
+    ("{SSHA512}", &["salted SHA-512"], "LDAP {SSHA512}"),
+    ("{CRYPT}", &["crypt(3)"], "LDAP {CRYPT}"),
+];
+
+fn hex_algorithms(len: usize) -> Option<&'static [&'static str]> {
+    Some(match len {
+        8 => &["CRC-32", "Adler-32", "FNV-1a 32"],
+        16 => &["MySQL 3.x", "CRC-64", "half MD5"],
+        32 => &["MD5", "NTLM", "MD4", "LM"],
+        40 => &["SHA-1", "RIPEMD-160"],
+        56 => &["SHA-224", "SHA3-224"],
+        64 => &["SHA-256", "SHA3-256", "BLAKE2s-256", "Keccak-256"],
+        96 => &["SHA-384", "SHA3-384"],
+        128 => &["SHA-512", "SHA3-512", "BLAKE2b-512", "Whirlpool"],
+        _ => return None,
+    })
+}
+
+fn digest_algorithms(bytes: usize) -> Option<&'static [&'static str]> {
+    Some(match bytes {
+        16 => &["MD5"],
+        20 => &["SHA-1"],
+        32 => &["SHA-256"],
+        48 => &["SHA-384"],
+        64 => &["SHA-512"],
+        _ => return None,
+    })
+}
+
+fn is_base64_digest(text: &str) -> Option<usize> {
+    let body = text.trim_end_matches('=');
+    if text.len() - body.len() > 2 || !body.bytes().all(|b| b.is_ascii_alphanumeric() || b"+/-_".contains(&b)) {
+        return None;
+    }
+    // Padding, when present, must complete the last quantum; unpadded (base64url) digests are common too.
+    let bytes = body.len() * 6 / 8;
+    let exact = (bytes * 4).div_ceil(3) == body.len();
+    (exact && (text.len() == body.len() || text.len().is_multiple_of(4))).then_some(bytes)
+}
+
+/// Lists digest formats `input` could be, from known prefixes, hex lengths and base64 digest sizes.
+pub fn identify_hash(input: &str) -> Vec<HashGuess> {
+    let text = input.trim();
+    let mut guesses = Vec::new();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return guesses;
+    }
+
+    if let Some((prefix, algorithms, format)) = PREFIXED.iter().find(|(p, _, _)| text.starts_with(p)) {
+        let caveat = (prefix.starts_with("$2") && text.len() != 60).then_some("bcrypt hashes are normally 60 characters");
+        guesses.push(HashGuess::new(algorithms, *format, caveat));
+        return guesses;
+    }
+
+    // Labelled digests: `sha256:<hex>` (OCI/Docker) and `sha384-<base64>` (Subresource Integrity).
+    if let Some((label, value)) = text.split_once([':', '-']) {
+        let label = label.to_ascii_lowercase();
+        if matches!(label.as_str(), "md5" | "sha1" | "sha256" | "sha384" | "sha512") {
+            let form = if text.as_bytes()[label.len()] == b':' {
+                "labelled hex digest"
+            } else {
+                "SRI-style base64 digest"
+            };
+            let fits = value.len() % 2 == 0 && value.bytes().all(|b| b.is_ascii_hexdigit()) || is_base64_digest(value).is_some();
+            if fits {
+                let algorithm = match label.as_str() {
+                    "md5" => "MD5",
+                    "sha1" => "SHA-1",
+                    "sha256" => "SHA-256",
+                    "sha384" => "SHA-384",
+                    _ => "SHA-512",
+                };
+                guesses.push(HashGuess::new(&[algorithm], format!("{} ({})", form, label), None));
+                return guesses;
+            }
+        }
+    }
+
+    if let Some(rest) = text.strip_prefix('*') {
+        if rest.len() == 40 && rest.bytes().all(|b| b.is_ascii_hexdigit()) {
+            guesses.push(HashGuess::new(&["MySQL 4.1+ (double SHA-1)"], "'*' followed by 40 hex digits", None));
+            return guesses;
+        }
+    }
+
+    if text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        if let Some(algorithms) = hex_algorithms(text.len()) {
+            let caveat = if text.bytes().any(|b| b.is_ascii_lowercase()) && text.bytes().any(|b| b.is_ascii_uppercase()) {
+                "mixed-case hex is unusual for a digest"
+            } else {
+                LENGTH_ONLY
+            };
+            guesses.push(HashGuess::new(algorithms, format!("{} hex digits ({} bytes)", text.len(), text.len() / 2), Some(caveat)));
+        }
+        return guesses;
+    }
+
+    if let Some(bytes) = is_base64_digest(text) {
+        if let Some(algorithms) = digest_algorithms(bytes) {
+            guesses.push(HashGuess::new(algorithms, format!("base64 of {} bytes", bytes), Some(LENGTH_ONLY)));
+        }
+    }
+    guesses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(input: &str) -> Vec<&'static str> {
+        identify_hash(input).into_iter().next().map(|g| g.algorithms).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_identify_hex_digests() {
+        assert_eq!(first("d41d8cd98f00b204e9800998ecf8427e")[0], "MD5");
+        assert_eq!(first("da39a3ee5e6b4b0d3255bfef95601890afd80709")[0], "SHA-1");
+        assert_eq!(first("E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855")[0], "SHA-256");
+        assert!(identify_hash("d41d8cd98f00b204e9800998ecf8427").is_empty());
+        assert!(identify_hash("hello world").is_empty());
+    }
+
+    #[test]
+    fn test_identify_prefixed_formats() {
+        let bcrypt = identify_hash("$2b$12$R9h/cIPz0gi.URNNX3kh2OPST9/PgBkqquzi.Ss7KIUgO2t0jWMUW");
+        assert_eq!(bcrypt[0].algorithms, vec!["bcrypt"]);
+        assert!(bcrypt[0].caveat.is_none());
+        assert_eq!(first("$6$salt$abc")[0], "SHA-512 crypt");
+        assert_eq!(first("$argon2id$v=19$m=65536,t=3,p=4$c2FsdA$aGFzaA")[0], "Argon2id");
+        assert_eq!(first("*2470C0C06DEE42FD1618BB99005ADCA2EC9D1E19")[0], "MySQL 4.1+ (double SHA-1)");
+        assert_eq!(first("sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")[0], "SHA-256");
+        assert_eq!(first("sha384-OLBgp1GsljhM2TJ+sbHjaiH9txEUvgdDTAzHv2P24donTt6/529l+9Ua0vFImLlb")[0], "SHA-384");
+    }
+
+    #[test]
+    fn test_identify_base64_digests() {
+        assert_eq!(first("47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=")[0], "SHA-256");
+        assert_eq!(first("1B2M2Y8AsgTpgAmY7PhCfg==")[0], "MD5");
+        assert_eq!(first("47DEQpj8HBSa-_TImW-5JCeuQeRkm5NMpJWZG3hSuFU")[0], "SHA-256");
+        assert!(identify_hash("aGVsbG8=").is_empty());
+    }
+}
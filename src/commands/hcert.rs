@@ -0,0 +1,275 @@
+use ciborium::value::Value;
+use serde_json::{Map, Number, Value as Json};
+
+use super::report::HcertResult;
+use crate::io::read_input;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{Context, InputSource, Mode};
+
+const COSE_SIGN1_TAG: u64 = 18;
+const CWT_TAG: u64 = 61;
+const CLAIM_ISS: i128 = 1;
+const CLAIM_EXP: i128 = 4;
+const CLAIM_IAT: i128 = 6;
+const CLAIM_HCERT: i128 = -260;
+const HEADER_ALG: i128 = 1;
+const HEADER_KID: i128 = 4;
+
+/// Splits off a context identifier such as `HC1:` (EU DCC) or `LT1:`.
+fn split_prefix(text: &str) -> (Option<&str>, &str) {
+    match text.split_once(':') {
+        Some((prefix, rest)) if prefix.len() == 3 && prefix.bytes().all(|b| b.is_ascii_alphanumeric()) => (Some(prefix), rest),
+        _ => (None, text),
+    }
+}
+
+fn cose_alg_name(alg: i128) -> String {
+    match alg {
+        -7 => "ES256".to_string(),
+        -35 => "ES384".to_string(),
+        -36 => "ES512".to_string(),
+        -37 => "PS256".to_string(),
+        -8 => "EdDSA".to_string(),
+        other => format!("COSE alg {}", other),
+    }
+}
+
+fn as_int(v: &Value) -> Option<i128> {
+    match v {
+        Value::Integer(i) => Some(i128::from(*i)),
+        _ => None,
+    }
+}
+
+fn map_get(map: &[(Value, Value)], key: i128) -> Option<&Value> {
+    map.iter().find(|(k, _)| as_int(k) == Some(key)).map(|(_, v)| v)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Converts CBOR to JSON for display: byte strings become hex, non-text map keys are stringified and tags are dropped.
+pub fn cbor_to_json(value: &Value) -> Json {
+    match value {
+        Value::Integer(i) => {
+            let n = i128::from(*i);
+            i64::try_from(n).map(Json::from).unwrap_or_else(|_| Json::String(n.to_string()))
+        }
+        Value::Bytes(b) => Json::String(hex(b)),
+        Value::Float(f) => Number::from_f64(*f).map(Json::Number).unwrap_or(Json::Null),
+        Value::Text(s) => Json::String(s.clone()),
+        Value::Bool(b) => Json::Bool(*b),
+        Value::Null => Json::Null,
+        Value::Tag(_, inner) => cbor_to_json(inner),
+        Value::Array(items) => Json::Array(items.iter().map(cbor_to_json).collect()),
+        Value::Map(entries) => {
+            let mut map = Map::new();
+            for (k, v) in entries {
+                let key = match k {
+                    Value::Text(s) => s.clone(),
+                    other => match cbor_to_json(other) {
+                        Json::String(s) => s,
+                        json => json.to_string(),
+                    },
+                };
+                map.insert(key, cbor_to_json(v));
+            }
+            Json::Object(map)
+        }
+        _ => Json::Null,
+    }
+}
+
+/// Formats seconds since the Unix epoch as an ISO 8601 UTC timestamp.
+pub fn format_epoch(secs: i64) -> String {
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+    // Civil-from-days (H. Hinnant), valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+fn inflate(ctx: &Context, data: &[u8]) -> Result<Vec<u8>> {
+    let limit = ctx.max_output_size.unwrap_or(usize::MAX);
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, limit).map_err(|e| match e.status {
+        // The inflater stops at the limit, so the true size is only known to be larger.
+        miniz_oxide::inflate::TINFLStatus::HasMoreOutput => MbaseError::OutputTooLarge { size: limit + 1, limit },
+        _ => MbaseError::invalid_input(format!("zlib inflate failed: {:?}", e.status)),
+    })
+}
+
+fn parse_cbor(data: &[u8], what: &str) -> Result<Value> {
+    ciborium::de::from_reader(data).map_err(|e| MbaseError::invalid_input(format!("invalid CBOR in {}: {}", what, e)))
+}
+
+pub fn run_hcert(ctx: &Context, input: &InputSource) -> Result<HcertResult> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    // Space is part of the base45 alphabet, so only line endings are trimmed.
+    let (prefix, body) = split_prefix(text.trim_matches(['\r', '\n']));
+    let mut warnings = Vec::new();
+
+    let decoded = ctx.decode(ctx.registry.get("base45")?, body, Mode::Strict, &Default::default())?;
+    let compressed = decoded.first() == Some(&0x78);
+    let cbor = if compressed { inflate(ctx, &decoded)? } else { decoded };
+
+    let mut envelope = parse_cbor(&cbor, "COSE envelope")?;
+    while let Value::Tag(tag, inner) = envelope {
+        if tag != COSE_SIGN1_TAG && tag != CWT_TAG {
+            warnings.push(format!("unexpected CBOR tag {}", tag));
+        }
+        envelope = *inner;
+    }
+    let parts = match envelope {
+        Value::Array(parts) if parts.len() == 4 => parts,
+        _ => return Err(MbaseError::invalid_input("expected COSE_Sign1 array of 4 elements")),
+    };
+    let (protected, unprotected, payload, signature) = match &parts[..] {
+        [Value::Bytes(p), u, Value::Bytes(pl), Value::Bytes(s)] => (p, u, pl, s),
+        _ => return Err(MbaseError::invalid_input("malformed COSE_Sign1 structure")),
+    };
+
+    let protected_map = if protected.is_empty() {
+        Value::Map(Vec::new())
+    } else {
+        parse_cbor(protected, "protected header")?
+    };
+    let header = |key| {
+        let from = |v: &Value| match v {
+            Value::Map(m) => map_get(m, key).cloned(),
+            _ => None,
+        };
+        from(&protected_map).or_else(|| from(unprotected))
+    };
+    let algorithm = header(HEADER_ALG).as_ref().and_then(as_int).map(cose_alg_name);
+    let kid = match header(HEADER_KID) {
+        Some(Value::Bytes(k)) => Some(ctx.registry.get("base64pad")?.encode(&k)?),
+        _ => None,
+    };
+
+    let claims = parse_cbor(payload, "CWT payload")?;
+    let claims = match &claims {
+        Value::Map(m) => m,
+        _ => return Err(MbaseError::invalid_input("CWT payload is not a map")),
+    };
+    let time_claim = |key| {
+        map_get(claims, key)
+            .and_then(as_int)
+            .and_then(|t| i64::try_from(t).ok())
+            .map(format_epoch)
+    };
+    let issuer = map_get(claims, CLAIM_ISS).and_then(|v| v.as_text()).map(String::from);
+    let issued_at = time_claim(CLAIM_IAT);
+    let expires_at = time_claim(CLAIM_EXP);
+    if map_get(claims, CLAIM_HCERT).is_none() {
+        warnings.push("no hcert claim (-260) in payload".to_string());
+    }
+    warnings.push("signature not verified (no trust list)".to_string());
+
+    Ok(HcertResult {
+        prefix: prefix.map(String::from),
+        base45_length: body.len(),
+        compressed,
+        cbor_length: cbor.len(),
+        algorithm,
+        kid,
+        signature_length: signature.len(),
+        issuer,
+        issued_at,
+        expires_at,
+        payload: cbor_to_json(&Value::Map(claims.clone())),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(n: i64) -> Value {
+        Value::Integer(n.into())
+    }
+
+    fn sample_hcert(compress: bool) -> String {
+        let mut protected = Vec::new();
+        ciborium::ser::into_writer(
+            &Value::Map(vec![(int(1), int(-7)), (int(4), Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef]))]),
+            &mut protected,
+        )
+        .unwrap();
+        let dcc = Value::Map(vec![
+            (Value::Text("ver".into()), Value::Text("1.3.0".into())),
+            (Value::Text("nam".into()), Value::Map(vec![(Value::Text("fnt".into()), Value::Text("MUSTERMANN".into()))])),
+        ]);
+        let claims = Value::Map(vec![
+            (int(1), Value::Text("AT".into())),
+            (int(6), int(1_620_000_000)),
+            (int(4), int(1_650_000_000)),
+            (int(-260), Value::Map(vec![(int(1), dcc)])),
+        ]);
+        let mut payload = Vec::new();
+        ciborium::ser::into_writer(&claims, &mut payload).unwrap();
+        let cose = Value::Tag(
+            COSE_SIGN1_TAG,
+            Box::new(Value::Array(vec![
+                Value::Bytes(protected),
+                Value::Map(Vec::new()),
+                Value::Bytes(payload),
+                Value::Bytes(vec![0u8; 64]),
+            ])),
+        );
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&cose, &mut cbor).unwrap();
+        let bytes = if compress {
+            miniz_oxide::deflate::compress_to_vec_zlib(&cbor, 9)
+        } else {
+            cbor
+        };
+        format!("HC1:{}", Context::default().registry.get("base45").unwrap().encode(&bytes).unwrap())
+    }
+
+    #[test]
+    fn test_hcert_inspect() {
+        let ctx = Context::default();
+        let result = run_hcert(&ctx, &InputSource::Literal(sample_hcert(true).into_bytes())).unwrap();
+        assert_eq!(result.prefix.as_deref(), Some("HC1"));
+        assert!(result.compressed);
+        assert_eq!(result.algorithm.as_deref(), Some("ES256"));
+        assert_eq!(result.kid.as_deref(), Some("3q2+7w=="));
+        assert_eq!(result.signature_length, 64);
+        assert_eq!(result.issuer.as_deref(), Some("AT"));
+        assert_eq!(result.issued_at.as_deref(), Some("2021-05-03T00:00:00Z"));
+        assert_eq!(result.expires_at.as_deref(), Some("2022-04-15T05:20:00Z"));
+        assert_eq!(result.payload["-260"]["1"]["nam"]["fnt"], "MUSTERMANN");
+    }
+
+    #[test]
+    fn test_hcert_uncompressed() {
+        let result = run_hcert(&Context::default(), &InputSource::Literal(sample_hcert(false).into_bytes())).unwrap();
+        assert!(!result.compressed);
+        assert_eq!(result.payload["1"], "AT");
+    }
+
+    #[test]
+    fn test_hcert_inflate_respects_output_limit() {
+        let ctx = Context::default().with_max_output_size(Some(16));
+        let err = run_hcert(&ctx, &InputSource::Literal(sample_hcert(true).into_bytes())).unwrap_err();
+        assert!(matches!(err, MbaseError::OutputTooLarge { limit: 16, .. }));
+    }
+
+    #[test]
+    fn test_format_epoch() {
+        assert_eq!(format_epoch(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_epoch(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_epoch(-1), "1969-12-31T23:59:59Z");
+    }
+}
@@ -1,7 +1,73 @@
+use super::report::{CodecExample, InfoResult};
+use mbase::codec::Codec;
 use mbase::error::Result;
-use mbase::types::{CodecMeta, Context};
+use mbase::types::{CodecMetadata, CodecOptions, Context, Mode};
 
-pub fn run_info(ctx: &Context, codec_name: &str) -> Result<CodecMeta> {
-    let codec = ctx.registry.get(codec_name)?;
-    Ok(codec.meta())
+/// Inputs tried for `info --examples`, text first. The byte strings suit codecs that take a fixed
+/// size, such as MAC and IPv6 addresses or BIP39 entropy.
+const SAMPLES: [&[u8]; 5] = [
+    b"hello",
+    b"Hello, World!",
+    &[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e],
+    &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01],
+    &[0x5a; 32],
+];
+
+const MAX_EXAMPLES: usize = 2;
+
+/// `sample` as an `--in` value: the text itself when it is printable ASCII, else `hex:`.
+fn input_arg(sample: &[u8]) -> String {
+    if sample.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        String::from_utf8_lossy(sample).into_owned()
+    } else {
+        format!("hex:{}", sample.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+    }
+}
+
+/// Encode/decode pairs for the first samples the codec accepts, computed rather than stored so
+/// they always match the codec.
+pub fn codec_examples(ctx: &Context, codec: &dyn Codec) -> Vec<CodecExample> {
+    let opts = CodecOptions::default();
+    SAMPLES
+        .iter()
+        .filter_map(|sample| {
+            let encoded = ctx.encode(codec, sample, &opts).ok()?;
+            let roundtrip = ctx.decode(codec, &encoded, Mode::Strict, &opts).is_ok_and(|d| d == *sample);
+            Some(CodecExample {
+                input: input_arg(sample),
+                encoded,
+                roundtrip,
+            })
+        })
+        .take(MAX_EXAMPLES)
+        .collect()
+}
+
+pub fn run_info(ctx: &Context, codec_name: &str, examples: bool) -> Result<InfoResult> {
+    let meta: CodecMetadata = ctx.registry.metadata_of(codec_name)?;
+    let examples = if examples {
+        codec_examples(ctx, ctx.registry.get(codec_name)?)
+    } else {
+        Vec::new()
+    };
+    Ok(InfoResult { meta, examples })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_info_examples_and_spec() {
+        let ctx = Context::default();
+        let info = run_info(&ctx, "b64", true).unwrap();
+        assert_eq!(info.meta.spec.as_deref(), Some("RFC 4648 §4"));
+        assert_eq!((info.examples[0].input.as_str(), info.examples[0].encoded.as_str()), ("hello", "aGVsbG8"));
+        assert!(info.examples.iter().all(|e| e.roundtrip));
+        assert_eq!(info.examples.len(), MAX_EXAMPLES);
+
+        let mac = run_info(&ctx, "mac", true).unwrap();
+        assert_eq!(mac.examples[0].input, "hex:001a2b3c4d5e");
+        assert!(run_info(&ctx, "base64", false).unwrap().examples.is_empty());
+    }
 }
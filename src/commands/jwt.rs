@@ -0,0 +1,69 @@
+use serde_json::Value as Json;
+
+use super::report::{JwtResult, JwtSegment};
+use crate::io::read_input;
+use mbase::codec::decode_jwt;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{InputSource, Mode};
+
+/// Segment names of a compact JWS (RFC 7515 §7.1) or JWE (RFC 7516 §7.1), and how many of the
+/// leading segments are JSON.
+fn layout(segments: usize) -> Option<(&'static str, &'static [&'static str], usize)> {
+    match segments {
+        3 => Some(("jws", &["header", "payload", "signature"], 2)),
+        5 => Some(("jwe", &["header", "encrypted_key", "iv", "ciphertext", "tag"], 1)),
+        _ => None,
+    }
+}
+
+/// `jwt`: decodes each segment of a compact token, parsing the header (and a JWS payload) as JSON.
+pub fn run_jwt(input: &InputSource, mode: Mode) -> Result<JwtResult> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let decoded = decode_jwt(text.trim(), mode)?;
+    let (kind, roles, json_segments) = layout(decoded.len())
+        .ok_or_else(|| MbaseError::invalid_input(format!("a compact token has 3 segments (JWS) or 5 (JWE), found {}", decoded.len())))?;
+    let segments = decoded
+        .into_iter()
+        .zip(roles)
+        .enumerate()
+        .map(|(index, (bytes, &role))| {
+            let json = (index < json_segments)
+                .then(|| serde_json::from_slice::<Json>(&bytes).ok())
+                .flatten();
+            JwtSegment {
+                role,
+                length: bytes.len(),
+                hex: json.is_none().then(|| bytes.iter().map(|b| format!("{:02x}", b)).collect()),
+                json,
+            }
+        })
+        .collect();
+    Ok(JwtResult { kind, segments })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(token: &str) -> Result<JwtResult> {
+        run_jwt(&InputSource::Literal(token.as_bytes().to_vec()), Mode::Strict)
+    }
+
+    #[test]
+    fn test_jwt_segments() {
+        // A `.` byte inside the signature does not disturb the split.
+        let result = jwt("eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.Li4").unwrap();
+        assert_eq!(result.kind, "jws");
+        assert_eq!(result.segments[0].json.as_ref().unwrap()["alg"], "HS256");
+        assert_eq!(result.segments[1].json.as_ref().unwrap()["sub"], "1");
+        assert_eq!((result.segments[2].role, result.segments[2].hex.as_deref()), ("signature", Some("2e2e")));
+
+        let result = jwt("eyJhbGciOiJSU0EtT0FFUCJ9.AQ.AQ.AQ.AQ").unwrap();
+        assert_eq!(result.kind, "jwe");
+        assert_eq!(result.segments[3].role, "ciphertext");
+
+        assert!(jwt("eyJhbGciOiJIUzI1NiJ9.e30").unwrap_err().to_string().contains("found 2"));
+        assert!(jwt("eyJ9.c2lnbg==.AQ").unwrap_err().to_string().contains("segment 2"));
+    }
+}
@@ -0,0 +1,299 @@
+use sha2::{Digest, Sha256};
+
+use super::report::{KeyField, KeyInfo, KeyResult};
+use crate::io::read_input;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{Context, InputSource, Mode};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Significant bits of an unsigned big-endian integer.
+fn bit_length(bytes: &[u8]) -> usize {
+    match bytes.iter().position(|&b| b != 0) {
+        Some(i) => (bytes.len() - i) * 8 - bytes[i].leading_zeros() as usize,
+        None => 0,
+    }
+}
+
+/// Reader for the RFC 4251 `string`/`mpint` encoding: a big-endian u32 length, then the bytes.
+struct Blob<'a> {
+    data: &'a [u8],
+    pos: usize,
+    fields: Vec<KeyField>,
+}
+
+impl<'a> Blob<'a> {
+    fn read(&mut self, name: &'static str) -> Result<&'a [u8]> {
+        let rest = &self.data[self.pos..];
+        if rest.len() < 4 {
+            return Err(MbaseError::invalid_input(format!("key blob truncated before field '{}' at byte {}", name, self.pos)));
+        }
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if rest.len() - 4 < len {
+            return Err(MbaseError::invalid_input(format!(
+                "field '{}' at byte {} claims {} bytes, only {} left",
+                name,
+                self.pos,
+                len,
+                rest.len() - 4
+            )));
+        }
+        let value = &rest[4..4 + len];
+        self.pos += 4 + len;
+        let shown = match core::str::from_utf8(value) {
+            Ok(text) if !text.is_empty() && text.bytes().all(|b| b.is_ascii_graphic()) => text.to_string(),
+            _ if value.len() > 32 => format!("{}...", hex(&value[..32])),
+            _ => hex(value),
+        };
+        self.fields.push(KeyField {
+            name,
+            length: len,
+            value: shown,
+        });
+        Ok(value)
+    }
+
+    fn read_str(&mut self, name: &'static str) -> Result<&'a str> {
+        let value = self.read(name)?;
+        core::str::from_utf8(value).map_err(|_| MbaseError::invalid_input(format!("field '{}' is not UTF-8", name)))
+    }
+}
+
+fn curve_bits(curve: &str) -> Option<usize> {
+    match curve {
+        "nistp256" => Some(256),
+        "nistp384" => Some(384),
+        "nistp521" => Some(521),
+        _ => None,
+    }
+}
+
+fn is_ssh_key_type(token: &str) -> bool {
+    token.starts_with("ssh-") || token.starts_with("ecdsa-sha2-") || token.starts_with("sk-")
+}
+
+/// Parses one `authorized_keys`-style line: `[options] type base64-blob [comment]`.
+fn inspect_openssh(ctx: &Context, line: &str) -> Result<KeyInfo> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let idx = tokens
+        .iter()
+        .position(|t| is_ssh_key_type(t))
+        .ok_or_else(|| MbaseError::invalid_input("no OpenSSH key type found"))?;
+    let key_type = tokens[idx];
+    let encoded = tokens
+        .get(idx + 1)
+        .ok_or_else(|| MbaseError::invalid_input(format!("missing base64 blob after '{}'", key_type)))?;
+    let comment = (tokens.len() > idx + 2).then(|| tokens[idx + 2..].join(" "));
+
+    let data = ctx.registry.get("base64pad")?.decode(encoded, Mode::Strict)?;
+    let mut blob = Blob {
+        data: &data,
+        pos: 0,
+        fields: Vec::new(),
+    };
+    let mut warnings = Vec::new();
+
+    let inner_type = blob.read_str("key-type")?;
+    if inner_type != key_type {
+        warnings.push(format!("prefix says '{}' but the blob encodes '{}'", key_type, inner_type));
+    }
+
+    let (algorithm, bits) = match inner_type {
+        "ssh-rsa" => {
+            blob.read("e")?;
+            ("RSA", Some(bit_length(blob.read("n")?)))
+        }
+        "ssh-dss" => {
+            let p = blob.read("p")?;
+            blob.read("q")?;
+            blob.read("g")?;
+            blob.read("y")?;
+            ("DSA", Some(bit_length(p)))
+        }
+        "ssh-ed25519" | "sk-ssh-ed25519@openssh.com" => {
+            let key = blob.read("public-key")?;
+            if key.len() != 32 {
+                warnings.push(format!("ed25519 key is {} bytes, expected 32", key.len()));
+            }
+            if inner_type.starts_with("sk-") {
+                blob.read_str("application")?;
+                ("Ed25519-SK", Some(256))
+            } else {
+                ("Ed25519", Some(256))
+            }
+        }
+        t if t.starts_with("ecdsa-sha2-") || t == "sk-ecdsa-sha2-nistp256@openssh.com" => {
+            let curve = blob.read_str("curve")?;
+            blob.read("public-point")?;
+            if !t.contains(curve) {
+                warnings.push(format!("curve '{}' does not match key type '{}'", curve, t));
+            }
+            if t.starts_with("sk-") {
+                blob.read_str("application")?;
+                ("ECDSA-SK", curve_bits(curve))
+            } else {
+                ("ECDSA", curve_bits(curve))
+            }
+        }
+        other => {
+            warnings.push(format!("unknown key type '{}'; fields after the type are not parsed", other));
+            ("unknown", None)
+        }
+    };
+    if blob.pos < data.len() && algorithm != "unknown" {
+        warnings.push(format!("{} trailing bytes after the key fields", data.len() - blob.pos));
+    }
+
+    let digest = ctx.registry.get("base64")?.encode(&Sha256::digest(&data))?;
+    Ok(KeyInfo {
+        format: "openssh",
+        key_type: inner_type.to_string(),
+        algorithm: algorithm.to_string(),
+        bits,
+        fingerprint: Some(format!("SHA256:{}", digest)),
+        comment,
+        fields: blob.fields,
+        warnings,
+    })
+}
+
+/// Parses an age recipient (`age1...`), plugin recipient (`age1name1...`) or identity (`AGE-SECRET-KEY-1...`).
+fn inspect_age(ctx: &Context, token: &str) -> Result<KeyInfo> {
+    let (hrp, data) = bech32::decode(token).map_err(|e| MbaseError::invalid_input(format!("invalid age bech32: {}", e)))?;
+    let hrp = hrp.to_lowercase();
+    let mut warnings = Vec::new();
+
+    let (key_type, algorithm, name) = if hrp == "age" {
+        ("recipient".to_string(), "X25519".to_string(), "public-key")
+    } else if hrp == "age-secret-key-" {
+        warnings.push("this is a secret key; do not share it".to_string());
+        ("identity".to_string(), "X25519".to_string(), "secret-key")
+    } else if let Some(plugin) = hrp.strip_prefix("age1") {
+        (format!("plugin recipient ({})", plugin), "plugin-defined".to_string(), "plugin-data")
+    } else if let Some(plugin) = hrp.strip_prefix("age-plugin-") {
+        let plugin = plugin.trim_end_matches('-');
+        warnings.push("this is a plugin identity; it may contain secret material".to_string());
+        (format!("plugin identity ({})", plugin), "plugin-defined".to_string(), "plugin-data")
+    } else {
+        return Err(MbaseError::invalid_input(format!("unknown age HRP '{}'", hrp)));
+    };
+
+    if algorithm == "X25519" && data.len() != 32 {
+        warnings.push(format!("X25519 key is {} bytes, expected 32", data.len()));
+    }
+    let bits = (algorithm == "X25519").then_some(256);
+    // age defines no fingerprint format; hash the public key bytes the way OpenSSH does.
+    let fingerprint = match key_type.as_str() {
+        "recipient" => Some(format!("SHA256:{}", ctx.registry.get("base64")?.encode(&Sha256::digest(&data))?)),
+        _ => None,
+    };
+
+    Ok(KeyInfo {
+        format: "age",
+        key_type,
+        algorithm,
+        bits,
+        fingerprint,
+        comment: None,
+        fields: vec![KeyField {
+            name,
+            length: data.len(),
+            value: if name == "secret-key" {
+                "(redacted)".to_string()
+            } else {
+                hex(&data)
+            },
+        }],
+        warnings,
+    })
+}
+
+pub fn run_key(ctx: &Context, input: &InputSource) -> Result<KeyResult> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let mut keys = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let first = line.split_whitespace().next().unwrap_or_default();
+        let lower = first.to_ascii_lowercase();
+        let key = if lower.starts_with("age1") || lower.starts_with("age-secret-key-1") || lower.starts_with("age-plugin-") {
+            inspect_age(ctx, first)
+        } else {
+            inspect_openssh(ctx, line)
+        };
+        keys.push(key.map_err(|e| MbaseError::invalid_input(format!("line {}: {}", number + 1, e)))?);
+    }
+
+    if keys.is_empty() {
+        return Err(MbaseError::invalid_input("no OpenSSH public key or age recipient found"));
+    }
+    Ok(KeyResult { keys })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB3jrJU8qqo3K+vC9Yp05u8d+1ZmpvqqiGqKRw2PHugm test@mbase";
+    const ECDSA: &str = "ecdsa-sha2-nistp384 AAAAE2VjZHNhLXNoYTItbmlzdHAzODQAAAAIbmlzdHAzODQAAABhBByZ/xE8hJruzhBWbsl+IvV7VNZry1sSqPxDbYjgARDiP/lA0ixlOLj2YOb2sBf45wRLRGJmuv6LVxYkZGz6ZPCjEhw1QOeCUSTXr+Fgunq4GzBuN6r/s4JlNSmLpJXdNA==";
+
+    fn inspect(text: &str) -> Result<KeyResult> {
+        run_key(&Context::default(), &InputSource::Literal(text.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn test_key_openssh_matches_ssh_keygen() {
+        let result = inspect(&format!("# keys\n{}\n\nfrom=\"10.0.0.1\" {}\n", ED25519, ECDSA)).unwrap();
+        let [ed, ec] = &result.keys[..] else { panic!("expected two keys") };
+
+        assert_eq!(ed.algorithm, "Ed25519");
+        assert_eq!(ed.bits, Some(256));
+        assert_eq!(ed.fingerprint.as_deref(), Some("SHA256:/FGcH08aQbYg+jwu1cF06uXblwBZHJ66VW1uOfeLkZQ"));
+        assert_eq!(ed.comment.as_deref(), Some("test@mbase"));
+        assert_eq!(ed.fields[1].name, "public-key");
+        assert_eq!(ed.fields[1].length, 32);
+
+        assert_eq!(ec.algorithm, "ECDSA");
+        assert_eq!(ec.bits, Some(384));
+        assert_eq!(ec.fingerprint.as_deref(), Some("SHA256:rqHzM6mpDKEH6o677BXDeMAUEYPs5atiNCGeAIVYcTw"));
+        assert_eq!(ec.comment, None);
+        assert!(ec.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_key_rsa_bits() {
+        let rsa = "ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQCsif0id3pSrdFWeiP3Aou00AU67JRbxsBVbtZggmMRwhDdTvhWSI0UWY4dZlVE3hXgajuzXZMFpOZsBO/NcP+T0GPevstemBhi022IRW/swtzTTzOp4Dp0P099PHqLUhJha03MaQHq7pEDpFr0xBSbkURIHUpZYv8AdRiexKw9MA0fX4vG0thHf0t/RKUgogQFElx8AYxrbQnROlv4hAfySxyuMrZF9TP++Boa1Vfv3fs0RI74On1GH0+pZRyrc7Avkc0Ha9ey94Pqa/8Ajeh57MIcUdsxZ5OiOEfYtHx2BGc9Q/UXhdpIQn2Hip49vMjtubv9QBoBWJ4WoviaWjET rsa@mbase";
+        let key = &inspect(rsa).unwrap().keys[0];
+        assert_eq!(key.bits, Some(2048));
+        assert_eq!(key.fingerprint.as_deref(), Some("SHA256:lZT8F5dBZTMetI3KIgPtHodMB/n42pjVJbAW2iO6RKY"));
+    }
+
+    #[test]
+    fn test_key_age() {
+        let result = inspect("age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p\n").unwrap();
+        let key = &result.keys[0];
+        assert_eq!(key.format, "age");
+        assert_eq!(key.key_type, "recipient");
+        assert_eq!(key.bits, Some(256));
+        assert!(key.fingerprint.as_ref().unwrap().starts_with("SHA256:"));
+
+        let identity = inspect("AGE-SECRET-KEY-1GFPYYSJZGFPYYSJZGFPYYSJZGFPYYSJZGFPYYSJZGFPYYSJZGFPQ4EGAEX").unwrap();
+        assert_eq!(identity.keys[0].key_type, "identity");
+        assert_eq!(identity.keys[0].fields[0].value, "(redacted)");
+    }
+
+    #[test]
+    fn test_key_errors() {
+        let truncated = &ED25519[..60];
+        assert!(inspect(truncated).is_err());
+        let err = inspect("hello world").unwrap_err();
+        assert!(err.to_string().contains("line 1"), "{}", err);
+        assert!(inspect("age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8q").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use super::report::LintCodecResult;
+use mbase::codec::lint::{lint_alphabet, LintOptions, Severity};
+use mbase::types::Context;
+
+pub fn run_lint_codec(ctx: &Context, alphabet: &str, opts: &LintOptions) -> LintCodecResult {
+    let issues = lint_alphabet(alphabet, opts, &ctx.registry);
+    LintCodecResult {
+        alphabet: alphabet.to_string(),
+        symbols: alphabet.chars().count(),
+        valid: issues.iter().all(|i| i.severity != Severity::Error),
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_codec_result() {
+        let ctx = Context::default();
+        let ok = run_lint_codec(&ctx, "0123456789abcdef", &LintOptions::default());
+        assert!(ok.valid);
+        assert_eq!(ok.symbols, 16);
+
+        let bad = run_lint_codec(&ctx, "abcabc", &LintOptions::default());
+        assert!(!bad.valid);
+        assert_eq!(bad.issues.len(), 3);
+    }
+}
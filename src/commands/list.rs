@@ -1,5 +1,137 @@
-use mbase::types::{CodecMeta, Context};
+use super::enc::OutputCharset;
+use super::report::CodecSummary;
+use mbase::codec::Codec;
+use mbase::types::{CaseSensitivity, CodecMetadata, Context, Mode, PaddingRule};
 
-pub fn run_list(ctx: &Context) -> Vec<CodecMeta> {
-    ctx.registry.list()
+/// Orders for `list`; registration (ID) order when none is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListSort {
+    Name,
+    /// Densest output first (bits of input per output character).
+    Efficiency,
+    /// Variants of one encoding next to each other.
+    Family,
+}
+
+/// Divisible by the block sizes of the block codecs (3, 4, 5 bytes).
+const SAMPLE_LEN: usize = 60;
+
+/// `None` for codecs that cannot carry arbitrary bytes (letter-only ciphers drop the rest).
+fn bits_per_char(codec: &dyn Codec) -> Option<f64> {
+    // Codecs that cap their input (bip39 takes at most 32 bytes) get a shorter sample.
+    [SAMPLE_LEN, 32].into_iter().find_map(|len| {
+        let sample: Vec<u8> = (0..len).map(|i| (i * 151 + 17) as u8).collect();
+        let encoded = codec.encode(&sample).ok()?;
+        if codec.decode(&encoded, Mode::Lenient).ok()? != sample {
+            return None;
+        }
+        Some((len * 8) as f64 / encoded.chars().count() as f64)
+    })
+}
+
+fn tags(meta: &CodecMetadata, sample: Option<&str>) -> Vec<&'static str> {
+    let mut tags = Vec::new();
+    if meta.multibase_code.is_some() {
+        tags.push("multibase");
+    }
+    if meta.padding == PaddingRule::Required {
+        tags.push("padded");
+    }
+    if meta.case_sensitivity == CaseSensitivity::Insensitive {
+        tags.push("case-insensitive");
+    }
+    if meta.streamable {
+        tags.push("streamable");
+    }
+    if meta.text_only {
+        tags.push("text-only");
+    }
+    if meta.lossy {
+        tags.push("lossy");
+    }
+    if let Some(sample) = sample {
+        if !sample.is_ascii() {
+            tags.push("unicode");
+        } else if OutputCharset::UrlSafe.allows(sample) {
+            tags.push("url-safe");
+        }
+    }
+    tags
+}
+
+pub fn run_list(ctx: &Context, sort: Option<ListSort>) -> Vec<CodecSummary> {
+    let mut codecs: Vec<CodecSummary> = ctx
+        .registry
+        .iter()
+        .map(|(id, codec)| {
+            let meta = CodecMetadata::new(id, codec);
+            let example = codec.encode(b"hello").ok();
+            CodecSummary {
+                tags: tags(&meta, example.as_deref()),
+                bits_per_char: bits_per_char(codec),
+                example,
+                meta,
+            }
+        })
+        .collect();
+
+    match sort {
+        None => {}
+        Some(ListSort::Name) => codecs.sort_by(|a, b| a.meta.name.cmp(&b.meta.name)),
+        Some(ListSort::Efficiency) => codecs.sort_by(|a, b| {
+            let density = |c: &CodecSummary| c.bits_per_char.unwrap_or(f64::NEG_INFINITY);
+            density(b).total_cmp(&density(a)).then_with(|| a.meta.name.cmp(&b.meta.name))
+        }),
+        Some(ListSort::Family) => codecs.sort_by(|a, b| {
+            let family = |c: &CodecSummary| c.meta.family.clone().unwrap_or_else(|| c.meta.name.clone());
+            family(a).cmp(&family(b)).then_with(|| a.meta.id.cmp(&b.meta.id))
+        }),
+    }
+    codecs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(codecs: &'a [CodecSummary], name: &str) -> &'a CodecSummary {
+        codecs.iter().find(|c| c.meta.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_list_summary_fields() {
+        let codecs = run_list(&Context::default(), None);
+        let hex = find(&codecs, "base16lower");
+        assert_eq!(hex.example.as_deref(), Some("68656c6c6f"));
+        assert_eq!(hex.bits_per_char, Some(4.0));
+        assert!(hex.tags.contains(&"multibase") && hex.tags.contains(&"url-safe"));
+
+        let base64pad = find(&codecs, "base64pad");
+        assert!(base64pad.tags.contains(&"padded"));
+        assert!(find(&codecs, "base65536").tags.contains(&"unicode"));
+        assert!(find(&codecs, "morse").tags.contains(&"text-only"));
+        assert!(find(&codecs, "bip39").bits_per_char.is_some());
+        assert_eq!(find(&codecs, "monero").example, None);
+    }
+
+    #[test]
+    fn test_list_sorting() {
+        let ctx = Context::default();
+        let by_name = run_list(&ctx, Some(ListSort::Name));
+        assert!(by_name.windows(2).all(|w| w[0].meta.name <= w[1].meta.name));
+
+        let by_density = run_list(&ctx, Some(ListSort::Efficiency));
+        assert_eq!(by_density[0].meta.name, "base65536");
+        assert!(by_density.last().unwrap().bits_per_char.is_none());
+
+        let by_family = run_list(&ctx, Some(ListSort::Family));
+        let base64: Vec<usize> = by_family
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.meta.family.as_deref() == Some("base64"))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(base64.len(), 5);
+        assert_eq!(base64[4] - base64[0], 4);
+    }
 }
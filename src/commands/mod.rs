@@ -1,26 +1,75 @@
+mod analyze;
+mod bits;
+mod clean;
+mod cmp;
 mod conv;
+mod crc;
 mod dec;
 mod detect;
+mod dns;
 mod enc;
 mod explain;
+mod extract;
 mod fmt;
+mod hashid;
+mod hcert;
 mod info;
+mod jwt;
+mod key;
+mod lint;
 mod list;
+mod pem;
+mod qr;
+mod radix;
+mod rand;
+mod recipes;
+mod recover;
+mod regress;
+mod report;
+mod sri;
 mod verify;
 
-pub use conv::{run_conv, run_conv_json};
-pub use dec::{run_decode, run_decode_all, run_decode_all_json, run_decode_json};
-pub use detect::run_detect;
-pub use enc::{run_encode, run_encode_all, run_encode_all_json, run_encode_json};
-pub use explain::run_explain;
-pub use fmt::{run_fmt, FmtOptions};
+pub use analyze::{recover_base64_alphabet, run_analyze};
+pub use bits::run_bits;
+pub use clean::clean_input;
+pub use cmp::run_cmp;
+pub use conv::{convert, run_conv, run_conv_json, ConvOptions};
+pub use crc::run_crc;
+pub use dec::{
+    decode_record, pick_sibling, run_decode_all, run_decode_all_json, run_decode_all_shifts, run_decode_both_modes, run_decode_json,
+    run_decode_passes, run_decode_passes_json, run_decode_pem_json, run_decode_warned, sibling_note,
+};
+pub use detect::{run_detect, run_detect_fields};
+pub use dns::run_dns;
+pub use enc::{
+    encode_record, encode_warnings, run_encode, run_encode_all, run_encode_all_json, run_encode_json, EncodeAllFilter, OutputCharset,
+};
+pub use explain::{run_explain, run_explain_extracted};
+pub use extract::extract_input;
+pub use fmt::{run_fmt, run_fmt_check, FmtOptions, LetterCase};
+pub use hcert::run_hcert;
 pub use info::run_info;
-pub use list::run_list;
-pub use verify::run_verify;
+pub use jwt::run_jwt;
+pub use key::run_key;
+pub use lint::run_lint_codec;
+pub use list::{run_list, ListSort};
+pub use qr::run_qr;
+pub use radix::{run_radix, Radix};
+pub use rand::run_rand;
+pub use recipes::{find_recipe, run_recipe, run_recipes, run_recipes_export, run_recipes_import, run_recipes_save};
+pub use recover::{run_recover, run_recover_json};
+pub use regress::{run_regress, run_regress_add, run_regress_update};
+pub use sri::{run_sri, SriAlgorithm};
+pub use verify::{junit_report, run_check_digit, run_verify, run_verify_tree};
 
-use crate::io::{write_output, OutputConfig};
-use mbase::error::Result;
-use mbase::types::{Context, InputSource, Mode, OutputDest};
+use crate::io::{read_input, recipes_path, write_output, write_recipes, OutputConfig, OutputDigest, Records};
+use mbase::bits::BitOp;
+use mbase::checkdigit::Scheme;
+use mbase::checksum::Checksum;
+use mbase::codec::lint::{LintOptions, Severity};
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode, OutputDest};
+use pem::{asn1_outline, format_asn1, read_pem};
 
 pub trait CommandHandler {
     fn execute(&self, ctx: &Context) -> Result<()>;
@@ -31,28 +80,80 @@ pub struct EncCommand {
     pub input: InputSource,
     pub output: OutputDest,
     pub multibase: bool,
+    pub opts: CodecOptions,
     pub all: bool,
+    pub filter: EncodeAllFilter,
+    pub records: Option<Records>,
+    pub digest: Option<OutputDigest>,
     pub json: bool,
 }
 
+/// Record mode converts each record on its own, which has no JSON or `--all` equivalent.
+fn check_records(records: &Option<Records>, json: bool, all: bool) -> Result<()> {
+    if records.is_some() && (json || all) {
+        return Err(MbaseError::invalid_input("--in0/--out0/--record-sep cannot be combined with --json or --all"));
+    }
+    Ok(())
+}
+
+/// The digest covers the bytes written to `--out`, which JSON (and outline) output never writes.
+fn check_digest(digest: &Option<OutputDigest>, json: bool) -> Result<()> {
+    if digest.is_some() && json {
+        return Err(MbaseError::invalid_input("--print-digest needs raw output; it cannot be combined with --json"));
+    }
+    Ok(())
+}
+
 impl CommandHandler for EncCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
+        check_records(&self.records, self.json, self.all)?;
+        check_digest(&self.digest, self.json)?;
+        if (self.filter.max_len.is_some() || self.filter.charset.is_some()) && !self.all {
+            return Err(MbaseError::invalid_input("--max-len and --charset filter the codecs of --all"));
+        }
+        if let Some(records) = &self.records {
+            let codec = ctx.registry.get(&self.codec)?;
+            let mut encoded = String::new();
+            let out = records.process(&self.input, |record, out| {
+                encoded.clear();
+                encode_record(ctx, codec, record, self.multibase, &self.opts, &mut encoded)?;
+                out.extend_from_slice(encoded.as_bytes());
+                Ok(())
+            })?;
+            let config = OutputConfig {
+                dest: self.output.clone(),
+                force: true,
+                digest: self.digest,
+                sparse: false,
+            };
+            return write_output(&out, &config);
+        }
+
+        let data = read_input(&self.input)?;
+        let codec = if self.all { None } else { Some(ctx.registry.get(&self.codec)?) };
+        for w in encode_warnings(codec, &data) {
+            eprintln!("warning: {}", w);
+        }
+        let input = InputSource::Literal(data);
+
         if self.json {
             if self.all {
-                let result = run_encode_all_json(ctx, &self.input)?;
-                println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                let result = run_encode_all_json(ctx, &input, &self.filter)?;
+                report::print_json("enc-all", &result);
             } else {
-                let result = run_encode_json(ctx, &self.codec, &self.input, self.multibase)?;
-                println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                let result = run_encode_json(ctx, &self.codec, &input, self.multibase, &self.opts)?;
+                report::print_json("enc", &result);
             }
             return Ok(());
         }
 
         if self.all {
-            let output_str = run_encode_all(ctx, &self.input)?;
+            let output_str = run_encode_all(ctx, &input, &self.filter)?;
             let config = OutputConfig {
                 dest: self.output.clone(),
                 force: true,
+                digest: self.digest,
+                sparse: false,
             };
             write_output(output_str.as_bytes(), &config)?;
             if matches!(self.output, OutputDest::Stdout) {
@@ -61,10 +162,12 @@ impl CommandHandler for EncCommand {
             return Ok(());
         }
 
-        let encoded = run_encode(ctx, &self.codec, &self.input, self.multibase)?;
+        let encoded = run_encode(ctx, &self.codec, &input, self.multibase, &self.opts)?;
         let config = OutputConfig {
             dest: self.output.clone(),
             force: true,
+            digest: self.digest,
+            sparse: false,
         };
         write_output(encoded.as_bytes(), &config)?;
         if matches!(self.output, OutputDest::Stdout) {
@@ -81,32 +184,297 @@ pub struct DecCommand {
     pub mode: Mode,
     pub force: bool,
     pub multibase: bool,
+    pub opts: CodecOptions,
     pub all: bool,
+    pub all_shifts: bool,
+    pub records: Option<Records>,
+    pub digest: Option<OutputDigest>,
+    pub extract: bool,
+    pub asn1: bool,
+    pub sparse: bool,
+    /// Decode to validate only; nothing is written.
+    pub check: bool,
+    /// Fall back to a family member when the codec cannot decode the input.
+    pub try_siblings: bool,
+    /// Undo copy/paste damage (quotes, punctuation, gutters, smart quotes) before decoding.
+    pub clean: bool,
+    /// Decode damaged block-codec input as far as possible, with a gap report.
+    pub recover: bool,
+    /// Percent-decode repeatedly, up to this many passes (urlencoding, lenient mode).
+    pub max_passes: Option<usize>,
+    /// Decode strictly and leniently and report the drift; nothing is written.
+    pub both_modes: bool,
     pub json: bool,
 }
 
 impl CommandHandler for DecCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
+        check_records(&self.records, self.json || self.all_shifts, self.all)?;
+        check_digest(&self.digest, self.json || self.all_shifts || self.asn1)?;
+        if self.extract && (self.multibase || self.all || self.all_shifts || self.records.is_some()) {
+            return Err(MbaseError::invalid_input(
+                "--extract needs a single --codec and cannot be combined with --multibase, --all, --all-shifts or record mode",
+            ));
+        }
+        if self.asn1 && (self.all || self.all_shifts || self.records.is_some()) {
+            return Err(MbaseError::invalid_input("--asn1 cannot be combined with --all, --all-shifts or record mode"));
+        }
+        if self.check && (self.json || self.all || self.all_shifts || self.asn1 || self.sparse || self.digest.is_some()) {
+            return Err(MbaseError::invalid_input(
+                "--check writes nothing; it cannot be combined with --json, --all, --all-shifts, --asn1, --sparse or --print-digest",
+            ));
+        }
+        if self.sparse && !matches!(self.output, OutputDest::File(_)) {
+            return Err(MbaseError::invalid_input("--sparse needs --out FILE"));
+        }
+        if self.try_siblings && (self.multibase || self.all || self.all_shifts) {
+            return Err(MbaseError::invalid_input(
+                "--try-siblings needs a single --codec and cannot be combined with --multibase, --all or --all-shifts",
+            ));
+        }
+        if self.clean && self.records.is_some() {
+            return Err(MbaseError::invalid_input("--clean cannot be combined with record mode"));
+        }
+        if self.recover
+            && (self.multibase || self.all || self.all_shifts || self.records.is_some() || self.extract || self.try_siblings || self.check)
+        {
+            return Err(MbaseError::invalid_input(
+                "--recover needs a single --codec and cannot be combined with --multibase, --all, --all-shifts, --extract, --try-siblings, --check or record mode",
+            ));
+        }
+        if self.max_passes.is_some() {
+            if self.mode != Mode::Lenient {
+                return Err(MbaseError::invalid_input("--max-passes needs --mode lenient"));
+            }
+            if self.multibase || self.all || self.all_shifts || self.records.is_some() || self.extract || self.try_siblings || self.recover
+            {
+                return Err(MbaseError::invalid_input(
+                    "--max-passes cannot be combined with --multibase, --all, --all-shifts, --extract, --try-siblings, --recover or record mode",
+                ));
+            }
+        }
+        if self.both_modes
+            && (self.multibase
+                || self.all
+                || self.all_shifts
+                || self.records.is_some()
+                || self.extract
+                || self.try_siblings
+                || self.recover
+                || self.max_passes.is_some()
+                || self.check
+                || self.asn1
+                || self.sparse
+                || self.digest.is_some())
+        {
+            return Err(MbaseError::invalid_input(
+                "--both-modes needs a single --codec and cannot be combined with --multibase, --all, --all-shifts, --extract, --try-siblings, --recover, --max-passes, --check, --asn1, --sparse, --print-digest or record mode",
+            ));
+        }
+        if let Some(records) = &self.records {
+            let out = records.process(&self.input, |record, out| {
+                let text = String::from_utf8_lossy(record);
+                let mut codec = self.codec.as_str();
+                if self.try_siblings {
+                    if let Some(sibling) = pick_sibling(ctx, codec, &text, self.mode, &self.opts)? {
+                        eprintln!("{}", sibling_note(codec, sibling));
+                        codec = sibling;
+                    }
+                }
+                decode_record(ctx, codec, &text, self.mode, self.multibase, &self.opts, out)
+            })?;
+            if self.check {
+                return Ok(());
+            }
+            let config = OutputConfig {
+                dest: self.output.clone(),
+                force: self.force,
+                digest: self.digest,
+                sparse: self.sparse,
+            };
+            return write_output(&out, &config);
+        }
+
+        let (input, cleaned) = if self.clean {
+            let alphabet = if self.multibase || self.all {
+                ""
+            } else {
+                ctx.registry.get(&self.codec)?.meta().alphabet
+            };
+            clean_input(&self.input, alphabet)?
+        } else {
+            (self.input.clone(), Vec::new())
+        };
+        if !self.json {
+            for note in &cleaned {
+                eprintln!("cleaned: {}", note);
+            }
+        }
+
+        if self.both_modes {
+            let result = run_decode_both_modes(ctx, &self.codec, &input, &self.opts)?;
+            if self.json {
+                report::print_json("dec-both-modes", &result);
+            } else {
+                for (name, outcome) in [("strict", &result.strict), ("lenient", &result.lenient)] {
+                    match (&outcome.preview, &outcome.error) {
+                        (Some(preview), _) => {
+                            println!("{:<8} {} bytes: {}", format!("{}:", name), outcome.output_length.unwrap_or_default(), preview)
+                        }
+                        (None, error) => println!("{:<8} error: {}", format!("{}:", name), error.as_deref().unwrap_or_default()),
+                    }
+                    for w in &outcome.warnings {
+                        eprintln!("warning: {} ({})", w, name);
+                    }
+                }
+                if result.differ {
+                    println!("drift:");
+                    for d in &result.drift {
+                        println!("  - {}", d);
+                    }
+                } else if result.strict.ok {
+                    println!("no drift: both modes decode to the same bytes");
+                } else {
+                    println!("no drift: both modes reject the input");
+                }
+            }
+            if !(result.strict.ok || result.lenient.ok) {
+                return Err(MbaseError::invalid_input("neither strict nor lenient mode decodes the input"));
+            }
+            if result.differ {
+                return Err(MbaseError::invalid_input("strict and lenient decodes differ"));
+            }
+            return Ok(());
+        }
+
+        if self.all_shifts {
+            let result = run_decode_all_shifts(ctx, &self.codec, &input, &self.opts)?;
+            if self.json {
+                report::print_json("dec-all-shifts", &result);
+            } else {
+                let best = result.best_shift;
+                println!("{:<7} {:<7} DECODED", "SHIFT", "SCORE");
+                println!("{}", "-".repeat(70));
+                for r in &result.results {
+                    let marker = if Some(r.shift) == best { "*" } else { " " };
+                    let label = format!("rot{}{}", r.shift, marker);
+                    println!("{:<7} {:<7} {}", label, format!("{:.0}%", r.score * 100.0), r.output);
+                }
+            }
+            return Ok(());
+        }
+
+        let (input, extracted) = if self.extract {
+            let (input, found) = extract_input(ctx, &self.codec, &input, self.mode)?;
+            (input, Some(found))
+        } else {
+            (input, None)
+        };
+
+        // PEM armor is base64 underneath; unwrap it rather than failing on the BEGIN/END lines.
+        let (input, pem) = if self.codec.starts_with("base64") && !(self.extract || self.multibase || self.all || self.recover) {
+            read_pem(ctx, &input)?
+        } else {
+            (input, None)
+        };
+
+        let (input, sibling) = if self.try_siblings && pem.is_none() {
+            let data = read_input(&input)?;
+            let sibling = pick_sibling(ctx, &self.codec, &String::from_utf8_lossy(&data), self.mode, &self.opts)?;
+            (InputSource::Literal(data), sibling)
+        } else {
+            (input, None)
+        };
+        let codec = sibling.unwrap_or(&self.codec);
+        let note = sibling.map(|s| sibling_note(&self.codec, s));
+
         if self.json {
             if self.all {
-                let result = run_decode_all_json(ctx, &self.input, self.mode)?;
-                println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                let result = run_decode_all_json(ctx, &input, self.mode)?;
+                report::print_json("dec-all", &result);
             } else {
-                let result = run_decode_json(ctx, &self.codec, &self.input, self.mode, self.multibase)?;
-                println!("{}", serde_json::to_string_pretty(&result).unwrap());
+                let mut result = match pem {
+                    Some(blocks) => run_decode_pem_json(&input, blocks)?,
+                    None if self.recover => run_recover_json(ctx, codec, &input)?,
+                    None => match self.max_passes {
+                        Some(max_passes) => run_decode_passes_json(ctx, codec, &input, max_passes)?,
+                        None => run_decode_json(ctx, codec, &input, self.mode, self.multibase, &self.opts)?,
+                    },
+                };
+                result.warnings.extend(note);
+                result.extracted = extracted;
+                result.cleaned = cleaned;
+                if self.asn1 {
+                    result.attach_asn1()?;
+                }
+                report::print_json("dec", &result);
             }
             return Ok(());
         }
 
         if self.all {
-            run_decode_all(ctx, &self.input, self.mode)?;
+            run_decode_all(ctx, &input, self.mode)?;
             return Ok(());
         }
 
-        let decoded = run_decode(ctx, &self.codec, &self.input, self.mode, self.multibase)?;
+        let decoded = match &pem {
+            Some(blocks) => {
+                for b in blocks {
+                    eprintln!("PEM {}: {} DER bytes", b.label, b.der_length);
+                }
+                blocks.iter().flat_map(|b| b.der.iter().copied()).collect()
+            }
+            None if self.recover => {
+                let (decoded, report) = run_recover(ctx, codec, &input)?;
+                eprintln!(
+                    "recovered {} of {} bytes; {} lost in {} gap(s)",
+                    report.recovered_bytes,
+                    report.output_length,
+                    report.lost_bytes,
+                    report.gaps.len()
+                );
+                for gap in &report.gaps {
+                    eprintln!("gap at byte {}: {} bytes (line {}: {})", gap.offset, gap.length, gap.line, gap.reason);
+                }
+                if report.truncated {
+                    eprintln!("input ends inside a block; the partial block's whole bytes were kept");
+                }
+                decoded
+            }
+            None if self.max_passes.is_some() => {
+                let max_passes = self.max_passes.unwrap_or_default();
+                let (decoded, passes) = run_decode_passes(ctx, codec, &input, max_passes)?;
+                for p in &passes {
+                    eprintln!("pass {}: {} escapes -> {} bytes: {}", p.pass, p.escapes, p.output_length, p.preview);
+                }
+                decoded
+            }
+            None => {
+                let (decoded, warnings) = run_decode_warned(ctx, codec, &input, self.mode, self.multibase, &self.opts)?;
+                for w in &warnings {
+                    eprintln!("warning: {}", w);
+                }
+                decoded
+            }
+        };
+        if let Some(note) = &note {
+            eprintln!("{}", note);
+        }
+        if let Some(found) = &extracted {
+            eprintln!("extracted {}..{}: {}", found.start, found.end, found.text);
+        }
+        if self.check {
+            return Ok(());
+        }
+        if self.asn1 {
+            print!("{}", format_asn1(&asn1_outline(&decoded)?));
+            return Ok(());
+        }
         let config = OutputConfig {
             dest: self.output.clone(),
             force: self.force,
+            digest: self.digest,
+            sparse: self.sparse,
         };
         write_output(&decoded, &config)?;
         Ok(())
@@ -118,22 +486,42 @@ pub struct ConvCommand {
     pub to: String,
     pub input: InputSource,
     pub output: OutputDest,
-    pub mode: Mode,
+    pub opts: ConvOptions,
+    pub records: Option<Records>,
+    pub digest: Option<OutputDigest>,
     pub json: bool,
 }
 
 impl CommandHandler for ConvCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
+        check_records(&self.records, self.json, false)?;
+        check_digest(&self.digest, self.json)?;
+        if let Some(records) = &self.records {
+            let out = records.process(&self.input, |record, out| {
+                out.extend_from_slice(convert(ctx, &self.from, &self.to, &String::from_utf8_lossy(record), &self.opts)?.as_bytes());
+                Ok(())
+            })?;
+            let config = OutputConfig {
+                dest: self.output.clone(),
+                force: true,
+                digest: self.digest,
+                sparse: false,
+            };
+            return write_output(&out, &config);
+        }
+
         if self.json {
-            let result = run_conv_json(ctx, &self.from, &self.to, &self.input, self.mode)?;
-            println!("{}", serde_json::to_string_pretty(&result).unwrap());
+            let result = run_conv_json(ctx, &self.from, &self.to, &self.input, &self.opts)?;
+            report::print_json("conv", &result);
             return Ok(());
         }
 
-        let converted = run_conv(ctx, &self.from, &self.to, &self.input, self.mode)?;
+        let converted = run_conv(ctx, &self.from, &self.to, &self.input, &self.opts)?;
         let config = OutputConfig {
             dest: self.output.clone(),
             force: true,
+            digest: self.digest,
+            sparse: false,
         };
         write_output(converted.as_bytes(), &config)?;
         if matches!(self.output, OutputDest::Stdout) {
@@ -144,20 +532,58 @@ impl CommandHandler for ConvCommand {
 }
 
 pub struct ListCommand {
+    pub long: bool,
+    pub sort: Option<ListSort>,
     pub json: bool,
 }
 
+/// Cuts `text` to `width` characters, marking the cut with an ellipsis.
+fn truncate_cell(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width - 1).collect();
+    cut.push('…');
+    cut
+}
+
 impl CommandHandler for ListCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
-        let codecs = run_list(ctx);
+        let codecs = run_list(ctx, self.sort);
         if self.json {
-            println!("{}", serde_json::to_string_pretty(&codecs).unwrap());
-        } else {
-            println!("{:<20} {:<8} DESCRIPTION", "NAME", "PREFIX");
-            println!("{}", "-".repeat(60));
+            report::print_json("list", &report::ListResult { codecs });
+        } else if self.long {
+            println!(
+                "{:<4} {:<20} {:<10} {:>9}  {:<24} {:<24} {:<14} TAGS",
+                "ID", "NAME", "FAMILY", "BITS/CHAR", "ALPHABET", "EXAMPLE (\"hello\")", "SPEC"
+            );
+            println!("{}", "-".repeat(135));
             for c in codecs {
+                let bits = c.bits_per_char.map_or("-".to_string(), |b| format!("{:.2}", b));
+                let alphabet = if c.meta.alphabet.is_empty() {
+                    "-"
+                } else {
+                    c.meta.alphabet.as_str()
+                };
+                let example = c.example.as_deref().map_or("-".to_string(), |e| e.escape_debug().to_string());
+                println!(
+                    "{:<4} {:<20} {:<10} {:>9}  {:<24} {:<24} {:<14} {}",
+                    c.meta.id,
+                    c.meta.name,
+                    c.meta.family.as_deref().unwrap_or("-"),
+                    bits,
+                    truncate_cell(&alphabet.escape_debug().to_string(), 24),
+                    truncate_cell(&example, 24),
+                    truncate_cell(c.meta.spec.as_deref().unwrap_or("-"), 14),
+                    c.tags.join(",")
+                );
+            }
+        } else {
+            println!("{:<4} {:<20} {:<8} DESCRIPTION", "ID", "NAME", "PREFIX");
+            println!("{}", "-".repeat(65));
+            for c in codecs.into_iter().map(|c| c.meta) {
                 let prefix = c.multibase_code.map_or("-".to_string(), |c| c.to_string());
-                println!("{:<20} {:<8} {}", c.name, prefix, c.description);
+                println!("{:<4} {:<20} {:<8} {}", c.id, c.name, prefix, c.description);
             }
         }
         Ok(())
@@ -166,22 +592,59 @@ impl CommandHandler for ListCommand {
 
 pub struct InfoCommand {
     pub codec: String,
+    pub examples: bool,
+    /// Print only the specification reference.
+    pub spec: bool,
     pub json: bool,
 }
 
 impl CommandHandler for InfoCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
-        let meta = run_info(ctx, &self.codec)?;
+        let info = run_info(ctx, &self.codec, self.examples)?;
+        let meta = &info.meta;
+        if self.spec {
+            return match (&meta.spec, &meta.spec_url) {
+                (Some(title), Some(url)) => {
+                    println!("{} {}", title, url);
+                    Ok(())
+                }
+                _ => Err(MbaseError::invalid_input(format!("{} does not follow a published specification", meta.name))),
+            };
+        }
         if self.json {
-            println!("{}", serde_json::to_string_pretty(&meta).unwrap());
+            report::print_json("info", &info);
         } else {
+            println!("ID:          {}", meta.id);
             println!("Name:        {}", meta.name);
             println!("Aliases:     {}", meta.aliases.join(", "));
             println!("Alphabet:    {}", meta.alphabet);
             println!("Multibase:   {}", meta.multibase_code.map_or("-".to_string(), |c| c.to_string()));
             println!("Padding:     {:?}", meta.padding);
             println!("Case:        {:?}", meta.case_sensitivity);
+            println!("Streamable:  {}", if meta.streamable { "yes" } else { "no" });
+            println!("Text only:   {}", if meta.text_only { "yes" } else { "no" });
+            println!("Lossy:       {}", if meta.lossy { "yes" } else { "no" });
             println!("Description: {}", meta.description);
+            match (&meta.spec, &meta.spec_url) {
+                (Some(title), Some(url)) => println!("Spec:        {} ({})", title, url),
+                _ => println!("Spec:        -"),
+            }
+            if self.examples {
+                println!("Examples:");
+                for e in &info.examples {
+                    let input = if e.input.chars().all(|c| c.is_ascii_alphanumeric() || ":_-".contains(c)) {
+                        e.input.clone()
+                    } else {
+                        format!("'{}'", e.input)
+                    };
+                    let note = if e.roundtrip {
+                        ""
+                    } else {
+                        "  (decoding does not restore the input)"
+                    };
+                    println!("  mbase enc --codec {} --in {}  ->  {}{}", meta.name, input, e.encoded.escape_debug(), note);
+                }
+            }
         }
         Ok(())
     }
@@ -191,20 +654,104 @@ pub struct VerifyCommand {
     pub codec: String,
     pub input: InputSource,
     pub mode: Mode,
+    pub require_canonical: bool,
+    /// Check the digits of a number with this scheme instead of decoding with `codec`.
+    pub scheme: Option<Scheme>,
+    pub generate: bool,
     pub json: bool,
 }
 
+impl VerifyCommand {
+    fn execute_check_digit(&self, scheme: Scheme) -> Result<()> {
+        let result = run_check_digit(&self.input, scheme, self.generate)?;
+        if self.json {
+            report::print_json("verify-check-digit", &result);
+        } else if self.generate && result.valid {
+            println!("{}", result.number);
+        } else if result.valid {
+            println!("valid");
+        } else {
+            println!("invalid: {}", result.error.as_deref().unwrap_or_default());
+        }
+        if result.valid {
+            return Ok(());
+        }
+        // Re-run to fail with the typed error, so a wrong check digit exits as a checksum mismatch.
+        let number = &result.number;
+        Err(if self.generate {
+            scheme.generate(number)
+        } else {
+            scheme.validate(number).map(|_| String::new())
+        }
+        .unwrap_err())
+    }
+}
+
 impl CommandHandler for VerifyCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
+        if let Some(scheme) = self.scheme {
+            return self.execute_check_digit(scheme);
+        }
         let result = run_verify(ctx, &self.codec, &self.input, self.mode)?;
         if self.json {
-            println!("{}", serde_json::to_string_pretty(&result).unwrap());
+            report::print_json("verify", &result);
         } else if result.valid {
-            println!("valid");
+            if result.canonical == Some(false) {
+                println!("valid (non-canonical)");
+                for issue in &result.issues {
+                    println!("  - {}", issue);
+                }
+                println!("canonical: {}", result.canonical_form.as_deref().unwrap_or_default());
+            } else {
+                println!("valid");
+            }
         } else {
             println!("invalid: {}", result.error.as_deref().unwrap_or_default());
             return Err(mbase::error::MbaseError::invalid_input(result.error.unwrap_or_default()));
         }
+        if self.require_canonical && result.canonical == Some(false) {
+            return Err(mbase::error::MbaseError::invalid_input(format!(
+                "non-canonical {} input: {}",
+                result.codec,
+                result.issues.join("; ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub struct VerifyTreeCommand {
+    pub manifest: std::path::PathBuf,
+    pub junit: bool,
+    pub json: bool,
+}
+
+impl CommandHandler for VerifyTreeCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_verify_tree(ctx, &self.manifest)?;
+        if self.json {
+            report::print_json("verify-tree", &result);
+        } else if self.junit {
+            print!("{}", junit_report(&result));
+        } else {
+            for fixture in &result.fixtures {
+                match (&fixture.error, &fixture.actual) {
+                    _ if fixture.passed => println!("ok    {} ({})", fixture.file, fixture.codec),
+                    (Some(error), _) => println!("FAIL  {} ({}): {}", fixture.file, fixture.codec, error),
+                    (None, actual) => println!(
+                        "FAIL  {} ({}): expected {}, got {}",
+                        fixture.file,
+                        fixture.codec,
+                        fixture.expected,
+                        actual.as_deref().unwrap_or_default()
+                    ),
+                }
+            }
+            println!("{} of {} fixtures passed", result.total - result.failed, result.total);
+        }
+        if result.failed > 0 {
+            return Err(mbase::error::MbaseError::invalid_input(format!("{} of {} fixtures failed", result.failed, result.total)));
+        }
         Ok(())
     }
 }
@@ -217,6 +764,12 @@ pub struct FmtCommand {
     pub wrap: Option<usize>,
     pub group: Option<usize>,
     pub sep: String,
+    pub case: Option<LetterCase>,
+    pub prefix: String,
+    pub suffix: String,
+    pub line_prefix: String,
+    /// Fail instead of writing when the input is not already formatted.
+    pub check: bool,
 }
 
 impl CommandHandler for FmtCommand {
@@ -225,11 +778,27 @@ impl CommandHandler for FmtCommand {
             wrap: self.wrap,
             group: self.group,
             separator: self.sep.clone(),
+            case: self.case,
+            prefix: self.prefix.clone(),
+            suffix: self.suffix.clone(),
+            line_prefix: self.line_prefix.clone(),
         };
+        if self.check {
+            return match run_fmt_check(ctx, &self.codec, &self.input, self.mode, &opts)? {
+                None => Ok(()),
+                Some(formatted) => Err(MbaseError::invalid_input(format!(
+                    "input is not formatted as {} ({} characters once formatted); run fmt without --check to rewrite it",
+                    self.codec,
+                    formatted.chars().count()
+                ))),
+            };
+        }
         let formatted = run_fmt(ctx, &self.codec, &self.input, self.mode, &opts)?;
         let config = OutputConfig {
             dest: self.output.clone(),
             force: true,
+            digest: None,
+            sparse: false,
         };
         write_output(formatted.as_bytes(), &config)?;
         if matches!(self.output, OutputDest::Stdout) {
@@ -243,29 +812,71 @@ pub struct DetectCommand {
     pub input: InputSource,
     pub json: bool,
     pub top: usize,
+    pub fields: bool,
 }
 
 impl CommandHandler for DetectCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
+        if self.fields {
+            let result = run_detect_fields(ctx, self.input.clone(), self.top)?;
+            if self.json {
+                report::print_json("detect-fields", &result);
+                return Ok(());
+            }
+            println!("{:<20} {:<16} {:<8} DECODES TO", "FIELD", "BEST", "CONF");
+            println!("{}", "-".repeat(60));
+            for field in &result.fields {
+                match field.candidates.first() {
+                    Some(m) => {
+                        let conf = format!("{:.0}%", m.candidate.confidence * 100.0);
+                        let preview = m.preview.as_deref().map_or_else(|| "-".to_string(), |p| format!("\"{}\"", p));
+                        println!("{:<20} {:<16} {:<8} {}", field.name, m.candidate.codec, conf, preview);
+                    }
+                    None => println!("{:<20} {:<16} {:<8} -", field.name, "-", "-"),
+                }
+                for h in &field.hashes {
+                    println!("{:>20} possible hash: {}", "", h.algorithms.join(", "));
+                }
+            }
+            return Ok(());
+        }
         let result = run_detect(ctx, self.input.clone(), self.top)?;
 
         if self.json {
-            println!("{}", serde_json::to_string_pretty(&result).unwrap());
+            report::print_json("detect", &result);
         } else {
             println!("Input: {}", result.input_preview);
+            for b in result.pem.iter().flatten() {
+                println!("PEM:   {} ({} DER bytes)", b.label, b.der_length);
+            }
             println!();
             if result.candidates.is_empty() {
                 println!("No likely codecs detected.");
             } else {
                 println!("{:<16} {:<8} REASONS", "CODEC", "CONF");
                 println!("{}", "-".repeat(60));
-                for c in &result.candidates {
+                for m in &result.candidates {
+                    let c = &m.candidate;
                     let conf = format!("{:.0}%", c.confidence * 100.0);
                     let reasons = c.reasons.join("; ");
                     println!("{:<16} {:<8} {}", c.codec, conf, reasons);
                     for w in &c.warnings {
                         println!("{:>16} warning: {}", "", w);
                     }
+                    if let (Some(preview), Some(len), Some(utf8)) = (&m.preview, m.output_length, m.utf8) {
+                        let kind = if utf8 { "UTF-8" } else { "binary" };
+                        println!("{:>16} decodes to: \"{}\" ({} bytes, {})", "", preview, len, kind);
+                    }
+                }
+            }
+            if !result.hashes.is_empty() {
+                println!();
+                println!("Possible hash digests (shape only, not verified):");
+                for h in &result.hashes {
+                    println!("  {:<40} {}", h.algorithms.join(", "), h.reason);
+                    if let Some(caveat) = h.caveat {
+                        println!("  {:<40} note: {}", "", caveat);
+                    }
                 }
             }
         }
@@ -277,23 +888,47 @@ pub struct ExplainCommand {
     pub codec: String,
     pub input: InputSource,
     pub mode: Mode,
+    pub extract: bool,
     pub json: bool,
 }
 
 impl CommandHandler for ExplainCommand {
     fn execute(&self, ctx: &Context) -> Result<()> {
-        let result = run_explain(ctx, self.input.clone(), &self.codec, self.mode)?;
+        let result = if self.extract {
+            run_explain_extracted(ctx, &self.input, &self.codec, self.mode)?
+        } else {
+            run_explain(ctx, self.input.clone(), &self.codec, self.mode)?
+        };
 
         if self.json {
-            println!("{}", serde_json::to_string_pretty(&result).unwrap());
+            report::print_json("explain", &result);
         } else {
             println!("Codec: {}", result.codec);
             println!("Input: {}", result.input_preview);
+            if let Some(found) = &result.extracted {
+                println!("Extracted: bytes {}..{} of the raw input", found.start, found.end);
+            }
             println!();
 
             if result.valid {
                 println!("Status: VALID");
                 println!("The input is valid for this codec.");
+                if !result.segments.is_empty() {
+                    println!();
+                    println!("Structure:");
+                    for seg in &result.segments {
+                        let text = if seg.text.chars().count() > 40 {
+                            format!("{}...", seg.text.chars().take(40).collect::<String>())
+                        } else {
+                            seg.text.clone()
+                        };
+                        print!("  {:>5}..{:<5} {:<10} {:?}", seg.start, seg.end, seg.kind, text);
+                        match &seg.note {
+                            Some(note) => println!("  ({})", note),
+                            None => println!(),
+                        }
+                    }
+                }
             } else if let Some(ref err) = result.error {
                 println!("Status: INVALID");
                 println!();
@@ -322,3 +957,554 @@ impl CommandHandler for ExplainCommand {
         Ok(())
     }
 }
+
+pub struct AnalyzeCommand {
+    pub codec: String,
+    pub input: InputSource,
+    pub top: usize,
+    pub known_prefix: Option<InputSource>,
+    pub json: bool,
+}
+
+impl CommandHandler for AnalyzeCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        if let Some(prefix) = &self.known_prefix {
+            if !ctx.registry.get(&self.codec)?.name().starts_with("base64") {
+                return Err(MbaseError::invalid_input("--known-prefix recovers base64 alphabets; use --codec base64"));
+            }
+            let result = recover_base64_alphabet(&self.input, &read_input(prefix)?)?;
+            if self.json {
+                report::print_json("analyze-alphabet", &result);
+                return Ok(());
+            }
+            println!("Alphabet: {}", result.alphabet);
+            println!(
+                "Symbols:  {} confirmed by the known prefix, {} guessed, {} unknown",
+                result.confirmed, result.guessed, result.unknown
+            );
+            if let Some(family) = &result.family {
+                println!("Family:   {}", family);
+            }
+            println!("Output:   {}", result.output);
+            if result.unknown == 0 {
+                println!();
+                println!("Decode with: mbase dec --codec base64pad --mode lenient --alphabet '{}'", result.alphabet);
+            }
+            return Ok(());
+        }
+
+        let result = run_analyze(ctx, &self.codec, &self.input, self.top)?;
+
+        if self.json {
+            report::print_json("analyze", &result);
+        } else {
+            println!("Codec: {} ({} keys tried)", result.codec, result.tried);
+            println!("Input: {}", result.input_preview);
+            println!();
+            println!("{:<14} {:<7} OUTPUT", "PARAMS", "SCORE");
+            println!("{}", "-".repeat(60));
+            for c in &result.candidates {
+                println!("{:<14} {:<7} {}", c.params, format!("{:.0}%", c.score * 100.0), c.output);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct DnsCommand {
+    pub input: InputSource,
+    pub to: Option<String>,
+    pub json: bool,
+}
+
+impl CommandHandler for DnsCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_dns(ctx, &self.input, self.to.as_deref())?;
+
+        if self.json {
+            report::print_json("dns", &result);
+            return Ok(());
+        }
+
+        for (i, rec) in result.records.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("{} {}", rec.owner, rec.rtype);
+            for f in &rec.fields {
+                match (&f.encoding, f.length) {
+                    (Some(enc), Some(len)) => {
+                        println!("  {:<18} {} ({}, {} bytes)", f.name, f.value, enc, len);
+                        println!("  {:<18} hex: {}", "", f.hex.as_deref().unwrap_or_default());
+                        if let Some(ref converted) = f.converted {
+                            println!("  {:<18} converted: {}", "", converted);
+                        }
+                    }
+                    _ => println!("  {:<18} {}", f.name, f.value),
+                }
+            }
+            for w in &rec.warnings {
+                println!("  warning: {}", w);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct SriCommand {
+    pub input: InputSource,
+    pub algorithm: SriAlgorithm,
+    pub verify: Option<String>,
+    pub json: bool,
+}
+
+impl CommandHandler for SriCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_sri(ctx, &self.input, self.algorithm, self.verify.as_deref())?;
+
+        if self.json {
+            report::print_json("sri", &result);
+        } else if let Some(verified) = result.verified {
+            if verified {
+                println!("valid: {}", result.integrity);
+            } else {
+                println!("mismatch: input hashes to {}", result.integrity);
+            }
+        } else {
+            println!("{}", result.integrity);
+        }
+
+        if result.verified == Some(false) {
+            return Err(mbase::error::MbaseError::ChecksumMismatch);
+        }
+        Ok(())
+    }
+}
+
+pub struct CrcCommand {
+    pub input: InputSource,
+    pub algorithm: Checksum,
+    pub decode: Option<String>,
+    pub mode: Mode,
+    pub to: String,
+    pub json: bool,
+}
+
+impl CommandHandler for CrcCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_crc(ctx, &self.input, self.algorithm, self.decode.as_deref(), self.mode, &self.to)?;
+        if self.json {
+            report::print_json("crc", &result);
+        } else {
+            println!("{}", result.output);
+        }
+        Ok(())
+    }
+}
+
+pub struct CmpCommand {
+    pub codec_a: String,
+    pub input_a: InputSource,
+    pub codec_b: String,
+    pub input_b: InputSource,
+    pub mode: Mode,
+    pub json: bool,
+}
+
+impl CommandHandler for CmpCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_cmp(ctx, (&self.codec_a, &self.input_a), (&self.codec_b, &self.input_b), self.mode)?;
+        if self.json {
+            report::print_json("cmp", &result);
+            return Ok(());
+        }
+        let Some(offset) = result.first_difference else {
+            println!("identical: {} bytes", result.length_a);
+            return Ok(());
+        };
+        println!(
+            "differ: byte {} (a: {} bytes {}, b: {} bytes {}), {} bytes differ",
+            offset, result.length_a, result.codec_a, result.length_b, result.codec_b, result.differing_bytes
+        );
+        if let Some(context) = &result.context {
+            println!("  from offset {}:", context.offset);
+            println!("  a: {}", context.a);
+            println!("  b: {}", context.b);
+        }
+        Err(MbaseError::invalid_input(format!("inputs differ at byte {}", offset)))
+    }
+}
+
+pub struct BitsCommand {
+    pub input: InputSource,
+    pub output: OutputDest,
+    pub ops: Vec<BitOp>,
+    pub force: bool,
+}
+
+impl CommandHandler for BitsCommand {
+    fn execute(&self, _ctx: &Context) -> Result<()> {
+        let data = run_bits(&self.input, &self.ops)?;
+        let config = OutputConfig {
+            dest: self.output.clone(),
+            force: self.force,
+            digest: None,
+            sparse: false,
+        };
+        write_output(&data, &config)
+    }
+}
+
+pub struct RadixCommand {
+    pub input: InputSource,
+    pub from: Radix,
+    pub to: Radix,
+    pub json: bool,
+}
+
+impl CommandHandler for RadixCommand {
+    fn execute(&self, _ctx: &Context) -> Result<()> {
+        let result = run_radix(&self.input, &self.from, &self.to)?;
+        if self.json {
+            report::print_json("radix", &result);
+        } else {
+            for value in &result.values {
+                println!("{}", value.output);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct QrCommand {
+    pub input: InputSource,
+    pub codecs: Vec<String>,
+    pub json: bool,
+}
+
+impl CommandHandler for QrCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_qr(ctx, &self.input, &self.codecs)?;
+        if self.json {
+            report::print_json("qr", &result);
+            return Ok(());
+        }
+        println!("{:<16} {:<13} {:>7}  {:>3} {:>3} {:>3} {:>3}", "PAYLOAD", "MODE", "LENGTH", "L", "M", "Q", "H");
+        for p in &result.payloads {
+            let versions: Vec<String> = p
+                .versions
+                .iter()
+                .map(|v| v.version.map_or_else(|| "-".to_string(), |v| v.to_string()))
+                .collect();
+            println!(
+                "{:<16} {:<13} {:>7}  {:>3} {:>3} {:>3} {:>3}",
+                p.source,
+                p.mode.name(),
+                p.length,
+                versions[0],
+                versions[1],
+                versions[2],
+                versions[3]
+            );
+        }
+        for p in &result.payloads {
+            if let Some(o) = &p.offending {
+                eprintln!(
+                    "note: {}: {:?} at position {} is outside the QR alphanumeric set (0-9 A-Z space $%*+-./:)",
+                    p.source, o.char, o.position
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct RandCommand {
+    pub codec: String,
+    pub bytes: usize,
+    pub opts: CodecOptions,
+    pub json: bool,
+}
+
+impl CommandHandler for RandCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_rand(ctx, &self.codec, self.bytes, &self.opts)?;
+
+        if self.json {
+            report::print_json("rand", &result);
+        } else {
+            println!("{}", result.output);
+        }
+        Ok(())
+    }
+}
+
+pub struct JwtCommand {
+    pub input: InputSource,
+    pub mode: Mode,
+    pub json: bool,
+}
+
+impl CommandHandler for JwtCommand {
+    fn execute(&self, _ctx: &Context) -> Result<()> {
+        let result = run_jwt(&self.input, self.mode)?;
+        if self.json {
+            report::print_json("jwt", &result);
+            return Ok(());
+        }
+        for segment in &result.segments {
+            match (&segment.json, &segment.hex) {
+                (Some(json), _) => println!("{:<14} {}", segment.role, json),
+                (None, hex) => println!("{:<14} {} bytes: {}", segment.role, segment.length, hex.as_deref().unwrap_or_default()),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct HcertCommand {
+    pub input: InputSource,
+    pub json: bool,
+}
+
+impl CommandHandler for HcertCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_hcert(ctx, &self.input)?;
+
+        if self.json {
+            report::print_json("hcert", &result);
+            return Ok(());
+        }
+
+        let na = "-".to_string();
+        println!("prefix:     {}", result.prefix.as_deref().unwrap_or("(none)"));
+        println!("base45:     {} chars", result.base45_length);
+        println!("zlib:       {}", if result.compressed { "yes" } else { "no" });
+        println!("cbor:       {} bytes", result.cbor_length);
+        println!("algorithm:  {}", result.algorithm.as_ref().unwrap_or(&na));
+        println!("kid:        {}", result.kid.as_ref().unwrap_or(&na));
+        println!("signature:  {} bytes", result.signature_length);
+        println!("issuer:     {}", result.issuer.as_ref().unwrap_or(&na));
+        println!("issued at:  {}", result.issued_at.as_ref().unwrap_or(&na));
+        println!("expires at: {}", result.expires_at.as_ref().unwrap_or(&na));
+        println!();
+        println!("{}", serde_json::to_string_pretty(&result.payload).unwrap());
+        for w in &result.warnings {
+            eprintln!("warning: {}", w);
+        }
+        Ok(())
+    }
+}
+
+pub struct KeyCommand {
+    pub input: InputSource,
+    pub json: bool,
+}
+
+impl CommandHandler for KeyCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_key(ctx, &self.input)?;
+
+        if self.json {
+            report::print_json("key", &result);
+            return Ok(());
+        }
+
+        for (i, key) in result.keys.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            let bits = key.bits.map(|b| b.to_string()).unwrap_or_else(|| "-".to_string());
+            println!("type:        {} ({})", key.key_type, key.format);
+            println!("algorithm:   {}", key.algorithm);
+            println!("bits:        {}", bits);
+            println!("fingerprint: {}", key.fingerprint.as_deref().unwrap_or("-"));
+            if let Some(comment) = &key.comment {
+                println!("comment:     {}", comment);
+            }
+            for field in &key.fields {
+                println!("  {:<13} {:>4} bytes  {}", field.name, field.length, field.value);
+            }
+            for w in &key.warnings {
+                eprintln!("warning: {}", w);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct LintCodecCommand {
+    pub alphabet: String,
+    pub opts: LintOptions,
+    pub json: bool,
+}
+
+impl CommandHandler for LintCodecCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let result = run_lint_codec(ctx, &self.alphabet, &self.opts);
+        if self.json {
+            report::print_json("lint-codec", &result);
+        } else {
+            for i in &result.issues {
+                let label = match i.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                println!("{:<8} {:<11} {}", label, i.kind, i.message);
+            }
+            if result.issues.is_empty() {
+                println!("ok: {} symbols, no issues", result.symbols);
+            }
+        }
+        if !result.valid {
+            let errors = result.issues.iter().filter(|i| i.severity == Severity::Error).count();
+            return Err(MbaseError::invalid_input(format!("alphabet has {} error(s)", errors)));
+        }
+        Ok(())
+    }
+}
+
+pub struct SchemaCommand {
+    pub report: Option<String>,
+}
+
+impl CommandHandler for SchemaCommand {
+    fn execute(&self, _ctx: &Context) -> Result<()> {
+        match &self.report {
+            Some(name) => println!("{}", serde_json::to_string_pretty(&report::run_schema(name)?).unwrap()),
+            None => {
+                for (name, command, _) in report::REPORTS {
+                    println!("{:<18} mbase {}", name, command);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The recipe file, `$MBASE_RECIPES` or `recipes` in the mbase config directory.
+fn recipe_store() -> Result<std::path::PathBuf> {
+    recipes_path().ok_or_else(|| MbaseError::invalid_input("no recipe file (set MBASE_RECIPES or HOME)"))
+}
+
+pub struct RecipesCommand {
+    /// Save `steps` under this name instead of listing.
+    pub save: Option<String>,
+    pub steps: Vec<String>,
+    pub import: Option<std::path::PathBuf>,
+    /// File to export to; `-` for stdout.
+    pub export: Option<String>,
+    pub names: Vec<String>,
+    pub replace: bool,
+    pub json: bool,
+}
+
+impl CommandHandler for RecipesCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let store = recipe_store()?;
+        if let Some(name) = &self.save {
+            let recipe = run_recipes_save(ctx, &store, name, &self.steps, self.replace)?;
+            println!("saved {} ({} step(s)) to {}", recipe.name, recipe.steps.len(), store.display());
+            return Ok(());
+        }
+        if let Some(file) = &self.import {
+            let count = run_recipes_import(ctx, &store, file, self.replace)?;
+            println!("imported {} recipe(s) into {}", count, store.display());
+            return Ok(());
+        }
+        if let Some(file) = &self.export {
+            let recipes = run_recipes_export(&store, &self.names)?;
+            if file == "-" {
+                print!("{}", crate::io::format_recipes(&recipes));
+            } else {
+                write_recipes(std::path::Path::new(file), &recipes)?;
+                println!("exported {} recipe(s) to {}", recipes.len(), file);
+            }
+            return Ok(());
+        }
+        let result = run_recipes(&store)?;
+        if self.json {
+            report::print_json("recipes", &result);
+            return Ok(());
+        }
+        if result.recipes.is_empty() {
+            println!("no recipes in {} (add one with `mbase recipes --save NAME --step ...`)", result.path);
+        }
+        let width = result.recipes.iter().map(|r| r.name.len()).max().unwrap_or(0);
+        for recipe in &result.recipes {
+            println!("{:<width$}  {}", recipe.name, recipe.steps.join(" | "), width = width);
+        }
+        Ok(())
+    }
+}
+
+pub struct RunCommand {
+    pub recipe: String,
+    pub input: InputSource,
+    pub output: OutputDest,
+    pub mode: Mode,
+    pub force: bool,
+}
+
+impl CommandHandler for RunCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        let recipe = find_recipe(&recipe_store()?, &self.recipe)?;
+        let data = run_recipe(ctx, &recipe, &self.input, self.mode)?;
+        let config = OutputConfig {
+            dest: self.output.clone(),
+            force: self.force,
+            digest: None,
+            sparse: false,
+        };
+        write_output(&data, &config)
+    }
+}
+
+pub struct RegressCommand {
+    pub corpus: std::path::PathBuf,
+    /// Record the input as a new case with this name instead of replaying.
+    pub add: Option<String>,
+    pub input: InputSource,
+    pub notes: Vec<String>,
+    pub update: bool,
+    pub json: bool,
+}
+
+impl CommandHandler for RegressCommand {
+    fn execute(&self, ctx: &Context) -> Result<()> {
+        if let Some(name) = &self.add {
+            let case = run_regress_add(ctx, &self.corpus, name, &self.input, &self.notes)?;
+            println!("added {} ({} outcomes recorded)", case.name, case.expected.len());
+            return Ok(());
+        }
+        if self.update {
+            let count = run_regress_update(ctx, &self.corpus)?;
+            println!("updated {} case(s)", count);
+            return Ok(());
+        }
+        let result = run_regress(ctx, &self.corpus)?;
+        if self.json {
+            report::print_json("regress", &result);
+        } else {
+            for d in &result.divergences {
+                let expected = d.expected.as_deref().unwrap_or("(not recorded)");
+                let actual = match (&d.actual, &d.panic) {
+                    (_, Some(message)) => format!("panic: {}", message),
+                    (Some(actual), None) => actual.clone(),
+                    (None, None) => "(codec not registered)".to_string(),
+                };
+                // As the line reads in the case file: `dec CODEC MODE`.
+                let probe = match d.operation.split_once(' ') {
+                    Some((op, mode)) => format!("{} {} {}", op, d.codec, mode),
+                    None => format!("{} {}", d.operation, d.codec),
+                };
+                println!("{}: {}: expected {}, got {}", d.case, probe, expected, actual);
+            }
+            println!("{} case(s), {} divergence(s)", result.cases, result.divergences.len());
+        }
+        if !result.passed {
+            return Err(MbaseError::invalid_input(format!("{} divergence(s) from the corpus", result.divergences.len())));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use serde::Serialize;
+
+use super::report::{QrFit, QrOffending, QrPayload, QrResult};
+use crate::io::read_input;
+use mbase::error::Result;
+use mbase::types::{CodecOptions, Context, InputSource};
+
+/// The QR alphanumeric character set, in value order. Base45 uses it as its alphabet.
+pub const QR_ALPHANUMERIC: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+const LEVELS: [&str; 4] = ["L", "M", "Q", "H"];
+
+/// Error-correction codewords per block, by level and version (index 0 unused).
+const ECC_PER_BLOCK: [[u16; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30,
+        30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+        28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30,
+        30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+        30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// Error-correction blocks, by level and version (index 0 unused).
+const ECC_BLOCKS: [[u16; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22,
+        24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38,
+        40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53,
+        56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60,
+        63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// Encoding mode a QR encoder picks for a payload sent as one segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrMode {
+    Numeric,
+    Alphanumeric,
+    Byte,
+}
+
+impl QrMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            QrMode::Numeric => "numeric",
+            QrMode::Alphanumeric => "alphanumeric",
+            QrMode::Byte => "byte",
+        }
+    }
+
+    /// Bits of the character count field, which widens at versions 10 and 27.
+    fn count_bits(self, version: usize) -> usize {
+        let range = match version {
+            1..=9 => 0,
+            10..=26 => 1,
+            _ => 2,
+        };
+        match self {
+            QrMode::Numeric => [10, 12, 14][range],
+            QrMode::Alphanumeric => [9, 11, 13][range],
+            QrMode::Byte => [8, 16, 16][range],
+        }
+    }
+
+    fn data_bits(self, len: usize) -> usize {
+        match self {
+            QrMode::Numeric => len / 3 * 10 + [0, 4, 7][len % 3],
+            QrMode::Alphanumeric => len / 2 * 11 + len % 2 * 6,
+            QrMode::Byte => len * 8,
+        }
+    }
+}
+
+/// Data codewords of a symbol: its data modules less the error-correction codewords.
+fn data_codewords(version: usize, level: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let align = version / 7 + 2;
+        modules -= (25 * align - 10) * align - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules / 8 - usize::from(ECC_PER_BLOCK[level][version] * ECC_BLOCKS[level][version])
+}
+
+/// Smallest version (1-40) holding `len` characters in `mode` at `level`.
+fn smallest_version(mode: QrMode, len: usize, level: usize) -> Option<u32> {
+    (1..=40)
+        .find(|&version| 4 + mode.count_bits(version) + mode.data_bits(len) <= data_codewords(version, level) * 8)
+        .map(|version| version as u32)
+}
+
+fn payload(source: String, text: &[u8]) -> QrPayload {
+    let offending = String::from_utf8_lossy(text)
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !QR_ALPHANUMERIC.contains(*c))
+        .map(|(position, char)| QrOffending { char, position });
+    let mode = if !text.is_empty() && text.iter().all(u8::is_ascii_digit) {
+        QrMode::Numeric
+    } else if offending.is_none() {
+        QrMode::Alphanumeric
+    } else {
+        QrMode::Byte
+    };
+    let versions = LEVELS
+        .iter()
+        .enumerate()
+        .map(|(level, name)| {
+            let version = smallest_version(mode, text.len(), level);
+            QrFit {
+                level: name,
+                version,
+                modules: version.map(|v| 17 + 4 * v),
+            }
+        })
+        .collect();
+    QrPayload {
+        source,
+        length: text.len(),
+        mode,
+        alphanumeric: offending.is_none(),
+        offending,
+        versions,
+    }
+}
+
+/// Sizes the input as a QR payload, or with `codecs` each encoding of it, to compare them.
+pub fn run_qr(ctx: &Context, input: &InputSource, codecs: &[String]) -> Result<QrResult> {
+    let data = read_input(input)?;
+    if codecs.is_empty() {
+        let text = data.strip_suffix(b"\n").map_or(&data[..], |t| t.strip_suffix(b"\r").unwrap_or(t));
+        return Ok(QrResult {
+            payloads: vec![payload("input".to_string(), text)],
+        });
+    }
+    let payloads = codecs
+        .iter()
+        .map(|name| {
+            let codec = ctx.registry.get(name)?;
+            Ok(payload(codec.name().to_string(), ctx.encode(codec, &data, &CodecOptions::new())?.as_bytes()))
+        })
+        .collect::<Result<_>>()?;
+    Ok(QrResult { payloads })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(p: &QrPayload) -> Vec<Option<u32>> {
+        p.versions.iter().map(|v| v.version).collect()
+    }
+
+    #[test]
+    fn test_qr_capacity_limits() {
+        // Largest payloads of versions 1 and 40 in the ISO/IEC 18004 capacity table.
+        assert_eq!(data_codewords(1, 0), 19);
+        assert_eq!(data_codewords(40, 3), 1276);
+        for (level, (v1, v40)) in [(25, 4296), (20, 3391), (16, 2420), (10, 1852)].into_iter().enumerate() {
+            assert_eq!(smallest_version(QrMode::Alphanumeric, v1, level), Some(1));
+            assert_eq!(smallest_version(QrMode::Alphanumeric, v1 + 1, level), Some(2));
+            assert_eq!(smallest_version(QrMode::Alphanumeric, v40, level), Some(40));
+            assert_eq!(smallest_version(QrMode::Alphanumeric, v40 + 1, level), None);
+        }
+        assert_eq!(smallest_version(QrMode::Byte, 2953, 0), Some(40));
+        assert_eq!(smallest_version(QrMode::Numeric, 41, 0), Some(1));
+    }
+
+    #[test]
+    fn test_qr_modes() {
+        let p = payload("input".into(), b"HC1:6BF+70790T9WJWG.FKY*4GO0.O1CV2");
+        assert_eq!((p.mode, p.alphanumeric), (QrMode::Alphanumeric, true));
+        assert_eq!(versions(&p), [Some(2), Some(2), Some(3), Some(3)]);
+
+        let p = payload("input".into(), b"Hello");
+        assert_eq!(p.mode, QrMode::Byte);
+        let offending = p.offending.unwrap();
+        assert_eq!((offending.char, offending.position), ('e', 1));
+        assert_eq!(payload("input".into(), b"0123").mode, QrMode::Numeric);
+    }
+
+    #[test]
+    fn test_qr_compare_codecs() {
+        let ctx = Context::default();
+        assert_eq!(ctx.registry.get("base45").unwrap().meta().alphabet, QR_ALPHANUMERIC);
+        let input = InputSource::Literal(vec![0xa5; 200]);
+        let result = run_qr(&ctx, &input, &["base45".into(), "base64".into()]).unwrap();
+        let (base45, base64) = (&result.payloads[0], &result.payloads[1]);
+        assert_eq!((base45.mode, base45.length), (QrMode::Alphanumeric, 300));
+        assert_eq!((base64.mode, base64.length), (QrMode::Byte, 267));
+        assert!(versions(base45) < versions(base64));
+
+        let result = run_qr(&ctx, &InputSource::Literal(b"HELLO\r\n".to_vec()), &[]).unwrap();
+        assert_eq!(result.payloads[0].length, 5);
+        assert!(run_qr(&ctx, &input, &["nope".into()]).is_err());
+    }
+}
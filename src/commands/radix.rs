@@ -0,0 +1,206 @@
+use super::report::{RadixResult, RadixValue};
+use crate::io::read_input;
+use mbase::error::{MbaseError, Result};
+use mbase::types::InputSource;
+
+/// Digits of the standard radixes: up to 36 case-insensitive `0-9a-z`, above that `0-9A-Za-z`
+/// (the `base62` order) followed by `-_`.
+const LOWER_DIGITS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+const MIXED_DIGITS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+
+/// A number base and its digits, in value order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Radix {
+    digits: Vec<char>,
+    case_insensitive: bool,
+}
+
+impl Radix {
+    /// Base `radix` (2-64) with the standard digits, or the base of `alphabet`; when both are
+    /// given they must agree. Neither means decimal.
+    pub fn new(radix: Option<u32>, alphabet: Option<&str>) -> Result<Self> {
+        let Some(alphabet) = alphabet else {
+            let radix = radix.unwrap_or(10);
+            if !(2..=64).contains(&radix) {
+                return Err(MbaseError::invalid_input(format!("radix {} is out of range (2-64)", radix)));
+            }
+            let digits = if radix <= 36 { LOWER_DIGITS } else { MIXED_DIGITS };
+            return Ok(Self {
+                digits: digits.chars().take(radix as usize).collect(),
+                case_insensitive: radix <= 36,
+            });
+        };
+        let digits: Vec<char> = alphabet.chars().collect();
+        if !(2..=64).contains(&digits.len()) {
+            return Err(MbaseError::invalid_input(format!("alphabet has {} digits; a radix needs 2-64", digits.len())));
+        }
+        if let Some((i, c)) = digits.iter().enumerate().find(|(i, c)| digits[..*i].contains(c)) {
+            return Err(MbaseError::invalid_input(format!("alphabet repeats '{}' at position {}", c, i)));
+        }
+        if let Some(radix) = radix.filter(|&r| r as usize != digits.len()) {
+            return Err(MbaseError::invalid_input(format!("radix {} does not match the {}-digit alphabet", radix, digits.len())));
+        }
+        Ok(Self {
+            digits,
+            case_insensitive: false,
+        })
+    }
+
+    pub fn radix(&self) -> u32 {
+        self.digits.len() as u32
+    }
+
+    fn value(&self, c: char) -> Option<u32> {
+        let c = if self.case_insensitive { c.to_ascii_lowercase() } else { c };
+        self.digits.iter().position(|&d| d == c).map(|v| v as u32)
+    }
+
+    /// The `0x`/`0o`/`0b` prefix a standard base 16, 8 or 2 number may carry.
+    fn prefix(&self) -> Option<&'static str> {
+        if !self.case_insensitive {
+            return None;
+        }
+        match self.radix() {
+            16 => Some("0x"),
+            8 => Some("0o"),
+            2 => Some("0b"),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrites the integer `number` (at byte `offset` of the input, for error positions) from one
+/// radix to another. A leading `-` is kept, leading zeros are dropped, and `_` groups digits
+/// unless it is a digit itself.
+fn convert(number: &str, offset: usize, from: &Radix, to: &Radix) -> Result<String> {
+    let (negative, mut body, mut start) = match number.strip_prefix('-') {
+        Some(rest) if from.value('-').is_none() => (true, rest, offset + 1),
+        _ => (false, number, offset),
+    };
+    if let Some(prefix) = from.prefix() {
+        if body.len() > 2 && body.get(..2).is_some_and(|p| p.eq_ignore_ascii_case(prefix)) {
+            body = &body[2..];
+            start += 2;
+        }
+    }
+    // Output digits, least significant first.
+    let mut out: Vec<u32> = Vec::new();
+    let mut seen = false;
+    for (i, c) in body.char_indices() {
+        let Some(digit) = from.value(c) else {
+            if c == '_' && seen {
+                continue;
+            }
+            return Err(MbaseError::invalid_input_at(start + i, format!("'{}' is not a base-{} digit", c, from.radix())));
+        };
+        seen = true;
+        let mut carry = digit;
+        for d in out.iter_mut() {
+            carry += *d * from.radix();
+            *d = carry % to.radix();
+            carry /= to.radix();
+        }
+        while carry > 0 {
+            out.push(carry % to.radix());
+            carry /= to.radix();
+        }
+    }
+    if !seen {
+        return Err(MbaseError::invalid_input_at(start, format!("'{}' has no base-{} digits", number, from.radix())));
+    }
+    if out.is_empty() {
+        out.push(0);
+    }
+    let sign = if negative && out != [0] { "-" } else { "" };
+    Ok(sign.chars().chain(out.iter().rev().map(|&d| to.digits[d as usize])).collect())
+}
+
+/// Converts each whitespace-separated integer of the input from one radix to another.
+pub fn run_radix(input: &InputSource, from: &Radix, to: &Radix) -> Result<RadixResult> {
+    let data = read_input(input)?;
+    let text = core::str::from_utf8(&data).map_err(|e| MbaseError::invalid_input_at(e.valid_up_to(), "input is not UTF-8"))?;
+    let mut values = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        let token = &rest[start..];
+        let end = token.find(char::is_whitespace).unwrap_or(token.len());
+        let offset = text.len() - rest.len() + start;
+        values.push(RadixValue {
+            input: token[..end].to_string(),
+            output: convert(&token[..end], offset, from, to)?,
+        });
+        rest = &token[end..];
+    }
+    if values.is_empty() {
+        return Err(MbaseError::invalid_input("no number to convert"));
+    }
+    Ok(RadixResult {
+        from: from.radix(),
+        to: to.radix(),
+        values,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radix(n: u32) -> Radix {
+        Radix::new(Some(n), None).unwrap()
+    }
+
+    fn conv(number: &str, from: u32, to: u32) -> Result<String> {
+        convert(number, 0, &radix(from), &radix(to))
+    }
+
+    #[test]
+    fn test_standard_radixes() {
+        assert_eq!(conv("DEADBEEF", 16, 10).unwrap(), "3735928559");
+        assert_eq!(conv("deadbeef", 16, 36).unwrap(), "1ps9wxb");
+        assert_eq!(conv("0xff", 16, 2).unwrap(), "11111111");
+        assert_eq!(conv("255", 10, 62).unwrap(), "47");
+        assert_eq!(conv("63", 10, 64).unwrap(), "_");
+        assert_eq!(conv("000", 10, 16).unwrap(), "0");
+        assert_eq!(conv("-1_000", 10, 16).unwrap(), "-3e8");
+        assert_eq!(conv("-0", 10, 2).unwrap(), "0");
+        let big = "340282366920938463463374607431768211455";
+        assert_eq!(conv(big, 10, 16).unwrap(), "f".repeat(32));
+        assert_eq!(conv(&"f".repeat(32), 16, 10).unwrap(), big);
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        let err = conv("12a", 10, 16).unwrap_err().to_string();
+        assert!(err.contains("'a' is not a base-10 digit"), "{}", err);
+        assert!(conv("", 10, 16).is_err());
+        assert!(conv("_1", 10, 16).is_err());
+        assert_ne!(conv("Ab", 62, 10).unwrap(), conv("ab", 62, 10).unwrap());
+        for from in [2, 8, 16] {
+            let err = conv("€1", from, 10).unwrap_err().to_string();
+            assert!(err.contains("position 0"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_custom_alphabets() {
+        assert!(Radix::new(Some(65), None).is_err());
+        assert!(Radix::new(None, Some("aa")).is_err());
+        assert!(Radix::new(Some(3), Some("01")).is_err());
+        let binary = Radix::new(None, Some("-+")).unwrap();
+        assert_eq!(convert("5", 0, &radix(10), &binary).unwrap(), "+-+");
+        assert_eq!(convert("+-+", 0, &binary, &radix(10)).unwrap(), "5");
+        let dna = Radix::new(Some(4), Some("ACGT")).unwrap();
+        assert_eq!(convert("GATTACA", 0, &dna, &radix(16)).unwrap(), "23c4");
+    }
+
+    #[test]
+    fn test_run_radix_tokens() {
+        let input = InputSource::Literal(b"ff 10\n  7f\n".to_vec());
+        let result = run_radix(&input, &radix(16), &radix(10)).unwrap();
+        let outputs: Vec<&str> = result.values.iter().map(|v| v.output.as_str()).collect();
+        assert_eq!(outputs, ["255", "16", "127"]);
+        let err = run_radix(&InputSource::Literal(b"ff zz".to_vec()), &radix(16), &radix(10)).unwrap_err();
+        assert!(matches!(err, MbaseError::InvalidInputAt { position: 3, .. }), "{:?}", err);
+        assert!(run_radix(&InputSource::Literal(b" \n".to_vec()), &radix(16), &radix(10)).is_err());
+    }
+}
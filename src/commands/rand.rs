@@ -0,0 +1,95 @@
+use super::report::RandResult;
+use mbase::error::Result;
+use mbase::types::{CodecOptions, Context};
+
+/// SplitMix64: tiny, well-distributed and stable across platforms, so a seed
+/// always reproduces the same bytes. Only used for `--seed`; not suitable for key material.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let word = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+}
+
+pub fn random_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    SplitMix64(seed).fill(&mut buf);
+    buf
+}
+
+pub fn run_rand(ctx: &Context, codec_name: &str, bytes: usize, opts: &CodecOptions) -> Result<RandResult> {
+    let codec = ctx.registry.get(codec_name)?;
+    // No encoding is shorter than its input, so refuse before generating bytes that cannot fit.
+    ctx.check_output_size(codec.encoded_len(bytes, opts).unwrap_or(bytes))?;
+    let data = match ctx.seed {
+        Some(seed) => random_bytes(seed, bytes),
+        None => {
+            let mut buf = vec![0u8; bytes];
+            getrandom::getrandom(&mut buf).map_err(std::io::Error::from)?;
+            buf
+        }
+    };
+    let output = ctx.encode(codec, &data, opts)?;
+
+    Ok(RandResult {
+        seed: ctx.seed,
+        codec: codec.name().to_string(),
+        bytes,
+        output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mbase::error::MbaseError;
+
+    #[test]
+    fn test_rand_seed_is_reproducible() {
+        let ctx = Context::default().with_seed(Some(42));
+        let a = run_rand(&ctx, "hex", 20, &CodecOptions::new()).unwrap();
+        let b = run_rand(&ctx, "hex", 20, &CodecOptions::new()).unwrap();
+        assert_eq!(a.output, b.output);
+        assert_eq!(a.seed, Some(42));
+        assert_eq!(a.output.len(), 40);
+
+        let c = run_rand(&Context::default().with_seed(Some(43)), "hex", 20, &CodecOptions::new()).unwrap();
+        assert_ne!(a.output, c.output);
+    }
+
+    #[test]
+    fn test_rand_known_vector() {
+        // First SplitMix64 output for seed 0.
+        assert_eq!(random_bytes(0, 8), 0xE220_A839_7B1D_CDAFu64.to_le_bytes());
+        assert_eq!(random_bytes(7, 5), random_bytes(7, 16)[..5]);
+    }
+
+    #[test]
+    fn test_rand_checks_limit_before_generating() {
+        let ctx = Context::default().with_seed(Some(1)).with_max_output_size(Some(10));
+        assert!(run_rand(&ctx, "hex", 5, &CodecOptions::new()).is_ok());
+        let err = run_rand(&ctx, "base64", usize::MAX, &CodecOptions::new()).unwrap_err();
+        assert!(matches!(err, MbaseError::OutputTooLarge { limit: 10, .. }));
+    }
+
+    #[test]
+    fn test_rand_unseeded_uses_os_randomness() {
+        let a = run_rand(&Context::default(), "hex", 32, &CodecOptions::new()).unwrap();
+        let b = run_rand(&Context::default(), "hex", 32, &CodecOptions::new()).unwrap();
+        assert_eq!(a.seed, None);
+        assert_eq!(a.output.len(), 64);
+        assert_ne!(a.output, b.output);
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use super::report::{RecipeEntry, RecipesResult};
+use crate::io::{parse_recipes, read_input, read_recipes, write_recipes, Recipe, RecipeStep};
+use mbase::error::{MbaseError, Result};
+use mbase::types::{Context, InputSource, Mode};
+
+/// Applies one step to the output of the previous one.
+fn apply_step(ctx: &Context, step: &RecipeStep, data: Vec<u8>, mode: Mode) -> Result<Vec<u8>> {
+    let opts = step.options()?;
+    match step {
+        RecipeStep::Enc { codec, .. } => Ok(ctx.encode(ctx.registry.get(codec)?, &data, &opts)?.into_bytes()),
+        RecipeStep::Dec { codec, .. } => {
+            let text = std::str::from_utf8(&data)
+                .map_err(|e| MbaseError::invalid_input_at(e.valid_up_to(), format!("{} needs text, but its input is not UTF-8", step)))?;
+            ctx.decode(ctx.registry.get(codec)?, text, mode, &opts)
+        }
+        RecipeStep::Bits(op) => op.apply(&data),
+    }
+}
+
+/// `run`: feeds the input through every step of `recipe`. Errors name the step that failed.
+pub fn run_recipe(ctx: &Context, recipe: &Recipe, input: &InputSource, mode: Mode) -> Result<Vec<u8>> {
+    recipe.steps.iter().enumerate().try_fold(read_input(input)?, |data, (i, step)| {
+        apply_step(ctx, step, data, mode)
+            .map_err(|e| MbaseError::invalid_input(format!("recipe '{}' step {} ({}): {}", recipe.name, i + 1, step, e)))
+    })
+}
+
+/// The recipe called `name` in the recipe file at `store`.
+pub fn find_recipe(store: &Path, name: &str) -> Result<Recipe> {
+    read_recipes(store)?
+        .into_iter()
+        .find(|r| r.name == name)
+        .ok_or_else(|| MbaseError::invalid_input(format!("no recipe '{}' in {} (see `mbase recipes`)", name, store.display())))
+}
+
+/// Rejects steps naming codecs that are not registered, so typos surface when saving.
+fn check_codecs(ctx: &Context, recipe: &Recipe) -> Result<()> {
+    for codec in recipe.steps.iter().filter_map(RecipeStep::codec) {
+        ctx.registry
+            .get(codec)
+            .map_err(|e| MbaseError::invalid_input(format!("recipe '{}': {}", recipe.name, e)))?;
+    }
+    Ok(())
+}
+
+/// Adds `recipes` to the store, replacing same-named ones only when `replace` is set.
+fn merge(ctx: &Context, store: &Path, recipes: Vec<Recipe>, replace: bool) -> Result<usize> {
+    let mut saved = read_recipes(store)?;
+    for recipe in &recipes {
+        check_codecs(ctx, recipe)?;
+        if !replace && saved.iter().any(|r| r.name == recipe.name && r != recipe) {
+            return Err(MbaseError::invalid_input(format!(
+                "recipe '{}' already exists in {}; use --replace to overwrite it",
+                recipe.name,
+                store.display()
+            )));
+        }
+    }
+    let count = recipes.len();
+    for recipe in recipes {
+        match saved.iter_mut().find(|r| r.name == recipe.name) {
+            Some(existing) => *existing = recipe,
+            None => saved.push(recipe),
+        }
+    }
+    write_recipes(store, &saved)?;
+    Ok(count)
+}
+
+/// `recipes`: the recipes saved in `store`.
+pub fn run_recipes(store: &Path) -> Result<RecipesResult> {
+    let recipes = read_recipes(store)?
+        .into_iter()
+        .map(|r| RecipeEntry {
+            name: r.name,
+            steps: r.steps.iter().map(ToString::to_string).collect(),
+        })
+        .collect();
+    Ok(RecipesResult {
+        path: store.display().to_string(),
+        recipes,
+    })
+}
+
+/// `recipes --save NAME --step ...`: saves a pipeline under `name`.
+pub fn run_recipes_save(ctx: &Context, store: &Path, name: &str, steps: &[String], replace: bool) -> Result<Recipe> {
+    if name.is_empty() || name.starts_with('#') || name.contains(char::is_whitespace) {
+        return Err(MbaseError::invalid_input(format!("'{}' is not a usable recipe name", name)));
+    }
+    let recipe = Recipe {
+        name: name.to_string(),
+        steps: steps.iter().map(|s| RecipeStep::parse(s)).collect::<Result<_>>()?,
+    };
+    merge(ctx, store, vec![recipe.clone()], replace)?;
+    Ok(recipe)
+}
+
+/// `recipes --import FILE`: adds the recipes of a shared file. Returns how many it held.
+pub fn run_recipes_import(ctx: &Context, store: &Path, file: &Path, replace: bool) -> Result<usize> {
+    let text = std::fs::read_to_string(file)?;
+    let recipes = parse_recipes(&text).map_err(|e| MbaseError::invalid_input(format!("{}: {}", file.display(), e)))?;
+    merge(ctx, store, recipes, replace)
+}
+
+/// `recipes --export`: the recipes called `names` (all when empty), to share as a recipe file.
+pub fn run_recipes_export(store: &Path, names: &[String]) -> Result<Vec<Recipe>> {
+    let saved = read_recipes(store)?;
+    if let Some(missing) = names.iter().find(|n| !saved.iter().any(|r| &r.name == *n)) {
+        return Err(MbaseError::invalid_input(format!("no recipe '{}' in {}", missing, store.display())));
+    }
+    Ok(saved.into_iter().filter(|r| names.is_empty() || names.contains(&r.name)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipes_save_run_share() {
+        let dir = std::env::temp_dir().join(format!("mbase-recipes-{}", std::process::id()));
+        let store = dir.join("recipes");
+        let ctx = Context::default();
+        let steps = ["dec:base64".to_string(), "bits:not".to_string(), "enc:hex".to_string()];
+        let recipe = run_recipes_save(&ctx, &store, "beacon", &steps, false).unwrap();
+        assert_eq!(run_recipe(&ctx, &recipe, &InputSource::Literal(b"AP8".to_vec()), Mode::Strict).unwrap(), b"ff00");
+        assert_eq!(find_recipe(&store, "beacon").unwrap(), recipe);
+        assert!(find_recipe(&store, "missing").is_err());
+
+        let err = run_recipe(&ctx, &recipe, &InputSource::Literal(b"A".to_vec()), Mode::Strict).unwrap_err();
+        assert!(err.to_string().contains("recipe 'beacon' step 1 (dec:base64)"), "{}", err);
+        assert!(run_recipes_save(&ctx, &store, "beacon", &steps[..1], false).is_err());
+        assert!(run_recipes_save(&ctx, &store, "typo", &["dec:base6".to_string()], false).is_err());
+        assert!(run_recipes_save(&ctx, &store, "bad name", &steps, false).is_err());
+
+        let shared = dir.join("shared");
+        let exported = run_recipes_export(&store, &[]).unwrap();
+        crate::io::write_recipes(&shared, &exported).unwrap();
+        std::fs::write(&shared, std::fs::read_to_string(&shared).unwrap() + "upper dec:hex enc:base32\n").unwrap();
+        assert_eq!(run_recipes_import(&ctx, &store, &shared, false).unwrap(), 2);
+        let listed = run_recipes(&store).unwrap();
+        assert_eq!(listed.recipes.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), ["beacon", "upper"]);
+        assert_eq!(listed.recipes[0].steps, steps);
+        assert!(run_recipes_export(&store, &["nope".to_string()]).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
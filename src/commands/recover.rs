@@ -0,0 +1,293 @@
+use super::report::{DecodeResult, RecoverGap, RecoverReport};
+use crate::io::read_input;
+use mbase::codec::Codec;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CaseSensitivity, Context, InputSource, PaddingRule};
+
+/// Written for every byte that could not be recovered.
+const PLACEHOLDER: u8 = 0;
+
+/// Bytes a uuencoded line carries when its length character is damaged too (`M`, the usual width).
+const UU_LINE_BYTES: usize = 45;
+
+/// How the symbols of a recoverable codec map to values.
+struct Symbols {
+    alphabet: Vec<char>,
+    bits: usize,
+    /// Characters per block; a block decodes to `block * bits / 8` bytes.
+    block: usize,
+    fold_case: bool,
+    padded: bool,
+    uuencode: bool,
+}
+
+impl Symbols {
+    /// Block codecs whose alphabet has one symbol per value of a fixed bit width (the base16,
+    /// base32 and base64 families) and uuencode.
+    fn for_codec(codec: &dyn Codec) -> Option<Self> {
+        let meta = codec.meta();
+        if meta.name == "uuencode" {
+            return Some(Self {
+                alphabet: (' '..='_').collect(),
+                bits: 6,
+                block: 4,
+                fold_case: false,
+                padded: false,
+                uuencode: true,
+            });
+        }
+        let (bytes, chars) = codec.stream_block()?;
+        let alphabet: Vec<char> = meta.alphabet.chars().collect();
+        let bits = bytes * 8 / chars;
+        if bytes * 8 % chars != 0 || alphabet.len() != 1 << bits {
+            return None;
+        }
+        Some(Self {
+            alphabet,
+            bits,
+            block: chars,
+            fold_case: meta.case_sensitivity != CaseSensitivity::Sensitive,
+            padded: meta.padding == PaddingRule::Required,
+            uuencode: false,
+        })
+    }
+
+    fn value(&self, c: char) -> Option<u32> {
+        if self.uuencode && c == '`' {
+            return Some(0);
+        }
+        let matches = |a: &char| *a == c || (self.fold_case && a.eq_ignore_ascii_case(&c));
+        self.alphabet.iter().position(matches).map(|v| v as u32)
+    }
+
+    fn block_bytes(&self) -> usize {
+        self.block * self.bits / 8
+    }
+
+    /// Whether a final block of `len` symbols was cut short: it holds leftover bits no
+    /// encoder would write, or the codec pads and `padding` is missing.
+    fn truncated(&self, len: usize, padding: bool) -> bool {
+        let rest = len % self.block;
+        rest != 0 && (rest != (rest * self.bits / 8 * 8).div_ceil(self.bits) || (self.padded && !padding))
+    }
+
+    /// Decodes `symbols`, writing placeholders for each block with a character outside the
+    /// alphabet. Returns the first bad character of each such block with the block's byte range
+    /// in the output; a final short block decodes to the whole bytes it holds.
+    fn decode(&self, symbols: &[char], out: &mut Vec<u8>) -> Vec<(usize, usize, char)> {
+        let mut damaged = Vec::new();
+        for block in symbols.chunks(self.block) {
+            let values: Vec<Option<u32>> = block.iter().map(|&c| self.value(c)).collect();
+            let len = block.len() * self.bits / 8;
+            if let Some(i) = values.iter().position(Option::is_none) {
+                damaged.push((out.len(), len, block[i]));
+                out.extend(core::iter::repeat_n(PLACEHOLDER, len));
+                continue;
+            }
+            let (mut acc, mut bits) = (0u32, 0);
+            for value in values.into_iter().flatten() {
+                acc = (acc << self.bits | value) & 0xffff;
+                bits += self.bits;
+                if bits >= 8 {
+                    bits -= 8;
+                    out.push((acc >> bits) as u8);
+                }
+            }
+        }
+        damaged
+    }
+}
+
+struct Recovery {
+    out: Vec<u8>,
+    gaps: Vec<RecoverGap>,
+}
+
+impl Recovery {
+    fn gap(&mut self, offset: usize, length: usize, line: usize, reason: String) {
+        self.gaps.push(RecoverGap {
+            offset,
+            length,
+            line,
+            reason,
+        });
+    }
+
+    fn placeholders(&mut self, length: usize, line: usize, reason: String) {
+        self.gap(self.out.len(), length, line, reason);
+        self.out.extend(core::iter::repeat_n(PLACEHOLDER, length));
+    }
+}
+
+/// The most common value of `lengths`, if at least two and at least half of them share it.
+fn modal_length(lengths: &[usize]) -> Option<usize> {
+    let mut counts = std::collections::BTreeMap::new();
+    for &len in lengths {
+        *counts.entry(len).or_insert(0) += 1;
+    }
+    let (&len, &count) = counts.iter().max_by_key(|&(len, count)| (count, len))?;
+    (count >= 2 && count * 2 >= lengths.len()).then_some(len)
+}
+
+fn recover_blocks(symbols: &Symbols, lines: &[(usize, Vec<char>)], recovery: &mut Recovery) {
+    // Wrapped input (MIME, PEM) has lines of one width holding whole blocks. A full line of
+    // another width has lost or gained characters, so nothing in it can be trusted to be aligned.
+    let widths: Vec<usize> = lines.iter().map(|(_, l)| l.len()).collect();
+    let width = match widths.split_last() {
+        Some((_, full)) if full.len() >= 2 => modal_length(full).filter(|w| w % symbols.block == 0),
+        _ => None,
+    };
+    let mut pending: Vec<char> = Vec::new();
+    for (i, (line, chars)) in lines.iter().enumerate() {
+        let last = i + 1 == lines.len();
+        if let Some(width) = width {
+            if !last && chars.len() != width {
+                let expected = width / symbols.block * symbols.block_bytes();
+                recovery.placeholders(expected, *line, format!("line has {} characters, expected {}", chars.len(), width));
+                continue;
+            }
+            pending.clear();
+        }
+        pending.extend(chars);
+        if width.is_none() && !last && !pending.len().is_multiple_of(symbols.block) {
+            continue;
+        }
+        for (offset, length, c) in symbols.decode(&pending, &mut recovery.out) {
+            recovery.gap(offset, length, *line, format!("invalid character {:?}", c));
+        }
+        pending.clear();
+    }
+}
+
+fn recover_uuencode(symbols: &Symbols, lines: &[(usize, Vec<char>)], recovery: &mut Recovery) {
+    for (line, chars) in lines {
+        let Some(length) = chars.first().and_then(|&c| symbols.value(c)).map(|v| v as usize) else {
+            recovery.placeholders(UU_LINE_BYTES, *line, "invalid length character".to_string());
+            continue;
+        };
+        let start = recovery.out.len();
+        let needed = length.div_ceil(3) * 4;
+        let body = &chars[1..chars.len().min(needed + 1)];
+        for (offset, len, c) in symbols.decode(body, &mut recovery.out) {
+            let len = len.min(length.saturating_sub(offset - start));
+            recovery.gap(offset, len, *line, format!("invalid character {:?}", c));
+        }
+        recovery.out.truncate(start + length);
+        let got = recovery.out.len() - start;
+        if got < length {
+            recovery.placeholders(length - got, *line, format!("line is {} characters short", needed + 1 - chars.len()));
+        }
+    }
+}
+
+/// `dec --recover`: decodes damaged block-codec input as far as it goes. Whitespace is
+/// dropped, blocks holding characters outside the alphabet become zero bytes, and a truncated
+/// final block gives the whole bytes it carries. The report lists each gap.
+pub fn run_recover(ctx: &Context, codec_name: &str, input: &InputSource) -> Result<(Vec<u8>, RecoverReport)> {
+    let codec = ctx.registry.get(codec_name)?;
+    let symbols = Symbols::for_codec(codec).ok_or_else(|| {
+        MbaseError::invalid_input(format!("--recover works with the base16, base32 and base64 families and uuencode, not {}", codec.name()))
+    })?;
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let lines: Vec<(usize, Vec<char>)> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !(l.starts_with("-----") || (symbols.uuencode && (l.starts_with("begin ") || *l == "end"))))
+        .map(|(i, l)| {
+            let chars = if symbols.uuencode {
+                l.trim_end().chars().collect()
+            } else {
+                l.chars().filter(|&c| c != '=' && !c.is_whitespace()).collect()
+            };
+            (i + 1, chars)
+        })
+        .filter(|(_, chars): &(usize, Vec<char>)| !chars.is_empty())
+        .collect();
+
+    let mut recovery = Recovery {
+        out: Vec::new(),
+        gaps: Vec::new(),
+    };
+    if symbols.uuencode {
+        recover_uuencode(&symbols, &lines, &mut recovery);
+    } else {
+        recover_blocks(&symbols, &lines, &mut recovery);
+    }
+    let symbol_count = lines.iter().map(|(_, l)| l.len()).sum::<usize>();
+    let truncated = !symbols.uuencode && symbols.truncated(symbol_count, text.trim_end().ends_with('='));
+    let lost_bytes = recovery.gaps.iter().map(|g| g.length).sum::<usize>();
+    let report = RecoverReport {
+        codec: codec.name().to_string(),
+        output_length: recovery.out.len(),
+        recovered_bytes: recovery.out.len() - lost_bytes,
+        lost_bytes,
+        truncated,
+        gaps: recovery.gaps,
+    };
+    Ok((recovery.out, report))
+}
+
+/// [`run_recover`] as a `dec --json` result with the report in `recovery`.
+pub fn run_recover_json(ctx: &Context, codec_name: &str, input: &InputSource) -> Result<DecodeResult> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data).trim().to_string();
+    let (decoded, report) = run_recover(ctx, codec_name, &InputSource::Literal(data))?;
+    let mut result = DecodeResult::new(report.codec.clone(), text, &decoded, None, Vec::new());
+    result.recovery = Some(report);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recover(codec: &str, text: &str) -> (Vec<u8>, RecoverReport) {
+        run_recover(&Context::default(), codec, &InputSource::Literal(text.as_bytes().to_vec())).unwrap()
+    }
+
+    #[test]
+    fn test_recover_damaged_blocks() {
+        // "hello world" with one block of base64 hit by line noise and a stray line break.
+        let (out, report) = recover("base64pad", "aGVs\nbG8g#29y\nbGQ=");
+        assert_eq!(out, b"hello \0\0\0ld");
+        assert_eq!((report.recovered_bytes, report.lost_bytes, report.truncated), (8, 3, false));
+        assert_eq!((report.gaps[0].offset, report.gaps[0].length, report.gaps[0].line), (6, 3, 2));
+        assert_eq!(report.gaps[0].reason, "invalid character '#'");
+
+        let (out, report) = recover("base32", "NBSWY3DPEB3W64TMMQ");
+        assert_eq!((out.as_slice(), report.gaps.len()), (&b"hello world"[..], 0));
+    }
+
+    #[test]
+    fn test_recover_truncated() {
+        let (out, report) = recover("base64pad", "aGVsbG8gd29ybG");
+        assert_eq!(out, b"hello worl");
+        assert!(report.truncated);
+        assert!(report.gaps.is_empty());
+        // Unpadded base64 may end on a partial block, but never on a single symbol.
+        assert!(!recover("base64", "aGVsbG8gd29ybGQ").1.truncated);
+        assert!(recover("base64", "aGVsbG8gd29ybGQhI").1.truncated);
+    }
+
+    #[test]
+    fn test_recover_wrapped_line_lost_characters() {
+        let line = "A".repeat(8);
+        let text = format!("{}\n{}\nAAAAAA\n{}\nAA", line, line, line);
+        let (out, report) = recover("base64pad", &text);
+        assert_eq!(out.len(), 6 * 4 + 1);
+        assert_eq!(report.gaps.len(), 1);
+        assert_eq!((report.gaps[0].offset, report.gaps[0].length, report.gaps[0].line), (12, 6, 3));
+        assert_eq!(report.gaps[0].reason, "line has 6 characters, expected 8");
+    }
+
+    #[test]
+    fn test_recover_uuencode() {
+        let text = "begin 644 hi.txt\n+:&5L;&\\@=V]R;&0*\n`\nend\n";
+        assert_eq!(recover("uuencode", text).0, b"hello world");
+        let (out, report) = recover("uuencode", "+:&5L;&\\@=V]R\n");
+        assert_eq!(out, b"hello wor\0\0");
+        assert_eq!((report.lost_bytes, report.gaps[0].reason.as_str()), (2, "line is 4 characters short"));
+        assert!(run_recover(&Context::default(), "base58btc", &InputSource::Literal(b"abc".to_vec())).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use super::report::{RegressDivergence, RegressResult};
+use crate::io::read_input;
+use mbase::corpus::{load_corpus, Case, Outcome};
+use mbase::error::{MbaseError, Result};
+use mbase::types::{Context, InputSource};
+
+/// Runs `f` with the panic hook silenced, so codec panics show up in the report only.
+fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = f();
+    std::panic::set_hook(hook);
+    result
+}
+
+fn loaded(dir: &Path) -> Result<Vec<Case>> {
+    let cases = load_corpus(dir)?;
+    if cases.is_empty() {
+        return Err(MbaseError::invalid_input(format!("no .case files in {}", dir.display())));
+    }
+    Ok(cases)
+}
+
+/// `regress`: replays every case of the corpus in `dir` and lists panics and divergences.
+pub fn run_regress(ctx: &Context, dir: &Path) -> Result<RegressResult> {
+    let cases = loaded(dir)?;
+    let mut divergences = Vec::new();
+    quietly(|| {
+        for case in &cases {
+            for d in case.replay(ctx) {
+                let panic = match &d.actual {
+                    Some(Outcome::Panic { message }) => Some(message.clone()),
+                    _ => None,
+                };
+                divergences.push(RegressDivergence {
+                    case: case.name.clone(),
+                    codec: d.codec.clone(),
+                    operation: d.operation_name().to_string(),
+                    expected: d.expected.as_ref().map(ToString::to_string),
+                    actual: d.actual.as_ref().map(ToString::to_string),
+                    panic,
+                });
+            }
+        }
+    });
+    Ok(RegressResult {
+        cases: cases.len(),
+        passed: divergences.is_empty(),
+        divergences,
+    })
+}
+
+/// `regress --update`: re-records the outcomes of every case. Returns how many were rewritten.
+pub fn run_regress_update(ctx: &Context, dir: &Path) -> Result<usize> {
+    let mut cases = loaded(dir)?;
+    quietly(|| cases.iter_mut().for_each(|case| case.update(ctx)));
+    for case in &cases {
+        case.save(dir)?;
+    }
+    Ok(cases.len())
+}
+
+/// `regress --add NAME`: records `input` as a new case.
+pub fn run_regress_add(ctx: &Context, dir: &Path, name: &str, input: &InputSource, notes: &[String]) -> Result<Case> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(MbaseError::invalid_input(format!("'{}' is not a usable case name", name)));
+    }
+    let path = dir.join(format!("{}.{}", name, mbase::corpus::CASE_EXTENSION));
+    if path.exists() {
+        return Err(MbaseError::invalid_input(format!("{} already exists", path.display())));
+    }
+    let data = read_input(input)?;
+    let case = quietly(|| Case::record(ctx, name, data, notes.to_vec()));
+    std::fs::create_dir_all(dir)?;
+    case.save(dir)?;
+    Ok(case)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regress_add_replay_update() {
+        let dir = std::env::temp_dir().join(format!("mbase-regress-{}", std::process::id()));
+        let ctx = Context::default();
+        let input = InputSource::Literal(b"aGVs bG8=".to_vec());
+        let case = run_regress_add(&ctx, &dir, "spaced", &input, &["lenient whitespace".to_string()]).unwrap();
+        assert!(case.to_text().starts_with("# lenient whitespace\ninput "));
+        assert!(run_regress_add(&ctx, &dir, "spaced", &input, &[]).is_err());
+        assert!(run_regress_add(&ctx, &dir, "../escape", &input, &[]).is_err());
+
+        let result = run_regress(&ctx, &dir).unwrap();
+        assert_eq!((result.cases, result.passed), (1, true));
+
+        let path = dir.join("spaced.case");
+        let text = std::fs::read_to_string(&path).unwrap();
+        let edited = text.replace("dec base64pad lenient ok 5 3610a686", "dec base64pad lenient err 10");
+        assert_ne!(text, edited);
+        std::fs::write(&path, edited).unwrap();
+        let result = run_regress(&ctx, &dir).unwrap();
+        assert!(!result.passed);
+        let d = &result.divergences[0];
+        assert_eq!((d.codec.as_str(), d.operation.as_str()), ("base64pad", "dec lenient"));
+        assert_eq!((d.expected.as_deref(), d.actual.as_deref()), (Some("err 10"), Some("ok 5 3610a686")));
+
+        assert_eq!(run_regress_update(&ctx, &dir).unwrap(), 1);
+        assert!(run_regress(&ctx, &dir).unwrap().passed);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
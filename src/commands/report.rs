@@ -0,0 +1,1455 @@
+//! The JSON documents that `--json` prints, in one place so their shape and their schema
+//! change together.
+//!
+//! Every document carries `$schema` (the ID of its schema, see `mbase schema`) and
+//! `schema_version`, which goes up whenever a field is removed, renamed or changes type.
+//! Adding a field does not bump the version.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value as Json};
+
+use super::detect::FieldSource;
+use super::qr::QrMode;
+use super::sri::SriAlgorithm;
+use mbase::checkdigit::Scheme;
+use mbase::checksum::Checksum;
+use mbase::codec::lint::LintIssue;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecMetadata, DetectCandidate};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// `$schema` of the documents of `report`, and `$id` of its schema.
+pub fn schema_id(report: &str) -> String {
+    format!("urn:mbase:schema:{}:{}", report, SCHEMA_VERSION)
+}
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    #[serde(rename = "$schema")]
+    schema: String,
+    schema_version: u32,
+    #[serde(flatten)]
+    report: &'a T,
+}
+
+/// `value` as the pretty-printed JSON document of `report`.
+pub fn to_json<T: Serialize>(report: &str, value: &T) -> String {
+    let versioned = Versioned {
+        schema: schema_id(report),
+        schema_version: SCHEMA_VERSION,
+        report: value,
+    };
+    serde_json::to_string_pretty(&versioned).unwrap()
+}
+
+pub fn print_json<T: Serialize>(report: &str, value: &T) {
+    println!("{}", to_json(report, value));
+}
+
+/// `mbase info --json`: the codec's metadata, plus worked examples with `--examples`.
+#[derive(Debug, Serialize)]
+pub struct InfoResult {
+    #[serde(flatten)]
+    pub meta: CodecMetadata,
+    pub examples: Vec<CodecExample>,
+}
+
+/// A sample input (as an `--in` value) and its encoding.
+#[derive(Debug, Serialize)]
+pub struct CodecExample {
+    pub input: String,
+    pub encoded: String,
+    /// Whether a strict decode of `encoded` gives back the input.
+    pub roundtrip: bool,
+}
+
+/// `mbase list --json`; a bare array could not carry the version fields.
+#[derive(Debug, Serialize)]
+pub struct ListResult {
+    pub codecs: Vec<CodecSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncodeResult {
+    pub codec: String,
+    pub input_length: usize,
+    pub output: String,
+    pub output_length: usize,
+    pub multibase_prefix: Option<char>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncodeAllResult {
+    pub input_length: usize,
+    pub results: Vec<EncodeCodecResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncodeCodecResult {
+    pub codec: String,
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodeResult {
+    pub codec: String,
+    pub input: String,
+    pub output_length: usize,
+    pub output_hex: String,
+    pub output_text: Option<String>,
+    pub multibase_prefix: Option<char>,
+    pub warnings: Vec<String>,
+    /// Set when `--extract` narrowed the input.
+    pub extracted: Option<Extracted>,
+    /// What `--clean` removed or replaced before decoding.
+    pub cleaned: Vec<String>,
+    /// Set when the input was PEM armor; the output is the concatenated DER of all blocks.
+    pub pem: Option<Vec<PemBlock>>,
+    /// DER outline of the output, with `--asn1`.
+    pub asn1: Option<Vec<Asn1Node>>,
+    /// What `--recover` could and could not decode.
+    pub recovery: Option<RecoverReport>,
+    /// One entry per percent-decoding pass of `--max-passes`; empty otherwise.
+    pub passes: Vec<DecodePass>,
+}
+
+/// One pass of `dec --max-passes`: how many `%XX` escapes its input had and what came out.
+#[derive(Debug, Serialize)]
+pub struct DecodePass {
+    pub pass: usize,
+    pub escapes: usize,
+    pub output_length: usize,
+    pub preview: String,
+}
+
+/// `dec --both-modes`: the input decoded strictly and leniently, and what separates the two.
+#[derive(Debug, Serialize)]
+pub struct DecodeModesResult {
+    pub codec: String,
+    pub input: String,
+    pub strict: ModeOutcome,
+    pub lenient: ModeOutcome,
+    /// One mode failed where the other decoded, or both decoded to different bytes.
+    pub differ: bool,
+    /// What lenient decoding changed to get its result; empty when the modes agree.
+    pub drift: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModeOutcome {
+    pub ok: bool,
+    pub output_length: Option<usize>,
+    pub output_hex: Option<String>,
+    pub preview: Option<String>,
+    pub error: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoverReport {
+    pub codec: String,
+    pub output_length: usize,
+    pub recovered_bytes: usize,
+    /// Bytes written as zero placeholders, the sum of the gap lengths.
+    pub lost_bytes: usize,
+    /// The input ended inside a block; the whole bytes of that block are kept.
+    pub truncated: bool,
+    pub gaps: Vec<RecoverGap>,
+}
+
+/// A run of placeholder bytes at `offset` of the output, from input line `line` (1-based).
+#[derive(Debug, Serialize)]
+pub struct RecoverGap {
+    pub offset: usize,
+    pub length: usize,
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodeAllResult {
+    pub input: String,
+    pub results: Vec<DecodeCodecResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodeCodecResult {
+    pub codec: String,
+    pub success: bool,
+    pub output_length: Option<usize>,
+    pub output_hex: Option<String>,
+    pub output_text: Option<String>,
+    pub utf8: Option<bool>,
+    /// First characters of the output with control and invalid bytes escaped.
+    pub preview: Option<String>,
+    /// Plaintext likelihood from 0.0 to 1.0 (see `english_score`).
+    pub score: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodeShiftsResult {
+    pub input: String,
+    pub best_shift: Option<u32>,
+    pub results: Vec<DecodeShiftResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodeShiftResult {
+    pub shift: u32,
+    pub score: f64,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConvertResult {
+    pub from_codec: String,
+    pub to_codec: String,
+    pub input: String,
+    pub output: String,
+    pub roundtrip_verified: bool,
+}
+
+/// [`CodecMetadata`] plus what a sample encoding shows about the codec.
+#[derive(Debug, Serialize)]
+pub struct CodecSummary {
+    #[serde(flatten)]
+    pub meta: CodecMetadata,
+    /// Encoding of "hello", when the codec accepts it.
+    pub example: Option<String>,
+    /// Input bits per output character (separators included), measured on a 60-byte sample.
+    pub bits_per_char: Option<f64>,
+    pub tags: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyResult {
+    pub valid: bool,
+    pub codec: String,
+    pub error: Option<String>,
+    /// Whether the input equals the re-encoding of its decoded bytes; `None` when invalid.
+    pub canonical: Option<bool>,
+    pub canonical_form: Option<String>,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckDigitResult {
+    pub scheme: Scheme,
+    /// The input without separators, or with its check digits added under `--generate`.
+    pub number: String,
+    /// The check digits the payload needs; `None` when the input is malformed.
+    pub check_digits: Option<String>,
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DetectResult {
+    pub candidates: Vec<DetectMatch>,
+    pub input_preview: String,
+    /// Set when the input is PEM armor.
+    pub pem: Option<Vec<PemBlock>>,
+    /// Digest formats the input is shaped like; informational, never verified.
+    pub hashes: Vec<HashGuess>,
+    /// Wall-clock time of the whole detection, in nanoseconds.
+    pub elapsed_ns: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DetectFieldsResult {
+    pub source: FieldSource,
+    pub fields: Vec<DetectField>,
+}
+
+/// One `name=value` field of a cookie header or query, with detection run on its value.
+#[derive(Debug, Serialize)]
+pub struct DetectField {
+    pub name: String,
+    /// The value as it appeared in the input.
+    pub raw: String,
+    /// The value after percent-decoding and unquoting, which detection ran on.
+    pub value: String,
+    /// Best first; empty for empty values.
+    pub candidates: Vec<DetectMatch>,
+    pub hashes: Vec<HashGuess>,
+}
+
+/// A candidate with what it decodes to, so the right one can be picked without running `dec`.
+#[derive(Debug, Serialize)]
+pub struct DetectMatch {
+    #[serde(flatten)]
+    pub candidate: DetectCandidate,
+    /// Decoded length, UTF-8 validity and escaped preview, for candidates at or above
+    /// `detect::PREVIEW_MIN_CONFIDENCE` whose lenient decode succeeds.
+    pub output_length: Option<usize>,
+    pub utf8: Option<bool>,
+    pub preview: Option<String>,
+    /// Time spent in the codec's `detect_score` and trial decode, in nanoseconds; `None` for
+    /// candidates found another way (PEM armor).
+    pub scoring_ns: Option<u64>,
+    /// Raw signals behind the score, for tools that rank candidates themselves.
+    pub features: Option<DetectFeatures>,
+}
+
+/// Scoring signals of one candidate, measured independently of its `detect_score`.
+#[derive(Debug, Serialize)]
+pub struct DetectFeatures {
+    /// Share of the non-whitespace input characters before any `=` padding that are in the
+    /// codec's alphabet, folding case unless the codec is case-sensitive; `None` for codecs
+    /// without a character alphabet.
+    pub charset_ratio: Option<f64>,
+    /// Characters per independent block, for block codecs.
+    pub block_chars: Option<usize>,
+    /// Non-whitespace input length modulo `block_chars`; 0 when the length fits.
+    pub length_remainder: Option<usize>,
+    /// Whether the input starts with the codec's multibase prefix.
+    pub prefix_hit: bool,
+    pub strict_decode: bool,
+    pub lenient_decode: bool,
+    /// `true` when the codec reported a valid checksum, `false` when the strict decode failed on
+    /// one, `None` for codecs without a checksum.
+    pub checksum: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplainResult {
+    pub codec: String,
+    pub input_preview: String,
+    pub valid: bool,
+    pub error: Option<ExplainError>,
+    pub suggestions: Vec<String>,
+    /// Members of the codec's family that decode the input, when the codec itself does not.
+    pub valid_as: Vec<String>,
+    pub segments: Vec<Segment>,
+    /// Set when `--extract` narrowed the input; segment offsets then refer to the raw input.
+    pub extracted: Option<Extracted>,
+}
+
+/// A labelled slice of a valid input; `start..end` are byte offsets into the trimmed input.
+#[derive(Debug, Serialize)]
+pub struct Segment {
+    pub kind: &'static str,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExplainError {
+    pub message: String,
+    pub position: Option<usize>,
+    pub offending_char: Option<char>,
+    pub context: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalyzeResult {
+    pub codec: String,
+    pub input_preview: String,
+    pub tried: usize,
+    pub candidates: Vec<AnalyzeCandidate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalyzeCandidate {
+    pub params: String,
+    pub score: f64,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AlphabetRecovery {
+    pub codec: String,
+    pub input_preview: String,
+    pub known_prefix_length: usize,
+    /// Recovered alphabet in digit order (index = sextet value); `?` marks symbols never seen.
+    pub alphabet: String,
+    /// Symbols fixed by the known plaintext.
+    pub confirmed: usize,
+    /// Symbols assigned by matching a known alphabet family or by plaintext scoring.
+    pub guessed: usize,
+    pub unknown: usize,
+    /// Set when the alphabet matched a rotation of a well-known base64 alphabet.
+    pub family: Option<String>,
+    pub output: String,
+    pub output_hex: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DnsResult {
+    pub records: Vec<DnsRecord>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DnsRecord {
+    pub owner: String,
+    pub ttl: Option<u32>,
+    pub class: Option<String>,
+    pub rtype: String,
+    pub fields: Vec<DnsField>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DnsField {
+    pub name: String,
+    pub value: String,
+    pub encoding: Option<String>,
+    pub length: Option<usize>,
+    pub hex: Option<String>,
+    pub converted: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SriResult {
+    pub algorithm: SriAlgorithm,
+    pub integrity: String,
+    pub input_length: usize,
+    pub verified: Option<bool>,
+    pub expected: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CrcResult {
+    pub algorithm: Checksum,
+    pub input_length: usize,
+    pub value: u32,
+    pub codec: String,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CmpResult {
+    pub equal: bool,
+    pub codec_a: String,
+    pub codec_b: String,
+    pub length_a: usize,
+    pub length_b: usize,
+    /// Offset of the first byte that differs, or the shorter length when one side is a prefix.
+    pub first_difference: Option<usize>,
+    /// Differing bytes at common offsets, plus the bytes only the longer side has.
+    pub differing_bytes: usize,
+    pub context: Option<CmpContext>,
+}
+
+/// Hex of both sides around the first difference, which is in brackets (`[end]` past the end).
+#[derive(Debug, Serialize)]
+pub struct CmpContext {
+    /// Offset of the first byte shown.
+    pub offset: usize,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RadixResult {
+    pub from: u32,
+    pub to: u32,
+    pub values: Vec<RadixValue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RadixValue {
+    pub input: String,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QrResult {
+    pub payloads: Vec<QrPayload>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QrPayload {
+    /// `input`, or the codec that produced the payload.
+    pub source: String,
+    pub length: usize,
+    pub mode: QrMode,
+    pub alphanumeric: bool,
+    /// First character outside the QR alphanumeric set.
+    pub offending: Option<QrOffending>,
+    pub versions: Vec<QrFit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QrOffending {
+    pub char: char,
+    pub position: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QrFit {
+    pub level: &'static str,
+    /// `None` when even version 40 is too small.
+    pub version: Option<u32>,
+    /// Width of the symbol in modules.
+    pub modules: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RandResult {
+    pub seed: Option<u64>,
+    pub codec: String,
+    pub bytes: usize,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HcertResult {
+    pub prefix: Option<String>,
+    pub base45_length: usize,
+    pub compressed: bool,
+    pub cbor_length: usize,
+    pub algorithm: Option<String>,
+    pub kid: Option<String>,
+    pub signature_length: usize,
+    pub issuer: Option<String>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub payload: Json,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JwtResult {
+    /// `jws` (3 segments) or `jwe` (5 segments).
+    pub kind: &'static str,
+    pub segments: Vec<JwtSegment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JwtSegment {
+    /// `header`, `payload`, `signature`, or for a JWE `encrypted_key`, `iv`, `ciphertext`, `tag`.
+    pub role: &'static str,
+    pub length: usize,
+    /// The header, or a JWS payload, when it parses as JSON.
+    pub json: Option<Json>,
+    /// The decoded bytes in hex, for segments that are not JSON.
+    pub hex: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyResult {
+    pub keys: Vec<KeyInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyInfo {
+    /// `openssh` or `age`.
+    pub format: &'static str,
+    pub key_type: String,
+    pub algorithm: String,
+    pub bits: Option<usize>,
+    pub fingerprint: Option<String>,
+    pub comment: Option<String>,
+    pub fields: Vec<KeyField>,
+    pub warnings: Vec<String>,
+}
+
+/// One length-prefixed field of the key blob.
+#[derive(Debug, Serialize)]
+pub struct KeyField {
+    pub name: &'static str,
+    pub length: usize,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LintCodecResult {
+    pub alphabet: String,
+    pub symbols: usize,
+    pub valid: bool,
+    pub issues: Vec<LintIssue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegressResult {
+    pub cases: usize,
+    pub passed: bool,
+    pub divergences: Vec<RegressDivergence>,
+}
+
+/// An outcome replay did not reproduce, or a panic. Outcomes are written as in case files.
+#[derive(Debug, Serialize)]
+pub struct RegressDivergence {
+    pub case: String,
+    pub codec: String,
+    /// `enc`, `dec strict` or `dec lenient`.
+    pub operation: String,
+    /// `None` when the case has no record for the codec.
+    pub expected: Option<String>,
+    /// `None` when the codec is no longer registered.
+    pub actual: Option<String>,
+    pub panic: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecipesResult {
+    /// The recipe file the list comes from.
+    pub path: String,
+    pub recipes: Vec<RecipeEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecipeEntry {
+    pub name: String,
+    /// Steps as written in the recipe file, such as `dec:base64`.
+    pub steps: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyTreeResult {
+    /// The manifest the fixtures come from.
+    pub manifest: String,
+    pub total: usize,
+    pub failed: usize,
+    pub fixtures: Vec<FixtureResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FixtureResult {
+    /// The fixture file, as written in the manifest.
+    pub file: String,
+    pub codec: String,
+    pub passed: bool,
+    /// The expected encoding, or the expected `sha256-...` digest of it.
+    pub expected: String,
+    /// What the file encodes to, in the same form as `expected`; `None` when it could not be encoded.
+    pub actual: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A digest format the input is shaped like. Purely informational: a digest cannot be
+/// confirmed without its preimage, and any random bytes of the right length match.
+#[derive(Debug, Serialize)]
+pub struct HashGuess {
+    /// Candidate algorithms, most common first.
+    pub algorithms: Vec<&'static str>,
+    pub reason: String,
+    pub caveat: Option<&'static str>,
+}
+
+/// One `-----BEGIN label-----` ... `-----END label-----` block.
+#[derive(Debug, Serialize)]
+pub struct PemBlock {
+    pub label: String,
+    /// RFC 1421 encapsulated headers such as `Proc-Type` (legacy encrypted keys).
+    pub headers: Vec<(String, String)>,
+    pub der_length: usize,
+    #[serde(skip)]
+    pub der: Vec<u8>,
+}
+
+/// One element of a DER structure, in pre-order.
+#[derive(Debug, Serialize)]
+pub struct Asn1Node {
+    pub offset: usize,
+    pub depth: usize,
+    pub header_length: usize,
+    pub length: usize,
+    pub constructed: bool,
+    pub tag: String,
+    pub value: Option<String>,
+}
+
+/// The part of a noisy input that was decoded; `start..end` are byte offsets into the raw input.
+#[derive(Debug, Clone, Serialize)]
+pub struct Extracted {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+fn string() -> Json {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Json {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn number() -> Json {
+    json!({ "type": "number" })
+}
+
+fn boolean() -> Json {
+    json!({ "type": "boolean" })
+}
+
+fn character() -> Json {
+    json!({ "type": "string", "minLength": 1, "maxLength": 1 })
+}
+
+fn one_of(values: &[&str]) -> Json {
+    json!({ "type": "string", "enum": values })
+}
+
+fn array(items: Json) -> Json {
+    json!({ "type": "array", "items": items })
+}
+
+/// `schema` or `null`, as serde writes `None`.
+fn nullable(mut schema: Json) -> Json {
+    let kind = schema["type"].take();
+    schema["type"] = json!([kind, "null"]);
+    schema
+}
+
+/// A struct: serde writes every field, `None` as `null`, so all of them are required.
+fn object(properties: Json) -> Json {
+    let required: Vec<String> = properties.as_object().unwrap().keys().cloned().collect();
+    json!({ "type": "object", "properties": properties, "required": required, "additionalProperties": false })
+}
+
+/// `base` with the fields of a `#[serde(flatten)]` struct added.
+fn flattened(inner: Json, outer: Json) -> Json {
+    let mut properties = inner["properties"].as_object().unwrap().clone();
+    properties.extend(outer.as_object().unwrap().clone());
+    object(Json::Object(properties))
+}
+
+fn encode_result() -> Json {
+    object(json!({
+        "codec": string(),
+        "input_length": integer(),
+        "output": string(),
+        "output_length": integer(),
+        "multibase_prefix": nullable(character()),
+    }))
+}
+
+fn encode_all_result() -> Json {
+    object(json!({
+        "input_length": integer(),
+        "results": array(object(json!({
+            "codec": string(),
+            "output": nullable(string()),
+            "error": nullable(string()),
+        }))),
+    }))
+}
+
+fn extracted() -> Json {
+    object(json!({ "start": integer(), "end": integer(), "text": string() }))
+}
+
+fn pem_block() -> Json {
+    let header = json!({ "type": "array", "items": string(), "minItems": 2, "maxItems": 2 });
+    object(json!({ "label": string(), "headers": array(header), "der_length": integer() }))
+}
+
+fn asn1_node() -> Json {
+    object(json!({
+        "offset": integer(),
+        "depth": integer(),
+        "header_length": integer(),
+        "length": integer(),
+        "constructed": boolean(),
+        "tag": string(),
+        "value": nullable(string()),
+    }))
+}
+
+fn decode_result() -> Json {
+    object(json!({
+        "codec": string(),
+        "input": string(),
+        "output_length": integer(),
+        "output_hex": string(),
+        "output_text": nullable(string()),
+        "multibase_prefix": nullable(character()),
+        "warnings": array(string()),
+        "extracted": nullable(extracted()),
+        "cleaned": array(string()),
+        "pem": nullable(array(pem_block())),
+        "asn1": nullable(array(asn1_node())),
+        "recovery": nullable(recover_report()),
+        "passes": array(object(json!({
+            "pass": integer(),
+            "escapes": integer(),
+            "output_length": integer(),
+            "preview": string(),
+        }))),
+    }))
+}
+
+fn mode_outcome() -> Json {
+    object(json!({
+        "ok": boolean(),
+        "output_length": nullable(integer()),
+        "output_hex": nullable(string()),
+        "preview": nullable(string()),
+        "error": nullable(string()),
+        "warnings": array(string()),
+    }))
+}
+
+fn decode_modes_result() -> Json {
+    object(json!({
+        "codec": string(),
+        "input": string(),
+        "strict": mode_outcome(),
+        "lenient": mode_outcome(),
+        "differ": boolean(),
+        "drift": array(string()),
+    }))
+}
+
+fn recover_report() -> Json {
+    object(json!({
+        "codec": string(),
+        "output_length": integer(),
+        "recovered_bytes": integer(),
+        "lost_bytes": integer(),
+        "truncated": boolean(),
+        "gaps": array(object(json!({
+            "offset": integer(),
+            "length": integer(),
+            "line": integer(),
+            "reason": string(),
+        }))),
+    }))
+}
+
+fn decode_all_result() -> Json {
+    object(json!({
+        "input": string(),
+        "results": array(object(json!({
+            "codec": string(),
+            "success": boolean(),
+            "output_length": nullable(integer()),
+            "output_hex": nullable(string()),
+            "output_text": nullable(string()),
+            "utf8": nullable(boolean()),
+            "preview": nullable(string()),
+            "score": nullable(number()),
+            "error": nullable(string()),
+        }))),
+    }))
+}
+
+fn decode_shifts_result() -> Json {
+    object(json!({
+        "input": string(),
+        "best_shift": nullable(integer()),
+        "results": array(object(json!({ "shift": integer(), "score": number(), "output": string() }))),
+    }))
+}
+
+fn convert_result() -> Json {
+    object(json!({
+        "from_codec": string(),
+        "to_codec": string(),
+        "input": string(),
+        "output": string(),
+        "roundtrip_verified": boolean(),
+    }))
+}
+
+fn codec_metadata() -> Json {
+    object(json!({
+        "id": integer(),
+        "name": string(),
+        "aliases": array(string()),
+        "alphabet": string(),
+        "multibase_code": nullable(character()),
+        "padding": one_of(&["None", "Required"]),
+        "case_sensitivity": one_of(&["Sensitive", "Insensitive", "Lower", "Upper"]),
+        "description": string(),
+        "streamable": boolean(),
+        "family": nullable(string()),
+        "text_only": boolean(),
+        "lossy": boolean(),
+        "spec": nullable(string()),
+        "spec_url": nullable(string()),
+    }))
+}
+
+fn info_result() -> Json {
+    flattened(
+        codec_metadata(),
+        json!({
+            "examples": array(object(json!({
+                "input": string(),
+                "encoded": string(),
+                "roundtrip": boolean(),
+            }))),
+        }),
+    )
+}
+
+fn list_result() -> Json {
+    let summary = flattened(
+        codec_metadata(),
+        json!({
+            "example": nullable(string()),
+            "bits_per_char": nullable(number()),
+            "tags": array(string()),
+        }),
+    );
+    object(json!({ "codecs": array(summary) }))
+}
+
+fn verify_result() -> Json {
+    object(json!({
+        "valid": boolean(),
+        "codec": string(),
+        "error": nullable(string()),
+        "canonical": nullable(boolean()),
+        "canonical_form": nullable(string()),
+        "issues": array(string()),
+    }))
+}
+
+fn check_digit_result() -> Json {
+    let schemes: Vec<&str> = Scheme::ALL.iter().map(|s| s.name()).collect();
+    object(json!({
+        "scheme": one_of(&schemes),
+        "number": string(),
+        "check_digits": nullable(string()),
+        "valid": boolean(),
+        "error": nullable(string()),
+    }))
+}
+
+fn detect_match() -> Json {
+    let candidate = object(json!({
+        "codec": string(),
+        "confidence": number(),
+        "reasons": array(string()),
+        "warnings": array(string()),
+    }));
+    flattened(
+        candidate,
+        json!({
+            "output_length": nullable(integer()),
+            "utf8": nullable(boolean()),
+            "preview": nullable(string()),
+            "scoring_ns": nullable(integer()),
+            "features": nullable(detect_features()),
+        }),
+    )
+}
+
+fn detect_features() -> Json {
+    object(json!({
+        "charset_ratio": nullable(number()),
+        "block_chars": nullable(integer()),
+        "length_remainder": nullable(integer()),
+        "prefix_hit": boolean(),
+        "strict_decode": boolean(),
+        "lenient_decode": boolean(),
+        "checksum": nullable(boolean()),
+    }))
+}
+
+fn hash_guess() -> Json {
+    object(json!({
+        "algorithms": array(string()),
+        "reason": string(),
+        "caveat": nullable(string()),
+    }))
+}
+
+fn detect_result() -> Json {
+    object(json!({
+        "candidates": array(detect_match()),
+        "input_preview": string(),
+        "pem": nullable(array(pem_block())),
+        "hashes": array(hash_guess()),
+        "elapsed_ns": integer(),
+    }))
+}
+
+fn detect_fields_result() -> Json {
+    object(json!({
+        "source": one_of(&["cookie", "query"]),
+        "fields": array(object(json!({
+            "name": string(),
+            "raw": string(),
+            "value": string(),
+            "candidates": array(detect_match()),
+            "hashes": array(hash_guess()),
+        }))),
+    }))
+}
+
+fn explain_result() -> Json {
+    object(json!({
+        "codec": string(),
+        "input_preview": string(),
+        "valid": boolean(),
+        "error": nullable(object(json!({
+            "message": string(),
+            "position": nullable(integer()),
+            "offending_char": nullable(character()),
+            "context": nullable(string()),
+        }))),
+        "suggestions": array(string()),
+        "valid_as": array(string()),
+        "segments": array(object(json!({
+            "kind": string(),
+            "start": integer(),
+            "end": integer(),
+            "text": string(),
+            "note": nullable(string()),
+        }))),
+        "extracted": nullable(extracted()),
+    }))
+}
+
+fn analyze_result() -> Json {
+    object(json!({
+        "codec": string(),
+        "input_preview": string(),
+        "tried": integer(),
+        "candidates": array(object(json!({ "params": string(), "score": number(), "output": string() }))),
+    }))
+}
+
+fn alphabet_recovery() -> Json {
+    object(json!({
+        "codec": string(),
+        "input_preview": string(),
+        "known_prefix_length": integer(),
+        "alphabet": string(),
+        "confirmed": integer(),
+        "guessed": integer(),
+        "unknown": integer(),
+        "family": nullable(string()),
+        "output": string(),
+        "output_hex": string(),
+    }))
+}
+
+fn dns_result() -> Json {
+    let field = object(json!({
+        "name": string(),
+        "value": string(),
+        "encoding": nullable(string()),
+        "length": nullable(integer()),
+        "hex": nullable(string()),
+        "converted": nullable(string()),
+    }));
+    object(json!({
+        "records": array(object(json!({
+            "owner": string(),
+            "ttl": nullable(integer()),
+            "class": nullable(string()),
+            "rtype": string(),
+            "fields": array(field),
+            "warnings": array(string()),
+        }))),
+    }))
+}
+
+fn sri_result() -> Json {
+    object(json!({
+        "algorithm": one_of(&["sha256", "sha384", "sha512"]),
+        "integrity": string(),
+        "input_length": integer(),
+        "verified": nullable(boolean()),
+        "expected": nullable(string()),
+    }))
+}
+
+fn crc_result() -> Json {
+    let names: Vec<&str> = Checksum::ALL.iter().map(|c| c.name()).collect();
+    object(json!({
+        "algorithm": one_of(&names),
+        "input_length": integer(),
+        "value": integer(),
+        "codec": string(),
+        "output": string(),
+    }))
+}
+
+fn cmp_result() -> Json {
+    object(json!({
+        "equal": boolean(),
+        "codec_a": string(),
+        "codec_b": string(),
+        "length_a": integer(),
+        "length_b": integer(),
+        "first_difference": nullable(integer()),
+        "differing_bytes": integer(),
+        "context": nullable(object(json!({
+            "offset": integer(),
+            "a": string(),
+            "b": string(),
+        }))),
+    }))
+}
+
+fn radix_result() -> Json {
+    object(json!({
+        "from": integer(),
+        "to": integer(),
+        "values": array(object(json!({
+            "input": string(),
+            "output": string(),
+        }))),
+    }))
+}
+
+fn qr_result() -> Json {
+    let fit = object(json!({
+        "level": one_of(&["L", "M", "Q", "H"]),
+        "version": nullable(integer()),
+        "modules": nullable(integer()),
+    }));
+    object(json!({
+        "payloads": array(object(json!({
+            "source": string(),
+            "length": integer(),
+            "mode": one_of(&["numeric", "alphanumeric", "byte"]),
+            "alphanumeric": boolean(),
+            "offending": nullable(object(json!({ "char": character(), "position": integer() }))),
+            "versions": array(fit),
+        }))),
+    }))
+}
+
+fn rand_result() -> Json {
+    object(json!({ "seed": nullable(integer()), "codec": string(), "bytes": integer(), "output": string() }))
+}
+
+fn hcert_result() -> Json {
+    object(json!({
+        "prefix": nullable(string()),
+        "base45_length": integer(),
+        "compressed": boolean(),
+        "cbor_length": integer(),
+        "algorithm": nullable(string()),
+        "kid": nullable(string()),
+        "signature_length": integer(),
+        "issuer": nullable(string()),
+        "issued_at": nullable(string()),
+        "expires_at": nullable(string()),
+        "payload": {},
+        "warnings": array(string()),
+    }))
+}
+
+fn jwt_result() -> Json {
+    object(json!({
+        "kind": one_of(&["jws", "jwe"]),
+        "segments": array(object(json!({
+            "role": one_of(&["header", "payload", "signature", "encrypted_key", "iv", "ciphertext", "tag"]),
+            "length": integer(),
+            "json": {},
+            "hex": nullable(string()),
+        }))),
+    }))
+}
+
+fn key_result() -> Json {
+    object(json!({
+        "keys": array(object(json!({
+            "format": one_of(&["openssh", "age"]),
+            "key_type": string(),
+            "algorithm": string(),
+            "bits": nullable(integer()),
+            "fingerprint": nullable(string()),
+            "comment": nullable(string()),
+            "fields": array(object(json!({ "name": string(), "length": integer(), "value": string() }))),
+            "warnings": array(string()),
+        }))),
+    }))
+}
+
+fn lint_codec_result() -> Json {
+    object(json!({
+        "alphabet": string(),
+        "symbols": integer(),
+        "valid": boolean(),
+        "issues": array(object(json!({
+            "severity": one_of(&["error", "warning"]),
+            "kind": string(),
+            "message": string(),
+        }))),
+    }))
+}
+
+fn regress_result() -> Json {
+    object(json!({
+        "cases": integer(),
+        "passed": boolean(),
+        "divergences": array(object(json!({
+            "case": string(),
+            "codec": string(),
+            "operation": one_of(&["enc", "dec strict", "dec lenient"]),
+            "expected": nullable(string()),
+            "actual": nullable(string()),
+            "panic": nullable(string()),
+        }))),
+    }))
+}
+
+fn recipes_result() -> Json {
+    object(json!({
+        "path": string(),
+        "recipes": array(object(json!({
+            "name": string(),
+            "steps": array(string()),
+        }))),
+    }))
+}
+
+fn verify_tree_result() -> Json {
+    object(json!({
+        "manifest": string(),
+        "total": integer(),
+        "failed": integer(),
+        "fixtures": array(object(json!({
+            "file": string(),
+            "codec": string(),
+            "passed": boolean(),
+            "expected": string(),
+            "actual": nullable(string()),
+            "error": nullable(string()),
+        }))),
+    }))
+}
+
+/// Builds the schema of one report, without the version fields.
+type SchemaFn = fn() -> Json;
+
+/// Report name, the command line that prints it, and its schema.
+pub const REPORTS: &[(&str, &str, SchemaFn)] = &[
+    ("enc", "enc --json", encode_result),
+    ("enc-all", "enc --all --json", encode_all_result),
+    ("dec", "dec --json", decode_result),
+    ("dec-all", "dec --all --json", decode_all_result),
+    ("dec-all-shifts", "dec --all-shifts --json", decode_shifts_result),
+    ("dec-both-modes", "dec --both-modes --json", decode_modes_result),
+    ("conv", "conv --json", convert_result),
+    ("list", "list --json", list_result),
+    ("info", "info --json", info_result),
+    ("verify", "verify --json", verify_result),
+    ("verify-check-digit", "verify --scheme --json", check_digit_result),
+    ("verify-tree", "verify-tree --json", verify_tree_result),
+    ("detect", "detect --json", detect_result),
+    ("detect-fields", "detect --fields --json", detect_fields_result),
+    ("explain", "explain --json", explain_result),
+    ("analyze", "analyze --json", analyze_result),
+    ("analyze-alphabet", "analyze --known-prefix --json", alphabet_recovery),
+    ("dns", "dns --json", dns_result),
+    ("sri", "sri --json", sri_result),
+    ("crc", "crc --json", crc_result),
+    ("cmp", "cmp --json", cmp_result),
+    ("radix", "radix --json", radix_result),
+    ("qr", "qr --json", qr_result),
+    ("rand", "rand --json", rand_result),
+    ("hcert", "hcert --json", hcert_result),
+    ("jwt", "jwt --json", jwt_result),
+    ("key", "key --json", key_result),
+    ("lint-codec", "lint-codec --json", lint_codec_result),
+    ("regress", "regress --json", regress_result),
+    ("recipes", "recipes --json", recipes_result),
+];
+
+/// The JSON Schema (draft 2020-12) of the documents of `report`, version fields included.
+pub fn run_schema(report: &str) -> Result<Json> {
+    let Some(&(name, command, body)) = REPORTS.iter().find(|(name, ..)| *name == report) else {
+        let names: Vec<&str> = REPORTS.iter().map(|(name, ..)| *name).collect();
+        return Err(MbaseError::invalid_input(format!("unknown report '{}' (expected one of: {})", report, names.join(", "))));
+    };
+    let mut body = body();
+    let properties = body["properties"].as_object_mut().unwrap();
+    properties.insert("$schema".to_string(), json!({ "const": schema_id(name) }));
+    properties.insert("schema_version".to_string(), json!({ "const": SCHEMA_VERSION }));
+    let required = body["required"].as_array_mut().unwrap();
+    required.insert(0, json!("$schema"));
+    required.insert(1, json!("schema_version"));
+
+    let mut schema = Map::new();
+    schema.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+    schema.insert("$id".to_string(), json!(schema_id(name)));
+    schema.insert("title".to_string(), json!(format!("mbase {}", command)));
+    schema.extend(body.as_object().unwrap().clone());
+    Ok(Json::Object(schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{
+        recover_base64_alphabet, run_analyze, run_check_digit, run_cmp, run_conv_json, run_crc, run_decode_all_json, run_decode_all_shifts,
+        run_decode_both_modes, run_decode_json, run_decode_passes_json, run_detect, run_detect_fields, run_dns, run_encode_all_json,
+        run_encode_json, run_explain, run_info, run_jwt, run_key, run_lint_codec, run_list, run_qr, run_radix, run_rand, run_sri,
+        run_verify, ConvOptions, EncodeAllFilter, Radix,
+    };
+    use mbase::codec::lint::LintOptions;
+    use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+    /// The subset of JSON Schema that `run_schema` emits.
+    fn check(schema: &Json, value: &Json, path: &str) {
+        if let Some(expected) = schema.get("const") {
+            assert_eq!(value, expected, "{}", path);
+        }
+        if let Some(values) = schema.get("enum") {
+            assert!(values.as_array().unwrap().contains(value), "{}: {} not in {}", path, value, values);
+        }
+        let kinds: Vec<&str> = match schema.get("type") {
+            Some(Json::String(kind)) => vec![kind],
+            Some(Json::Array(kinds)) => kinds.iter().map(|k| k.as_str().unwrap()).collect(),
+            _ => return,
+        };
+        let kind = match value {
+            Json::Null => "null",
+            Json::Bool(_) => "boolean",
+            Json::Number(n) if n.is_u64() => "integer",
+            Json::Number(_) => "number",
+            Json::String(_) => "string",
+            Json::Array(_) => "array",
+            Json::Object(_) => "object",
+        };
+        assert!(kinds.contains(&kind) || (kind == "integer" && kinds.contains(&"number")), "{}: {} is not {:?}", path, value, kinds);
+        if let (Json::Object(fields), Some(properties)) = (value, schema.get("properties")) {
+            for required in schema["required"].as_array().unwrap() {
+                assert!(fields.contains_key(required.as_str().unwrap()), "{}: missing {}", path, required);
+            }
+            for (key, field) in fields {
+                let property = properties.get(key).unwrap_or_else(|| panic!("{}: unexpected field {}", path, key));
+                check(property, field, &format!("{}.{}", path, key));
+            }
+        }
+        if let (Json::Array(items), Some(item)) = (value, schema.get("items")) {
+            for (i, value) in items.iter().enumerate() {
+                check(item, value, &format!("{}[{}]", path, i));
+            }
+        }
+    }
+
+    fn assert_valid<T: Serialize>(report: &str, value: &T) {
+        let document: Json = serde_json::from_str(&to_json(report, value)).unwrap();
+        assert_eq!(document["$schema"], schema_id(report));
+        check(&run_schema(report).unwrap(), &document, report);
+    }
+
+    fn literal(s: &str) -> InputSource {
+        InputSource::Literal(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_schemas_are_well_formed() {
+        for (name, _, _) in REPORTS {
+            let schema = run_schema(name).unwrap();
+            assert_eq!(schema["$id"], schema_id(name));
+            let properties = schema["properties"].as_object().unwrap();
+            assert_eq!(properties.len(), schema["required"].as_array().unwrap().len());
+            assert_eq!(properties["schema_version"]["const"], SCHEMA_VERSION);
+        }
+        let err = run_schema("decode").unwrap_err().to_string();
+        assert!(err.contains("unknown report 'decode'") && err.contains("dec-all-shifts"));
+    }
+
+    #[test]
+    fn test_envelope_leads_the_document() {
+        let json = to_json(
+            "rand",
+            &RandResult {
+                seed: Some(1),
+                codec: "base64".into(),
+                bytes: 0,
+                output: String::new(),
+            },
+        );
+        assert!(json.starts_with("{\n  \"$schema\": \"urn:mbase:schema:rand:1\",\n  \"schema_version\": 1,\n  \"seed\": 1,"));
+    }
+
+    #[test]
+    fn test_codec_reports_match_schemas() {
+        let ctx = Context::default();
+        let opts = CodecOptions::default();
+        assert_valid("enc", &run_encode_json(&ctx, "base58btc", &literal("hello"), true, &opts).unwrap());
+        assert_valid("enc-all", &run_encode_all_json(&ctx, &literal("hi"), &EncodeAllFilter::default()).unwrap());
+        assert_valid("dec", &run_decode_json(&ctx, "base64pad", &literal("aGVsbG8="), Mode::Strict, false, &opts).unwrap());
+        assert_valid("dec", &run_decode_passes_json(&ctx, "urlencoding", &literal("a%2520b"), 3).unwrap());
+        assert_valid("dec-all", &run_decode_all_json(&ctx, &literal("aGVsbG8="), Mode::Lenient).unwrap());
+        assert_valid("dec-all-shifts", &run_decode_all_shifts(&ctx, "rot13", &literal("uryyb jbeyq"), &CodecOptions::new()).unwrap());
+        assert_valid("dec-both-modes", &run_decode_both_modes(&ctx, "base64pad", &literal("aGVs bG8"), &opts).unwrap());
+        let conv = ConvOptions::default();
+        assert_valid("conv", &run_conv_json(&ctx, "base16", "base64", &literal("68656c6c6f"), &conv).unwrap());
+        assert_valid(
+            "list",
+            &ListResult {
+                codecs: run_list(&ctx, None),
+            },
+        );
+        assert_valid("info", &run_info(&ctx, "base32", true).unwrap());
+        assert_valid("verify", &run_verify(&ctx, "base64", &literal("aGVsbG8"), Mode::Strict).unwrap());
+        assert_valid("verify", &run_verify(&ctx, "base64", &literal("aGVsbG8="), Mode::Strict).unwrap());
+        assert_valid("verify-check-digit", &run_check_digit(&literal("4111111111111112"), Scheme::Luhn, false).unwrap());
+        assert_valid("verify-check-digit", &run_check_digit(&literal("GB WEST 1234 5698 7654 32"), Scheme::Iban, true).unwrap());
+        assert_valid("detect", &run_detect(&ctx, literal("5d41402abc4b2a76b9719d911017c592"), 3).unwrap());
+        let cookie = literal("Cookie: session=aGVsbG8gd29ybGQ%3D; theme=");
+        assert_valid("detect-fields", &run_detect_fields(&ctx, cookie, 3).unwrap());
+        assert_valid("explain", &run_explain(&ctx, literal("aGV$bG8="), "base64", Mode::Strict).unwrap());
+        assert_valid("rand", &run_rand(&ctx, "base32", 8, &opts).unwrap());
+        assert_valid("lint-codec", &run_lint_codec(&ctx, "0123456789abcdefO", &LintOptions::default()));
+    }
+
+    #[test]
+    fn test_inspection_reports_match_schemas() {
+        let ctx = Context::default();
+        assert_valid("analyze", &run_analyze(&ctx, "rot", &literal("uryyb jbeyq"), 3).unwrap());
+        let cipher = literal("TVqQAAMAAAAEAAAA");
+        assert_valid("analyze-alphabet", &recover_base64_alphabet(&cipher, b"MZ").unwrap());
+        let nsec3 = "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom.example. NSEC3 1 1 12 aabbccdd 2t7b4g4vsa5smi47k61mv5bv1a22bojr MX";
+        assert_valid("dns", &run_dns(&ctx, &literal(nsec3), Some("base64")).unwrap());
+        assert_valid("sri", &run_sri(&ctx, &literal("hello"), SriAlgorithm::Sha384, None).unwrap());
+        let qr = run_qr(&ctx, &literal("hello"), &["base45".into(), "base64".into()]).unwrap();
+        assert_valid("qr", &qr);
+        let (a, b) = (literal("68656c6c6f"), literal("68656c70"));
+        assert_valid("cmp", &run_cmp(&ctx, ("hex", &a), ("hex", &b), Mode::Strict).unwrap());
+        let (hex, dec) = (Radix::new(Some(16), None).unwrap(), Radix::new(None, None).unwrap());
+        assert_valid("radix", &run_radix(&literal("ff 10"), &hex, &dec).unwrap());
+        assert_valid("crc", &run_crc(&ctx, &literal("hello"), Checksum::Crc16Ccitt, None, Mode::Strict, "hex").unwrap());
+        let ed25519 = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIB3jrJU8qqo3K+vC9Yp05u8d+1ZmpvqqiGqKRw2PHugm test@mbase";
+        assert_valid("key", &run_key(&ctx, &literal(ed25519)).unwrap());
+        assert_valid("jwt", &run_jwt(&literal("eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.c2ln"), Mode::Strict).unwrap());
+        let divergence = RegressDivergence {
+            case: "lone-pad".into(),
+            codec: "base64".into(),
+            operation: "dec strict".into(),
+            expected: Some("err 10".into()),
+            actual: Some("panic".into()),
+            panic: Some("index out of bounds".into()),
+        };
+        let regress = RegressResult {
+            cases: 1,
+            passed: false,
+            divergences: vec![divergence],
+        };
+        assert_valid("regress", &regress);
+        let recipes = RecipesResult {
+            path: "recipes".into(),
+            recipes: vec![RecipeEntry {
+                name: "beacon".into(),
+                steps: vec!["dec:base64".into(), "enc:hex".into()],
+            }],
+        };
+        assert_valid("recipes", &recipes);
+        let fixture = FixtureResult {
+            file: "fixtures/hello.bin".into(),
+            codec: "base64".into(),
+            passed: false,
+            expected: "aGVsbG8".into(),
+            actual: None,
+            error: Some("cannot read fixtures/hello.bin".into()),
+        };
+        let tree = VerifyTreeResult {
+            manifest: "manifest.toml".into(),
+            total: 1,
+            failed: 1,
+            fixtures: vec![fixture],
+        };
+        assert_valid("verify-tree", &tree);
+    }
+}
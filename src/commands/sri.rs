@@ -0,0 +1,152 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::report::SriResult;
+use crate::io::read_input;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{Context, InputSource, Mode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SriAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl SriAlgorithm {
+    pub(crate) fn prefix(self) -> &'static str {
+        match self {
+            SriAlgorithm::Sha256 => "sha256",
+            SriAlgorithm::Sha384 => "sha384",
+            SriAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn from_prefix(s: &str) -> Option<Self> {
+        match s {
+            "sha256" => Some(SriAlgorithm::Sha256),
+            "sha384" => Some(SriAlgorithm::Sha384),
+            "sha512" => Some(SriAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            SriAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            SriAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            SriAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+}
+
+/// Parses an `integrity` attribute value into (algorithm, base64 digest) pairs.
+/// Unknown algorithms and `?options` suffixes are ignored, as browsers do.
+fn parse_metadata(integrity: &str) -> Vec<(SriAlgorithm, &str)> {
+    integrity
+        .split_whitespace()
+        .filter_map(|token| {
+            let (alg, rest) = token.split_once('-')?;
+            let digest = rest.split('?').next().unwrap_or(rest);
+            SriAlgorithm::from_prefix(&alg.to_ascii_lowercase()).map(|a| (a, digest))
+        })
+        .collect()
+}
+
+pub(super) fn integrity_for(ctx: &Context, algorithm: SriAlgorithm, data: &[u8]) -> Result<String> {
+    let encoded = ctx.registry.get("base64pad")?.encode(&algorithm.digest(data))?;
+    Ok(format!("{}-{}", algorithm.prefix(), encoded))
+}
+
+/// Checks `data` against an `integrity` value. Per the SRI spec only the strongest listed
+/// algorithm is considered, and any of its digests may match.
+pub(super) fn check_integrity(ctx: &Context, expected: &str, data: &[u8]) -> Result<(SriAlgorithm, bool)> {
+    let metadata = parse_metadata(expected);
+    let strongest = metadata
+        .iter()
+        .map(|(alg, _)| *alg)
+        .max()
+        .ok_or_else(|| MbaseError::invalid_input(format!("no sha256/sha384/sha512 digest found in '{}'", expected)))?;
+
+    let base64 = ctx.registry.get("base64pad")?;
+    let actual = strongest.digest(data);
+    let verified = metadata
+        .iter()
+        .filter(|(alg, _)| *alg == strongest)
+        .any(|(_, digest)| base64.decode(digest, Mode::Strict).is_ok_and(|d| d == actual));
+    Ok((strongest, verified))
+}
+
+pub fn run_sri(ctx: &Context, input: &InputSource, algorithm: SriAlgorithm, verify: Option<&str>) -> Result<SriResult> {
+    let data = read_input(input)?;
+
+    let Some(expected) = verify else {
+        return Ok(SriResult {
+            algorithm,
+            integrity: integrity_for(ctx, algorithm, &data)?,
+            input_length: data.len(),
+            verified: None,
+            expected: None,
+        });
+    };
+
+    let (strongest, verified) = check_integrity(ctx, expected, &data)?;
+    Ok(SriResult {
+        algorithm: strongest,
+        integrity: integrity_for(ctx, strongest, &data)?,
+        input_length: data.len(),
+        verified: Some(verified),
+        expected: Some(expected.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &[u8] = b"alert('Hello, world.');";
+
+    #[test]
+    fn test_sri_generate() {
+        // Example from the W3C SRI specification.
+        let ctx = Context::default();
+        let result = run_sri(&ctx, &InputSource::Literal(SCRIPT.to_vec()), SriAlgorithm::Sha384, None).unwrap();
+        assert_eq!(result.integrity, "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO");
+        assert_eq!(result.verified, None);
+    }
+
+    #[test]
+    fn test_sri_verify() {
+        let ctx = Context::default();
+        let input = InputSource::Literal(SCRIPT.to_vec());
+        let good = "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO";
+        let result = run_sri(&ctx, &input, SriAlgorithm::Sha384, Some(good)).unwrap();
+        assert_eq!(result.verified, Some(true));
+
+        let bad = "sha384-dOTZf16X8p34q2/kYyEFm0jh89uTjikhnzjeLeF0FHsEaYKb1A1cv+Lyv4Hk8vHd";
+        let result = run_sri(&ctx, &input, SriAlgorithm::Sha384, Some(bad)).unwrap();
+        assert_eq!(result.verified, Some(false));
+    }
+
+    #[test]
+    fn test_sri_verify_uses_strongest_algorithm() {
+        let ctx = Context::default();
+        let input = InputSource::Literal(SCRIPT.to_vec());
+        let sha256 = run_sri(&ctx, &input, SriAlgorithm::Sha256, None).unwrap().integrity;
+        // A matching sha256 does not help when a (wrong) sha512 is also listed.
+        let mixed = format!("{} sha512-AAAA", sha256);
+        let result = run_sri(&ctx, &input, SriAlgorithm::Sha384, Some(&mixed)).unwrap();
+        assert_eq!(result.algorithm, SriAlgorithm::Sha512);
+        assert_eq!(result.verified, Some(false));
+
+        let result = run_sri(&ctx, &input, SriAlgorithm::Sha384, Some(&format!("{}?ct=text/js md5-xyz", sha256))).unwrap();
+        assert_eq!(result.verified, Some(true));
+    }
+
+    #[test]
+    fn test_sri_verify_rejects_missing_metadata() {
+        let ctx = Context::default();
+        assert!(run_sri(&ctx, &InputSource::Literal(SCRIPT.to_vec()), SriAlgorithm::Sha384, Some("md5-abc")).is_err());
+    }
+}
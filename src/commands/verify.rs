@@ -1,15 +1,44 @@
-use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
 
+use serde::Deserialize;
+
+use super::report::{CheckDigitResult, FixtureResult, VerifyResult, VerifyTreeResult};
+use super::sri::{check_integrity, integrity_for};
 use crate::io::read_input;
-use mbase::error::Result;
-use mbase::types::{Context, InputSource, Mode};
+use mbase::checkdigit::Scheme;
+use mbase::error::{MbaseError, Result};
+use mbase::types::{CodecOptions, Context, InputSource, Mode};
+
+/// Explains how `input` departs from `canonical`: whitespace, padding, case or alternate symbols.
+fn canonical_issues(input: &str, canonical: &str) -> Vec<String> {
+    let mut issues = Vec::new();
+    let compact: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.len() != input.len() {
+        issues.push("contains whitespace".to_string());
+    }
+
+    let body = compact.trim_end_matches('=');
+    let canonical_body = canonical.trim_end_matches('=');
+    let pad = compact.len() - body.len();
+    let canonical_pad = canonical.len() - canonical_body.len();
+    if pad != canonical_pad {
+        issues.push(format!("has {} padding character(s), canonical form has {}", pad, canonical_pad));
+    }
 
-#[derive(Debug, Serialize)]
-pub struct VerifyResult {
-    pub schema_version: u32,
-    pub valid: bool,
-    pub codec: String,
-    pub error: Option<String>,
+    if body != canonical_body {
+        if body.eq_ignore_ascii_case(canonical_body) {
+            issues.push("non-canonical letter case".to_string());
+        } else {
+            let pos = body
+                .chars()
+                .zip(canonical_body.chars())
+                .position(|(a, b)| !a.eq_ignore_ascii_case(&b))
+                .unwrap_or_else(|| body.chars().count().min(canonical_body.chars().count()));
+            issues.push(format!("differs from canonical form at position {} (non-zero trailing bits or alias symbol)", pos));
+        }
+    }
+    issues
 }
 
 pub fn run_verify(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mode) -> Result<VerifyResult> {
@@ -18,18 +47,303 @@ pub fn run_verify(ctx: &Context, codec_name: &str, input: &InputSource, mode: Mo
     let data = read_input(input)?;
     let text = String::from_utf8_lossy(&data);
 
-    match codec.validate(&text, mode) {
-        Ok(()) => Ok(VerifyResult {
-            schema_version: 1,
-            valid: true,
-            codec: codec_name.to_string(),
-            error: None,
-        }),
+    match ctx.decode(codec, &text, mode, &CodecOptions::new()) {
+        Ok(decoded) => {
+            let canonical_form = ctx.encode(codec, &decoded, &CodecOptions::new())?;
+            let trimmed = text.trim();
+            let cleaned = ctx.apply_lenient_policy(codec, trimmed, mode);
+            let mut issues = canonical_issues(&cleaned, &canonical_form);
+            if cleaned != trimmed {
+                issues.insert(0, "contains separators ignored by --ignore-chars".to_string());
+            }
+            let canonical = issues.is_empty();
+            Ok(VerifyResult {
+                valid: true,
+                codec: codec_name.to_string(),
+                error: None,
+                canonical: Some(canonical),
+                canonical_form: (!canonical).then_some(canonical_form),
+                issues,
+            })
+        }
         Err(e) => Ok(VerifyResult {
-            schema_version: 1,
             valid: false,
             codec: codec_name.to_string(),
             error: Some(e.to_string()),
+            canonical: None,
+            canonical_form: None,
+            issues: vec![],
         }),
     }
 }
+
+/// `verify --scheme`: checks the digits of a number, or with `generate` adds them to a payload.
+pub fn run_check_digit(input: &InputSource, scheme: Scheme, generate: bool) -> Result<CheckDigitResult> {
+    let data = read_input(input)?;
+    let text = String::from_utf8_lossy(&data);
+    let number: String = text.chars().filter(|&c| !c.is_whitespace() && c != '-').collect();
+    let (number, generated) = match generate {
+        true => match scheme.generate(&number) {
+            Ok(full) => (full, Ok(())),
+            Err(e) => (number, Err(e)),
+        },
+        false => (number, Ok(())),
+    };
+    let check_digits = generated
+        .as_ref()
+        .ok()
+        .and_then(|_| scheme.split(&number).and_then(|(payload, _)| scheme.check_digits(&payload)).ok());
+    let error = generated.and_then(|_| scheme.validate(&number)).err().map(|e| e.to_string());
+    Ok(CheckDigitResult {
+        scheme,
+        number,
+        check_digits,
+        valid: error.is_none(),
+        error,
+    })
+}
+
+/// A `verify-tree` manifest: a list of `[[fixture]]` tables.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    #[serde(rename = "fixture", default)]
+    fixtures: Vec<FixtureSpec>,
+}
+
+/// One fixture: a file, relative to the manifest, and the encoding it must produce, given
+/// either in full or as an SRI-style digest (`sha256-...`) of the encoded text.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureSpec {
+    file: String,
+    codec: String,
+    encoded: Option<String>,
+    digest: Option<String>,
+    #[serde(default)]
+    opts: BTreeMap<String, String>,
+}
+
+/// Encodes one fixture and compares it with the manifest. Returns what it produced, in the
+/// form of the expectation, and whether the two match.
+fn check_fixture(ctx: &Context, dir: &Path, spec: &FixtureSpec) -> Result<(String, bool)> {
+    let codec = ctx.registry.get(&spec.codec)?;
+    let opts = spec.opts.iter().fold(CodecOptions::new(), |opts, (k, v)| opts.with(k, v));
+    let data = std::fs::read(dir.join(&spec.file)).map_err(|e| MbaseError::invalid_input(format!("cannot read {}: {}", spec.file, e)))?;
+    let encoded = ctx.encode(codec, &data, &opts)?;
+    match (&spec.encoded, &spec.digest) {
+        (Some(expected), None) => {
+            let passed = &encoded == expected;
+            Ok((encoded, passed))
+        }
+        (None, Some(expected)) => {
+            let (algorithm, passed) = check_integrity(ctx, expected, encoded.as_bytes())?;
+            Ok((integrity_for(ctx, algorithm, encoded.as_bytes())?, passed))
+        }
+        _ => Err(MbaseError::invalid_input("a fixture needs exactly one of `encoded` or `digest`")),
+    }
+}
+
+/// `verify-tree`: encodes every fixture listed in `manifest` and compares the result with the
+/// expected encoding. A fixture that cannot be read or encoded fails; it does not stop the run.
+pub fn run_verify_tree(ctx: &Context, manifest: &Path) -> Result<VerifyTreeResult> {
+    let text = std::fs::read_to_string(manifest)?;
+    let parsed: Manifest = toml::from_str(&text).map_err(|e| MbaseError::invalid_input(format!("{}: {}", manifest.display(), e)))?;
+    let dir = manifest.parent().unwrap_or(Path::new(""));
+
+    let fixtures: Vec<FixtureResult> = parsed
+        .fixtures
+        .into_iter()
+        .map(|spec| {
+            let outcome = check_fixture(ctx, dir, &spec);
+            let expected = spec.encoded.or(spec.digest).unwrap_or_default();
+            let (actual, passed, error) = match outcome {
+                Ok((actual, passed)) => (Some(actual), passed, None),
+                Err(e) => (None, false, Some(e.to_string())),
+            };
+            FixtureResult {
+                file: spec.file,
+                codec: spec.codec,
+                passed,
+                expected,
+                actual,
+                error,
+            }
+        })
+        .collect();
+    Ok(VerifyTreeResult {
+        manifest: manifest.display().to_string(),
+        total: fixtures.len(),
+        failed: fixtures.iter().filter(|f| !f.passed).count(),
+        fixtures,
+    })
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `verify-tree --junit`: the result as a JUnit XML test suite, one test case per fixture.
+pub fn junit_report(result: &VerifyTreeResult) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        xml_escape(&result.manifest),
+        result.total,
+        result.failed
+    ));
+    for fixture in &result.fixtures {
+        let case = format!("<testcase classname=\"{}\" name=\"{}\"", xml_escape(&fixture.codec), xml_escape(&fixture.file));
+        if fixture.passed {
+            xml.push_str(&format!("  {}/>\n", case));
+            continue;
+        }
+        let message = match (&fixture.error, &fixture.actual) {
+            (Some(error), _) => error.clone(),
+            (None, actual) => format!("expected {}, got {}", fixture.expected, actual.as_deref().unwrap_or_default()),
+        };
+        xml.push_str(&format!("  {}>\n    <failure message=\"{}\"/>\n  </testcase>\n", case, xml_escape(&message)));
+    }
+    xml.push_str("</testsuite>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mbase::types::LenientPolicy;
+
+    fn verify(codec: &str, input: &str, mode: Mode) -> VerifyResult {
+        run_verify(&Context::default(), codec, &InputSource::Literal(input.as_bytes().to_vec()), mode).unwrap()
+    }
+
+    #[test]
+    fn test_verify_canonical() {
+        let result = verify("base64pad", "aGVsbG8=", Mode::Strict);
+        assert!(result.valid);
+        assert_eq!(result.canonical, Some(true));
+        assert!(result.canonical_form.is_none());
+    }
+
+    #[test]
+    fn test_verify_non_canonical_case_and_whitespace() {
+        let result = verify("base16lower", "DE AD", Mode::Lenient);
+        assert!(result.valid);
+        assert_eq!(result.canonical, Some(false));
+        assert_eq!(result.canonical_form.as_deref(), Some("dead"));
+        assert_eq!(result.issues, vec!["contains whitespace", "non-canonical letter case"]);
+    }
+
+    #[test]
+    fn test_verify_non_canonical_alias_and_padding() {
+        let result = verify("crockford32", "DIJPRV3F", Mode::Lenient);
+        assert_eq!(result.canonical, Some(false));
+        assert_eq!(result.canonical_form.as_deref(), Some("D1JPRV3F"));
+        assert!(result.issues[0].contains("position 1"));
+
+        let result = verify("base32padlower", "nbswy3dp", Mode::Lenient);
+        if result.valid {
+            assert_eq!(result.canonical, Some(true));
+        }
+    }
+
+    #[test]
+    fn test_verify_applies_ignore_chars() {
+        let ctx = Context::default().with_lenient_policy(LenientPolicy::parse("-.").unwrap());
+        let result = run_verify(&ctx, "base58btc", &InputSource::Literal(b"Cn8e-VZg".to_vec()), Mode::Lenient).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.issues, vec!["contains separators ignored by --ignore-chars"]);
+        // Quoted-printable text is printable ASCII, so its hyphens and dots are data.
+        let qp = ctx.registry.get("quoted-printable").unwrap();
+        assert_eq!(ctx.decode(qp, "a-b.c", Mode::Lenient, &CodecOptions::new()).unwrap(), b"a-b.c");
+    }
+
+    #[test]
+    fn test_verify_invalid_has_no_canonical_report() {
+        let result = verify("base64pad", "a!", Mode::Strict);
+        assert!(!result.valid);
+        assert_eq!(result.canonical, None);
+    }
+
+    fn check(input: &str, scheme: Scheme, generate: bool) -> CheckDigitResult {
+        run_check_digit(&InputSource::Literal(input.as_bytes().to_vec()), scheme, generate).unwrap()
+    }
+
+    #[test]
+    fn test_check_digit() {
+        let result = check("4111 1111 1111 1111\n", Scheme::Luhn, false);
+        assert!(result.valid && result.error.is_none());
+        assert_eq!((result.number.as_str(), result.check_digits.as_deref()), ("4111111111111111", Some("1")));
+
+        let result = check("GB83WEST12345698765432", Scheme::Iban, false);
+        assert!(!result.valid);
+        assert_eq!(result.check_digits.as_deref(), Some("82"));
+        assert!(result.error.unwrap().contains("expected 82"));
+
+        let result = check("5x24", Scheme::Damm, false);
+        assert_eq!((result.valid, result.check_digits), (false, None));
+    }
+
+    #[test]
+    fn test_check_digit_generate() {
+        let result = check("GB WEST 1234 5698 7654 32", Scheme::Iban, true);
+        assert!(result.valid);
+        assert_eq!(result.number, "GB82WEST12345698765432");
+        assert_eq!(check("236", Scheme::Verhoeff, true).number, "2363");
+        assert!(!check("23a", Scheme::Verhoeff, true).valid);
+    }
+
+    #[test]
+    fn test_verify_tree() {
+        let dir = std::env::temp_dir().join(format!("mbase-verify-tree-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("fixtures")).unwrap();
+        std::fs::write(dir.join("fixtures/hello.bin"), b"hello").unwrap();
+        let manifest = dir.join("manifest.toml");
+        std::fs::write(
+            &manifest,
+            r#"
+[[fixture]]
+file = "fixtures/hello.bin"
+codec = "base64"
+encoded = "aGVsbG8"
+
+[[fixture]]
+file = "fixtures/hello.bin"
+codec = "base64"
+digest = "sha256-SZ975TVsJwqCC87RD4gWI6LGJUefjg2U1ejcrP0qFdk="
+
+[[fixture]]
+file = "fixtures/hello.bin"
+codec = "caesar"
+opts = { shift = "1" }
+encoded = "ifmmq"
+
+[[fixture]]
+file = "fixtures/missing.bin"
+codec = "hex"
+encoded = "00"
+"#,
+        )
+        .unwrap();
+
+        let result = run_verify_tree(&Context::default(), &manifest).unwrap();
+        assert_eq!((result.total, result.failed), (4, 2));
+        assert!(result.fixtures[0].passed && result.fixtures[1].passed);
+        assert_eq!(result.fixtures[2].actual.as_deref(), Some("ifmmp"));
+        assert!(result.fixtures[2].error.is_none());
+        assert!(result.fixtures[3].error.as_deref().unwrap().contains("missing.bin"));
+
+        let junit = junit_report(&result);
+        assert!(junit.contains("tests=\"4\" failures=\"2\""));
+        assert!(junit.contains("<failure message=\"expected ifmmq, got ifmmp\"/>"));
+
+        std::fs::write(&manifest, "[[fixture]]\nfile = \"x\"\ncodec = \"hex\"\n").unwrap();
+        assert!(run_verify_tree(&Context::default(), &manifest).unwrap().fixtures[0].error.is_some());
+        std::fs::write(&manifest, "[[fixture]]\nfile = \"x\"\ncodec = \"hex\"\nencode = \"00\"\n").unwrap();
+        assert!(run_verify_tree(&Context::default(), &manifest).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,401 @@
+//! Regression corpus: saved inputs that once misbehaved, each with the outcome every codec gave
+//! it. Replaying a case runs the input through every codec again (encode, and decode in both
+//! modes) and reports panics and outcomes that differ from the recorded ones.
+//!
+//! A case is a `.case` text file:
+//!
+//! ```text
+//! # base64 decode of a lone '=' panicked (issue 123)
+//! input 3d
+//! enc base64 ok 2 e70e2ec9
+//! dec base64 strict err 10
+//! dec base64 lenient ok 0 00000000
+//! ```
+//!
+//! `input` is the hex of the input bytes, which decode reads as (lossy) UTF-8. An outcome is
+//! `ok LENGTH CRC32` of the output, `err EXIT-CODE`, or `panic`. Error messages are not recorded,
+//! so rewording one is not a regression.
+
+use std::collections::BTreeMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+use crate::checksum::Checksum;
+use crate::codec::Codec;
+use crate::error::{MbaseError, Result};
+use crate::types::{Context, Mode};
+
+/// File extension of corpus cases.
+pub const CASE_EXTENSION: &str = "case";
+
+/// What a codec was asked to do with a case's input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Operation {
+    Encode,
+    DecodeStrict,
+    DecodeLenient,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 3] = [Operation::Encode, Operation::DecodeStrict, Operation::DecodeLenient];
+
+    /// How the operation is written in a case file: `enc`, `dec MODE`.
+    fn words(self) -> &'static str {
+        match self {
+            Operation::Encode => "enc",
+            Operation::DecodeStrict => "dec strict",
+            Operation::DecodeLenient => "dec lenient",
+        }
+    }
+}
+
+/// The outcome of one operation. Outputs are kept as length and CRC-32, errors as exit code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Ok {
+        length: usize,
+        crc32: u32,
+    },
+    Err {
+        exit_code: u8,
+    },
+    /// The message is kept for the report but not written to case files.
+    Panic {
+        message: String,
+    },
+}
+
+impl Outcome {
+    fn of_output(output: &[u8]) -> Self {
+        Outcome::Ok {
+            length: output.len(),
+            crc32: Checksum::Crc32.compute(output),
+        }
+    }
+
+    fn of_error(error: &MbaseError) -> Self {
+        Outcome::Err {
+            exit_code: error.exit_code() as u8,
+        }
+    }
+
+    /// Whether `self` and `other` match as recorded (panic messages are not compared).
+    fn matches(&self, other: &Outcome) -> bool {
+        match (self, other) {
+            (Outcome::Panic { .. }, Outcome::Panic { .. }) => true,
+            _ => self == other,
+        }
+    }
+}
+
+impl core::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Outcome::Ok { length, crc32 } => write!(f, "ok {} {:08x}", length, crc32),
+            Outcome::Err { exit_code } => write!(f, "err {}", exit_code),
+            Outcome::Panic { .. } => write!(f, "panic"),
+        }
+    }
+}
+
+/// A saved input and the outcomes recorded for it, keyed by codec name and operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    /// File stem of the case.
+    pub name: String,
+    /// `#` lines at the top of the file: where the input came from, what went wrong.
+    pub notes: Vec<String>,
+    pub input: Vec<u8>,
+    pub expected: BTreeMap<(String, Operation), Outcome>,
+}
+
+/// A recorded outcome that replay did not reproduce, or a panic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub codec: String,
+    pub operation: Operation,
+    /// `None` when the case has no record for this codec and operation.
+    pub expected: Option<Outcome>,
+    /// `None` when the codec is no longer registered.
+    pub actual: Option<Outcome>,
+}
+
+impl Divergence {
+    pub fn operation_name(&self) -> &'static str {
+        self.operation.words()
+    }
+}
+
+fn panic_message(payload: &(dyn core::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+fn run_one(codec: &dyn Codec, operation: Operation, input: &[u8], text: &str) -> Outcome {
+    let result = catch_unwind(AssertUnwindSafe(|| match operation {
+        Operation::Encode => codec.encode(input).map(String::into_bytes),
+        Operation::DecodeStrict => codec.decode(text, Mode::Strict),
+        Operation::DecodeLenient => codec.decode(text, Mode::Lenient),
+    }));
+    match result {
+        Ok(Ok(output)) => Outcome::of_output(&output),
+        Ok(Err(e)) => Outcome::of_error(&e),
+        Err(payload) => Outcome::Panic {
+            message: panic_message(payload.as_ref()),
+        },
+    }
+}
+
+/// Runs `input` through every codec of `ctx`: encode, then decode in both modes. Panics are
+/// caught and returned as [`Outcome::Panic`]; the panic hook still runs, so callers that want
+/// quiet output should replace it first.
+pub fn outcomes(ctx: &Context, input: &[u8]) -> BTreeMap<(String, Operation), Outcome> {
+    let text = String::from_utf8_lossy(input);
+    let mut outcomes = BTreeMap::new();
+    for (_, codec) in ctx.registry.iter() {
+        for operation in Operation::ALL {
+            outcomes.insert((codec.name().to_string(), operation), run_one(codec, operation, input, &text));
+        }
+    }
+    outcomes
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn parse_outcome(words: &[&str]) -> Option<Outcome> {
+    match words {
+        ["ok", length, crc32] => Some(Outcome::Ok {
+            length: length.parse().ok()?,
+            crc32: u32::from_str_radix(crc32, 16).ok()?,
+        }),
+        ["err", code] => Some(Outcome::Err {
+            exit_code: code.parse().ok()?,
+        }),
+        ["panic"] => Some(Outcome::Panic { message: String::new() }),
+        _ => None,
+    }
+}
+
+impl Case {
+    /// A new case for `input`, recording what every codec of `ctx` does with it now.
+    pub fn record(ctx: &Context, name: impl Into<String>, input: Vec<u8>, notes: Vec<String>) -> Self {
+        let expected = outcomes(ctx, &input);
+        Self {
+            name: name.into(),
+            notes,
+            input,
+            expected,
+        }
+    }
+
+    /// Parses a case file; `name` is its file stem.
+    pub fn parse(name: &str, text: &str) -> Result<Self> {
+        let error =
+            |line: usize, message: &str| MbaseError::invalid_input(format!("{}.{} line {}: {}", name, CASE_EXTENSION, line, message));
+        let mut notes = Vec::new();
+        let mut input = None;
+        let mut expected = BTreeMap::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            if let Some(note) = line.strip_prefix('#') {
+                if input.is_none() {
+                    notes.push(note.trim().to_string());
+                }
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let (codec, operation, rest) = match words.as_slice() {
+                [] => continue,
+                ["input", data] => {
+                    input = Some(unhex(data).ok_or_else(|| error(line_no, "input is not hex"))?);
+                    continue;
+                }
+                ["input"] => {
+                    input = Some(Vec::new());
+                    continue;
+                }
+                ["enc", codec, rest @ ..] => (codec, Operation::Encode, rest),
+                ["dec", codec, "strict", rest @ ..] => (codec, Operation::DecodeStrict, rest),
+                ["dec", codec, "lenient", rest @ ..] => (codec, Operation::DecodeLenient, rest),
+                _ => return Err(error(line_no, "expected 'input HEX', 'enc CODEC OUTCOME' or 'dec CODEC MODE OUTCOME'")),
+            };
+            let outcome = parse_outcome(rest).ok_or_else(|| error(line_no, "expected 'ok LENGTH CRC32', 'err CODE' or 'panic'"))?;
+            expected.insert((codec.to_string(), operation), outcome);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            notes,
+            input: input.ok_or_else(|| error(text.lines().count(), "no input line"))?,
+            expected,
+        })
+    }
+
+    /// The case file text; [`Case::parse`] reads it back.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for note in &self.notes {
+            text.push_str(&format!("# {}\n", note));
+        }
+        text.push_str(&format!("input {}\n", hex(&self.input)));
+        for ((codec, operation), outcome) in &self.expected {
+            match operation {
+                Operation::Encode => text.push_str(&format!("enc {} {}\n", codec, outcome)),
+                Operation::DecodeStrict | Operation::DecodeLenient => {
+                    let mode = operation.words().trim_start_matches("dec ");
+                    text.push_str(&format!("dec {} {} {}\n", codec, mode, outcome));
+                }
+            }
+        }
+        text
+    }
+
+    /// Replays the case against `ctx`. Panics are always reported; codecs the case has no
+    /// record of are otherwise not, so adding a codec does not fail every case.
+    pub fn replay(&self, ctx: &Context) -> Vec<Divergence> {
+        let actual = outcomes(ctx, &self.input);
+        let mut divergences = Vec::new();
+        for ((codec, operation), outcome) in &actual {
+            let expected = self.expected.get(&(codec.clone(), *operation));
+            let diverged = match expected {
+                Some(expected) => !expected.matches(outcome),
+                None => false,
+            };
+            if diverged || matches!(outcome, Outcome::Panic { .. }) {
+                divergences.push(Divergence {
+                    codec: codec.clone(),
+                    operation: *operation,
+                    expected: expected.cloned(),
+                    actual: Some(outcome.clone()),
+                });
+            }
+        }
+        for ((codec, operation), outcome) in &self.expected {
+            if !actual.contains_key(&(codec.clone(), *operation)) {
+                divergences.push(Divergence {
+                    codec: codec.clone(),
+                    operation: *operation,
+                    expected: Some(outcome.clone()),
+                    actual: None,
+                });
+            }
+        }
+        divergences
+    }
+
+    /// Re-records every outcome from `ctx`, keeping the input and notes.
+    pub fn update(&mut self, ctx: &Context) {
+        self.expected = outcomes(ctx, &self.input);
+    }
+
+    /// Writes the case to `dir/NAME.case`.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::write(dir.join(format!("{}.{}", self.name, CASE_EXTENSION)), self.to_text())?;
+        Ok(())
+    }
+}
+
+/// Loads every `.case` file in `dir`, sorted by name.
+pub fn load_corpus(dir: &Path) -> Result<Vec<Case>> {
+    let mut cases = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != CASE_EXTENSION) {
+            continue;
+        }
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        cases.push(Case::parse(&name, &std::fs::read_to_string(&path)?)?);
+    }
+    cases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::registry::Registry;
+    use crate::types::{CaseSensitivity, CodecMeta, DetectCandidate, PaddingRule};
+
+    /// Panics on strict decoding of `!`, the kind of bug a corpus case pins down.
+    struct Brittle;
+
+    impl Codec for Brittle {
+        fn meta(&self) -> CodecMeta {
+            CodecMeta {
+                name: "brittle",
+                aliases: &[],
+                alphabet: "01",
+                multibase_code: None,
+                padding: PaddingRule::None,
+                case_sensitivity: CaseSensitivity::Sensitive,
+                description: "test codec",
+            }
+        }
+
+        fn encode(&self, input: &[u8]) -> Result<String> {
+            Ok(hex(input))
+        }
+
+        fn decode(&self, input: &str, mode: Mode) -> Result<Vec<u8>> {
+            if input == "!" && mode == Mode::Strict {
+                panic!("brittle decoder");
+            }
+            unhex(input).ok_or_else(|| MbaseError::invalid_input("not hex"))
+        }
+
+        fn detect_score(&self, _input: &str) -> DetectCandidate {
+            crate::codec::util::confidence::no_match("brittle")
+        }
+    }
+
+    fn context() -> Context {
+        Context::default().with_registry(Registry::with_codecs(vec![(1, Box::new(Brittle))]).unwrap())
+    }
+
+    #[test]
+    fn test_case_text_roundtrip() {
+        let case = Case::record(&Context::default(), "hello", b"aGVsbG8=".to_vec(), vec!["from a bug report".into()]);
+        let text = case.to_text();
+        assert!(text.starts_with("# from a bug report\ninput 614756736247383d\n"));
+        assert!(text.contains("dec base64pad strict ok 5 3610a686\n"));
+        assert!(text.contains("dec base16lower strict err 10\n"));
+        assert_eq!(Case::parse("hello", &text).unwrap(), case);
+        assert!(case.replay(&Context::default()).is_empty());
+        assert!(Case::parse("bad", "input zz\n").is_err());
+        assert!(Case::parse("bad", "enc base64 ok 1 0\n").is_err());
+    }
+
+    #[test]
+    fn test_replay_reports_panics_and_divergences() {
+        let ctx = context();
+        let case = Case::record(&ctx, "bang", b"!".to_vec(), Vec::new());
+        assert!(matches!(case.expected[&("brittle".to_string(), Operation::DecodeStrict)], Outcome::Panic { .. }));
+        let divergences = case.replay(&ctx);
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].operation_name(), "dec strict");
+        assert!(matches!(&divergences[0].actual, Some(Outcome::Panic { message }) if message == "brittle decoder"));
+
+        let mut case = Case::parse("edited", "input 6161\nenc brittle ok 4 00000000\nenc gone err 10\n").unwrap();
+        let divergences = case.replay(&ctx);
+        assert_eq!(divergences.len(), 2);
+        assert_eq!(divergences[0].expected, Some(Outcome::Ok { length: 4, crc32: 0 }));
+        assert_eq!((divergences[1].codec.as_str(), &divergences[1].actual), ("gone", &None));
+        case.update(&ctx);
+        assert!(case.replay(&ctx).is_empty());
+        assert_eq!(case.expected.len(), 3);
+    }
+}
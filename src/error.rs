@@ -1,6 +1,7 @@
-use std::process::ExitCode as StdExitCode;
 use thiserror::Error;
 
+use crate::prelude::*;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ExitCode {
@@ -10,11 +11,13 @@ pub enum ExitCode {
     ChecksumMismatch = 11,
     IoError = 12,
     UnsupportedCodec = 13,
+    LimitExceeded = 14,
 }
 
-impl From<ExitCode> for StdExitCode {
+#[cfg(feature = "std")]
+impl From<ExitCode> for std::process::ExitCode {
     fn from(code: ExitCode) -> Self {
-        StdExitCode::from(code as u8)
+        std::process::ExitCode::from(code as u8)
     }
 }
 
@@ -25,8 +28,8 @@ pub enum LengthConstraint {
     Range { min: usize, max: Option<usize> },
 }
 
-impl std::fmt::Display for LengthConstraint {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for LengthConstraint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             LengthConstraint::Exact(n) => write!(f, "exactly {}", n),
             LengthConstraint::MultipleOf(n) => write!(f, "multiple of {}", n),
@@ -36,6 +39,25 @@ impl std::fmt::Display for LengthConstraint {
     }
 }
 
+/// Which registry key two codecs collided on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryConflictKind {
+    /// A codec name or alias.
+    Name,
+    MultibaseCode,
+    Id,
+}
+
+impl core::fmt::Display for RegistryConflictKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RegistryConflictKind::Name => write!(f, "name"),
+            RegistryConflictKind::MultibaseCode => write!(f, "multibase code"),
+            RegistryConflictKind::Id => write!(f, "codec ID"),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum MbaseError {
     #[error("invalid input: {message}")]
@@ -44,6 +66,9 @@ pub enum MbaseError {
     #[error("invalid character '{char}' at position {position}")]
     InvalidCharacter { char: char, position: usize },
 
+    #[error("invalid input at position {position}: {message}")]
+    InvalidInputAt { position: usize, message: String },
+
     #[error("invalid length: expected {expected}, got {actual}{}", if !.message.is_empty() { format!(" ({})", .message) } else { String::new() })]
     InvalidLength {
         expected: LengthConstraint,
@@ -57,11 +82,26 @@ pub enum MbaseError {
     #[error("checksum mismatch")]
     ChecksumMismatch,
 
+    #[error("checksum mismatch at position {position}: {message}")]
+    ChecksumMismatchAt { position: usize, message: String },
+
+    #[cfg(feature = "std")]
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("unsupported codec: {name}")]
     UnsupportedCodec { name: String },
+
+    #[error("output too large: {size} bytes exceeds limit of {limit} bytes")]
+    OutputTooLarge { size: usize, limit: usize },
+
+    #[error("registry conflict: {kind} '{key}' is claimed by both '{first}' and '{second}'")]
+    RegistryConflict {
+        kind: RegistryConflictKind,
+        key: String,
+        first: String,
+        second: String,
+    },
 }
 
 impl MbaseError {
@@ -69,11 +109,15 @@ impl MbaseError {
         match self {
             MbaseError::InvalidInput { .. }
             | MbaseError::InvalidCharacter { .. }
+            | MbaseError::InvalidInputAt { .. }
             | MbaseError::InvalidLength { .. }
             | MbaseError::InvalidPadding { .. } => ExitCode::InvalidInput,
-            MbaseError::ChecksumMismatch => ExitCode::ChecksumMismatch,
+            MbaseError::ChecksumMismatch | MbaseError::ChecksumMismatchAt { .. } => ExitCode::ChecksumMismatch,
+            #[cfg(feature = "std")]
             MbaseError::Io(_) => ExitCode::IoError,
             MbaseError::UnsupportedCodec { .. } => ExitCode::UnsupportedCodec,
+            MbaseError::OutputTooLarge { .. } => ExitCode::LimitExceeded,
+            MbaseError::RegistryConflict { .. } => ExitCode::GeneralError,
         }
     }
 
@@ -86,6 +130,13 @@ impl MbaseError {
         Self::InvalidCharacter { char: ch, position: pos }
     }
 
+    pub fn invalid_input_at(position: usize, message: impl Into<String>) -> Self {
+        Self::InvalidInputAt {
+            position,
+            message: message.into(),
+        }
+    }
+
     pub fn invalid_length(expected: LengthConstraint, actual: usize) -> Self {
         Self::InvalidLength {
             expected,
@@ -106,6 +157,13 @@ impl MbaseError {
         Self::InvalidPadding { message: message.into() }
     }
 
+    pub fn checksum_mismatch_at(position: usize, message: impl Into<String>) -> Self {
+        Self::ChecksumMismatchAt {
+            position,
+            message: message.into(),
+        }
+    }
+
     pub fn unsupported_codec(name: impl Into<String>) -> Self {
         Self::UnsupportedCodec { name: name.into() }
     }
@@ -118,4 +176,4 @@ impl From<String> for MbaseError {
     }
 }
 
-pub type Result<T> = std::result::Result<T, MbaseError>;
+pub type Result<T> = core::result::Result<T, MbaseError>;
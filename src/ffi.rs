@@ -0,0 +1,254 @@
+//! C ABI over the codec registry. Build the shared library with
+//! `cargo build --release -p mbase-ffi` (the `ffi/` workspace member).
+//!
+//! Every function returns an [`ExitCode`] value as `int`, matching the CLI's exit codes, so the
+//! numbers are stable. Strings handed out by the library must be released with
+//! [`mbase_string_free`], byte buffers with [`mbase_bytes_free`]. The message for the last failure
+//! on the calling thread is available from [`mbase_last_error`]. A panic never unwinds into the
+//! caller: it is reported as `MBASE_ERR_GENERAL`. See `include/mbase.h`.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::codec::Registry;
+use crate::error::{ExitCode, MbaseError, Result};
+use crate::types::Mode;
+
+/// A required pointer argument was null.
+pub const MBASE_ERR_NULL: c_int = 2;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message).unwrap_or_else(|_| CString::new("error message contained NUL").unwrap());
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+fn report(result: Result<()>) -> c_int {
+    match result {
+        Ok(()) => {
+            LAST_ERROR.with(|e| *e.borrow_mut() = None);
+            ExitCode::Success as c_int
+        }
+        Err(e) => {
+            set_last_error(e.to_string());
+            e.exit_code() as c_int
+        }
+    }
+}
+
+/// Runs an entry point's body, turning a panic into `MBASE_ERR_GENERAL` with its message as the
+/// last error, since unwinding across `extern "C"` aborts the host.
+fn guard(body: impl FnOnce() -> c_int) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        set_last_error(format!("internal error: {}", message));
+        ExitCode::GeneralError as c_int
+    })
+}
+
+/// `p` must be non-null; callers reject null arguments with [`MBASE_ERR_NULL`] first.
+unsafe fn str_arg<'a>(p: *const c_char, what: &str) -> Result<&'a str> {
+    CStr::from_ptr(p)
+        .to_str()
+        .map_err(|_| MbaseError::invalid_input(format!("{} is not valid UTF-8", what)))
+}
+
+fn into_c_string(s: String) -> Result<*mut c_char> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|_| MbaseError::invalid_input("output contains NUL byte"))
+}
+
+/// Encodes `len` bytes at `data` with `codec`, storing a NUL-terminated string in `*out`.
+///
+/// # Safety
+/// `codec` must be a NUL-terminated string, `data` must point to `len` readable bytes (or be null
+/// when `len` is 0) and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn mbase_encode(codec: *const c_char, data: *const u8, len: usize, out: *mut *mut c_char) -> c_int {
+    guard(|| {
+        if codec.is_null() || out.is_null() || (data.is_null() && len > 0) {
+            set_last_error("null argument".to_string());
+            return MBASE_ERR_NULL;
+        }
+        *out = ptr::null_mut();
+        let input = if len == 0 { &[][..] } else { std::slice::from_raw_parts(data, len) };
+        report((|| {
+            let encoded = Registry::global().get(str_arg(codec, "codec")?)?.encode(input)?;
+            *out = into_c_string(encoded)?;
+            Ok(())
+        })())
+    })
+}
+
+/// Decodes the NUL-terminated `text` with `codec`. On success `*out` and `*out_len` describe a
+/// buffer owned by the caller. A non-zero `lenient` selects lenient mode.
+///
+/// # Safety
+/// `codec` and `text` must be NUL-terminated strings; `out` and `out_len` must be valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn mbase_decode(
+    codec: *const c_char,
+    text: *const c_char,
+    lenient: c_int,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> c_int {
+    guard(|| {
+        if codec.is_null() || text.is_null() || out.is_null() || out_len.is_null() {
+            set_last_error("null argument".to_string());
+            return MBASE_ERR_NULL;
+        }
+        *out = ptr::null_mut();
+        *out_len = 0;
+        let mode = if lenient != 0 { Mode::Lenient } else { Mode::Strict };
+        report((|| {
+            let decoded = Registry::global()
+                .get(str_arg(codec, "codec")?)?
+                .decode(str_arg(text, "text")?, mode)?;
+            *out_len = decoded.len();
+            *out = Box::into_raw(decoded.into_boxed_slice()) as *mut u8;
+            Ok(())
+        })())
+    })
+}
+
+/// Stores a JSON array of codec metadata (name, aliases, alphabet, ...) in `*out`.
+///
+/// # Safety
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn mbase_list_codecs(out: *mut *mut c_char) -> c_int {
+    guard(|| {
+        if out.is_null() {
+            set_last_error("null argument".to_string());
+            return MBASE_ERR_NULL;
+        }
+        *out = ptr::null_mut();
+        report((|| {
+            *out = into_c_string(serde_json::to_string(&Registry::global().metadata()).unwrap())?;
+            Ok(())
+        })())
+    })
+}
+
+/// Stores a JSON array of detection candidates for `text`, most likely first, in `*out`.
+///
+/// # Safety
+/// `text` must be a NUL-terminated string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn mbase_detect(text: *const c_char, out: *mut *mut c_char) -> c_int {
+    guard(|| {
+        if text.is_null() || out.is_null() {
+            set_last_error("null argument".to_string());
+            return MBASE_ERR_NULL;
+        }
+        *out = ptr::null_mut();
+        report((|| {
+            let candidates = Registry::global().detect(str_arg(text, "text")?.trim());
+            *out = into_c_string(serde_json::to_string(&candidates).unwrap())?;
+            Ok(())
+        })())
+    })
+}
+
+/// Message for the last failed call on this thread, or null. Valid until the next call.
+#[no_mangle]
+pub extern "C" fn mbase_last_error() -> *const c_char {
+    panic::catch_unwind(|| LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))).unwrap_or(ptr::null())
+}
+
+/// Releases a string returned by this library. Null is ignored.
+///
+/// # Safety
+/// `s` must come from this library and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn mbase_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(CString::from_raw(s))));
+    }
+}
+
+/// Releases a buffer returned by [`mbase_decode`]. Null is ignored.
+///
+/// # Safety
+/// `data` and `len` must be exactly as returned by `mbase_decode`, and not freed already.
+#[no_mangle]
+pub unsafe extern "C" fn mbase_bytes_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi_roundtrip() {
+        unsafe {
+            let mut text = ptr::null_mut();
+            assert_eq!(mbase_encode(c"base64pad".as_ptr(), b"hello".as_ptr(), 5, &mut text), 0);
+            assert_eq!(CStr::from_ptr(text).to_str().unwrap(), "aGVsbG8=");
+
+            let (mut bytes, mut len) = (ptr::null_mut(), 0);
+            assert_eq!(mbase_decode(c"base64pad".as_ptr(), text, 0, &mut bytes, &mut len), 0);
+            assert_eq!(std::slice::from_raw_parts(bytes, len), b"hello");
+            mbase_bytes_free(bytes, len);
+            mbase_string_free(text);
+        }
+    }
+
+    #[test]
+    fn test_ffi_error_codes() {
+        unsafe {
+            let mut text = ptr::null_mut();
+            assert_eq!(mbase_encode(c"nope".as_ptr(), ptr::null(), 0, &mut text), ExitCode::UnsupportedCodec as c_int);
+            assert!(text.is_null());
+            assert!(CStr::from_ptr(mbase_last_error()).to_str().unwrap().contains("nope"));
+
+            let (mut bytes, mut len) = (ptr::null_mut(), 0);
+            assert_eq!(mbase_decode(c"hex".as_ptr(), c"zz".as_ptr(), 0, &mut bytes, &mut len), ExitCode::InvalidInput as c_int);
+            assert_eq!(mbase_decode(c"hex".as_ptr(), c"zz".as_ptr(), 0, ptr::null_mut(), &mut len), MBASE_ERR_NULL);
+            assert_eq!(mbase_decode(ptr::null(), c"00".as_ptr(), 0, &mut bytes, &mut len), MBASE_ERR_NULL);
+            assert_eq!(mbase_decode(c"hex".as_ptr(), ptr::null(), 0, &mut bytes, &mut len), MBASE_ERR_NULL);
+            assert_eq!(mbase_encode(ptr::null(), ptr::null(), 0, &mut text), MBASE_ERR_NULL);
+            assert_eq!(mbase_detect(ptr::null(), &mut text), MBASE_ERR_NULL);
+        }
+    }
+
+    #[test]
+    fn test_ffi_panic_is_an_error_code() {
+        assert_eq!(guard(|| panic!("codec bug")), ExitCode::GeneralError as c_int);
+        let message = unsafe { CStr::from_ptr(mbase_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "internal error: codec bug");
+        assert_eq!(guard(|| MBASE_ERR_NULL), MBASE_ERR_NULL);
+    }
+
+    #[test]
+    fn test_ffi_list_and_detect() {
+        unsafe {
+            let mut json = ptr::null_mut();
+            assert_eq!(mbase_list_codecs(&mut json), 0);
+            assert!(CStr::from_ptr(json).to_str().unwrap().contains("\"base58btc\""));
+            mbase_string_free(json);
+
+            assert_eq!(mbase_detect(c"aGVsbG8=".as_ptr(), &mut json), 0);
+            let parsed: Vec<serde_json::Value> = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert!(parsed.iter().any(|c| c["codec"] == "base64pad"));
+            assert!(parsed
+                .windows(2)
+                .all(|w| w[0]["confidence"].as_f64() >= w[1]["confidence"].as_f64()));
+            mbase_string_free(json);
+        }
+    }
+}
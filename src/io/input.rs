@@ -1,22 +1,155 @@
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::{self, IsTerminal};
+use std::sync::RwLock;
 
+#[cfg(feature = "net")]
+use crate::error::MbaseError;
 use crate::error::Result;
 use crate::types::InputSource;
 
-pub fn read_input(source: &InputSource) -> Result<Vec<u8>> {
-    match source {
-        InputSource::Stdin => {
-            let mut buf = Vec::new();
-            io::stdin().read_to_end(&mut buf)?;
-            Ok(buf)
+/// What `read_input` does with whitespace at the ends of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputPolicy {
+    /// Bytes as read, except the Enter that ends input typed at a terminal. The default of
+    /// commands that take raw bytes (`enc`, `crc`, `sri`, `bits`, `regress --add`).
+    Bytes,
+    /// Without one trailing `\n` or `\r\n`. The default of commands that take encoded text.
+    StripNewline,
+    /// Exactly as read (`--keep-trailing-newline`).
+    Keep,
+    /// Without leading and trailing ASCII whitespace (`--trim`).
+    Trim,
+}
+
+impl InputPolicy {
+    /// Applies the policy to `data`; `typed` is set for stdin read from a terminal.
+    pub fn apply(self, data: &mut Vec<u8>, typed: bool) {
+        match self {
+            InputPolicy::Bytes if typed => super::prompt::trim_line_ending(data),
+            InputPolicy::StripNewline => super::prompt::trim_line_ending(data),
+            InputPolicy::Trim => {
+                let end = data.iter().rposition(|b| !b.is_ascii_whitespace()).map_or(0, |i| i + 1);
+                data.truncate(end);
+                let start = data.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(0);
+                data.drain(..start);
+            }
+            InputPolicy::Bytes | InputPolicy::Keep => {}
         }
-        InputSource::File(path) => {
-            let mut file = File::open(path)?;
-            let mut buf = Vec::new();
-            file.read_to_end(&mut buf)?;
-            Ok(buf)
+    }
+}
+
+static POLICY: RwLock<InputPolicy> = RwLock::new(InputPolicy::Bytes);
+
+/// Sets the policy every later `read_input` applies.
+pub fn set_input_policy(policy: InputPolicy) {
+    *POLICY.write().unwrap() = policy;
+}
+
+/// Largest response body `--in https://...` will read.
+#[cfg(feature = "net")]
+pub const MAX_FETCH_BYTES: u64 = 16 * 1024 * 1024;
+/// Overall deadline for connecting and reading a URL.
+#[cfg(feature = "net")]
+pub const FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+pub fn read_input(source: &InputSource) -> Result<Vec<u8>> {
+    let mut buf = match source {
+        InputSource::Stdin => super::prompt::read_stdin()?,
+        InputSource::File(path) => super::progress::read_file(File::open(path)?)?,
+        InputSource::Literal(data) => data.clone(),
+        #[cfg(feature = "net")]
+        InputSource::Url(url) => fetch(url, MAX_FETCH_BYTES, FETCH_TIMEOUT)?,
+    };
+    let typed = matches!(source, InputSource::Stdin) && io::stdin().is_terminal();
+    POLICY.read().unwrap().apply(&mut buf, typed);
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        let from = match source {
+            InputSource::Stdin => "stdin".to_string(),
+            InputSource::File(path) => path.display().to_string(),
+            InputSource::Literal(_) => "literal".to_string(),
+            #[cfg(feature = "net")]
+            InputSource::Url(url) => url.clone(),
+        };
+        tracing::debug!(%from, bytes = buf.len(), "read input");
+    }
+    Ok(buf)
+}
+
+/// GETs `url`, failing on non-2xx status, bodies over `limit` bytes, or when `timeout` elapses.
+#[cfg(feature = "net")]
+pub fn fetch(url: &str, limit: u64, timeout: std::time::Duration) -> Result<Vec<u8>> {
+    let agent: ureq::Agent = ureq::Agent::config_builder().timeout_global(Some(timeout)).build().into();
+    let fail = |e: ureq::Error| match e {
+        ureq::Error::BodyExceedsLimit(limit) => {
+            MbaseError::invalid_input(format!("response from {} exceeds the {} byte limit", url, limit))
         }
-        InputSource::Literal(data) => Ok(data.clone()),
+        ureq::Error::StatusCode(code) => MbaseError::invalid_input(format!("{} returned HTTP {}", url, code)),
+        ureq::Error::Io(e) => MbaseError::Io(e),
+        other => MbaseError::Io(io::Error::other(format!("fetching {}: {}", url, other))),
+    };
+    let mut response = agent.get(url).call().map_err(fail)?;
+    response.body_mut().with_config().limit(limit).read_to_vec().map_err(fail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "net")]
+    use std::io::{Read, Write};
+    #[cfg(feature = "net")]
+    use std::net::TcpListener;
+    #[cfg(feature = "net")]
+    use std::time::Duration;
+
+    #[test]
+    fn test_input_policy() {
+        let applied = |policy: InputPolicy, data: &[u8], typed: bool| {
+            let mut buf = data.to_vec();
+            policy.apply(&mut buf, typed);
+            buf
+        };
+        assert_eq!(applied(InputPolicy::Bytes, b"foo\n", false), b"foo\n");
+        assert_eq!(applied(InputPolicy::Bytes, b"foo\n", true), b"foo");
+        assert_eq!(applied(InputPolicy::StripNewline, b"foo\r\n\n", false), b"foo\r\n");
+        assert_eq!(applied(InputPolicy::Keep, b"foo\n", true), b"foo\n");
+        assert_eq!(applied(InputPolicy::Trim, b" \tfoo bar\r\n", false), b"foo bar");
+        assert_eq!(applied(InputPolicy::Trim, b" \n ", false), b"");
+    }
+
+    /// Serves one canned HTTP response on a loopback port and returns its URL.
+    #[cfg(feature = "net")]
+    fn serve_once(status: &'static str, body: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let head = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, body.len());
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+        });
+        format!("http://{}/payload", addr)
+    }
+
+    #[test]
+    #[cfg(feature = "net")]
+    fn test_fetch_reads_body() {
+        let url = serve_once("200 OK", b"SGVsbG8=");
+        assert_eq!(fetch(&url, 1024, Duration::from_secs(5)).unwrap(), b"SGVsbG8=");
+    }
+
+    #[test]
+    #[cfg(feature = "net")]
+    fn test_fetch_enforces_limit_and_status() {
+        let url = serve_once("200 OK", b"0123456789");
+        let err = fetch(&url, 4, Duration::from_secs(5)).unwrap_err();
+        assert!(err.to_string().contains("exceeds the 4 byte limit"), "{}", err);
+
+        let url = serve_once("404 Not Found", b"");
+        assert!(fetch(&url, 1024, Duration::from_secs(5))
+            .unwrap_err()
+            .to_string()
+            .contains("HTTP 404"));
     }
 }
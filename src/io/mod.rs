@@ -1,5 +1,15 @@
 mod input;
 mod output;
+mod presets;
+mod progress;
+mod prompt;
+mod recipes;
+mod records;
 
-pub use input::read_input;
-pub use output::{write_output, OutputConfig};
+pub use input::{read_input, set_input_policy, InputPolicy};
+pub use output::{write_output, OutputConfig, OutputDigest};
+pub use presets::find_preset;
+pub use progress::set_progress;
+pub use prompt::set_hidden_input;
+pub use recipes::{format_recipes, parse_recipes, read_recipes, recipes_path, write_recipes, Recipe, RecipeStep};
+pub use records::Records;
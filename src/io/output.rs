@@ -1,20 +1,39 @@
 use std::fs::File;
-use std::io::{self, IsTerminal, Write};
+use std::io::{self, IsTerminal, Seek, SeekFrom, Write};
 
+use crate::commands::SriAlgorithm;
 use crate::error::Result;
 use crate::types::OutputDest;
+use mbase::codec::Codec;
+
+/// `--print-digest`: a hash of the written bytes, reported on stderr once the output is complete.
+#[derive(Clone, Copy)]
+pub struct OutputDigest {
+    pub algorithm: SriAlgorithm,
+    pub codec: &'static dyn Codec,
+}
 
 pub struct OutputConfig {
     pub dest: OutputDest,
     pub force: bool,
+    pub digest: Option<OutputDigest>,
+    /// Leave all-zero blocks of a file output as holes instead of writing them.
+    pub sparse: bool,
 }
 
+/// Granularity of `--sparse` holes; a common filesystem block size.
+const SPARSE_BLOCK: usize = 4096;
+
 pub fn write_output(data: &[u8], config: &OutputConfig) -> Result<()> {
+    tracing::debug!(dest = ?config.dest, bytes = data.len(), "write output");
     match &config.dest {
         OutputDest::File(path) => {
             let mut file = File::create(path)?;
-            file.write_all(data)?;
-            Ok(())
+            if config.sparse {
+                write_sparse(&mut file, data)?;
+            } else {
+                file.write_all(data)?;
+            }
         }
         OutputDest::Stdout => {
             let stdout = io::stdout();
@@ -23,10 +42,28 @@ pub fn write_output(data: &[u8], config: &OutputConfig) -> Result<()> {
             } else {
                 let mut handle = stdout.lock();
                 handle.write_all(data)?;
+                handle.flush()?;
             }
-            Ok(())
         }
     }
+    if let Some(digest) = &config.digest {
+        let value = digest.codec.encode(&digest.algorithm.digest(data))?;
+        eprintln!("{} ({} bytes): {}", digest.algorithm.prefix(), data.len(), value);
+    }
+    Ok(())
+}
+
+/// Writes `data`, seeking over all-zero blocks so the filesystem can leave them unallocated.
+fn write_sparse(file: &mut File, data: &[u8]) -> io::Result<()> {
+    for block in data.chunks(SPARSE_BLOCK) {
+        if block.iter().all(|&b| b == 0) {
+            file.seek(SeekFrom::Current(block.len() as i64))?;
+        } else {
+            file.write_all(block)?;
+        }
+    }
+    // A trailing hole is only part of the file once the length says so.
+    file.set_len(data.len() as u64)
 }
 
 fn is_safe_for_terminal(data: &[u8]) -> bool {
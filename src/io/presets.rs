@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use crate::error::{MbaseError, Result};
+
+/// A named custom base64 table from the presets file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlphabetPreset {
+    pub name: String,
+    pub alphabet: String,
+    pub pad: Option<char>,
+}
+
+/// The path in `$var`, else `file` in the mbase config directory (`$XDG_CONFIG_HOME/mbase` or
+/// `~/.config/mbase`).
+pub(super) fn config_path(var: &str, file: &str) -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(var) {
+        return Some(PathBuf::from(path));
+    }
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("mbase").join(file))
+}
+
+/// `$MBASE_ALPHABETS`, else `alphabets` in the mbase config directory.
+pub fn presets_path() -> Option<PathBuf> {
+    config_path("MBASE_ALPHABETS", "alphabets")
+}
+
+/// Parses a presets file: one `NAME ALPHABET [PAD]` per line. Blank lines and lines starting
+/// with `#` are skipped; the alphabet itself may contain `#`.
+pub fn parse_presets(text: &str) -> Result<Vec<AlphabetPreset>> {
+    let mut presets = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (name, alphabet, pad) = match fields.as_slice() {
+            [name, alphabet] => (name, alphabet, None),
+            [name, alphabet, pad] if pad.chars().count() == 1 => (name, alphabet, pad.chars().next()),
+            _ => return Err(MbaseError::invalid_input(format!("alphabet presets line {}: expected NAME ALPHABET [PAD]", index + 1))),
+        };
+        presets.push(AlphabetPreset {
+            name: name.to_string(),
+            alphabet: alphabet.to_string(),
+            pad,
+        });
+    }
+    Ok(presets)
+}
+
+/// Looks `name` up in the presets file; a missing file has no presets.
+pub fn find_preset(name: &str) -> Result<AlphabetPreset> {
+    let path = presets_path();
+    let text = match &path {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        },
+        None => String::new(),
+    };
+    let location = path.map_or_else(|| "no presets file (set MBASE_ALPHABETS)".to_string(), |p| p.display().to_string());
+    parse_presets(&text)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| MbaseError::invalid_input(format!("'{}' is neither a 64-character alphabet nor a preset in {}", name, location)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_presets() {
+        let text = "# malware families\n\nfamily-a ZYXWVUTSRQPONMLKJIHGFEDCBAzyxwvutsrqponmlkjihgfedcba9876543210+/\n\
+                    proto  ./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz  ~\n";
+        let presets = parse_presets(text).unwrap();
+        assert_eq!(presets.len(), 2);
+        assert_eq!((presets[0].name.as_str(), presets[0].pad), ("family-a", None));
+        assert_eq!((presets[1].alphabet.len(), presets[1].pad), (64, Some('~')));
+        assert!(parse_presets("only-a-name\n").is_err());
+        assert!(parse_presets("name abc pad\n").is_err());
+    }
+}
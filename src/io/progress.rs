@@ -0,0 +1,52 @@
+use std::fs::File;
+use std::io::{self, IsTerminal, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Files smaller than this are read without a progress bar.
+pub const PROGRESS_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns progress bars on for this process; they are still only drawn when stderr is a terminal.
+pub fn set_progress(enabled: bool) {
+    ENABLED.store(enabled && io::stderr().is_terminal(), Ordering::Relaxed);
+}
+
+fn progress_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Reads `file` to the end, reporting bytes read on stderr when it is at least [`PROGRESS_THRESHOLD`] long.
+pub(super) fn read_file(mut file: File) -> io::Result<Vec<u8>> {
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut buf = Vec::with_capacity(usize::try_from(len).unwrap_or(0));
+    if !progress_enabled() || len < PROGRESS_THRESHOLD {
+        file.read_to_end(&mut buf)?;
+        return Ok(buf);
+    }
+
+    let bar = ProgressBar::with_draw_target(Some(len), ProgressDrawTarget::stderr()).with_style(
+        ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} {binary_bytes_per_sec} eta {eta}").expect("valid progress template"),
+    );
+    let result = bar.wrap_read(&mut file).read_to_end(&mut buf);
+    bar.finish_and_clear();
+    result?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_file_without_terminal() {
+        let path = std::env::temp_dir().join(format!("mbase-progress-{}", std::process::id()));
+        std::fs::write(&path, b"hello").unwrap();
+        set_progress(true);
+        let data = read_file(File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(data, b"hello");
+    }
+}
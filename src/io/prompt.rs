@@ -0,0 +1,115 @@
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shown on stderr when stdin is a terminal, so `mbase enc` does not look hung.
+pub const PROMPT: &str = "Enter data (Ctrl-D to finish):";
+pub const HIDDEN_PROMPT: &str = "Enter data (hidden, Enter to finish):";
+
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Makes terminal reads of stdin use a no-echo, single-line prompt (for secrets).
+pub fn set_hidden_input(hidden: bool) {
+    HIDDEN.store(hidden, Ordering::Relaxed);
+}
+
+/// Reads stdin to the end, prompting first when it is an interactive terminal.
+///
+/// With [`set_hidden_input`] a terminal read is one line typed without echo, and the line
+/// ending is not part of the data. Piped stdin is read as-is either way.
+pub(super) fn read_stdin() -> io::Result<Vec<u8>> {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        let mut buf = Vec::new();
+        stdin.lock().read_to_end(&mut buf)?;
+        return Ok(buf);
+    }
+    let hidden = HIDDEN.load(Ordering::Relaxed);
+    let mut stderr = io::stderr();
+    writeln!(stderr, "{}", if hidden { HIDDEN_PROMPT } else { PROMPT })?;
+
+    let mut buf = Vec::new();
+    if !hidden {
+        stdin.lock().read_to_end(&mut buf)?;
+        return Ok(buf);
+    }
+    {
+        let _echo = EchoOff::new()?;
+        stdin.lock().read_until(b'\n', &mut buf)?;
+    }
+    // The newline the user typed was not echoed either.
+    writeln!(stderr)?;
+    trim_line_ending(&mut buf);
+    Ok(buf)
+}
+
+/// Drops one trailing `\n` or `\r\n` from `buf`.
+pub(super) fn trim_line_ending(buf: &mut Vec<u8>) {
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+    }
+}
+
+/// Turns terminal echo off for stdin until dropped.
+#[cfg(unix)]
+struct EchoOff(libc::termios);
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> io::Result<Self> {
+        // SAFETY: `termios` is plain data that `tcgetattr` fills in; fd 0 is stdin.
+        unsafe {
+            let mut saved: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut saved) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut quiet = saved;
+            quiet.c_lflag &= !libc::ECHO;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &quiet) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(EchoOff(saved))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        // SAFETY: restores the attributes read in `new`.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct EchoOff;
+
+#[cfg(not(unix))]
+impl EchoOff {
+    fn new() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "--hidden is only supported on Unix terminals"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_line_ending() {
+        for (typed, data) in [
+            (&b"secret\n"[..], &b"secret"[..]),
+            (b"secret\r\n", b"secret"),
+            (b"secret", b"secret"),
+            (b"a\n\n", b"a\n"),
+        ] {
+            let mut buf = typed.to_vec();
+            trim_line_ending(&mut buf);
+            assert_eq!(buf, data);
+        }
+    }
+}
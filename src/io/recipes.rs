@@ -0,0 +1,153 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::presets::config_path;
+use crate::error::{MbaseError, Result};
+use mbase::bits::BitOp;
+use mbase::types::CodecOptions;
+
+/// One stage of a recipe, written `enc:CODEC[:KEY=VALUE...]`, `dec:CODEC[:KEY=VALUE...]` or
+/// `bits:OP`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipeStep {
+    Enc { codec: String, opts: Vec<String> },
+    Dec { codec: String, opts: Vec<String> },
+    Bits(BitOp),
+}
+
+impl RecipeStep {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || MbaseError::invalid_input(format!("recipe step '{}' is not enc:CODEC, dec:CODEC or bits:OP", spec));
+        let (op, rest) = spec.split_once(':').ok_or_else(invalid)?;
+        if op == "bits" {
+            return Ok(RecipeStep::Bits(BitOp::parse(rest)?));
+        }
+        let mut parts = rest.split(':');
+        let codec = parts.next().filter(|c| !c.is_empty()).ok_or_else(invalid)?.to_string();
+        let opts: Vec<String> = parts.map(String::from).collect();
+        CodecOptions::parse(&opts)?;
+        match op {
+            "enc" => Ok(RecipeStep::Enc { codec, opts }),
+            "dec" => Ok(RecipeStep::Dec { codec, opts }),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The codec the step uses, if any.
+    pub fn codec(&self) -> Option<&str> {
+        match self {
+            RecipeStep::Enc { codec, .. } | RecipeStep::Dec { codec, .. } => Some(codec),
+            RecipeStep::Bits(_) => None,
+        }
+    }
+
+    pub fn options(&self) -> Result<CodecOptions> {
+        match self {
+            RecipeStep::Enc { opts, .. } | RecipeStep::Dec { opts, .. } => CodecOptions::parse(opts),
+            RecipeStep::Bits(_) => Ok(CodecOptions::new()),
+        }
+    }
+}
+
+impl fmt::Display for RecipeStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, codec, opts) = match self {
+            RecipeStep::Bits(op) => return write!(f, "bits:{}", op),
+            RecipeStep::Enc { codec, opts } => ("enc", codec, opts),
+            RecipeStep::Dec { codec, opts } => ("dec", codec, opts),
+        };
+        write!(f, "{}:{}", op, codec)?;
+        opts.iter().try_for_each(|opt| write!(f, ":{}", opt))
+    }
+}
+
+/// A named pipeline of steps, applied to the input in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    pub name: String,
+    pub steps: Vec<RecipeStep>,
+}
+
+/// `$MBASE_RECIPES`, else `recipes` in the mbase config directory.
+pub fn recipes_path() -> Option<PathBuf> {
+    config_path("MBASE_RECIPES", "recipes")
+}
+
+/// Parses a recipe file: one `NAME STEP...` per line. Blank lines and lines starting with `#`
+/// are skipped; option values cannot contain `:` or whitespace.
+pub fn parse_recipes(text: &str) -> Result<Vec<Recipe>> {
+    let mut recipes: Vec<Recipe> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at_line = |e: MbaseError| MbaseError::invalid_input(format!("recipes line {}: {}", index + 1, e));
+        let mut fields = line.split_whitespace();
+        let name = fields.next().unwrap_or_default();
+        let steps = fields.map(RecipeStep::parse).collect::<Result<Vec<_>>>().map_err(at_line)?;
+        if steps.is_empty() {
+            return Err(at_line(MbaseError::invalid_input("expected NAME STEP...")));
+        }
+        if recipes.iter().any(|r| r.name == name) {
+            return Err(at_line(MbaseError::invalid_input(format!("recipe '{}' is defined twice", name))));
+        }
+        recipes.push(Recipe {
+            name: name.to_string(),
+            steps,
+        });
+    }
+    Ok(recipes)
+}
+
+/// The recipe file text for `recipes`, names aligned.
+pub fn format_recipes(recipes: &[Recipe]) -> String {
+    let width = recipes.iter().map(|r| r.name.chars().count()).max().unwrap_or(0);
+    let mut text = String::from("# mbase recipes: NAME STEP... (see `mbase recipes`)\n");
+    for recipe in recipes {
+        let steps: Vec<String> = recipe.steps.iter().map(ToString::to_string).collect();
+        text.push_str(&format!("{:<width$}  {}\n", recipe.name, steps.join(" "), width = width));
+    }
+    text
+}
+
+/// The recipes in `path`; a missing file has none.
+pub fn read_recipes(path: &Path) -> Result<Vec<Recipe>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse_recipes(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes `recipes` to `path`, creating its directory.
+pub fn write_recipes(path: &Path, recipes: &[Recipe]) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, format_recipes(recipes))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_recipes() {
+        let text = "# triage runbook\n\nbeacon  dec:base64 bits:not enc:hex\nrot dec:caesar:shift=5:alphabet=greek\n";
+        let recipes = parse_recipes(text).unwrap();
+        assert_eq!(recipes.len(), 2);
+        assert_eq!(recipes[0].steps[1], RecipeStep::Bits(BitOp::Not));
+        assert_eq!(recipes[1].steps[0].options().unwrap().get("alphabet"), Some("greek"));
+        assert_eq!(parse_recipes(&format_recipes(&recipes)).unwrap(), recipes);
+        assert!(format_recipes(&recipes).contains("\nrot     dec:caesar:shift=5:alphabet=greek\n"));
+
+        assert!(parse_recipes("lonely\n").is_err());
+        assert!(parse_recipes("a dec:base64\na enc:hex\n").is_err());
+        assert!(parse_recipes("a hex\n").is_err());
+        assert!(parse_recipes("a dec:\n").is_err());
+        assert!(parse_recipes("a dec:hex:noequals\n").is_err());
+        assert!(parse_recipes("a bits:spin\n").unwrap_err().to_string().contains("line 1"));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::{MbaseError, Result};
+use crate::io::read_input;
+use crate::types::InputSource;
+
+/// Record framing for batch mode: `--in0`/`--out0` use NUL, `--record-sep` sets both sides.
+///
+/// With only `--out0`, input records are lines; with only `--in0`, output records are lines.
+/// Every output record is followed by the separator, as with `find -print0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Records {
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+}
+
+impl Records {
+    pub fn from_flags(in0: bool, out0: bool, record_sep: Option<&str>) -> Result<Option<Self>> {
+        if !in0 && !out0 && record_sep.is_none() {
+            return Ok(None);
+        }
+        let sep = record_sep.map(parse_separator).transpose()?.unwrap_or_else(|| b"\n".to_vec());
+        Ok(Some(Self {
+            input: if in0 { vec![0] } else { sep.clone() },
+            output: if out0 { vec![0] } else { sep },
+        }))
+    }
+
+    /// Splits `data` on the input separator; a trailing separator does not start an empty record.
+    pub fn split<'a>(&self, data: &'a [u8]) -> Vec<&'a [u8]> {
+        let mut records = Vec::new();
+        let mut rest = data;
+        while !rest.is_empty() {
+            match rest.windows(self.input.len()).position(|w| w == self.input.as_slice()) {
+                Some(i) => {
+                    records.push(&rest[..i]);
+                    rest = &rest[i + self.input.len()..];
+                }
+                None => {
+                    records.push(rest);
+                    break;
+                }
+            }
+        }
+        records
+    }
+
+    /// Reads `source` and joins what `f` appends for each record with the output separator.
+    ///
+    /// `f` writes straight into the shared output buffer, so converting a record allocates
+    /// nothing beyond what the conversion itself needs.
+    pub fn process(&self, source: &InputSource, mut f: impl FnMut(&[u8], &mut Vec<u8>) -> Result<()>) -> Result<Vec<u8>> {
+        let data = read_input(source)?;
+        let mut out = Vec::with_capacity(data.len());
+        for (index, record) in self.split(&data).into_iter().enumerate() {
+            f(record, &mut out).inspect_err(|e| {
+                tracing::warn!(record = index + 1, error = %e, "record failed");
+            })?;
+            out.extend_from_slice(&self.output);
+        }
+        Ok(out)
+    }
+}
+
+/// Parses a separator with `\n`, `\r`, `\t`, `\0`, `\\` and `\xNN` escapes.
+pub fn parse_separator(s: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push(b'\n'),
+            Some('r') => out.push(b'\r'),
+            Some('t') => out.push(b'\t'),
+            Some('0') => out.push(0),
+            Some('\\') => out.push(b'\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 2)
+                    .ok_or_else(|| MbaseError::invalid_input(format!("invalid \\x escape in record separator: '\\x{}'", hex)))?;
+                out.push(byte);
+            }
+            other => {
+                return Err(MbaseError::invalid_input(format!(
+                    "unknown escape '\\{}' in record separator",
+                    other.map(String::from).unwrap_or_default()
+                )))
+            }
+        }
+    }
+    if out.is_empty() {
+        return Err(MbaseError::invalid_input("record separator must not be empty"));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_from_flags() {
+        assert_eq!(Records::from_flags(false, false, None).unwrap(), None);
+        let r = Records::from_flags(true, false, None).unwrap().unwrap();
+        assert_eq!((r.input, r.output), (vec![0], b"\n".to_vec()));
+        let r = Records::from_flags(false, true, Some("\\x1e")).unwrap().unwrap();
+        assert_eq!((r.input, r.output), (vec![0x1e], vec![0]));
+    }
+
+    #[test]
+    fn test_records_split() {
+        let r = Records::from_flags(true, true, None).unwrap().unwrap();
+        assert_eq!(r.split(b"a\nb\0c\0"), vec![&b"a\nb"[..], b"c"]);
+        assert_eq!(r.split(b"\0x"), vec![&b""[..], b"x"]);
+        let r = Records::from_flags(false, false, Some("::")).unwrap().unwrap();
+        assert_eq!(r.split(b"a::b:c"), vec![&b"a"[..], b"b:c"]);
+    }
+
+    #[test]
+    fn test_records_process() {
+        let r = Records::from_flags(true, false, None).unwrap().unwrap();
+        let out = r
+            .process(&InputSource::Literal(b"ab\0c".to_vec()), |record, out| {
+                out.extend(record.iter().map(u8::to_ascii_uppercase));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(out, b"AB\nC\n");
+    }
+
+    #[test]
+    fn test_parse_separator_escapes() {
+        assert_eq!(parse_separator("\\r\\n").unwrap(), b"\r\n");
+        assert_eq!(parse_separator("|").unwrap(), b"|");
+        assert!(parse_separator("").is_err());
+        assert!(parse_separator("\\xg0").is_err());
+        assert!(parse_separator("\\q").is_err());
+    }
+}
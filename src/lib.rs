@@ -1,6 +1,52 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod bits;
+pub mod checkdigit;
+pub mod checksum;
 pub mod codec;
+#[cfg(feature = "std")]
+pub mod corpus;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod prelude;
+pub mod serde_as;
+#[cfg(feature = "std")]
+pub mod stream;
 pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use error::{MbaseError, Result};
-pub use types::{CaseSensitivity, CodecMeta, Context, DetectCandidate, InputSource, Mode, OutputDest, PaddingRule};
+pub use types::{CaseSensitivity, CodecMeta, CodecMetadata, CodecOptions, Context, DetectCandidate, LenientPolicy, Mode, PaddingRule};
+#[cfg(feature = "std")]
+pub use types::{InputSource, OutputDest};
+
+use codec::Registry;
+use prelude::*;
+
+/// The built-in codecs, built on first use and shared by every thread; the same registry as
+/// [`Registry::global`].
+pub fn registry() -> &'static Registry {
+    Registry::global()
+}
+
+/// Encodes `input` with a built-in codec, by name or alias.
+///
+/// ```
+/// assert_eq!(mbase::encode("base58btc", b"hello").unwrap(), "Cn8eVZg");
+/// assert_eq!(mbase::decode("base58btc", "Cn8eVZg").unwrap(), b"hello");
+/// ```
+pub fn encode(codec: &str, input: &[u8]) -> Result<String> {
+    registry().get(codec)?.encode(input)
+}
+
+/// Decodes `input` with a built-in codec in strict mode. For lenient decoding or codec options,
+/// go through [`registry`].
+pub fn decode(codec: &str, input: &str) -> Result<Vec<u8>> {
+    registry().get(codec)?.decode(input, Mode::Strict)
+}
@@ -12,6 +12,7 @@ use mbase::{error, types, Context};
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
+    init_logging(cli.verbose, cli.log_json);
 
     match run(cli) {
         Ok(()) => ExitCode::SUCCESS,
@@ -22,8 +23,42 @@ fn main() -> ExitCode {
     }
 }
 
+fn init_logging(verbose: u8, json: bool) {
+    use tracing::level_filters::LevelFilter;
+
+    let level = match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .with_target(false);
+    if json {
+        builder.json().init();
+    } else {
+        builder
+            .with_ansi(std::io::IsTerminal::is_terminal(&std::io::stderr()))
+            .without_time()
+            .init();
+    }
+}
+
 fn run(cli: Cli) -> error::Result<()> {
-    let ctx = Context::default();
+    let ctx = Context::default()
+        .with_seed(cli.seed)
+        .with_max_output_size(cli.max_output_size)
+        .with_lenient_policy(cli.ignore_chars.unwrap_or_default());
+    io::set_progress(!cli.no_progress && !cli.log_json && !cli.command.json());
+    io::set_hidden_input(cli.hidden);
+    io::set_input_policy(if cli.trim {
+        io::InputPolicy::Trim
+    } else if cli.keep_trailing_newline {
+        io::InputPolicy::Keep
+    } else {
+        cli.command.input_policy()
+    });
 
     let handler: Box<dyn CommandHandler> = match cli.command {
         Command::Enc {
@@ -31,14 +66,28 @@ fn run(cli: Cli) -> error::Result<()> {
             r#in,
             out,
             multibase,
+            opts,
+            alphabet,
             all,
+            strip_trailing_newline: _,
+            max_len,
+            charset,
+            records,
+            digest,
             json,
         } => Box::new(commands::EncCommand {
             codec,
-            input: types::InputSource::parse(&r#in),
+            input: types::InputSource::parse(&r#in)?,
             output: types::OutputDest::parse(&out),
             multibase,
+            opts: alphabet.apply(types::CodecOptions::parse(&opts)?)?,
             all,
+            filter: commands::EncodeAllFilter {
+                max_len,
+                charset: charset.map(Into::into),
+            },
+            records: records.build()?,
+            digest: digest.build()?,
             json,
         }),
 
@@ -49,16 +98,43 @@ fn run(cli: Cli) -> error::Result<()> {
             mode,
             force,
             multibase,
+            opts,
+            alphabet,
             all,
+            all_shifts,
+            extract,
+            asn1,
+            sparse,
+            check,
+            try_siblings,
+            clean,
+            recover,
+            max_passes,
+            both_modes,
+            records,
+            digest,
             json,
         } => Box::new(commands::DecCommand {
             codec,
-            input: types::InputSource::parse(&r#in),
+            input: types::InputSource::parse(&r#in)?,
             output: types::OutputDest::parse(&out),
             mode: mode.into(),
             force,
             multibase,
+            opts: alphabet.apply(types::CodecOptions::parse(&opts)?)?,
             all,
+            all_shifts,
+            records: records.build()?,
+            digest: digest.build()?,
+            extract,
+            asn1,
+            sparse,
+            check,
+            try_siblings,
+            clean,
+            recover,
+            max_passes,
+            both_modes,
             json,
         }),
 
@@ -67,28 +143,67 @@ fn run(cli: Cli) -> error::Result<()> {
             to,
             r#in,
             out,
-            mode,
+            from_mode,
+            from_opts,
+            to_opts,
+            verify_roundtrip,
+            records,
+            digest,
             json,
         } => Box::new(commands::ConvCommand {
             from,
             to,
-            input: types::InputSource::parse(&r#in),
+            input: types::InputSource::parse(&r#in)?,
             output: types::OutputDest::parse(&out),
-            mode: mode.into(),
+            opts: commands::ConvOptions {
+                from_mode: from_mode.into(),
+                from_opts: types::CodecOptions::parse(&from_opts)?,
+                to_opts: types::CodecOptions::parse(&to_opts)?,
+                verify_roundtrip,
+            },
+            records: records.build()?,
+            digest: digest.build()?,
             json,
         }),
 
-        Command::List { json } => Box::new(commands::ListCommand { json }),
+        Command::List { long, sort, json } => Box::new(commands::ListCommand {
+            long,
+            sort: sort.map(Into::into),
+            json,
+        }),
 
-        Command::Info { codec, json } => Box::new(commands::InfoCommand { codec, json }),
+        Command::Info {
+            codec,
+            examples,
+            spec,
+            json,
+        } => Box::new(commands::InfoCommand {
+            codec,
+            examples,
+            spec,
+            json,
+        }),
 
-        Command::Verify { codec, r#in, mode, json } => Box::new(commands::VerifyCommand {
+        Command::Verify {
             codec,
-            input: types::InputSource::parse(&r#in),
+            r#in,
+            mode,
+            require_canonical,
+            scheme,
+            generate,
+            json,
+        } => Box::new(commands::VerifyCommand {
+            codec,
+            input: types::InputSource::parse(&r#in)?,
             mode: mode.into(),
+            require_canonical,
+            scheme: scheme.map(Into::into),
+            generate,
             json,
         }),
 
+        Command::VerifyTree { manifest, junit, json } => Box::new(commands::VerifyTreeCommand { manifest, junit, json }),
+
         Command::Fmt {
             codec,
             r#in,
@@ -97,28 +212,221 @@ fn run(cli: Cli) -> error::Result<()> {
             wrap,
             group,
             sep,
+            upper,
+            lower,
+            prefix,
+            suffix,
+            line_prefix,
+            check,
         } => Box::new(commands::FmtCommand {
             codec,
-            input: types::InputSource::parse(&r#in),
+            input: types::InputSource::parse(&r#in)?,
             output: types::OutputDest::parse(&out),
             mode: mode.into(),
             wrap,
             group,
             sep,
+            case: match (upper, lower) {
+                (true, _) => Some(commands::LetterCase::Upper),
+                (_, true) => Some(commands::LetterCase::Lower),
+                _ => None,
+            },
+            prefix,
+            suffix,
+            line_prefix,
+            check,
         }),
 
-        Command::Detect { r#in, json, top } => Box::new(commands::DetectCommand {
-            input: types::InputSource::parse(&r#in),
+        Command::Detect { r#in, json, top, fields } => Box::new(commands::DetectCommand {
+            input: types::InputSource::parse(&r#in)?,
             json,
             top,
+            fields,
         }),
 
-        Command::Explain { codec, r#in, mode, json } => Box::new(commands::ExplainCommand {
+        Command::Explain {
             codec,
-            input: types::InputSource::parse(&r#in),
+            r#in,
+            mode,
+            extract,
+            json,
+        } => Box::new(commands::ExplainCommand {
+            codec,
+            input: types::InputSource::parse(&r#in)?,
             mode: mode.into(),
+            extract,
             json,
         }),
+
+        Command::Analyze {
+            codec,
+            r#in,
+            top,
+            known_prefix,
+            json,
+        } => Box::new(commands::AnalyzeCommand {
+            codec,
+            input: types::InputSource::parse(&r#in)?,
+            top,
+            known_prefix: known_prefix.as_deref().map(types::InputSource::parse).transpose()?,
+            json,
+        }),
+
+        Command::Dns { r#in, to, json } => Box::new(commands::DnsCommand {
+            input: types::InputSource::parse(&r#in)?,
+            to,
+            json,
+        }),
+
+        Command::Sri { r#in, algo, verify, json } => Box::new(commands::SriCommand {
+            input: types::InputSource::parse(&r#in)?,
+            algorithm: algo.into(),
+            verify,
+            json,
+        }),
+
+        Command::Crc {
+            r#in,
+            algo,
+            decode,
+            mode,
+            to,
+            json,
+        } => Box::new(commands::CrcCommand {
+            input: types::InputSource::parse(&r#in)?,
+            algorithm: algo.into(),
+            decode,
+            mode: mode.into(),
+            to,
+            json,
+        }),
+
+        Command::Cmp {
+            codec_a,
+            in_a,
+            codec_b,
+            in_b,
+            mode,
+            json,
+        } => Box::new(commands::CmpCommand {
+            codec_a,
+            input_a: types::InputSource::parse(&in_a)?,
+            codec_b,
+            input_b: types::InputSource::parse(&in_b)?,
+            mode: mode.into(),
+            json,
+        }),
+
+        Command::Bits { r#in, out, ops, force } => Box::new(commands::BitsCommand {
+            input: types::InputSource::parse(&r#in)?,
+            output: types::OutputDest::parse(&out),
+            ops: ops.iter().map(|op| mbase::bits::BitOp::parse(op)).collect::<Result<_, _>>()?,
+            force,
+        }),
+
+        Command::Radix {
+            r#in,
+            from,
+            to,
+            from_alphabet,
+            to_alphabet,
+            json,
+        } => Box::new(commands::RadixCommand {
+            input: types::InputSource::parse(&r#in)?,
+            from: commands::Radix::new(from, from_alphabet.as_deref())?,
+            to: commands::Radix::new(to, to_alphabet.as_deref())?,
+            json,
+        }),
+
+        Command::Qr { r#in, codecs, json } => Box::new(commands::QrCommand {
+            input: types::InputSource::parse(&r#in)?,
+            codecs,
+            json,
+        }),
+
+        Command::Rand { codec, bytes, opts, json } => Box::new(commands::RandCommand {
+            codec,
+            bytes,
+            opts: types::CodecOptions::parse(&opts)?,
+            json,
+        }),
+
+        Command::Jwt { r#in, mode, json } => Box::new(commands::JwtCommand {
+            input: types::InputSource::parse(&r#in)?,
+            mode: mode.into(),
+            json,
+        }),
+
+        Command::Hcert { r#in, json } => Box::new(commands::HcertCommand {
+            input: types::InputSource::parse(&r#in)?,
+            json,
+        }),
+
+        Command::Key { r#in, json } => Box::new(commands::KeyCommand {
+            input: types::InputSource::parse(&r#in)?,
+            json,
+        }),
+
+        Command::LintCodec {
+            alphabet,
+            prefix,
+            case_insensitive,
+            json,
+        } => Box::new(commands::LintCodecCommand {
+            alphabet,
+            opts: mbase::codec::lint::LintOptions { case_insensitive, prefix },
+            json,
+        }),
+
+        Command::Regress {
+            corpus,
+            add,
+            r#in,
+            note,
+            update,
+            json,
+        } => Box::new(commands::RegressCommand {
+            corpus,
+            add,
+            input: types::InputSource::parse(&r#in)?,
+            notes: note,
+            update,
+            json,
+        }),
+
+        Command::Recipes {
+            save,
+            steps,
+            import,
+            export,
+            names,
+            replace,
+            json,
+        } => Box::new(commands::RecipesCommand {
+            save,
+            steps,
+            import,
+            export,
+            names,
+            replace,
+            json,
+        }),
+
+        Command::Run {
+            recipe,
+            r#in,
+            out,
+            mode,
+            force,
+        } => Box::new(commands::RunCommand {
+            recipe,
+            input: types::InputSource::parse(&r#in)?,
+            output: types::OutputDest::parse(&out),
+            mode: mode.into(),
+            force,
+        }),
+
+        Command::Schema { report } => Box::new(commands::SchemaCommand { report }),
     };
 
     handler.execute(&ctx)
@@ -0,0 +1,7 @@
+//! `alloc` items that the std prelude would otherwise provide, so the codec core builds without std.
+
+pub use alloc::boxed::Box;
+pub use alloc::format;
+pub use alloc::string::{String, ToString};
+pub use alloc::vec;
+pub use alloc::vec::Vec;
@@ -0,0 +1,165 @@
+//! Field adapters for `#[serde(with = "...")]` that store bytes as text in a chosen codec.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Token {
+//!     #[serde(with = "mbase::serde_as::Base64Url")]
+//!     nonce: Vec<u8>,
+//!     #[serde(with = "mbase::serde_as::HexLenient")]
+//!     digest: Vec<u8>,
+//! }
+//! ```
+//!
+//! Other codecs or modes can be declared with [`serde_codec!`](crate::serde_codec).
+
+use core::marker::PhantomData;
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+use crate::codec::Registry;
+use crate::prelude::*;
+use crate::types::Mode;
+
+/// Binds an adapter to a registry codec name and decode mode.
+pub trait CodecSpec {
+    const CODEC: &'static str;
+    const MODE: Mode;
+}
+
+/// Generic adapter; the named adapters below delegate to it.
+pub struct As<C>(PhantomData<C>);
+
+impl<C: CodecSpec> As<C> {
+    pub fn serialize<S, T>(bytes: &T, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: AsRef<[u8]> + ?Sized,
+    {
+        let encoded = Registry::global()
+            .get(C::CODEC)
+            .and_then(|codec| codec.encode(bytes.as_ref()))
+            .map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&encoded)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> core::result::Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: From<Vec<u8>>,
+    {
+        let text = String::deserialize(deserializer)?;
+        Registry::global()
+            .get(C::CODEC)
+            .and_then(|codec| codec.decode(&text, C::MODE))
+            .map(T::from)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Declares a `#[serde(with = "...")]` adapter for any registered codec.
+///
+/// ```
+/// mbase::serde_codec!(pub Crockford, "crockford32", mbase::Mode::Lenient);
+///
+/// #[derive(serde::Serialize)]
+/// struct Id {
+///     #[serde(with = "Crockford")]
+///     raw: Vec<u8>,
+/// }
+/// ```
+#[macro_export]
+macro_rules! serde_codec {
+    ($vis:vis $name:ident, $codec:expr, $mode:expr) => {
+        $vis struct $name;
+
+        impl $crate::serde_as::CodecSpec for $name {
+            const CODEC: &'static str = $codec;
+            const MODE: $crate::Mode = $mode;
+        }
+
+        impl $name {
+            pub fn serialize<S, T>(bytes: &T, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+                T: AsRef<[u8]> + ?Sized,
+            {
+                $crate::serde_as::As::<$name>::serialize(bytes, serializer)
+            }
+
+            pub fn deserialize<'de, D, T>(deserializer: D) -> ::core::result::Result<T, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+                T: From<$crate::serde_as::Bytes>,
+            {
+                $crate::serde_as::As::<$name>::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+/// Byte buffer type produced by the adapters.
+pub type Bytes = Vec<u8>;
+
+serde_codec!(pub Hex, "base16lower", Mode::Strict);
+serde_codec!(pub HexLenient, "base16lower", Mode::Lenient);
+serde_codec!(pub Base32, "base32padupper", Mode::Strict);
+serde_codec!(pub Base58, "base58btc", Mode::Strict);
+serde_codec!(pub Base64, "base64pad", Mode::Strict);
+serde_codec!(pub Base64Lenient, "base64pad", Mode::Lenient);
+serde_codec!(pub Base64Url, "base64url", Mode::Strict);
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "Base64")]
+        key: Vec<u8>,
+        #[serde(with = "HexLenient")]
+        digest: Vec<u8>,
+        #[serde(with = "Base58")]
+        id: Vec<u8>,
+    }
+
+    #[test]
+    fn test_serde_as_roundtrip() {
+        let record = Record {
+            key: b"hello".to_vec(),
+            digest: vec![0xde, 0xad, 0xbe, 0xef],
+            id: b"hello".to_vec(),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"key":"aGVsbG8=","digest":"deadbeef","id":"Cn8eVZg"}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn test_serde_as_modes() {
+        let lenient = r#"{"key":"aGVsbG8=","digest":"de ad\nbe ef","id":"Cn8eVZg"}"#;
+        assert_eq!(serde_json::from_str::<Record>(lenient).unwrap().digest, vec![0xde, 0xad, 0xbe, 0xef]);
+
+        let strict = r#"{"key":"aGVs bG8=","digest":"deadbeef","id":"Cn8eVZg"}"#;
+        let err = serde_json::from_str::<Record>(strict).unwrap_err();
+        assert!(err.to_string().contains("invalid"), "{}", err);
+    }
+
+    #[test]
+    fn test_serde_codec_macro() {
+        serde_codec!(Upper, "base16upper", Mode::Strict);
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapped {
+            #[serde(with = "Upper")]
+            data: Vec<u8>,
+        }
+
+        let json = serde_json::to_string(&Wrapped { data: vec![0xab, 0xcd] }).unwrap();
+        assert_eq!(json, r#"{"data":"ABCD"}"#);
+        assert!(serde_json::from_str::<Wrapped>(r#"{"data":"abcd"}"#).is_err());
+    }
+}
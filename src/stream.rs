@@ -0,0 +1,328 @@
+//! `std::io` adapters for codecs that can be applied piecewise (see [`Codec::stream_block`]).
+//!
+//! ```
+//! use std::io::{Read, Write};
+//! use mbase::codec::Registry;
+//! use mbase::stream::{DecodeWriter, EncodeReader};
+//! use mbase::Mode;
+//!
+//! let base64 = Registry::global().get("base64pad").unwrap();
+//! let mut text = String::new();
+//! EncodeReader::new(base64, &b"hello world"[..]).unwrap().read_to_string(&mut text).unwrap();
+//! assert_eq!(text, "aGVsbG8gd29ybGQ=");
+//!
+//! let mut writer = DecodeWriter::new(base64, Mode::Strict, Vec::new()).unwrap();
+//! writer.write_all(text.as_bytes()).unwrap();
+//! assert_eq!(writer.finish().unwrap(), b"hello world");
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::codec::{Codec, StreamDecoder, StreamEncoder};
+use crate::error::{MbaseError, Result};
+use crate::types::Mode;
+
+/// Blocks processed per underlying read or decode call.
+const BLOCKS_PER_CHUNK: usize = 1024;
+/// Bytes (or characters) handed to a stateful encoder or decoder at a time.
+const STATE_CHUNK: usize = 4096;
+
+fn io_error(e: MbaseError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn unsupported(codec: &dyn Codec) -> MbaseError {
+    MbaseError::invalid_input(format!("codec '{}' does not support streaming", codec.name()))
+}
+
+/// Runs a fixed-block codec ([`Codec::stream_block`]) one whole block at a time.
+struct BlockEncoder<'a> {
+    codec: &'a dyn Codec,
+    block: usize,
+    pending: Vec<u8>,
+}
+
+impl StreamEncoder for BlockEncoder<'_> {
+    fn update(&mut self, input: &[u8], out: &mut String) -> Result<()> {
+        self.pending.extend_from_slice(input);
+        let take = self.pending.len() - self.pending.len() % self.block;
+        if take > 0 {
+            out.push_str(&self.codec.encode(&self.pending[..take])?);
+            self.pending.drain(..take);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut String) -> Result<()> {
+        if !self.pending.is_empty() {
+            out.push_str(&self.codec.encode(&self.pending)?);
+            self.pending.clear();
+        }
+        Ok(())
+    }
+}
+
+struct BlockDecoder<'a> {
+    codec: &'a dyn Codec,
+    mode: Mode,
+    block: usize,
+    pending: String,
+}
+
+impl StreamDecoder for BlockDecoder<'_> {
+    fn update(&mut self, input: &str, out: &mut Vec<u8>) -> Result<()> {
+        self.pending.push_str(input);
+        let take = self.pending.len() - self.pending.len() % self.block;
+        if take > 0 {
+            out.extend_from_slice(&self.codec.decode(&self.pending[..take], self.mode)?);
+            self.pending.drain(..take);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        if !self.pending.is_empty() {
+            out.extend_from_slice(&self.codec.decode(&self.pending, self.mode)?);
+            self.pending.clear();
+        }
+        Ok(())
+    }
+}
+
+/// The codec's own stateful encoder, else a block-wise one; and how much input to gather per call.
+fn encoder(codec: &dyn Codec) -> Result<(Box<dyn StreamEncoder + '_>, usize)> {
+    if let Some(encoder) = codec.stream_encoder() {
+        return Ok((encoder, STATE_CHUNK));
+    }
+    let (block, _) = codec.stream_block().ok_or_else(|| unsupported(codec))?;
+    let encoder = BlockEncoder {
+        codec,
+        block,
+        pending: Vec::new(),
+    };
+    Ok((Box::new(encoder), block * BLOCKS_PER_CHUNK))
+}
+
+fn decoder(codec: &dyn Codec, mode: Mode) -> Result<(Box<dyn StreamDecoder + '_>, usize)> {
+    if let Some(decoder) = codec.stream_decoder(mode) {
+        return Ok((decoder, STATE_CHUNK));
+    }
+    let (_, block) = codec.stream_block().ok_or_else(|| unsupported(codec))?;
+    let decoder = BlockDecoder {
+        codec,
+        mode,
+        block,
+        pending: String::new(),
+    };
+    Ok((Box::new(decoder), block * BLOCKS_PER_CHUNK))
+}
+
+/// Reads bytes from `R` and yields their encoding.
+pub struct EncodeReader<'a, R> {
+    inner: R,
+    encoder: Box<dyn StreamEncoder + 'a>,
+    chunk: usize,
+    pending: Vec<u8>,
+    encoded: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<'a, R: Read> EncodeReader<'a, R> {
+    pub fn new(codec: &'a dyn Codec, inner: R) -> Result<Self> {
+        let (encoder, chunk) = encoder(codec)?;
+        Ok(Self {
+            inner,
+            encoder,
+            chunk,
+            pending: Vec::new(),
+            encoded: Vec::new(),
+            pos: 0,
+            done: false,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn refill(&mut self) -> io::Result<()> {
+        self.pending.clear();
+        while self.pending.len() < self.chunk {
+            let start = self.pending.len();
+            self.pending.resize(self.chunk, 0);
+            let n = self.inner.read(&mut self.pending[start..])?;
+            self.pending.truncate(start + n);
+            if n == 0 {
+                self.done = true;
+                break;
+            }
+        }
+
+        let mut text = String::new();
+        self.encoder.update(&self.pending, &mut text).map_err(io_error)?;
+        if self.done {
+            self.encoder.finish(&mut text).map_err(io_error)?;
+        }
+        self.encoded = text.into_bytes();
+        self.pos = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for EncodeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.encoded.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.refill()?;
+        }
+        let n = buf.len().min(self.encoded.len() - self.pos);
+        buf[..n].copy_from_slice(&self.encoded[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Accepts encoded text and writes the decoded bytes to `W`.
+///
+/// Call [`finish`](Self::finish) once all text is written; the trailing partial block is only
+/// decoded there. Error positions of block codecs are relative to the block being decoded. In
+/// lenient mode ASCII whitespace is dropped before blocking.
+pub struct DecodeWriter<'a, W> {
+    inner: W,
+    decoder: Box<dyn StreamDecoder + 'a>,
+    mode: Mode,
+    chunk: usize,
+    pending: String,
+}
+
+impl<'a, W: Write> DecodeWriter<'a, W> {
+    pub fn new(codec: &'a dyn Codec, mode: Mode, inner: W) -> Result<Self> {
+        let (decoder, chunk) = decoder(codec, mode)?;
+        Ok(Self {
+            inner,
+            decoder,
+            mode,
+            chunk,
+            pending: String::new(),
+        })
+    }
+
+    fn decode_pending(&mut self) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        self.decoder.update(&self.pending, &mut bytes).map_err(io_error)?;
+        self.pending.clear();
+        self.inner.write_all(&bytes)
+    }
+
+    /// Decodes any buffered text, flushes, and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.decode_pending()?;
+        let mut bytes = Vec::new();
+        self.decoder.finish(&mut bytes).map_err(io_error)?;
+        self.inner.write_all(&bytes)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for DecodeWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &b in buf {
+            if !b.is_ascii() {
+                return Err(io_error(MbaseError::invalid_input(format!("non-ASCII byte 0x{:02x} in encoded stream", b))));
+            }
+            if self.mode == Mode::Lenient && b.is_ascii_whitespace() {
+                continue;
+            }
+            self.pending.push(b as char);
+            if self.pending.len() >= self.chunk {
+                self.decode_pending()?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.decode_pending()?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::Registry;
+
+    /// Hands out at most `n` bytes per read to exercise block reassembly.
+    struct Trickle<'a>(&'a [u8], usize);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.1.min(buf.len()).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_stream_matches_one_shot() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 251) as u8).collect();
+        for name in [
+            "base64pad",
+            "base64url",
+            "base32padlower",
+            "base16upper",
+            "base45",
+            "base2",
+            "base91",
+            "base92chunked",
+        ] {
+            let codec = Registry::global().get(name).unwrap();
+            let expected = codec.encode(&data).unwrap();
+
+            let mut text = String::new();
+            EncodeReader::new(codec, Trickle(&data, 7))
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            assert_eq!(text, expected, "{}", name);
+
+            let mut writer = DecodeWriter::new(codec, Mode::Strict, Vec::new()).unwrap();
+            for piece in expected.as_bytes().chunks(13) {
+                writer.write_all(piece).unwrap();
+            }
+            assert_eq!(writer.finish().unwrap(), data, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_decode_writer_lenient_skips_whitespace() {
+        let codec = Registry::global().get("base64pad").unwrap();
+        let mut writer = DecodeWriter::new(codec, Mode::Lenient, Vec::new()).unwrap();
+        writer.write_all(b"aGVs\nbG8g\r\nd29y bGQ=\n").unwrap();
+        assert_eq!(writer.finish().unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        for name in ["morse", "base92"] {
+            let codec = Registry::global().get(name).unwrap();
+            assert!(EncodeReader::new(codec, &b""[..]).is_err());
+        }
+        // Code pages emit non-ASCII text and fold CRLF, which the ASCII block writer cannot carry.
+        for name in ["ebcdic", "petscii"] {
+            let codec = Registry::global().get(name).unwrap();
+            assert!(EncodeReader::new(codec, &b""[..]).is_err());
+            assert!(DecodeWriter::new(codec, Mode::Strict, Vec::new()).is_err());
+        }
+
+        let hex = Registry::global().get("base16lower").unwrap();
+        let mut writer = DecodeWriter::new(hex, Mode::Strict, Vec::new()).unwrap();
+        writer.write_all(b"abzz").unwrap();
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
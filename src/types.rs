@@ -1,23 +1,205 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
 use serde::Serialize;
+#[cfg(feature = "std")]
 use std::path::PathBuf;
 
-use crate::codec::Registry;
+use crate::codec::confusables::{self, Substitution};
+use crate::codec::{util, Codec, Registry};
+use crate::error::{MbaseError, Result};
+use crate::prelude::*;
 
 pub struct Context {
-    pub registry: &'static Registry,
+    /// The codecs this context can reach; see [`Context::with_registry`].
+    pub registry: Registry,
+    /// Seed for commands that use randomness; `None` means pick one per invocation.
+    pub seed: Option<u64>,
+    /// Largest encoded or decoded output allowed, in bytes.
+    pub max_output_size: Option<usize>,
+    /// Separators dropped from input before a lenient decode.
+    pub lenient: LenientPolicy,
 }
 
 impl Context {
-    pub fn new(registry: &'static Registry) -> Self {
-        Self { registry }
+    pub fn new(registry: Registry) -> Self {
+        Self {
+            registry,
+            seed: None,
+            max_output_size: None,
+            lenient: LenientPolicy::default(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_max_output_size(mut self, limit: Option<usize>) -> Self {
+        self.max_output_size = limit;
+        self
+    }
+
+    /// Swaps in another registry, such as [`Registry::filtered`] to sandbox which codecs an
+    /// embedding application offers.
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_lenient_policy(mut self, policy: LenientPolicy) -> Self {
+        self.lenient = policy;
+        self
+    }
+
+    pub fn check_output_size(&self, size: usize) -> Result<()> {
+        match self.max_output_size {
+            Some(limit) if size > limit => Err(MbaseError::OutputTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks an output size known before encoding or decoding, if any.
+    fn check_known_size(&self, size: Option<usize>) -> Result<()> {
+        match size {
+            Some(size) if self.max_output_size.is_some() => self.check_output_size(size),
+            _ => Ok(()),
+        }
+    }
+
+    /// `opts` carrying the output limit, for codecs that check it while building output.
+    fn limited<'a>(&self, opts: &'a CodecOptions) -> Cow<'a, CodecOptions> {
+        match self.max_output_size {
+            Some(limit) => Cow::Owned(opts.clone().with_output_limit(Some(limit))),
+            None => Cow::Borrowed(opts),
+        }
+    }
+
+    /// Encodes under the output limit. Codecs that know their exact encoded length
+    /// ([`Codec::encoded_len`]) are rejected before any output is built; codecs that build large
+    /// output stop as soon as it passes the limit.
+    pub fn encode(&self, codec: &dyn Codec, input: &[u8], opts: &CodecOptions) -> Result<String> {
+        self.check_known_size(codec.encoded_len(input.len(), opts))?;
+        let encoded = codec.encode_with(input, &self.limited(opts))?;
+        tracing::debug!(codec = codec.name(), input_len = input.len(), output_len = encoded.len(), "encoded");
+        self.check_output_size(encoded.len())?;
+        Ok(encoded)
+    }
+
+    /// `input` after the lenient policy, as [`Context::decode`] hands it to `codec`.
+    pub fn apply_lenient_policy<'a>(&self, codec: &dyn Codec, input: &'a str, mode: Mode) -> Cow<'a, str> {
+        util::clean_with_policy(input, mode, &self.lenient, |c| codec.is_symbol(c))
+    }
+
+    /// `input` with lookalike characters folded to ASCII, for a lenient decode the codec rejected
+    /// as is; `None` when nothing was folded. Text-only codecs and codecs without a symbol
+    /// alphabet (word lists) take any character literally and are never folded.
+    fn fold_lookalikes(&self, codec: &dyn Codec, input: &str, mode: Mode) -> Option<(String, Vec<Substitution>)> {
+        let alphabet = codec.meta().alphabet;
+        if mode == Mode::Strict || codec.text_only() || alphabet.is_empty() {
+            return None;
+        }
+        match confusables::fold(input, alphabet) {
+            (Cow::Owned(folded), substitutions) => Some((folded, substitutions)),
+            (Cow::Borrowed(_), _) => None,
+        }
+    }
+
+    /// Non-fatal notes on a lenient decode of `input`: each lookalike character that had to be
+    /// replaced, then the codec's own [`Codec::decode_warnings`].
+    pub fn decode_warnings(&self, codec: &dyn Codec, input: &str, mode: Mode, opts: &CodecOptions) -> Vec<String> {
+        let cleaned = self.apply_lenient_policy(codec, input, mode);
+        if codec.decode_with(&cleaned, mode, opts).is_err() {
+            if let Some((folded, substitutions)) = self.fold_lookalikes(codec, input, mode) {
+                let mut warnings: Vec<String> = substitutions.iter().map(Substitution::warning).collect();
+                warnings.extend(codec.decode_warnings(&self.apply_lenient_policy(codec, &folded, mode), mode));
+                return warnings;
+            }
+        }
+        codec.decode_warnings(&cleaned, mode)
+    }
+
+    /// Decodes under the output limit, first applying the lenient policy in lenient mode. Input a
+    /// lenient decode rejects is retried with lookalike characters (`а`, `８`, `–`) read as ASCII.
+    /// Codecs that know their exact decoded length ([`Codec::decoded_len`]) are rejected before
+    /// any output is built; others are checked once decoded.
+    pub fn decode(&self, codec: &dyn Codec, input: &str, mode: Mode, opts: &CodecOptions) -> Result<Vec<u8>> {
+        let cleaned = self.apply_lenient_policy(codec, input, mode);
+        self.check_known_size(codec.decoded_len(&cleaned, mode, opts))?;
+        let opts = &*self.limited(opts);
+        let decoded = match codec.decode_with(&cleaned, mode, opts) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                let Some((folded, _)) = self.fold_lookalikes(codec, input, mode) else {
+                    return Err(e);
+                };
+                let cleaned = self.apply_lenient_policy(codec, &folded, mode);
+                codec.decode_with(&cleaned, mode, opts).map_err(|_| e)?
+            }
+        };
+        tracing::debug!(codec = codec.name(), ?mode, input_len = input.len(), output_len = decoded.len(), "decoded");
+        self.check_output_size(decoded.len())?;
+        Ok(decoded)
+    }
+
+    /// The siblings of `name` (see [`Registry::siblings`]) that decode `input` in `mode` under
+    /// this context, for "valid base64url, not base64" hints.
+    pub fn decoding_siblings(&self, name: &str, input: &str, mode: Mode) -> Result<Vec<&dyn Codec>> {
+        let opts = CodecOptions::new();
+        Ok(self
+            .registry
+            .siblings(name)?
+            .into_iter()
+            .filter(|c| self.decode(*c, input, mode, &opts).is_ok())
+            .collect())
+    }
+
+    /// [`Context::encode`] appending to `out`; record loops reuse one buffer this way. Under an
+    /// output limit, codecs without an exact length go through [`Context::encode`] so they see it.
+    pub fn encode_into(&self, codec: &dyn Codec, input: &[u8], opts: &CodecOptions, out: &mut String) -> Result<()> {
+        let known = codec.encoded_len(input.len(), opts);
+        if !opts.is_empty() || (self.max_output_size.is_some() && known.is_none()) {
+            out.push_str(&self.encode(codec, input, opts)?);
+            return Ok(());
+        }
+        self.check_known_size(known)?;
+        let start = out.len();
+        codec.encode_into(input, out)?;
+        if let Err(e) = self.check_output_size(out.len() - start) {
+            out.truncate(start);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// [`Context::decode`] appending to `out`.
+    pub fn decode_into(&self, codec: &dyn Codec, input: &str, mode: Mode, opts: &CodecOptions, out: &mut Vec<u8>) -> Result<()> {
+        if !opts.is_empty() {
+            out.extend_from_slice(&self.decode(codec, input, mode, opts)?);
+            return Ok(());
+        }
+        let cleaned = self.apply_lenient_policy(codec, input, mode);
+        self.check_known_size(codec.decoded_len(&cleaned, mode, opts))?;
+        let start = out.len();
+        if let Err(e) = codec.decode_into(&cleaned, mode, out) {
+            out.truncate(start);
+            let Some((folded, _)) = self.fold_lookalikes(codec, input, mode) else {
+                return Err(e);
+            };
+            let cleaned = self.apply_lenient_policy(codec, &folded, mode);
+            codec.decode_into(&cleaned, mode, out).map_err(|_| e)?;
+        }
+        if let Err(e) = self.check_output_size(out.len() - start) {
+            out.truncate(start);
+            return Err(e);
+        }
+        Ok(())
     }
 }
 
 impl Default for Context {
     fn default() -> Self {
-        Self {
-            registry: Registry::global(),
-        }
+        Self::new(Registry::global().clone())
     }
 }
 
@@ -28,26 +210,220 @@ pub enum Mode {
     Lenient,
 }
 
+/// Separators that lenient decoding drops for every codec, on top of each codec's own cleaning.
+///
+/// Characters that belong to the codec's alphabet are never dropped, so `-` survives in base64url.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LenientPolicy {
+    pub strip_hyphens: bool,
+    pub strip_underscores: bool,
+    pub strip_dots: bool,
+    /// Replace runs of whitespace with one space and trim the ends (for word and symbol codecs).
+    pub collapse_whitespace: bool,
+}
+
+impl LenientPolicy {
+    /// Parses `--ignore-chars`: any of `-`, `_`, `.` and the word `whitespace`, optionally comma-separated.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut policy = Self::default();
+        for token in spec.split(',').filter(|t| !t.is_empty()) {
+            if token == "whitespace" {
+                policy.collapse_whitespace = true;
+                continue;
+            }
+            for c in token.chars() {
+                match c {
+                    '-' => policy.strip_hyphens = true,
+                    '_' => policy.strip_underscores = true,
+                    '.' => policy.strip_dots = true,
+                    other => {
+                        return Err(MbaseError::invalid_input(format!("cannot ignore '{}': expected '-', '_', '.' or 'whitespace'", other)))
+                    }
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `c` is one of the separators this policy drops.
+    pub fn strips(&self, c: char) -> bool {
+        (c == '-' && self.strip_hyphens) || (c == '_' && self.strip_underscores) || (c == '.' && self.strip_dots)
+    }
+}
+
+/// How a text-only codec treats input bytes that are not UTF-8 (`--opt utf8=strict|lenient`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Utf8Policy {
+    /// Reject the input, naming the first invalid byte.
+    #[default]
+    Strict,
+    /// Encode each invalid sequence as the codec's marker character (`?` or `X`).
+    Lenient,
+}
+
+/// Codec-specific `key=value` settings, e.g. `--opt sep=. --opt group=2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodecOptions {
+    values: BTreeMap<String, String>,
+    /// Output budget from [`Context::max_output_size`], for codecs that build large output.
+    output_limit: Option<usize>,
+}
+
+impl CodecOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(specs: &[String]) -> Result<Self> {
+        let mut opts = Self::new();
+        for spec in specs {
+            let (key, value) = spec
+                .split_once('=')
+                .ok_or_else(|| MbaseError::invalid_input(format!("codec option '{}' must be KEY=VALUE", spec)))?;
+            if key.is_empty() {
+                return Err(MbaseError::invalid_input(format!("codec option '{}' has an empty key", spec)));
+            }
+            opts.values.insert(key.to_string(), value.to_string());
+        }
+        Ok(opts)
+    }
+
+    pub fn with(mut self, key: &str, value: &str) -> Self {
+        self.values.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.values.remove(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Sets the output budget a codec checks while it builds its output; not a `key=value`
+    /// option, so [`ensure_known`](Self::ensure_known) never sees it.
+    pub fn with_output_limit(mut self, limit: Option<usize>) -> Self {
+        self.output_limit = limit;
+        self
+    }
+
+    /// Fails once `size` bytes of output pass the budget, so an encoder can stop early instead
+    /// of building all of an output that will be refused.
+    pub fn check_output_size(&self, size: usize) -> Result<()> {
+        match self.output_limit {
+            Some(limit) if size > limit => Err(MbaseError::OutputTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn get_char(&self, key: &str) -> Result<Option<char>> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(v) => {
+                let mut chars = v.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Some(c)),
+                    _ => Err(MbaseError::invalid_input(format!("option '{}' expects a single character, got '{}'", key, v))),
+                }
+            }
+        }
+    }
+
+    pub fn get_usize(&self, key: &str) -> Result<Option<usize>> {
+        self.get(key)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| MbaseError::invalid_input(format!("option '{}' expects a number, got '{}'", key, v)))
+            })
+            .transpose()
+    }
+
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>> {
+        self.get(key)
+            .map(|v| match v {
+                "1" | "true" | "yes" | "on" => Ok(true),
+                "0" | "false" | "no" | "off" => Ok(false),
+                _ => Err(MbaseError::invalid_input(format!("option '{}' expects true/false, got '{}'", key, v))),
+            })
+            .transpose()
+    }
+
+    /// The `utf8` option of text-only codecs; strict unless `utf8=lenient` is given.
+    pub fn utf8_policy(&self) -> Result<Utf8Policy> {
+        match self.get("utf8") {
+            None | Some("strict") => Ok(Utf8Policy::Strict),
+            Some("lenient") => Ok(Utf8Policy::Lenient),
+            Some(other) => Err(MbaseError::invalid_input(format!("option 'utf8' expects strict or lenient, got '{}'", other))),
+        }
+    }
+
+    /// Rejects keys the codec does not understand so typos don't pass silently.
+    pub fn ensure_known(&self, codec: &str, known: &[&str]) -> Result<()> {
+        match self.values.keys().find(|k| !known.contains(&k.as_str())) {
+            None => Ok(()),
+            Some(key) if known.is_empty() => {
+                Err(MbaseError::invalid_input(format!("codec '{}' does not accept options (got '{}')", codec, key)))
+            }
+            Some(key) => Err(MbaseError::invalid_input(format!(
+                "unknown option '{}' for codec '{}' (expected one of: {})",
+                key,
+                codec,
+                known.join(", ")
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub enum InputSource {
     Stdin,
     File(PathBuf),
     Literal(Vec<u8>),
+    /// `http://` or `https://` resource, fetched with a size and time limit.
+    #[cfg(feature = "net")]
+    Url(String),
 }
 
+#[cfg(feature = "std")]
 impl InputSource {
-    pub fn parse(s: &str) -> Self {
-        match s {
+    /// Parses an `--in` argument: `-` (stdin), `@path` or `file:path`, an `http(s)://` URL (with
+    /// the `net` feature), `str:text`, or bytes given inline as `hex:deadbeef` / `b64:SGVsbG8=`.
+    /// Anything else is literal text.
+    pub fn parse(s: &str) -> Result<Self> {
+        let inline = |codec: &str, scheme: &str, data: &str| {
+            Registry::global()
+                .get(codec)?
+                .decode(data, Mode::Lenient)
+                .map(InputSource::Literal)
+                .map_err(|e| MbaseError::invalid_input(format!("invalid {}: input: {}", scheme, e)))
+        };
+        Ok(match s {
             "-" => InputSource::Stdin,
             s if s.starts_with('@') => InputSource::File(PathBuf::from(&s[1..])),
+            s if s.starts_with("file:") => InputSource::File(PathBuf::from(&s[5..])),
+            #[cfg(feature = "net")]
+            s if s.starts_with("https://") || s.starts_with("http://") => InputSource::Url(s.to_string()),
+            s if s.starts_with("str:") => InputSource::Literal(s.as_bytes()[4..].to_vec()),
+            s if s.starts_with("hex:") => inline("base16lower", "hex", &s[4..])?,
+            s if s.starts_with("b64:") => inline("base64pad", "b64", &s[4..])?,
             s => {
                 // Warn if input looks like a path
                 if Self::looks_like_path(s) {
-                    eprintln!("Warning: treating '{}' as literal data. Use @{} to read from file.", s, s);
+                    eprintln!("Warning: treating '{}' as literal data. Use @{} to read from file, or str:{} to silence this.", s, s, s);
                 }
                 InputSource::Literal(s.as_bytes().to_vec())
             }
-        }
+        })
     }
 
     fn looks_like_path(s: &str) -> bool {
@@ -61,12 +437,14 @@ impl InputSource {
     }
 }
 
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub enum OutputDest {
     Stdout,
     File(PathBuf),
 }
 
+#[cfg(feature = "std")]
 impl OutputDest {
     pub fn parse(s: &str) -> Self {
         match s {
@@ -102,6 +480,53 @@ pub struct CodecMeta {
     pub description: &'static str,
 }
 
+/// Owned snapshot of a codec's [`CodecMeta`] plus its stable registry ID and capabilities.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodecMetadata {
+    pub id: u16,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub alphabet: String,
+    pub multibase_code: Option<char>,
+    pub padding: PaddingRule,
+    pub case_sensitivity: CaseSensitivity,
+    pub description: String,
+    /// Whether the codec encodes in independent blocks and can stream large inputs.
+    pub streamable: bool,
+    /// Variant group such as `base64` or `base32`; `None` for codecs without siblings.
+    pub family: Option<String>,
+    /// Whether the codec encodes text rather than arbitrary bytes (see [`Codec::text_only`]).
+    pub text_only: bool,
+    /// Whether decoding may not restore the encoded input (see [`Codec::lossy`]).
+    pub lossy: bool,
+    /// Short title of the specification the codec implements, such as `RFC 4648 §4`.
+    pub spec: Option<String>,
+    pub spec_url: Option<String>,
+}
+
+impl CodecMetadata {
+    pub fn new(id: u16, codec: &dyn Codec) -> Self {
+        let meta = codec.meta();
+        let spec = crate::codec::registry::spec_of(meta.name);
+        Self {
+            id,
+            name: meta.name.to_string(),
+            aliases: meta.aliases.iter().map(|a| a.to_string()).collect(),
+            alphabet: meta.alphabet.to_string(),
+            multibase_code: meta.multibase_code,
+            padding: meta.padding,
+            case_sensitivity: meta.case_sensitivity,
+            description: meta.description.to_string(),
+            streamable: codec.is_streamable(),
+            family: crate::codec::registry::family_of(meta.name).map(String::from),
+            text_only: codec.text_only(),
+            lossy: codec.lossy(),
+            spec: spec.map(|(title, _)| title.to_string()),
+            spec_url: spec.map(|(_, url)| url.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DetectCandidate {
     pub codec: String,
@@ -109,3 +534,38 @@ pub struct DetectCandidate {
     pub reasons: Vec<String>,
     pub warnings: Vec<String>,
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn literal(s: &str) -> Vec<u8> {
+        match InputSource::parse(s).unwrap() {
+            InputSource::Literal(bytes) => bytes,
+            other => panic!("expected literal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_input_source_schemes() {
+        assert!(matches!(InputSource::parse("-").unwrap(), InputSource::Stdin));
+        assert!(matches!(InputSource::parse("@a/b.bin").unwrap(), InputSource::File(p) if p == std::path::Path::new("a/b.bin")));
+        assert!(matches!(InputSource::parse("file:data.txt").unwrap(), InputSource::File(p) if p == std::path::Path::new("data.txt")));
+        #[cfg(feature = "net")]
+        assert!(matches!(InputSource::parse("https://example.com/x").unwrap(), InputSource::Url(u) if u == "https://example.com/x"));
+        #[cfg(not(feature = "net"))]
+        assert_eq!(literal("https://example.com/a?b=c"), b"https://example.com/a?b=c");
+        assert_eq!(literal("hex:DEAD beef"), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(literal("b64:SGVsbG8="), b"Hello");
+        assert_eq!(literal("b64:SGVsbG8"), b"Hello");
+        assert_eq!(literal("str:@not-a-file"), b"@not-a-file");
+        assert_eq!(literal("plain text"), b"plain text");
+    }
+
+    #[test]
+    fn test_input_source_bad_inline_bytes() {
+        let err = InputSource::parse("hex:xyz").unwrap_err();
+        assert!(err.to_string().contains("invalid hex: input"), "{}", err);
+        assert!(InputSource::parse("b64:!!!").is_err());
+    }
+}
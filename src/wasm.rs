@@ -0,0 +1,154 @@
+//! JavaScript bindings (`--features wasm`, build with `wasm-pack build --features wasm`).
+//!
+//! Structured results are returned as JSON strings; the TypeScript shapes are declared below so
+//! callers can write `JSON.parse(detect(text)) as DetectCandidate[]`.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::codec::Registry;
+use crate::error::MbaseError;
+use crate::types::Mode;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &'static str = r#"
+export interface CodecMeta {
+  name: string;
+  aliases: string[];
+  alphabet: string;
+  multibase_code: string | null;
+  padding: "None" | "Required";
+  case_sensitivity: "Sensitive" | "Insensitive" | "Lower" | "Upper";
+  description: string;
+}
+
+export interface DetectCandidate {
+  codec: string;
+  confidence: number;
+  reasons: string[];
+  warnings: string[];
+}
+
+export interface Explanation {
+  codec: string;
+  valid: boolean;
+  decoded_length: number | null;
+  error: string | null;
+  exit_code: number;
+  position: number | null;
+  character: string | null;
+}
+"#;
+
+#[derive(Debug, Serialize)]
+pub struct Explanation {
+    pub codec: String,
+    pub valid: bool,
+    pub decoded_length: Option<usize>,
+    pub error: Option<String>,
+    pub exit_code: u8,
+    pub position: Option<usize>,
+    pub character: Option<char>,
+}
+
+fn mode(lenient: bool) -> Mode {
+    if lenient {
+        Mode::Lenient
+    } else {
+        Mode::Strict
+    }
+}
+
+fn js_error(e: MbaseError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// Encodes bytes with the named codec.
+#[wasm_bindgen]
+pub fn encode(codec: &str, data: &[u8]) -> Result<String, JsError> {
+    Registry::global().get(codec).and_then(|c| c.encode(data)).map_err(js_error)
+}
+
+/// Decodes text with the named codec into a `Uint8Array`.
+#[wasm_bindgen]
+pub fn decode(codec: &str, text: &str, lenient: bool) -> Result<Vec<u8>, JsError> {
+    Registry::global()
+        .get(codec)
+        .and_then(|c| c.decode(text, mode(lenient)))
+        .map_err(js_error)
+}
+
+/// JSON `DetectCandidate[]`, most likely first.
+#[wasm_bindgen]
+pub fn detect(text: &str) -> String {
+    serde_json::to_string(&Registry::global().detect(text.trim())).unwrap()
+}
+
+/// JSON `CodecMeta[]` for every registered codec.
+#[wasm_bindgen(js_name = listCodecs)]
+pub fn list_codecs() -> String {
+    serde_json::to_string(&Registry::global().metadata()).unwrap()
+}
+
+/// JSON `Explanation`: whether `text` decodes, and where it fails if not.
+#[wasm_bindgen]
+pub fn explain(codec: &str, text: &str, lenient: bool) -> Result<String, JsError> {
+    let c = Registry::global().get(codec).map_err(js_error)?;
+    let explanation = match c.decode(text, mode(lenient)) {
+        Ok(decoded) => Explanation {
+            codec: c.name().to_string(),
+            valid: true,
+            decoded_length: Some(decoded.len()),
+            error: None,
+            exit_code: 0,
+            position: None,
+            character: None,
+        },
+        Err(e) => {
+            let (position, character) = match &e {
+                MbaseError::InvalidCharacter { char, position } => (Some(*position), Some(*char)),
+                MbaseError::ChecksumMismatchAt { position, .. } | MbaseError::InvalidInputAt { position, .. } => (Some(*position), None),
+                _ => (None, None),
+            };
+            Explanation {
+                codec: c.name().to_string(),
+                valid: false,
+                decoded_length: None,
+                error: Some(e.to_string()),
+                exit_code: e.exit_code() as u8,
+                position,
+                character,
+            }
+        }
+    };
+    Ok(serde_json::to_string(&explanation).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wasm_encode_decode() {
+        assert_eq!(encode("base58btc", b"hello").unwrap(), "Cn8eVZg");
+        assert_eq!(decode("base58btc", "Cn8eVZg", false).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_wasm_explain() {
+        let ok: serde_json::Value = serde_json::from_str(&explain("hex", "6869", false).unwrap()).unwrap();
+        assert_eq!(ok["valid"], true);
+        assert_eq!(ok["decoded_length"], 2);
+
+        let bad: serde_json::Value = serde_json::from_str(&explain("hex", "68zz", false).unwrap()).unwrap();
+        assert_eq!(bad["valid"], false);
+        assert_eq!(bad["exit_code"], 10);
+    }
+
+    #[test]
+    fn test_wasm_detect_and_list() {
+        let candidates: Vec<serde_json::Value> = serde_json::from_str(&detect("aGVsbG8=")).unwrap();
+        assert!(candidates.iter().any(|c| c["codec"] == "base64pad"));
+        assert!(list_codecs().contains("\"crockford32\""));
+    }
+}